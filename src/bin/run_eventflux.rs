@@ -3,7 +3,6 @@
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 
 use clap::Parser;
@@ -17,6 +16,9 @@ use eventflux_rust::core::persistence::{
     FilePersistenceStore, PersistenceStore, SqlitePersistenceStore,
 };
 
+/// Maximum time to wait for buffered events to be processed on shutdown
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Parser, Debug)]
 #[command(about = "Run a EventFluxQL file", author, version)]
 struct Cli {
@@ -201,8 +203,37 @@ async fn main() {
         runtime.name
     );
 
-    // Keep the main thread alive until interrupted
-    loop {
-        thread::sleep(Duration::from_secs(1));
+    // Run until interrupted, then stop sources, drain, snapshot and stop sinks
+    wait_for_shutdown_signal().await;
+    println!("Shutting down EventFlux app '{}'", runtime.name);
+    let shutdown = tokio::task::spawn_blocking(move || runtime.graceful_shutdown(DRAIN_TIMEOUT));
+    if let Err(e) = shutdown.await {
+        eprintln!("Graceful shutdown failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Wait for SIGINT (Ctrl+C) or, on Unix, SIGTERM
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+                return;
+            }
+            Err(e) => eprintln!("Failed to install SIGTERM handler: {e}"),
+        }
+    }
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        eprintln!("Failed to listen for Ctrl+C: {e}");
+        // Without a signal handler, keep running until the process is killed
+        std::future::pending::<()>().await;
     }
 }
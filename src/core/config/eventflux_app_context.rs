@@ -3,7 +3,8 @@
 // Corresponds to io.eventflux.core.config.EventFluxAppContext
 use super::eventflux_context::EventFluxContext;
 use crate::core::config::{
-    ApplicationConfig, ConfigManager, EventFluxConfig, ProcessorConfigReader,
+    ApplicationConfig, ConfigManager, EventFluxConfig, PersistenceBackendType,
+    ProcessorConfigReader,
 };
 use crate::core::util::executor_service::ExecutorService;
 use crate::core::util::id_generator::IdGenerator;
@@ -11,13 +12,13 @@ use crate::core::util::Scheduler;
 use crate::query_api::EventFluxApp; // From query_api
 use std::cell::RefCell;
 use std::collections::HashMap; // For scriptFunctionMap
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::thread_local;
 // use super::statistics_manager::StatisticsManager; // TODO: Define later
 // use super::timestamp_generator::TimestampGenerator; // TODO: Define later
 // use super::snapshot_service::SnapshotService; // TODO: Define later
 // use super::id_generator::IdGenerator; // TODO: Define later
-// use crate::core::function::Script; // TODO: Define later
+use crate::core::function::Script;
 // use crate::core::trigger::Trigger; // TODO: Define later
 // use crate::core::util::extension::ExternalReferencedHolder; // TODO: Define later
 // use crate::core::util::Scheduler; // TODO: Define later
@@ -29,21 +30,18 @@ use std::thread_local;
 pub struct StatisticsManagerPlaceholder {}
 #[derive(Debug, Clone, Default)]
 pub struct TimestampGeneratorPlaceholder {}
-use crate::core::persistence::SnapshotService;
+use crate::core::persistence::{
+    IncrementalCheckpointConfig, PersistenceBackendConfig, SnapshotService,
+};
 use crate::core::util::thread_barrier::ThreadBarrier;
+use crate::core::window::named_window::NamedWindowShared;
 #[derive(Debug, Clone, Default)]
 pub struct ScriptPlaceholder {}
+/// A script function declared with `CREATE FUNCTION`, compiled by its language.
 #[derive(Debug, Clone)]
-pub struct ScriptFunctionPlaceholder {
+pub struct ScriptFunction {
     pub return_type: crate::query_api::definition::attribute::Type,
-}
-
-impl Default for ScriptFunctionPlaceholder {
-    fn default() -> Self {
-        Self {
-            return_type: crate::query_api::definition::attribute::Type::OBJECT,
-        }
-    }
+    pub script: Box<dyn Script>,
 }
 #[derive(Debug, Clone, Default)]
 pub struct TriggerPlaceholder {}
@@ -110,9 +108,11 @@ pub struct EventFluxAppContext {
     // Simplified placeholder fields for now
     _external_refs_placeholder: Vec<String>,
     _triggers_placeholder: Vec<String>,
-    /// Placeholder storage for script functions keyed by name.
-    pub script_function_map: HashMap<String, ScriptFunctionPlaceholder>,
+    /// Compiled script functions keyed by name.
+    pub script_function_map: HashMap<String, ScriptFunction>,
     _schedulers_placeholder: Vec<String>,
+    /// Named windows (`CREATE WINDOW`) of this app readable by its queries.
+    named_windows: Arc<RwLock<HashMap<String, Arc<NamedWindowShared>>>>,
 
     // Configuration system integration
     /// Global EventFlux configuration
@@ -227,6 +227,7 @@ impl EventFluxAppContext {
             _triggers_placeholder: Vec::new(),
             script_function_map: HashMap::new(),
             _schedulers_placeholder: Vec::new(),
+            named_windows: Arc::new(RwLock::new(HashMap::new())),
 
             // Configuration system integration
             global_config,
@@ -235,21 +236,62 @@ impl EventFluxAppContext {
         }
     }
 
-    /// Register a script function with the given name and return type.
+    /// Register a compiled script function with the given name and return type.
     pub fn add_script_function(
         &mut self,
         name: String,
         return_type: crate::query_api::definition::attribute::Type,
+        script: Box<dyn Script>,
     ) {
-        self.script_function_map
-            .insert(name, ScriptFunctionPlaceholder { return_type });
+        self.script_function_map.insert(
+            name,
+            ScriptFunction {
+                return_type,
+                script,
+            },
+        );
     }
 
-    /// Lookup a registered script function placeholder by name.
-    pub fn get_script_function(&self, name: &str) -> Option<&ScriptFunctionPlaceholder> {
+    /// Lookup a registered script function by name.
+    pub fn get_script_function(&self, name: &str) -> Option<&ScriptFunction> {
         self.script_function_map.get(name)
     }
 
+    pub fn add_named_window(&self, name: String, window: Arc<NamedWindowShared>) {
+        self.named_windows.write().unwrap().insert(name, window);
+    }
+
+    pub fn get_named_window(&self, name: &str) -> Option<Arc<NamedWindowShared>> {
+        self.named_windows.read().unwrap().get(name).cloned()
+    }
+
+    /// Compile every function definition of the app with its script language.
+    ///
+    /// Fails on an unknown language or a body that does not compile, so broken
+    /// functions are reported when the app is created rather than per event.
+    pub fn register_script_functions(&mut self) -> Result<(), String> {
+        let definitions: Vec<_> = self
+            .eventflux_app
+            .function_definition_map
+            .values()
+            .cloned()
+            .collect();
+        for definition in definitions {
+            let language = self
+                .eventflux_context
+                .get_script_language(&definition.language)
+                .ok_or_else(|| {
+                    format!(
+                        "Function '{}' uses unknown script language '{}'",
+                        definition.id, definition.language
+                    )
+                })?;
+            let script = language.compile(&definition)?;
+            self.add_script_function(definition.id.clone(), definition.return_type, script);
+        }
+        Ok(())
+    }
+
     // --- Getter and Setter examples ---
     pub fn get_eventflux_context(&self) -> Arc<EventFluxContext> {
         Arc::clone(&self.eventflux_context)
@@ -521,6 +563,78 @@ impl EventFluxAppContext {
     pub fn is_distributed_mode(&self) -> bool {
         self.global_config.is_distributed_mode()
     }
+
+    /// Get the interval between automatic snapshots
+    ///
+    /// The application's `persistence.interval` takes precedence over
+    /// `eventflux.persistence.interval`. A zero interval disables periodic snapshots.
+    pub fn get_snapshot_interval(&self) -> Option<std::time::Duration> {
+        let global = self
+            .global_config
+            .eventflux
+            .persistence
+            .as_ref()
+            .filter(|p| p.enabled);
+        self.app_config
+            .as_ref()
+            .and_then(|c| c.persistence.as_ref())
+            .and_then(|p| p.interval)
+            .or_else(|| global.and_then(|p| p.interval))
+            .filter(|interval| !interval.is_zero())
+    }
+
+    /// Check if the last persisted revision is restored when the application starts
+    pub fn is_restore_on_start_enabled(&self) -> bool {
+        self.global_config
+            .eventflux
+            .persistence
+            .as_ref()
+            .is_some_and(|p| p.enabled && p.restore_on_start)
+    }
+
+    /// Check if the application takes incremental checkpoints
+    pub fn is_incremental_checkpointing_enabled(&self) -> bool {
+        self.app_config
+            .as_ref()
+            .and_then(|c| c.persistence.as_ref())
+            .is_some_and(|p| p.enabled && p.incremental)
+    }
+
+    /// Get the incremental checkpoint settings of the application
+    ///
+    /// Checkpoints and the write-ahead log are kept under `persistence.path`,
+    /// defaulting to `<app name>` under the file store's `eventflux.persistence.path`, or to
+    /// `./checkpoints/<app name>` when neither is set.
+    pub fn get_incremental_checkpoint_config(&self) -> Option<IncrementalCheckpointConfig> {
+        let persistence = self
+            .app_config
+            .as_ref()
+            .and_then(|c| c.persistence.as_ref())
+            .filter(|p| p.enabled && p.incremental)?;
+        let base_path = persistence.path.clone().unwrap_or_else(|| {
+            let root = self
+                .global_config
+                .eventflux
+                .persistence
+                .as_ref()
+                .filter(|p| p.backend_type == PersistenceBackendType::File)
+                .and_then(|p| p.path.as_deref())
+                .unwrap_or("./checkpoints");
+            format!("{root}/{}", self.name)
+        });
+        let defaults = IncrementalCheckpointConfig::default();
+        Some(IncrementalCheckpointConfig {
+            checkpoint_interval: persistence.checkpoint_interval,
+            max_incremental_count: persistence
+                .max_incremental_count
+                .unwrap_or(defaults.max_incremental_count),
+            backend_config: PersistenceBackendConfig::LocalFile {
+                base_path,
+                sync_writes: true,
+            },
+            ..defaults
+        })
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +108,8 @@ pub struct EventFluxContext {
     collection_aggregation_functions: Arc<
         RwLock<HashMap<String, Box<dyn crate::core::extension::CollectionAggregationFunction>>>,
     >,
+    /// Script languages usable in `CREATE FUNCTION ... LANGUAGE <name>`
+    script_languages: Arc<RwLock<HashMap<String, Box<dyn crate::core::function::ScriptLanguage>>>>,
     /// Configurations for data sources keyed by name
     data_source_configs: Arc<RwLock<HashMap<String, DataSourceConfig>>>,
     /// Stores registered data sources. Key: data source name.
@@ -136,6 +138,7 @@ impl EventFluxContext {
             sink_mapper_factories: Arc::new(RwLock::new(HashMap::new())),
             table_factories: Arc::new(RwLock::new(HashMap::new())),
             collection_aggregation_functions: Arc::new(RwLock::new(HashMap::new())),
+            script_languages: Arc::new(RwLock::new(HashMap::new())),
             data_source_configs: Arc::new(RwLock::new(HashMap::new())),
             data_sources: Arc::new(RwLock::new(HashMap::new())),
             tables: Arc::new(RwLock::new(HashMap::new())),
@@ -388,31 +391,63 @@ impl EventFluxContext {
             .cloned()
     }
 
+    // --- Script Language Methods ---
+    /// Register a script language under its [`ScriptLanguage::name`](crate::core::function::ScriptLanguage::name).
+    pub fn add_script_language(&self, language: Box<dyn crate::core::function::ScriptLanguage>) {
+        self.script_languages
+            .write()
+            .unwrap()
+            .insert(language.name().to_lowercase(), language);
+    }
+
+    pub fn get_script_language(
+        &self,
+        name: &str,
+    ) -> Option<Box<dyn crate::core::function::ScriptLanguage>> {
+        self.script_languages
+            .read()
+            .unwrap()
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
     fn register_default_extensions(&mut self) {
         use crate::core::executor::function::builtin_wrapper::register_builtin_scalar_functions;
         use crate::core::extension::{
-            CollectionAvgFunction, CollectionCountFunction, CollectionMaxFunction,
+            AttributeAggregatorFactory, CollectionAvgFunction, CollectionCollectFunction,
+            CollectionCountFunction, CollectionDistinctCountFunction, CollectionDistinctFunction,
+            CollectionFirstFunction, CollectionLastFunction, CollectionMaxFunction,
             CollectionMinFunction, CollectionStdDevFunction, CollectionSumFunction, LogSinkFactory,
             TimerSourceFactory,
         };
         use crate::core::query::processor::stream::window::{
-            CronWindowFactory, ExternalTimeBatchWindowFactory, ExternalTimeWindowFactory,
-            LengthBatchWindowFactory, LengthWindowFactory, LossyCountingWindowFactory,
-            SessionWindowFactory, SortWindowFactory, TimeBatchWindowFactory, TimeWindowFactory,
+            CronWindowFactory, DelayWindowFactory, ExpressionWindowFactory,
+            ExternalTimeBatchWindowFactory, ExternalTimeWindowFactory, FirstUniqueWindowFactory,
+            FrequentWindowFactory, LengthBatchWindowFactory, LengthWindowFactory,
+            LossyCountingWindowFactory, SessionWindowFactory, SortWindowFactory,
+            TimeBatchWindowFactory, TimeLengthWindowFactory, TimeWindowFactory,
+            UniqueWindowFactory,
         };
         use crate::core::query::selector::attribute::aggregator::{
-            AvgAttributeAggregatorFactory, CountAttributeAggregatorFactory,
+            ApproxDistinctAttributeAggregatorFactory, ArgMaxAttributeAggregatorFactory,
+            ArgMinAttributeAggregatorFactory, AvgAttributeAggregatorFactory,
+            CoMomentAttributeAggregatorFactory, CoMomentStat, CountAttributeAggregatorFactory,
             DistinctCountAttributeAggregatorFactory, FirstAttributeAggregatorFactory,
             LastAttributeAggregatorFactory, MaxAttributeAggregatorFactory,
-            MaxForeverAttributeAggregatorFactory, MinAttributeAggregatorFactory,
-            MinForeverAttributeAggregatorFactory, StdDevAttributeAggregatorFactory,
-            SumAttributeAggregatorFactory,
+            MaxForeverAttributeAggregatorFactory, MedianAttributeAggregatorFactory,
+            MinAttributeAggregatorFactory, MinForeverAttributeAggregatorFactory,
+            PercentileAttributeAggregatorFactory, StdDevAttributeAggregatorFactory,
+            SumAttributeAggregatorFactory, TopKAttributeAggregatorFactory,
+            WeightedAvgAttributeAggregatorFactory,
         };
         use crate::core::stream::input::source::rabbitmq_source::RabbitMQSourceFactory;
         use crate::core::stream::input::source::websocket_source::WebSocketSourceFactory;
         use crate::core::stream::output::sink::rabbitmq_sink::RabbitMQSinkFactory;
         use crate::core::stream::output::sink::websocket_sink::WebSocketSinkFactory;
-        use crate::core::table::{CacheTableFactory, InMemoryTableFactory, JdbcTableFactory};
+        use crate::core::table::{
+            CacheTableFactory, InMemoryTableFactory, JdbcTableFactory, PostgresTableFactory,
+            RedisTableFactory, SqliteTableFactory,
+        };
 
         self.add_window_factory("length".to_string(), Box::new(LengthWindowFactory));
         self.add_window_factory("time".to_string(), Box::new(TimeWindowFactory));
@@ -436,6 +471,15 @@ impl EventFluxContext {
         self.add_window_factory("cron".to_string(), Box::new(CronWindowFactory));
         self.add_window_factory("session".to_string(), Box::new(SessionWindowFactory));
         self.add_window_factory("sort".to_string(), Box::new(SortWindowFactory));
+        self.add_window_factory("unique".to_string(), Box::new(UniqueWindowFactory));
+        self.add_window_factory(
+            "firstUnique".to_string(),
+            Box::new(FirstUniqueWindowFactory),
+        );
+        self.add_window_factory("delay".to_string(), Box::new(DelayWindowFactory));
+        self.add_window_factory("frequent".to_string(), Box::new(FrequentWindowFactory));
+        self.add_window_factory("expression".to_string(), Box::new(ExpressionWindowFactory));
+        self.add_window_factory("timeLength".to_string(), Box::new(TimeLengthWindowFactory));
 
         self.add_attribute_aggregator_factory(
             "sum".to_string(),
@@ -473,6 +517,44 @@ impl EventFluxContext {
             "stddev".to_string(),
             Box::new(StdDevAttributeAggregatorFactory),
         );
+        self.add_attribute_aggregator_factory(
+            "percentile".to_string(),
+            Box::new(PercentileAttributeAggregatorFactory),
+        );
+        self.add_attribute_aggregator_factory(
+            "median".to_string(),
+            Box::new(MedianAttributeAggregatorFactory),
+        );
+        self.add_attribute_aggregator_factory(
+            "approx_distinct".to_string(),
+            Box::new(ApproxDistinctAttributeAggregatorFactory),
+        );
+        self.add_attribute_aggregator_factory(
+            "top_k".to_string(),
+            Box::new(TopKAttributeAggregatorFactory),
+        );
+        for stat in [
+            CoMomentStat::Corr,
+            CoMomentStat::CovarPop,
+            CoMomentStat::CovarSamp,
+            CoMomentStat::RegrSlope,
+            CoMomentStat::RegrIntercept,
+        ] {
+            let factory = CoMomentAttributeAggregatorFactory(stat);
+            self.add_attribute_aggregator_factory(factory.name().to_string(), Box::new(factory));
+        }
+        self.add_attribute_aggregator_factory(
+            "wavg".to_string(),
+            Box::new(WeightedAvgAttributeAggregatorFactory),
+        );
+        self.add_attribute_aggregator_factory(
+            "arg_max".to_string(),
+            Box::new(ArgMaxAttributeAggregatorFactory),
+        );
+        self.add_attribute_aggregator_factory(
+            "arg_min".to_string(),
+            Box::new(ArgMinAttributeAggregatorFactory),
+        );
         self.add_attribute_aggregator_factory(
             "first".to_string(),
             Box::new(FirstAttributeAggregatorFactory),
@@ -485,6 +567,9 @@ impl EventFluxContext {
         self.add_table_factory("inMemory".to_string(), Box::new(InMemoryTableFactory));
         self.add_table_factory("jdbc".to_string(), Box::new(JdbcTableFactory));
         self.add_table_factory("cache".to_string(), Box::new(CacheTableFactory));
+        self.add_table_factory("sqlite".to_string(), Box::new(SqliteTableFactory));
+        self.add_table_factory("postgres".to_string(), Box::new(PostgresTableFactory));
+        self.add_table_factory("redis".to_string(), Box::new(RedisTableFactory));
         self.add_source_factory("timer".to_string(), Box::new(TimerSourceFactory));
         self.add_source_factory("rabbitmq".to_string(), Box::new(RabbitMQSourceFactory));
         self.add_source_factory("websocket".to_string(), Box::new(WebSocketSourceFactory));
@@ -529,6 +614,30 @@ impl EventFluxContext {
             "stdDev".to_string(),
             Box::new(CollectionStdDevFunction),
         );
+        self.add_collection_aggregation_function(
+            "first".to_string(),
+            Box::new(CollectionFirstFunction),
+        );
+        self.add_collection_aggregation_function(
+            "last".to_string(),
+            Box::new(CollectionLastFunction),
+        );
+        self.add_collection_aggregation_function(
+            "collect".to_string(),
+            Box::new(CollectionCollectFunction),
+        );
+        self.add_collection_aggregation_function(
+            "distinct".to_string(),
+            Box::new(CollectionDistinctFunction),
+        );
+        self.add_collection_aggregation_function(
+            "distinctCount".to_string(),
+            Box::new(CollectionDistinctCountFunction),
+        );
+
+        self.add_script_language(Box::new(
+            crate::core::function::RhaiScriptLanguage::default(),
+        ));
 
         register_builtin_scalar_functions(self);
     }
@@ -815,6 +924,7 @@ impl Clone for EventFluxContext {
             sink_mapper_factories: Arc::clone(&self.sink_mapper_factories),
             table_factories: Arc::clone(&self.table_factories),
             collection_aggregation_functions: Arc::clone(&self.collection_aggregation_functions),
+            script_languages: Arc::clone(&self.script_languages),
             data_source_configs: Arc::clone(&self.data_source_configs),
             data_sources: Arc::clone(&self.data_sources),
             tables: Arc::clone(&self.tables),
@@ -2,7 +2,11 @@
 
 // Corresponds to io.eventflux.core.config.EventFluxQueryContext
 use super::eventflux_app_context::EventFluxAppContext;
+use crate::core::executor::collection_aggregation_executor::{
+    CollectionAggregationExecutor, PatternCollections,
+};
 use crate::core::persistence::StateHolder;
+use crate::core::query::selector::AnalyticFunction;
 use crate::core::util::id_generator::IdGenerator;
 use crate::query_api::execution::query::output::OutputEventType;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -29,6 +33,11 @@ pub struct EventFluxQueryContext {
     pub id_generator: IdGenerator,                          // new-ed in Java constructor
     pub stateful: AtomicBool,                               // whether any state holders registered
     pub aggregator_counter: AtomicUsize,                    // counter for unique aggregator IDs
+    /// Analytic (`OVER`) calls parsed for this query, collected by the select processor
+    analytic_functions: Mutex<Vec<Arc<AnalyticFunction>>>,
+    /// Count-quantified captures of a pattern query and the collection
+    /// aggregations parsed over them, collected by the pattern terminal
+    pattern_collections: Mutex<Option<PatternCollections>>,
 }
 
 impl Clone for EventFluxQueryContext {
@@ -43,6 +52,8 @@ impl Clone for EventFluxQueryContext {
             id_generator: self.id_generator.clone(),
             stateful: AtomicBool::new(self.stateful.load(Ordering::SeqCst)),
             aggregator_counter: AtomicUsize::new(self.aggregator_counter.load(Ordering::SeqCst)),
+            analytic_functions: Mutex::new(self.analytic_functions.lock().unwrap().clone()),
+            pattern_collections: Mutex::new(self.pattern_collections.lock().unwrap().clone()),
         }
     }
 }
@@ -64,6 +75,8 @@ impl EventFluxQueryContext {
             id_generator: IdGenerator::default(),
             stateful: AtomicBool::new(false),
             aggregator_counter: AtomicUsize::new(0),
+            analytic_functions: Mutex::new(Vec::new()),
+            pattern_collections: Mutex::new(None),
         }
     }
 
@@ -123,6 +136,56 @@ impl EventFluxQueryContext {
         self.aggregator_counter.fetch_add(1, Ordering::SeqCst)
     }
 
+    /// Record an analytic function parsed from this query's select list
+    pub fn register_analytic_function(&self, function: Arc<AnalyticFunction>) {
+        self.analytic_functions.lock().unwrap().push(function);
+    }
+
+    /// Take the analytic functions registered since the last call
+    pub fn take_analytic_functions(&self) -> Vec<Arc<AnalyticFunction>> {
+        std::mem::take(&mut *self.analytic_functions.lock().unwrap())
+    }
+
+    /// Declare the count-quantified captures of this pattern query
+    pub fn set_pattern_collections(&self, collections: PatternCollections) {
+        *self.pattern_collections.lock().unwrap() = Some(collections);
+    }
+
+    /// Chain position and stream of a count-quantified capture
+    pub fn pattern_capture(
+        &self,
+        alias: &str,
+    ) -> Option<(usize, Arc<crate::query_api::definition::StreamDefinition>)> {
+        self.pattern_collections
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|c| c.capture(alias))
+    }
+
+    /// Record a collection aggregation over a pattern capture and return the
+    /// index of its result in the flattened match
+    pub fn register_collection_aggregation(
+        &self,
+        executor: CollectionAggregationExecutor,
+    ) -> Option<usize> {
+        self.pattern_collections
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(|c| c.register(executor))
+    }
+
+    /// Take the collection aggregations registered since the last call
+    pub fn take_collection_aggregations(&self) -> Vec<CollectionAggregationExecutor> {
+        self.pattern_collections
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(|c| c.take_executors())
+            .unwrap_or_default()
+    }
+
     /// Register a state holder with the application's `SnapshotService`.
     /// The provided `name` is namespaced by the query name to ensure uniqueness.
     pub fn register_state_holder(&self, name: String, holder: Arc<Mutex<dyn StateHolder>>) {
@@ -275,6 +275,26 @@ mod tests {
         assert!(persistence.enabled);
     }
 
+    #[test]
+    fn test_apply_config_overrides_persistence_interval() {
+        let mut config = EventFluxConfig::default();
+
+        let overrides = vec![
+            "eventflux.persistence.type=file".to_string(),
+            "eventflux.persistence.interval=30s".to_string(),
+            "eventflux.persistence.restore_on_start=false".to_string(),
+        ];
+
+        apply_config_overrides(&mut config, &overrides).unwrap();
+
+        let persistence = config.eventflux.persistence.as_ref().unwrap();
+        assert_eq!(
+            persistence.interval,
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert!(!persistence.restore_on_start);
+    }
+
     #[test]
     fn test_apply_config_overrides_runtime() {
         let mut config = EventFluxConfig::default();
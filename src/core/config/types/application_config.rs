@@ -1093,10 +1093,26 @@ pub struct PersistenceConfig {
     #[serde(with = "duration_serde", default = "default_checkpoint_interval")]
     pub checkpoint_interval: Duration,
 
+    /// Interval between automatic snapshots, overriding `eventflux.persistence.interval`
+    #[serde(
+        default,
+        with = "optional_duration_serde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub interval: Option<Duration>,
+
     /// Enable incremental checkpointing
     #[serde(default = "default_true")]
     pub incremental: bool,
 
+    /// Directory for incremental checkpoints and the write-ahead log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Incremental checkpoints taken between two full checkpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_incremental_count: Option<usize>,
+
     /// Persistence-specific configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<serde_yaml::Value>,
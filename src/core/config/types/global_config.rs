@@ -4,7 +4,7 @@
 //!
 //! Defines global runtime configuration for EventFlux applications.
 
-use super::duration_serde;
+use super::{duration_serde, optional_duration_serde};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -1056,6 +1056,17 @@ fn default_checkpoint_interval() -> Duration {
 ///     type: redis
 ///     url: redis://localhost:6379
 /// ```
+///
+/// With an `interval`, every application is snapshotted periodically and
+/// resumes from its last revision when it starts:
+///
+/// ```yaml
+/// eventflux:
+///   persistence:
+///     type: file
+///     path: ./snapshots
+///     interval: 30s
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GlobalPersistenceConfig {
     /// Persistence backend type
@@ -1078,6 +1089,18 @@ pub struct GlobalPersistenceConfig {
     #[serde(with = "duration_serde", default = "default_checkpoint_interval")]
     pub checkpoint_interval: Duration,
 
+    /// Interval between automatic snapshots (none disables periodic persistence)
+    #[serde(
+        default,
+        with = "optional_duration_serde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub interval: Option<Duration>,
+
+    /// Restore the last persisted revision when an application starts
+    #[serde(default = "default_true")]
+    pub restore_on_start: bool,
+
     /// Enable compression for persisted state
     #[serde(default)]
     pub compression: PersistenceCompression,
@@ -1095,6 +1118,8 @@ impl Default for GlobalPersistenceConfig {
             url: None,
             enabled: true,
             checkpoint_interval: default_checkpoint_interval(),
+            interval: None,
+            restore_on_start: true,
             compression: PersistenceCompression::default(),
             options: std::collections::HashMap::new(),
         }
@@ -1143,6 +1168,7 @@ impl GlobalPersistenceConfig {
         self.backend_type = other.backend_type;
         self.enabled = other.enabled;
         self.checkpoint_interval = other.checkpoint_interval;
+        self.restore_on_start = other.restore_on_start;
         self.compression = other.compression;
 
         if other.path.is_some() {
@@ -1151,6 +1177,9 @@ impl GlobalPersistenceConfig {
         if other.url.is_some() {
             self.url = other.url;
         }
+        if other.interval.is_some() {
+            self.interval = other.interval;
+        }
 
         // Merge options
         self.options.extend(other.options);
@@ -50,7 +50,7 @@ use std::time::Duration;
 
 // Utility function for duration parsing from strings
 pub fn parse_duration(s: &str) -> Result<Duration, String> {
-    // Support formats like "30s", "5m", "1h", "2d"
+    // Support formats like "250ms", "30s", "5m", "1h", "2d" and "1 hour"
     let s = s.trim();
     if s.is_empty() {
         return Err("Empty duration string".to_string());
@@ -62,11 +62,13 @@ pub fn parse_duration(s: &str) -> Result<Duration, String> {
         return Err("Duration must include a unit (s, m, h, d)".to_string());
     };
 
+    let number_part = number_part.trim();
     let number: f64 = number_part
         .parse()
         .map_err(|_| format!("Invalid number in duration: {}", number_part))?;
 
     let multiplier = match unit_part.to_lowercase().as_str() {
+        "ms" | "millisecond" | "milliseconds" => 0.001,
         "s" | "sec" | "second" | "seconds" => 1.0,
         "m" | "min" | "minute" | "minutes" => 60.0,
         "h" | "hr" | "hour" | "hours" => 3600.0,
@@ -149,6 +151,8 @@ mod tests {
         assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
         assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(172800));
         assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("1 hour").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
 
         assert!(parse_duration("").is_err());
         assert!(parse_duration("30").is_err());
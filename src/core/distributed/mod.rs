@@ -15,7 +15,9 @@ pub mod processing_engine;
 pub mod runtime_mode;
 pub mod state_backend;
 pub mod transport;
-pub use state_backend::{InMemoryBackend, RedisBackend, RedisConfig, StateBackend};
+pub use state_backend::{
+    shared_redis_pool, InMemoryBackend, RedisBackend, RedisConfig, StateBackend,
+};
 pub mod coordinator;
 pub mod message_broker;
 
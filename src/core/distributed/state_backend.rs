@@ -11,6 +11,7 @@
 use super::{DistributedError, DistributedResult};
 use async_trait::async_trait;
 use deadpool_redis::{Config, Pool, Runtime};
+use once_cell::sync::Lazy;
 use redis::{AsyncCommands, RedisResult};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -130,6 +131,43 @@ impl Default for RedisConfig {
     }
 }
 
+/// Pools by Redis URL, shared by every state backend, persistence store and
+/// table connecting to the same server
+static SHARED_POOLS: Lazy<std::sync::Mutex<HashMap<String, Pool>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Connection pool for `config.url`. The first caller for a URL creates the
+/// pool with its size and timeout; later callers share it.
+pub fn shared_redis_pool(config: &RedisConfig) -> DistributedResult<Pool> {
+    let mut pools = SHARED_POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&config.url) {
+        return Ok(pool.clone());
+    }
+
+    let mut cfg = Config::from_url(&config.url);
+
+    // Initialize pool config if it doesn't exist
+    if cfg.pool.is_none() {
+        cfg.pool = Some(deadpool_redis::PoolConfig::default());
+    }
+
+    // Configure pool settings
+    if let Some(ref mut pool_config) = cfg.pool {
+        pool_config.max_size = config.max_connections;
+        pool_config.timeouts.wait = Some(std::time::Duration::from_millis(
+            config.connection_timeout_ms,
+        ));
+    }
+
+    let pool =
+        cfg.create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| DistributedError::StateError {
+                message: format!("Failed to create Redis pool: {}", e),
+            })?;
+    pools.insert(config.url.clone(), pool.clone());
+    Ok(pool)
+}
+
 /// Redis state backend for distributed state management
 pub struct RedisBackend {
     pool: Option<Pool>,
@@ -213,26 +251,7 @@ impl RedisBackend {
 #[async_trait]
 impl StateBackend for RedisBackend {
     async fn initialize(&mut self) -> DistributedResult<()> {
-        let mut cfg = Config::from_url(&self.config.url);
-
-        // Initialize pool config if it doesn't exist
-        if cfg.pool.is_none() {
-            cfg.pool = Some(deadpool_redis::PoolConfig::default());
-        }
-
-        // Configure pool settings
-        if let Some(ref mut pool_config) = cfg.pool {
-            pool_config.max_size = self.config.max_connections;
-            pool_config.timeouts.wait = Some(std::time::Duration::from_millis(
-                self.config.connection_timeout_ms,
-            ));
-        }
-
-        let pool =
-            cfg.create_pool(Some(Runtime::Tokio1))
-                .map_err(|e| DistributedError::StateError {
-                    message: format!("Failed to create Redis pool: {}", e),
-                })?;
+        let pool = shared_redis_pool(&self.config)?;
 
         // Test connection
         let conn = pool.get().await.map_err(|e| DistributedError::StateError {
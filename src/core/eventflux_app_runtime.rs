@@ -67,6 +67,11 @@ pub struct EventFluxAppRuntime {
     pub query_runtimes: Vec<Arc<QueryRuntime>>,
     pub partition_runtimes: Vec<Arc<PartitionRuntime>>,
     pub trigger_runtimes: Vec<Arc<TriggerRuntime>>,
+    pub table_eviction_runtimes: Vec<Arc<crate::core::table::TableEvictionRuntime>>,
+    pub table_flush_runtimes: Vec<Arc<crate::core::table::TableFlushRuntime>>,
+    pub table_load_runtimes: Vec<Arc<crate::core::table::TableLoadRuntime>>,
+    pub snapshot_runtime: Option<Arc<crate::core::persistence::SnapshotRuntime>>,
+    pub checkpoint_runtime: Option<Arc<crate::core::persistence::CheckpointRuntime>>,
     pub scheduler: Option<Arc<crate::core::util::Scheduler>>,
     pub table_map: HashMap<String, Arc<Mutex<TableRuntimePlaceholder>>>,
     pub window_map: HashMap<String, Arc<Mutex<WindowRuntime>>>,
@@ -108,6 +113,21 @@ impl EventFluxAppRuntime {
         // EventFluxContext is needed to initialize EventFluxAppContext if not already done
         eventflux_context: Arc<crate::core::config::eventflux_context::EventFluxContext>,
         eventflux_app_string: Option<String>,
+    ) -> Result<Self, String> {
+        Self::new_with_annotations(
+            api_eventflux_app,
+            eventflux_context,
+            eventflux_app_string,
+            Arc::new(EventFluxConfig::default()),
+        )
+    }
+
+    /// Internal method to create runtime from @app annotations under the given global configuration
+    fn new_with_annotations(
+        api_eventflux_app: Arc<ApiEventFluxApp>,
+        eventflux_context: Arc<crate::core::config::eventflux_context::EventFluxContext>,
+        eventflux_app_string: Option<String>,
+        global_config: Arc<EventFluxConfig>,
     ) -> Result<Self, String> {
         // 1. Create EventFluxAppContext using @app level annotations when present
         let mut name = api_eventflux_app.name.clone();
@@ -159,6 +179,7 @@ impl EventFluxAppRuntime {
             Arc::clone(&api_eventflux_app),
             eventflux_app_string.unwrap_or_default(),
         );
+        ctx.update_global_config(global_config);
         ctx.set_playback(is_playback);
         ctx.set_enforce_order(enforce_order);
         ctx.set_root_metrics_level(root_metrics);
@@ -191,6 +212,7 @@ impl EventFluxAppRuntime {
         }
         let snapshot_service = Arc::new(ss);
         ctx.set_snapshot_service(Arc::clone(&snapshot_service));
+        ctx.register_script_functions()?;
         let eventflux_app_context = Arc::new(ctx);
 
         // 2. Parse the ApiEventFluxApp into a builder
@@ -209,6 +231,7 @@ impl EventFluxAppRuntime {
         api_eventflux_app: Arc<ApiEventFluxApp>,
         eventflux_context: Arc<crate::core::config::eventflux_context::EventFluxContext>,
         eventflux_app_string: Option<String>,
+        global_config: Arc<EventFluxConfig>,
         app_config: Option<ApplicationConfig>,
     ) -> Result<Self, String> {
         // If we have application configuration, apply it before creating the runtime
@@ -218,11 +241,17 @@ impl EventFluxAppRuntime {
                 api_eventflux_app,
                 eventflux_context,
                 eventflux_app_string,
+                global_config,
                 &config,
             )
         } else {
             // Fall back to standard creation if no config provided
-            Self::new(api_eventflux_app, eventflux_context, eventflux_app_string)
+            Self::new_with_annotations(
+                api_eventflux_app,
+                eventflux_context,
+                eventflux_app_string,
+                global_config,
+            )
         }
     }
 
@@ -231,6 +260,7 @@ impl EventFluxAppRuntime {
         api_eventflux_app: Arc<ApiEventFluxApp>,
         eventflux_context: Arc<crate::core::config::eventflux_context::EventFluxContext>,
         eventflux_app_string: Option<String>,
+        global_config: Arc<EventFluxConfig>,
         app_config: &ApplicationConfig,
     ) -> Result<Self, String> {
         // 1. Create EventFluxAppContext using YAML/TOML configuration (ApplicationConfig)
@@ -251,10 +281,10 @@ impl EventFluxAppRuntime {
             eventflux_context,
             name.clone(),
             Arc::clone(&api_eventflux_app),
-            String::new(),                        // eventflux_app_string
-            Arc::new(EventFluxConfig::default()), // global_config
-            Some(app_config.clone()),             // app_config
-            None,                                 // config_manager
+            String::new(),            // eventflux_app_string
+            global_config,            // global_config
+            Some(app_config.clone()), // app_config
+            None,                     // config_manager
         );
 
         ctx.set_root_metrics_level(root_metrics);
@@ -279,6 +309,7 @@ impl EventFluxAppRuntime {
         }
         let snapshot_service = Arc::new(ss);
         ctx.set_snapshot_service(Arc::clone(&snapshot_service));
+        ctx.register_script_functions()?;
         let eventflux_app_context = Arc::new(ctx);
 
         // 2. Parse the ApiEventFluxApp into a builder with configuration
@@ -814,7 +845,30 @@ impl EventFluxAppRuntime {
             )));
         }
 
-        // 4. Execution phase - start all components (with rollback on error)
+        // 4. Resume from the last checkpoint or persisted revision before any event flows
+        let recovered = match self.recover_checkpoint() {
+            Ok(recovered) => recovered.is_some(),
+            Err(e) => {
+                log::error!("Failed to recover from checkpoints: {}", e);
+                *self.state.write().unwrap() = RuntimeState::Failed;
+                return Err(EventFluxError::app_runtime(format!(
+                    "Failed to recover from checkpoints: {}",
+                    e
+                )));
+            }
+        };
+        if !recovered && self.eventflux_app_context.is_restore_on_start_enabled() {
+            if let Err(e) = self.restore_last_revision() {
+                log::error!("Failed to restore last revision: {}", e);
+                *self.state.write().unwrap() = RuntimeState::Failed;
+                return Err(EventFluxError::app_runtime(format!(
+                    "Failed to restore last revision: {}",
+                    e
+                )));
+            }
+        }
+
+        // 5. Execution phase - start all components (with rollback on error)
         // Start all registered sources
         if let Err(e) = self.start_all_sources() {
             log::error!("Failed to start sources: {}", e);
@@ -849,21 +903,45 @@ impl EventFluxAppRuntime {
             tr.start();
         }
 
+        // Start table row eviction
+        for er in &self.table_eviction_runtimes {
+            er.start();
+        }
+
+        // Start write-behind flushes of cached tables
+        for fr in &self.table_flush_runtimes {
+            fr.start();
+        }
+
+        // Start periodic reloads of preloaded tables
+        for lr in &self.table_load_runtimes {
+            lr.start();
+        }
+
         // Start partitions
         for pr in &self.partition_runtimes {
             pr.start();
         }
 
-        // 5. Success - update state
+        // Start periodic snapshots
+        if let Some(sr) = &self.snapshot_runtime {
+            sr.start();
+        }
+
+        // Start incremental checkpoints
+        if let Some(cr) = &self.checkpoint_runtime {
+            cr.start();
+        }
+
+        // 6. Success - update state
         *self.state.write().unwrap() = RuntimeState::Running;
         log::info!("EventFluxAppRuntime '{}' started successfully", self.name);
         Ok(())
     }
 
     pub fn shutdown(&self) {
-        // Stop all source and sink handlers first
+        // Stop sources first; sinks stay up until buffered output is flushed
         self.stop_all_sources();
-        self.stop_all_sinks();
 
         if let Some(scheduler) = &self.scheduler {
             scheduler.shutdown();
@@ -871,16 +949,109 @@ impl EventFluxAppRuntime {
         for tr in &self.trigger_runtimes {
             tr.shutdown();
         }
+        if let Some(sr) = &self.snapshot_runtime {
+            sr.shutdown();
+        }
+        if let Some(cr) = &self.checkpoint_runtime {
+            cr.shutdown();
+        }
+        for er in &self.table_eviction_runtimes {
+            er.shutdown();
+        }
+        for lr in &self.table_load_runtimes {
+            lr.shutdown();
+        }
         for pr in &self.partition_runtimes {
             pr.shutdown();
         }
         for qr in &self.query_runtimes {
             qr.flush();
         }
+        // Write out buffered table writes, including those flushed above
+        for fr in &self.table_flush_runtimes {
+            fr.shutdown();
+        }
+        self.stop_all_sinks();
         // Persisted revisions are retained after shutdown for potential restoration
         log::info!("EventFluxAppRuntime '{}' shutdown", self.name);
     }
 
+    /// Shut down without losing in-flight events.
+    ///
+    /// Sources are stopped first and events already buffered in stream junctions
+    /// are processed (waiting at most `drain_timeout`). When a persistence store is
+    /// configured a final snapshot is taken, and a final checkpoint when the app
+    /// checkpoints incrementally, before the remaining components are shut down
+    /// and buffered output is flushed to the sinks.
+    pub fn graceful_shutdown(&self, drain_timeout: std::time::Duration) {
+        self.stop_all_sources();
+        if let Some(sr) = &self.snapshot_runtime {
+            sr.shutdown();
+        }
+        if let Some(cr) = &self.checkpoint_runtime {
+            cr.shutdown();
+        }
+
+        if !self.drain_junctions(drain_timeout) {
+            log::warn!(
+                "EventFluxAppRuntime '{}' did not drain within {:?}",
+                self.name,
+                drain_timeout
+            );
+        }
+
+        let has_store = self
+            .eventflux_app_context
+            .get_snapshot_service()
+            .is_some_and(|service| service.persistence_store.is_some());
+        if has_store {
+            // Threads still delivering events must not race the snapshot,
+            // as with the periodic snapshots
+            let persisted = match self.eventflux_app_context.get_thread_barrier() {
+                Some(barrier) => barrier.paused(|| self.persist()),
+                None => self.persist(),
+            };
+            match persisted {
+                Ok(report) => log::info!(
+                    "Persisted final snapshot of '{}' (revision: {})",
+                    self.name,
+                    report.revision
+                ),
+                Err(e) => log::error!("Failed to persist final snapshot of '{}': {}", self.name, e),
+            }
+        }
+        if self.checkpoint_runtime.is_some() {
+            if let Err(e) = self.checkpoint() {
+                log::error!("Failed to take final checkpoint of '{}': {}", self.name, e);
+            }
+        }
+
+        self.shutdown();
+    }
+
+    /// Wait until every stream junction has handed off its buffered events
+    /// and no thread is processing an event.
+    fn drain_junctions(&self, timeout: std::time::Duration) -> bool {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let drained = self
+                .stream_junction_map
+                .values()
+                .all(|junction| junction.lock().unwrap().is_drained())
+                && self
+                    .eventflux_app_context
+                    .get_thread_barrier()
+                    .is_none_or(|barrier| barrier.get_active_threads() == 0);
+            if drained {
+                return true;
+            }
+            if std::time::Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
     /// Persist the current snapshot using the configured SnapshotService.
     ///
     /// # Returns
@@ -898,6 +1069,21 @@ impl EventFluxAppRuntime {
         service.persist()
     }
 
+    /// Take an incremental checkpoint of the current state.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(CheckpointResult))` - The checkpoint that was stored
+    /// * `Ok(None)` - Nothing changed since the previous checkpoint
+    /// * `Err(String)` - Incremental checkpoints are not configured or the checkpoint failed
+    pub fn checkpoint(&self) -> Result<Option<crate::core::persistence::CheckpointResult>, String> {
+        let runtime = self
+            .checkpoint_runtime
+            .as_ref()
+            .ok_or("Incremental checkpoints not configured")?;
+        runtime.checkpoint().map_err(|e| e.to_string())
+    }
+
     /// Capture a snapshot of the current state via the SnapshotService.
     pub fn snapshot(&self) -> Result<Vec<u8>, String> {
         let service = self
@@ -926,32 +1112,67 @@ impl EventFluxAppRuntime {
 
         // Use ThreadBarrier to coordinate with event processing threads
         if let Some(barrier) = self.eventflux_app_context.get_thread_barrier() {
-            // Lock the barrier to prevent new events from entering
-            barrier.lock();
-
-            // Wait for all active threads to complete their current processing
-            while barrier.get_active_threads() > 0 {
-                std::thread::sleep(std::time::Duration::from_millis(1));
-            }
-
             // Perform the restoration while event processing is blocked
-            let result = service.restore_revision(revision);
+            barrier.paused(|| {
+                let result = service.restore_revision(revision);
 
-            // Clear SelectProcessor group states after restoration to ensure fresh aggregator state
-            if result.is_ok() {
-                self.clear_select_processor_group_states();
-            }
-
-            // Unlock the barrier to resume normal processing
-            barrier.unlock();
-
-            result
+                // Clear SelectProcessor group states after restoration to ensure fresh aggregator state
+                if result.is_ok() {
+                    self.clear_select_processor_group_states();
+                }
+                result
+            })
         } else {
             // No barrier configured, proceed with restoration (may have timing issues)
             service.restore_revision(revision)
         }
     }
 
+    /// Restore the last revision persisted for this application, if any.
+    ///
+    /// Returns the restored revision, or `None` when no persistence store is
+    /// configured or nothing has been persisted yet.
+    pub fn restore_last_revision(&self) -> Result<Option<String>, String> {
+        let Some(store) = self
+            .eventflux_app_context
+            .get_snapshot_service()
+            .and_then(|service| service.persistence_store.clone())
+        else {
+            return Ok(None);
+        };
+        let Some(revision) = store.get_last_revision(&self.name) else {
+            return Ok(None);
+        };
+        self.restore_revision(&revision)?;
+        log::info!(
+            "EventFluxAppRuntime '{}' restored revision {}",
+            self.name,
+            revision
+        );
+        Ok(Some(revision))
+    }
+
+    /// Recover the state from the last full checkpoint and the incremental
+    /// checkpoints and write-ahead log entries that follow it.
+    ///
+    /// Returns the last checkpoint applied, or `None` when incremental checkpoints
+    /// are not configured or none has been taken yet.
+    fn recover_checkpoint(&self) -> Result<Option<crate::core::persistence::CheckpointId>, String> {
+        let Some(runtime) = &self.checkpoint_runtime else {
+            return Ok(None);
+        };
+        let recovered = runtime.recover().map_err(|e| e.to_string())?;
+        if let Some(checkpoint_id) = recovered {
+            self.clear_select_processor_group_states();
+            log::info!(
+                "EventFluxAppRuntime '{}' recovered checkpoint {}",
+                self.name,
+                checkpoint_id
+            );
+        }
+        Ok(recovered)
+    }
+
     /// Clear group states in all SelectProcessors to ensure fresh state after restoration
     fn clear_select_processor_group_states(&self) {
         for query_runtime in &self.query_runtimes {
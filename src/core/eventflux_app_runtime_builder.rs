@@ -6,6 +6,7 @@ use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::config::ApplicationConfig;
 use crate::core::eventflux_app_runtime::EventFluxAppRuntime; // Actual EventFluxAppRuntime
 use crate::core::partition::PartitionRuntime;
+use crate::core::persistence::{CheckpointRuntime, IncrementalCheckpointSystem, SnapshotRuntime};
 use crate::core::query::query_runtime::QueryRuntime;
 use crate::core::stream::stream_junction::StreamJunction;
 // use crate::core::window::WindowRuntime; // TODO: Will be used when window runtime is implemented
@@ -18,6 +19,7 @@ use std::sync::{Arc, Mutex};
 // Placeholders for runtime components until they are defined
 #[derive(Debug, Clone, Default)]
 pub struct TableRuntimePlaceholder {}
+use crate::core::table::{TableEvictionRuntime, TableFlushRuntime, TableLoadRuntime};
 use crate::core::trigger::TriggerRuntime;
 
 #[derive(Debug)]
@@ -39,6 +41,9 @@ pub struct EventFluxAppRuntimeBuilder {
     pub query_runtimes: Vec<Arc<QueryRuntime>>,
     pub partition_runtimes: Vec<Arc<PartitionRuntime>>,
     pub trigger_runtimes: Vec<Arc<TriggerRuntime>>,
+    pub table_eviction_runtimes: Vec<Arc<TableEvictionRuntime>>,
+    pub table_flush_runtimes: Vec<Arc<TableFlushRuntime>>,
+    pub table_load_runtimes: Vec<Arc<TableLoadRuntime>>,
 }
 
 impl EventFluxAppRuntimeBuilder {
@@ -60,6 +65,9 @@ impl EventFluxAppRuntimeBuilder {
             query_runtimes: Vec::new(),
             partition_runtimes: Vec::new(),
             trigger_runtimes: Vec::new(),
+            table_eviction_runtimes: Vec::new(),
+            table_flush_runtimes: Vec::new(),
+            table_load_runtimes: Vec::new(),
         }
     }
 
@@ -127,6 +135,15 @@ impl EventFluxAppRuntimeBuilder {
     pub fn add_trigger_runtime(&mut self, trigger_runtime: Arc<TriggerRuntime>) {
         self.trigger_runtimes.push(trigger_runtime);
     }
+    pub fn add_table_eviction_runtime(&mut self, eviction_runtime: Arc<TableEvictionRuntime>) {
+        self.table_eviction_runtimes.push(eviction_runtime);
+    }
+    pub fn add_table_flush_runtime(&mut self, flush_runtime: Arc<TableFlushRuntime>) {
+        self.table_flush_runtimes.push(flush_runtime);
+    }
+    pub fn add_table_load_runtime(&mut self, load_runtime: Arc<TableLoadRuntime>) {
+        self.table_load_runtimes.push(load_runtime);
+    }
 
     // build() method that consumes the builder and returns a EventFluxAppRuntime
     pub fn build(
@@ -148,6 +165,53 @@ impl EventFluxAppRuntimeBuilder {
                 )))
             });
 
+        // Periodic snapshots need somewhere to write them
+        let snapshot_runtime =
+            self.eventflux_app_context
+                .get_snapshot_interval()
+                .and_then(|interval| {
+                    let service = self.eventflux_app_context.get_snapshot_service()?;
+                    if service.persistence_store.is_none() {
+                        log::warn!(
+                        "Persistence interval set for '{}' but no persistence store is configured",
+                        self.eventflux_app_context.name
+                    );
+                        return None;
+                    }
+                    Some(Arc::new(SnapshotRuntime::new(
+                        service,
+                        self.eventflux_app_context.get_thread_barrier(),
+                        interval.as_millis() as i64,
+                        Arc::clone(&scheduler),
+                    )))
+                });
+
+        // Incremental checkpoints are kept on disk next to their write-ahead log
+        let checkpoint_runtime = self
+            .eventflux_app_context
+            .get_incremental_checkpoint_config()
+            .and_then(|config| {
+                let service = self.eventflux_app_context.get_snapshot_service()?;
+                let interval_ms = config.checkpoint_interval.as_millis() as i64;
+                match IncrementalCheckpointSystem::new(config) {
+                    Ok(system) => Some(Arc::new(CheckpointRuntime::new(
+                        system,
+                        service,
+                        self.eventflux_app_context.get_thread_barrier(),
+                        interval_ms,
+                        Arc::clone(&scheduler),
+                    ))),
+                    Err(e) => {
+                        log::error!(
+                            "Failed to set up incremental checkpoints for '{}': {}",
+                            self.eventflux_app_context.name,
+                            e
+                        );
+                        None
+                    }
+                }
+            });
+
         // Pre-resolve all element configurations (YAML base + SQL WITH overrides)
         // This happens ONCE at construction time, before any attachment
         let resolved_configs = {
@@ -178,6 +242,11 @@ impl EventFluxAppRuntimeBuilder {
             window_map: self.window_map,
             aggregation_map: self.aggregation_map,
             trigger_runtimes: self.trigger_runtimes,
+            table_eviction_runtimes: self.table_eviction_runtimes,
+            table_flush_runtimes: self.table_flush_runtimes,
+            table_load_runtimes: self.table_load_runtimes,
+            snapshot_runtime,
+            checkpoint_runtime,
             source_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
             sink_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
             table_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
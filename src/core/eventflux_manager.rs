@@ -141,14 +141,16 @@ impl EventFluxManager {
             ));
         }
 
-        // Get application-specific configuration
-        let app_config = self.get_application_config(&app_name).await?;
+        // Get global and application-specific configuration
+        let config = self.get_config().await?;
+        let app_config = config.applications.get(&app_name).cloned();
 
         // EventFluxAppRuntime::new now handles creation of EventFluxAppContext and parsing
         let runtime = Arc::new(EventFluxAppRuntime::new_with_config(
             Arc::clone(&api_eventflux_app),
             Arc::clone(&self.eventflux_context),
             eventflux_app_str_opt.clone(),
+            Arc::new(config),
             app_config,
         )?);
 
@@ -229,25 +231,61 @@ impl EventFluxManager {
         name: &str,
         library_path: ExtensionClassPlaceholder,
     ) -> Result<(), String> {
+        if library_path.ends_with(".wasm") {
+            return self.set_wasm_extension(name, &library_path);
+        }
         unsafe {
             let lib = Library::new(&library_path).map_err(|e| e.to_string())?;
 
-            macro_rules! call_if_exists {
-                ($sym:expr) => {
-                    if let Ok(f) = lib.get::<extension::RegisterFn>($sym) {
-                        f(self);
-                    }
-                };
-            }
+            if let Ok(version_fn) =
+                lib.get::<extension::abi::AbiVersionFn>(extension::abi::ABI_VERSION_SYMBOL)
+            {
+                extension::abi::check_abi_version(name, version_fn())?;
+                let extension_fn = lib
+                    .get::<extension::abi::AbiExtensionFn>(extension::abi::ABI_EXTENSION_SYMBOL)
+                    .map_err(|_| {
+                        format!(
+                            "Extension '{name}' exports eventflux_abi_version but not eventflux_extension"
+                        )
+                    })?;
+                let descriptor = extension_fn();
+                if descriptor.is_null() {
+                    return Err(format!("Extension '{name}' returned a null descriptor"));
+                }
+                // The library is never unloaded, so its descriptor lives for the
+                // rest of the process.
+                extension::abi::register_extension(&self.eventflux_context, &*descriptor)?;
+            } else {
+                let mut found = false;
+                macro_rules! call_if_exists {
+                    ($sym:expr) => {
+                        if let Ok(f) = lib.get::<extension::RegisterFn>($sym) {
+                            f(self);
+                            found = true;
+                        }
+                    };
+                }
 
-            call_if_exists!(extension::REGISTER_EXTENSION_FN);
-            call_if_exists!(extension::REGISTER_WINDOWS_FN);
-            call_if_exists!(extension::REGISTER_FUNCTIONS_FN);
-            call_if_exists!(extension::REGISTER_SOURCES_FN);
-            call_if_exists!(extension::REGISTER_SINKS_FN);
-            call_if_exists!(extension::REGISTER_STORES_FN);
-            call_if_exists!(extension::REGISTER_SOURCE_MAPPERS_FN);
-            call_if_exists!(extension::REGISTER_SINK_MAPPERS_FN);
+                call_if_exists!(extension::REGISTER_EXTENSION_FN);
+                call_if_exists!(extension::REGISTER_WINDOWS_FN);
+                call_if_exists!(extension::REGISTER_FUNCTIONS_FN);
+                call_if_exists!(extension::REGISTER_SOURCES_FN);
+                call_if_exists!(extension::REGISTER_SINKS_FN);
+                call_if_exists!(extension::REGISTER_STORES_FN);
+                call_if_exists!(extension::REGISTER_SOURCE_MAPPERS_FN);
+                call_if_exists!(extension::REGISTER_SINK_MAPPERS_FN);
+
+                if !found {
+                    return Err(format!(
+                        "{library_path} is not an EventFlux extension: it exports neither \
+                         eventflux_abi_version nor any register_* entry point"
+                    ));
+                }
+                log::warn!(
+                    "[EventFluxManager] extension '{name}' uses the unstable register_* entry points; \
+                     it must be built with the same compiler and EventFlux version as the host"
+                );
+            }
 
             self.loaded_libraries
                 .lock()
@@ -259,6 +297,24 @@ impl EventFluxManager {
         Ok(())
     }
 
+    /// Load a sandboxed `.wasm` plugin and register what its manifest declares
+    #[cfg(feature = "wasm")]
+    fn set_wasm_extension(&self, name: &str, path: &str) -> Result<(), String> {
+        use crate::core::extension::wasm::{register_plugin, WasmLimits, WasmPlugin};
+
+        let plugin = WasmPlugin::from_file(name, path, WasmLimits::default())?;
+        register_plugin(&self.eventflux_context, Arc::new(plugin));
+        log::info!("[EventFluxManager] loaded wasm extension '{name}' from {path}");
+        Ok(())
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn set_wasm_extension(&self, name: &str, path: &str) -> Result<(), String> {
+        Err(format!(
+            "Cannot load wasm extension '{name}' from {path}: built without the 'wasm' feature"
+        ))
+    }
+
     // Specific method for adding scalar function factories
     pub fn add_scalar_function_factory(
         &self,
@@ -575,12 +631,12 @@ impl EventFluxManager {
             .expect("Mutex poisoned")
             .values()
         {
-            if let Some(service) = runtime.eventflux_app_context.get_snapshot_service() {
-                if let Some(store) = &service.persistence_store {
-                    if let Some(last) = store.get_last_revision(&runtime.name) {
-                        let _ = runtime.restore_revision(&last);
-                    }
-                }
+            if let Err(e) = runtime.restore_last_revision() {
+                log::error!(
+                    "App '{}': Failed to restore last revision - {}",
+                    runtime.name,
+                    e
+                );
             }
         }
     }
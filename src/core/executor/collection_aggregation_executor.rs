@@ -40,11 +40,14 @@ use crate::core::event::state::state_event::StateEvent;
 use crate::core::event::stream::stream_event::StreamEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::CollectionAggregationFunction;
 use crate::core::util::eventflux_constants::{
     BEFORE_WINDOW_DATA_INDEX, ON_AFTER_WINDOW_DATA_INDEX, OUTPUT_DATA_INDEX,
     STATE_OUTPUT_DATA_INDEX,
 };
 use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::definition::StreamDefinition;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 // ============================================================================
@@ -775,6 +778,128 @@ impl ExpressionExecutor for CollectionStdDevExecutor {
     }
 }
 
+// ============================================================================
+// CollectionAggregationExecutor (registry-backed)
+// ============================================================================
+
+/// Executor applying a registered [`CollectionAggregationFunction`] to a
+/// pattern collection: `first(e1.user)`, `collect(e1.symbol)`, `min(e1.ip)`
+///
+/// Unlike the numeric executors above, values are passed to the function as
+/// typed `AttributeValue`s, so string and boolean attributes aggregate too.
+/// Events missing the attribute contribute `Null`.
+#[derive(Debug, Clone)]
+pub struct CollectionAggregationExecutor {
+    /// Position in StateEvent.stream_events[] (e1=0, e2=1, ...)
+    pub chain_index: usize,
+
+    /// Attribute position: [data_type_index, attribute_index]; `None` for
+    /// count-only calls such as `count(e1)`
+    pub attribute_position: Option<[i32; 2]>,
+
+    function: Box<dyn CollectionAggregationFunction>,
+    return_type: ApiAttributeType,
+}
+
+impl CollectionAggregationExecutor {
+    /// Create an executor over attribute `input_type` values, or over the
+    /// events themselves when `attribute_position` is `None`
+    pub fn new(
+        chain_index: usize,
+        attribute_position: Option<[i32; 2]>,
+        function: Box<dyn CollectionAggregationFunction>,
+        input_type: ApiAttributeType,
+    ) -> Result<Self, String> {
+        if attribute_position.is_none() && !function.supports_count_only() {
+            return Err(format!(
+                "{}() over a pattern collection requires an attribute, e.g. {}(e1.value)",
+                function.name(),
+                function.name()
+            ));
+        }
+        let return_type = function.return_type(input_type);
+        Ok(Self {
+            chain_index,
+            attribute_position,
+            function,
+            return_type,
+        })
+    }
+}
+
+impl ExpressionExecutor for CollectionAggregationExecutor {
+    fn execute(&self, event_opt: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let complex_event = event_opt?;
+        let state_event = complex_event.as_any().downcast_ref::<StateEvent>()?;
+
+        let values: Vec<AttributeValue> = state_event
+            .get_event_chain(self.chain_index)
+            .into_iter()
+            .map(|stream_event| {
+                self.attribute_position
+                    .and_then(|position| get_attribute_from_event(stream_event, &position))
+                    .unwrap_or(AttributeValue::Null)
+            })
+            .collect();
+        self.function.aggregate(&values)
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(
+        &self,
+        _eventflux_app_context: &Arc<EventFluxAppContext>,
+    ) -> Box<dyn ExpressionExecutor> {
+        Box::new(self.clone())
+    }
+}
+
+/// Collection aggregations in the select list of one pattern query
+///
+/// The selector sees each match flattened into a single event holding only
+/// the first event of every capture. Collection aggregations therefore run on
+/// the matched `StateEvent` before it is flattened, and their results are
+/// appended after the capture attributes, where the select list reads them.
+#[derive(Debug, Clone, Default)]
+pub struct PatternCollections {
+    /// Count-quantified capture alias → (chain position, captured stream)
+    captures: HashMap<String, (usize, Arc<StreamDefinition>)>,
+    /// Number of capture attributes in the flattened event
+    width: usize,
+    executors: Vec<CollectionAggregationExecutor>,
+}
+
+impl PatternCollections {
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// Declare a count-quantified capture such as `e1` in `e1=Login{3,5}`
+    pub fn add_capture(&mut self, alias: String, chain_index: usize, def: Arc<StreamDefinition>) {
+        self.captures.insert(alias, (chain_index, def));
+    }
+
+    pub fn capture(&self, alias: &str) -> Option<(usize, Arc<StreamDefinition>)> {
+        self.captures.get(alias).cloned()
+    }
+
+    /// Add an aggregation and return the index of its result in the
+    /// flattened event
+    pub fn register(&mut self, executor: CollectionAggregationExecutor) -> usize {
+        self.executors.push(executor);
+        self.width + self.executors.len() - 1
+    }
+
+    pub fn take_executors(&mut self) -> Vec<CollectionAggregationExecutor> {
+        std::mem::take(&mut self.executors)
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
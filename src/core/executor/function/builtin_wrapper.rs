@@ -202,6 +202,119 @@ fn build_uuid(
     Ok(Box::new(UuidFunctionExecutor::new()))
 }
 
+fn build_uuid_v5(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 {
+        return Err("uuid_v5() requires two arguments (namespace, name)".to_string());
+    }
+    let name = args.remove(1);
+    let namespace = args.remove(0);
+    Ok(Box::new(UuidV5FunctionExecutor::new(namespace, name)?))
+}
+
+fn build_hash(
+    algorithm: HashAlgorithm,
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err(format!("{}() requires one argument", algorithm.name()));
+    }
+    Ok(Box::new(HashFunctionExecutor::new(
+        algorithm,
+        args.remove(0),
+    )?))
+}
+
+fn build_md5(
+    args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    build_hash(HashAlgorithm::Md5, args)
+}
+
+fn build_sha1(
+    args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    build_hash(HashAlgorithm::Sha1, args)
+}
+
+fn build_sha256(
+    args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    build_hash(HashAlgorithm::Sha256, args)
+}
+
+fn build_crc32(
+    args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    build_hash(HashAlgorithm::Crc32, args)
+}
+
+fn build_xxhash64(
+    args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    build_hash(HashAlgorithm::XxHash64, args)
+}
+
+fn build_hmac_sha256(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 {
+        return Err("hmac_sha256() requires two arguments (key, value)".to_string());
+    }
+    let value = args.remove(1);
+    let key = args.remove(0);
+    Ok(Box::new(HmacSha256FunctionExecutor::new(key, value)?))
+}
+
+fn build_base64_encode(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err("base64_encode() requires one argument".to_string());
+    }
+    Ok(Box::new(EncodeFunctionExecutor::new(
+        BinaryEncoding::Base64,
+        args.remove(0),
+    )?))
+}
+
+fn build_base64_decode(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err("base64_decode() requires one argument".to_string());
+    }
+    Ok(Box::new(DecodeFunctionExecutor::new(
+        BinaryEncoding::Base64,
+        args.remove(0),
+    )?))
+}
+
+fn build_hex(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err("hex() requires one argument".to_string());
+    }
+    Ok(Box::new(EncodeFunctionExecutor::new(
+        BinaryEncoding::Hex,
+        args.remove(0),
+    )?))
+}
+
+fn build_unhex(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err("unhex() requires one argument".to_string());
+    }
+    Ok(Box::new(DecodeFunctionExecutor::new(
+        BinaryEncoding::Hex,
+        args.remove(0),
+    )?))
+}
+
 fn build_now(
     _args: Vec<Box<dyn ExpressionExecutor>>,
 ) -> Result<Box<dyn ExpressionExecutor>, String> {
@@ -641,6 +754,108 @@ fn build_rpad(
     Ok(Box::new(RpadFunctionExecutor::new(str_arg, len, pad)?))
 }
 
+fn build_regexp_matches(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 {
+        return Err("regexp_matches() requires two arguments (value, pattern)".to_string());
+    }
+    let pattern = args.remove(1);
+    let value = args.remove(0);
+    Ok(Box::new(RegexpMatchesFunctionExecutor::new(
+        value, pattern,
+    )?))
+}
+
+fn build_regexp_extract(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(
+            "regexp_extract() requires two or three arguments (value, pattern[, group])"
+                .to_string(),
+        );
+    }
+    let group = if args.len() == 3 {
+        Some(args.remove(2))
+    } else {
+        None
+    };
+    let pattern = args.remove(1);
+    let value = args.remove(0);
+    Ok(Box::new(RegexpExtractFunctionExecutor::new(
+        value, pattern, group,
+    )?))
+}
+
+fn build_regexp_replace(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 3 {
+        return Err(
+            "regexp_replace() requires three arguments (value, pattern, replacement)".to_string(),
+        );
+    }
+    let replacement = args.remove(2);
+    let pattern = args.remove(1);
+    let value = args.remove(0);
+    Ok(Box::new(RegexpReplaceFunctionExecutor::new(
+        value,
+        pattern,
+        replacement,
+    )?))
+}
+
+fn build_split_part(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 3 {
+        return Err("split_part() requires three arguments (value, delimiter, n)".to_string());
+    }
+    let index = args.remove(2);
+    let delim = args.remove(1);
+    let value = args.remove(0);
+    Ok(Box::new(SplitPartFunctionExecutor::new(
+        value, delim, index,
+    )?))
+}
+
+fn build_split(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 {
+        return Err("split() requires two arguments (value, delimiter)".to_string());
+    }
+    let delim = args.remove(1);
+    let value = args.remove(0);
+    Ok(Box::new(SplitFunctionExecutor::new(value, delim)?))
+}
+
+fn build_parse_url(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err("parse_url() requires two or three arguments (url, part[, key])".to_string());
+    }
+    let key = if args.len() == 3 {
+        Some(args.remove(2))
+    } else {
+        None
+    };
+    let part = args.remove(1);
+    let url = args.remove(0);
+    Ok(Box::new(ParseUrlFunctionExecutor::new(url, part, key)?))
+}
+
+fn build_url_decode(
+    mut args: Vec<Box<dyn ExpressionExecutor>>,
+) -> Result<Box<dyn ExpressionExecutor>, String> {
+    if args.len() != 1 {
+        return Err("url_decode() requires one argument".to_string());
+    }
+    Ok(Box::new(UrlDecodeFunctionExecutor::new(args.remove(0))?))
+}
+
 /// Register default builtin scalar functions into the provided EventFluxContext.
 pub fn register_builtin_scalar_functions(
     ctx: &crate::core::config::eventflux_context::EventFluxContext,
@@ -698,6 +913,56 @@ pub fn register_builtin_scalar_functions(
         "uuid".to_string(),
         Box::new(BuiltinScalarFunction::new("uuid", build_uuid)),
     );
+    ctx.add_scalar_function_factory(
+        "uuid_v5".to_string(),
+        Box::new(BuiltinScalarFunction::new("uuid_v5", build_uuid_v5)),
+    );
+    ctx.add_scalar_function_factory(
+        "md5".to_string(),
+        Box::new(BuiltinScalarFunction::new("md5", build_md5)),
+    );
+    ctx.add_scalar_function_factory(
+        "sha1".to_string(),
+        Box::new(BuiltinScalarFunction::new("sha1", build_sha1)),
+    );
+    ctx.add_scalar_function_factory(
+        "sha256".to_string(),
+        Box::new(BuiltinScalarFunction::new("sha256", build_sha256)),
+    );
+    ctx.add_scalar_function_factory(
+        "hmac_sha256".to_string(),
+        Box::new(BuiltinScalarFunction::new("hmac_sha256", build_hmac_sha256)),
+    );
+    ctx.add_scalar_function_factory(
+        "crc32".to_string(),
+        Box::new(BuiltinScalarFunction::new("crc32", build_crc32)),
+    );
+    ctx.add_scalar_function_factory(
+        "xxhash64".to_string(),
+        Box::new(BuiltinScalarFunction::new("xxhash64", build_xxhash64)),
+    );
+    ctx.add_scalar_function_factory(
+        "base64_encode".to_string(),
+        Box::new(BuiltinScalarFunction::new(
+            "base64_encode",
+            build_base64_encode,
+        )),
+    );
+    ctx.add_scalar_function_factory(
+        "base64_decode".to_string(),
+        Box::new(BuiltinScalarFunction::new(
+            "base64_decode",
+            build_base64_decode,
+        )),
+    );
+    ctx.add_scalar_function_factory(
+        "hex".to_string(),
+        Box::new(BuiltinScalarFunction::new("hex", build_hex)),
+    );
+    ctx.add_scalar_function_factory(
+        "unhex".to_string(),
+        Box::new(BuiltinScalarFunction::new("unhex", build_unhex)),
+    );
     ctx.add_scalar_function_factory(
         "now".to_string(),
         Box::new(BuiltinScalarFunction::new("now", build_now)),
@@ -887,4 +1152,41 @@ pub fn register_builtin_scalar_functions(
         "rpad".to_string(),
         Box::new(BuiltinScalarFunction::new("rpad", build_rpad)),
     );
+    ctx.add_scalar_function_factory(
+        "regexp_matches".to_string(),
+        Box::new(BuiltinScalarFunction::new(
+            "regexp_matches",
+            build_regexp_matches,
+        )),
+    );
+    ctx.add_scalar_function_factory(
+        "regexp_extract".to_string(),
+        Box::new(BuiltinScalarFunction::new(
+            "regexp_extract",
+            build_regexp_extract,
+        )),
+    );
+    ctx.add_scalar_function_factory(
+        "regexp_replace".to_string(),
+        Box::new(BuiltinScalarFunction::new(
+            "regexp_replace",
+            build_regexp_replace,
+        )),
+    );
+    ctx.add_scalar_function_factory(
+        "split_part".to_string(),
+        Box::new(BuiltinScalarFunction::new("split_part", build_split_part)),
+    );
+    ctx.add_scalar_function_factory(
+        "split".to_string(),
+        Box::new(BuiltinScalarFunction::new("split", build_split)),
+    );
+    ctx.add_scalar_function_factory(
+        "parse_url".to_string(),
+        Box::new(BuiltinScalarFunction::new("parse_url", build_parse_url)),
+    );
+    ctx.add_scalar_function_factory(
+        "url_decode".to_string(),
+        Box::new(BuiltinScalarFunction::new("url_decode", build_url_decode)),
+    );
 }
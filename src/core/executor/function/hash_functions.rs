@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/executor/function/hash_functions.rs
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Hash applied by [`HashFunctionExecutor`]. All hashes run over the UTF-8
+/// bytes of the input so results are identical on every platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// Lowercase hex MD5 digest
+    Md5,
+    /// Lowercase hex SHA-1 digest
+    Sha1,
+    /// Lowercase hex SHA-256 digest
+    Sha256,
+    /// CRC-32 (IEEE) checksum as a LONG
+    Crc32,
+    /// XXH64 with seed 0, bit-cast to a signed LONG
+    XxHash64,
+}
+
+impl HashAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Crc32 => "crc32",
+            HashAlgorithm::XxHash64 => "xxhash64",
+        }
+    }
+
+    fn return_type(&self) -> ApiAttributeType {
+        match self {
+            HashAlgorithm::Crc32 | HashAlgorithm::XxHash64 => ApiAttributeType::LONG,
+            _ => ApiAttributeType::STRING,
+        }
+    }
+
+    fn hash(&self, bytes: &[u8]) -> AttributeValue {
+        match self {
+            HashAlgorithm::Md5 => AttributeValue::String(hex::encode(Md5::digest(bytes))),
+            HashAlgorithm::Sha1 => AttributeValue::String(hex::encode(Sha1::digest(bytes))),
+            HashAlgorithm::Sha256 => AttributeValue::String(hex::encode(Sha256::digest(bytes))),
+            HashAlgorithm::Crc32 => AttributeValue::Long(crc32fast::hash(bytes) as i64),
+            HashAlgorithm::XxHash64 => {
+                AttributeValue::Long(xxhash_rust::xxh64::xxh64(bytes, 0) as i64)
+            }
+        }
+    }
+}
+
+/// md5/sha1/sha256/crc32/xxhash64(str)
+#[derive(Debug)]
+pub struct HashFunctionExecutor {
+    algorithm: HashAlgorithm,
+    expr: Box<dyn ExpressionExecutor>,
+}
+
+impl HashFunctionExecutor {
+    pub fn new(
+        algorithm: HashAlgorithm,
+        expr: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if expr.get_return_type() != ApiAttributeType::STRING {
+            return Err(format!("{}() requires a STRING argument", algorithm.name()));
+        }
+        Ok(Self { algorithm, expr })
+    }
+}
+
+impl ExpressionExecutor for HashFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        match self.expr.execute(event)? {
+            AttributeValue::String(s) => Some(self.algorithm.hash(s.as_bytes())),
+            AttributeValue::Null => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.algorithm.return_type()
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(HashFunctionExecutor {
+            algorithm: self.algorithm,
+            expr: self.expr.clone_executor(ctx),
+        })
+    }
+}
+
+/// hmac_sha256(key, value) - lowercase hex HMAC-SHA256 of `value` keyed by `key`
+#[derive(Debug)]
+pub struct HmacSha256FunctionExecutor {
+    key_executor: Box<dyn ExpressionExecutor>,
+    value_executor: Box<dyn ExpressionExecutor>,
+}
+
+impl HmacSha256FunctionExecutor {
+    pub fn new(
+        key_executor: Box<dyn ExpressionExecutor>,
+        value_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if key_executor.get_return_type() != ApiAttributeType::STRING
+            || value_executor.get_return_type() != ApiAttributeType::STRING
+        {
+            return Err("hmac_sha256() requires STRING key and value arguments".to_string());
+        }
+        Ok(Self {
+            key_executor,
+            value_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for HmacSha256FunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let key_val = self.key_executor.execute(event)?;
+        let value_val = self.value_executor.execute(event)?;
+        match (key_val, value_val) {
+            (AttributeValue::String(key), AttributeValue::String(value)) => {
+                // HMAC accepts keys of any length
+                let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).ok()?;
+                mac.update(value.as_bytes());
+                Some(AttributeValue::String(hex::encode(
+                    mac.finalize().into_bytes(),
+                )))
+            }
+            (AttributeValue::Null, _) | (_, AttributeValue::Null) => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(HmacSha256FunctionExecutor {
+            key_executor: self.key_executor.clone_executor(ctx),
+            value_executor: self.value_executor.clone_executor(ctx),
+        })
+    }
+}
+
+/// Text encoding of raw bytes used by the encode/decode functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// Standard alphabet with padding
+    Base64,
+    /// Lowercase hex; decoding accepts either case
+    Hex,
+}
+
+impl BinaryEncoding {
+    fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            BinaryEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+            BinaryEncoding::Hex => hex::encode(bytes),
+        }
+    }
+
+    fn decode(&self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            BinaryEncoding::Base64 => base64::engine::general_purpose::STANDARD.decode(text).ok(),
+            BinaryEncoding::Hex => hex::decode(text).ok(),
+        }
+    }
+
+    fn encode_name(&self) -> &'static str {
+        match self {
+            BinaryEncoding::Base64 => "base64_encode",
+            BinaryEncoding::Hex => "hex",
+        }
+    }
+
+    fn decode_name(&self) -> &'static str {
+        match self {
+            BinaryEncoding::Base64 => "base64_decode",
+            BinaryEncoding::Hex => "unhex",
+        }
+    }
+}
+
+/// base64_encode(str) / hex(str) - encode the UTF-8 bytes of `str`
+#[derive(Debug)]
+pub struct EncodeFunctionExecutor {
+    encoding: BinaryEncoding,
+    expr: Box<dyn ExpressionExecutor>,
+}
+
+impl EncodeFunctionExecutor {
+    pub fn new(
+        encoding: BinaryEncoding,
+        expr: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if expr.get_return_type() != ApiAttributeType::STRING {
+            return Err(format!(
+                "{}() requires a STRING argument",
+                encoding.encode_name()
+            ));
+        }
+        Ok(Self { encoding, expr })
+    }
+}
+
+impl ExpressionExecutor for EncodeFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        match self.expr.execute(event)? {
+            AttributeValue::String(s) => {
+                Some(AttributeValue::String(self.encoding.encode(s.as_bytes())))
+            }
+            AttributeValue::Null => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(EncodeFunctionExecutor {
+            encoding: self.encoding,
+            expr: self.expr.clone_executor(ctx),
+        })
+    }
+}
+
+/// base64_decode(str) / unhex(str) - decode `str`; NULL if it is malformed or
+/// the decoded bytes are not valid UTF-8
+#[derive(Debug)]
+pub struct DecodeFunctionExecutor {
+    encoding: BinaryEncoding,
+    expr: Box<dyn ExpressionExecutor>,
+}
+
+impl DecodeFunctionExecutor {
+    pub fn new(
+        encoding: BinaryEncoding,
+        expr: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if expr.get_return_type() != ApiAttributeType::STRING {
+            return Err(format!(
+                "{}() requires a STRING argument",
+                encoding.decode_name()
+            ));
+        }
+        Ok(Self { encoding, expr })
+    }
+}
+
+impl ExpressionExecutor for DecodeFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        match self.expr.execute(event)? {
+            AttributeValue::String(s) => Some(
+                self.encoding
+                    .decode(&s)
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .map_or(AttributeValue::Null, AttributeValue::String),
+            ),
+            AttributeValue::Null => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(DecodeFunctionExecutor {
+            encoding: self.encoding,
+            expr: self.expr.clone_executor(ctx),
+        })
+    }
+}
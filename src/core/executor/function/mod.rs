@@ -9,9 +9,11 @@ pub mod convert_function_executor;
 pub mod date_functions;
 pub mod default_function_executor;
 pub mod event_timestamp_function_executor;
+pub mod hash_functions;
 pub mod instance_of_checkers;
 pub mod math_functions;
 pub mod nullif_function_executor;
+pub mod regex_functions;
 pub mod scalar_function_executor;
 pub mod script_function_executor;
 pub mod string_functions;
@@ -29,6 +31,10 @@ pub use self::date_functions::{
 };
 pub use self::default_function_executor::DefaultFunctionExecutor;
 pub use self::event_timestamp_function_executor::EventTimestampFunctionExecutor;
+pub use self::hash_functions::{
+    BinaryEncoding, DecodeFunctionExecutor, EncodeFunctionExecutor, HashAlgorithm,
+    HashFunctionExecutor, HmacSha256FunctionExecutor,
+};
 pub use self::instance_of_checkers::*;
 pub use self::math_functions::{
     AbsFunctionExecutor, AcosFunctionExecutor, AsinFunctionExecutor, AtanFunctionExecutor,
@@ -38,13 +44,18 @@ pub use self::math_functions::{
     SinFunctionExecutor, SqrtFunctionExecutor, TanFunctionExecutor, TruncFunctionExecutor,
 };
 pub use self::nullif_function_executor::NullIfFunctionExecutor;
+pub use self::regex_functions::{
+    RegexpExtractFunctionExecutor, RegexpMatchesFunctionExecutor, RegexpReplaceFunctionExecutor,
+};
 pub use self::scalar_function_executor::ScalarFunctionExecutor;
 pub use self::script_function_executor::ScriptFunctionExecutor;
 pub use self::string_functions::{
     AsciiFunctionExecutor, ChrFunctionExecutor, ConcatFunctionExecutor, LeftFunctionExecutor,
     LengthFunctionExecutor, LikeFunctionExecutor, LowerFunctionExecutor, LpadFunctionExecutor,
-    LtrimFunctionExecutor, PositionFunctionExecutor, RepeatFunctionExecutor,
-    ReplaceFunctionExecutor, ReverseFunctionExecutor, RightFunctionExecutor, RpadFunctionExecutor,
-    RtrimFunctionExecutor, SubstringFunctionExecutor, TrimFunctionExecutor, UpperFunctionExecutor,
+    LtrimFunctionExecutor, ParseUrlFunctionExecutor, PositionFunctionExecutor,
+    RepeatFunctionExecutor, ReplaceFunctionExecutor, ReverseFunctionExecutor,
+    RightFunctionExecutor, RpadFunctionExecutor, RtrimFunctionExecutor, SplitFunctionExecutor,
+    SplitPartFunctionExecutor, SubstringFunctionExecutor, TrimFunctionExecutor,
+    UpperFunctionExecutor, UrlDecodeFunctionExecutor,
 };
-pub use self::uuid_function_executor::UuidFunctionExecutor;
+pub use self::uuid_function_executor::{UuidFunctionExecutor, UuidV5FunctionExecutor};
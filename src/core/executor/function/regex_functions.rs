@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/executor/function/regex_functions.rs
+
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use regex::Regex;
+use std::sync::{Arc, Mutex};
+
+/// A regex argument. Patterns that evaluate without an event are compiled
+/// once when the function is built, so an invalid literal pattern fails the
+/// query at parse time; other patterns are compiled per event, reusing the
+/// previous regex while the pattern does not change.
+#[derive(Debug)]
+pub enum RegexPattern {
+    Constant(Regex),
+    Dynamic {
+        executor: Box<dyn ExpressionExecutor>,
+        last: Mutex<Option<Regex>>,
+    },
+}
+
+impl RegexPattern {
+    pub fn new(executor: Box<dyn ExpressionExecutor>, function: &str) -> Result<Self, String> {
+        if executor.get_return_type() != ApiAttributeType::STRING {
+            return Err(format!("{function}() pattern must be a STRING"));
+        }
+        match executor.execute(None) {
+            Some(AttributeValue::String(pattern)) => Regex::new(&pattern)
+                .map(RegexPattern::Constant)
+                .map_err(|e| format!("{function}() invalid pattern '{pattern}': {e}")),
+            _ => Ok(RegexPattern::Dynamic {
+                executor,
+                last: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Number of capture groups, when known before any event arrives
+    fn static_captures_len(&self) -> Option<usize> {
+        match self {
+            RegexPattern::Constant(re) => Some(re.captures_len()),
+            RegexPattern::Dynamic { .. } => None,
+        }
+    }
+
+    /// Run `f` with the regex for `event`; `None` if the pattern is NULL or
+    /// does not compile
+    fn with_regex<R>(
+        &self,
+        event: Option<&dyn ComplexEvent>,
+        f: impl FnOnce(&Regex) -> R,
+    ) -> Option<R> {
+        match self {
+            RegexPattern::Constant(re) => Some(f(re)),
+            RegexPattern::Dynamic { executor, last } => {
+                let pattern = match executor.execute(event)? {
+                    AttributeValue::String(p) => p,
+                    _ => return None,
+                };
+                let mut last = last.lock().unwrap();
+                if last.as_ref().map(|re| re.as_str()) != Some(pattern.as_str()) {
+                    *last = Some(Regex::new(&pattern).ok()?);
+                }
+                last.as_ref().map(f)
+            }
+        }
+    }
+
+    fn clone_pattern(&self, ctx: &Arc<EventFluxAppContext>) -> Self {
+        match self {
+            RegexPattern::Constant(re) => RegexPattern::Constant(re.clone()),
+            RegexPattern::Dynamic { executor, .. } => RegexPattern::Dynamic {
+                executor: executor.clone_executor(ctx),
+                last: Mutex::new(None),
+            },
+        }
+    }
+}
+
+/// Evaluate `executor` as a string; `Err(())` marks a NULL result
+fn string_arg(
+    executor: &dyn ExpressionExecutor,
+    event: Option<&dyn ComplexEvent>,
+) -> Option<Result<String, ()>> {
+    match executor.execute(event)? {
+        AttributeValue::String(s) => Some(Ok(s)),
+        AttributeValue::Null => Some(Err(())),
+        _ => None,
+    }
+}
+
+/// regexp_matches(str, pattern) - whether `pattern` matches anywhere in `str`
+#[derive(Debug)]
+pub struct RegexpMatchesFunctionExecutor {
+    value_executor: Box<dyn ExpressionExecutor>,
+    pattern: RegexPattern,
+}
+
+impl RegexpMatchesFunctionExecutor {
+    pub fn new(
+        value_executor: Box<dyn ExpressionExecutor>,
+        pattern_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            value_executor,
+            pattern: RegexPattern::new(pattern_executor, "regexp_matches")?,
+        })
+    }
+}
+
+impl ExpressionExecutor for RegexpMatchesFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let s = match string_arg(self.value_executor.as_ref(), event)? {
+            Ok(s) => s,
+            Err(()) => return Some(AttributeValue::Null),
+        };
+        Some(
+            self.pattern
+                .with_regex(event, |re| AttributeValue::Bool(re.is_match(&s)))
+                .unwrap_or(AttributeValue::Null),
+        )
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::BOOL
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(RegexpMatchesFunctionExecutor {
+            value_executor: self.value_executor.clone_executor(ctx),
+            pattern: self.pattern.clone_pattern(ctx),
+        })
+    }
+}
+
+/// regexp_extract(str, pattern[, group]) - capture group `group` (default 1)
+/// of the first match, NULL if there is no match
+#[derive(Debug)]
+pub struct RegexpExtractFunctionExecutor {
+    value_executor: Box<dyn ExpressionExecutor>,
+    pattern: RegexPattern,
+    group: usize,
+}
+
+impl RegexpExtractFunctionExecutor {
+    pub fn new(
+        value_executor: Box<dyn ExpressionExecutor>,
+        pattern_executor: Box<dyn ExpressionExecutor>,
+        group_executor: Option<Box<dyn ExpressionExecutor>>,
+    ) -> Result<Self, String> {
+        let group = match group_executor.map(|g| g.execute(None)) {
+            None => 1,
+            Some(Some(AttributeValue::Int(g))) if g >= 0 => g as usize,
+            Some(Some(AttributeValue::Long(g))) if g >= 0 => g as usize,
+            _ => {
+                return Err(
+                    "regexp_extract() group must be a non-negative integer constant".to_string(),
+                )
+            }
+        };
+        let pattern = RegexPattern::new(pattern_executor, "regexp_extract")?;
+        if let Some(captures) = pattern.static_captures_len() {
+            if group >= captures {
+                return Err(format!(
+                    "regexp_extract() group {group} out of range; the pattern has {} group(s)",
+                    captures - 1
+                ));
+            }
+        }
+        Ok(Self {
+            value_executor,
+            pattern,
+            group,
+        })
+    }
+}
+
+impl ExpressionExecutor for RegexpExtractFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let s = match string_arg(self.value_executor.as_ref(), event)? {
+            Ok(s) => s,
+            Err(()) => return Some(AttributeValue::Null),
+        };
+        let extracted = self.pattern.with_regex(event, |re| {
+            re.captures(&s)
+                .and_then(|caps| caps.get(self.group))
+                .map(|m| m.as_str().to_string())
+        });
+        Some(
+            extracted
+                .flatten()
+                .map_or(AttributeValue::Null, AttributeValue::String),
+        )
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(RegexpExtractFunctionExecutor {
+            value_executor: self.value_executor.clone_executor(ctx),
+            pattern: self.pattern.clone_pattern(ctx),
+            group: self.group,
+        })
+    }
+}
+
+/// regexp_replace(str, pattern, replacement) - replace every match;
+/// `replacement` may refer to groups as `$1` or `${name}`
+#[derive(Debug)]
+pub struct RegexpReplaceFunctionExecutor {
+    value_executor: Box<dyn ExpressionExecutor>,
+    pattern: RegexPattern,
+    replacement_executor: Box<dyn ExpressionExecutor>,
+}
+
+impl RegexpReplaceFunctionExecutor {
+    pub fn new(
+        value_executor: Box<dyn ExpressionExecutor>,
+        pattern_executor: Box<dyn ExpressionExecutor>,
+        replacement_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            value_executor,
+            pattern: RegexPattern::new(pattern_executor, "regexp_replace")?,
+            replacement_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for RegexpReplaceFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let s = match string_arg(self.value_executor.as_ref(), event)? {
+            Ok(s) => s,
+            Err(()) => return Some(AttributeValue::Null),
+        };
+        let replacement = match string_arg(self.replacement_executor.as_ref(), event)? {
+            Ok(r) => r,
+            Err(()) => return Some(AttributeValue::Null),
+        };
+        Some(
+            self.pattern
+                .with_regex(event, |re| {
+                    AttributeValue::String(re.replace_all(&s, replacement.as_str()).into_owned())
+                })
+                .unwrap_or(AttributeValue::Null),
+        )
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(RegexpReplaceFunctionExecutor {
+            value_executor: self.value_executor.clone_executor(ctx),
+            pattern: self.pattern.clone_pattern(ctx),
+            replacement_executor: self.replacement_executor.clone_executor(ctx),
+        })
+    }
+}
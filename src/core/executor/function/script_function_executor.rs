@@ -5,46 +5,90 @@ use crate::core::event::complex_event::ComplexEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
 use crate::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
+use crate::core::function::Script;
 use crate::query_api::definition::attribute::Type as ApiAttributeType;
 use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+/// Calls a script function declared with `CREATE FUNCTION`
+///
+/// A failing call (limit exceeded, runtime error, wrong result type) is logged
+/// and yields null so one bad event does not stop the query.
+#[derive(Debug)]
 pub struct ScriptFunctionExecutor {
     name: String,
     return_type: ApiAttributeType,
+    script: Box<dyn Script>,
+    args: Vec<Box<dyn ExpressionExecutor>>,
+    /// Context the arguments were bound to by `init`, used to clone them
+    app_ctx: Option<Arc<EventFluxAppContext>>,
+}
+
+impl Clone for ScriptFunctionExecutor {
+    fn clone(&self) -> Self {
+        let mut cloned = Self::new(self.name.clone(), self.return_type, self.script.clone());
+        if let Some(ctx) = &self.app_ctx {
+            cloned.args = self.args.iter().map(|e| e.clone_executor(ctx)).collect();
+            cloned.app_ctx = Some(Arc::clone(ctx));
+        }
+        cloned
+    }
 }
 
 impl ScriptFunctionExecutor {
-    pub fn new(name: String, return_type: ApiAttributeType) -> Self {
-        Self { name, return_type }
+    pub fn new(name: String, return_type: ApiAttributeType, script: Box<dyn Script>) -> Self {
+        Self {
+            name,
+            return_type,
+            script,
+            args: Vec::new(),
+            app_ctx: None,
+        }
     }
 }
 
 impl ExpressionExecutor for ScriptFunctionExecutor {
-    fn execute(&self, _event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
-        eprintln!("Script function '{}' not implemented", self.name);
-        Some(AttributeValue::Null)
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let args: Vec<AttributeValue> = self
+            .args
+            .iter()
+            .map(|e| e.execute(event).unwrap_or(AttributeValue::Null))
+            .collect();
+        match self.script.eval(&args) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::warn!("{e}");
+                Some(AttributeValue::Null)
+            }
+        }
     }
 
     fn get_return_type(&self) -> ApiAttributeType {
         self.return_type
     }
 
-    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
-        Box::new(self.clone())
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        let mut cloned = Self::new(self.name.clone(), self.return_type, self.script.clone());
+        cloned.args = self.args.iter().map(|e| e.clone_executor(ctx)).collect();
+        cloned.app_ctx = Some(Arc::clone(ctx));
+        Box::new(cloned)
     }
 }
 
 impl ScalarFunctionExecutor for ScriptFunctionExecutor {
     fn init(
         &mut self,
-        _args: &Vec<Box<dyn ExpressionExecutor>>,
-        _ctx: &Arc<EventFluxAppContext>,
+        args: &Vec<Box<dyn ExpressionExecutor>>,
+        ctx: &Arc<EventFluxAppContext>,
     ) -> Result<(), String> {
+        self.script.init(ctx)?;
+        self.args = args.iter().map(|e| e.clone_executor(ctx)).collect();
+        self.app_ctx = Some(Arc::clone(ctx));
         Ok(())
     }
 
-    fn destroy(&mut self) {}
+    fn destroy(&mut self) {
+        self.args.clear();
+    }
 
     fn get_name(&self) -> String {
         self.name.clone()
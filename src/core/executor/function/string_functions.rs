@@ -958,3 +958,334 @@ impl ExpressionExecutor for RpadFunctionExecutor {
         })
     }
 }
+
+/// split_part(str, delimiter, n) - the `n`th field (1-based) of `str` split on
+/// `delimiter`; negative `n` counts from the end, out of range yields ''
+#[derive(Debug)]
+pub struct SplitPartFunctionExecutor {
+    str_executor: Box<dyn ExpressionExecutor>,
+    delim_executor: Box<dyn ExpressionExecutor>,
+    index_executor: Box<dyn ExpressionExecutor>,
+}
+
+impl SplitPartFunctionExecutor {
+    pub fn new(
+        str_executor: Box<dyn ExpressionExecutor>,
+        delim_executor: Box<dyn ExpressionExecutor>,
+        index_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if str_executor.get_return_type() != ApiAttributeType::STRING
+            || delim_executor.get_return_type() != ApiAttributeType::STRING
+        {
+            return Err("split_part() requires STRING value and delimiter arguments".to_string());
+        }
+        if !matches!(
+            index_executor.get_return_type(),
+            ApiAttributeType::INT | ApiAttributeType::LONG
+        ) {
+            return Err("split_part() field index must be INT or LONG".to_string());
+        }
+        if let Some(AttributeValue::Int(0) | AttributeValue::Long(0)) = index_executor.execute(None)
+        {
+            return Err("split_part() field index must not be zero".to_string());
+        }
+        Ok(Self {
+            str_executor,
+            delim_executor,
+            index_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for SplitPartFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let str_val = self.str_executor.execute(event)?;
+        let delim_val = self.delim_executor.execute(event)?;
+        let index_val = self.index_executor.execute(event)?;
+
+        let (s, delim) = match (str_val, delim_val) {
+            (AttributeValue::String(s), AttributeValue::String(d)) => (s, d),
+            (AttributeValue::Null, _) | (_, AttributeValue::Null) => {
+                return Some(AttributeValue::Null)
+            }
+            _ => return None,
+        };
+        let index = match index_val {
+            AttributeValue::Int(i) => i as i64,
+            AttributeValue::Long(l) => l,
+            AttributeValue::Null => return Some(AttributeValue::Null),
+            _ => return None,
+        };
+
+        let fields: Vec<&str> = if delim.is_empty() {
+            vec![s.as_str()]
+        } else {
+            s.split(delim.as_str()).collect()
+        };
+        let pos = if index > 0 {
+            index - 1
+        } else {
+            fields.len() as i64 + index
+        };
+        let field = if index != 0 && pos >= 0 {
+            fields.get(pos as usize).copied().unwrap_or("")
+        } else {
+            ""
+        };
+        Some(AttributeValue::String(field.to_string()))
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(SplitPartFunctionExecutor {
+            str_executor: self.str_executor.clone_executor(ctx),
+            delim_executor: self.delim_executor.clone_executor(ctx),
+            index_executor: self.index_executor.clone_executor(ctx),
+        })
+    }
+}
+
+/// split(str, delimiter) - the fields of `str` as an array of strings
+#[derive(Debug)]
+pub struct SplitFunctionExecutor {
+    str_executor: Box<dyn ExpressionExecutor>,
+    delim_executor: Box<dyn ExpressionExecutor>,
+}
+
+impl SplitFunctionExecutor {
+    pub fn new(
+        str_executor: Box<dyn ExpressionExecutor>,
+        delim_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if str_executor.get_return_type() != ApiAttributeType::STRING
+            || delim_executor.get_return_type() != ApiAttributeType::STRING
+        {
+            return Err("split() requires STRING value and delimiter arguments".to_string());
+        }
+        Ok(Self {
+            str_executor,
+            delim_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for SplitFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let str_val = self.str_executor.execute(event)?;
+        let delim_val = self.delim_executor.execute(event)?;
+        let (s, delim) = match (str_val, delim_val) {
+            (AttributeValue::String(s), AttributeValue::String(d)) => (s, d),
+            (AttributeValue::Null, _) | (_, AttributeValue::Null) => {
+                return Some(AttributeValue::Null)
+            }
+            _ => return None,
+        };
+        let fields: Vec<AttributeValue> = if delim.is_empty() {
+            vec![AttributeValue::String(s)]
+        } else {
+            s.split(delim.as_str())
+                .map(|f| AttributeValue::String(f.to_string()))
+                .collect()
+        };
+        Some(AttributeValue::Object(Some(Box::new(fields))))
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::OBJECT
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(SplitFunctionExecutor {
+            str_executor: self.str_executor.clone_executor(ctx),
+            delim_executor: self.delim_executor.clone_executor(ctx),
+        })
+    }
+}
+
+const URL_PARTS: [&str; 8] = [
+    "HOST",
+    "PATH",
+    "QUERY",
+    "REF",
+    "PROTOCOL",
+    "AUTHORITY",
+    "FILE",
+    "USERINFO",
+];
+
+/// Extract `part` of `url` (Hive `parse_url` semantics); `None` if the URL has
+/// no scheme or the part is absent
+fn url_part(url: &str, part: &str, key: Option<&str>) -> Option<String> {
+    let (protocol, rest) = url.split_once("://")?;
+    let (rest, reference) = match rest.split_once('#') {
+        Some((r, f)) => (r, Some(f)),
+        None => (rest, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((r, q)) => (r, Some(q)),
+        None => (rest, None),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((u, h)) => (Some(u), h),
+        None => (None, authority),
+    };
+    let host = if host_port.starts_with('[') {
+        host_port.split_inclusive(']').next().unwrap_or(host_port)
+    } else {
+        host_port.split(':').next().unwrap_or(host_port)
+    };
+
+    match part {
+        "PROTOCOL" => Some(protocol.to_string()),
+        "AUTHORITY" => Some(authority.to_string()),
+        "USERINFO" => userinfo.map(str::to_string),
+        "HOST" => Some(host.to_string()),
+        "PATH" => Some(path.to_string()),
+        "FILE" => Some(match query {
+            Some(q) => format!("{path}?{q}"),
+            None => path.to_string(),
+        }),
+        "REF" => reference.map(str::to_string),
+        "QUERY" => match key {
+            None => query.map(str::to_string),
+            Some(key) => query?
+                .split('&')
+                .find_map(|pair| match pair.split_once('=') {
+                    Some((k, v)) if k == key => Some(v.to_string()),
+                    None if pair == key => Some(String::new()),
+                    _ => None,
+                }),
+        },
+        _ => None,
+    }
+}
+
+/// parse_url(url, part[, key]) - one component of `url`; `part` is one of
+/// HOST, PATH, QUERY, REF, PROTOCOL, AUTHORITY, FILE or USERINFO, and `key`
+/// selects a single query parameter when `part` is QUERY
+#[derive(Debug)]
+pub struct ParseUrlFunctionExecutor {
+    url_executor: Box<dyn ExpressionExecutor>,
+    part_executor: Box<dyn ExpressionExecutor>,
+    key_executor: Option<Box<dyn ExpressionExecutor>>,
+}
+
+impl ParseUrlFunctionExecutor {
+    pub fn new(
+        url_executor: Box<dyn ExpressionExecutor>,
+        part_executor: Box<dyn ExpressionExecutor>,
+        key_executor: Option<Box<dyn ExpressionExecutor>>,
+    ) -> Result<Self, String> {
+        if url_executor.get_return_type() != ApiAttributeType::STRING
+            || part_executor.get_return_type() != ApiAttributeType::STRING
+        {
+            return Err("parse_url() requires STRING url and part arguments".to_string());
+        }
+        if let Some(AttributeValue::String(part)) = part_executor.execute(None) {
+            if !URL_PARTS.contains(&part.as_str()) {
+                return Err(format!(
+                    "parse_url() unknown part '{part}', expected one of {}",
+                    URL_PARTS.join(", ")
+                ));
+            }
+            if key_executor.is_some() && part != "QUERY" {
+                return Err("parse_url() key is only valid for the QUERY part".to_string());
+            }
+        }
+        if let Some(key) = &key_executor {
+            if key.get_return_type() != ApiAttributeType::STRING {
+                return Err("parse_url() key must be a STRING".to_string());
+            }
+        }
+        Ok(Self {
+            url_executor,
+            part_executor,
+            key_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for ParseUrlFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let url_val = self.url_executor.execute(event)?;
+        let part_val = self.part_executor.execute(event)?;
+        let (url, part) = match (url_val, part_val) {
+            (AttributeValue::String(u), AttributeValue::String(p)) => (u, p),
+            (AttributeValue::Null, _) | (_, AttributeValue::Null) => {
+                return Some(AttributeValue::Null)
+            }
+            _ => return None,
+        };
+        let key = match &self.key_executor {
+            Some(k) => match k.execute(event)? {
+                AttributeValue::String(k) => Some(k),
+                AttributeValue::Null => return Some(AttributeValue::Null),
+                _ => return None,
+            },
+            None => None,
+        };
+        Some(
+            url_part(&url, &part, key.as_deref())
+                .map_or(AttributeValue::Null, AttributeValue::String),
+        )
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(ParseUrlFunctionExecutor {
+            url_executor: self.url_executor.clone_executor(ctx),
+            part_executor: self.part_executor.clone_executor(ctx),
+            key_executor: self.key_executor.as_ref().map(|k| k.clone_executor(ctx)),
+        })
+    }
+}
+
+/// url_decode(str) - decode percent-escapes and '+' as space; NULL if the
+/// result is not valid UTF-8
+#[derive(Debug)]
+pub struct UrlDecodeFunctionExecutor {
+    expr: Box<dyn ExpressionExecutor>,
+}
+
+impl UrlDecodeFunctionExecutor {
+    pub fn new(expr: Box<dyn ExpressionExecutor>) -> Result<Self, String> {
+        if expr.get_return_type() != ApiAttributeType::STRING {
+            return Err("url_decode() requires a STRING argument".to_string());
+        }
+        Ok(Self { expr })
+    }
+}
+
+impl ExpressionExecutor for UrlDecodeFunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        match self.expr.execute(event)? {
+            AttributeValue::String(s) => Some(
+                urlencoding::decode(&s.replace('+', " ")).map_or(AttributeValue::Null, |d| {
+                    AttributeValue::String(d.into_owned())
+                }),
+            ),
+            AttributeValue::Null => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(UrlDecodeFunctionExecutor {
+            expr: self.expr.clone_executor(ctx),
+        })
+    }
+}
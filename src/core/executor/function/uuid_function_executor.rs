@@ -8,7 +8,7 @@ use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
 use crate::query_api::definition::attribute::Type as ApiAttributeType; // Import Type enum
 use std::sync::Arc; // For EventFluxAppContext in clone_executor
-use uuid::Uuid; // Requires `uuid` crate with "v4" feature // For clone_executor
+use uuid::Uuid; // Requires `uuid` crate with "v4" and "v5" features
 
 // Java UUIDFunctionExecutor extends FunctionExecutor but is stateless and takes no arguments.
 #[derive(Debug, Default, Clone)] // Can be Clone and Default as it has no fields
@@ -42,3 +42,80 @@ impl ExpressionExecutor for UuidFunctionExecutor {
         Box::new(self.clone())
     }
 }
+
+/// Namespace argument of uuid_v5: a UUID literal or one of the RFC 4122
+/// well-known names `dns`, `url`, `oid` and `x500`
+fn parse_namespace(ns: &str) -> Option<Uuid> {
+    match ns.to_ascii_lowercase().as_str() {
+        "dns" => Some(Uuid::NAMESPACE_DNS),
+        "url" => Some(Uuid::NAMESPACE_URL),
+        "oid" => Some(Uuid::NAMESPACE_OID),
+        "x500" => Some(Uuid::NAMESPACE_X500),
+        other => Uuid::parse_str(other).ok(),
+    }
+}
+
+/// uuid_v5(namespace, name) - deterministic name-based (SHA-1) UUID
+#[derive(Debug)]
+pub struct UuidV5FunctionExecutor {
+    namespace_executor: Box<dyn ExpressionExecutor>,
+    name_executor: Box<dyn ExpressionExecutor>,
+}
+
+impl UuidV5FunctionExecutor {
+    pub fn new(
+        namespace_executor: Box<dyn ExpressionExecutor>,
+        name_executor: Box<dyn ExpressionExecutor>,
+    ) -> Result<Self, String> {
+        if namespace_executor.get_return_type() != ApiAttributeType::STRING
+            || name_executor.get_return_type() != ApiAttributeType::STRING
+        {
+            return Err("uuid_v5() requires STRING namespace and name arguments".to_string());
+        }
+        if let Some(AttributeValue::String(ns)) = namespace_executor.execute(None) {
+            if parse_namespace(&ns).is_none() {
+                return Err(format!(
+                    "uuid_v5() namespace '{ns}' is not a UUID or one of dns, url, oid, x500"
+                ));
+            }
+        }
+        Ok(Self {
+            namespace_executor,
+            name_executor,
+        })
+    }
+}
+
+impl ExpressionExecutor for UuidV5FunctionExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let ns_val = self.namespace_executor.execute(event)?;
+        let name_val = self.name_executor.execute(event)?;
+        match (ns_val, name_val) {
+            (AttributeValue::String(ns), AttributeValue::String(name)) => {
+                Some(parse_namespace(&ns).map_or(AttributeValue::Null, |ns| {
+                    AttributeValue::String(
+                        Uuid::new_v5(&ns, name.as_bytes()).hyphenated().to_string(),
+                    )
+                }))
+            }
+            (AttributeValue::Null, _) | (_, AttributeValue::Null) => Some(AttributeValue::Null),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        ApiAttributeType::STRING
+    }
+
+    fn clone_executor(
+        &self,
+        eventflux_app_context: &Arc<EventFluxAppContext>,
+    ) -> Box<dyn ExpressionExecutor> {
+        Box::new(UuidV5FunctionExecutor {
+            namespace_executor: self
+                .namespace_executor
+                .clone_executor(eventflux_app_context),
+            name_executor: self.name_executor.clone_executor(eventflux_app_context),
+        })
+    }
+}
@@ -15,8 +15,9 @@ pub mod variable_expression_executor; // For incremental aggregation executors
 
 pub use self::cast_executor::CastExecutor;
 pub use self::collection_aggregation_executor::{
-    CollectionAvgExecutor, CollectionCountExecutor, CollectionMinMaxExecutor,
-    CollectionStdDevExecutor, CollectionSumExecutor, MinMaxType,
+    CollectionAggregationExecutor, CollectionAvgExecutor, CollectionCountExecutor,
+    CollectionMinMaxExecutor, CollectionStdDevExecutor, CollectionSumExecutor, MinMaxType,
+    PatternCollections,
 };
 pub use self::condition::*;
 pub use self::constant_expression_executor::ConstantExpressionExecutor;
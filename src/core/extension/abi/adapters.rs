@@ -0,0 +1,1004 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Adapters exposing C ABI descriptors through the regular extension traits
+
+use std::collections::{HashMap, VecDeque};
+use std::ffi::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{
+    EfAggregator, EfBytes, EfConfigEntry, EfFunction, EfOut, EfSink, EfSinkMapper, EfSource,
+    EfSourceMapper, EfStr, EfValue, EfWindow, EF_ERROR, EF_OK,
+};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::event::Event;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
+use crate::core::extension::{
+    AttributeAggregatorFactory, SinkFactory, SinkMapperFactory, SourceFactory, SourceMapperFactory,
+    WindowProcessorFactory,
+};
+use crate::core::query::processor::stream::window::WindowProcessor;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::query::selector::attribute::aggregator::{
+    AttributeAggregatorExecutor, AttributeAggregatorExpressionExecutor,
+};
+use crate::core::stream::input::source::{Source, SourceCallback};
+use crate::core::stream::mapper::{SinkMapper, SourceMapper};
+use crate::core::stream::output::sink::Sink;
+use crate::core::util::parser::expression_parser::ExpressionParserContext;
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::execution::query::input::handler::WindowHandler;
+use crate::query_api::expression::{constant::ConstantValueWithFloat, Expression};
+
+// ============================================================================
+// Call plumbing
+// ============================================================================
+
+/// Results reported by the extension through [`EfOut`] during one call
+#[derive(Default)]
+struct Collected {
+    values: Vec<AttributeValue>,
+    rows: Vec<Vec<AttributeValue>>,
+    payloads: Vec<Vec<u8>>,
+    error: Option<String>,
+}
+
+impl Collected {
+    unsafe fn from_ctx<'a>(ctx: *mut c_void) -> &'a mut Collected {
+        &mut *(ctx as *mut Collected)
+    }
+
+    fn fail(&mut self, error: String) {
+        self.error.get_or_insert(error);
+    }
+}
+
+unsafe extern "C" fn collect_value(ctx: *mut c_void, value: *const EfValue) {
+    let collected = Collected::from_ctx(ctx);
+    match (*value).to_attribute_value() {
+        Ok(v) => collected.values.push(v),
+        Err(e) => collected.fail(e),
+    }
+}
+
+unsafe extern "C" fn collect_row(ctx: *mut c_void, values: *const EfValue, len: usize) {
+    let collected = Collected::from_ctx(ctx);
+    let values = if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(values, len)
+    };
+    match values.iter().map(|v| v.to_attribute_value()).collect() {
+        Ok(row) => collected.rows.push(row),
+        Err(e) => collected.fail(e),
+    }
+}
+
+unsafe extern "C" fn collect_bytes(ctx: *mut c_void, bytes: EfBytes) {
+    Collected::from_ctx(ctx)
+        .payloads
+        .push(bytes.as_slice().to_vec());
+}
+
+unsafe extern "C" fn collect_error(ctx: *mut c_void, message: EfStr) {
+    let message = message.as_str().map(str::to_string).unwrap_or_else(|e| e);
+    Collected::from_ctx(ctx).fail(message);
+}
+
+/// Run one extension call, collecting whatever it reports
+fn invoke(what: &str, call: impl FnOnce(*const EfOut) -> i32) -> Result<Collected, String> {
+    let mut collected = Collected::default();
+    let out = EfOut {
+        ctx: &mut collected as *mut Collected as *mut c_void,
+        value: collect_value,
+        row: collect_row,
+        bytes: collect_bytes,
+        error: collect_error,
+    };
+    let status = call(&out);
+    match (status, collected.error.take()) {
+        (_, Some(e)) => Err(format!("{what}: {e}")),
+        (EF_OK, None) => Ok(collected),
+        (status, None) => Err(format!("{what}: failed with status {status}")),
+    }
+}
+
+/// Extension-owned state pointer, destroyed on drop
+#[derive(Debug)]
+struct State {
+    ptr: *mut c_void,
+    destroy: unsafe extern "C" fn(*mut c_void),
+}
+
+// The ABI requires state to be usable from any thread; the host serializes
+// calls on a single state behind a mutex.
+unsafe impl Send for State {}
+unsafe impl Sync for State {}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { (self.destroy)(self.ptr) }
+        }
+    }
+}
+
+type CreateFn = unsafe extern "C" fn(
+    config: *const EfConfigEntry,
+    len: usize,
+    out: *const EfOut,
+) -> *mut c_void;
+
+fn create_state(
+    what: &str,
+    config: &HashMap<String, String>,
+    create: CreateFn,
+    destroy: unsafe extern "C" fn(*mut c_void),
+) -> Result<State, String> {
+    let entries: Vec<EfConfigEntry> = config
+        .iter()
+        .map(|(k, v)| EfConfigEntry {
+            key: EfStr::borrowed(k),
+            value: EfStr::borrowed(v),
+        })
+        .collect();
+    let mut state = State {
+        ptr: ptr::null_mut(),
+        destroy,
+    };
+    invoke(what, |out| {
+        state.ptr = unsafe { create(entries.as_ptr(), entries.len(), out) };
+        if state.ptr.is_null() {
+            EF_ERROR
+        } else {
+            EF_OK
+        }
+    })?;
+    Ok(state)
+}
+
+fn mapping_failed(message: String) -> EventFluxError {
+    EventFluxError::MappingFailed {
+        message,
+        source: None,
+    }
+}
+
+// ============================================================================
+// Scalar functions
+// ============================================================================
+
+pub struct AbiScalarFunction {
+    descriptor: &'static EfFunction,
+    name: &'static str,
+    return_type: ApiAttributeType,
+    args: Vec<Box<dyn ExpressionExecutor>>,
+}
+
+impl std::fmt::Debug for AbiScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbiScalarFunction")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl AbiScalarFunction {
+    pub fn new(
+        descriptor: &'static EfFunction,
+        name: &'static str,
+        return_type: ApiAttributeType,
+    ) -> Self {
+        Self {
+            descriptor,
+            name,
+            return_type,
+            args: Vec::new(),
+        }
+    }
+}
+
+impl ExpressionExecutor for AbiScalarFunction {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let args: Vec<AttributeValue> = self
+            .args
+            .iter()
+            .map(|e| e.execute(event).unwrap_or(AttributeValue::Null))
+            .collect();
+        let values: Vec<EfValue> = args.iter().map(EfValue::borrow).collect();
+        let result = invoke(self.name, |out| unsafe {
+            (self.descriptor.call)(values.as_ptr(), values.len(), out)
+        });
+        match result {
+            Ok(mut collected) => Some(collected.values.pop().unwrap_or(AttributeValue::Null)),
+            Err(e) => {
+                log::warn!("{e}");
+                Some(AttributeValue::Null)
+            }
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(Self {
+            descriptor: self.descriptor,
+            name: self.name,
+            return_type: self.return_type,
+            args: self.args.iter().map(|e| e.clone_executor(ctx)).collect(),
+        })
+    }
+}
+
+impl ScalarFunctionExecutor for AbiScalarFunction {
+    fn init(
+        &mut self,
+        args: &Vec<Box<dyn ExpressionExecutor>>,
+        ctx: &Arc<EventFluxAppContext>,
+    ) -> Result<(), String> {
+        self.args = args.iter().map(|e| e.clone_executor(ctx)).collect();
+        Ok(())
+    }
+
+    fn destroy(&mut self) {
+        self.args.clear();
+    }
+
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn clone_scalar_function(&self) -> Box<dyn ScalarFunctionExecutor> {
+        Box::new(Self::new(self.descriptor, self.name, self.return_type))
+    }
+}
+
+// ============================================================================
+// Aggregators
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct AbiAggregatorFactory {
+    descriptor: &'static EfAggregator,
+    name: &'static str,
+    return_type: ApiAttributeType,
+}
+
+impl AbiAggregatorFactory {
+    pub fn new(
+        descriptor: &'static EfAggregator,
+        name: &'static str,
+        return_type: ApiAttributeType,
+    ) -> Self {
+        Self {
+            descriptor,
+            name,
+            return_type,
+        }
+    }
+}
+
+impl AttributeAggregatorFactory for AbiAggregatorFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let state = State {
+            ptr: unsafe { (self.descriptor.create)() },
+            destroy: self.descriptor.destroy,
+        };
+        Box::new(AbiAggregatorExecutor {
+            descriptor: self.descriptor,
+            name: self.name,
+            return_type: self.return_type,
+            arg_exec: None,
+            state: Mutex::new(state),
+            app_ctx: None,
+        })
+    }
+
+    fn return_type(&self, _arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        Ok(self.return_type)
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(self.clone())
+    }
+}
+
+/// Aggregator whose state lives in the extension
+pub struct AbiAggregatorExecutor {
+    descriptor: &'static EfAggregator,
+    name: &'static str,
+    return_type: ApiAttributeType,
+    arg_exec: Option<Box<dyn ExpressionExecutor>>,
+    state: Mutex<State>,
+    app_ctx: Option<Arc<EventFluxAppContext>>,
+}
+
+impl std::fmt::Debug for AbiAggregatorExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbiAggregatorExecutor")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl AbiAggregatorExecutor {
+    fn update(
+        &self,
+        data: Option<AttributeValue>,
+        step: unsafe extern "C" fn(*mut c_void, *const EfValue, *const EfOut) -> i32,
+    ) -> Option<AttributeValue> {
+        let state = self.state.lock().unwrap();
+        if state.ptr.is_null() {
+            log::warn!("{}: extension failed to create aggregator state", self.name);
+            return None;
+        }
+        let data = data.unwrap_or(AttributeValue::Null);
+        let value = EfValue::borrow(&data);
+        match invoke(self.name, |out| unsafe { step(state.ptr, &value, out) }) {
+            Ok(mut collected) => collected.values.pop(),
+            Err(e) => {
+                log::warn!("{e}");
+                None
+            }
+        }
+    }
+}
+
+impl AttributeAggregatorExecutor for AbiAggregatorExecutor {
+    fn init(
+        &mut self,
+        mut e: Vec<Box<dyn ExpressionExecutor>>,
+        _m: ProcessingMode,
+        _ex: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        if e.len() != 1 {
+            return Err(format!(
+                "{} aggregator requires exactly one argument",
+                self.name
+            ));
+        }
+        self.arg_exec = Some(e.remove(0));
+        self.app_ctx = Some(Arc::clone(&ctx.eventflux_app_context));
+        Ok(())
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.update(data, self.descriptor.add)
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.update(data, self.descriptor.remove)
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        let state = self.state.lock().unwrap();
+        if !state.ptr.is_null() {
+            unsafe { (self.descriptor.reset)(state.ptr) };
+        }
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = self.app_ctx.as_ref().unwrap();
+        let state = {
+            let state = self.state.lock().unwrap();
+            let ptr = if state.ptr.is_null() {
+                unsafe { (self.descriptor.create)() }
+            } else {
+                unsafe { (self.descriptor.clone)(state.ptr) }
+            };
+            State {
+                ptr,
+                destroy: self.descriptor.destroy,
+            }
+        };
+        Box::new(AbiAggregatorExecutor {
+            descriptor: self.descriptor,
+            name: self.name,
+            return_type: self.return_type,
+            arg_exec: self.arg_exec.as_ref().map(|e| e.clone_executor(ctx)),
+            state: Mutex::new(state),
+            app_ctx: Some(Arc::clone(ctx)),
+        })
+    }
+}
+
+impl ExpressionExecutor for AbiAggregatorExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let event = event?;
+        let data = self.arg_exec.as_ref().and_then(|e| e.execute(Some(event)));
+        match event.get_event_type() {
+            ComplexEventType::Current => self.process_add(data),
+            ComplexEventType::Expired => self.process_remove(data),
+            ComplexEventType::Reset => self.reset(),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Windows
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct AbiWindowFactory {
+    descriptor: &'static EfWindow,
+    name: &'static str,
+}
+
+impl AbiWindowFactory {
+    pub fn new(descriptor: &'static EfWindow, name: &'static str) -> Self {
+        Self { descriptor, name }
+    }
+}
+
+fn constant_parameters(handler: &WindowHandler, name: &str) -> Result<Vec<AttributeValue>, String> {
+    handler
+        .get_parameters()
+        .iter()
+        .map(|expr| match expr {
+            Expression::Constant(c) => Ok(match &c.value {
+                ConstantValueWithFloat::String(s) => AttributeValue::String(s.clone()),
+                ConstantValueWithFloat::Int(i) => AttributeValue::Int(*i),
+                ConstantValueWithFloat::Long(l) | ConstantValueWithFloat::Time(l) => {
+                    AttributeValue::Long(*l)
+                }
+                ConstantValueWithFloat::Float(f) => AttributeValue::Float(*f),
+                ConstantValueWithFloat::Double(d) => AttributeValue::Double(*d),
+                ConstantValueWithFloat::Bool(b) => AttributeValue::Bool(*b),
+                ConstantValueWithFloat::Null => AttributeValue::Null,
+            }),
+            _ => Err(format!("{name} window parameters must be constants")),
+        })
+        .collect()
+}
+
+impl WindowProcessorFactory for AbiWindowFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        _parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        let params = constant_parameters(handler, self.name)?;
+        let processor =
+            AbiWindowProcessor::new(self.descriptor, self.name, params, app_ctx, query_ctx)?;
+        Ok(Arc::new(Mutex::new(processor)))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(self.clone())
+    }
+}
+
+/// Sliding window whose expiry policy lives in the extension; the events are
+/// buffered on the host
+#[derive(Debug)]
+pub struct AbiWindowProcessor {
+    meta: CommonProcessorMeta,
+    descriptor: &'static EfWindow,
+    name: &'static str,
+    params: Vec<AttributeValue>,
+    state: Mutex<State>,
+    buffer: Mutex<VecDeque<StreamEvent>>,
+}
+
+impl AbiWindowProcessor {
+    fn new(
+        descriptor: &'static EfWindow,
+        name: &'static str,
+        params: Vec<AttributeValue>,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Result<Self, String> {
+        let values: Vec<EfValue> = params.iter().map(EfValue::borrow).collect();
+        let mut state = State {
+            ptr: ptr::null_mut(),
+            destroy: descriptor.destroy,
+        };
+        invoke(name, |out| {
+            state.ptr = unsafe { (descriptor.create)(values.as_ptr(), values.len(), out) };
+            if state.ptr.is_null() {
+                EF_ERROR
+            } else {
+                EF_OK
+            }
+        })?;
+        Ok(Self {
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            descriptor,
+            name,
+            params,
+            state: Mutex::new(state),
+            buffer: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    /// Buffer `se` and return the chain to forward: expired events first,
+    /// then the arriving event
+    fn slide(&self, se: &StreamEvent) -> Box<dyn ComplexEvent> {
+        let mut buf = self.buffer.lock().unwrap();
+        let values: Vec<EfValue> = se.before_window_data.iter().map(EfValue::borrow).collect();
+        let expire = {
+            let state = self.state.lock().unwrap();
+            unsafe {
+                (self.descriptor.on_event)(
+                    state.ptr,
+                    se.timestamp,
+                    values.as_ptr(),
+                    values.len(),
+                    buf.len(),
+                )
+            }
+        };
+        if expire < 0 {
+            log::warn!("{}: window rejected event (status {expire})", self.name);
+        }
+        let expire = (expire.max(0) as usize).min(buf.len());
+
+        let mut head: Box<dyn ComplexEvent> = Box::new(se.clone_without_next());
+        for mut old in buf.drain(..expire).rev() {
+            old.set_event_type(ComplexEventType::Expired);
+            old.set_timestamp(se.timestamp);
+            *old.mut_next_ref_option() = Some(head);
+            head = Box::new(old);
+        }
+        buf.push_back(se.clone_without_next());
+        head
+    }
+}
+
+impl Processor for AbiWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        if let Some(ref next) = self.meta.next_processor {
+            if let Some(ref chunk) = complex_event_chunk {
+                let mut current_opt = Some(chunk.as_ref() as &dyn ComplexEvent);
+                while let Some(ev) = current_opt {
+                    if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                        let out = self.slide(se);
+                        next.lock().unwrap().process(Some(out));
+                    }
+                    current_opt = ev.get_next();
+                }
+            } else {
+                next.lock().unwrap().process(None);
+            }
+        }
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        // The parameters were accepted when this window was created
+        Box::new(
+            Self::new(
+                self.descriptor,
+                self.name,
+                self.params.clone(),
+                Arc::clone(&self.meta.eventflux_app_context),
+                Arc::clone(query_ctx),
+            )
+            .unwrap_or_else(|e| panic!("{e}")),
+        )
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::SLIDE
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+}
+
+impl WindowProcessor for AbiWindowProcessor {}
+
+// ============================================================================
+// Sources
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct AbiSourceFactory {
+    descriptor: &'static EfSource,
+    name: &'static str,
+    formats: &'static [&'static str],
+}
+
+impl AbiSourceFactory {
+    pub fn new(
+        descriptor: &'static EfSource,
+        name: &'static str,
+        formats: &'static [&'static str],
+    ) -> Self {
+        Self {
+            descriptor,
+            name,
+            formats,
+        }
+    }
+}
+
+impl SourceFactory for AbiSourceFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn supported_formats(&self) -> &[&str] {
+        self.formats
+    }
+
+    fn required_parameters(&self) -> &[&str] {
+        &[]
+    }
+
+    fn optional_parameters(&self) -> &[&str] {
+        &[]
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn Source>, EventFluxError> {
+        Ok(Box::new(AbiSource {
+            descriptor: self.descriptor,
+            name: self.name,
+            config: config.clone(),
+            running: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SourceFactory> {
+        Box::new(self.clone())
+    }
+}
+
+/// Polls the extension from a dedicated thread while running
+#[derive(Debug, Clone)]
+pub struct AbiSource {
+    descriptor: &'static EfSource,
+    name: &'static str,
+    config: HashMap<String, String>,
+    running: Arc<AtomicBool>,
+}
+
+impl Source for AbiSource {
+    fn start(&mut self, callback: Arc<dyn SourceCallback>) {
+        let state = match create_state(
+            self.name,
+            &self.config,
+            self.descriptor.create,
+            self.descriptor.destroy,
+        ) {
+            Ok(state) => state,
+            Err(e) => {
+                log::error!("{e}");
+                return;
+            }
+        };
+        let running = Arc::clone(&self.running);
+        running.store(true, Ordering::SeqCst);
+        let descriptor = self.descriptor;
+        let name = self.name;
+        let interval = Duration::from_millis(descriptor.poll_interval_ms);
+
+        thread::spawn(move || {
+            while running.load(Ordering::SeqCst) {
+                let payloads =
+                    match invoke(name, |out| unsafe { (descriptor.poll)(state.ptr, out) }) {
+                        Ok(collected) => collected.payloads,
+                        Err(e) => {
+                            log::warn!("{e}");
+                            Vec::new()
+                        }
+                    };
+                if payloads.is_empty() {
+                    thread::sleep(interval);
+                }
+                for payload in payloads {
+                    if let Err(e) = callback.on_data(&payload) {
+                        log::warn!("{name}: {e}");
+                    }
+                }
+            }
+        });
+    }
+
+    fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn clone_box(&self) -> Box<dyn Source> {
+        Box::new(AbiSource {
+            running: Arc::new(AtomicBool::new(false)),
+            ..self.clone()
+        })
+    }
+}
+
+// ============================================================================
+// Sinks
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct AbiSinkFactory {
+    descriptor: &'static EfSink,
+    name: &'static str,
+    formats: &'static [&'static str],
+}
+
+impl AbiSinkFactory {
+    pub fn new(
+        descriptor: &'static EfSink,
+        name: &'static str,
+        formats: &'static [&'static str],
+    ) -> Self {
+        Self {
+            descriptor,
+            name,
+            formats,
+        }
+    }
+}
+
+impl SinkFactory for AbiSinkFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn supported_formats(&self) -> &[&str] {
+        self.formats
+    }
+
+    fn required_parameters(&self) -> &[&str] {
+        &[]
+    }
+
+    fn optional_parameters(&self) -> &[&str] {
+        &[]
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn Sink>, EventFluxError> {
+        let state = create_state(
+            self.name,
+            config,
+            self.descriptor.create,
+            self.descriptor.destroy,
+        )
+        .map_err(EventFluxError::configuration)?;
+        Ok(Box::new(AbiSink {
+            descriptor: self.descriptor,
+            name: self.name,
+            state: Arc::new(Mutex::new(state)),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SinkFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiSink {
+    descriptor: &'static EfSink,
+    name: &'static str,
+    state: Arc<Mutex<State>>,
+}
+
+impl Sink for AbiSink {
+    fn publish(&self, payload: &[u8]) -> Result<(), EventFluxError> {
+        let state = self.state.lock().unwrap();
+        invoke(self.name, |out| unsafe {
+            (self.descriptor.publish)(state.ptr, EfBytes::from_slice(payload), out)
+        })
+        .map(|_| ())
+        .map_err(|message| EventFluxError::ConnectionUnavailable {
+            message,
+            source: None,
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn Sink> {
+        Box::new(self.clone())
+    }
+}
+
+// ============================================================================
+// Mappers
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct AbiSourceMapperFactory {
+    descriptor: &'static EfSourceMapper,
+    name: &'static str,
+}
+
+impl AbiSourceMapperFactory {
+    pub fn new(descriptor: &'static EfSourceMapper, name: &'static str) -> Self {
+        Self { descriptor, name }
+    }
+}
+
+impl SourceMapperFactory for AbiSourceMapperFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn SourceMapper>, EventFluxError> {
+        let state = create_state(
+            self.name,
+            config,
+            self.descriptor.create,
+            self.descriptor.destroy,
+        )
+        .map_err(EventFluxError::configuration)?;
+        Ok(Box::new(AbiSourceMapper {
+            descriptor: self.descriptor,
+            name: self.name,
+            state: Arc::new(Mutex::new(state)),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SourceMapperFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiSourceMapper {
+    descriptor: &'static EfSourceMapper,
+    name: &'static str,
+    state: Arc<Mutex<State>>,
+}
+
+impl SourceMapper for AbiSourceMapper {
+    fn map(&self, input: &[u8]) -> Result<Vec<Event>, EventFluxError> {
+        let rows = {
+            let state = self.state.lock().unwrap();
+            invoke(self.name, |out| unsafe {
+                (self.descriptor.map)(state.ptr, EfBytes::from_slice(input), out)
+            })
+            .map_err(mapping_failed)?
+            .rows
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        Ok(rows
+            .into_iter()
+            .map(|data| Event::new_with_data(timestamp, data))
+            .collect())
+    }
+
+    fn clone_box(&self) -> Box<dyn SourceMapper> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiSinkMapperFactory {
+    descriptor: &'static EfSinkMapper,
+    name: &'static str,
+}
+
+impl AbiSinkMapperFactory {
+    pub fn new(descriptor: &'static EfSinkMapper, name: &'static str) -> Self {
+        Self { descriptor, name }
+    }
+}
+
+impl SinkMapperFactory for AbiSinkMapperFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn SinkMapper>, EventFluxError> {
+        let state = create_state(
+            self.name,
+            config,
+            self.descriptor.create,
+            self.descriptor.destroy,
+        )
+        .map_err(EventFluxError::configuration)?;
+        Ok(Box::new(AbiSinkMapper {
+            descriptor: self.descriptor,
+            name: self.name,
+            state: Arc::new(Mutex::new(state)),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SinkMapperFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AbiSinkMapper {
+    descriptor: &'static EfSinkMapper,
+    name: &'static str,
+    state: Arc<Mutex<State>>,
+}
+
+impl SinkMapper for AbiSinkMapper {
+    fn map(&self, events: &[Event]) -> Result<Vec<u8>, EventFluxError> {
+        let columns = events.first().map_or(0, |e| e.data.len());
+        if events.iter().any(|e| e.data.len() != columns) {
+            return Err(mapping_failed(format!(
+                "{}: events in a batch must have the same number of attributes",
+                self.name
+            )));
+        }
+        let values: Vec<EfValue> = events
+            .iter()
+            .flat_map(|e| e.data.iter().map(EfValue::borrow))
+            .collect();
+        let state = self.state.lock().unwrap();
+        let payloads = invoke(self.name, |out| unsafe {
+            (self.descriptor.map)(state.ptr, values.as_ptr(), events.len(), columns, out)
+        })
+        .map_err(mapping_failed)?
+        .payloads;
+        Ok(payloads.concat())
+    }
+
+    fn clone_box(&self) -> Box<dyn SinkMapper> {
+        Box::new(self.clone())
+    }
+}
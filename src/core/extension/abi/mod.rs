@@ -0,0 +1,897 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Stable C ABI for Native Extensions
+//!
+//! Native extensions that register through the `register_*` entry points link
+//! against EventFlux's Rust types and only work with the exact compiler and
+//! crate version they were built with. Extensions built against this module
+//! exchange nothing but the `repr(C)` types below, so a library keeps loading
+//! as long as the host supports its ABI version.
+//!
+//! # Entry Points
+//!
+//! | Symbol | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `eventflux_abi_version` | `extern "C" fn() -> u32` | ABI version the library was built for |
+//! | `eventflux_extension` | `extern "C" fn() -> *const EfExtension` | Static descriptor of everything the library provides |
+//!
+//! `EventFluxManager::set_extension` calls `eventflux_abi_version` first and
+//! rejects the library unless it returns [`EVENTFLUX_ABI_VERSION`]. Libraries
+//! without the symbol fall back to the legacy `register_*` entry points.
+//!
+//! # Memory Rules
+//!
+//! * Everything the host passes in (arguments, payloads, config) is borrowed
+//!   for the duration of the call only.
+//! * Extensions never hand allocations to the host. Results are reported
+//!   through the [`EfOut`] callbacks, which copy the data before returning.
+//! * Descriptors and the names they point to must live as long as the library
+//!   is loaded; libraries are never unloaded once registered.
+//! * State pointers returned by `create` belong to the extension and are
+//!   released through the matching `destroy`. The host serializes calls on a
+//!   single state pointer but may use different states from different threads.
+//!
+//! Every fallible call returns [`EF_OK`] or [`EF_ERROR`]; on error the
+//! extension should report a message through [`EfOut::error`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! unsafe extern "C" fn plus_one(args: *const EfValue, len: usize, out: *const EfOut) -> i32 {
+//!     let args = std::slice::from_raw_parts(args, len);
+//!     (*out).emit_value(&EfValue::long(args[0].long + 1));
+//!     EF_OK
+//! }
+//!
+//! static FUNCTIONS: [EfFunction; 1] = [EfFunction {
+//!     name: EfStr::from_static("plusOne"),
+//!     return_type: EF_TYPE_LONG,
+//!     call: plus_one,
+//! }];
+//!
+//! static EXTENSION: EfExtension = EfExtension {
+//!     functions: FUNCTIONS.as_ptr(),
+//!     function_count: FUNCTIONS.len(),
+//!     ..EfExtension::empty(EfStr::from_static("math"))
+//! };
+//!
+//! #[no_mangle]
+//! pub extern "C" fn eventflux_abi_version() -> u32 { EVENTFLUX_ABI_VERSION }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn eventflux_extension() -> *const EfExtension { &EXTENSION }
+//! ```
+
+mod adapters;
+
+pub use adapters::{
+    AbiAggregatorExecutor, AbiAggregatorFactory, AbiScalarFunction, AbiSink, AbiSinkFactory,
+    AbiSinkMapper, AbiSinkMapperFactory, AbiSource, AbiSourceFactory, AbiSourceMapper,
+    AbiSourceMapperFactory, AbiWindowFactory, AbiWindowProcessor,
+};
+
+use std::ffi::c_void;
+use std::ptr;
+
+use crate::core::config::eventflux_context::EventFluxContext;
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
+use crate::core::extension::{
+    AttributeAggregatorFactory, SinkFactory, SinkMapperFactory, SourceFactory, SourceMapperFactory,
+    WindowProcessorFactory,
+};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+
+/// ABI version implemented by this host
+pub const EVENTFLUX_ABI_VERSION: u32 = 1;
+
+/// Symbol returning the library's ABI version
+pub const ABI_VERSION_SYMBOL: &[u8] = b"eventflux_abi_version";
+/// Symbol returning the library's [`EfExtension`] descriptor
+pub const ABI_EXTENSION_SYMBOL: &[u8] = b"eventflux_extension";
+
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+pub type AbiExtensionFn = unsafe extern "C" fn() -> *const EfExtension;
+
+/// Status returned by a successful call
+pub const EF_OK: i32 = 0;
+/// Status returned by a failed call
+pub const EF_ERROR: i32 = 1;
+
+// Value tags, also used as declared return types
+pub const EF_TYPE_NULL: u32 = 0;
+pub const EF_TYPE_STRING: u32 = 1;
+pub const EF_TYPE_INT: u32 = 2;
+pub const EF_TYPE_LONG: u32 = 3;
+pub const EF_TYPE_FLOAT: u32 = 4;
+pub const EF_TYPE_DOUBLE: u32 = 5;
+pub const EF_TYPE_BOOL: u32 = 6;
+pub const EF_TYPE_BYTES: u32 = 7;
+
+// ============================================================================
+// Data types
+// ============================================================================
+
+/// Borrowed byte buffer
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EfBytes {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl EfBytes {
+    pub const fn empty() -> Self {
+        Self {
+            ptr: ptr::null(),
+            len: 0,
+        }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+
+    /// # Safety
+    /// `ptr` must point to `len` readable bytes that outlive `'a`.
+    pub unsafe fn as_slice<'a>(&self) -> &'a [u8] {
+        if self.ptr.is_null() || self.len == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(self.ptr, self.len)
+        }
+    }
+}
+
+/// Borrowed UTF-8 string
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EfStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl EfStr {
+    pub const fn from_static(s: &'static str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+
+    pub fn borrowed(s: &str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+
+    /// # Safety
+    /// `ptr` must point to `len` readable bytes that outlive `'a`.
+    pub unsafe fn as_str<'a>(&self) -> Result<&'a str, String> {
+        let bytes = EfBytes {
+            ptr: self.ptr,
+            len: self.len,
+        }
+        .as_slice();
+        std::str::from_utf8(bytes).map_err(|e| format!("invalid UTF-8 in extension string: {e}"))
+    }
+}
+
+/// A single attribute value
+///
+/// `tag` selects the active field: integers and booleans use `long`, floating
+/// point values use `double`, strings and bytes use `bytes`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EfValue {
+    pub tag: u32,
+    pub long: i64,
+    pub double: f64,
+    pub bytes: EfBytes,
+}
+
+impl EfValue {
+    pub const fn null() -> Self {
+        Self {
+            tag: EF_TYPE_NULL,
+            long: 0,
+            double: 0.0,
+            bytes: EfBytes::empty(),
+        }
+    }
+
+    pub const fn long(value: i64) -> Self {
+        Self {
+            tag: EF_TYPE_LONG,
+            long: value,
+            ..Self::null()
+        }
+    }
+
+    pub const fn int(value: i32) -> Self {
+        Self {
+            tag: EF_TYPE_INT,
+            long: value as i64,
+            ..Self::null()
+        }
+    }
+
+    pub const fn double(value: f64) -> Self {
+        Self {
+            tag: EF_TYPE_DOUBLE,
+            double: value,
+            ..Self::null()
+        }
+    }
+
+    pub const fn bool(value: bool) -> Self {
+        Self {
+            tag: EF_TYPE_BOOL,
+            long: value as i64,
+            ..Self::null()
+        }
+    }
+
+    /// String value borrowing `s`
+    pub fn string(s: &str) -> Self {
+        Self {
+            tag: EF_TYPE_STRING,
+            bytes: EfBytes::from_slice(s.as_bytes()),
+            ..Self::null()
+        }
+    }
+
+    /// Borrow an attribute value; string and byte values point into `value`.
+    /// `OBJECT` values have no ABI representation and are passed as null.
+    pub fn borrow(value: &AttributeValue) -> Self {
+        match value {
+            AttributeValue::String(s) => Self::string(s),
+            AttributeValue::Int(i) => Self::int(*i),
+            AttributeValue::Long(l) => Self::long(*l),
+            AttributeValue::Float(f) => Self {
+                tag: EF_TYPE_FLOAT,
+                double: *f as f64,
+                ..Self::null()
+            },
+            AttributeValue::Double(d) => Self::double(*d),
+            AttributeValue::Bool(b) => Self::bool(*b),
+            AttributeValue::Bytes(b) => Self {
+                tag: EF_TYPE_BYTES,
+                bytes: EfBytes::from_slice(b),
+                ..Self::null()
+            },
+            AttributeValue::Object(_) | AttributeValue::Null => Self::null(),
+        }
+    }
+
+    /// Copy into an owned attribute value
+    ///
+    /// # Safety
+    /// For string and byte values, `bytes` must be valid for reads.
+    pub unsafe fn to_attribute_value(&self) -> Result<AttributeValue, String> {
+        Ok(match self.tag {
+            EF_TYPE_NULL => AttributeValue::Null,
+            EF_TYPE_STRING => AttributeValue::String(
+                EfStr {
+                    ptr: self.bytes.ptr,
+                    len: self.bytes.len,
+                }
+                .as_str()?
+                .to_string(),
+            ),
+            EF_TYPE_INT => AttributeValue::Int(self.long as i32),
+            EF_TYPE_LONG => AttributeValue::Long(self.long),
+            EF_TYPE_FLOAT => AttributeValue::Float(self.double as f32),
+            EF_TYPE_DOUBLE => AttributeValue::Double(self.double),
+            EF_TYPE_BOOL => AttributeValue::Bool(self.long != 0),
+            EF_TYPE_BYTES => AttributeValue::Bytes(self.bytes.as_slice().to_vec()),
+            other => return Err(format!("unknown extension value tag {other}")),
+        })
+    }
+}
+
+/// Configuration entry passed to `create` callbacks
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct EfConfigEntry {
+    pub key: EfStr,
+    pub value: EfStr,
+}
+
+/// Host callbacks through which an extension reports results
+///
+/// Each callback copies its argument before returning. `ctx` must be passed
+/// back unchanged.
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfOut {
+    pub ctx: *mut c_void,
+    /// Report a value (function result, aggregate)
+    pub value: unsafe extern "C" fn(ctx: *mut c_void, value: *const EfValue),
+    /// Report a row of values (source mapper output, one per event)
+    pub row: unsafe extern "C" fn(ctx: *mut c_void, values: *const EfValue, len: usize),
+    /// Report a byte payload (source poll, sink mapper output)
+    pub bytes: unsafe extern "C" fn(ctx: *mut c_void, bytes: EfBytes),
+    /// Report an error message
+    pub error: unsafe extern "C" fn(ctx: *mut c_void, message: EfStr),
+}
+
+impl EfOut {
+    /// # Safety
+    /// Must only be called on an `EfOut` received from the host.
+    pub unsafe fn emit_value(&self, value: &EfValue) {
+        (self.value)(self.ctx, value)
+    }
+
+    /// # Safety
+    /// Must only be called on an `EfOut` received from the host.
+    pub unsafe fn emit_row(&self, values: &[EfValue]) {
+        (self.row)(self.ctx, values.as_ptr(), values.len())
+    }
+
+    /// # Safety
+    /// Must only be called on an `EfOut` received from the host.
+    pub unsafe fn emit_bytes(&self, bytes: &[u8]) {
+        (self.bytes)(self.ctx, EfBytes::from_slice(bytes))
+    }
+
+    /// # Safety
+    /// Must only be called on an `EfOut` received from the host.
+    pub unsafe fn emit_error(&self, message: &str) {
+        (self.error)(self.ctx, EfStr::borrowed(message))
+    }
+}
+
+// ============================================================================
+// Descriptors
+// ============================================================================
+
+/// Stateless scalar function
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfFunction {
+    pub name: EfStr,
+    pub return_type: u32,
+    pub call: unsafe extern "C" fn(args: *const EfValue, len: usize, out: *const EfOut) -> i32,
+}
+
+/// Window aggregator over a single argument
+///
+/// `add` and `remove` report the current aggregate through [`EfOut::value`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfAggregator {
+    pub name: EfStr,
+    pub return_type: u32,
+    pub create: unsafe extern "C" fn() -> *mut c_void,
+    pub add:
+        unsafe extern "C" fn(state: *mut c_void, value: *const EfValue, out: *const EfOut) -> i32,
+    pub remove:
+        unsafe extern "C" fn(state: *mut c_void, value: *const EfValue, out: *const EfOut) -> i32,
+    pub reset: unsafe extern "C" fn(state: *mut c_void),
+    pub clone: unsafe extern "C" fn(state: *mut c_void) -> *mut c_void,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Sliding window
+///
+/// The host buffers the events. For every arriving event `on_event` returns
+/// how many of the oldest `held` events expire (negative on error).
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfWindow {
+    pub name: EfStr,
+    pub create:
+        unsafe extern "C" fn(params: *const EfValue, len: usize, out: *const EfOut) -> *mut c_void,
+    pub on_event: unsafe extern "C" fn(
+        state: *mut c_void,
+        timestamp: i64,
+        values: *const EfValue,
+        len: usize,
+        held: usize,
+    ) -> i64,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Polling source
+///
+/// The host calls `poll` from a dedicated thread; each payload reported
+/// through [`EfOut::bytes`] is handed to the stream's mapper. When a poll
+/// produces nothing the host waits `poll_interval_ms` before polling again.
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfSource {
+    pub name: EfStr,
+    pub formats: *const EfStr,
+    pub format_count: usize,
+    pub poll_interval_ms: u64,
+    pub create: unsafe extern "C" fn(
+        config: *const EfConfigEntry,
+        len: usize,
+        out: *const EfOut,
+    ) -> *mut c_void,
+    pub poll: unsafe extern "C" fn(state: *mut c_void, out: *const EfOut) -> i32,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Sink publishing mapped payloads
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfSink {
+    pub name: EfStr,
+    pub formats: *const EfStr,
+    pub format_count: usize,
+    pub create: unsafe extern "C" fn(
+        config: *const EfConfigEntry,
+        len: usize,
+        out: *const EfOut,
+    ) -> *mut c_void,
+    pub publish:
+        unsafe extern "C" fn(state: *mut c_void, payload: EfBytes, out: *const EfOut) -> i32,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Source mapper turning a payload into rows through [`EfOut::row`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfSourceMapper {
+    pub name: EfStr,
+    pub create: unsafe extern "C" fn(
+        config: *const EfConfigEntry,
+        len: usize,
+        out: *const EfOut,
+    ) -> *mut c_void,
+    pub map: unsafe extern "C" fn(state: *mut c_void, input: EfBytes, out: *const EfOut) -> i32,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Sink mapper turning `rows * columns` values (row-major) into a payload
+/// reported through [`EfOut::bytes`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfSinkMapper {
+    pub name: EfStr,
+    pub create: unsafe extern "C" fn(
+        config: *const EfConfigEntry,
+        len: usize,
+        out: *const EfOut,
+    ) -> *mut c_void,
+    pub map: unsafe extern "C" fn(
+        state: *mut c_void,
+        values: *const EfValue,
+        rows: usize,
+        columns: usize,
+        out: *const EfOut,
+    ) -> i32,
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// Everything a library provides
+#[repr(C)]
+#[derive(Debug)]
+pub struct EfExtension {
+    pub abi_version: u32,
+    pub name: EfStr,
+    pub functions: *const EfFunction,
+    pub function_count: usize,
+    pub aggregators: *const EfAggregator,
+    pub aggregator_count: usize,
+    pub windows: *const EfWindow,
+    pub window_count: usize,
+    pub sources: *const EfSource,
+    pub source_count: usize,
+    pub sinks: *const EfSink,
+    pub sink_count: usize,
+    pub source_mappers: *const EfSourceMapper,
+    pub source_mapper_count: usize,
+    pub sink_mappers: *const EfSinkMapper,
+    pub sink_mapper_count: usize,
+}
+
+impl EfExtension {
+    /// Descriptor providing nothing, for use with struct update syntax
+    pub const fn empty(name: EfStr) -> Self {
+        Self {
+            abi_version: EVENTFLUX_ABI_VERSION,
+            name,
+            functions: ptr::null(),
+            function_count: 0,
+            aggregators: ptr::null(),
+            aggregator_count: 0,
+            windows: ptr::null(),
+            window_count: 0,
+            sources: ptr::null(),
+            source_count: 0,
+            sinks: ptr::null(),
+            sink_count: 0,
+            source_mappers: ptr::null(),
+            source_mapper_count: 0,
+            sink_mappers: ptr::null(),
+            sink_mapper_count: 0,
+        }
+    }
+}
+
+// Descriptors are immutable and usually declared as statics.
+unsafe impl Sync for EfExtension {}
+unsafe impl Sync for EfFunction {}
+unsafe impl Sync for EfAggregator {}
+unsafe impl Sync for EfWindow {}
+unsafe impl Sync for EfSource {}
+unsafe impl Sync for EfSink {}
+unsafe impl Sync for EfSourceMapper {}
+unsafe impl Sync for EfSinkMapper {}
+unsafe impl Sync for EfStr {}
+
+// ============================================================================
+// Registration
+// ============================================================================
+
+/// Reject libraries built for a different ABI version
+pub fn check_abi_version(name: &str, version: u32) -> Result<(), String> {
+    if version == EVENTFLUX_ABI_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "Extension '{name}' was built for EventFlux extension ABI version {version}, \
+             but this host supports version {EVENTFLUX_ABI_VERSION}; rebuild the extension \
+             against a matching EventFlux release"
+        ))
+    }
+}
+
+pub(crate) fn type_from_tag(tag: u32) -> Result<ApiAttributeType, String> {
+    Ok(match tag {
+        EF_TYPE_STRING => ApiAttributeType::STRING,
+        EF_TYPE_INT => ApiAttributeType::INT,
+        EF_TYPE_LONG => ApiAttributeType::LONG,
+        EF_TYPE_FLOAT => ApiAttributeType::FLOAT,
+        EF_TYPE_DOUBLE => ApiAttributeType::DOUBLE,
+        EF_TYPE_BOOL => ApiAttributeType::BOOL,
+        EF_TYPE_BYTES => ApiAttributeType::OBJECT,
+        other => return Err(format!("unsupported return type tag {other}")),
+    })
+}
+
+/// # Safety
+/// `ptr` must point to `len` descriptors that live for the rest of the process.
+unsafe fn descriptors<T>(ptr: *const T, len: usize) -> Result<&'static [T], String> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err("descriptor array is null but its count is not zero".to_string())
+    } else {
+        Ok(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Validate `extension` and register everything it declares in `context`
+///
+/// Nothing is registered unless the whole descriptor is valid.
+///
+/// # Safety
+/// `extension` and everything it points to must stay valid for the rest of
+/// the process, which holds for libraries kept loaded by `EventFluxManager`.
+pub unsafe fn register_extension(
+    context: &EventFluxContext,
+    extension: &'static EfExtension,
+) -> Result<(), String> {
+    let ext_name = extension.name.as_str()?;
+    check_abi_version(ext_name, extension.abi_version)?;
+    let invalid = |what: &str, e: String| format!("Extension '{ext_name}': invalid {what}: {e}");
+
+    let mut functions = Vec::new();
+    for f in descriptors(extension.functions, extension.function_count)
+        .map_err(|e| invalid("functions", e))?
+    {
+        let name = f.name.as_str().map_err(|e| invalid("function", e))?;
+        let return_type =
+            type_from_tag(f.return_type).map_err(|e| invalid(&format!("function '{name}'"), e))?;
+        functions.push(AbiScalarFunction::new(f, name, return_type));
+    }
+    let mut aggregators = Vec::new();
+    for a in descriptors(extension.aggregators, extension.aggregator_count)
+        .map_err(|e| invalid("aggregators", e))?
+    {
+        let name = a.name.as_str().map_err(|e| invalid("aggregator", e))?;
+        let return_type = type_from_tag(a.return_type)
+            .map_err(|e| invalid(&format!("aggregator '{name}'"), e))?;
+        aggregators.push(AbiAggregatorFactory::new(a, name, return_type));
+    }
+    let mut windows = Vec::new();
+    for w in
+        descriptors(extension.windows, extension.window_count).map_err(|e| invalid("windows", e))?
+    {
+        let name = w.name.as_str().map_err(|e| invalid("window", e))?;
+        windows.push(AbiWindowFactory::new(w, name));
+    }
+    let mut sources = Vec::new();
+    for s in
+        descriptors(extension.sources, extension.source_count).map_err(|e| invalid("sources", e))?
+    {
+        let name = s.name.as_str().map_err(|e| invalid("source", e))?;
+        let formats = format_names(s.formats, s.format_count)
+            .map_err(|e| invalid(&format!("source '{name}'"), e))?;
+        sources.push(AbiSourceFactory::new(s, name, formats));
+    }
+    let mut sinks = Vec::new();
+    for s in descriptors(extension.sinks, extension.sink_count).map_err(|e| invalid("sinks", e))? {
+        let name = s.name.as_str().map_err(|e| invalid("sink", e))?;
+        let formats = format_names(s.formats, s.format_count)
+            .map_err(|e| invalid(&format!("sink '{name}'"), e))?;
+        sinks.push(AbiSinkFactory::new(s, name, formats));
+    }
+    let mut source_mappers = Vec::new();
+    for m in descriptors(extension.source_mappers, extension.source_mapper_count)
+        .map_err(|e| invalid("source mappers", e))?
+    {
+        let name = m.name.as_str().map_err(|e| invalid("source mapper", e))?;
+        source_mappers.push(AbiSourceMapperFactory::new(m, name));
+    }
+    let mut sink_mappers = Vec::new();
+    for m in descriptors(extension.sink_mappers, extension.sink_mapper_count)
+        .map_err(|e| invalid("sink mappers", e))?
+    {
+        let name = m.name.as_str().map_err(|e| invalid("sink mapper", e))?;
+        sink_mappers.push(AbiSinkMapperFactory::new(m, name));
+    }
+
+    for f in functions {
+        context.add_scalar_function_factory(f.get_name(), Box::new(f));
+    }
+    for a in aggregators {
+        context.add_attribute_aggregator_factory(a.name().to_string(), Box::new(a));
+    }
+    for w in windows {
+        context.add_window_factory(w.name().to_string(), Box::new(w));
+    }
+    for s in sources {
+        context.add_source_factory(s.name().to_string(), Box::new(s));
+    }
+    for s in sinks {
+        context.add_sink_factory(s.name().to_string(), Box::new(s));
+    }
+    for m in source_mappers {
+        context.add_source_mapper_factory(m.name().to_string(), Box::new(m));
+    }
+    for m in sink_mappers {
+        context.add_sink_mapper_factory(m.name().to_string(), Box::new(m));
+    }
+    Ok(())
+}
+
+unsafe fn format_names(ptr: *const EfStr, len: usize) -> Result<&'static [&'static str], String> {
+    let names = descriptors(ptr, len)?
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Result<Vec<&'static str>, String>>()?;
+    Ok(Box::leak(names.into_boxed_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::event::Event;
+    use crate::core::executor::expression_executor::ExpressionExecutor;
+
+    unsafe extern "C" fn answer(_args: *const EfValue, _len: usize, out: *const EfOut) -> i32 {
+        (*out).emit_value(&EfValue::long(42));
+        EF_OK
+    }
+
+    unsafe extern "C" fn broken(_args: *const EfValue, _len: usize, out: *const EfOut) -> i32 {
+        (*out).emit_error("boom");
+        EF_ERROR
+    }
+
+    unsafe extern "C" fn sum_create() -> *mut c_void {
+        Box::into_raw(Box::new(0i64)) as *mut c_void
+    }
+
+    unsafe extern "C" fn sum_add(state: *mut c_void, v: *const EfValue, out: *const EfOut) -> i32 {
+        let sum = &mut *(state as *mut i64);
+        *sum += (*v).long;
+        (*out).emit_value(&EfValue::long(*sum));
+        EF_OK
+    }
+
+    unsafe extern "C" fn sum_remove(
+        state: *mut c_void,
+        v: *const EfValue,
+        out: *const EfOut,
+    ) -> i32 {
+        let sum = &mut *(state as *mut i64);
+        *sum -= (*v).long;
+        (*out).emit_value(&EfValue::long(*sum));
+        EF_OK
+    }
+
+    unsafe extern "C" fn sum_reset(state: *mut c_void) {
+        *(state as *mut i64) = 0;
+    }
+
+    unsafe extern "C" fn sum_clone(state: *mut c_void) -> *mut c_void {
+        Box::into_raw(Box::new(*(state as *mut i64))) as *mut c_void
+    }
+
+    unsafe extern "C" fn sum_destroy(state: *mut c_void) {
+        drop(Box::from_raw(state as *mut i64));
+    }
+
+    unsafe extern "C" fn mapper_create(
+        _config: *const EfConfigEntry,
+        _len: usize,
+        _out: *const EfOut,
+    ) -> *mut c_void {
+        ptr::NonNull::<c_void>::dangling().as_ptr()
+    }
+
+    unsafe extern "C" fn mapper_destroy(_state: *mut c_void) {}
+
+    /// One row per input byte
+    unsafe extern "C" fn bytes_to_rows(
+        _state: *mut c_void,
+        input: EfBytes,
+        out: *const EfOut,
+    ) -> i32 {
+        for b in input.as_slice() {
+            (*out).emit_row(&[EfValue::int(*b as i32)]);
+        }
+        EF_OK
+    }
+
+    /// Writes the batch shape as `rows x columns`
+    unsafe extern "C" fn shape(
+        _state: *mut c_void,
+        _values: *const EfValue,
+        rows: usize,
+        columns: usize,
+        out: *const EfOut,
+    ) -> i32 {
+        (*out).emit_bytes(format!("{rows}x{columns}").as_bytes());
+        EF_OK
+    }
+
+    static FUNCTIONS: [EfFunction; 2] = [
+        EfFunction {
+            name: EfStr::from_static("answer"),
+            return_type: EF_TYPE_LONG,
+            call: answer,
+        },
+        EfFunction {
+            name: EfStr::from_static("broken"),
+            return_type: EF_TYPE_LONG,
+            call: broken,
+        },
+    ];
+
+    static AGGREGATORS: [EfAggregator; 1] = [EfAggregator {
+        name: EfStr::from_static("abiSum"),
+        return_type: EF_TYPE_LONG,
+        create: sum_create,
+        add: sum_add,
+        remove: sum_remove,
+        reset: sum_reset,
+        clone: sum_clone,
+        destroy: sum_destroy,
+    }];
+
+    static SOURCE_MAPPERS: [EfSourceMapper; 1] = [EfSourceMapper {
+        name: EfStr::from_static("perByte"),
+        create: mapper_create,
+        map: bytes_to_rows,
+        destroy: mapper_destroy,
+    }];
+
+    static SINK_MAPPERS: [EfSinkMapper; 1] = [EfSinkMapper {
+        name: EfStr::from_static("shape"),
+        create: mapper_create,
+        map: shape,
+        destroy: mapper_destroy,
+    }];
+
+    static EXTENSION: EfExtension = EfExtension {
+        functions: FUNCTIONS.as_ptr(),
+        function_count: FUNCTIONS.len(),
+        aggregators: AGGREGATORS.as_ptr(),
+        aggregator_count: AGGREGATORS.len(),
+        source_mappers: SOURCE_MAPPERS.as_ptr(),
+        source_mapper_count: SOURCE_MAPPERS.len(),
+        sink_mappers: SINK_MAPPERS.as_ptr(),
+        sink_mapper_count: SINK_MAPPERS.len(),
+        ..EfExtension::empty(EfStr::from_static("test"))
+    };
+
+    static MISMATCHED: EfExtension = EfExtension {
+        abi_version: EVENTFLUX_ABI_VERSION + 1,
+        ..EfExtension::empty(EfStr::from_static("future"))
+    };
+
+    static DANGLING: EfExtension = EfExtension {
+        function_count: 1,
+        ..EfExtension::empty(EfStr::from_static("dangling"))
+    };
+
+    #[test]
+    fn test_version_mismatch_is_rejected() {
+        assert!(check_abi_version("ok", EVENTFLUX_ABI_VERSION).is_ok());
+        let err = check_abi_version("old", 0).unwrap_err();
+        assert!(err.contains("'old'") && err.contains("ABI version 0"));
+
+        let context = EventFluxContext::new();
+        let err = unsafe { register_extension(&context, &MISMATCHED) }.unwrap_err();
+        assert!(err.contains("'future'"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_descriptor_is_rejected() {
+        let context = EventFluxContext::new();
+        let err = unsafe { register_extension(&context, &DANGLING) }.unwrap_err();
+        assert!(err.contains("invalid functions"), "{err}");
+    }
+
+    #[test]
+    fn test_functions_and_aggregators() {
+        let context = EventFluxContext::new();
+        unsafe { register_extension(&context, &EXTENSION) }.unwrap();
+
+        let answer = context.get_scalar_function_factory("answer").unwrap();
+        assert_eq!(answer.get_return_type(), ApiAttributeType::LONG);
+        assert_eq!(answer.execute(None), Some(AttributeValue::Long(42)));
+        let broken = context.get_scalar_function_factory("broken").unwrap();
+        assert_eq!(broken.execute(None), Some(AttributeValue::Null));
+
+        let sum = context
+            .get_attribute_aggregator_factory("abiSum")
+            .unwrap()
+            .create();
+        assert_eq!(
+            sum.process_add(Some(AttributeValue::Long(5))),
+            Some(AttributeValue::Long(5))
+        );
+        assert_eq!(
+            sum.process_add(Some(AttributeValue::Long(3))),
+            Some(AttributeValue::Long(8))
+        );
+        assert_eq!(
+            sum.process_remove(Some(AttributeValue::Long(5))),
+            Some(AttributeValue::Long(3))
+        );
+        sum.reset();
+        assert_eq!(
+            sum.process_add(Some(AttributeValue::Long(1))),
+            Some(AttributeValue::Long(1))
+        );
+    }
+
+    #[test]
+    fn test_mappers() {
+        let context = EventFluxContext::new();
+        unsafe { register_extension(&context, &EXTENSION) }.unwrap();
+        let config = std::collections::HashMap::new();
+
+        let source = context
+            .get_source_mapper_factory("perByte")
+            .unwrap()
+            .create_initialized(&config)
+            .unwrap();
+        let events = source.map(&[1, 2]).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].data, vec![AttributeValue::Int(2)]);
+
+        let sink = context
+            .get_sink_mapper_factory("shape")
+            .unwrap()
+            .create_initialized(&config)
+            .unwrap();
+        let events = vec![
+            Event::new_with_data(0, vec![AttributeValue::Int(1), AttributeValue::Null]),
+            Event::new_with_data(0, vec![AttributeValue::Int(2), AttributeValue::Null]),
+        ];
+        assert_eq!(sink.map(&events).unwrap(), b"2x2".to_vec());
+    }
+}
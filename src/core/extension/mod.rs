@@ -23,6 +23,9 @@
 //!
 //! All built-in extensions are registered in `EventFluxContext::register_default_extensions()`.
 //! Custom extensions can be registered via `EventFluxContext::add_*_factory()` methods.
+//! `EventFluxManager::set_extension` loads them from native libraries built
+//! against the stable C ABI (see [`abi`]) or, with the `wasm` feature, from
+//! sandboxed `.wasm` plugins (see [`wasm`]).
 //!
 //! # Example: Adding a Custom Aggregator
 //!
@@ -44,8 +47,12 @@
 //! context.add_attribute_aggregator_factory("median".to_string(), Box::new(MedianAggregatorFactory));
 //! ```
 
+pub mod abi;
 pub mod example_factories;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
 
@@ -68,8 +75,10 @@ pub use crate::core::stream::mapper::factory::{
 use crate::core::config::{
     eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
 };
+use crate::core::event::value::AttributeValue;
 use crate::core::query::processor::Processor;
 use crate::core::query::selector::attribute::aggregator::AttributeAggregatorExecutor;
+use crate::core::query::selector::order_by_event_comparator::compare_attr_values;
 use crate::query_api::definition::attribute::Type as ApiAttributeType;
 use crate::query_api::execution::query::input::handler::WindowHandler;
 
@@ -136,7 +145,7 @@ impl Clone for Box<dyn AttributeAggregatorFactory> {
 ///
 /// ```sql
 /// FROM PATTERN (e1=FailedLogin{3,5} -> e2=AccountLocked)
-/// SELECT count(e1), sum(e1.responseTime), avg(e1.responseTime)
+/// SELECT count(e1), avg(e1.responseTime), distinct(e1.ip), first(e1.user)
 /// ```
 ///
 /// # Implementation
@@ -147,16 +156,16 @@ impl Clone for Box<dyn AttributeAggregatorFactory> {
 ///
 /// impl CollectionAggregationFunction for MedianFunction {
 ///     fn name(&self) -> &'static str { "median" }
-///     fn aggregate(&self, values: &[f64]) -> Option<f64> {
-///         if values.is_empty() { return None; }
-///         let mut sorted = values.to_vec();
-///         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+///     fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+///         let mut sorted: Vec<f64> = values.iter().filter_map(numeric_value).collect();
+///         if sorted.is_empty() { return None; }
+///         sorted.sort_by(f64::total_cmp);
 ///         let mid = sorted.len() / 2;
-///         Some(if sorted.len() % 2 == 0 {
+///         Some(AttributeValue::Double(if sorted.len() % 2 == 0 {
 ///             (sorted[mid - 1] + sorted[mid]) / 2.0
 ///         } else {
 ///             sorted[mid]
-///         })
+///         }))
 ///     }
 ///     fn return_type(&self, _: ApiAttributeType) -> ApiAttributeType {
 ///         ApiAttributeType::DOUBLE
@@ -170,11 +179,12 @@ pub trait CollectionAggregationFunction: Debug + Send + Sync {
     /// Unique name for this aggregation function (e.g., "sum", "avg", "median")
     fn name(&self) -> &'static str;
 
-    /// Aggregate over a slice of numeric values.
+    /// Aggregate over the attribute values of every event in the collection,
+    /// in match order. Events without the attribute contribute `Null`.
     ///
     /// Returns `None` if the slice is empty or if no valid values exist.
     /// This matches SQL NULL semantics for aggregations over empty sets.
-    fn aggregate(&self, values: &[f64]) -> Option<f64>;
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue>;
 
     /// Whether this function supports count-only mode (no attribute needed).
     ///
@@ -188,7 +198,8 @@ pub trait CollectionAggregationFunction: Debug + Send + Sync {
     ///
     /// - `avg` always returns DOUBLE
     /// - `sum` preserves the input type (INT → LONG, FLOAT → DOUBLE)
-    /// - `min`/`max` preserve the input type
+    /// - `min`/`max`/`first`/`last` preserve the input type
+    /// - `collect`/`distinct` return OBJECT (a `Vec<AttributeValue>`)
     fn return_type(&self, input_type: ApiAttributeType) -> ApiAttributeType;
 
     /// Clone this function for registry storage.
@@ -206,6 +217,36 @@ impl Clone for Box<dyn CollectionAggregationFunction> {
     }
 }
 
+/// Numeric view of a value for collection aggregations; `None` for nulls and
+/// non-numeric types
+pub fn numeric_value(value: &AttributeValue) -> Option<f64> {
+    match value {
+        AttributeValue::Int(i) => Some(*i as f64),
+        AttributeValue::Long(l) => Some(*l as f64),
+        AttributeValue::Float(f) => Some(*f as f64),
+        AttributeValue::Double(d) => Some(*d),
+        _ => None,
+    }
+}
+
+/// Ordering used by collection `min`/`max`: numbers by value across numeric
+/// types, strings lexicographically, `false < true`
+fn compare_collection_values(a: &AttributeValue, b: &AttributeValue) -> Ordering {
+    match (numeric_value(a), numeric_value(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        _ => compare_attr_values(a, b),
+    }
+}
+
+/// Wrap a list of values the way multi-valued results are represented
+fn array_value(values: Vec<AttributeValue>) -> AttributeValue {
+    AttributeValue::Object(Some(Box::new(values)))
+}
+
+fn non_null(values: &[AttributeValue]) -> impl Iterator<Item = &AttributeValue> {
+    values.iter().filter(|v| !matches!(v, AttributeValue::Null))
+}
+
 // ============================================================================
 // Built-in Collection Aggregation Functions
 // ============================================================================
@@ -219,8 +260,8 @@ impl CollectionAggregationFunction for CollectionCountFunction {
         "count"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
-        Some(values.len() as f64)
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        Some(AttributeValue::Long(values.len() as i64))
     }
 
     fn supports_count_only(&self) -> bool {
@@ -249,11 +290,31 @@ impl CollectionAggregationFunction for CollectionSumFunction {
         "sum"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
-        if values.is_empty() {
-            None
-        } else {
-            Some(values.iter().sum())
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        let mut int_sum: i128 = 0;
+        let mut float_sum = 0.0;
+        let mut integral = true;
+        let mut has_value = false;
+        for value in values {
+            match value {
+                AttributeValue::Int(i) => int_sum += *i as i128,
+                AttributeValue::Long(l) => int_sum += *l as i128,
+                other => match numeric_value(other) {
+                    Some(f) => {
+                        float_sum += f;
+                        integral = false;
+                    }
+                    None => continue,
+                },
+            }
+            has_value = true;
+        }
+        if !has_value {
+            return None;
+        }
+        match i64::try_from(int_sum) {
+            Ok(sum) if integral => Some(AttributeValue::Long(sum)),
+            _ => Some(AttributeValue::Double(int_sum as f64 + float_sum)),
         }
     }
 
@@ -282,12 +343,12 @@ impl CollectionAggregationFunction for CollectionAvgFunction {
         "avg"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
-        if values.is_empty() {
-            None
-        } else {
-            Some(values.iter().sum::<f64>() / values.len() as f64)
-        }
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        let (sum, n) = values
+            .iter()
+            .filter_map(numeric_value)
+            .fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+        (n > 0).then(|| AttributeValue::Double(sum / n as f64))
     }
 
     fn return_type(&self, _input_type: ApiAttributeType) -> ApiAttributeType {
@@ -312,8 +373,10 @@ impl CollectionAggregationFunction for CollectionMinFunction {
         "min"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
-        values.iter().copied().reduce(f64::min)
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        non_null(values)
+            .min_by(|a, b| compare_collection_values(a, b))
+            .cloned()
     }
 
     fn return_type(&self, input_type: ApiAttributeType) -> ApiAttributeType {
@@ -325,7 +388,7 @@ impl CollectionAggregationFunction for CollectionMinFunction {
     }
 
     fn description(&self) -> &str {
-        "Finds minimum attribute value in a pattern collection"
+        "Finds minimum attribute value (numeric, string or boolean) in a pattern collection"
     }
 }
 
@@ -338,8 +401,10 @@ impl CollectionAggregationFunction for CollectionMaxFunction {
         "max"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
-        values.iter().copied().reduce(f64::max)
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        non_null(values)
+            .max_by(|a, b| compare_collection_values(a, b))
+            .cloned()
     }
 
     fn return_type(&self, input_type: ApiAttributeType) -> ApiAttributeType {
@@ -351,7 +416,7 @@ impl CollectionAggregationFunction for CollectionMaxFunction {
     }
 
     fn description(&self) -> &str {
-        "Finds maximum attribute value in a pattern collection"
+        "Finds maximum attribute value (numeric, string or boolean) in a pattern collection"
     }
 }
 
@@ -364,14 +429,15 @@ impl CollectionAggregationFunction for CollectionStdDevFunction {
         "stdDev"
     }
 
-    fn aggregate(&self, values: &[f64]) -> Option<f64> {
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        let values: Vec<f64> = values.iter().filter_map(numeric_value).collect();
         if values.is_empty() {
             return None;
         }
         let n = values.len() as f64;
         let mean: f64 = values.iter().sum::<f64>() / n;
         let sum_sq_diff: f64 = values.iter().map(|x| (x - mean).powi(2)).sum();
-        Some((sum_sq_diff / n).sqrt())
+        Some(AttributeValue::Double((sum_sq_diff / n).sqrt()))
     }
 
     fn return_type(&self, _input_type: ApiAttributeType) -> ApiAttributeType {
@@ -387,6 +453,148 @@ impl CollectionAggregationFunction for CollectionStdDevFunction {
     }
 }
 
+/// Collection first function: `first(e1.user)` - first non-null value
+#[derive(Debug, Clone)]
+pub struct CollectionFirstFunction;
+
+impl CollectionAggregationFunction for CollectionFirstFunction {
+    fn name(&self) -> &'static str {
+        "first"
+    }
+
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        non_null(values).next().cloned()
+    }
+
+    fn return_type(&self, input_type: ApiAttributeType) -> ApiAttributeType {
+        input_type
+    }
+
+    fn clone_box(&self) -> Box<dyn CollectionAggregationFunction> {
+        Box::new(self.clone())
+    }
+
+    fn description(&self) -> &str {
+        "Returns the first non-null attribute value in a pattern collection"
+    }
+}
+
+/// Collection last function: `last(e1.user)` - last non-null value
+#[derive(Debug, Clone)]
+pub struct CollectionLastFunction;
+
+impl CollectionAggregationFunction for CollectionLastFunction {
+    fn name(&self) -> &'static str {
+        "last"
+    }
+
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        non_null(values).last().cloned()
+    }
+
+    fn return_type(&self, input_type: ApiAttributeType) -> ApiAttributeType {
+        input_type
+    }
+
+    fn clone_box(&self) -> Box<dyn CollectionAggregationFunction> {
+        Box::new(self.clone())
+    }
+
+    fn description(&self) -> &str {
+        "Returns the last non-null attribute value in a pattern collection"
+    }
+}
+
+/// Collection collect function: `collect(e1.symbol)` - all values as an array
+#[derive(Debug, Clone)]
+pub struct CollectionCollectFunction;
+
+impl CollectionAggregationFunction for CollectionCollectFunction {
+    fn name(&self) -> &'static str {
+        "collect"
+    }
+
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        Some(array_value(values.to_vec()))
+    }
+
+    fn return_type(&self, _input_type: ApiAttributeType) -> ApiAttributeType {
+        ApiAttributeType::OBJECT
+    }
+
+    fn clone_box(&self) -> Box<dyn CollectionAggregationFunction> {
+        Box::new(self.clone())
+    }
+
+    fn description(&self) -> &str {
+        "Collects attribute values (including nulls) in match order into an array"
+    }
+}
+
+/// Collection distinct function: `distinct(e1.ip)` - unique values as an array
+#[derive(Debug, Clone)]
+pub struct CollectionDistinctFunction;
+
+impl CollectionAggregationFunction for CollectionDistinctFunction {
+    fn name(&self) -> &'static str {
+        "distinct"
+    }
+
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        let mut unique: Vec<AttributeValue> = Vec::new();
+        for value in non_null(values) {
+            if !unique.contains(value) {
+                unique.push(value.clone());
+            }
+        }
+        Some(array_value(unique))
+    }
+
+    fn return_type(&self, _input_type: ApiAttributeType) -> ApiAttributeType {
+        ApiAttributeType::OBJECT
+    }
+
+    fn clone_box(&self) -> Box<dyn CollectionAggregationFunction> {
+        Box::new(self.clone())
+    }
+
+    fn description(&self) -> &str {
+        "Collects the unique non-null attribute values, in first-seen order, into an array"
+    }
+}
+
+/// Collection distinct count function: `distinctCount(e1.ip)`
+#[derive(Debug, Clone)]
+pub struct CollectionDistinctCountFunction;
+
+impl CollectionAggregationFunction for CollectionDistinctCountFunction {
+    fn name(&self) -> &'static str {
+        "distinctCount"
+    }
+
+    fn aggregate(&self, values: &[AttributeValue]) -> Option<AttributeValue> {
+        let mut unique: Vec<&AttributeValue> = Vec::new();
+        for value in non_null(values) {
+            if !unique.contains(&value) {
+                unique.push(value);
+            }
+        }
+        Some(AttributeValue::Long(unique.len() as i64))
+    }
+
+    fn return_type(&self, _input_type: ApiAttributeType) -> ApiAttributeType {
+        ApiAttributeType::LONG
+    }
+
+    fn clone_box(&self) -> Box<dyn CollectionAggregationFunction> {
+        Box::new(self.clone())
+    }
+
+    fn description(&self) -> &str {
+        "Counts unique non-null attribute values in a pattern collection"
+    }
+}
+
 pub trait SourceFactory: Debug + Send + Sync {
     fn name(&self) -> &'static str;
 
@@ -501,6 +709,18 @@ pub trait TableFactory: Debug + Send + Sync {
         properties: std::collections::HashMap<String, String>,
         ctx: Arc<crate::core::config::eventflux_context::EventFluxContext>,
     ) -> Result<Arc<dyn crate::core::table::Table>, String>;
+
+    /// Create a table from its full definition so keys, indexes and column
+    /// types can be honoured. Factories that only need properties can rely
+    /// on the default, which delegates to [`TableFactory::create`].
+    fn create_for_definition(
+        &self,
+        definition: &crate::query_api::definition::TableDefinition,
+        properties: std::collections::HashMap<String, String>,
+        ctx: Arc<crate::core::config::eventflux_context::EventFluxContext>,
+    ) -> Result<Arc<dyn crate::core::table::Table>, String> {
+        self.create(definition.abstract_definition.id.clone(), properties, ctx)
+    }
     fn clone_box(&self) -> Box<dyn TableFactory>;
 }
 impl Clone for Box<dyn TableFactory> {
@@ -610,6 +830,10 @@ impl SinkFactory for LogSinkFactory {
 }
 
 /// FFI callback type used when dynamically loading extensions.
+///
+/// These legacy entry points pass Rust types across the library boundary and
+/// only work when the library was built with the same compiler and EventFlux
+/// version as the host; new extensions should use the [`abi`] entry points.
 pub type RegisterFn = unsafe extern "C" fn(&crate::core::eventflux_manager::EventFluxManager);
 
 /// Symbol names looked up by [`EventFluxManager::set_extension`].
@@ -833,6 +1057,29 @@ mod tests {
         assert!(names.contains(&"min".to_string()));
         assert!(names.contains(&"max".to_string()));
         assert!(names.contains(&"stdDev".to_string()));
+        assert!(names.contains(&"first".to_string()));
+        assert!(names.contains(&"last".to_string()));
+        assert!(names.contains(&"collect".to_string()));
+        assert!(names.contains(&"distinct".to_string()));
+        assert!(names.contains(&"distinctCount".to_string()));
+    }
+
+    fn doubles(values: &[f64]) -> Vec<AttributeValue> {
+        values.iter().map(|v| AttributeValue::Double(*v)).collect()
+    }
+
+    fn strings(values: &[&str]) -> Vec<AttributeValue> {
+        values
+            .iter()
+            .map(|v| AttributeValue::String(v.to_string()))
+            .collect()
+    }
+
+    fn as_array(value: Option<AttributeValue>) -> Vec<AttributeValue> {
+        match value {
+            Some(AttributeValue::Object(Some(b))) => *b.downcast::<Vec<AttributeValue>>().unwrap(),
+            other => panic!("expected array, got {other:?}"),
+        }
     }
 
     #[test]
@@ -844,19 +1091,28 @@ mod tests {
         assert!(sum_fn.is_some());
         let sum_fn = sum_fn.unwrap();
         assert_eq!(sum_fn.name(), "sum");
-        assert_eq!(sum_fn.aggregate(&[1.0, 2.0, 3.0, 4.0]), Some(10.0));
+        assert_eq!(
+            sum_fn.aggregate(&doubles(&[1.0, 2.0, 3.0, 4.0])),
+            Some(AttributeValue::Double(10.0))
+        );
 
         let avg_fn = context.get_collection_aggregation_function("avg");
         assert!(avg_fn.is_some());
         let avg_fn = avg_fn.unwrap();
         assert_eq!(avg_fn.name(), "avg");
-        assert_eq!(avg_fn.aggregate(&[1.0, 2.0, 3.0, 4.0]), Some(2.5));
+        assert_eq!(
+            avg_fn.aggregate(&doubles(&[1.0, 2.0, 3.0, 4.0])),
+            Some(AttributeValue::Double(2.5))
+        );
 
         let count_fn = context.get_collection_aggregation_function("count");
         assert!(count_fn.is_some());
         let count_fn = count_fn.unwrap();
         assert!(count_fn.supports_count_only());
-        assert_eq!(count_fn.aggregate(&[1.0, 2.0, 3.0]), Some(3.0));
+        assert_eq!(
+            count_fn.aggregate(&doubles(&[1.0, 2.0, 3.0])),
+            Some(AttributeValue::Long(3))
+        );
 
         // Test non-existent function returns None
         let unknown = context.get_collection_aggregation_function("unknown");
@@ -878,16 +1134,80 @@ mod tests {
         let count_fn = context
             .get_collection_aggregation_function("count")
             .unwrap();
-        assert_eq!(count_fn.aggregate(&[]), Some(0.0));
+        assert_eq!(count_fn.aggregate(&[]), Some(AttributeValue::Long(0)));
 
         // Test min/max
         let min_fn = context.get_collection_aggregation_function("min").unwrap();
-        assert_eq!(min_fn.aggregate(&[5.0, 2.0, 8.0, 1.0]), Some(1.0));
+        assert_eq!(
+            min_fn.aggregate(&doubles(&[5.0, 2.0, 8.0, 1.0])),
+            Some(AttributeValue::Double(1.0))
+        );
         assert_eq!(min_fn.aggregate(&[]), None);
 
         let max_fn = context.get_collection_aggregation_function("max").unwrap();
-        assert_eq!(max_fn.aggregate(&[5.0, 2.0, 8.0, 1.0]), Some(8.0));
+        assert_eq!(
+            max_fn.aggregate(&doubles(&[5.0, 2.0, 8.0, 1.0])),
+            Some(AttributeValue::Double(8.0))
+        );
         assert_eq!(max_fn.aggregate(&[]), None);
+
+        // Integer sums stay integral
+        assert_eq!(
+            sum_fn.aggregate(&[
+                AttributeValue::Int(2),
+                AttributeValue::Null,
+                AttributeValue::Long(3)
+            ]),
+            Some(AttributeValue::Long(5))
+        );
+    }
+
+    #[test]
+    fn test_collection_aggregation_typed_values() {
+        let context = EventFluxContext::new();
+        let get = |name: &str| context.get_collection_aggregation_function(name).unwrap();
+        let mut users = strings(&["bob", "alice", "carol", "alice"]);
+        users.insert(0, AttributeValue::Null);
+
+        assert_eq!(
+            get("min").aggregate(&users),
+            Some(AttributeValue::String("alice".into()))
+        );
+        assert_eq!(
+            get("max").aggregate(&users),
+            Some(AttributeValue::String("carol".into()))
+        );
+        assert_eq!(
+            get("first").aggregate(&users),
+            Some(AttributeValue::String("bob".into()))
+        );
+        assert_eq!(
+            get("last").aggregate(&users),
+            Some(AttributeValue::String("alice".into()))
+        );
+        assert_eq!(
+            get("distinctCount").aggregate(&users),
+            Some(AttributeValue::Long(3))
+        );
+        assert_eq!(
+            as_array(get("distinct").aggregate(&users)),
+            strings(&["bob", "alice", "carol"])
+        );
+        assert_eq!(as_array(get("collect").aggregate(&users)).len(), 5);
+        assert_eq!(
+            get("collect").return_type(ApiAttributeType::STRING),
+            ApiAttributeType::OBJECT
+        );
+
+        let flags = [AttributeValue::Bool(true), AttributeValue::Bool(false)];
+        assert_eq!(
+            get("min").aggregate(&flags),
+            Some(AttributeValue::Bool(false))
+        );
+        // Mixed numeric types compare by value
+        let mixed = [AttributeValue::Int(7), AttributeValue::Double(6.5)];
+        assert_eq!(get("max").aggregate(&mixed), Some(AttributeValue::Int(7)));
+        assert_eq!(get("first").aggregate(&[AttributeValue::Null]), None);
     }
 
     #[test]
@@ -906,6 +1226,12 @@ mod tests {
         assert!(names.contains(&"sort".to_string()));
         assert!(names.contains(&"lossyCounting".to_string()));
         assert!(names.contains(&"cron".to_string()));
+        assert!(names.contains(&"unique".to_string()));
+        assert!(names.contains(&"firstUnique".to_string()));
+        assert!(names.contains(&"delay".to_string()));
+        assert!(names.contains(&"frequent".to_string()));
+        assert!(names.contains(&"expression".to_string()));
+        assert!(names.contains(&"timeLength".to_string()));
     }
 
     #[test]
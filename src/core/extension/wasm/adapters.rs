@@ -0,0 +1,567 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Adapters exposing plugin exports through the regular extension traits
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+
+use super::plugin::{AggregatorExport, AggregatorRequest, WasmPlugin, WasmRequest};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::event::value::AttributeValue;
+use crate::core::event::Event;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
+use crate::core::extension::{AttributeAggregatorFactory, SinkMapperFactory, SourceMapperFactory};
+use crate::core::persistence::{
+    AccessPattern, ChangeLog, CheckpointId, CompressionType, SchemaVersion, SerializationHints,
+    StateError, StateHolder, StateMetadata, StateSize, StateSnapshot,
+};
+use crate::core::query::processor::ProcessingMode;
+use crate::core::query::selector::attribute::aggregator::{
+    AttributeAggregatorExecutor, AttributeAggregatorExpressionExecutor,
+};
+use crate::core::stream::mapper::{SinkMapper, SourceMapper};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+
+/// Factory traits name extensions with `&'static str`; plugin names live for
+/// the rest of the process once loaded, like native extension libraries.
+fn leak_name(name: &str) -> &'static str {
+    Box::leak(name.to_string().into_boxed_str())
+}
+
+fn mapping_failed(message: String) -> EventFluxError {
+    EventFluxError::MappingFailed {
+        message,
+        source: None,
+    }
+}
+
+// ============================================================================
+// Scalar functions
+// ============================================================================
+
+/// Scalar function implemented by a plugin's `call` request
+pub struct WasmScalarFunction {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+    return_type: ApiAttributeType,
+    args: Vec<Box<dyn ExpressionExecutor>>,
+}
+
+impl std::fmt::Debug for WasmScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmScalarFunction")
+            .field("plugin", &self.plugin.name())
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl WasmScalarFunction {
+    pub fn new(plugin: Arc<WasmPlugin>, name: &str, return_type: ApiAttributeType) -> Self {
+        Self {
+            plugin,
+            name: leak_name(name),
+            return_type,
+            args: Vec::new(),
+        }
+    }
+}
+
+impl ExpressionExecutor for WasmScalarFunction {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let args: Vec<AttributeValue> = self
+            .args
+            .iter()
+            .map(|e| e.execute(event).unwrap_or(AttributeValue::Null))
+            .collect();
+        let request = WasmRequest::Call {
+            name: self.name,
+            args: &args,
+        };
+        match self.plugin.invoke(&request) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::warn!("{e}");
+                Some(AttributeValue::Null)
+            }
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(Self {
+            plugin: Arc::clone(&self.plugin),
+            name: self.name,
+            return_type: self.return_type,
+            args: self.args.iter().map(|e| e.clone_executor(ctx)).collect(),
+        })
+    }
+}
+
+impl ScalarFunctionExecutor for WasmScalarFunction {
+    fn init(
+        &mut self,
+        args: &Vec<Box<dyn ExpressionExecutor>>,
+        ctx: &Arc<EventFluxAppContext>,
+    ) -> Result<(), String> {
+        self.args = args.iter().map(|e| e.clone_executor(ctx)).collect();
+        Ok(())
+    }
+
+    fn destroy(&mut self) {
+        self.args.clear();
+    }
+
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn clone_scalar_function(&self) -> Box<dyn ScalarFunctionExecutor> {
+        Box::new(Self {
+            plugin: Arc::clone(&self.plugin),
+            name: self.name,
+            return_type: self.return_type,
+            args: Vec::new(),
+        })
+    }
+}
+
+// ============================================================================
+// Aggregators
+// ============================================================================
+
+/// Aggregator implemented by a plugin's aggregator exports
+#[derive(Debug, Clone)]
+pub struct WasmAggregatorFactory {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+    return_type: ApiAttributeType,
+}
+
+impl WasmAggregatorFactory {
+    pub fn new(plugin: Arc<WasmPlugin>, name: &str, return_type: ApiAttributeType) -> Self {
+        Self {
+            plugin,
+            name: leak_name(name),
+            return_type,
+        }
+    }
+}
+
+impl AttributeAggregatorFactory for WasmAggregatorFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(WasmAggregatorExecutor::new(
+            Arc::clone(&self.plugin),
+            self.name,
+            self.return_type,
+        ))
+    }
+
+    fn return_type(&self, _arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        Ok(self.return_type)
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(self.clone())
+    }
+}
+
+/// Guest-side aggregator state, addressed by a host-assigned id
+#[derive(Debug, Clone)]
+struct WasmAggregatorState {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+    id: u64,
+}
+
+impl WasmAggregatorState {
+    fn new(plugin: Arc<WasmPlugin>, name: &'static str) -> Self {
+        let id = plugin.next_aggregator_id();
+        Self { plugin, name, id }
+    }
+
+    fn send<T: DeserializeOwned>(
+        &self,
+        export: AggregatorExport,
+        value: Option<&AttributeValue>,
+        state: Option<&[u8]>,
+    ) -> Result<T, String> {
+        let request = AggregatorRequest {
+            name: self.name,
+            id: self.id,
+            value,
+            state,
+        };
+        self.plugin.call_aggregator(export, &request)
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>, String> {
+        self.send(AggregatorExport::Snapshot, None, None)
+    }
+
+    fn restore(&self, state: &[u8]) -> Result<(), String> {
+        self.send(AggregatorExport::Restore, None, Some(state))
+    }
+}
+
+/// Applies each change to the aggregator state kept in the plugin, which
+/// answers with the new aggregate
+pub struct WasmAggregatorExecutor {
+    return_type: ApiAttributeType,
+    arg_exec: Option<Box<dyn ExpressionExecutor>>,
+    state: WasmAggregatorState,
+    app_ctx: Option<Arc<EventFluxAppContext>>,
+}
+
+impl std::fmt::Debug for WasmAggregatorExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmAggregatorExecutor")
+            .field("plugin", &self.state.plugin.name())
+            .field("name", &self.state.name)
+            .field("id", &self.state.id)
+            .finish()
+    }
+}
+
+impl WasmAggregatorExecutor {
+    fn new(plugin: Arc<WasmPlugin>, name: &'static str, return_type: ApiAttributeType) -> Self {
+        Self {
+            return_type,
+            arg_exec: None,
+            state: WasmAggregatorState::new(plugin, name),
+            app_ctx: None,
+        }
+    }
+
+    fn update(
+        &self,
+        export: AggregatorExport,
+        data: Option<AttributeValue>,
+    ) -> Option<AttributeValue> {
+        let value = data.unwrap_or(AttributeValue::Null);
+        match self.state.send(export, Some(&value), None) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::warn!("{e}");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for WasmAggregatorExecutor {
+    fn drop(&mut self) {
+        if let Err(e) = self.state.send::<()>(AggregatorExport::Release, None, None) {
+            log::debug!("{e}");
+        }
+    }
+}
+
+impl AttributeAggregatorExecutor for WasmAggregatorExecutor {
+    fn init(
+        &mut self,
+        mut e: Vec<Box<dyn ExpressionExecutor>>,
+        _m: ProcessingMode,
+        _ex: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        if e.len() != 1 {
+            return Err(format!(
+                "{} aggregator requires exactly one argument",
+                self.state.name
+            ));
+        }
+        self.arg_exec = Some(e.remove(0));
+        self.app_ctx = Some(Arc::clone(&ctx.eventflux_app_context));
+
+        let component_id = format!(
+            "{}_aggregator_{}",
+            self.state.name.to_lowercase(),
+            ctx.next_aggregator_id()
+        );
+        let holder = WasmAggregatorStateHolder {
+            state: self.state.clone(),
+            component_id: component_id.clone(),
+        };
+        ctx.register_state_holder(component_id, Arc::new(Mutex::new(holder)));
+        Ok(())
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.update(AggregatorExport::Add, data)
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.update(AggregatorExport::Remove, data)
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        if let Err(e) = self
+            .state
+            .send::<AttributeValue>(AggregatorExport::Reset, None, None)
+        {
+            log::warn!("{e}");
+        }
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = self.app_ctx.as_ref().unwrap();
+        let state = WasmAggregatorState::new(Arc::clone(&self.state.plugin), self.state.name);
+        if let Err(e) = self
+            .state
+            .snapshot()
+            .and_then(|snapshot| state.restore(&snapshot))
+        {
+            log::warn!("{e}");
+        }
+        Box::new(WasmAggregatorExecutor {
+            return_type: self.return_type,
+            arg_exec: self.arg_exec.as_ref().map(|e| e.clone_executor(ctx)),
+            state,
+            app_ctx: Some(Arc::clone(ctx)),
+        })
+    }
+}
+
+impl ExpressionExecutor for WasmAggregatorExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let event = event?;
+        let data = self.arg_exec.as_ref().and_then(|e| e.execute(Some(event)));
+        match event.get_event_type() {
+            ComplexEventType::Current => self.process_add(data),
+            ComplexEventType::Expired => self.process_remove(data),
+            ComplexEventType::Reset => self.reset(),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
+/// Persists an aggregator's guest-side state through the plugin's
+/// `eventflux_agg_snapshot` and `eventflux_agg_restore` exports
+#[derive(Debug)]
+struct WasmAggregatorStateHolder {
+    state: WasmAggregatorState,
+    component_id: String,
+}
+
+impl StateHolder for WasmAggregatorStateHolder {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion::new(1, 0, 0)
+    }
+
+    fn serialize_state(&self, _hints: &SerializationHints) -> Result<StateSnapshot, StateError> {
+        let data = self
+            .state
+            .snapshot()
+            .map_err(|message| StateError::SerializationError { message })?;
+        let checksum = StateSnapshot::calculate_checksum(&data);
+        Ok(StateSnapshot {
+            version: self.schema_version(),
+            checkpoint_id: 0,
+            data,
+            compression: CompressionType::None,
+            checksum,
+            metadata: self.component_metadata(),
+        })
+    }
+
+    fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+        if !snapshot.verify_integrity() {
+            return Err(StateError::ChecksumMismatch);
+        }
+        self.state
+            .restore(&snapshot.data)
+            .map_err(|message| StateError::DeserializationError { message })
+    }
+
+    fn get_changelog(&self, _since: CheckpointId) -> Result<ChangeLog, StateError> {
+        Err(StateError::SerializationError {
+            message: format!(
+                "wasm aggregator {} does not support incremental checkpointing",
+                self.state.name
+            ),
+        })
+    }
+
+    fn apply_changelog(&self, _changes: &ChangeLog) -> Result<(), StateError> {
+        Err(StateError::DeserializationError {
+            message: format!(
+                "wasm aggregator {} does not support incremental changes",
+                self.state.name
+            ),
+        })
+    }
+
+    fn estimate_size(&self) -> StateSize {
+        StateSize {
+            bytes: 0,
+            entries: 1,
+            estimated_growth_rate: 0.0,
+        }
+    }
+
+    fn access_pattern(&self) -> AccessPattern {
+        AccessPattern::Hot
+    }
+
+    fn component_metadata(&self) -> StateMetadata {
+        StateMetadata::new(self.component_id.clone(), "WasmAggregator".to_string())
+    }
+}
+
+// ============================================================================
+// Mappers
+// ============================================================================
+
+/// Source mapper implemented by a plugin's `map_source` request
+#[derive(Debug, Clone)]
+pub struct WasmSourceMapperFactory {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+}
+
+impl WasmSourceMapperFactory {
+    pub fn new(plugin: Arc<WasmPlugin>, name: &str) -> Self {
+        Self {
+            plugin,
+            name: leak_name(name),
+        }
+    }
+}
+
+impl SourceMapperFactory for WasmSourceMapperFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn SourceMapper>, EventFluxError> {
+        Ok(Box::new(WasmSourceMapper {
+            plugin: Arc::clone(&self.plugin),
+            name: self.name,
+            config: config.clone(),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SourceMapperFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WasmSourceMapper {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+    config: HashMap<String, String>,
+}
+
+impl SourceMapper for WasmSourceMapper {
+    fn map(&self, input: &[u8]) -> Result<Vec<Event>, EventFluxError> {
+        let request = WasmRequest::MapSource {
+            name: self.name,
+            config: &self.config,
+            input,
+        };
+        let rows: Vec<Vec<AttributeValue>> =
+            self.plugin.invoke(&request).map_err(mapping_failed)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        Ok(rows
+            .into_iter()
+            .map(|data| Event::new_with_data(timestamp, data))
+            .collect())
+    }
+
+    fn clone_box(&self) -> Box<dyn SourceMapper> {
+        Box::new(self.clone())
+    }
+}
+
+/// Sink mapper implemented by a plugin's `map_sink` request
+#[derive(Debug, Clone)]
+pub struct WasmSinkMapperFactory {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+}
+
+impl WasmSinkMapperFactory {
+    pub fn new(plugin: Arc<WasmPlugin>, name: &str) -> Self {
+        Self {
+            plugin,
+            name: leak_name(name),
+        }
+    }
+}
+
+impl SinkMapperFactory for WasmSinkMapperFactory {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn create_initialized(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<Box<dyn SinkMapper>, EventFluxError> {
+        Ok(Box::new(WasmSinkMapper {
+            plugin: Arc::clone(&self.plugin),
+            name: self.name,
+            config: config.clone(),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn SinkMapperFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WasmSinkMapper {
+    plugin: Arc<WasmPlugin>,
+    name: &'static str,
+    config: HashMap<String, String>,
+}
+
+impl SinkMapper for WasmSinkMapper {
+    fn map(&self, events: &[Event]) -> Result<Vec<u8>, EventFluxError> {
+        let request = WasmRequest::MapSink {
+            name: self.name,
+            config: &self.config,
+            events: events.iter().map(|e| e.data.as_slice()).collect(),
+        };
+        self.plugin.invoke(&request).map_err(mapping_failed)
+    }
+
+    fn clone_box(&self) -> Box<dyn SinkMapper> {
+        Box::new(self.clone())
+    }
+}
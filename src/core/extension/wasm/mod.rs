@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! WASM Plugin Host
+//!
+//! Loads extensions from `.wasm` modules instead of native libraries. A plugin
+//! is compiled once against the versioned interface below and runs under any
+//! EventFlux build that supports that version, sandboxed from the host process.
+//!
+//! # Plugin Interface (version 2)
+//!
+//! A plugin is a core WebAssembly module with **no imports** (it gets no WASI,
+//! file, network or clock access) exporting:
+//!
+//! | Export | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `memory` | memory | Linear memory used to exchange messages |
+//! | `eventflux_abi_version` | `() -> i32` | Must return [`WASM_ABI_VERSION`] |
+//! | `eventflux_alloc` | `(len: i32) -> i32` | Allocate `len` bytes for a host message |
+//! | `eventflux_free` | `(ptr: i32, len: i32)` | Optional; release a message buffer |
+//! | `eventflux_manifest` | `() -> i64` | Packed `(ptr << 32) \| len` of the manifest JSON |
+//! | `eventflux_invoke` | `(ptr: i32, len: i32) -> i64` | Handle a request, return packed response |
+//! | `eventflux_agg_*` | `(ptr: i32, len: i32) -> i64` | Aggregator state exports, see below |
+//!
+//! The manifest declares what the plugin provides:
+//!
+//! ```json
+//! {
+//!   "functions":      [{"name": "haversine", "return_type": "DOUBLE"}],
+//!   "aggregators":    [{"name": "median", "return_type": "DOUBLE"}],
+//!   "source_mappers": ["msgpack"],
+//!   "sink_mappers":   ["msgpack"]
+//! }
+//! ```
+//!
+//! Requests and responses are JSON. Values use the serde form of
+//! [`AttributeValue`](crate::core::event::value::AttributeValue)
+//! (`{"Double": 1.5}`, `{"String": "a"}`, `"Null"`); byte buffers are arrays of
+//! numbers. Every response is `{"ok": <result>}` or `{"err": "<message>"}`.
+//!
+//! | Request | Result |
+//! |---------|--------|
+//! | `{"call": {"name", "args": [value]}}` | value |
+//! | `{"map_source": {"name", "config", "input": bytes}}` | `[[value]]`, one row per event |
+//! | `{"map_sink": {"name", "config", "events": [[value]]}}` | bytes |
+//!
+//! Aggregators keep their state in the guest. Each aggregator instance is
+//! addressed by a host-assigned `id`; a plugin declaring aggregators must
+//! export all of the following, each taking `{"name", "id", ...}`:
+//!
+//! | Export | Extra fields | Result |
+//! |--------|--------------|--------|
+//! | `eventflux_agg_add` | `value` | aggregate after adding `value` |
+//! | `eventflux_agg_remove` | `value` | aggregate after removing `value` |
+//! | `eventflux_agg_reset` | | aggregate after clearing the state |
+//! | `eventflux_agg_snapshot` | | the state as bytes |
+//! | `eventflux_agg_restore` | `state`: bytes | `null` |
+//! | `eventflux_agg_release` | | `null`; the id is not used again |
+//!
+//! An id the guest has not seen starts from empty state. Snapshots are
+//! persisted with the app's other state, so the bytes only need to be
+//! readable by the same plugin.
+//!
+//! # Limits
+//!
+//! Every call runs with a fuel budget and the instance's memory is capped (see
+//! [`WasmLimits`]). A call that traps or runs out of fuel fails, and the
+//! instance is discarded and re-created for the next call; aggregator state
+//! held by the discarded instance is lost.
+//!
+//! # Loading
+//!
+//! ```ignore
+//! let manager = EventFluxManager::new();
+//! manager.set_extension("geo", "plugins/geo.wasm".to_string())?;
+//! ```
+
+mod adapters;
+mod plugin;
+
+pub use adapters::{
+    WasmAggregatorExecutor, WasmAggregatorFactory, WasmScalarFunction, WasmSinkMapper,
+    WasmSinkMapperFactory, WasmSourceMapper, WasmSourceMapperFactory,
+};
+pub use plugin::{WasmManifest, WasmPlugin};
+
+use std::sync::Arc;
+
+use crate::core::config::eventflux_context::EventFluxContext;
+
+/// Plugin interface version implemented by this host
+pub const WASM_ABI_VERSION: i32 = 2;
+
+/// Per-plugin resource limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// Fuel (roughly, executed instructions) available to each call
+    pub fuel: u64,
+    /// Maximum linear memory of a plugin instance, in bytes
+    pub max_memory_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            max_memory_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Register everything a plugin's manifest declares in `context`
+pub fn register_plugin(context: &EventFluxContext, plugin: Arc<WasmPlugin>) {
+    let manifest = plugin.manifest().clone();
+    for function in manifest.functions {
+        context.add_scalar_function_factory(
+            function.name.clone(),
+            Box::new(WasmScalarFunction::new(
+                Arc::clone(&plugin),
+                &function.name,
+                function.return_type,
+            )),
+        );
+    }
+    for aggregator in manifest.aggregators {
+        context.add_attribute_aggregator_factory(
+            aggregator.name.clone(),
+            Box::new(WasmAggregatorFactory::new(
+                Arc::clone(&plugin),
+                &aggregator.name,
+                aggregator.return_type,
+            )),
+        );
+    }
+    for name in manifest.source_mappers {
+        context.add_source_mapper_factory(
+            name.clone(),
+            Box::new(WasmSourceMapperFactory::new(Arc::clone(&plugin), &name)),
+        );
+    }
+    for name in manifest.sink_mappers {
+        context.add_sink_mapper_factory(
+            name.clone(),
+            Box::new(WasmSinkMapperFactory::new(Arc::clone(&plugin), &name)),
+        );
+    }
+}
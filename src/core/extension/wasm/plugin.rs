@@ -0,0 +1,544 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Loading, instantiating and calling WASM plugins
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasmtime::{
+    Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+use super::{WasmLimits, WASM_ABI_VERSION};
+use crate::core::event::value::AttributeValue;
+use crate::query_api::definition::attribute::Type as AttributeType;
+
+/// What a plugin provides, read from its `eventflux_manifest` export
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WasmManifest {
+    pub functions: Vec<WasmFunctionSpec>,
+    pub aggregators: Vec<WasmFunctionSpec>,
+    pub source_mappers: Vec<String>,
+    pub sink_mappers: Vec<String>,
+}
+
+/// A named function or aggregator with its declared return type
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasmFunctionSpec {
+    pub name: String,
+    pub return_type: AttributeType,
+}
+
+#[derive(Deserialize)]
+struct RawManifest {
+    #[serde(default)]
+    functions: Vec<RawFunctionSpec>,
+    #[serde(default)]
+    aggregators: Vec<RawFunctionSpec>,
+    #[serde(default)]
+    source_mappers: Vec<String>,
+    #[serde(default)]
+    sink_mappers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawFunctionSpec {
+    name: String,
+    return_type: String,
+}
+
+impl RawFunctionSpec {
+    fn resolve(self) -> Result<WasmFunctionSpec, String> {
+        let return_type = match self.return_type.to_uppercase().as_str() {
+            "STRING" => AttributeType::STRING,
+            "INT" => AttributeType::INT,
+            "LONG" => AttributeType::LONG,
+            "FLOAT" => AttributeType::FLOAT,
+            "DOUBLE" => AttributeType::DOUBLE,
+            "BOOL" => AttributeType::BOOL,
+            "OBJECT" => AttributeType::OBJECT,
+            other => {
+                return Err(format!(
+                    "'{}' declares unknown return type '{other}'",
+                    self.name
+                ))
+            }
+        };
+        Ok(WasmFunctionSpec {
+            name: self.name,
+            return_type,
+        })
+    }
+}
+
+/// Requests handled by the `eventflux_invoke` export
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum WasmRequest<'a> {
+    Call {
+        name: &'a str,
+        args: &'a [AttributeValue],
+    },
+    MapSource {
+        name: &'a str,
+        config: &'a HashMap<String, String>,
+        input: &'a [u8],
+    },
+    MapSink {
+        name: &'a str,
+        config: &'a HashMap<String, String>,
+        events: Vec<&'a [AttributeValue]>,
+    },
+}
+
+/// Exports through which a plugin keeps aggregator state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AggregatorExport {
+    Add,
+    Remove,
+    Reset,
+    Snapshot,
+    Restore,
+    Release,
+}
+
+impl AggregatorExport {
+    const ALL: [AggregatorExport; 6] = [
+        AggregatorExport::Add,
+        AggregatorExport::Remove,
+        AggregatorExport::Reset,
+        AggregatorExport::Snapshot,
+        AggregatorExport::Restore,
+        AggregatorExport::Release,
+    ];
+
+    pub(super) fn export_name(self) -> &'static str {
+        match self {
+            AggregatorExport::Add => "eventflux_agg_add",
+            AggregatorExport::Remove => "eventflux_agg_remove",
+            AggregatorExport::Reset => "eventflux_agg_reset",
+            AggregatorExport::Snapshot => "eventflux_agg_snapshot",
+            AggregatorExport::Restore => "eventflux_agg_restore",
+            AggregatorExport::Release => "eventflux_agg_release",
+        }
+    }
+}
+
+/// Message sent to the aggregator exports
+#[derive(Serialize)]
+pub(super) struct AggregatorRequest<'a> {
+    pub(super) name: &'a str,
+    /// Host-assigned id of the aggregator instance whose state is addressed
+    pub(super) id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) value: Option<&'a AttributeValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) state: Option<&'a [u8]>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WasmResponse<T> {
+    Ok(T),
+    Err(String),
+}
+
+/// A live instance together with its store and interface exports
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    instance: Instance,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    free: Option<TypedFunc<(i32, i32), ()>>,
+    manifest: TypedFunc<(), i64>,
+}
+
+impl WasmInstance {
+    fn new(engine: &Engine, module: &Module, limits: &WasmLimits) -> Result<Self, String> {
+        let mut store = Store::new(
+            engine,
+            StoreLimitsBuilder::new()
+                .memory_size(limits.max_memory_bytes)
+                .instances(1)
+                .build(),
+        );
+        store.limiter(|l| l);
+        store.set_fuel(limits.fuel).map_err(|e| e.to_string())?;
+
+        // An empty linker: plugins importing anything fail to instantiate
+        let instance: Instance = Linker::new(engine)
+            .instantiate(&mut store, module)
+            .map_err(|e| format!("instantiation failed: {e}"))?;
+
+        let version = instance
+            .get_typed_func::<(), i32>(&mut store, "eventflux_abi_version")
+            .map_err(|_| "missing export 'eventflux_abi_version'".to_string())?
+            .call(&mut store, ())
+            .map_err(|e| e.to_string())?;
+        if version != WASM_ABI_VERSION {
+            return Err(format!(
+                "plugin interface version {version} is not supported (host supports {WASM_ABI_VERSION})"
+            ));
+        }
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("missing export 'memory'")?;
+        let missing = |name: &str| format!("missing or mistyped export '{name}'");
+        let alloc = instance
+            .get_typed_func(&mut store, "eventflux_alloc")
+            .map_err(|_| missing("eventflux_alloc"))?;
+        instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "eventflux_invoke")
+            .map_err(|_| missing("eventflux_invoke"))?;
+        let manifest = instance
+            .get_typed_func(&mut store, "eventflux_manifest")
+            .map_err(|_| missing("eventflux_manifest"))?;
+        let free = instance.get_typed_func(&mut store, "eventflux_free").ok();
+
+        Ok(Self {
+            store,
+            instance,
+            memory,
+            alloc,
+            free,
+            manifest,
+        })
+    }
+
+    /// A `(ptr: i32, len: i32) -> i64` message export
+    fn message_export(&mut self, name: &str) -> Result<TypedFunc<(i32, i32), i64>, String> {
+        self.instance
+            .get_typed_func(&mut self.store, name)
+            .map_err(|_| format!("missing or mistyped export '{name}'"))
+    }
+
+    /// Copy a packed `(ptr << 32) | len` buffer out of linear memory
+    fn read_packed(&mut self, packed: i64) -> Result<(i32, Vec<u8>), String> {
+        let ptr = (packed as u64 >> 32) as usize;
+        let len = (packed as u64 & 0xFFFF_FFFF) as usize;
+        let mut buf = vec![0u8; len];
+        self.memory
+            .read(&self.store, ptr, &mut buf)
+            .map_err(|_| format!("response buffer {ptr}+{len} is out of bounds"))?;
+        Ok((ptr as i32, buf))
+    }
+
+    fn release(&mut self, ptr: i32, len: usize) -> Result<(), String> {
+        if let Some(free) = &self.free {
+            free.call(&mut self.store, (ptr, len as i32))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn read_manifest(&mut self) -> Result<Vec<u8>, String> {
+        let packed = self
+            .manifest
+            .call(&mut self.store, ())
+            .map_err(|e| e.to_string())?;
+        Ok(self.read_packed(packed)?.1)
+    }
+
+    fn call(&mut self, export: &str, payload: &[u8], fuel: u64) -> Result<Vec<u8>, String> {
+        let export = self.message_export(export)?;
+        self.store.set_fuel(fuel).map_err(|e| e.to_string())?;
+        let len = i32::try_from(payload.len()).map_err(|_| "request too large".to_string())?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(|e| e.to_string())?;
+        self.memory
+            .write(&mut self.store, ptr as usize, payload)
+            .map_err(|_| "request buffer is out of bounds".to_string())?;
+        let packed = export
+            .call(&mut self.store, (ptr, len))
+            .map_err(|e| e.to_string())?;
+        let (out_ptr, out) = self.read_packed(packed)?;
+        self.release(ptr, payload.len())?;
+        self.release(out_ptr, out.len())?;
+        Ok(out)
+    }
+}
+
+/// A loaded WASM plugin shared by all the extensions it provides
+///
+/// Calls into one plugin are serialized on its single instance.
+pub struct WasmPlugin {
+    name: String,
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+    manifest: WasmManifest,
+    instance: Mutex<Option<WasmInstance>>,
+    next_aggregator_id: AtomicU64,
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPlugin")
+            .field("name", &self.name)
+            .field("limits", &self.limits)
+            .field("manifest", &self.manifest)
+            .finish()
+    }
+}
+
+impl WasmPlugin {
+    /// Load a plugin from a `.wasm` file
+    pub fn from_file(
+        name: &str,
+        path: impl AsRef<Path>,
+        limits: WasmLimits,
+    ) -> Result<Self, String> {
+        let engine = Self::engine()?;
+        let module = Module::from_file(&engine, path.as_ref()).map_err(|e| {
+            format!(
+                "Failed to load wasm plugin '{name}' from {}: {e}",
+                path.as_ref().display()
+            )
+        })?;
+        Self::from_module(name, engine, module, limits)
+    }
+
+    /// Load a plugin from module bytes (binary, or text format)
+    pub fn from_bytes(name: &str, bytes: &[u8], limits: WasmLimits) -> Result<Self, String> {
+        let engine = Self::engine()?;
+        let module = Module::new(&engine, bytes)
+            .map_err(|e| format!("Failed to load wasm plugin '{name}': {e}"))?;
+        Self::from_module(name, engine, module, limits)
+    }
+
+    fn engine() -> Result<Engine, String> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).map_err(|e| e.to_string())
+    }
+
+    fn from_module(
+        name: &str,
+        engine: Engine,
+        module: Module,
+        limits: WasmLimits,
+    ) -> Result<Self, String> {
+        let rejected = |e: String| format!("Rejected wasm plugin '{name}': {e}");
+        let mut instance = WasmInstance::new(&engine, &module, &limits).map_err(rejected)?;
+        let raw: RawManifest = serde_json::from_slice(&instance.read_manifest().map_err(rejected)?)
+            .map_err(|e| rejected(format!("invalid manifest: {e}")))?;
+        let resolve = |specs: Vec<RawFunctionSpec>| {
+            specs
+                .into_iter()
+                .map(RawFunctionSpec::resolve)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(rejected)
+        };
+        let manifest = WasmManifest {
+            functions: resolve(raw.functions)?,
+            aggregators: resolve(raw.aggregators)?,
+            source_mappers: raw.source_mappers,
+            sink_mappers: raw.sink_mappers,
+        };
+        if !manifest.aggregators.is_empty() {
+            for export in AggregatorExport::ALL {
+                instance
+                    .message_export(export.export_name())
+                    .map_err(rejected)?;
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            module,
+            limits,
+            manifest,
+            instance: Mutex::new(Some(instance)),
+            next_aggregator_id: AtomicU64::new(0),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn manifest(&self) -> &WasmManifest {
+        &self.manifest
+    }
+
+    pub fn limits(&self) -> WasmLimits {
+        self.limits
+    }
+
+    /// Id addressing the guest-side state of a new aggregator instance
+    pub(super) fn next_aggregator_id(&self) -> u64 {
+        self.next_aggregator_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Send a request to `eventflux_invoke` and decode the `ok` result
+    pub(super) fn invoke<T: DeserializeOwned>(
+        &self,
+        request: &WasmRequest<'_>,
+    ) -> Result<T, String> {
+        self.call_export("eventflux_invoke", request)
+    }
+
+    /// Send a message to one of the aggregator exports and decode the `ok` result
+    pub(super) fn call_aggregator<T: DeserializeOwned>(
+        &self,
+        export: AggregatorExport,
+        request: &AggregatorRequest<'_>,
+    ) -> Result<T, String> {
+        self.call_export(export.export_name(), request)
+    }
+
+    /// Send a JSON message to a message export and decode the `ok` result
+    ///
+    /// A trap (including running out of fuel or memory) discards the instance
+    /// so the next call starts from a fresh one. Aggregator state held by the
+    /// discarded instance is lost with it.
+    fn call_export<T: DeserializeOwned>(
+        &self,
+        export: &str,
+        message: &impl Serialize,
+    ) -> Result<T, String> {
+        let payload = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+        let mut guard = self.instance.lock().unwrap();
+        if guard.is_none() {
+            *guard = Some(WasmInstance::new(&self.engine, &self.module, &self.limits)?);
+        }
+        let response = match guard
+            .as_mut()
+            .unwrap()
+            .call(export, &payload, self.limits.fuel)
+        {
+            Ok(response) => response,
+            Err(e) => {
+                *guard = None;
+                return Err(format!("wasm plugin '{}' failed: {e}", self.name));
+            }
+        };
+        drop(guard);
+
+        match serde_json::from_slice::<WasmResponse<T>>(&response) {
+            Ok(WasmResponse::Ok(value)) => Ok(value),
+            Ok(WasmResponse::Err(e)) => Err(format!("wasm plugin '{}': {e}", self.name)),
+            Err(e) => Err(format!(
+                "wasm plugin '{}' returned an invalid response: {e}",
+                self.name
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal plugin: answers every request with `{"ok":{"Long":42}}`
+    const ANSWER_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0)
+            "{\"functions\":[{\"name\":\"answer\",\"return_type\":\"LONG\"}],\"aggregators\":[{\"name\":\"answer_agg\",\"return_type\":\"LONG\"}]}")
+          (data (i32.const 256) "{\"ok\":{\"Long\":42}}")
+          (func (export "eventflux_abi_version") (result i32) (i32.const VERSION))
+          (func (export "eventflux_alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "eventflux_manifest") (result i64) (i64.const 113))
+          (func $invoke (export "eventflux_invoke") (param i32 i32) (result i64)
+            INVOKE_BODY)
+          AGGREGATOR_EXPORTS)
+    "#;
+
+    fn plugin(version: i32, invoke_body: &str) -> Result<WasmPlugin, String> {
+        let aggregator_exports = AggregatorExport::ALL
+            .iter()
+            .map(|export| format!("(export \"{}\" (func $invoke))", export.export_name()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        plugin_with_exports(version, invoke_body, &aggregator_exports)
+    }
+
+    fn plugin_with_exports(
+        version: i32,
+        invoke_body: &str,
+        aggregator_exports: &str,
+    ) -> Result<WasmPlugin, String> {
+        let wat = ANSWER_PLUGIN
+            .replace("VERSION", &version.to_string())
+            .replace("INVOKE_BODY", invoke_body)
+            .replace("AGGREGATOR_EXPORTS", aggregator_exports);
+        WasmPlugin::from_bytes("test", wat.as_bytes(), WasmLimits::default())
+    }
+
+    // (256 << 32) | 18
+    const ANSWER: &str = "(i64.const 1099511627794)";
+
+    #[test]
+    fn test_manifest_and_call() {
+        let plugin = plugin(WASM_ABI_VERSION, ANSWER).unwrap();
+        assert_eq!(plugin.manifest().functions[0].name, "answer");
+        assert_eq!(
+            plugin.manifest().aggregators[0].return_type,
+            AttributeType::LONG
+        );
+
+        let value: AttributeValue = plugin
+            .invoke(&WasmRequest::Call {
+                name: "answer",
+                args: &[AttributeValue::Int(1)],
+            })
+            .unwrap();
+        assert_eq!(value, AttributeValue::Long(42));
+    }
+
+    #[test]
+    fn test_aggregator_exports() {
+        let plugin = plugin(WASM_ABI_VERSION, ANSWER).unwrap();
+        let value: AttributeValue = plugin
+            .call_aggregator(
+                AggregatorExport::Add,
+                &AggregatorRequest {
+                    name: "answer_agg",
+                    id: plugin.next_aggregator_id(),
+                    value: Some(&AttributeValue::Int(1)),
+                    state: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(value, AttributeValue::Long(42));
+        assert_eq!(plugin.next_aggregator_id(), 1);
+
+        let err = plugin_with_exports(WASM_ABI_VERSION, ANSWER, "").unwrap_err();
+        assert!(err.contains("eventflux_agg_add"), "{err}");
+    }
+
+    #[test]
+    fn test_incompatible_version_rejected() {
+        let err = plugin(WASM_ABI_VERSION + 1, ANSWER).unwrap_err();
+        assert!(err.contains("not supported"), "{err}");
+    }
+
+    #[test]
+    fn test_imports_rejected() {
+        let wat = r#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#;
+        assert!(WasmPlugin::from_bytes("test", wat.as_bytes(), WasmLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_fuel_exhaustion_fails_call_and_recovers() {
+        let plugin = plugin(WASM_ABI_VERSION, "(loop $l (br $l)) (i64.const 0)").unwrap();
+        let request = WasmRequest::Call {
+            name: "answer",
+            args: &[],
+        };
+        assert!(plugin.invoke::<AttributeValue>(&request).is_err());
+        // The trapped instance is replaced rather than reused
+        assert!(plugin.invoke::<AttributeValue>(&request).is_err());
+        assert!(plugin.instance.lock().unwrap().is_none());
+    }
+}
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+pub mod rhai_script;
 pub mod script;
 
-pub use script::Script;
+pub use rhai_script::RhaiScriptLanguage;
+pub use script::{Script, ScriptLanguage, ScriptLimits};
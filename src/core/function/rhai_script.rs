@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Rhai script functions (`LANGUAGE rhai`)
+//!
+//! Rhai is an embedded interpreter with no file, network or process access.
+//! The engine additionally drops `print`/`debug` output, disables `eval` and
+//! module imports, and enforces [`ScriptLimits`] on every call.
+//!
+//! ```sql
+//! CREATE FUNCTION risk(amount DOUBLE, country STRING) RETURNS DOUBLE
+//! LANGUAGE rhai AS 'if country == "US" { amount * 0.1 } else { amount * 0.3 }';
+//! ```
+
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Dynamic, Engine, Scope, AST};
+
+use super::script::{Script, ScriptLanguage, ScriptLimits};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::event::value::AttributeValue;
+use crate::query_api::definition::attribute::{Attribute, Type as AttributeType};
+use crate::query_api::definition::FunctionDefinition;
+
+pub const RHAI_LANGUAGE: &str = "rhai";
+
+/// Operations between wall-clock checks
+const TIMEOUT_CHECK_INTERVAL: u64 = 1024;
+
+thread_local! {
+    /// Deadline of the script call running on this thread
+    static CALL_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RhaiScriptLanguage {
+    limits: ScriptLimits,
+}
+
+impl RhaiScriptLanguage {
+    pub fn new(limits: ScriptLimits) -> Self {
+        Self { limits }
+    }
+
+    fn engine(&self) -> Engine {
+        let limits = self.limits;
+        let mut engine = Engine::new();
+        engine.set_max_operations(limits.max_operations);
+        engine.set_max_call_levels(limits.max_call_depth);
+        engine.set_max_string_size(limits.max_string_size);
+        engine.set_max_array_size(limits.max_collection_size);
+        engine.set_max_map_size(limits.max_collection_size);
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        engine.on_progress(|operations| {
+            if operations % TIMEOUT_CHECK_INTERVAL != 0 {
+                return None;
+            }
+            let expired = CALL_DEADLINE.with(|d| d.get().is_some_and(|at| Instant::now() > at));
+            expired.then(|| Dynamic::from("script call timed out"))
+        });
+        engine
+    }
+}
+
+impl ScriptLanguage for RhaiScriptLanguage {
+    fn name(&self) -> &'static str {
+        RHAI_LANGUAGE
+    }
+
+    fn compile(&self, definition: &FunctionDefinition) -> Result<Box<dyn Script>, String> {
+        let engine = self.engine();
+        let ast = engine
+            .compile(&definition.body)
+            .map_err(|e| format!("Function '{}' failed to compile: {e}", definition.id))?;
+        Ok(Box::new(RhaiScript {
+            name: definition.id.clone(),
+            parameters: definition.parameters.clone(),
+            return_type: definition.return_type,
+            timeout: self.limits.timeout,
+            engine: Arc::new(engine),
+            ast: Arc::new(ast),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn ScriptLanguage> {
+        Box::new(self.clone())
+    }
+}
+
+/// A compiled Rhai function body; clones share the engine and AST
+#[derive(Clone)]
+pub struct RhaiScript {
+    name: String,
+    parameters: Vec<Attribute>,
+    return_type: AttributeType,
+    timeout: std::time::Duration,
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+}
+
+impl std::fmt::Debug for RhaiScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RhaiScript")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl Script for RhaiScript {
+    fn init(&mut self, _ctx: &Arc<EventFluxAppContext>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn eval(&self, args: &[AttributeValue]) -> Result<AttributeValue, String> {
+        if args.len() != self.parameters.len() {
+            return Err(format!(
+                "Function '{}' expects {} argument(s), got {}",
+                self.name,
+                self.parameters.len(),
+                args.len()
+            ));
+        }
+
+        let mut scope = Scope::new();
+        for (param, value) in self.parameters.iter().zip(args) {
+            let value = to_dynamic(value, param.attribute_type).ok_or_else(|| {
+                format!(
+                    "Function '{}': argument '{}' cannot be passed as {:?}",
+                    self.name, param.name, param.attribute_type
+                )
+            })?;
+            scope.push_dynamic(param.name.as_str(), value);
+        }
+
+        let previous = CALL_DEADLINE.with(|d| d.replace(Some(Instant::now() + self.timeout)));
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast);
+        CALL_DEADLINE.with(|d| d.set(previous));
+
+        let value = result.map_err(|e| format!("Function '{}' failed: {e}", self.name))?;
+        from_dynamic(value, self.return_type).ok_or_else(|| {
+            format!(
+                "Function '{}' did not return a {:?} value",
+                self.name, self.return_type
+            )
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn Script> {
+        Box::new(self.clone())
+    }
+}
+
+/// Convert an argument to the Rhai value for its declared parameter type
+///
+/// Numeric arguments widen or narrow to the parameter type; null becomes `()`.
+fn to_dynamic(value: &AttributeValue, param_type: AttributeType) -> Option<Dynamic> {
+    let as_f64 = || match value {
+        AttributeValue::Int(v) => Some(*v as f64),
+        AttributeValue::Long(v) => Some(*v as f64),
+        AttributeValue::Float(v) => Some(*v as f64),
+        AttributeValue::Double(v) => Some(*v),
+        _ => None,
+    };
+    let as_i64 = || match value {
+        AttributeValue::Int(v) => Some(*v as i64),
+        AttributeValue::Long(v) => Some(*v),
+        _ => None,
+    };
+
+    if matches!(value, AttributeValue::Null) {
+        return Some(Dynamic::UNIT);
+    }
+    match param_type {
+        AttributeType::INT | AttributeType::LONG => as_i64().map(Dynamic::from),
+        AttributeType::FLOAT | AttributeType::DOUBLE => as_f64().map(Dynamic::from),
+        AttributeType::BOOL => match value {
+            AttributeValue::Bool(b) => Some(Dynamic::from(*b)),
+            _ => None,
+        },
+        AttributeType::STRING => match value {
+            AttributeValue::String(s) => Some(Dynamic::from(s.clone())),
+            other => Some(Dynamic::from(other.to_string())),
+        },
+        AttributeType::OBJECT => match value {
+            AttributeValue::String(s) => Some(Dynamic::from(s.clone())),
+            AttributeValue::Bool(b) => Some(Dynamic::from(*b)),
+            AttributeValue::Int(_) | AttributeValue::Long(_) => as_i64().map(Dynamic::from),
+            AttributeValue::Float(_) | AttributeValue::Double(_) => as_f64().map(Dynamic::from),
+            _ => None,
+        },
+    }
+}
+
+/// Convert a script result to the function's declared return type
+fn from_dynamic(value: Dynamic, return_type: AttributeType) -> Option<AttributeValue> {
+    if value.is_unit() {
+        return Some(AttributeValue::Null);
+    }
+    let as_f64 = |v: &Dynamic| {
+        v.as_float()
+            .ok()
+            .or_else(|| v.as_int().ok().map(|i| i as f64))
+    };
+    match return_type {
+        AttributeType::INT => value
+            .as_int()
+            .ok()
+            .and_then(|i| i32::try_from(i).ok())
+            .map(AttributeValue::Int),
+        AttributeType::LONG => value.as_int().ok().map(AttributeValue::Long),
+        AttributeType::FLOAT => as_f64(&value).map(|f| AttributeValue::Float(f as f32)),
+        AttributeType::DOUBLE => as_f64(&value).map(AttributeValue::Double),
+        AttributeType::BOOL => value.as_bool().ok().map(AttributeValue::Bool),
+        AttributeType::STRING => Some(AttributeValue::String(if value.is_string() {
+            value.into_string().ok()?
+        } else {
+            value.to_string()
+        })),
+        AttributeType::OBJECT => {
+            if let Ok(b) = value.as_bool() {
+                Some(AttributeValue::Bool(b))
+            } else if let Ok(i) = value.as_int() {
+                Some(AttributeValue::Long(i))
+            } else if let Ok(f) = value.as_float() {
+                Some(AttributeValue::Double(f))
+            } else {
+                Some(AttributeValue::String(value.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn risk() -> FunctionDefinition {
+        FunctionDefinition::new(
+            "risk".to_string(),
+            RHAI_LANGUAGE.to_string(),
+            r#"if country == "US" { amount * 0.1 } else { amount * 0.3 }"#.to_string(),
+            AttributeType::DOUBLE,
+        )
+        .parameter("amount".to_string(), AttributeType::DOUBLE)
+        .parameter("country".to_string(), AttributeType::STRING)
+    }
+
+    #[test]
+    fn test_eval_with_typed_arguments() {
+        let script = RhaiScriptLanguage::default().compile(&risk()).unwrap();
+        // INT argument widens to the DOUBLE parameter
+        let value = script
+            .eval(&[
+                AttributeValue::Int(100),
+                AttributeValue::String("DE".to_string()),
+            ])
+            .unwrap();
+        assert_eq!(value, AttributeValue::Double(30.0));
+    }
+
+    #[test]
+    fn test_syntax_error_reported_at_compile() {
+        let mut def = risk();
+        def.body = "amount *".to_string();
+        assert!(RhaiScriptLanguage::default().compile(&def).is_err());
+    }
+
+    #[test]
+    fn test_operation_limit_stops_runaway_loop() {
+        let def = FunctionDefinition::new(
+            "spin".to_string(),
+            RHAI_LANGUAGE.to_string(),
+            "loop {}".to_string(),
+            AttributeType::INT,
+        );
+        let script = RhaiScriptLanguage::new(ScriptLimits {
+            max_operations: 1_000,
+            ..ScriptLimits::default()
+        })
+        .compile(&def)
+        .unwrap();
+        assert!(script.eval(&[]).is_err());
+    }
+
+    #[test]
+    fn test_timeout_stops_long_call() {
+        let def = FunctionDefinition::new(
+            "spin".to_string(),
+            RHAI_LANGUAGE.to_string(),
+            "loop {}".to_string(),
+            AttributeType::INT,
+        );
+        let script = RhaiScriptLanguage::new(ScriptLimits {
+            max_operations: 0,
+            timeout: std::time::Duration::from_millis(10),
+            ..ScriptLimits::default()
+        })
+        .compile(&def)
+        .unwrap();
+        assert!(script.eval(&[]).unwrap_err().contains("timed out"));
+    }
+}
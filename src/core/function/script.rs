@@ -1,14 +1,57 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+//! Script functions
+//!
+//! `CREATE FUNCTION ... LANGUAGE <lang> AS '<body>'` defines a scalar function
+//! whose body is compiled by the [`ScriptLanguage`] registered for `<lang>` in
+//! [`EventFluxContext`](crate::core::config::eventflux_context::EventFluxContext).
+//! Every call runs under [`ScriptLimits`] so a misbehaving body cannot stall
+//! the event pipeline.
+
 use crate::core::config::eventflux_app_context::EventFluxAppContext;
-use crate::core::event::complex_event::ComplexEvent;
 use crate::core::event::value::AttributeValue;
+use crate::query_api::definition::FunctionDefinition;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Resource limits applied to every script function call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptLimits {
+    /// Maximum number of interpreter operations per call
+    pub max_operations: u64,
+    /// Maximum wall-clock time per call
+    pub timeout: Duration,
+    /// Maximum nesting of function calls inside the script
+    pub max_call_depth: usize,
+    /// Maximum length of any string the script builds
+    pub max_string_size: usize,
+    /// Maximum number of elements in any array or map the script builds
+    pub max_collection_size: usize,
+}
 
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 100_000,
+            timeout: Duration::from_millis(50),
+            max_call_depth: 32,
+            max_string_size: 64 * 1024,
+            max_collection_size: 10_000,
+        }
+    }
+}
+
+/// A compiled script function body
 pub trait Script: Debug + Send + Sync {
     fn init(&mut self, ctx: &Arc<EventFluxAppContext>) -> Result<(), String>;
-    fn eval(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue>;
+
+    /// Run the body with `args` bound to the declared parameters
+    ///
+    /// Arguments arrive in declaration order; the result is converted to the
+    /// function's declared return type.
+    fn eval(&self, args: &[AttributeValue]) -> Result<AttributeValue, String>;
+
     fn clone_box(&self) -> Box<dyn Script>;
 }
 
@@ -17,3 +60,20 @@ impl Clone for Box<dyn Script> {
         self.clone_box()
     }
 }
+
+/// Compiles function bodies written in one scripting language
+pub trait ScriptLanguage: Debug + Send + Sync {
+    /// Name used in `LANGUAGE <name>`
+    fn name(&self) -> &'static str;
+
+    /// Compile a function definition, reporting syntax errors up front
+    fn compile(&self, definition: &FunctionDefinition) -> Result<Box<dyn Script>, String>;
+
+    fn clone_box(&self) -> Box<dyn ScriptLanguage>;
+}
+
+impl Clone for Box<dyn ScriptLanguage> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
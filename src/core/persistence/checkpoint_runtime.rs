@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Periodic incremental checkpoints of an application's state.
+
+use chrono::Utc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::core::util::ThreadBarrier;
+
+use super::incremental::{CheckpointResult, IncrementalCheckpointSystem};
+use super::snapshot_service::SnapshotService;
+use super::state_holder::{CheckpointId, StateError};
+
+/// Checkpoints the state holders of an application on a fixed interval.
+///
+/// Event processing is paused while the state is captured. A failed
+/// checkpoint is logged and the next one is taken in full.
+pub struct CheckpointRuntime {
+    checkpointer: Checkpointer,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for CheckpointRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckpointRuntime")
+            .field(
+                "eventflux_app_id",
+                &self.checkpointer.service.eventflux_app_id,
+            )
+            .field("interval_ms", &self.interval_ms)
+            .finish()
+    }
+}
+
+impl CheckpointRuntime {
+    pub fn new(
+        system: IncrementalCheckpointSystem,
+        service: Arc<SnapshotService>,
+        barrier: Option<Arc<ThreadBarrier>>,
+        interval_ms: i64,
+        scheduler: Arc<Scheduler>,
+    ) -> Self {
+        Self {
+            checkpointer: Checkpointer {
+                system: Arc::new(Mutex::new(system)),
+                service,
+                barrier,
+            },
+            interval_ms,
+            scheduler,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Schedule periodic checkpoints; a zero interval leaves them to `checkpoint`
+    pub fn start(&self) {
+        if self.interval_ms <= 0 || self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let task = CheckpointTask {
+            checkpointer: self.checkpointer.clone(),
+            interval_ms: self.interval_ms,
+            scheduler: Arc::clone(&self.scheduler),
+            running: Arc::clone(&self.running),
+        };
+        self.scheduler.notify_at(
+            Utc::now().timestamp_millis() + self.interval_ms,
+            Arc::new(task),
+        );
+    }
+
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Take a checkpoint now; `None` when nothing changed since the last one
+    pub fn checkpoint(&self) -> Result<Option<CheckpointResult>, StateError> {
+        self.checkpointer.checkpoint()
+    }
+
+    /// Restore the application's state from its checkpoints
+    pub fn recover(&self) -> Result<Option<CheckpointId>, StateError> {
+        self.checkpointer.recover()
+    }
+}
+
+#[derive(Clone)]
+struct Checkpointer {
+    system: Arc<Mutex<IncrementalCheckpointSystem>>,
+    service: Arc<SnapshotService>,
+    barrier: Option<Arc<ThreadBarrier>>,
+}
+
+impl Checkpointer {
+    fn checkpoint(&self) -> Result<Option<CheckpointResult>, StateError> {
+        let holders = self.service.get_state_holders();
+        self.paused(|| self.system.lock().unwrap().checkpoint(&holders))
+    }
+
+    fn recover(&self) -> Result<Option<CheckpointId>, StateError> {
+        let holders = self.service.get_state_holders();
+        self.paused(|| self.system.lock().unwrap().recover(&holders))
+    }
+
+    /// Run `f` while no event is being processed
+    fn paused<T>(&self, f: impl FnOnce() -> T) -> T {
+        match &self.barrier {
+            Some(barrier) => barrier.paused(f),
+            None => f(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CheckpointTask {
+    checkpointer: Checkpointer,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for CheckpointTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckpointTask")
+            .field(
+                "eventflux_app_id",
+                &self.checkpointer.service.eventflux_app_id,
+            )
+            .field("interval_ms", &self.interval_ms)
+            .finish()
+    }
+}
+
+impl Schedulable for CheckpointTask {
+    fn on_time(&self, timestamp: i64) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        let app_id = &self.checkpointer.service.eventflux_app_id;
+        match self.checkpointer.checkpoint() {
+            Ok(Some(result)) => log::debug!(
+                "Checkpointed '{}' (checkpoint: {}, full: {}, operations: {})",
+                app_id,
+                result.checkpoint_id,
+                result.full,
+                result.operation_count
+            ),
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to checkpoint '{}': {}", app_id, e),
+        }
+        self.scheduler
+            .notify_at(timestamp + self.interval_ms, Arc::new(self.clone()));
+    }
+}
@@ -570,6 +570,7 @@ impl CheckpointMerger for AdvancedCheckpointMerger {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: changes,
+            component_snapshots: HashMap::new(),
             total_size: compressed_size,
             compression_ratio,
             wal_offset_range: wal_range,
@@ -644,6 +645,7 @@ impl AdvancedCheckpointMerger {
 
         // Merge all changes from the group
         let mut merged_changes = HashMap::new();
+        let mut merged_snapshots = HashMap::new();
         let mut total_size = 0;
 
         for checkpoint in group {
@@ -657,6 +659,8 @@ impl AdvancedCheckpointMerger {
                     entry.add_operation(operation.clone());
                 }
             }
+            // The latest snapshot of a component supersedes earlier ones
+            merged_snapshots.extend(checkpoint.component_snapshots.clone());
             total_size += checkpoint.total_size;
         }
 
@@ -669,6 +673,7 @@ impl AdvancedCheckpointMerger {
             sequence_number: first.sequence_number,
             created_at: first.created_at,
             component_changes: merged_changes,
+            component_snapshots: merged_snapshots,
             total_size,
             compression_ratio: 0.8, // Assume some compression from merging
             wal_offset_range: (first.wal_offset_range.0, last.wal_offset_range.1),
@@ -821,6 +826,7 @@ mod tests {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: changes,
+            component_snapshots: HashMap::new(),
             total_size: 100,
             compression_ratio: 1.0,
             wal_offset_range: (0, 10),
@@ -845,6 +851,7 @@ mod tests {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: HashMap::new(),
+            component_snapshots: HashMap::new(),
             total_size: 100,
             compression_ratio: 1.0,
             wal_offset_range: (0, 10),
@@ -859,6 +866,7 @@ mod tests {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: HashMap::new(),
+            component_snapshots: HashMap::new(),
             total_size: 100,
             compression_ratio: 1.0,
             wal_offset_range: (10, 20),
@@ -891,6 +899,7 @@ mod tests {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: HashMap::new(),
+            component_snapshots: HashMap::new(),
             total_size: 100,
             compression_ratio: 1.0,
             wal_offset_range: (0, 10),
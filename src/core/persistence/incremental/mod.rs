@@ -33,12 +33,14 @@ pub mod recovery_engine;
 pub mod write_ahead_log;
 
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::core::persistence::state_holder::{
-    ChangeLog, CheckpointId, ComponentId, StateError, StateSnapshot,
+    ChangeLog, CheckpointId, ComponentId, CompressionType, SchemaVersion, SerializationHints,
+    StateError, StateHolder, StateMetadata, StateSnapshot,
 };
+use crate::core::util::to_bytes;
 
 /// Configuration for incremental checkpointing system
 #[derive(Debug, Clone)]
@@ -55,6 +57,10 @@ pub struct IncrementalCheckpointConfig {
     /// WAL retention period
     pub wal_retention: Duration,
 
+    /// Directory holding the WAL segments, `wal` under the local backend's
+    /// `base_path` when unset
+    pub wal_dir: Option<String>,
+
     /// Batch size for state updates
     pub batch_size: usize,
 
@@ -78,6 +84,7 @@ impl Default for IncrementalCheckpointConfig {
             max_incremental_count: 10,
             wal_segment_size: 64 * 1024 * 1024,       // 64MB
             wal_retention: Duration::from_secs(3600), // 1 hour
+            wal_dir: None,
             batch_size: 1000,
             enable_compression: true,
             merger_threads: 2,
@@ -182,6 +189,9 @@ pub struct IncrementalCheckpoint {
     /// Components included in this checkpoint
     pub component_changes: HashMap<ComponentId, ChangeLog>,
 
+    /// Complete state of the components that can't report their changes
+    pub component_snapshots: HashMap<ComponentId, StateSnapshot>,
+
     /// Total size of checkpoint data
     pub total_size: usize,
 
@@ -195,8 +205,11 @@ pub struct IncrementalCheckpoint {
 /// Result of incremental checkpoint operation
 #[derive(Debug)]
 pub struct CheckpointResult {
-    /// Checkpoint metadata
-    pub checkpoint: IncrementalCheckpoint,
+    /// Identifier of the checkpoint that was written
+    pub checkpoint_id: CheckpointId,
+
+    /// Whether the checkpoint holds the complete state rather than changes
+    pub full: bool,
 
     /// Duration taken to create checkpoint
     pub duration: Duration,
@@ -234,6 +247,13 @@ pub struct IncrementalCheckpointSystem {
     /// Last full checkpoint ID
     last_full_checkpoint: Option<CheckpointId>,
 
+    /// ID of the next checkpoint to write
+    next_checkpoint_id: CheckpointId,
+
+    /// Checksum of the last stored snapshot of each component, so unchanged
+    /// snapshots are left out of incremental checkpoints
+    snapshot_checksums: HashMap<ComponentId, u64>,
+
     /// System metrics
     metrics: IncrementalCheckpointMetrics,
 }
@@ -266,6 +286,376 @@ pub struct IncrementalCheckpointMetrics {
     pub failed_checkpoints: u64,
 }
 
+/// Contents of a full checkpoint
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct FullCheckpointData {
+    /// Snapshot of every component
+    components: HashMap<ComponentId, StateSnapshot>,
+
+    /// WAL tail when the snapshots were taken
+    wal_offset: LogOffset,
+}
+
+/// State the components recorded for the next checkpoint
+#[derive(Default)]
+struct PendingChanges {
+    /// Changes of the components that report them
+    changes: Vec<(ComponentId, Arc<Mutex<dyn StateHolder>>, ChangeLog)>,
+
+    /// Changed snapshots of the components that can't report their changes
+    snapshots: HashMap<ComponentId, StateSnapshot>,
+}
+
+impl IncrementalCheckpointSystem {
+    /// Open the checkpoint backend and write-ahead log described by `config`
+    ///
+    /// Checkpoint IDs continue after those already in the backend. The first
+    /// checkpoint taken is always a full one.
+    pub fn new(config: IncrementalCheckpointConfig) -> Result<Self, StateError> {
+        let backend: Arc<dyn PersistenceBackend> =
+            Arc::from(persistence_backend::create_backend(&config.backend_config)?);
+        let wal_dir = match (&config.wal_dir, &config.backend_config) {
+            (Some(wal_dir), _) => wal_dir.clone(),
+            (None, PersistenceBackendConfig::LocalFile { base_path, .. }) => {
+                format!("{base_path}/wal")
+            }
+            (None, _) => {
+                return Err(StateError::InvalidStateData {
+                    message: "A WAL directory is required for checkpoints not kept on local files"
+                        .to_string(),
+                })
+            }
+        };
+        let wal: Arc<dyn WriteAheadLog> = Arc::new(write_ahead_log::SegmentedWAL::recover(
+            &wal_dir,
+            config.wal_segment_size,
+            write_ahead_log::WALConfig {
+                max_batch_size: config.batch_size,
+                ..Default::default()
+            },
+        )?);
+        let merger =
+            checkpoint_merger::AdvancedCheckpointMerger::new(checkpoint_merger::MergerConfig {
+                max_chain_length: config.max_incremental_count,
+                enable_delta_compression: config.enable_compression,
+                ..Default::default()
+            });
+        let merger: Arc<dyn CheckpointMerger> = Arc::new(merger);
+        let recovery = recovery_engine::AdvancedRecoveryEngine::new(
+            Arc::clone(&backend),
+            Arc::clone(&merger),
+            recovery_engine::RecoveryConfig {
+                optimistic_recovery: false,
+                wal_read_batch: config.batch_size,
+                ..Default::default()
+            },
+        )
+        .with_wal(Arc::clone(&wal));
+        let next_checkpoint_id = backend.list_checkpoints()?.last().map_or(1, |id| id + 1);
+
+        Ok(Self {
+            config,
+            wal,
+            merger,
+            backend,
+            recovery: Arc::new(recovery),
+            coordinator: None,
+            current_chain: Vec::new(),
+            last_full_checkpoint: None,
+            next_checkpoint_id,
+            snapshot_checksums: HashMap::new(),
+            metrics: IncrementalCheckpointMetrics::default(),
+        })
+    }
+
+    /// Report the outcome of every checkpoint to `coordinator`
+    pub fn with_coordinator(mut self, coordinator: Arc<dyn DistributedCoordinator>) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    /// Checkpoint the state of `holders`
+    ///
+    /// The changes recorded since the previous checkpoint are appended to the
+    /// WAL and stored as an incremental checkpoint. Components that can't
+    /// report their changes are snapshotted into the incremental checkpoint
+    /// instead, when their state changed; those snapshots aren't in the WAL.
+    /// A full checkpoint is taken when there is no full checkpoint to build
+    /// on, or after `max_incremental_count` incrementals. Returns `None` when
+    /// nothing changed.
+    pub fn checkpoint(
+        &mut self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<Option<CheckpointResult>, StateError> {
+        let start = Instant::now();
+        let checkpoint_id = self.next_checkpoint_id;
+        let incremental = self.last_full_checkpoint.is_some()
+            && self.current_chain.len() < self.config.max_incremental_count;
+        let result = if incremental {
+            self.collect_changes(holders)
+                .and_then(|pending| self.incremental_checkpoint(pending, start))
+        } else {
+            self.full_checkpoint(holders, start).map(Some)
+        };
+
+        if result.is_err() {
+            // The changes may already be in the WAL, start over from a full checkpoint
+            self.metrics.failed_checkpoints += 1;
+            self.last_full_checkpoint = None;
+        }
+        if let Some(coordinator) = &self.coordinator {
+            if !matches!(result, Ok(None)) {
+                let node_id = self
+                    .config
+                    .distributed_config
+                    .as_ref()
+                    .map_or("local", |distributed| distributed.node_id.as_str());
+                if let Err(e) =
+                    coordinator.report_completion(checkpoint_id, node_id, result.is_ok())
+                {
+                    log::warn!("Failed to report checkpoint {checkpoint_id}: {e}");
+                }
+            }
+        }
+        result
+    }
+
+    /// Restore `holders` from the stored checkpoints
+    ///
+    /// Recovery goes through the [`RecoveryEngine`], which loads the latest
+    /// full checkpoint, applies the incremental checkpoints taken after it in
+    /// order, then any changes in the WAL that never made it into a
+    /// checkpoint. The next checkpoint is a full one. Returns the last
+    /// checkpoint applied, or `None` when there is nothing to restore.
+    pub fn recover(
+        &mut self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<Option<CheckpointId>, StateError> {
+        let start = Instant::now();
+        let Some(last_applied) = self.recovery.recover(holders)? else {
+            return Ok(None);
+        };
+
+        // Holders are not tracking changes against the restored chain
+        self.current_chain.clear();
+        self.last_full_checkpoint = None;
+        self.snapshot_checksums.clear();
+        self.next_checkpoint_id = self.next_checkpoint_id.max(last_applied + 1);
+        self.metrics.avg_recovery_time = start.elapsed();
+        Ok(Some(last_applied))
+    }
+
+    /// Checkpointing metrics
+    pub fn metrics(&self) -> &IncrementalCheckpointMetrics {
+        &self.metrics
+    }
+
+    /// Pending changes of every holder, and a snapshot of each holder that
+    /// can't report its changes if it changed since the last checkpoint
+    fn collect_changes(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<PendingChanges, StateError> {
+        let since = self.next_checkpoint_id - 1;
+        let mut pending = PendingChanges::default();
+        for (component_id, holder) in holders {
+            let guard = holder.lock().unwrap();
+            match guard.get_changelog(since) {
+                Ok(changes) => {
+                    pending
+                        .changes
+                        .push((component_id.clone(), Arc::clone(holder), changes))
+                }
+                Err(e) => {
+                    log::trace!(
+                        "'{}' can't report its changes ({}), snapshotting it",
+                        component_id,
+                        e
+                    );
+                    let mut snapshot = guard.serialize_state(&SerializationHints::default())?;
+                    if self.snapshot_checksums.get(component_id) != Some(&snapshot.checksum) {
+                        snapshot.checkpoint_id = self.next_checkpoint_id;
+                        pending.snapshots.insert(component_id.clone(), snapshot);
+                    }
+                }
+            }
+        }
+        Ok(pending)
+    }
+
+    fn incremental_checkpoint(
+        &mut self,
+        pending: PendingChanges,
+        start: Instant,
+    ) -> Result<Option<CheckpointResult>, StateError> {
+        let operation_count: usize = pending
+            .changes
+            .iter()
+            .map(|(_, _, changes)| changes.operations.len())
+            .sum();
+        if operation_count == 0 && pending.snapshots.is_empty() {
+            return Ok(None);
+        }
+
+        let checkpoint_id = self.next_checkpoint_id;
+        let base_checkpoint_id = checkpoint_id - 1;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+
+        // Log the changes before the checkpoint so a failed store can be replayed
+        let mut entries = Vec::new();
+        for (component_id, _, changes) in &pending.changes {
+            for operations in changes.operations.chunks(self.config.batch_size.max(1)) {
+                let mut change = ChangeLog::new(base_checkpoint_id, checkpoint_id);
+                for operation in operations {
+                    change.add_operation(operation.clone());
+                }
+                entries.push(LogEntry {
+                    component_id: component_id.clone(),
+                    sequence: checkpoint_id,
+                    timestamp,
+                    change,
+                    metadata: HashMap::new(),
+                });
+            }
+        }
+        let wal_start = self.wal.tail_offset()?;
+        self.wal.append_batch(entries)?;
+        let wal_end = self.wal.tail_offset()?;
+
+        let changes = pending
+            .changes
+            .iter()
+            .filter(|(_, _, changes)| !changes.operations.is_empty())
+            .map(|(component_id, _, changes)| (component_id.clone(), changes.clone()))
+            .collect();
+        let mut checkpoint =
+            self.merger
+                .create_incremental(base_checkpoint_id, changes, (wal_start, wal_end))?;
+        checkpoint.checkpoint_id = checkpoint_id;
+        checkpoint.sequence_number = self.current_chain.len() as u64 + 1;
+        checkpoint.total_size += pending
+            .snapshots
+            .values()
+            .map(|snapshot| snapshot.data.len())
+            .sum::<usize>();
+        checkpoint.component_snapshots = pending.snapshots;
+        let storage_location = self.backend.store_incremental_checkpoint(&checkpoint)?;
+
+        for (_, holder, changes) in &pending.changes {
+            holder
+                .lock()
+                .unwrap()
+                .commit_changelog(checkpoint_id, changes.operations.len());
+        }
+        for (component_id, snapshot) in &checkpoint.component_snapshots {
+            self.snapshot_checksums
+                .insert(component_id.clone(), snapshot.checksum);
+        }
+
+        let duration = start.elapsed();
+        let count = self.metrics.total_incrementals;
+        self.metrics.avg_incremental_size = ((self.metrics.avg_incremental_size as u64 * count
+            + checkpoint.total_size as u64)
+            / (count + 1)) as usize;
+        self.metrics.avg_incremental_duration =
+            (self.metrics.avg_incremental_duration * count as u32 + duration) / (count as u32 + 1);
+        self.metrics.total_incrementals += 1;
+        self.current_chain.push(checkpoint);
+        self.next_checkpoint_id += 1;
+
+        Ok(Some(CheckpointResult {
+            checkpoint_id,
+            full: false,
+            duration,
+            operation_count,
+            storage_location,
+        }))
+    }
+
+    fn full_checkpoint(
+        &mut self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+        start: Instant,
+    ) -> Result<CheckpointResult, StateError> {
+        let checkpoint_id = self.next_checkpoint_id;
+        let since = checkpoint_id - 1;
+        let hints = SerializationHints::default();
+
+        // Changes pending now are part of the snapshot and can be dropped afterwards
+        let mut components = HashMap::with_capacity(holders.len());
+        let mut checksums = HashMap::with_capacity(holders.len());
+        let mut pending = Vec::with_capacity(holders.len());
+        for (component_id, holder) in holders {
+            let guard = holder.lock().unwrap();
+            let operations = guard
+                .get_changelog(since)
+                .map_or(0, |changes| changes.operations.len());
+            let mut snapshot = guard.serialize_state(&hints)?;
+            snapshot.checkpoint_id = checkpoint_id;
+            checksums.insert(component_id.clone(), snapshot.checksum);
+            components.insert(component_id.clone(), snapshot);
+            pending.push((Arc::clone(holder), operations));
+        }
+
+        let wal_offset = self.wal.tail_offset()?;
+        let data = to_bytes(&FullCheckpointData {
+            components,
+            wal_offset,
+        })
+        .map_err(|e| StateError::SerializationError {
+            message: format!("Failed to serialize full checkpoint: {e}"),
+        })?;
+        let snapshot = StateSnapshot {
+            version: SchemaVersion::new(1, 0, 0),
+            checkpoint_id,
+            checksum: StateSnapshot::calculate_checksum(&data),
+            data,
+            compression: CompressionType::None,
+            metadata: StateMetadata::new(
+                format!("checkpoint_{checkpoint_id}"),
+                "IncrementalCheckpointSystem".to_string(),
+            ),
+        };
+        let storage_location = self
+            .backend
+            .store_full_checkpoint(checkpoint_id, &snapshot)?;
+
+        for (holder, operations) in &pending {
+            holder
+                .lock()
+                .unwrap()
+                .commit_changelog(checkpoint_id, *operations);
+        }
+
+        // Older checkpoints and logged changes are superseded
+        self.wal.trim_to(wal_offset)?;
+        for old_id in self.backend.list_checkpoints()? {
+            if old_id < checkpoint_id {
+                if let Err(e) = self.backend.delete_checkpoint(old_id) {
+                    log::warn!("Failed to delete superseded checkpoint {old_id}: {e}");
+                }
+            }
+        }
+
+        self.metrics.total_full_checkpoints += 1;
+        self.current_chain.clear();
+        self.last_full_checkpoint = Some(checkpoint_id);
+        self.snapshot_checksums = checksums;
+        self.next_checkpoint_id += 1;
+
+        Ok(CheckpointResult {
+            checkpoint_id,
+            full: true,
+            duration: start.elapsed(),
+            operation_count: pending.iter().map(|(_, operations)| operations).sum(),
+            storage_location,
+        })
+    }
+}
+
 // Trait definitions for pluggable components
 
 /// Write-Ahead Log trait for durable state change logging
@@ -345,6 +735,9 @@ pub trait PersistenceBackend: Send + Sync {
 
     /// Delete old checkpoints (for cleanup)
     fn cleanup_checkpoints(&self, before: Instant) -> Result<usize, StateError>;
+
+    /// Delete a single checkpoint
+    fn delete_checkpoint(&self, checkpoint_id: CheckpointId) -> Result<(), StateError>;
 }
 
 /// Recovery engine for reconstructing state from checkpoints
@@ -370,6 +763,14 @@ pub trait RecoveryEngine: Send + Sync {
         target_time: Instant,
         components: &[ComponentId],
     ) -> Result<RecoveryPath, StateError>;
+
+    /// Restore `holders` along the path to the latest checkpoint, then replay
+    /// the changes logged after it. Returns the last checkpoint or WAL
+    /// sequence applied, or `None` when there is no checkpoint to restore.
+    fn recover(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<Option<CheckpointId>, StateError>;
 }
 
 /// Distributed coordinator for cluster-wide checkpointing
@@ -590,6 +590,30 @@ impl PersistenceBackend for FilePersistenceBackend {
 
         Ok(removed_count)
     }
+
+    fn delete_checkpoint(&self, checkpoint_id: CheckpointId) -> Result<(), StateError> {
+        let file_lock = self.get_file_lock(checkpoint_id);
+        let _lock = file_lock.lock().unwrap();
+
+        let file_path = self.checkpoint_file_path(checkpoint_id);
+        if file_path.exists() {
+            std::fs::remove_file(&file_path).map_err(|e| StateError::InvalidStateData {
+                message: format!("Failed to remove checkpoint file: {e}"),
+            })?;
+        }
+
+        let removed = self
+            .metadata_cache
+            .write()
+            .unwrap()
+            .remove(&checkpoint_id)
+            .is_some();
+        if removed {
+            self.save_metadata_cache()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for MemoryPersistenceBackend {
@@ -705,6 +729,18 @@ impl PersistenceBackend for MemoryPersistenceBackend {
         // For now, just return 0 (no cleanup performed)
         Ok(0)
     }
+
+    fn delete_checkpoint(&self, checkpoint_id: CheckpointId) -> Result<(), StateError> {
+        self.full_checkpoints
+            .write()
+            .unwrap()
+            .remove(&checkpoint_id);
+        self.incremental_checkpoints
+            .write()
+            .unwrap()
+            .remove(&checkpoint_id);
+        Ok(())
+    }
 }
 
 impl DistributedPersistenceBackend {
@@ -777,6 +813,11 @@ impl PersistenceBackend for DistributedPersistenceBackend {
         // Placeholder implementation
         Ok(0)
     }
+
+    fn delete_checkpoint(&self, _checkpoint_id: CheckpointId) -> Result<(), StateError> {
+        // Placeholder implementation
+        Ok(())
+    }
 }
 
 /// Create persistence backend from configuration
@@ -842,6 +883,7 @@ mod tests {
                 .unwrap()
                 .as_micros() as u64,
             component_changes: HashMap::new(),
+            component_snapshots: HashMap::new(),
             total_size: 100,
             compression_ratio: 1.0,
             wal_offset_range: (0, 10),
@@ -959,4 +1001,25 @@ mod tests {
         let remaining = backend.list_checkpoints().unwrap();
         assert!(remaining.is_empty());
     }
+
+    #[test]
+    fn test_file_backend_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = FilePersistenceBackend::new(temp_dir.path(), true).unwrap();
+
+        backend
+            .store_full_checkpoint(1, &create_test_snapshot(1))
+            .unwrap();
+        backend
+            .store_incremental_checkpoint(&create_test_incremental(2, 1))
+            .unwrap();
+
+        backend.delete_checkpoint(1).unwrap();
+        assert_eq!(backend.list_checkpoints().unwrap(), vec![2]);
+        assert!(backend.load_full_checkpoint(1).is_err());
+
+        // Deletion survives reopening the backend
+        let reopened = FilePersistenceBackend::new(temp_dir.path(), true).unwrap();
+        assert_eq!(reopened.list_checkpoints().unwrap(), vec![2]);
+    }
 }
@@ -6,17 +6,21 @@
 //!
 //! Advanced recovery engine for reconstructing state from incremental checkpoints
 //! with parallel recovery, dependency resolution, and point-in-time recovery capabilities.
+//! With a write-ahead log attached it also restores state holders directly, replaying
+//! the changes that never made it into a checkpoint.
 
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use super::{
-    CheckpointMerger, IncrementalCheckpoint, PersistenceBackend, RecoveryEngine, RecoveryPath,
+    CheckpointMerger, FullCheckpointData, IncrementalCheckpoint, LogOffset, PersistenceBackend,
+    RecoveryEngine, RecoveryPath, WriteAheadLog,
 };
 use crate::core::persistence::state_holder::{
-    CheckpointId, ComponentId, StateError, StateSnapshot,
+    ChangeLog, CheckpointId, ComponentId, StateError, StateHolder, StateSnapshot,
 };
+use crate::core::util::from_bytes;
 
 /// Advanced recovery engine with parallel processing
 pub struct AdvancedRecoveryEngine {
@@ -26,6 +30,9 @@ pub struct AdvancedRecoveryEngine {
     /// Checkpoint merger for reconstruction
     merger: Arc<dyn CheckpointMerger>,
 
+    /// Write-ahead log replayed after the checkpoints (optional)
+    wal: Option<Arc<dyn WriteAheadLog>>,
+
     /// Recovery configuration
     config: RecoveryConfig,
 
@@ -53,6 +60,9 @@ pub struct RecoveryConfig {
 
     /// Recovery verification level
     pub verification_level: VerificationLevel,
+
+    /// Number of WAL entries read at a time while replaying the log
+    pub wal_read_batch: usize,
 }
 
 impl Default for RecoveryConfig {
@@ -63,6 +73,7 @@ impl Default for RecoveryConfig {
             optimistic_recovery: true,
             prefetch_strategy: PrefetchStrategy::Adaptive,
             verification_level: VerificationLevel::Standard,
+            wal_read_batch: 1000,
         }
     }
 }
@@ -215,35 +226,181 @@ impl AdvancedRecoveryEngine {
         Self {
             backend,
             merger,
+            wal: None,
             config,
             checkpoint_cache: HashMap::new(),
             stats: RecoveryStatistics::default(),
         }
     }
 
+    /// Replay the changes in `wal` that follow the recovered checkpoints
+    pub fn with_wal(mut self, wal: Arc<dyn WriteAheadLog>) -> Self {
+        self.wal = Some(wal);
+        self
+    }
+
     /// Build checkpoint information cache
     fn build_checkpoint_cache(&mut self) -> Result<(), StateError> {
-        let checkpoint_ids = self.backend.list_checkpoints()?;
+        self.checkpoint_cache = self
+            .load_checkpoints()?
+            .iter()
+            .map(|(checkpoint_id, checkpoint)| {
+                (
+                    *checkpoint_id,
+                    Self::checkpoint_info(*checkpoint_id, checkpoint),
+                )
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Load every stored checkpoint; checkpoints that can't be loaded are skipped
+    fn load_checkpoints(&self) -> Result<HashMap<CheckpointId, CachedCheckpoint>, StateError> {
+        let mut checkpoints = HashMap::new();
+        for checkpoint_id in self.backend.list_checkpoints()? {
+            match self.load_checkpoint(checkpoint_id) {
+                Ok(checkpoint) => {
+                    checkpoints.insert(checkpoint_id, checkpoint);
+                }
+                Err(e) => log::warn!("Skipping unreadable checkpoint {checkpoint_id}: {e}"),
+            }
+        }
+        Ok(checkpoints)
+    }
 
-        for checkpoint_id in checkpoint_ids {
-            // Try to determine checkpoint type and metadata
-            // In a real implementation, this would be more sophisticated
-            let info = CheckpointInfo {
+    /// Recovery planning information of a loaded checkpoint
+    fn checkpoint_info(
+        checkpoint_id: CheckpointId,
+        checkpoint: &CachedCheckpoint,
+    ) -> CheckpointInfo {
+        match checkpoint {
+            CachedCheckpoint::Full(snapshot) => CheckpointInfo {
                 checkpoint_id,
-                checkpoint_type: CheckpointType::Full, // Simplified
-                created_at: SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_micros() as u64,
-                size_bytes: 1000, // Estimated
+                checkpoint_type: CheckpointType::Full,
+                created_at: snapshot.metadata.created_at * 1_000_000,
+                size_bytes: snapshot.data.len(),
                 dependencies: Vec::new(),
-                components: HashSet::new(),
-            };
+                components: from_bytes::<FullCheckpointData>(&snapshot.data)
+                    .map(|data| data.components.into_keys().collect())
+                    .unwrap_or_default(),
+            },
+            CachedCheckpoint::Incremental(incremental) => CheckpointInfo {
+                checkpoint_id,
+                checkpoint_type: CheckpointType::Incremental {
+                    base: incremental.base_checkpoint_id,
+                },
+                created_at: incremental.created_at,
+                size_bytes: incremental.total_size,
+                dependencies: vec![incremental.base_checkpoint_id],
+                components: incremental
+                    .component_changes
+                    .keys()
+                    .chain(incremental.component_snapshots.keys())
+                    .cloned()
+                    .collect(),
+            },
+        }
+    }
 
-            self.checkpoint_cache.insert(checkpoint_id, info);
+    /// Path to the latest checkpoint whose chain leads back to a full checkpoint
+    fn latest_recovery_path(
+        checkpoint_cache: &HashMap<CheckpointId, CheckpointInfo>,
+    ) -> Option<RecoveryPath> {
+        let mut checkpoint_ids: Vec<_> = checkpoint_cache.keys().copied().collect();
+        checkpoint_ids.sort_unstable();
+        checkpoint_ids.iter().rev().find_map(|&target| {
+            let chain = Self::checkpoint_chain(checkpoint_cache, target);
+            let (&base_checkpoint, incremental_chain) = chain.split_first()?;
+            if !matches!(
+                checkpoint_cache[&base_checkpoint].checkpoint_type,
+                CheckpointType::Full
+            ) {
+                log::warn!(
+                    "Checkpoint {target} does not lead back to a full checkpoint, skipping it"
+                );
+                return None;
+            }
+            let total_size = chain
+                .iter()
+                .map(|checkpoint_id| checkpoint_cache[checkpoint_id].size_bytes)
+                .sum::<usize>();
+            Some(RecoveryPath {
+                base_checkpoint,
+                incremental_chain: incremental_chain.to_vec(),
+                estimated_duration: Duration::from_millis(total_size as u64 / 1000), // Rough estimate
+                total_size,
+            })
+        })
+    }
+
+    /// Replay the WAL entries from `offset` on that were logged after the
+    /// `last_applied` checkpoint, i.e. for a checkpoint that was never stored.
+    /// Returns the last sequence replayed, or `last_applied` when there was none.
+    fn replay_wal(
+        &self,
+        mut offset: LogOffset,
+        last_applied: CheckpointId,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<CheckpointId, StateError> {
+        let Some(wal) = &self.wal else {
+            return Ok(last_applied);
+        };
+        let mut max_sequence = last_applied;
+        loop {
+            let entries = wal.read_from(offset, self.config.wal_read_batch.max(1))?;
+            if entries.is_empty() {
+                break;
+            }
+            offset += entries.len() as LogOffset;
+            for entry in entries.iter().filter(|entry| entry.sequence > last_applied) {
+                self.apply_changes(holders, &entry.component_id, &entry.change)?;
+                max_sequence = max_sequence.max(entry.sequence);
+            }
         }
+        Ok(max_sequence)
+    }
 
-        Ok(())
+    fn restore_snapshot(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+        component_id: &ComponentId,
+        snapshot: &StateSnapshot,
+    ) -> Result<(), StateError> {
+        self.with_holder(holders, component_id, |holder| {
+            holder.deserialize_state(snapshot)
+        })
+    }
+
+    fn apply_changes(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+        component_id: &ComponentId,
+        changes: &ChangeLog,
+    ) -> Result<(), StateError> {
+        self.with_holder(holders, component_id, |holder| {
+            holder.apply_changelog(changes)
+        })
+    }
+
+    /// Run `restore` on the holder of `component_id`; missing holders are
+    /// skipped, as are failures under optimistic recovery
+    fn with_holder(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+        component_id: &ComponentId,
+        restore: impl FnOnce(&dyn StateHolder) -> Result<(), StateError>,
+    ) -> Result<(), StateError> {
+        let Some(holder) = holders.get(component_id) else {
+            log::warn!("No state holder for checkpointed component '{component_id}'");
+            return Ok(());
+        };
+        match restore(&*holder.lock().unwrap()) {
+            Err(e) if self.config.optimistic_recovery => {
+                log::warn!("Skipping state of '{component_id}' that failed to restore: {e}");
+                Ok(())
+            }
+            result => result,
+        }
     }
 
     /// Create a recovery plan for the given components and target checkpoint
@@ -291,11 +448,23 @@ impl AdvancedRecoveryEngine {
         &self,
         target_checkpoint_id: CheckpointId,
     ) -> Result<Vec<CheckpointId>, StateError> {
+        Ok(Self::checkpoint_chain(
+            &self.checkpoint_cache,
+            target_checkpoint_id,
+        ))
+    }
+
+    /// Chain of checkpoints in `checkpoint_cache` leading to the target,
+    /// starting at the first one whose base is full or missing
+    fn checkpoint_chain(
+        checkpoint_cache: &HashMap<CheckpointId, CheckpointInfo>,
+        target_checkpoint_id: CheckpointId,
+    ) -> Vec<CheckpointId> {
         let mut chain = Vec::new();
         let mut current_id = target_checkpoint_id;
 
         // Walk backwards through the checkpoint chain
-        while let Some(checkpoint_info) = self.checkpoint_cache.get(&current_id) {
+        while let Some(checkpoint_info) = checkpoint_cache.get(&current_id) {
             chain.push(current_id);
 
             match &checkpoint_info.checkpoint_type {
@@ -312,7 +481,7 @@ impl AdvancedRecoveryEngine {
 
         // Reverse to get forward chain
         chain.reverse();
-        Ok(chain)
+        chain
     }
 
     /// Execute recovery plan with parallel processing
@@ -597,6 +766,62 @@ impl RecoveryEngine for AdvancedRecoveryEngine {
             total_size,
         })
     }
+
+    fn recover(
+        &self,
+        holders: &HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) -> Result<Option<CheckpointId>, StateError> {
+        let checkpoints = self.load_checkpoints()?;
+        let checkpoint_cache: HashMap<_, _> = checkpoints
+            .iter()
+            .map(|(checkpoint_id, checkpoint)| {
+                (
+                    *checkpoint_id,
+                    Self::checkpoint_info(*checkpoint_id, checkpoint),
+                )
+            })
+            .collect();
+        let Some(path) = Self::latest_recovery_path(&checkpoint_cache) else {
+            return Ok(None);
+        };
+
+        let Some(CachedCheckpoint::Full(snapshot)) = checkpoints.get(&path.base_checkpoint) else {
+            return Err(StateError::CheckpointNotFound {
+                checkpoint_id: path.base_checkpoint,
+            });
+        };
+        let data: FullCheckpointData =
+            from_bytes(&snapshot.data).map_err(|e| StateError::DeserializationError {
+                message: format!(
+                    "Failed to deserialize full checkpoint {}: {e}",
+                    path.base_checkpoint
+                ),
+            })?;
+        for (component_id, component_snapshot) in &data.components {
+            self.restore_snapshot(holders, component_id, component_snapshot)?;
+        }
+
+        let mut last_applied = path.base_checkpoint;
+        for checkpoint_id in &path.incremental_chain {
+            let Some(CachedCheckpoint::Incremental(incremental)) = checkpoints.get(checkpoint_id)
+            else {
+                return Err(StateError::CheckpointNotFound {
+                    checkpoint_id: *checkpoint_id,
+                });
+            };
+            for (component_id, changes) in &incremental.component_changes {
+                self.apply_changes(holders, component_id, changes)?;
+            }
+            for (component_id, component_snapshot) in &incremental.component_snapshots {
+                self.restore_snapshot(holders, component_id, component_snapshot)?;
+            }
+            last_applied = *checkpoint_id;
+        }
+
+        // Everything logged before the full checkpoint is part of it
+        self.replay_wal(data.wal_offset, last_applied, holders)
+            .map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -604,7 +829,14 @@ mod tests {
     use super::*;
     use crate::core::persistence::incremental::checkpoint_merger::AdvancedCheckpointMerger;
     use crate::core::persistence::incremental::persistence_backend::MemoryPersistenceBackend;
-    use crate::core::persistence::state_holder::{CompressionType, SchemaVersion, StateMetadata};
+    use crate::core::persistence::incremental::write_ahead_log::{SegmentedWAL, WALConfig};
+    use crate::core::persistence::incremental::LogEntry;
+    use crate::core::persistence::state_holder::{
+        AccessPattern, CompressionType, SchemaVersion, SerializationHints, StateMetadata,
+        StateOperation, StateSize,
+    };
+    use crate::core::util::to_bytes;
+    use tempfile::TempDir;
 
     fn create_test_snapshot(checkpoint_id: CheckpointId) -> StateSnapshot {
         let data = vec![1, 2, 3, 4, 5];
@@ -769,4 +1001,210 @@ mod tests {
         assert_eq!(plan.checkpoints_needed, vec![1]);
         assert_eq!(plan.total_size, 1000);
     }
+
+    /// Holder whose state is the list of inserted keys
+    #[derive(Default)]
+    struct KeysHolder {
+        keys: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl KeysHolder {
+        fn keys(&self) -> Vec<String> {
+            self.keys
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|key| String::from_utf8(key.clone()).unwrap())
+                .collect()
+        }
+    }
+
+    impl StateHolder for KeysHolder {
+        fn schema_version(&self) -> SchemaVersion {
+            SchemaVersion::new(1, 0, 0)
+        }
+
+        fn serialize_state(
+            &self,
+            _hints: &SerializationHints,
+        ) -> Result<StateSnapshot, StateError> {
+            Ok(snapshot_of(to_bytes(&*self.keys.lock().unwrap()).unwrap()))
+        }
+
+        fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+            *self.keys.lock().unwrap() = from_bytes(&snapshot.data).unwrap();
+            Ok(())
+        }
+
+        fn get_changelog(&self, since: CheckpointId) -> Result<ChangeLog, StateError> {
+            Ok(ChangeLog::new(since, since + 1))
+        }
+
+        fn apply_changelog(&self, changes: &ChangeLog) -> Result<(), StateError> {
+            let mut keys = self.keys.lock().unwrap();
+            for operation in &changes.operations {
+                match operation {
+                    StateOperation::Insert { key, .. } => keys.push(key.clone()),
+                    _ => {
+                        return Err(StateError::InvalidStateData {
+                            message: "unsupported operation".to_string(),
+                        })
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn estimate_size(&self) -> StateSize {
+            StateSize {
+                bytes: 0,
+                entries: self.keys.lock().unwrap().len(),
+                estimated_growth_rate: 0.0,
+            }
+        }
+
+        fn access_pattern(&self) -> AccessPattern {
+            AccessPattern::Hot
+        }
+
+        fn component_metadata(&self) -> StateMetadata {
+            StateMetadata::new("keys".to_string(), "KeysHolder".to_string())
+        }
+    }
+
+    fn snapshot_of(data: Vec<u8>) -> StateSnapshot {
+        let mut snapshot = create_test_snapshot(0);
+        snapshot.checksum = StateSnapshot::calculate_checksum(&data);
+        snapshot.data = data;
+        snapshot
+    }
+
+    fn inserts(from: CheckpointId, keys: &[&str]) -> ChangeLog {
+        let mut changes = ChangeLog::new(from, from + 1);
+        for key in keys {
+            changes.add_operation(StateOperation::Insert {
+                key: key.as_bytes().to_vec(),
+                value: Vec::new(),
+            });
+        }
+        changes
+    }
+
+    fn store_full(
+        backend: &MemoryPersistenceBackend,
+        checkpoint_id: CheckpointId,
+        keys: &[&str],
+        wal_offset: LogOffset,
+    ) {
+        let state: Vec<Vec<u8>> = keys.iter().map(|key| key.as_bytes().to_vec()).collect();
+        let data = FullCheckpointData {
+            components: HashMap::from([(
+                "keys".to_string(),
+                snapshot_of(to_bytes(&state).unwrap()),
+            )]),
+            wal_offset,
+        };
+        backend
+            .store_full_checkpoint(checkpoint_id, &snapshot_of(to_bytes(&data).unwrap()))
+            .unwrap();
+    }
+
+    fn store_incremental(
+        backend: &MemoryPersistenceBackend,
+        checkpoint_id: CheckpointId,
+        base_checkpoint_id: CheckpointId,
+        changes: ChangeLog,
+    ) {
+        backend
+            .store_incremental_checkpoint(&IncrementalCheckpoint {
+                checkpoint_id,
+                base_checkpoint_id,
+                sequence_number: 1,
+                created_at: 0,
+                component_changes: HashMap::from([("keys".to_string(), changes)]),
+                component_snapshots: HashMap::new(),
+                total_size: 0,
+                compression_ratio: 1.0,
+                wal_offset_range: (0, 0),
+            })
+            .unwrap();
+    }
+
+    fn create_wal_engine(
+        dir: &TempDir,
+        backend: Arc<MemoryPersistenceBackend>,
+        config: RecoveryConfig,
+    ) -> AdvancedRecoveryEngine {
+        let merger = Arc::new(AdvancedCheckpointMerger::new(Default::default()));
+        let wal = SegmentedWAL::recover(dir.path(), 1024 * 1024, WALConfig::default()).unwrap();
+        AdvancedRecoveryEngine::new(backend, merger, config).with_wal(Arc::new(wal))
+    }
+
+    fn create_holders() -> (
+        Arc<Mutex<KeysHolder>>,
+        HashMap<ComponentId, Arc<Mutex<dyn StateHolder>>>,
+    ) {
+        let holder = Arc::new(Mutex::new(KeysHolder::default()));
+        let shared: Arc<Mutex<dyn StateHolder>> = holder.clone();
+        (holder, HashMap::from([("keys".to_string(), shared)]))
+    }
+
+    #[test]
+    fn test_recover_holders_without_checkpoints() {
+        let dir = TempDir::new().unwrap();
+        let backend = Arc::new(MemoryPersistenceBackend::new());
+        let engine = create_wal_engine(&dir, backend, RecoveryConfig::default());
+
+        assert_eq!(engine.recover(&create_holders().1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_recover_holders_from_chain_and_wal() {
+        let dir = TempDir::new().unwrap();
+        let backend = Arc::new(MemoryPersistenceBackend::new());
+        // The first entry was logged before the full checkpoint was taken
+        store_full(&backend, 1, &["a"], 1);
+        store_incremental(&backend, 2, 1, inserts(1, &["b"]));
+        // Built on a checkpoint that is gone, so it doesn't lead back to checkpoint 1
+        store_incremental(&backend, 5, 4, inserts(4, &["x"]));
+        let engine = create_wal_engine(&dir, backend, RecoveryConfig::default());
+        let wal = engine.wal.as_ref().unwrap();
+        for (sequence, key) in [(3, "stale"), (3, "c")] {
+            wal.append(LogEntry {
+                component_id: "keys".to_string(),
+                sequence,
+                timestamp: 0,
+                change: inserts(2, &[key]),
+                metadata: HashMap::new(),
+            })
+            .unwrap();
+        }
+        let (holder, holders) = create_holders();
+
+        assert_eq!(engine.recover(&holders).unwrap(), Some(3));
+        assert_eq!(holder.lock().unwrap().keys(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_recover_holders_optimistically() {
+        let dir = TempDir::new().unwrap();
+        let backend = Arc::new(MemoryPersistenceBackend::new());
+        store_full(&backend, 1, &["a"], 0);
+        let mut unsupported = ChangeLog::new(1, 2);
+        unsupported.add_operation(StateOperation::Clear);
+        store_incremental(&backend, 2, 1, unsupported);
+
+        let strict = RecoveryConfig {
+            optimistic_recovery: false,
+            ..Default::default()
+        };
+        let engine = create_wal_engine(&dir, Arc::clone(&backend), strict);
+        assert!(engine.recover(&create_holders().1).is_err());
+        drop(engine);
+
+        let engine = create_wal_engine(&dir, backend, RecoveryConfig::default());
+        let (holder, holders) = create_holders();
+        assert_eq!(engine.recover(&holders).unwrap(), Some(2));
+        assert_eq!(holder.lock().unwrap().keys(), vec!["a"]);
+    }
 }
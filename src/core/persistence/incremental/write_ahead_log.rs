@@ -183,7 +183,7 @@ impl SegmentedWAL {
 
         // Initialize with first segment
         let segment_id = 0;
-        let segment_path = base_dir.join(format!("wal-{segment_id:010}.log"));
+        let segment_path = segment_file_path(&base_dir, segment_id, 0);
 
         let active_segment = WALSegment::new(segment_id, &segment_path, 0)?;
 
@@ -223,20 +223,13 @@ impl SegmentedWAL {
             let file_name = entry.file_name();
             let file_name = file_name.to_string_lossy();
 
-            if file_name.starts_with("wal-") && file_name.ends_with(".log") {
-                if let Some(segment_id_str) = file_name
-                    .strip_prefix("wal-")
-                    .and_then(|s| s.strip_suffix(".log"))
-                {
-                    if let Ok(segment_id) = segment_id_str.parse::<u64>() {
-                        segments.push((segment_id, entry.path()));
-                    }
-                }
+            if let Some((segment_id, start_offset)) = parse_segment_file_name(&file_name) {
+                segments.push((segment_id, start_offset, entry.path()));
             }
         }
 
         // Sort segments by ID
-        segments.sort_by_key(|(id, _)| *id);
+        segments.sort_by_key(|(id, _, _)| *id);
 
         if segments.is_empty() {
             return Self::new(base_dir, segment_size, config);
@@ -247,17 +240,22 @@ impl SegmentedWAL {
         let mut global_offset = 0;
         let mut next_segment_id = 0;
 
-        for (segment_id, segment_path) in &segments[..segments.len().saturating_sub(1)] {
-            let segment = WALSegment::recover(*segment_id, segment_path, global_offset)?;
+        // Offsets continue from the first segment left after trimming
+        for (segment_id, start_offset, segment_path) in &segments[..segments.len() - 1] {
+            let start_offset = start_offset.unwrap_or(global_offset);
+            let segment = WALSegment::recover(*segment_id, segment_path, start_offset)?;
             global_offset = segment.end_offset;
             completed_segments.insert(*segment_id, segment);
             next_segment_id = segment_id + 1;
         }
 
         // Last segment becomes active
-        let (last_segment_id, last_segment_path) = &segments[segments.len() - 1];
-        let active_segment =
-            WALSegment::recover(*last_segment_id, last_segment_path, global_offset)?;
+        let (last_segment_id, last_start_offset, last_segment_path) = &segments[segments.len() - 1];
+        let active_segment = WALSegment::recover(
+            *last_segment_id,
+            last_segment_path,
+            last_start_offset.unwrap_or(global_offset),
+        )?;
 
         if active_segment.end_offset > global_offset {
             global_offset = active_segment.end_offset;
@@ -295,8 +293,8 @@ impl SegmentedWAL {
         let new_segment_id = *segment_sequence;
         *segment_sequence += 1;
 
-        let segment_path = self.base_dir.join(format!("wal-{new_segment_id:010}.log"));
         let start_offset = *self.global_offset.lock().unwrap();
+        let segment_path = segment_file_path(&self.base_dir, new_segment_id, start_offset);
         let new_segment = WALSegment::new(new_segment_id, &segment_path, start_offset)?;
 
         let old_segment = std::mem::replace(&mut *active_segment, new_segment);
@@ -438,6 +436,15 @@ impl WriteAheadLog for SegmentedWAL {
     }
 
     fn trim_to(&self, offset: LogOffset) -> Result<(), StateError> {
+        // Seal the active segment when it is entirely covered, so it is trimmed too
+        let covered = {
+            let active_segment = self.active_segment.lock().unwrap();
+            active_segment.entry_count > 0 && active_segment.end_offset <= offset
+        };
+        if covered {
+            self.rotate_segment()?;
+        }
+
         let mut completed_segments = self.completed_segments.write().unwrap();
         let mut segments_to_remove = Vec::new();
 
@@ -460,7 +467,7 @@ impl WriteAheadLog for SegmentedWAL {
     }
 
     fn sync(&self) -> Result<(), StateError> {
-        let active_segment = self.active_segment.lock().unwrap();
+        let mut active_segment = self.active_segment.lock().unwrap();
         active_segment.sync()?;
 
         // Update metrics
@@ -567,6 +574,24 @@ impl SegmentedWAL {
     }
 }
 
+/// Path of a segment file; the name carries the segment's first offset so
+/// offsets survive trimming and restarts
+fn segment_file_path(base_dir: &Path, segment_id: u64, start_offset: LogOffset) -> PathBuf {
+    base_dir.join(format!("wal-{segment_id:010}-{start_offset:020}.log"))
+}
+
+/// Segment ID and, when recorded, first offset of a segment file name
+fn parse_segment_file_name(file_name: &str) -> Option<(u64, Option<LogOffset>)> {
+    let name = file_name.strip_prefix("wal-")?.strip_suffix(".log")?;
+    match name.split_once('-') {
+        Some((segment_id, start_offset)) => {
+            Some((segment_id.parse().ok()?, Some(start_offset.parse().ok()?)))
+        }
+        // Segments written before offsets were part of the name
+        None => Some((name.parse().ok()?, None)),
+    }
+}
+
 impl WALSegment {
     /// Create a new WAL segment
     fn new(segment_id: u64, file_path: &Path, start_offset: LogOffset) -> Result<Self, StateError> {
@@ -599,7 +624,7 @@ impl WALSegment {
     ) -> Result<Self, StateError> {
         let file = OpenOptions::new()
             .read(true)
-            .write(true)
+            .append(true)
             .open(file_path)
             .map_err(|e| StateError::InvalidStateData {
                 message: format!("Failed to open WAL segment file: {e}"),
@@ -773,8 +798,13 @@ impl WALSegment {
         Ok(())
     }
 
-    /// Sync segment to disk
-    fn sync(&self) -> Result<(), StateError> {
+    /// Flush buffered entries and sync the segment to disk
+    fn sync(&mut self) -> Result<(), StateError> {
+        self.file
+            .flush()
+            .map_err(|e| StateError::InvalidStateData {
+                message: format!("Failed to flush segment: {e}"),
+            })?;
         self.file
             .get_ref()
             .sync_all()
@@ -860,6 +890,31 @@ mod tests {
         assert_eq!(recovered_wal.tail_offset().unwrap(), 2);
     }
 
+    #[test]
+    fn test_trim_keeps_offsets_across_recovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = WALConfig::default();
+
+        {
+            let wal = SegmentedWAL::new(temp_dir.path(), 1024 * 1024, config.clone()).unwrap();
+            for i in 0..3 {
+                wal.append(create_test_entry("component1", i)).unwrap();
+            }
+
+            // Trimming past the tail drops the active segment's entries too
+            wal.trim_to(3).unwrap();
+            assert!(wal.completed_segments.read().unwrap().is_empty());
+            assert_eq!(wal.append(create_test_entry("component1", 3)).unwrap(), 3);
+        }
+
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        let recovered_wal = SegmentedWAL::recover(temp_dir.path(), 1024 * 1024, config).unwrap();
+        assert_eq!(recovered_wal.tail_offset().unwrap(), 4);
+        let entries = recovered_wal.read_from(3, 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].sequence, 3);
+    }
+
     #[test]
     fn test_segment_rotation() {
         let temp_dir = TempDir::new().unwrap();
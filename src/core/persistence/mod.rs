@@ -2,8 +2,10 @@
 
 // eventflux_rust/src/core/persistence/mod.rs
 
+pub mod checkpoint_runtime;
 pub mod data_source;
 pub mod persistence_store; // For PersistenceStore traits
+pub mod snapshot_runtime;
 pub mod snapshot_service;
 
 // Enhanced state management system (Phase 1)
@@ -14,11 +16,13 @@ pub mod state_registry;
 // Incremental checkpointing system (Phase 2)
 pub mod incremental;
 
+pub use self::checkpoint_runtime::CheckpointRuntime;
 pub use self::data_source::{DataSource, DataSourceConfig, SqliteDataSource};
 pub use self::persistence_store::{
     FilePersistenceStore, InMemoryPersistenceStore, IncrementalPersistenceStore, PersistenceStore,
     RedisPersistenceStore, SqlitePersistenceStore,
 };
+pub use self::snapshot_runtime::SnapshotRuntime;
 pub use self::snapshot_service::{PersistReport, SnapshotService};
 
 // Enhanced state management exports
@@ -40,7 +44,8 @@ pub use self::incremental::checkpoint_merger::MergerConfig;
 pub use self::incremental::recovery_engine::RecoveryConfig;
 pub use self::incremental::write_ahead_log::WALConfig;
 pub use self::incremental::{
-    CheckpointMerger, ClusterHealth, DistributedConfig, DistributedCoordinator,
-    IncrementalCheckpoint, IncrementalCheckpointConfig, LogEntry, LogOffset, PartitionStatus,
-    PersistenceBackend, PersistenceBackendConfig, RecoveryEngine, RecoveryPath, WriteAheadLog,
+    CheckpointMerger, CheckpointResult, ClusterHealth, DistributedConfig, DistributedCoordinator,
+    IncrementalCheckpoint, IncrementalCheckpointConfig, IncrementalCheckpointSystem, LogEntry,
+    LogOffset, PartitionStatus, PersistenceBackend, PersistenceBackendConfig, RecoveryEngine,
+    RecoveryPath, WriteAheadLog,
 };
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Periodic persistence of an application's snapshot.
+
+use chrono::Utc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::core::util::ThreadBarrier;
+
+use super::snapshot_service::SnapshotService;
+
+/// Persists the snapshot of an application on a fixed interval.
+///
+/// Event processing is paused while the state is captured. A failed
+/// snapshot is logged and retried on the next tick; the previous revision
+/// stays in the store.
+#[derive(Debug)]
+pub struct SnapshotRuntime {
+    service: Arc<SnapshotService>,
+    barrier: Option<Arc<ThreadBarrier>>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl SnapshotRuntime {
+    pub fn new(
+        service: Arc<SnapshotService>,
+        barrier: Option<Arc<ThreadBarrier>>,
+        interval_ms: i64,
+        scheduler: Arc<Scheduler>,
+    ) -> Self {
+        Self {
+            service,
+            barrier,
+            interval_ms,
+            scheduler,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let task = SnapshotTask {
+            service: Arc::clone(&self.service),
+            barrier: self.barrier.clone(),
+            interval_ms: self.interval_ms,
+            scheduler: Arc::clone(&self.scheduler),
+            running: Arc::clone(&self.running),
+        };
+        self.scheduler.notify_at(
+            Utc::now().timestamp_millis() + self.interval_ms,
+            Arc::new(task),
+        );
+    }
+
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SnapshotTask {
+    service: Arc<SnapshotService>,
+    barrier: Option<Arc<ThreadBarrier>>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl Schedulable for SnapshotTask {
+    fn on_time(&self, timestamp: i64) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        let persisted = match &self.barrier {
+            Some(barrier) => barrier.paused(|| self.service.persist()),
+            None => self.service.persist(),
+        };
+        match persisted {
+            Ok(report) => log::debug!(
+                "Persisted snapshot of '{}' (revision: {})",
+                self.service.eventflux_app_id,
+                report.revision
+            ),
+            Err(e) => log::error!(
+                "Failed to persist periodic snapshot of '{}': {}",
+                self.service.eventflux_app_id,
+                e
+            ),
+        }
+        self.scheduler
+            .notify_at(timestamp + self.interval_ms, Arc::new(self.clone()));
+    }
+}
@@ -73,6 +73,11 @@ impl SnapshotService {
         );
     }
 
+    /// Get the registered state holders keyed by component id.
+    pub fn get_state_holders(&self) -> HashMap<String, Arc<Mutex<dyn StateHolder>>> {
+        self.state_holders.lock().unwrap().clone()
+    }
+
     /// Retrieve a copy of the internal state.
     pub fn snapshot(&self) -> Vec<u8> {
         self.state.lock().unwrap().clone()
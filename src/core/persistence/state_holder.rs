@@ -233,6 +233,9 @@ pub trait StateHolder: Send + Sync {
     /// Apply incremental changes
     fn apply_changelog(&self, changes: &ChangeLog) -> Result<(), StateError>;
 
+    /// Acknowledge the first `operations` changes from `get_changelog` as durable in `checkpoint_id`
+    fn commit_changelog(&self, _checkpoint_id: CheckpointId, _operations: usize) {}
+
     /// Estimate state size for resource planning
     fn estimate_size(&self) -> StateSize;
 
@@ -94,15 +94,29 @@ impl TableJoinProcessor {
         event
     }
 
-    fn forward(&self, se: StreamEvent) {
-        if let Some(ref next) = self.next_processor {
-            next.lock().unwrap().process(Some(Box::new(se)));
+    /// Forward `events` as one chunk, so the selector orders and aggregates
+    /// all rows joined from an input chunk together
+    fn forward(&self, events: Vec<StreamEvent>) {
+        let Some(ref next) = self.next_processor else {
+            return;
+        };
+        let mut head: Option<Box<dyn ComplexEvent>> = None;
+        let mut tail = &mut head;
+        for event in events {
+            *tail = Some(Box::new(event));
+            if let Some(ref mut t) = *tail {
+                tail = t.mut_next_ref_option();
+            }
+        }
+        if head.is_some() {
+            next.lock().unwrap().process(head);
         }
     }
 }
 
 impl Processor for TableJoinProcessor {
     fn process(&self, mut chunk: Option<Box<dyn ComplexEvent>>) {
+        let mut joined_events = Vec::new();
         while let Some(mut ce) = chunk {
             chunk = ce.set_next(None);
             if let Some(se) = ce.as_any().downcast_ref::<StreamEvent>() {
@@ -115,25 +129,23 @@ impl Processor for TableJoinProcessor {
                         let mut matched = false;
                         for row in &rows {
                             matched = true;
-                            let joined = self.build_joined_event(se, Some(row));
-                            self.forward(joined);
+                            joined_events.push(self.build_joined_event(se, Some(row)));
                         }
                         if !matched && matches!(self.join_type, JoinType::LeftOuterJoin) {
-                            let joined = self.build_joined_event(se, None);
-                            self.forward(joined);
+                            joined_events.push(self.build_joined_event(se, None));
                         }
                     }
                     Err(e) => {
                         log::error!("Failed to find rows for join: {}", e);
                         // For left outer join, still emit the event without join data
                         if matches!(self.join_type, JoinType::LeftOuterJoin) {
-                            let joined = self.build_joined_event(se, None);
-                            self.forward(joined);
+                            joined_events.push(self.build_joined_event(se, None));
                         }
                     }
                 }
             }
         }
+        self.forward(joined_events);
     }
 
     fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
@@ -12,8 +12,8 @@ pub mod insert_into_stream_processor;
 pub mod insert_into_table_processor;
 pub mod update_table_processor;
 // pub mod update_or_insert_stream_processor; // For UPDATE OR INSERT
-// pub mod output_rate_limiter; // Core engine's rate limiter
-pub mod callback_processor; // Added
+pub mod callback_processor;
+pub mod ratelimit; // Time and snapshot output rate limiters
 
 // Note: core::stream::output::StreamCallback is for external callbacks on streams.
 // Query-specific output callbacks (QueryCallback in Java) might also go here or a sub-module.
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Core output rate limiters for time-based and snapshot `OUTPUT ... EVERY` clauses.
+//!
+//! Event-count based limiting is handled by
+//! [`OutputRateLimiter`](crate::core::query::selector::select_processor::OutputRateLimiter).
+
+pub mod snapshot_output_rate_limiter;
+pub mod time_output_rate_limiter;
+
+pub use self::snapshot_output_rate_limiter::SnapshotOutputRateLimiter;
+pub use self::time_output_rate_limiter::TimeOutputRateLimiter;
+
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::query::processor::Processor;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Output row retained by a limiter between flushes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BufferedRow {
+    pub timestamp: i64,
+    pub data: Vec<AttributeValue>,
+}
+
+impl BufferedRow {
+    pub(crate) fn from_event(event: &dyn ComplexEvent) -> Self {
+        Self {
+            timestamp: event.get_timestamp(),
+            data: event.get_output_data().map_or(Vec::new(), |d| d.to_vec()),
+        }
+    }
+
+    /// Rebuild a CURRENT output event carrying this row
+    pub(crate) fn to_event(&self, timestamp: i64) -> StreamEvent {
+        let mut se = StreamEvent::new(timestamp, 0, 0, self.data.len());
+        se.output_data = Some(self.data.clone());
+        se
+    }
+}
+
+/// Link rows into an event chain and hand it to `next`
+pub(crate) fn emit_rows(
+    next: &Option<Arc<Mutex<dyn Processor>>>,
+    rows: &[BufferedRow],
+    timestamp: Option<i64>,
+) {
+    if rows.is_empty() {
+        return;
+    }
+    if let Some(ref next) = next {
+        let mut head: Option<Box<dyn ComplexEvent>> = None;
+        let mut tail = &mut head;
+        for row in rows {
+            *tail = Some(Box::new(row.to_event(timestamp.unwrap_or(row.timestamp))));
+            if let Some(ref mut t) = *tail {
+                tail = t.mut_next_ref_option();
+            }
+        }
+        next.lock().unwrap().process(head);
+    }
+}
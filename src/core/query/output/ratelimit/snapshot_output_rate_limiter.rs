@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Snapshot output rate limiter (`OUTPUT SNAPSHOT EVERY <duration>`)
+//!
+//! Instead of forwarding individual results, the limiter keeps the query's
+//! current result set and emits all of it once per period:
+//!
+//! - Aggregating queries keep the latest output row per group-by key
+//!   (a single row when there is no GROUP BY). A group is dropped once all of
+//!   its events have expired from the window
+//! - Non-aggregating queries over a window keep every row currently in the
+//!   window; EXPIRED events remove the matching row
+//! - Non-aggregating queries without a window keep only the last row
+
+use super::{emit_rows, BufferedRow};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::persistence::{
+    AccessPattern, ChangeLog, CheckpointId, CompressionType, SchemaVersion, SerializationHints,
+    StateError, StateHolder, StateMetadata, StateSize, StateSnapshot,
+};
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::query::selector::GroupByKeyGenerator;
+use crate::core::util::eventflux_constants::KEY_DELIMITER;
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Row held for a group and the number of its events still in the window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct GroupRow {
+    seq: u64,
+    live: u64,
+}
+
+/// Current result set retained between snapshots
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnapshotState {
+    /// Rows in first-seen order, keyed by insertion sequence
+    rows: BTreeMap<u64, BufferedRow>,
+    /// Row held for each group key (aggregating queries)
+    groups: HashMap<String, GroupRow>,
+    /// Sequences of the rows with each value key, oldest first (windowed queries)
+    by_value: HashMap<String, VecDeque<u64>>,
+    next_seq: u64,
+    /// Whether the periodic snapshot task has been started (runtime only)
+    #[serde(skip)]
+    started: bool,
+    /// Timestamp of the next snapshot (used when no scheduler is available)
+    next_snapshot: Option<i64>,
+}
+
+impl SnapshotState {
+    fn push(&mut self, row: BufferedRow) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.rows.insert(seq, row);
+        seq
+    }
+
+    /// Replace the group's row, dropping the group once none of its events remain
+    fn upsert(&mut self, key: String, row: BufferedRow, expired: bool) {
+        let Some(group) = self.groups.get_mut(&key) else {
+            if !expired {
+                let seq = self.push(row);
+                self.groups.insert(key, GroupRow { seq, live: 1 });
+            }
+            return;
+        };
+        if expired {
+            group.live = group.live.saturating_sub(1);
+        } else {
+            group.live += 1;
+        }
+        let GroupRow { seq, live } = *group;
+        if live == 0 {
+            self.groups.remove(&key);
+            self.rows.remove(&seq);
+        } else {
+            self.rows.insert(seq, row);
+        }
+    }
+
+    fn push_windowed(&mut self, row: BufferedRow) {
+        let key = value_key(&row);
+        let seq = self.push(row);
+        self.by_value.entry(key).or_default().push_back(seq);
+    }
+
+    /// Remove the oldest row with the same values as `row`
+    fn remove_windowed(&mut self, row: &BufferedRow) {
+        let key = value_key(row);
+        let Some(seqs) = self.by_value.get_mut(&key) else {
+            return;
+        };
+        if let Some(seq) = seqs.pop_front() {
+            self.rows.remove(&seq);
+        }
+        if seqs.is_empty() {
+            self.by_value.remove(&key);
+        }
+    }
+
+    fn replace_last(&mut self, row: BufferedRow) {
+        self.rows.clear();
+        self.push(row);
+    }
+
+    fn snapshot_rows(&self) -> Vec<BufferedRow> {
+        self.rows.values().cloned().collect()
+    }
+}
+
+fn value_key(row: &BufferedRow) -> String {
+    row.data
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(KEY_DELIMITER)
+}
+
+#[derive(Debug)]
+pub struct SnapshotOutputRateLimiter {
+    meta: CommonProcessorMeta,
+    period_ms: i64,
+    /// True when the selector contains aggregators (keep latest row per group)
+    aggregated: bool,
+    /// True when the query reads from a window, so EXPIRED events retract rows
+    windowed: bool,
+    group_by_key_generator: Option<Arc<GroupByKeyGenerator>>,
+    scheduler: Option<Arc<Scheduler>>,
+    state: Arc<Mutex<SnapshotState>>,
+}
+
+impl SnapshotOutputRateLimiter {
+    pub fn new(
+        eventflux_app_context: Arc<EventFluxAppContext>,
+        eventflux_query_context: Arc<EventFluxQueryContext>,
+        period_ms: i64,
+        aggregated: bool,
+        windowed: bool,
+        group_by_key_generator: Option<Arc<GroupByKeyGenerator>>,
+    ) -> Self {
+        // In playback mode event timestamps drive the period instead of the wall clock
+        let scheduler = if eventflux_app_context.is_playback() {
+            None
+        } else {
+            eventflux_app_context.get_scheduler()
+        };
+        let state = Arc::new(Mutex::new(SnapshotState::default()));
+        let holder = Arc::new(Mutex::new(SnapshotOutputRateLimiterStateHolder {
+            state: Arc::clone(&state),
+        }));
+        eventflux_query_context
+            .register_state_holder("snapshot_output_rate_limiter".into(), holder);
+        Self {
+            meta: CommonProcessorMeta::new(eventflux_app_context, eventflux_query_context),
+            period_ms,
+            aggregated,
+            windowed,
+            group_by_key_generator,
+            scheduler,
+            state,
+        }
+    }
+
+    fn snapshot_task(&self) -> SnapshotTask {
+        SnapshotTask {
+            state: Arc::clone(&self.state),
+            next: self.meta.next_processor.as_ref().map(Arc::clone),
+            period_ms: self.period_ms,
+            scheduler: self.scheduler.as_ref().map(Arc::clone),
+        }
+    }
+
+    fn group_key(&self, event: &dyn ComplexEvent) -> String {
+        self.group_by_key_generator
+            .as_ref()
+            .and_then(|g| g.construct_event_key(event))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone)]
+struct SnapshotTask {
+    state: Arc<Mutex<SnapshotState>>,
+    next: Option<Arc<Mutex<dyn Processor>>>,
+    period_ms: i64,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl SnapshotTask {
+    fn emit_snapshot(&self, timestamp: i64) {
+        let rows = {
+            let mut state = self.state.lock().unwrap();
+            state.next_snapshot = Some(timestamp + self.period_ms);
+            state.snapshot_rows()
+        };
+        emit_rows(&self.next, &rows, Some(timestamp));
+    }
+}
+
+impl Schedulable for SnapshotTask {
+    fn on_time(&self, timestamp: i64) {
+        self.emit_snapshot(timestamp);
+        if let Some(ref scheduler) = self.scheduler {
+            scheduler.notify_at(timestamp + self.period_ms, Arc::new(self.clone()));
+        }
+    }
+}
+
+impl Processor for SnapshotOutputRateLimiter {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let mut current = complex_event_chunk;
+
+        while let Some(mut ev) = current {
+            current = ev.set_next(None);
+            let event_type = ev.get_event_type();
+            if !matches!(
+                event_type,
+                ComplexEventType::Current | ComplexEventType::Expired
+            ) {
+                continue;
+            }
+            let timestamp = ev.get_timestamp();
+            let row = BufferedRow::from_event(ev.as_ref());
+            let key = if self.aggregated {
+                Some(self.group_key(ev.as_ref()))
+            } else {
+                None
+            };
+
+            let (start, due) = {
+                let mut state = self.state.lock().unwrap();
+                let start = !state.started;
+                if start {
+                    state.started = true;
+                    state.next_snapshot = Some(timestamp + self.period_ms);
+                }
+                let due = match state.next_snapshot {
+                    Some(at) if self.scheduler.is_none() && timestamp >= at => Some(at),
+                    _ => None,
+                };
+                (start, due)
+            };
+
+            // Without a scheduler (playback), event timestamps drive the snapshots
+            if let Some(at) = due {
+                self.snapshot_task().emit_snapshot(at);
+                // After a gap of several periods, wait for the first boundary
+                // past this event rather than one period after `at`
+                let periods = (timestamp - at).checked_div(self.period_ms).unwrap_or(0) + 1;
+                self.state.lock().unwrap().next_snapshot = Some(at + periods * self.period_ms);
+            }
+            if start {
+                if let Some(ref scheduler) = self.scheduler {
+                    scheduler.notify_at(timestamp + self.period_ms, Arc::new(self.snapshot_task()));
+                }
+            }
+
+            let expired = event_type == ComplexEventType::Expired;
+            let mut state = self.state.lock().unwrap();
+            match key {
+                // Aggregated rows (including those produced by expiry) replace the group's row
+                Some(key) => state.upsert(key, row, expired),
+                None if !self.windowed => state.replace_last(row),
+                None if expired => state.remove_windowed(&row),
+                None => state.push_windowed(row),
+            }
+        }
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next_processor: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next_processor;
+    }
+
+    fn clone_processor(
+        &self,
+        eventflux_query_context: &Arc<EventFluxQueryContext>,
+    ) -> Box<dyn Processor> {
+        Box::new(SnapshotOutputRateLimiter::new(
+            Arc::clone(&self.meta.eventflux_app_context),
+            Arc::clone(eventflux_query_context),
+            self.period_ms,
+            self.aggregated,
+            self.windowed,
+            self.group_by_key_generator.as_ref().map(Arc::clone),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::BATCH
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+struct SnapshotOutputRateLimiterStateHolder {
+    state: Arc<Mutex<SnapshotState>>,
+}
+
+impl StateHolder for SnapshotOutputRateLimiterStateHolder {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion::new(1, 0, 0)
+    }
+
+    fn serialize_state(&self, _hints: &SerializationHints) -> Result<StateSnapshot, StateError> {
+        let data = {
+            let state = self.state.lock().unwrap();
+            crate::core::util::to_bytes(&*state).map_err(|e| StateError::SerializationError {
+                message: format!("Failed to serialize SnapshotOutputRateLimiter state: {e}"),
+            })?
+        };
+        let checksum = StateSnapshot::calculate_checksum(&data);
+        Ok(StateSnapshot {
+            version: self.schema_version(),
+            checkpoint_id: 0,
+            data,
+            compression: CompressionType::None,
+            checksum,
+            metadata: self.component_metadata(),
+        })
+    }
+
+    fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+        if !snapshot.verify_integrity() {
+            return Err(StateError::ChecksumMismatch);
+        }
+        let mut restored: SnapshotState =
+            crate::core::util::from_bytes(&snapshot.data).map_err(|e| {
+                StateError::DeserializationError {
+                    message: format!("Failed to deserialize SnapshotOutputRateLimiter state: {e}"),
+                }
+            })?;
+        let mut state = self.state.lock().unwrap();
+        // Keep the running task's bookkeeping; only the result set is restored
+        restored.started = state.started;
+        *state = restored;
+        Ok(())
+    }
+
+    fn get_changelog(&self, _since: CheckpointId) -> Result<ChangeLog, StateError> {
+        Err(StateError::SerializationError {
+            message: "SnapshotOutputRateLimiter doesn't support incremental checkpointing"
+                .to_string(),
+        })
+    }
+
+    fn apply_changelog(&self, _changes: &ChangeLog) -> Result<(), StateError> {
+        Err(StateError::DeserializationError {
+            message: "SnapshotOutputRateLimiter doesn't support incremental changes".to_string(),
+        })
+    }
+
+    fn estimate_size(&self) -> StateSize {
+        let rows = self.state.lock().unwrap().rows.len();
+        StateSize {
+            bytes: rows * std::mem::size_of::<(u64, BufferedRow)>(),
+            entries: rows,
+            estimated_growth_rate: 0.0,
+        }
+    }
+
+    fn access_pattern(&self) -> AccessPattern {
+        AccessPattern::Hot
+    }
+
+    fn component_metadata(&self) -> StateMetadata {
+        StateMetadata::new(
+            "snapshot_output_rate_limiter".to_string(),
+            "SnapshotOutputRateLimiter".to_string(),
+        )
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Time-based output rate limiter (`OUTPUT [ALL|FIRST|LAST] EVERY <duration>`)
+
+use super::{emit_rows, BufferedRow};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::persistence::{
+    AccessPattern, ChangeLog, CheckpointId, CompressionType, SchemaVersion, SerializationHints,
+    StateError, StateHolder, StateMetadata, StateSize, StateSnapshot,
+};
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::query_api::execution::query::output::ratelimit::OutputRateBehavior;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Mutable limiter state shared with the flush task and the state holder
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TimeLimiterState {
+    rows: Vec<BufferedRow>,
+    /// Whether FIRST behavior already emitted an event in the current period
+    first_emitted: bool,
+    /// Timestamp at which the current period ends
+    next_flush: Option<i64>,
+    /// Whether the periodic flush task has been started (runtime only)
+    #[serde(skip)]
+    started: bool,
+}
+
+/// Emits buffered output once per period.
+///
+/// - `ALL` buffers every CURRENT event and emits them at the end of the period
+/// - `LAST` keeps only the most recent event of the period
+/// - `FIRST` emits the first event of a period immediately and drops the rest
+#[derive(Debug)]
+pub struct TimeOutputRateLimiter {
+    meta: CommonProcessorMeta,
+    period_ms: i64,
+    behavior: OutputRateBehavior,
+    scheduler: Option<Arc<Scheduler>>,
+    state: Arc<Mutex<TimeLimiterState>>,
+}
+
+impl TimeOutputRateLimiter {
+    pub fn new(
+        eventflux_app_context: Arc<EventFluxAppContext>,
+        eventflux_query_context: Arc<EventFluxQueryContext>,
+        period_ms: i64,
+        behavior: OutputRateBehavior,
+    ) -> Self {
+        // In playback mode event timestamps drive the period instead of the wall clock
+        let scheduler = if eventflux_app_context.is_playback() {
+            None
+        } else {
+            eventflux_app_context.get_scheduler()
+        };
+        let state = Arc::new(Mutex::new(TimeLimiterState::default()));
+        let holder = Arc::new(Mutex::new(TimeOutputRateLimiterStateHolder {
+            state: Arc::clone(&state),
+        }));
+        eventflux_query_context.register_state_holder("time_output_rate_limiter".into(), holder);
+        Self {
+            meta: CommonProcessorMeta::new(eventflux_app_context, eventflux_query_context),
+            period_ms,
+            behavior,
+            scheduler,
+            state,
+        }
+    }
+
+    fn flush_task(&self) -> TimeFlushTask {
+        TimeFlushTask {
+            state: Arc::clone(&self.state),
+            next: self.meta.next_processor.as_ref().map(Arc::clone),
+            period_ms: self.period_ms,
+            scheduler: self.scheduler.as_ref().map(Arc::clone),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TimeFlushTask {
+    state: Arc<Mutex<TimeLimiterState>>,
+    next: Option<Arc<Mutex<dyn Processor>>>,
+    period_ms: i64,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl TimeFlushTask {
+    fn flush(&self, timestamp: i64) {
+        let rows = {
+            let mut state = self.state.lock().unwrap();
+            state.first_emitted = false;
+            state.next_flush = Some(timestamp + self.period_ms);
+            std::mem::take(&mut state.rows)
+        };
+        emit_rows(&self.next, &rows, Some(timestamp));
+    }
+}
+
+impl Schedulable for TimeFlushTask {
+    fn on_time(&self, timestamp: i64) {
+        self.flush(timestamp);
+        if let Some(ref scheduler) = self.scheduler {
+            scheduler.notify_at(timestamp + self.period_ms, Arc::new(self.clone()));
+        }
+    }
+}
+
+impl Processor for TimeOutputRateLimiter {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let mut current = complex_event_chunk;
+        let mut immediate = Vec::new();
+
+        while let Some(mut ev) = current {
+            current = ev.set_next(None);
+            if ev.get_event_type() != ComplexEventType::Current {
+                continue;
+            }
+            let timestamp = ev.get_timestamp();
+
+            let (start, due) = {
+                let mut state = self.state.lock().unwrap();
+                let start = !state.started;
+                if start {
+                    state.started = true;
+                    state.next_flush = Some(timestamp + self.period_ms);
+                }
+                let due = match state.next_flush {
+                    Some(at) if self.scheduler.is_none() && timestamp >= at => Some(at),
+                    _ => None,
+                };
+                (start, due)
+            };
+
+            // Without a scheduler (playback), event timestamps drive the flush
+            if let Some(at) = due {
+                self.flush_task().flush(at);
+                // After a gap of several periods, wait for the first boundary
+                // past this event rather than one period after `at`
+                let periods = (timestamp - at).checked_div(self.period_ms).unwrap_or(0) + 1;
+                self.state.lock().unwrap().next_flush = Some(at + periods * self.period_ms);
+            }
+            if start {
+                if let Some(ref scheduler) = self.scheduler {
+                    scheduler.notify_at(timestamp + self.period_ms, Arc::new(self.flush_task()));
+                }
+            }
+
+            let mut state = self.state.lock().unwrap();
+            match self.behavior {
+                OutputRateBehavior::All => state.rows.push(BufferedRow::from_event(ev.as_ref())),
+                OutputRateBehavior::Last => {
+                    state.rows.clear();
+                    state.rows.push(BufferedRow::from_event(ev.as_ref()));
+                }
+                OutputRateBehavior::First => {
+                    if !state.first_emitted {
+                        state.first_emitted = true;
+                        immediate.push(BufferedRow::from_event(ev.as_ref()));
+                    }
+                }
+            }
+        }
+
+        emit_rows(&self.meta.next_processor, &immediate, None);
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next_processor: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next_processor;
+    }
+
+    fn clone_processor(
+        &self,
+        eventflux_query_context: &Arc<EventFluxQueryContext>,
+    ) -> Box<dyn Processor> {
+        Box::new(TimeOutputRateLimiter::new(
+            Arc::clone(&self.meta.eventflux_app_context),
+            Arc::clone(eventflux_query_context),
+            self.period_ms,
+            self.behavior,
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::BATCH
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+struct TimeOutputRateLimiterStateHolder {
+    state: Arc<Mutex<TimeLimiterState>>,
+}
+
+impl StateHolder for TimeOutputRateLimiterStateHolder {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion::new(1, 0, 0)
+    }
+
+    fn serialize_state(&self, _hints: &SerializationHints) -> Result<StateSnapshot, StateError> {
+        let data = {
+            let state = self.state.lock().unwrap();
+            crate::core::util::to_bytes(&*state).map_err(|e| StateError::SerializationError {
+                message: format!("Failed to serialize TimeOutputRateLimiter state: {e}"),
+            })?
+        };
+        let checksum = StateSnapshot::calculate_checksum(&data);
+        Ok(StateSnapshot {
+            version: self.schema_version(),
+            checkpoint_id: 0,
+            data,
+            compression: CompressionType::None,
+            checksum,
+            metadata: self.component_metadata(),
+        })
+    }
+
+    fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+        if !snapshot.verify_integrity() {
+            return Err(StateError::ChecksumMismatch);
+        }
+        let mut restored: TimeLimiterState = crate::core::util::from_bytes(&snapshot.data)
+            .map_err(|e| StateError::DeserializationError {
+                message: format!("Failed to deserialize TimeOutputRateLimiter state: {e}"),
+            })?;
+        let mut state = self.state.lock().unwrap();
+        // Keep the running task's bookkeeping; only the buffered output is restored
+        restored.started = state.started;
+        *state = restored;
+        Ok(())
+    }
+
+    fn get_changelog(&self, _since: CheckpointId) -> Result<ChangeLog, StateError> {
+        Err(StateError::SerializationError {
+            message: "TimeOutputRateLimiter doesn't support incremental checkpointing".to_string(),
+        })
+    }
+
+    fn apply_changelog(&self, _changes: &ChangeLog) -> Result<(), StateError> {
+        Err(StateError::DeserializationError {
+            message: "TimeOutputRateLimiter doesn't support incremental changes".to_string(),
+        })
+    }
+
+    fn estimate_size(&self) -> StateSize {
+        let rows = self.state.lock().unwrap().rows.len();
+        StateSize {
+            bytes: rows * std::mem::size_of::<BufferedRow>(),
+            entries: rows,
+            estimated_growth_rate: 0.0,
+        }
+    }
+
+    fn access_pattern(&self) -> AccessPattern {
+        AccessPattern::Hot
+    }
+
+    fn component_metadata(&self) -> StateMetadata {
+        StateMetadata::new(
+            "time_output_rate_limiter".to_string(),
+            "TimeOutputRateLimiter".to_string(),
+        )
+    }
+}
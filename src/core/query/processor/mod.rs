@@ -7,6 +7,7 @@
 use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::config::eventflux_query_context::EventFluxQueryContext;
 use crate::core::event::complex_event::ComplexEvent;
+use crate::core::query::processor::stream::window::WindowContents;
 // MetaStreamEvent and ApiAbstractDefinition were commented out, keep as is for now.
 // use crate::core::event::stream::meta_stream_event::MetaStreamEvent;
 // use crate::query_api::definition::AbstractDefinition as ApiAbstractDefinition;
@@ -68,6 +69,12 @@ pub trait Processor: Debug + Send + Sync {
     fn clear_group_states(&self) {
         // Default implementation does nothing
     }
+
+    /// View of the events a window processor holds; `None` for processors
+    /// that do not buffer events
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        None
+    }
 }
 
 // Declare submodules within processor directory
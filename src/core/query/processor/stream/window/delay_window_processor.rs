@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/delay_window_processor.rs
+
+//! Delay window: `delay(duration)`
+//!
+//! Holds each event back for `duration` and then releases it downstream as a
+//! CURRENT event. Released events are not kept, so the window never emits
+//! EXPIRED events. Releases are timed by the app's scheduler; events restored
+//! from a snapshot are scheduled again when the state is restored.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot_window_state_holder::{SnapshotWindowStateHolder, WindowState};
+use super::types::WINDOW_TYPE_DELAY;
+use super::{buffer_view, duration_param, emit_events, WindowContents, WindowProcessor};
+use crate::core::config::{
+    eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
+};
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::extension::WindowProcessorFactory;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::util::parser::expression_parser::ExpressionParserContext;
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::query_api::execution::query::input::handler::WindowHandler;
+
+/// Events waiting to be released, in arrival order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DelayWindowState {
+    pending: VecDeque<StreamEvent>,
+}
+
+impl WindowState for DelayWindowState {
+    fn entries(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct DelayWindowProcessor {
+    meta: CommonProcessorMeta,
+    pub delay_ms: i64,
+    release: Arc<DelayRelease>,
+}
+
+impl DelayWindowProcessor {
+    pub fn new(
+        delay_ms: i64,
+        scheduler: Arc<Scheduler>,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Self {
+        let release = Arc::new(DelayRelease {
+            state: Arc::new(Mutex::new(DelayWindowState::default())),
+            next: RwLock::new(None),
+            scheduler,
+            delay_ms,
+        });
+        let restored = Arc::clone(&release);
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&release.state),
+            format!("delay_window_{}_{}", query_ctx.get_name(), delay_ms),
+            WINDOW_TYPE_DELAY,
+        )
+        .on_restore(move || restored.schedule_pending())
+        .register(&query_ctx);
+
+        Self {
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            delay_ms,
+            release,
+        }
+    }
+
+    pub fn from_handler(
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        _parse_ctx: &ExpressionParserContext,
+    ) -> Result<Self, String> {
+        let delay = duration_param(WINDOW_TYPE_DELAY, handler.get_parameters().first())?;
+        let scheduler = app_ctx
+            .get_scheduler()
+            .ok_or_else(|| format!("{WINDOW_TYPE_DELAY} window requires a scheduler"))?;
+        Ok(Self::new(delay, scheduler, app_ctx, query_ctx))
+    }
+}
+
+/// Releases every pending event whose delay has elapsed
+///
+/// Release is driven by time rather than by a particular event, so one
+/// notification releases every event that is due.
+#[derive(Debug)]
+struct DelayRelease {
+    state: Arc<Mutex<DelayWindowState>>,
+    next: RwLock<Option<Arc<Mutex<dyn Processor>>>>,
+    scheduler: Arc<Scheduler>,
+    delay_ms: i64,
+}
+
+impl DelayRelease {
+    /// Schedule the release of an event that arrived at `timestamp`
+    fn schedule(self: &Arc<Self>, timestamp: i64) {
+        self.scheduler.notify_at(
+            timestamp + self.delay_ms,
+            Arc::clone(self) as Arc<dyn Schedulable>,
+        );
+    }
+
+    /// Schedule the release of every pending event, after a restore
+    fn schedule_pending(self: &Arc<Self>) {
+        let mut arrivals: Vec<i64> = {
+            let state = self.state.lock().unwrap();
+            state.pending.iter().map(|e| e.timestamp).collect()
+        };
+        arrivals.dedup();
+        for timestamp in arrivals {
+            self.schedule(timestamp);
+        }
+    }
+}
+
+impl Schedulable for DelayRelease {
+    fn on_time(&self, timestamp: i64) {
+        let Some(next) = self.next.read().unwrap().clone() else {
+            return;
+        };
+        let released: Vec<StreamEvent> = {
+            let mut state = self.state.lock().unwrap();
+            let due = state
+                .pending
+                .iter()
+                .take_while(|e| e.timestamp + self.delay_ms <= timestamp)
+                .count();
+            state.pending.drain(..due).collect()
+        };
+        emit_events(&next, released);
+    }
+}
+
+impl Processor for DelayWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        if self.meta.next_processor.is_none() {
+            return;
+        }
+        let mut current_opt = complex_event_chunk.as_deref();
+        while let Some(ev) = current_opt {
+            if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                self.release
+                    .state
+                    .lock()
+                    .unwrap()
+                    .pending
+                    .push_back(se.clone_without_next());
+                self.release.schedule(se.timestamp);
+            }
+            current_opt = ev.get_next();
+        }
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        *self.release.next.write().unwrap() = next.clone();
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        Box::new(Self::new(
+            self.delay_ms,
+            Arc::clone(&self.release.scheduler),
+            Arc::clone(&self.meta.eventflux_app_context),
+            Arc::clone(query_ctx),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::DEFAULT
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.release.state, |state| {
+            state
+                .pending
+                .iter()
+                .map(StreamEvent::clone_without_next)
+                .collect()
+        }))
+    }
+}
+
+impl WindowProcessor for DelayWindowProcessor {}
+
+#[derive(Debug, Clone)]
+pub struct DelayWindowFactory;
+
+impl WindowProcessorFactory for DelayWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_DELAY
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(DelayWindowProcessor::from_handler(
+            handler, app_ctx, query_ctx, parse_ctx,
+        )?)))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/expression_window_processor.rs
+
+//! Expression window: `expression(condition)`
+//!
+//! Keeps events for as long as the boolean `condition` holds over the window,
+//! e.g. `expression(count() <= 10)` or `expression(sum(price) < 1000)`.
+//! Aggregates in the condition see each event as it enters (CURRENT) and as it
+//! leaves (EXPIRED), so after every arrival the oldest events are expired until
+//! the condition is true again.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot_window_state_holder::{SnapshotWindowStateHolder, WindowState};
+use super::types::WINDOW_TYPE_EXPRESSION;
+use super::{buffer_view, emit_events, expired_copy, WindowContents, WindowProcessor};
+use crate::core::config::{
+    eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
+};
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::WindowProcessorFactory;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::util::parser::expression_parser::{parse_expression, ExpressionParserContext};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::execution::query::input::handler::WindowHandler;
+
+/// Events currently in the window, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExpressionWindowState {
+    events: VecDeque<StreamEvent>,
+}
+
+impl WindowState for ExpressionWindowState {
+    fn entries(&self) -> usize {
+        self.events.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct ExpressionWindowProcessor {
+    meta: CommonProcessorMeta,
+    condition: Box<dyn ExpressionExecutor>,
+    state: Arc<Mutex<ExpressionWindowState>>,
+}
+
+impl ExpressionWindowProcessor {
+    pub fn new(
+        condition: Box<dyn ExpressionExecutor>,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(ExpressionWindowState::default()));
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&state),
+            format!("expression_window_{}", query_ctx.get_name()),
+            WINDOW_TYPE_EXPRESSION,
+        )
+        .register(&query_ctx);
+
+        Self {
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            condition,
+            state,
+        }
+    }
+
+    pub fn from_handler(
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Self, String> {
+        let expr = handler
+            .get_parameters()
+            .first()
+            .ok_or("Expression window requires a condition")?;
+        let condition = parse_expression(expr, parse_ctx)
+            .map_err(|e| format!("Failed to parse expression window condition: {e}"))?;
+        if condition.get_return_type() != ApiAttributeType::BOOL {
+            return Err("Expression window condition must be boolean".to_string());
+        }
+        Ok(Self::new(condition, app_ctx, query_ctx))
+    }
+
+    fn holds(&self, event: &StreamEvent) -> bool {
+        matches!(
+            self.condition.execute(Some(event)),
+            Some(AttributeValue::Bool(true))
+        )
+    }
+
+    fn admit(
+        &self,
+        state: &mut ExpressionWindowState,
+        se: &StreamEvent,
+        out: &mut Vec<StreamEvent>,
+    ) {
+        let current = se.clone_without_next();
+        let mut satisfied = self.holds(&current);
+        state.events.push_back(current.clone_without_next());
+        out.push(current);
+
+        while !satisfied {
+            let Some(oldest) = state.events.pop_front() else {
+                break;
+            };
+            let expired = expired_copy(&oldest, se.timestamp);
+            satisfied = self.holds(&expired);
+            out.push(expired);
+        }
+    }
+}
+
+impl Processor for ExpressionWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let Some(ref next) = self.meta.next_processor else {
+            return;
+        };
+        let mut out = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let mut current_opt = complex_event_chunk.as_deref();
+            while let Some(ev) = current_opt {
+                if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                    self.admit(&mut state, se, &mut out);
+                }
+                current_opt = ev.get_next();
+            }
+        }
+        emit_events(next, out);
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        let app_ctx = Arc::clone(&self.meta.eventflux_app_context);
+        Box::new(Self::new(
+            self.condition.clone_executor(&app_ctx),
+            app_ctx,
+            Arc::clone(query_ctx),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::SLIDE
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.state, |state| {
+            state
+                .events
+                .iter()
+                .map(StreamEvent::clone_without_next)
+                .collect()
+        }))
+    }
+}
+
+impl WindowProcessor for ExpressionWindowProcessor {}
+
+#[derive(Debug, Clone)]
+pub struct ExpressionWindowFactory;
+
+impl WindowProcessorFactory for ExpressionWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_EXPRESSION
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(
+            ExpressionWindowProcessor::from_handler(handler, app_ctx, query_ctx, parse_ctx)?,
+        )))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
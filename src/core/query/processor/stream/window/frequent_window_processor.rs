@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/frequent_window_processor.rs
+
+//! Frequent window: `frequent(count, attr, ...)`
+//!
+//! Tracks the approximately most frequent keys with the Misra-Gries algorithm,
+//! holding the latest event of at most `count` keys. When an unseen key
+//! arrives while the window is full, its occurrence cancels one occurrence of
+//! every tracked key: each counter is decremented and keys that reach zero are
+//! expired, least recently seen first. The new event takes a slot freed this
+//! way and is dropped when no counter reached zero. Without key attributes the
+//! whole event is the key.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot_window_state_holder::{SnapshotWindowStateHolder, WindowState};
+use super::types::WINDOW_TYPE_FREQUENT;
+use super::{
+    buffer_view, emit_events, expired_copy, parse_key_executors, positive_int_param,
+    WindowContents, WindowProcessor,
+};
+use crate::core::config::{
+    eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
+};
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::WindowProcessorFactory;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::query::selector::GroupByKeyGenerator;
+use crate::core::util::eventflux_constants::KEY_DELIMITER;
+use crate::core::util::parser::expression_parser::ExpressionParserContext;
+use crate::query_api::execution::query::input::handler::WindowHandler;
+
+/// Counter and latest event of a tracked key
+#[derive(Debug, Serialize, Deserialize)]
+struct FrequentEntry {
+    key: String,
+    count: u64,
+    event: StreamEvent,
+}
+
+/// Tracked keys, ordered by when each key was last seen
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FrequentWindowState {
+    entries: BTreeMap<u64, FrequentEntry>,
+    /// Sequence number of each tracked key's entry
+    keys: HashMap<String, u64>,
+    next_seq: u64,
+}
+
+impl FrequentWindowState {
+    fn push(&mut self, entry: FrequentEntry) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.keys.insert(entry.key.clone(), seq);
+        self.entries.insert(seq, entry);
+    }
+
+    fn take(&mut self, key: &str) -> Option<FrequentEntry> {
+        let seq = self.keys.remove(key)?;
+        self.entries.remove(&seq)
+    }
+}
+
+impl WindowState for FrequentWindowState {
+    fn entries(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct FrequentWindowProcessor {
+    meta: CommonProcessorMeta,
+    most_frequent_count: usize,
+    key_executors: Vec<Box<dyn ExpressionExecutor>>,
+    key_generator: GroupByKeyGenerator,
+    state: Arc<Mutex<FrequentWindowState>>,
+}
+
+impl FrequentWindowProcessor {
+    pub fn new(
+        most_frequent_count: usize,
+        key_executors: Vec<Box<dyn ExpressionExecutor>>,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(FrequentWindowState::default()));
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&state),
+            format!(
+                "frequent_window_{}_{}",
+                query_ctx.get_name(),
+                most_frequent_count
+            ),
+            WINDOW_TYPE_FREQUENT,
+        )
+        .register(&query_ctx);
+
+        let key_generator = GroupByKeyGenerator::new(
+            key_executors
+                .iter()
+                .map(|e| e.clone_executor(&app_ctx))
+                .collect(),
+        );
+        Self {
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            most_frequent_count,
+            key_executors,
+            key_generator,
+            state,
+        }
+    }
+
+    pub fn from_handler(
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Self, String> {
+        let params = handler.get_parameters();
+        let count = positive_int_param(WINDOW_TYPE_FREQUENT, params.first())?;
+        let key_executors = parse_key_executors(
+            WINDOW_TYPE_FREQUENT,
+            params.get(1..).unwrap_or(&[]),
+            parse_ctx,
+        )?;
+        Ok(Self::new(count, key_executors, app_ctx, query_ctx))
+    }
+
+    fn event_key(&self, se: &StreamEvent) -> String {
+        self.key_generator
+            .construct_event_key(se)
+            .unwrap_or_else(|| {
+                se.before_window_data
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(KEY_DELIMITER)
+            })
+    }
+
+    fn admit(&self, state: &mut FrequentWindowState, se: &StreamEvent, out: &mut Vec<StreamEvent>) {
+        let key = self.event_key(se);
+
+        if let Some(mut entry) = state.take(&key) {
+            entry.count += 1;
+            let old = std::mem::replace(&mut entry.event, se.clone_without_next());
+            out.push(expired_copy(&old, se.timestamp));
+            out.push(se.clone_without_next());
+            state.push(entry);
+            return;
+        }
+
+        if state.entries.len() >= self.most_frequent_count {
+            // Window full: the new key's single occurrence cancels one
+            // occurrence of every tracked key
+            let mut exhausted = Vec::new();
+            for entry in state.entries.values_mut() {
+                entry.count -= 1;
+                if entry.count == 0 {
+                    exhausted.push(entry.key.clone());
+                }
+            }
+            for key in exhausted {
+                if let Some(entry) = state.take(&key) {
+                    out.push(expired_copy(&entry.event, se.timestamp));
+                }
+            }
+        }
+
+        if state.entries.len() < self.most_frequent_count {
+            state.push(FrequentEntry {
+                key,
+                count: 1,
+                event: se.clone_without_next(),
+            });
+            out.push(se.clone_without_next());
+        }
+    }
+}
+
+impl Processor for FrequentWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let Some(ref next) = self.meta.next_processor else {
+            return;
+        };
+        let mut out = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let mut current_opt = complex_event_chunk.as_deref();
+            while let Some(ev) = current_opt {
+                if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                    self.admit(&mut state, se, &mut out);
+                }
+                current_opt = ev.get_next();
+            }
+        }
+        emit_events(next, out);
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        let app_ctx = Arc::clone(&self.meta.eventflux_app_context);
+        let key_executors = self
+            .key_executors
+            .iter()
+            .map(|e| e.clone_executor(&app_ctx))
+            .collect();
+        Box::new(Self::new(
+            self.most_frequent_count,
+            key_executors,
+            app_ctx,
+            Arc::clone(query_ctx),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::SLIDE
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.state, |state| {
+            state
+                .entries
+                .values()
+                .map(|entry| entry.event.clone_without_next())
+                .collect()
+        }))
+    }
+}
+
+impl WindowProcessor for FrequentWindowProcessor {}
+
+#[derive(Debug, Clone)]
+pub struct FrequentWindowFactory;
+
+impl WindowProcessorFactory for FrequentWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_FREQUENT
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(FrequentWindowProcessor::from_handler(
+            handler, app_ctx, query_ctx, parse_ctx,
+        )?)))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
@@ -288,6 +288,13 @@ impl StateHolder for LengthWindowStateHolder {
         Ok(())
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        let mut change_log = self.change_log.lock().unwrap();
+        let committed = operations.min(change_log.len());
+        change_log.drain(..committed);
+        *self.last_checkpoint_id.lock().unwrap() = Some(checkpoint_id);
+    }
+
     fn estimate_size(&self) -> StateSize {
         let entries = if let Ok(buffer) = self.buffer.try_lock() {
             buffer.len()
@@ -57,13 +57,162 @@ use time_batch_window_state_holder::TimeBatchWindowStateHolder;
 // Import enhanced external time window state holder
 mod external_time_window_state_holder;
 
+// Full-snapshot state holder for the keyed, delay and hybrid windows (also
+// reused by the sketch aggregators)
+pub mod snapshot_window_state_holder;
+
+mod unique_window_processor;
+pub use unique_window_processor::{
+    FirstUniqueWindowFactory, UniqueRetention, UniqueWindowFactory, UniqueWindowProcessor,
+};
+
+mod frequent_window_processor;
+pub use frequent_window_processor::{FrequentWindowFactory, FrequentWindowProcessor};
+
+mod delay_window_processor;
+pub use delay_window_processor::{DelayWindowFactory, DelayWindowProcessor};
+
+mod expression_window_processor;
+pub use expression_window_processor::{ExpressionWindowFactory, ExpressionWindowProcessor};
+
+mod time_length_window_processor;
+pub use time_length_window_processor::{TimeLengthWindowFactory, TimeLengthWindowProcessor};
+
 pub trait WindowProcessor: Processor {}
 
+/// Read access to the events a window processor currently holds
+///
+/// Named windows answer joins from the window processor's own buffer through
+/// this view instead of keeping a copy of the window's contents.
+pub trait WindowContents: Debug + Send + Sync {
+    /// Events currently in the window
+    fn events(&self) -> Vec<StreamEvent>;
+}
+
+/// [`WindowContents`] over a buffer shared with a window processor
+struct BufferView<T> {
+    buffer: Arc<Mutex<T>>,
+    read: fn(&T) -> Vec<StreamEvent>,
+}
+
+impl<T> Debug for BufferView<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferView").finish_non_exhaustive()
+    }
+}
+
+impl<T: Send> WindowContents for BufferView<T> {
+    fn events(&self) -> Vec<StreamEvent> {
+        (self.read)(&self.buffer.lock().unwrap())
+    }
+}
+
+/// View of `buffer` whose events are read with `read`
+fn buffer_view<T: Send + 'static>(
+    buffer: &Arc<Mutex<T>>,
+    read: fn(&T) -> Vec<StreamEvent>,
+) -> Arc<dyn WindowContents> {
+    Arc::new(BufferView {
+        buffer: Arc::clone(buffer),
+        read,
+    })
+}
+
+/// Events of a sliding window buffer, oldest first
+fn sliding_events(buffer: &VecDeque<Arc<StreamEvent>>) -> Vec<StreamEvent> {
+    buffer.iter().map(|e| e.clone_without_next()).collect()
+}
+
+/// Events of the batch a batch window emitted last (held until it expires)
+fn batch_events(batch: &[StreamEvent]) -> Vec<StreamEvent> {
+    batch.iter().map(StreamEvent::clone_without_next).collect()
+}
+
+/// Link `events` into a chunk and hand it to `next`; does nothing when empty
+fn emit_events(next: &Arc<Mutex<dyn Processor>>, events: Vec<StreamEvent>) {
+    let mut head: Option<Box<dyn ComplexEvent>> = None;
+    let mut tail = &mut head;
+    for event in events {
+        *tail = Some(Box::new(event));
+        tail = tail.as_mut().unwrap().mut_next_ref_option();
+    }
+    if head.is_some() {
+        next.lock().unwrap().process(head);
+    }
+}
+
+/// Copy of `event` marked EXPIRED at `timestamp`
+fn expired_copy(event: &StreamEvent, timestamp: i64) -> StreamEvent {
+    let mut expired = event.clone_without_next();
+    expired.set_event_type(ComplexEventType::Expired);
+    expired.set_timestamp(timestamp);
+    expired
+}
+
+/// Parse window parameters naming the attributes that key a window
+fn parse_key_executors(
+    window_name: &str,
+    params: &[Expression],
+    parse_ctx: &crate::core::util::parser::expression_parser::ExpressionParserContext,
+) -> Result<Vec<Box<dyn crate::core::executor::expression_executor::ExpressionExecutor>>, String> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let executor =
+                crate::core::util::parser::expression_parser::parse_expression(param, parse_ctx)
+                    .map_err(|e| format!("Failed to parse {window_name} window key: {e}"))?;
+            if !executor.is_variable_executor() {
+                return Err(format!(
+                    "{window_name} window keys must be stream attributes (parameter {})",
+                    i + 1
+                ));
+            }
+            Ok(executor)
+        })
+        .collect()
+}
+
+/// Read a positive integer constant window parameter
+fn positive_int_param(window_name: &str, param: Option<&Expression>) -> Result<usize, String> {
+    match param {
+        Some(Expression::Constant(c)) => match &c.value {
+            ConstantValueWithFloat::Int(i) if *i > 0 => Ok(*i as usize),
+            ConstantValueWithFloat::Long(l) if *l > 0 => Ok(*l as usize),
+            _ => Err(format!(
+                "{window_name} window size must be a positive integer"
+            )),
+        },
+        Some(_) => Err(format!("{window_name} window size must be constant")),
+        None => Err(format!("{window_name} window requires a size parameter")),
+    }
+}
+
+/// Read a time (or long millisecond) constant window parameter
+fn duration_param(window_name: &str, param: Option<&Expression>) -> Result<i64, String> {
+    match param {
+        Some(Expression::Constant(c)) => match &c.value {
+            ConstantValueWithFloat::Time(t) if *t >= 0 => Ok(*t),
+            ConstantValueWithFloat::Long(l) if *l >= 0 => Ok(*l),
+            ConstantValueWithFloat::Int(i) if *i >= 0 => Ok(*i as i64),
+            _ => Err(format!(
+                "{window_name} window duration must be a non-negative time constant"
+            )),
+        },
+        Some(_) => Err(format!("{window_name} window duration must be constant")),
+        None => Err(format!(
+            "{window_name} window requires a duration parameter"
+        )),
+    }
+}
+
 #[derive(Debug)]
 pub struct LengthWindowProcessor {
     meta: CommonProcessorMeta,
     pub length: usize,
     buffer: Arc<Mutex<VecDeque<Arc<StreamEvent>>>>,
+    /// Records buffer changes when the app takes incremental checkpoints
+    state_holder: Option<LengthWindowStateHolder>,
 }
 
 impl LengthWindowProcessor {
@@ -89,11 +238,15 @@ impl LengthWindowProcessor {
         if let Some(snapshot_service) = app_ctx.get_snapshot_service() {
             snapshot_service.register_state_holder(component_id, state_holder_arc);
         }
+        let state_holder = app_ctx
+            .is_incremental_checkpointing_enabled()
+            .then(|| (*state_holder).clone());
 
         Self {
             meta: CommonProcessorMeta::new(app_ctx, query_ctx),
             length,
             buffer,
+            state_holder,
         }
     }
 
@@ -132,6 +285,9 @@ impl Processor for LengthWindowProcessor {
                             let mut buf = self.buffer.lock().unwrap();
                             if buf.len() >= self.length {
                                 if let Some(old) = buf.pop_front() {
+                                    if let Some(ref state_holder) = self.state_holder {
+                                        state_holder.record_event_removed(&old);
+                                    }
                                     let mut ex = old.as_ref().clone_without_next();
                                     ex.set_event_type(ComplexEventType::Expired);
                                     ex.set_timestamp(se.timestamp);
@@ -139,6 +295,9 @@ impl Processor for LengthWindowProcessor {
                                 }
                             }
                             buf.push_back(Arc::new(se.clone_without_next()));
+                            if let Some(ref state_holder) = self.state_holder {
+                                state_holder.record_event_added(se);
+                            }
                         }
                         if let Some(mut ex) = expired {
                             let tail = ex.mut_next_ref_option();
@@ -188,6 +347,10 @@ impl Processor for LengthWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.buffer, sliding_events))
+    }
 }
 
 impl WindowProcessor for LengthWindowProcessor {}
@@ -458,6 +621,10 @@ impl Processor for TimeWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.buffer, sliding_events))
+    }
 }
 
 impl WindowProcessor for TimeWindowProcessor {}
@@ -537,6 +704,16 @@ impl NewStateHolder for TimeWindowProcessor {
         }
     }
 
+    fn commit_changelog(
+        &self,
+        checkpoint_id: crate::core::persistence::state_holder::CheckpointId,
+        operations: usize,
+    ) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> crate::core::persistence::state_holder::StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -850,6 +1027,10 @@ impl Processor for LengthBatchWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.expired, |batch| batch_events(batch)))
+    }
 }
 
 impl WindowProcessor for LengthBatchWindowProcessor {}
@@ -903,6 +1084,12 @@ impl NewStateHolder for LengthBatchWindowProcessor {
         }
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -1217,6 +1404,10 @@ impl Processor for TimeBatchWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.expired, |batch| batch_events(batch)))
+    }
 }
 
 impl WindowProcessor for TimeBatchWindowProcessor {}
@@ -1270,6 +1461,12 @@ impl NewStateHolder for TimeBatchWindowProcessor {
         }
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -1438,6 +1635,10 @@ impl Processor for ExternalTimeWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.buffer, sliding_events))
+    }
 }
 
 impl WindowProcessor for ExternalTimeWindowProcessor {}
@@ -1644,6 +1845,10 @@ impl Processor for ExternalTimeBatchWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.expired, |batch| batch_events(batch)))
+    }
 }
 
 impl WindowProcessor for ExternalTimeBatchWindowProcessor {}
@@ -1895,6 +2100,10 @@ impl Processor for CronWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.expired, |batch| batch_events(batch)))
+    }
 }
 
 impl WindowProcessor for CronWindowProcessor {}
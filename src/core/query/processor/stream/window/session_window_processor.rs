@@ -10,7 +10,7 @@ use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
 use crate::core::event::stream::StreamEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
-use crate::core::query::processor::stream::window::WindowProcessor;
+use crate::core::query::processor::stream::window::{buffer_view, WindowContents, WindowProcessor};
 use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
 use crate::core::util::scheduler::{Schedulable, Scheduler};
 use crate::query_api::execution::query::input::handler::WindowHandler;
@@ -480,6 +480,24 @@ impl Processor for SessionWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.state, |state| {
+            let mut events: Vec<StreamEvent> = state
+                .session_map
+                .values()
+                .flat_map(|c| {
+                    c.previous_session
+                        .events
+                        .iter()
+                        .chain(&c.current_session.events)
+                })
+                .map(|e| e.clone_without_next())
+                .collect();
+            events.sort_by_key(|e| e.timestamp);
+            events
+        }))
+    }
 }
 
 impl WindowProcessor for SessionWindowProcessor {}
@@ -528,6 +528,13 @@ impl StateHolder for SessionWindowStateHolder {
         Ok(())
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        let mut change_log = self.change_log.lock().unwrap();
+        let committed = operations.min(change_log.len());
+        change_log.drain(..committed);
+        *self.last_checkpoint_id.lock().unwrap() = Some(checkpoint_id);
+    }
+
     fn estimate_size(&self) -> StateSize {
         // Simplified implementation to avoid lock contention issues
         // Try to lock state, but don't hang if it fails
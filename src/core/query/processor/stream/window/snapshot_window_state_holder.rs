@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/snapshot_window_state_holder.rs
+
+//! Full-snapshot StateHolder shared by the keyed, delay and sort windows
+//!
+//! Windows such as `unique`, `firstUnique`, `frequent`, `delay`, `expression`,
+//! `timeLength` and `sort` keep all of their state in a single serializable value.
+//! This holder snapshots that value as a whole; incremental changelogs are
+//! not tracked, so checkpoints of these windows are always full. The sketch
+//! aggregators (`percentile`, `approx_distinct`, `top_k`) and the per-partition
+//! state of analytic functions (`LAG`, `ROW_NUMBER`, ...) reuse it the same way.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::persistence::state_holder::{
+    AccessPattern, ChangeLog, CheckpointId, SchemaVersion, SerializationHints, StateError,
+    StateHolder, StateMetadata, StateSize, StateSnapshot,
+};
+use crate::core::util::compression::{
+    CompressibleStateHolder, CompressionHints, DataCharacteristics, DataSizeRange,
+};
+
+/// State kept by a snapshot-persisted window
+pub trait WindowState: Serialize + DeserializeOwned + Debug + Send + 'static {
+    /// Number of events currently held
+    fn entries(&self) -> usize;
+}
+
+/// StateHolder snapshotting a window's whole state
+pub struct SnapshotWindowStateHolder<S: WindowState> {
+    state: Arc<Mutex<S>>,
+    component_id: String,
+    component_type: &'static str,
+    on_restore: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl<S: WindowState> Debug for SnapshotWindowStateHolder<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotWindowStateHolder")
+            .field("state", &self.state)
+            .field("component_id", &self.component_id)
+            .field("component_type", &self.component_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: WindowState> Clone for SnapshotWindowStateHolder<S> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            component_id: self.component_id.clone(),
+            component_type: self.component_type,
+            on_restore: self.on_restore.clone(),
+        }
+    }
+}
+
+impl<S: WindowState> SnapshotWindowStateHolder<S> {
+    pub fn new(state: Arc<Mutex<S>>, component_id: String, component_type: &'static str) -> Self {
+        Self {
+            state,
+            component_id,
+            component_type,
+            on_restore: None,
+        }
+    }
+
+    /// Run `hook` after the state is restored from a snapshot, e.g. to re-arm timers
+    pub fn on_restore(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_restore = Some(Arc::new(hook));
+        self
+    }
+
+    /// Register this holder with the query's snapshot service
+    pub fn register(&self, query_ctx: &EventFluxQueryContext) {
+        query_ctx.register_state_holder(
+            self.component_id.clone(),
+            Arc::new(Mutex::new(self.clone())),
+        );
+    }
+}
+
+impl<S: WindowState> StateHolder for SnapshotWindowStateHolder<S> {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion::new(1, 0, 0)
+    }
+
+    fn serialize_state(&self, hints: &SerializationHints) -> Result<StateSnapshot, StateError> {
+        let data = {
+            let state = self.state.lock().unwrap();
+            crate::core::util::to_bytes(&*state).map_err(|e| StateError::SerializationError {
+                message: format!("Failed to serialize {} state: {e}", self.component_type),
+            })?
+        };
+
+        let (data, compression) =
+            self.compress_state_data(&data, hints.prefer_compression.clone())?;
+        let checksum = StateSnapshot::calculate_checksum(&data);
+
+        Ok(StateSnapshot {
+            version: self.schema_version(),
+            checkpoint_id: 0,
+            data,
+            compression,
+            checksum,
+            metadata: self.component_metadata(),
+        })
+    }
+
+    fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+        if !snapshot.verify_integrity() {
+            return Err(StateError::ChecksumMismatch);
+        }
+
+        let data = self.decompress_state_data(&snapshot.data, snapshot.compression.clone())?;
+        let restored: S =
+            crate::core::util::from_bytes(&data).map_err(|e| StateError::DeserializationError {
+                message: format!("Failed to deserialize {} state: {e}", self.component_type),
+            })?;
+
+        *self.state.lock().unwrap() = restored;
+        if let Some(hook) = &self.on_restore {
+            hook();
+        }
+        Ok(())
+    }
+
+    fn get_changelog(&self, _since: CheckpointId) -> Result<ChangeLog, StateError> {
+        Err(StateError::SerializationError {
+            message: format!(
+                "{} does not support incremental checkpointing",
+                self.component_type
+            ),
+        })
+    }
+
+    fn apply_changelog(&self, _changes: &ChangeLog) -> Result<(), StateError> {
+        Err(StateError::DeserializationError {
+            message: format!(
+                "{} does not support incremental changes",
+                self.component_type
+            ),
+        })
+    }
+
+    fn estimate_size(&self) -> StateSize {
+        let entries = self.state.lock().unwrap().entries();
+        StateSize {
+            bytes: entries * 128,
+            entries,
+            estimated_growth_rate: 0.0,
+        }
+    }
+
+    fn access_pattern(&self) -> AccessPattern {
+        AccessPattern::Hot
+    }
+
+    fn component_metadata(&self) -> StateMetadata {
+        StateMetadata::new(self.component_id.clone(), self.component_type.to_string())
+    }
+}
+
+impl<S: WindowState> CompressibleStateHolder for SnapshotWindowStateHolder<S> {
+    fn compression_hints(&self) -> CompressionHints {
+        CompressionHints {
+            prefer_speed: true,
+            prefer_ratio: false,
+            data_type: DataCharacteristics::ModeratelyRepetitive,
+            target_latency_ms: Some(2),
+            min_compression_ratio: Some(0.3),
+            expected_size_range: DataSizeRange::Medium,
+        }
+    }
+}
@@ -12,14 +12,31 @@ use crate::core::config::{
 use crate::core::config::eventflux_context::EventFluxContext;
 use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
 use crate::core::event::stream::StreamEvent;
-use crate::core::query::processor::stream::window::WindowProcessor;
+use crate::core::query::processor::stream::window::snapshot_window_state_holder::{
+    SnapshotWindowStateHolder, WindowState,
+};
+use crate::core::query::processor::stream::window::types::WINDOW_TYPE_SORT;
+use crate::core::query::processor::stream::window::{buffer_view, WindowContents, WindowProcessor};
 use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
 use crate::core::query::selector::order_by_event_comparator::OrderByEventComparator;
 use crate::query_api::execution::query::input::handler::WindowHandler;
 use crate::query_api::expression::{constant::ConstantValueWithFloat, Expression};
 
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
+/// Events in the window, kept in sort order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SortWindowState {
+    events: Vec<StreamEvent>,
+}
+
+impl WindowState for SortWindowState {
+    fn entries(&self) -> usize {
+        self.events.len()
+    }
+}
+
 /// A sort window maintains a fixed-size sliding window of events in sorted order
 #[derive(Debug)]
 pub struct SortWindowProcessor {
@@ -28,7 +45,7 @@ pub struct SortWindowProcessor {
     /// Maximum number of events to keep in the window
     length_to_keep: usize,
     /// Sorted buffer of events
-    sorted_window: Arc<Mutex<Vec<Arc<StreamEvent>>>>,
+    sorted_window: Arc<Mutex<SortWindowState>>,
     /// Comparator for sorting events
     comparator: OrderByEventComparator,
 }
@@ -45,10 +62,20 @@ impl SortWindowProcessor {
         let effective_length = Self::calculate_effective_window_size(length_to_keep, &app_ctx);
         let initial_capacity = Self::calculate_initial_capacity(effective_length, &app_ctx);
 
+        let sorted_window = Arc::new(Mutex::new(SortWindowState {
+            events: Vec::with_capacity(initial_capacity),
+        }));
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&sorted_window),
+            format!("sort_window_{}_{}", query_ctx.get_name(), length_to_keep),
+            WINDOW_TYPE_SORT,
+        )
+        .register(&query_ctx);
+
         let processor = SortWindowProcessor {
             meta: CommonProcessorMeta::new(app_ctx, query_ctx),
             length_to_keep: effective_length,
-            sorted_window: Arc::new(Mutex::new(Vec::with_capacity(initial_capacity))),
+            sorted_window,
             comparator,
         };
 
@@ -163,12 +190,13 @@ impl SortWindowProcessor {
             _ => return Err("Sort window length must be a constant".to_string()),
         };
 
-        // Parse remaining parameters as attribute/order pairs
-        // Format: sort(length, attr1, 'asc', attr2, 'desc', ...)
+        // Parse remaining parameters as sort keys
+        // Format: sort(length, attr1, ['asc' | 'desc'], ['nulls first' | 'nulls last'], attr2, ...)
         let mut executors: Vec<
             Box<dyn crate::core::executor::expression_executor::ExpressionExecutor>,
         > = Vec::new();
         let mut ascending: Vec<bool> = Vec::new();
+        let mut nulls_first: Vec<Option<bool>> = Vec::new();
 
         let remaining_params = &params[1..];
         let mut i = 0;
@@ -193,38 +221,44 @@ impl SortWindowProcessor {
             }
 
             executors.push(executor);
+            i += 1;
 
-            // Check for optional order specification ('asc' or 'desc')
-            let is_ascending = if i + 1 < remaining_params.len() {
-                if let Expression::Constant(c) = &remaining_params[i + 1] {
-                    if let ConstantValueWithFloat::String(order_str) = &c.value {
-                        let order_lower = order_str.to_lowercase();
-                        if order_lower == "asc" {
-                            i += 1; // Consume the order parameter
-                            true
-                        } else if order_lower == "desc" {
-                            i += 1; // Consume the order parameter
-                            false
-                        } else {
-                            // STRICT VALIDATION: Reject invalid order strings
-                            return Err(format!(
-                                "Sort window order parameter must be 'asc' or 'desc', found: '{}'. \
-                                Valid usage: WINDOW('sort', size, attribute, 'asc') or WINDOW('sort', size, attribute, 'desc')",
-                                order_str
-                            ));
-                        }
-                    } else {
-                        true // Default to ascending if not a string
+            // Optional order and null-ordering modifiers, in that order
+            let mut key_ascending = None;
+            let mut key_nulls_first = None;
+            while let Some(Expression::Constant(c)) = remaining_params.get(i) {
+                let ConstantValueWithFloat::String(modifier) = &c.value else {
+                    break;
+                };
+                let modifier_lower = modifier.to_lowercase();
+                match modifier_lower
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .as_slice()
+                {
+                    ["asc"] if key_ascending.is_none() && key_nulls_first.is_none() => {
+                        key_ascending = Some(true)
+                    }
+                    ["desc"] if key_ascending.is_none() && key_nulls_first.is_none() => {
+                        key_ascending = Some(false)
+                    }
+                    ["nulls", "first"] if key_nulls_first.is_none() => key_nulls_first = Some(true),
+                    ["nulls", "last"] if key_nulls_first.is_none() => key_nulls_first = Some(false),
+                    _ => {
+                        // STRICT VALIDATION: Reject invalid order strings
+                        return Err(format!(
+                            "Sort window order parameter must be 'asc' or 'desc', optionally followed by \
+                            'nulls first' or 'nulls last', found: '{}'. \
+                            Valid usage: WINDOW('sort', size, attribute, 'desc', 'nulls last')",
+                            modifier
+                        ));
                     }
-                } else {
-                    true // Default to ascending if next param is not a constant
                 }
-            } else {
-                true // Default to ascending if no order specified
-            };
+                i += 1;
+            }
 
-            ascending.push(is_ascending);
-            i += 1;
+            ascending.push(key_ascending.unwrap_or(true));
+            nulls_first.push(key_nulls_first);
         }
 
         // Validate that we have at least one sort attribute
@@ -232,38 +266,37 @@ impl SortWindowProcessor {
             return Err("Sort window requires at least one sort attribute".to_string());
         }
 
-        let comparator = OrderByEventComparator::new(executors, ascending);
+        let comparator =
+            OrderByEventComparator::new(executors, ascending).with_nulls_first(nulls_first);
 
         Ok(Self::new(length_to_keep, comparator, app_ctx, query_ctx))
     }
 
     /// Process an incoming event
-    fn process_event(&self, event: Arc<StreamEvent>) -> Result<Vec<Box<dyn ComplexEvent>>, String> {
-        let mut sorted_buffer = self
+    ///
+    /// The buffer is kept sorted; a new event is inserted after any equal
+    /// events, so among ties the most recent arrival is expired first.
+    fn process_event(&self, event: &StreamEvent) -> Result<Vec<Box<dyn ComplexEvent>>, String> {
+        let mut state = self
             .sorted_window
             .lock()
             .map_err(|_| "Failed to acquire sort window lock".to_string())?;
 
-        // Add the new event to the buffer
-        // Note: We store Arc references for efficiency. Events are immutable in EventFlux,
-        // so sharing via Arc is safe and more idiomatic than cloning.
-        sorted_buffer.push(Arc::clone(&event));
+        let position = state
+            .events
+            .partition_point(|held| self.comparator.compare(held, event).is_le());
+        state.events.insert(position, event.clone_without_next());
 
         let mut result = Vec::new();
 
         // Always emit the current event first
-        let mut current_stream_event = event.as_ref().clone_without_next();
+        let mut current_stream_event = event.clone_without_next();
         current_stream_event.set_event_type(ComplexEventType::Current);
         result.push(Box::new(current_stream_event) as Box<dyn ComplexEvent>);
 
-        // If we exceed the window size, sort and remove the last element
-        if sorted_buffer.len() > self.length_to_keep {
-            // Sort using the OrderByEventComparator with attribute-based sorting
-            sorted_buffer.sort_by(|a, b| self.comparator.compare(a.as_ref(), b.as_ref()));
-
-            // Remove the last element (highest in sort order) and mark it as expired
-            if let Some(expired_event) = sorted_buffer.pop() {
-                let mut expired_stream_event = expired_event.as_ref().clone_without_next();
+        // If we exceed the window size, remove the last element in sort order
+        if state.events.len() > self.length_to_keep {
+            if let Some(mut expired_stream_event) = state.events.pop() {
                 expired_stream_event.set_event_type(ComplexEventType::Expired);
 
                 // CRITICAL: Update timestamp on expired event to current time
@@ -287,7 +320,7 @@ impl Processor for SortWindowProcessor {
 
                 while let Some(ev) = current_opt {
                     if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
-                        match self.process_event(Arc::new(se.clone_without_next())) {
+                        match self.process_event(se) {
                             Ok(events) => {
                                 all_events.extend(events);
                             }
@@ -326,8 +359,8 @@ impl Processor for SortWindowProcessor {
     fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
         Box::new(Self::new(
             self.length_to_keep,
-            // TODO: Clone comparator properly
-            OrderByEventComparator::new(Vec::new(), Vec::new()),
+            self.comparator
+                .clone_with_context(&self.meta.eventflux_app_context),
             Arc::clone(&self.meta.eventflux_app_context),
             Arc::clone(query_ctx),
         ))
@@ -348,6 +381,16 @@ impl Processor for SortWindowProcessor {
     fn is_stateful(&self) -> bool {
         true
     }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.sorted_window, |state| {
+            state
+                .events
+                .iter()
+                .map(StreamEvent::clone_without_next)
+                .collect()
+        }))
+    }
 }
 
 impl WindowProcessor for SortWindowProcessor {}
@@ -383,8 +426,51 @@ mod tests {
         let _processor = SortWindowProcessor {
             meta: CommonProcessorMeta::new(app_ctx, query_ctx),
             length_to_keep: 3,
-            sorted_window: Arc::new(Mutex::new(Vec::new())),
+            sorted_window: Arc::new(Mutex::new(SortWindowState::default())),
             comparator,
         };
     }
+
+    #[test]
+    fn test_sort_window_state_snapshot_restore() {
+        use crate::core::event::value::AttributeValue;
+        use crate::core::persistence::state_holder::{SerializationHints, StateHolder};
+
+        let state = Arc::new(Mutex::new(SortWindowState::default()));
+        {
+            let mut state = state.lock().unwrap();
+            for (ts, price) in [(2000, 10.0), (1000, 20.0)] {
+                let mut event = StreamEvent::new(ts, 1, 0, 0);
+                event.before_window_data = vec![AttributeValue::Double(price)];
+                state.events.push(event);
+            }
+        }
+        let holder = SnapshotWindowStateHolder::new(
+            Arc::clone(&state),
+            "sort_window_test".to_string(),
+            WINDOW_TYPE_SORT,
+        );
+
+        let snapshot = holder
+            .serialize_state(&SerializationHints::default())
+            .unwrap();
+        state.lock().unwrap().events.clear();
+        holder.deserialize_state(&snapshot).unwrap();
+
+        // The buffer comes back in its stored, price-sorted order, which is
+        // not timestamp order
+        let restored = state.lock().unwrap();
+        let rows: Vec<_> = restored
+            .events
+            .iter()
+            .map(|e| (e.timestamp, e.before_window_data[0].clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (2000, AttributeValue::Double(10.0)),
+                (1000, AttributeValue::Double(20.0))
+            ]
+        );
+    }
 }
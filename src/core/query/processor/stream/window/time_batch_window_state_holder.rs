@@ -550,6 +550,13 @@ impl StateHolder for TimeBatchWindowStateHolder {
         Ok(())
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        let mut change_log = self.change_log.lock().unwrap();
+        let committed = operations.min(change_log.len());
+        change_log.drain(..committed);
+        *self.last_checkpoint_id.lock().unwrap() = Some(checkpoint_id);
+    }
+
     fn estimate_size(&self) -> StateSize {
         let buffer = self.buffer.lock().unwrap();
         let expired = self.expired.lock().unwrap();
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/time_length_window_processor.rs
+
+//! Time-length window: `timeLength(duration, length)`
+//!
+//! Sliding window that holds at most `length` events, none older than
+//! `duration`. An event expires as soon as either bound is exceeded.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot_window_state_holder::{SnapshotWindowStateHolder, WindowState};
+use super::types::WINDOW_TYPE_TIME_LENGTH;
+use super::{
+    buffer_view, duration_param, emit_events, expired_copy, positive_int_param, WindowContents,
+    WindowProcessor,
+};
+use crate::core::config::{
+    eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
+};
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::extension::WindowProcessorFactory;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::util::parser::expression_parser::ExpressionParserContext;
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::query_api::execution::query::input::handler::WindowHandler;
+
+/// Events currently in the window, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimeLengthWindowState {
+    events: VecDeque<StreamEvent>,
+}
+
+impl WindowState for TimeLengthWindowState {
+    fn entries(&self) -> usize {
+        self.events.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct TimeLengthWindowProcessor {
+    meta: CommonProcessorMeta,
+    pub duration_ms: i64,
+    pub length: usize,
+    scheduler: Option<Arc<Scheduler>>,
+    state: Arc<Mutex<TimeLengthWindowState>>,
+}
+
+impl TimeLengthWindowProcessor {
+    pub fn new(
+        duration_ms: i64,
+        length: usize,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(TimeLengthWindowState::default()));
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&state),
+            format!(
+                "time_length_window_{}_{}_{}",
+                query_ctx.get_name(),
+                duration_ms,
+                length
+            ),
+            WINDOW_TYPE_TIME_LENGTH,
+        )
+        .register(&query_ctx);
+
+        Self {
+            scheduler: app_ctx.get_scheduler(),
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            duration_ms,
+            length,
+            state,
+        }
+    }
+
+    pub fn from_handler(
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        _parse_ctx: &ExpressionParserContext,
+    ) -> Result<Self, String> {
+        let params = handler.get_parameters();
+        let duration = duration_param(WINDOW_TYPE_TIME_LENGTH, params.first())?;
+        let length = positive_int_param(WINDOW_TYPE_TIME_LENGTH, params.get(1))?;
+        Ok(Self::new(duration, length, app_ctx, query_ctx))
+    }
+}
+
+/// Expires every event older than the window duration
+struct TimeLengthExpireTask {
+    state: Arc<Mutex<TimeLengthWindowState>>,
+    next: Arc<Mutex<dyn Processor>>,
+    duration_ms: i64,
+}
+
+impl Schedulable for TimeLengthExpireTask {
+    fn on_time(&self, timestamp: i64) {
+        let expired: Vec<StreamEvent> = {
+            let mut state = self.state.lock().unwrap();
+            let due = state
+                .events
+                .iter()
+                .take_while(|e| e.timestamp + self.duration_ms <= timestamp)
+                .count();
+            state
+                .events
+                .drain(..due)
+                .map(|e| expired_copy(&e, timestamp))
+                .collect()
+        };
+        emit_events(&self.next, expired);
+    }
+}
+
+impl Processor for TimeLengthWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let Some(ref next) = self.meta.next_processor else {
+            return;
+        };
+        let mut out = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let mut current_opt = complex_event_chunk.as_deref();
+            while let Some(ev) = current_opt {
+                if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                    if state.events.len() >= self.length {
+                        if let Some(oldest) = state.events.pop_front() {
+                            out.push(expired_copy(&oldest, se.timestamp));
+                        }
+                    }
+                    state.events.push_back(se.clone_without_next());
+                    out.push(se.clone_without_next());
+
+                    if let Some(ref scheduler) = self.scheduler {
+                        let task = TimeLengthExpireTask {
+                            state: Arc::clone(&self.state),
+                            next: Arc::clone(next),
+                            duration_ms: self.duration_ms,
+                        };
+                        scheduler.notify_at(se.timestamp + self.duration_ms, Arc::new(task));
+                    }
+                }
+                current_opt = ev.get_next();
+            }
+        }
+        emit_events(next, out);
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        Box::new(Self::new(
+            self.duration_ms,
+            self.length,
+            Arc::clone(&self.meta.eventflux_app_context),
+            Arc::clone(query_ctx),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::SLIDE
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.state, |state| {
+            state
+                .events
+                .iter()
+                .map(StreamEvent::clone_without_next)
+                .collect()
+        }))
+    }
+}
+
+impl WindowProcessor for TimeLengthWindowProcessor {}
+
+#[derive(Debug, Clone)]
+pub struct TimeLengthWindowFactory;
+
+impl WindowProcessorFactory for TimeLengthWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_TIME_LENGTH
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(
+            TimeLengthWindowProcessor::from_handler(handler, app_ctx, query_ctx, parse_ctx)?,
+        )))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
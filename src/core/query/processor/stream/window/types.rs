@@ -34,6 +34,24 @@ pub const WINDOW_TYPE_CRON: &str = "cron";
 /// Lossy counting window: Approximate frequency counting
 pub const WINDOW_TYPE_LOSSY_COUNTING: &str = "lossyCounting";
 
+/// Unique window: Keeps the latest event per key
+pub const WINDOW_TYPE_UNIQUE: &str = "unique";
+
+/// First unique window: Keeps the first event per key
+pub const WINDOW_TYPE_FIRST_UNIQUE: &str = "firstUnique";
+
+/// Delay window: Releases each event after duration D
+pub const WINDOW_TYPE_DELAY: &str = "delay";
+
+/// Frequent window: Keeps events of the most frequent keys (Misra-Gries)
+pub const WINDOW_TYPE_FREQUENT: &str = "frequent";
+
+/// Expression window: Keeps events while a condition holds
+pub const WINDOW_TYPE_EXPRESSION: &str = "expression";
+
+/// Time-length window: Keeps at most N events for at most duration D
+pub const WINDOW_TYPE_TIME_LENGTH: &str = "timeLength";
+
 /// Check if a window type is supported
 pub fn is_supported_window_type(window_type: &str) -> bool {
     matches!(
@@ -48,6 +66,12 @@ pub fn is_supported_window_type(window_type: &str) -> bool {
             | WINDOW_TYPE_SORT
             | WINDOW_TYPE_CRON
             | WINDOW_TYPE_LOSSY_COUNTING
+            | WINDOW_TYPE_UNIQUE
+            | WINDOW_TYPE_FIRST_UNIQUE
+            | WINDOW_TYPE_DELAY
+            | WINDOW_TYPE_FREQUENT
+            | WINDOW_TYPE_EXPRESSION
+            | WINDOW_TYPE_TIME_LENGTH
     )
 }
 
@@ -60,6 +84,8 @@ mod tests {
         assert!(is_supported_window_type(WINDOW_TYPE_LENGTH));
         assert!(is_supported_window_type(WINDOW_TYPE_TIME));
         assert!(is_supported_window_type(WINDOW_TYPE_TIME_BATCH));
+        assert!(is_supported_window_type(WINDOW_TYPE_FIRST_UNIQUE));
+        assert!(is_supported_window_type(WINDOW_TYPE_TIME_LENGTH));
         assert!(!is_supported_window_type("unknown"));
         assert!(!is_supported_window_type("tumbling")); // Alias, not a real type
         assert!(!is_supported_window_type("sliding")); // Not implemented yet
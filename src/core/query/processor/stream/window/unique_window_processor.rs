@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/processor/stream/window/unique_window_processor.rs
+
+//! Unique and first-unique windows
+//!
+//! Both windows hold one event per key, where the key is built from the
+//! attributes given as window parameters:
+//! - `unique(attr, ...)` keeps the latest event per key; a newer event expires
+//!   the one it replaces.
+//! - `firstUnique(attr, ...)` keeps the first event per key and drops later
+//!   events with a key already seen.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot_window_state_holder::{SnapshotWindowStateHolder, WindowState};
+use super::types::{WINDOW_TYPE_FIRST_UNIQUE, WINDOW_TYPE_UNIQUE};
+use super::{
+    buffer_view, emit_events, expired_copy, parse_key_executors, WindowContents, WindowProcessor,
+};
+use crate::core::config::{
+    eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext,
+};
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::WindowProcessorFactory;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::query::selector::GroupByKeyGenerator;
+use crate::core::util::parser::expression_parser::ExpressionParserContext;
+use crate::query_api::execution::query::input::handler::WindowHandler;
+
+/// Events held by a unique window, by key
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UniqueWindowState {
+    events: HashMap<String, StreamEvent>,
+}
+
+impl WindowState for UniqueWindowState {
+    fn entries(&self) -> usize {
+        self.events.len()
+    }
+}
+
+/// Which event a unique window keeps for a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniqueRetention {
+    /// Keep the newest event, expiring the one it replaces (`unique`)
+    Latest,
+    /// Keep the first event, dropping later ones (`firstUnique`)
+    First,
+}
+
+impl UniqueRetention {
+    fn window_type(self) -> &'static str {
+        match self {
+            UniqueRetention::Latest => WINDOW_TYPE_UNIQUE,
+            UniqueRetention::First => WINDOW_TYPE_FIRST_UNIQUE,
+        }
+    }
+}
+
+/// Holds one event per key
+#[derive(Debug)]
+pub struct UniqueWindowProcessor {
+    meta: CommonProcessorMeta,
+    retention: UniqueRetention,
+    key_executors: Vec<Box<dyn ExpressionExecutor>>,
+    key_generator: GroupByKeyGenerator,
+    state: Arc<Mutex<UniqueWindowState>>,
+}
+
+impl UniqueWindowProcessor {
+    pub fn new(
+        retention: UniqueRetention,
+        key_executors: Vec<Box<dyn ExpressionExecutor>>,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+    ) -> Self {
+        let window_type = retention.window_type();
+        let state = Arc::new(Mutex::new(UniqueWindowState::default()));
+        SnapshotWindowStateHolder::new(
+            Arc::clone(&state),
+            format!("{window_type}_window_{}", query_ctx.get_name()),
+            window_type,
+        )
+        .register(&query_ctx);
+
+        let key_generator = GroupByKeyGenerator::new(
+            key_executors
+                .iter()
+                .map(|e| e.clone_executor(&app_ctx))
+                .collect(),
+        );
+        Self {
+            meta: CommonProcessorMeta::new(app_ctx, query_ctx),
+            retention,
+            key_executors,
+            key_generator,
+            state,
+        }
+    }
+
+    pub fn from_handler(
+        retention: UniqueRetention,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Self, String> {
+        let window_type = retention.window_type();
+        let key_executors = parse_key_executors(window_type, handler.get_parameters(), parse_ctx)?;
+        if key_executors.is_empty() {
+            return Err(format!(
+                "{window_type} window requires at least one key attribute"
+            ));
+        }
+        Ok(Self::new(retention, key_executors, app_ctx, query_ctx))
+    }
+
+    fn admit(&self, state: &mut UniqueWindowState, se: &StreamEvent, out: &mut Vec<StreamEvent>) {
+        let key = self
+            .key_generator
+            .construct_event_key(se)
+            .unwrap_or_default();
+        match self.retention {
+            UniqueRetention::Latest => {
+                if let Some(old) = state.events.insert(key, se.clone_without_next()) {
+                    out.push(expired_copy(&old, se.timestamp));
+                }
+                out.push(se.clone_without_next());
+            }
+            UniqueRetention::First => {
+                if let Entry::Vacant(slot) = state.events.entry(key) {
+                    slot.insert(se.clone_without_next());
+                    out.push(se.clone_without_next());
+                }
+            }
+        }
+    }
+}
+
+impl Processor for UniqueWindowProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let Some(ref next) = self.meta.next_processor else {
+            return;
+        };
+        let mut out = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let mut current_opt = complex_event_chunk.as_deref();
+            while let Some(ev) = current_opt {
+                if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                    self.admit(&mut state, se, &mut out);
+                }
+                current_opt = ev.get_next();
+            }
+        }
+        emit_events(next, out);
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        self.meta.next_processor.as_ref().map(Arc::clone)
+    }
+
+    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
+        self.meta.next_processor = next;
+    }
+
+    fn clone_processor(&self, query_ctx: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
+        let app_ctx = Arc::clone(&self.meta.eventflux_app_context);
+        let key_executors = self
+            .key_executors
+            .iter()
+            .map(|e| e.clone_executor(&app_ctx))
+            .collect();
+        Box::new(Self::new(
+            self.retention,
+            key_executors,
+            app_ctx,
+            Arc::clone(query_ctx),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::SLIDE
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+
+    fn window_contents(&self) -> Option<Arc<dyn WindowContents>> {
+        Some(buffer_view(&self.state, |state| {
+            let mut events: Vec<StreamEvent> = state
+                .events
+                .values()
+                .map(StreamEvent::clone_without_next)
+                .collect();
+            events.sort_by_key(|e| e.timestamp);
+            events
+        }))
+    }
+}
+
+impl WindowProcessor for UniqueWindowProcessor {}
+
+#[derive(Debug, Clone)]
+pub struct UniqueWindowFactory;
+
+impl WindowProcessorFactory for UniqueWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_UNIQUE
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(UniqueWindowProcessor::from_handler(
+            UniqueRetention::Latest,
+            handler,
+            app_ctx,
+            query_ctx,
+            parse_ctx,
+        )?)))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FirstUniqueWindowFactory;
+
+impl WindowProcessorFactory for FirstUniqueWindowFactory {
+    fn name(&self) -> &'static str {
+        WINDOW_TYPE_FIRST_UNIQUE
+    }
+
+    fn create(
+        &self,
+        handler: &WindowHandler,
+        app_ctx: Arc<EventFluxAppContext>,
+        query_ctx: Arc<EventFluxQueryContext>,
+        parse_ctx: &ExpressionParserContext,
+    ) -> Result<Arc<Mutex<dyn Processor>>, String> {
+        Ok(Arc::new(Mutex::new(UniqueWindowProcessor::from_handler(
+            UniqueRetention::First,
+            handler,
+            app_ctx,
+            query_ctx,
+            parse_ctx,
+        )?)))
+    }
+
+    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> {
+        Box::new(Self {})
+    }
+}
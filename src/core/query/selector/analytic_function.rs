@@ -0,0 +1,715 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Analytic functions: `ROW_NUMBER`, `LAG`, `LEAD` and running `SUM`, `COUNT`,
+//! `AVG`, `MIN` and `MAX` evaluated `OVER (PARTITION BY ... ORDER BY ...)`.
+//!
+//! The select processor hands every chunk to [`AnalyticStage::prepare_chunk`]
+//! before projecting it: each function computes one value per event of the
+//! chunk, and the [`AnalyticFunctionExecutor`] embedded in the select
+//! expression returns the value of the event being projected.
+//!
+//! Only current events advance a partition; expired events evaluate to NULL.
+//! A RESET (emitted by batch windows before each new batch) clears all
+//! partitions, so over a batch window rows are numbered and totalled per
+//! batch, while over a plain stream they accumulate for the life of the query.
+//! `ORDER BY` orders the current events within a chunk; across chunks rows
+//! are taken in arrival order.
+//!
+//! Per-partition state is bounded: a counter and accumulator for running
+//! aggregates and the last `offset` values for `LAG`. `LEAD` looks ahead
+//! within the chunk being processed only and keeps no state across chunks.
+//! At most [`MAX_ANALYTIC_PARTITIONS`] partitions are kept per function; past
+//! that the least recently used partition is evicted, and a later row of an
+//! evicted partition starts it afresh.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::numeric_value;
+use crate::core::query::processor::stream::window::snapshot_window_state_holder::{
+    SnapshotWindowStateHolder, WindowState,
+};
+use crate::core::query::selector::order_by_event_comparator::compare_attr_values;
+use crate::core::query::selector::{GroupByKeyGenerator, OrderByEventComparator};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::execution::query::selection::OrderByAttribute;
+
+/// Largest `LAG`/`LEAD` offset; bounds the history kept per partition
+pub const MAX_ANALYTIC_OFFSET: usize = 1024;
+
+/// Most partitions kept per analytic function before the least recently
+/// used one is evicted
+pub const MAX_ANALYTIC_PARTITIONS: usize = 100_000;
+
+thread_local! {
+    /// Functions rebuilt by [`AnalyticStage::clone_stage`], by the address of
+    /// the function they replace, while the select list is being cloned
+    static REBOUND: RefCell<HashMap<usize, Arc<AnalyticFunction>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Aggregate accumulated from the start of the partition to the current row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunningAggregate {
+    Sum,
+    Count,
+    Avg,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalyticKind {
+    RowNumber,
+    Lag(usize),
+    Lead(usize),
+    Running(RunningAggregate),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PartitionState {
+    /// Tick of the partition's latest row, its key in `AnalyticState::recency`
+    last_used: u64,
+    rows: u64,
+    /// Last values, oldest first (`LAG`)
+    history: VecDeque<AttributeValue>,
+    count: u64,
+    long_sum: i64,
+    double_sum: f64,
+    extreme: Option<AttributeValue>,
+}
+
+/// Position, value and default of a `LEAD` row
+type LeadRow = (usize, AttributeValue, AttributeValue);
+
+/// Partitions of one analytic function, persisted with snapshots
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnalyticState {
+    partitions: HashMap<String, PartitionState>,
+    /// Partition keys by the tick of their latest row, least recent first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl AnalyticState {
+    /// Partition of `key`, marked as most recently used; evicts the least
+    /// recently used partition when a new one would exceed `capacity`
+    fn touch(&mut self, key: String, capacity: usize) -> &mut PartitionState {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.partitions.get(&key).map(|p| p.last_used) {
+            Some(previous) => {
+                self.recency.remove(&previous);
+            }
+            None => {
+                if self.partitions.len() >= capacity {
+                    if let Some((_, evicted)) = self.recency.pop_first() {
+                        self.partitions.remove(&evicted);
+                    }
+                }
+            }
+        }
+        self.recency.insert(tick, key.clone());
+        let partition = self.partitions.entry(key).or_default();
+        partition.last_used = tick;
+        partition
+    }
+
+    fn clear(&mut self) {
+        self.partitions.clear();
+        self.recency.clear();
+    }
+}
+
+impl WindowState for AnalyticState {
+    fn entries(&self) -> usize {
+        self.partitions.len()
+    }
+}
+
+/// One analytic call of a query, shared by every executor cloned from it
+/// within one select processor
+#[derive(Debug)]
+pub struct AnalyticFunction {
+    kind: AnalyticKind,
+    value_exec: Option<Box<dyn ExpressionExecutor>>,
+    default_exec: Option<Box<dyn ExpressionExecutor>>,
+    partition_key: GroupByKeyGenerator,
+    order_by: Vec<OrderByAttribute>,
+    order_comparator: Option<OrderByEventComparator>,
+    return_type: ApiAttributeType,
+    state: Arc<Mutex<AnalyticState>>,
+    /// Values computed for the chunk being projected, by position
+    results: Mutex<Vec<AttributeValue>>,
+    cursor: AtomicUsize,
+}
+
+impl AnalyticFunction {
+    /// Build `name(args) OVER (PARTITION BY partition_execs ORDER BY order_by)`
+    /// and register its state with the query's snapshot service
+    pub fn new(
+        name: &str,
+        mut args: Vec<Box<dyn ExpressionExecutor>>,
+        partition_execs: Vec<Box<dyn ExpressionExecutor>>,
+        order_by: Vec<OrderByAttribute>,
+        order_comparator: Option<OrderByEventComparator>,
+        query_ctx: &EventFluxQueryContext,
+    ) -> Result<Self, String> {
+        let (kind, return_type) = match name.to_lowercase().as_str() {
+            "row_number" => {
+                if !args.is_empty() {
+                    return Err("row_number() takes no arguments".to_string());
+                }
+                (AnalyticKind::RowNumber, ApiAttributeType::LONG)
+            }
+            fname @ ("lag" | "lead") => {
+                if args.is_empty() || args.len() > 3 {
+                    return Err(format!(
+                        "{fname}() expects (value[, offset[, default]]), found {} argument(s)",
+                        args.len()
+                    ));
+                }
+                let offset = match args.get(1).map(|a| a.execute(None)) {
+                    None => 1,
+                    Some(Some(AttributeValue::Int(n))) if n > 0 => n as usize,
+                    Some(Some(AttributeValue::Long(n))) if n > 0 => n as usize,
+                    _ => {
+                        return Err(format!(
+                            "{fname}() offset must be a positive integer constant"
+                        ))
+                    }
+                };
+                if offset > MAX_ANALYTIC_OFFSET {
+                    return Err(format!(
+                        "{fname}() offset must not exceed {MAX_ANALYTIC_OFFSET}"
+                    ));
+                }
+                let kind = if fname == "lag" {
+                    AnalyticKind::Lag(offset)
+                } else {
+                    AnalyticKind::Lead(offset)
+                };
+                (kind, args[0].get_return_type())
+            }
+            "count" => {
+                if args.len() > 1 {
+                    return Err("count() OVER takes at most one argument".to_string());
+                }
+                (
+                    AnalyticKind::Running(RunningAggregate::Count),
+                    ApiAttributeType::LONG,
+                )
+            }
+            fname @ ("sum" | "avg" | "min" | "max") => {
+                if args.len() != 1 {
+                    return Err(format!("{fname}() OVER takes exactly one argument"));
+                }
+                let arg_type = args[0].get_return_type();
+                let numeric = matches!(
+                    arg_type,
+                    ApiAttributeType::INT
+                        | ApiAttributeType::LONG
+                        | ApiAttributeType::FLOAT
+                        | ApiAttributeType::DOUBLE
+                );
+                match fname {
+                    "sum" | "avg" if !numeric => {
+                        return Err(format!("{fname}() OVER requires a numeric argument"))
+                    }
+                    "sum" => (
+                        AnalyticKind::Running(RunningAggregate::Sum),
+                        match arg_type {
+                            ApiAttributeType::INT | ApiAttributeType::LONG => {
+                                ApiAttributeType::LONG
+                            }
+                            _ => ApiAttributeType::DOUBLE,
+                        },
+                    ),
+                    "avg" => (
+                        AnalyticKind::Running(RunningAggregate::Avg),
+                        ApiAttributeType::DOUBLE,
+                    ),
+                    "min" => (AnalyticKind::Running(RunningAggregate::Min), arg_type),
+                    _ => (AnalyticKind::Running(RunningAggregate::Max), arg_type),
+                }
+            }
+            other => {
+                return Err(format!(
+                    "Function '{other}' cannot be used with OVER; supported: row_number, lag, lead, sum, count, avg, min, max"
+                ))
+            }
+        };
+
+        let default_exec = if args.len() == 3 { args.pop() } else { None };
+        let value_exec = if args.is_empty() {
+            None
+        } else {
+            Some(args.swap_remove(0))
+        };
+
+        Ok(Self {
+            kind,
+            value_exec,
+            default_exec,
+            partition_key: GroupByKeyGenerator::new(partition_execs),
+            order_by,
+            order_comparator,
+            return_type,
+            state: Self::register_state(query_ctx),
+            results: Mutex::new(Vec::new()),
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    /// Empty partitions registered with the query's snapshot service
+    fn register_state(query_ctx: &EventFluxQueryContext) -> Arc<Mutex<AnalyticState>> {
+        let state = Arc::new(Mutex::new(AnalyticState::default()));
+        let component_id = format!("analytic_{}", query_ctx.next_aggregator_id());
+        SnapshotWindowStateHolder::new(Arc::clone(&state), component_id, "AnalyticFunction")
+            .register(query_ctx);
+        state
+    }
+
+    /// Same call with no partitions yet, registered with `query_ctx`
+    fn clone_function(
+        &self,
+        app_ctx: &Arc<EventFluxAppContext>,
+        query_ctx: &EventFluxQueryContext,
+    ) -> Self {
+        Self {
+            kind: self.kind,
+            value_exec: self.value_exec.as_ref().map(|e| e.clone_executor(app_ctx)),
+            default_exec: self
+                .default_exec
+                .as_ref()
+                .map(|e| e.clone_executor(app_ctx)),
+            partition_key: self.partition_key.clone_with_context(app_ctx),
+            order_by: self.order_by.clone(),
+            order_comparator: self
+                .order_comparator
+                .as_ref()
+                .map(|c| c.clone_with_context(app_ctx)),
+            return_type: self.return_type,
+            state: Self::register_state(query_ctx),
+            results: Mutex::new(Vec::new()),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    /// Compute the value of every event of `events`, in order
+    pub fn prepare(&self, events: &[&dyn ComplexEvent]) {
+        let mut results = vec![AttributeValue::Null; events.len()];
+        // LEAD: rows of each (batch, partition) as (position, value, default)
+        let mut lead_rows: HashMap<(usize, String), Vec<LeadRow>> = HashMap::new();
+        let mut batch = 0usize;
+        let mut state = self.state.lock().unwrap();
+
+        for (pos, event) in events.iter().enumerate() {
+            match event.get_event_type() {
+                ComplexEventType::Reset => {
+                    state.clear();
+                    batch += 1;
+                    continue;
+                }
+                ComplexEventType::Current => {}
+                _ => continue,
+            }
+            let key = self
+                .partition_key
+                .construct_event_key(*event)
+                .unwrap_or_default();
+            let value = self
+                .value_exec
+                .as_ref()
+                .and_then(|e| e.execute(Some(*event)))
+                .unwrap_or(AttributeValue::Null);
+            let default = self
+                .default_exec
+                .as_ref()
+                .and_then(|e| e.execute(Some(*event)))
+                .unwrap_or(AttributeValue::Null);
+
+            if let AnalyticKind::Lead(_) = self.kind {
+                lead_rows
+                    .entry((batch, key))
+                    .or_default()
+                    .push((pos, value, default));
+                continue;
+            }
+            let partition = state.touch(key, MAX_ANALYTIC_PARTITIONS);
+            results[pos] = self.advance(partition, value, default);
+        }
+
+        if let AnalyticKind::Lead(offset) = self.kind {
+            for rows in lead_rows.into_values() {
+                for (i, (pos, _, default)) in rows.iter().enumerate() {
+                    results[*pos] = match rows.get(i + offset) {
+                        Some((_, value, _)) => value.clone(),
+                        None => default.clone(),
+                    };
+                }
+            }
+        }
+
+        *self.results.lock().unwrap() = results;
+        self.cursor.store(0, Ordering::SeqCst);
+    }
+
+    /// Add the current row to its partition and return the function's value
+    fn advance(
+        &self,
+        partition: &mut PartitionState,
+        value: AttributeValue,
+        default: AttributeValue,
+    ) -> AttributeValue {
+        let is_null = matches!(value, AttributeValue::Null);
+        match self.kind {
+            AnalyticKind::RowNumber => {
+                partition.rows += 1;
+                AttributeValue::Long(partition.rows as i64)
+            }
+            AnalyticKind::Lag(offset) => {
+                let lagged = if partition.history.len() == offset {
+                    partition.history.front().cloned().unwrap_or(default)
+                } else {
+                    default
+                };
+                partition.history.push_back(value);
+                if partition.history.len() > offset {
+                    partition.history.pop_front();
+                }
+                lagged
+            }
+            AnalyticKind::Lead(_) => AttributeValue::Null,
+            AnalyticKind::Running(RunningAggregate::Count) => {
+                // COUNT(*) counts every row, COUNT(x) the non-null values
+                if self.value_exec.is_none() || !is_null {
+                    partition.count += 1;
+                }
+                AttributeValue::Long(partition.count as i64)
+            }
+            AnalyticKind::Running(RunningAggregate::Sum) => {
+                if !is_null {
+                    partition.count += 1;
+                    match value {
+                        AttributeValue::Int(i) => {
+                            partition.long_sum = partition.long_sum.wrapping_add(i as i64)
+                        }
+                        AttributeValue::Long(l) => {
+                            partition.long_sum = partition.long_sum.wrapping_add(l)
+                        }
+                        other => partition.double_sum += numeric_value(&other).unwrap_or(0.0),
+                    }
+                }
+                match (partition.count, self.return_type) {
+                    (0, _) => AttributeValue::Null,
+                    (_, ApiAttributeType::LONG) => AttributeValue::Long(partition.long_sum),
+                    _ => AttributeValue::Double(partition.double_sum),
+                }
+            }
+            AnalyticKind::Running(RunningAggregate::Avg) => {
+                if let Some(v) = numeric_value(&value) {
+                    partition.count += 1;
+                    partition.double_sum += v;
+                }
+                if partition.count == 0 {
+                    AttributeValue::Null
+                } else {
+                    AttributeValue::Double(partition.double_sum / partition.count as f64)
+                }
+            }
+            AnalyticKind::Running(aggregate @ (RunningAggregate::Min | RunningAggregate::Max)) => {
+                if !is_null {
+                    let replace = match &partition.extreme {
+                        None => true,
+                        Some(current) => {
+                            let ord = compare_attr_values(&value, current);
+                            if aggregate == RunningAggregate::Min {
+                                ord.is_lt()
+                            } else {
+                                ord.is_gt()
+                            }
+                        }
+                    };
+                    if replace {
+                        partition.extreme = Some(value);
+                    }
+                }
+                partition.extreme.clone().unwrap_or(AttributeValue::Null)
+            }
+        }
+    }
+
+    fn seek(&self, position: usize) {
+        self.cursor.store(position, Ordering::SeqCst);
+    }
+
+    /// Value of the event the select processor is projecting
+    pub fn current_value(&self) -> Option<AttributeValue> {
+        let position = self.cursor.load(Ordering::SeqCst);
+        self.results.lock().unwrap().get(position).cloned()
+    }
+}
+
+/// Select expression node reading an [`AnalyticFunction`]'s value
+#[derive(Debug)]
+pub struct AnalyticFunctionExecutor {
+    function: Arc<AnalyticFunction>,
+}
+
+impl AnalyticFunctionExecutor {
+    pub fn new(function: Arc<AnalyticFunction>) -> Self {
+        Self { function }
+    }
+}
+
+impl ExpressionExecutor for AnalyticFunctionExecutor {
+    fn execute(&self, _event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        self.function.current_value()
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.function.get_return_type()
+    }
+
+    fn clone_executor(
+        &self,
+        _eventflux_app_context: &Arc<EventFluxAppContext>,
+    ) -> Box<dyn ExpressionExecutor> {
+        // Per-group clones of the select list must read the values the
+        // select processor prepared, so they share the function; clones made
+        // for a new select processor read the function rebuilt for it
+        let key = Arc::as_ptr(&self.function) as usize;
+        let function = REBOUND
+            .with(|rebound| rebound.borrow().get(&key).cloned())
+            .unwrap_or_else(|| Arc::clone(&self.function));
+        Box::new(Self { function })
+    }
+}
+
+/// All analytic functions of one query
+#[derive(Debug)]
+pub struct AnalyticStage {
+    functions: Vec<Arc<AnalyticFunction>>,
+}
+
+impl AnalyticStage {
+    /// All functions must agree on their `ORDER BY`, which orders the rows
+    /// of a chunk before any of them is evaluated
+    pub fn new(functions: Vec<Arc<AnalyticFunction>>) -> Result<Self, String> {
+        if let Some(first) = functions.first() {
+            if functions.iter().any(|f| f.order_by != first.order_by) {
+                return Err(
+                    "all analytic functions of a query must use the same ORDER BY".to_string(),
+                );
+            }
+        }
+        Ok(Self { functions })
+    }
+
+    /// Rebuild every function with its own partitions for another select
+    /// processor (e.g. a partition instance) and run `clone_select`, whose
+    /// cloned analytic executors then read the rebuilt functions
+    pub fn clone_stage<R>(
+        &self,
+        app_ctx: &Arc<EventFluxAppContext>,
+        query_ctx: &EventFluxQueryContext,
+        clone_select: impl FnOnce() -> R,
+    ) -> (Self, R) {
+        let functions: Vec<Arc<AnalyticFunction>> = self
+            .functions
+            .iter()
+            .map(|f| Arc::new(f.clone_function(app_ctx, query_ctx)))
+            .collect();
+        REBOUND.with(|rebound| {
+            let mut rebound = rebound.borrow_mut();
+            for (old, new) in self.functions.iter().zip(&functions) {
+                rebound.insert(Arc::as_ptr(old) as usize, Arc::clone(new));
+            }
+        });
+        let cloned = clone_select();
+        REBOUND.with(|rebound| rebound.borrow_mut().clear());
+        (Self { functions }, cloned)
+    }
+
+    /// Order the chunk and compute every function's values for it
+    pub fn prepare_chunk(
+        &self,
+        chunk: Option<Box<dyn ComplexEvent>>,
+    ) -> Option<Box<dyn ComplexEvent>> {
+        let mut events: Vec<Box<dyn ComplexEvent>> = Vec::new();
+        let mut current = chunk;
+        while let Some(mut ev) = current {
+            current = ev.set_next(None);
+            events.push(ev);
+        }
+
+        if let Some(cmp) = self
+            .functions
+            .first()
+            .and_then(|f| f.order_comparator.as_ref())
+        {
+            // Sort each run of current events; expired and reset events keep
+            // their place
+            let mut start = 0;
+            while start < events.len() {
+                let is_current =
+                    |ev: &dyn ComplexEvent| ev.get_event_type() == ComplexEventType::Current;
+                if !is_current(events[start].as_ref()) {
+                    start += 1;
+                    continue;
+                }
+                let end = events[start..]
+                    .iter()
+                    .position(|ev| !is_current(ev.as_ref()))
+                    .map_or(events.len(), |n| start + n);
+                events[start..end].sort_by(|a, b| cmp.compare(a.as_ref(), b.as_ref()));
+                start = end;
+            }
+        }
+
+        {
+            let refs: Vec<&dyn ComplexEvent> = events.iter().map(|e| e.as_ref()).collect();
+            for function in &self.functions {
+                function.prepare(&refs);
+            }
+        }
+
+        let mut head: Option<Box<dyn ComplexEvent>> = None;
+        let mut tail = &mut head;
+        for ev in events {
+            *tail = Some(ev);
+            if let Some(ref mut t) = *tail {
+                tail = t.mut_next_ref_option();
+            }
+        }
+        head
+    }
+
+    /// Point every function at the `position`-th event of the prepared chunk
+    pub fn seek(&self, position: usize) {
+        for function in &self.functions {
+            function.seek(position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::eventflux_context::EventFluxContext;
+    use crate::core::event::stream::StreamEvent;
+    use crate::core::executor::variable_expression_executor::VariableExpressionExecutor;
+    use crate::core::util::eventflux_constants::BEFORE_WINDOW_DATA_INDEX;
+
+    type Select = (Box<dyn ExpressionExecutor>, Box<dyn ExpressionExecutor>);
+
+    fn app_context() -> Arc<EventFluxAppContext> {
+        let app = Arc::new(crate::query_api::eventflux_app::EventFluxApp::new(
+            "TestApp".to_string(),
+        ));
+        Arc::new(EventFluxAppContext::new(
+            Arc::new(EventFluxContext::new()),
+            "TestApp".to_string(),
+            app,
+            String::new(),
+        ))
+    }
+
+    fn query_context(app_ctx: &Arc<EventFluxAppContext>, partition: &str) -> EventFluxQueryContext {
+        EventFluxQueryContext::new(
+            Arc::clone(app_ctx),
+            "q".to_string(),
+            Some(partition.to_string()),
+        )
+    }
+
+    /// Project `(symbol, volume)` rows one chunk at a time, as
+    /// `(ROW_NUMBER(), SUM(volume))`
+    fn project(stage: &AnalyticStage, select: &Select, rows: &[(&str, i32)]) -> Vec<(i64, i64)> {
+        rows.iter()
+            .map(|(symbol, volume)| {
+                let event = StreamEvent::new_with_data(
+                    0,
+                    vec![
+                        AttributeValue::String(symbol.to_string()),
+                        AttributeValue::Int(*volume),
+                    ],
+                );
+                stage.prepare_chunk(Some(Box::new(event)));
+                stage.seek(0);
+                match (select.0.execute(None), select.1.execute(None)) {
+                    (Some(AttributeValue::Long(n)), Some(AttributeValue::Long(total))) => {
+                        (n, total)
+                    }
+                    other => panic!("unexpected analytic values {other:?}"),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cloned_stages_keep_their_own_partitions() {
+        let app_ctx = app_context();
+        let query_ctx = query_context(&app_ctx, "template");
+        let volume = || -> Box<dyn ExpressionExecutor> {
+            Box::new(VariableExpressionExecutor::new(
+                [0, 0, BEFORE_WINDOW_DATA_INDEX as i32, 1],
+                ApiAttributeType::INT,
+                "volume".to_string(),
+            ))
+        };
+        let row_number = Arc::new(
+            AnalyticFunction::new("row_number", vec![], vec![], vec![], None, &query_ctx).unwrap(),
+        );
+        let sum = Arc::new(
+            AnalyticFunction::new("sum", vec![volume()], vec![], vec![], None, &query_ctx).unwrap(),
+        );
+        let stage = AnalyticStage::new(vec![Arc::clone(&row_number), Arc::clone(&sum)]).unwrap();
+        let select: Select = (
+            Box::new(AnalyticFunctionExecutor::new(row_number)),
+            Box::new(AnalyticFunctionExecutor::new(sum)),
+        );
+
+        // One select processor per partition key, as a partition instance
+        // clones it
+        let instance = |key: &str| {
+            stage.clone_stage(&app_ctx, &query_context(&app_ctx, key), || {
+                (
+                    select.0.clone_executor(&app_ctx),
+                    select.1.clone_executor(&app_ctx),
+                )
+            })
+        };
+        let (stage_a, select_a) = instance("A");
+        let (stage_b, select_b) = instance("B");
+
+        assert_eq!(project(&stage_a, &select_a, &[("A", 100)]), vec![(1, 100)]);
+        assert_eq!(project(&stage_b, &select_b, &[("B", 5)]), vec![(1, 5)]);
+        assert_eq!(
+            project(&stage_a, &select_a, &[("A", 50), ("A", 1)]),
+            vec![(2, 150), (3, 151)]
+        );
+        assert_eq!(project(&stage_b, &select_b, &[("B", 5)]), vec![(2, 10)]);
+
+        // Per-group clones within an instance read the values it prepared
+        let group_clone = select_a.0.clone_executor(&app_ctx);
+        assert_eq!(group_clone.execute(None), Some(AttributeValue::Long(3)));
+
+        // The template has seen none of the instances' rows
+        assert_eq!(project(&stage, &select, &[("C", 7)]), vec![(1, 7)]);
+    }
+}
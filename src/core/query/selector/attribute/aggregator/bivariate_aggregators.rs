@@ -0,0 +1,628 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/selector/attribute/aggregator/bivariate_aggregators.rs
+
+//! Two-argument aggregators
+//!
+//! | Aggregator | Result |
+//! |------------|--------|
+//! | `corr(x, y)` | Pearson correlation coefficient |
+//! | `covar_pop(x, y)`, `covar_samp(x, y)` | Population / sample covariance |
+//! | `regr_slope(y, x)`, `regr_intercept(y, x)` | Least-squares fit of `y` on `x` (SQL argument order) |
+//! | `wavg(value, weight)` | Weighted average |
+//! | `arg_max(by, value)`, `arg_min(by, value)` | `value` of the event with the largest / smallest `by` |
+//!
+//! Events where either argument is null are ignored. All aggregators update
+//! incrementally on both add and remove, so they work in sliding windows, and
+//! their state is persisted as a full snapshot.
+
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dispatch_args, value_as_f64, AttributeAggregatorExecutor, AttributeAggregatorExpressionExecutor,
+};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::ComplexEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::AttributeAggregatorFactory;
+use crate::core::query::processor::stream::window::snapshot_window_state_holder::{
+    SnapshotWindowStateHolder, WindowState,
+};
+use crate::core::query::processor::ProcessingMode;
+use crate::core::query::selector::order_by_event_comparator::compare_attr_values;
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+
+/// State of a two-argument aggregator; only called with non-null arguments
+pub trait BivariateState: WindowState + Clone + Default {
+    fn add(&mut self, a: &AttributeValue, b: &AttributeValue);
+    fn remove(&mut self, a: &AttributeValue, b: &AttributeValue);
+    fn clear(&mut self);
+    fn result(&self) -> Option<AttributeValue>;
+}
+
+// ============================================================================
+// Co-moments: corr, covar_*, regr_*
+// ============================================================================
+
+/// Statistic derived from [`CoMomentState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CoMomentStat {
+    #[default]
+    Corr,
+    CovarPop,
+    CovarSamp,
+    /// Slope of the first argument regressed on the second
+    RegrSlope,
+    /// Intercept of the first argument regressed on the second
+    RegrIntercept,
+}
+
+/// Running means and co-moments (Welford's algorithm extended to two
+/// variables), reversible so values can be removed again
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoMomentState {
+    stat: CoMomentStat,
+    count: u64,
+    mean_a: f64,
+    mean_b: f64,
+    /// Sum of (a - mean_a)^2
+    m2_a: f64,
+    /// Sum of (b - mean_b)^2
+    m2_b: f64,
+    /// Sum of (a - mean_a) * (b - mean_b)
+    c_ab: f64,
+}
+
+impl CoMomentState {
+    pub fn new(stat: CoMomentStat) -> Self {
+        Self {
+            stat,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_values(&mut self, a: f64, b: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let da = a - self.mean_a;
+        let db = b - self.mean_b;
+        self.mean_a += da / n;
+        self.mean_b += db / n;
+        self.m2_a += da * (a - self.mean_a);
+        self.m2_b += db * (b - self.mean_b);
+        self.c_ab += da * (b - self.mean_b);
+    }
+
+    pub fn remove_values(&mut self, a: f64, b: f64) {
+        if self.count <= 1 {
+            *self = Self::new(self.stat);
+            return;
+        }
+        let n = self.count as f64;
+        let prev_mean_a = (n * self.mean_a - a) / (n - 1.0);
+        let prev_mean_b = (n * self.mean_b - b) / (n - 1.0);
+        self.m2_a -= (a - prev_mean_a) * (a - self.mean_a);
+        self.m2_b -= (b - prev_mean_b) * (b - self.mean_b);
+        self.c_ab -= (a - prev_mean_a) * (b - self.mean_b);
+        self.mean_a = prev_mean_a;
+        self.mean_b = prev_mean_b;
+        self.count -= 1;
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        let n = self.count as f64;
+        // Clamp rounding drift left behind by removals
+        let m2_a = self.m2_a.max(0.0);
+        let m2_b = self.m2_b.max(0.0);
+        match self.stat {
+            CoMomentStat::CovarPop if self.count >= 1 => Some(self.c_ab / n),
+            CoMomentStat::CovarSamp if self.count >= 2 => Some(self.c_ab / (n - 1.0)),
+            CoMomentStat::Corr if m2_a > 0.0 && m2_b > 0.0 => {
+                Some((self.c_ab / (m2_a * m2_b).sqrt()).clamp(-1.0, 1.0))
+            }
+            CoMomentStat::RegrSlope if m2_b > 0.0 => Some(self.c_ab / m2_b),
+            CoMomentStat::RegrIntercept if m2_b > 0.0 => {
+                Some(self.mean_a - self.c_ab / m2_b * self.mean_b)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl WindowState for CoMomentState {
+    fn entries(&self) -> usize {
+        self.count as usize
+    }
+}
+
+impl BivariateState for CoMomentState {
+    fn add(&mut self, a: &AttributeValue, b: &AttributeValue) {
+        if let (Some(a), Some(b)) = (value_as_f64(a), value_as_f64(b)) {
+            self.add_values(a, b);
+        }
+    }
+
+    fn remove(&mut self, a: &AttributeValue, b: &AttributeValue) {
+        if let (Some(a), Some(b)) = (value_as_f64(a), value_as_f64(b)) {
+            self.remove_values(a, b);
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::new(self.stat);
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        self.value().map(AttributeValue::Double)
+    }
+}
+
+// ============================================================================
+// Weighted average
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeightedAvgState {
+    count: u64,
+    weighted_sum: f64,
+    weight_sum: f64,
+}
+
+impl WindowState for WeightedAvgState {
+    fn entries(&self) -> usize {
+        self.count as usize
+    }
+}
+
+impl BivariateState for WeightedAvgState {
+    fn add(&mut self, a: &AttributeValue, b: &AttributeValue) {
+        if let (Some(v), Some(w)) = (value_as_f64(a), value_as_f64(b)) {
+            self.count += 1;
+            self.weighted_sum += v * w;
+            self.weight_sum += w;
+        }
+    }
+
+    fn remove(&mut self, a: &AttributeValue, b: &AttributeValue) {
+        if let (Some(v), Some(w)) = (value_as_f64(a), value_as_f64(b)) {
+            if self.count <= 1 {
+                *self = Self::default();
+                return;
+            }
+            self.count -= 1;
+            self.weighted_sum -= v * w;
+            self.weight_sum -= w;
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        (self.count > 0 && self.weight_sum != 0.0)
+            .then(|| AttributeValue::Double(self.weighted_sum / self.weight_sum))
+    }
+}
+
+// ============================================================================
+// arg_max / arg_min
+// ============================================================================
+
+/// `(by, value)` pairs kept sorted by `by`; ties keep arrival order and
+/// resolve to the earliest event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArgExtremumState {
+    max: bool,
+    entries: Vec<(AttributeValue, AttributeValue)>,
+}
+
+impl ArgExtremumState {
+    pub fn new(max: bool) -> Self {
+        Self {
+            max,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl WindowState for ArgExtremumState {
+    fn entries(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl BivariateState for ArgExtremumState {
+    fn add(&mut self, by: &AttributeValue, value: &AttributeValue) {
+        let pos = self
+            .entries
+            .partition_point(|(b, _)| compare_attr_values(b, by) != Ordering::Greater);
+        self.entries.insert(pos, (by.clone(), value.clone()));
+    }
+
+    fn remove(&mut self, by: &AttributeValue, value: &AttributeValue) {
+        let start = self
+            .entries
+            .partition_point(|(b, _)| compare_attr_values(b, by) == Ordering::Less);
+        if let Some(offset) = self.entries[start..]
+            .iter()
+            .take_while(|(b, _)| compare_attr_values(b, by) == Ordering::Equal)
+            .position(|(_, v)| v == value)
+        {
+            self.entries.remove(start + offset);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        let (_, value) = if self.max {
+            let (max_by, _) = self.entries.last()?;
+            let first_of_max = self
+                .entries
+                .partition_point(|(b, _)| compare_attr_values(b, max_by) == Ordering::Less);
+            &self.entries[first_of_max]
+        } else {
+            self.entries.first()?
+        };
+        Some(value.clone())
+    }
+}
+
+// ============================================================================
+// Executor
+// ============================================================================
+
+/// Aggregator over exactly two arguments, accumulating into a [`BivariateState`]
+#[derive(Debug)]
+pub struct BivariateAggregatorExecutor<S: BivariateState> {
+    name: &'static str,
+    return_type: ApiAttributeType,
+    /// Take the return type from the second argument (`arg_max`/`arg_min`)
+    returns_second_arg: bool,
+    arg_execs: Vec<Box<dyn ExpressionExecutor>>,
+    state: Arc<Mutex<S>>,
+    app_ctx: Option<Arc<EventFluxAppContext>>,
+}
+
+impl<S: BivariateState> BivariateAggregatorExecutor<S> {
+    pub fn new(name: &'static str, return_type: ApiAttributeType, state: S) -> Self {
+        Self {
+            name,
+            return_type,
+            returns_second_arg: false,
+            arg_execs: Vec::new(),
+            state: Arc::new(Mutex::new(state)),
+            app_ctx: None,
+        }
+    }
+
+    pub fn returning_second_arg(name: &'static str, state: S) -> Self {
+        Self {
+            returns_second_arg: true,
+            ..Self::new(name, ApiAttributeType::OBJECT, state)
+        }
+    }
+}
+
+impl<S: BivariateState> AttributeAggregatorExecutor for BivariateAggregatorExecutor<S> {
+    fn init(
+        &mut self,
+        e: Vec<Box<dyn ExpressionExecutor>>,
+        _m: ProcessingMode,
+        _ex: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        if e.len() != 2 {
+            return Err(format!("{}() requires exactly two arguments", self.name));
+        }
+        if self.returns_second_arg {
+            self.return_type = e[1].get_return_type();
+        }
+        self.arg_execs = e;
+        self.app_ctx = Some(Arc::clone(&ctx.eventflux_app_context));
+
+        let component_id = format!("{}_aggregator_{}", self.name, ctx.next_aggregator_id());
+        SnapshotWindowStateHolder::new(Arc::clone(&self.state), component_id, self.name)
+            .register(ctx);
+        Ok(())
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.process_add_args(vec![data])
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.process_remove_args(vec![data])
+    }
+
+    fn process_add_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        let mut st = self.state.lock().unwrap();
+        if let [Some(a), Some(b)] = args.as_slice() {
+            st.add(a, b);
+        }
+        st.result()
+    }
+
+    fn process_remove_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        let mut st = self.state.lock().unwrap();
+        if let [Some(a), Some(b)] = args.as_slice() {
+            st.remove(a, b);
+        }
+        st.result()
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        self.state.lock().unwrap().clear();
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = self.app_ctx.as_ref().unwrap();
+        Box::new(Self {
+            name: self.name,
+            return_type: self.return_type,
+            returns_second_arg: self.returns_second_arg,
+            arg_execs: self
+                .arg_execs
+                .iter()
+                .map(|e| e.clone_executor(ctx))
+                .collect(),
+            state: Arc::new(Mutex::new(self.state.lock().unwrap().clone())),
+            app_ctx: Some(Arc::clone(ctx)),
+        })
+    }
+}
+
+impl<S: BivariateState> ExpressionExecutor for BivariateAggregatorExecutor<S> {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        dispatch_args(self, &self.arg_execs, event)
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Factories
+// ============================================================================
+
+/// Factory for `corr`, `covar_pop`, `covar_samp`, `regr_slope` and `regr_intercept`
+#[derive(Debug, Clone)]
+pub struct CoMomentAttributeAggregatorFactory(pub CoMomentStat);
+
+impl AttributeAggregatorFactory for CoMomentAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            CoMomentStat::Corr => "corr",
+            CoMomentStat::CovarPop => "covar_pop",
+            CoMomentStat::CovarSamp => "covar_samp",
+            CoMomentStat::RegrSlope => "regr_slope",
+            CoMomentStat::RegrIntercept => "regr_intercept",
+        }
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(BivariateAggregatorExecutor::new(
+            self.name(),
+            ApiAttributeType::DOUBLE,
+            CoMomentState::new(self.0),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(self.clone())
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn description(&self) -> &str {
+        match self.0 {
+            CoMomentStat::Corr => "Pearson correlation coefficient of two numeric arguments",
+            CoMomentStat::CovarPop => "Population covariance of two numeric arguments",
+            CoMomentStat::CovarSamp => "Sample covariance of two numeric arguments",
+            CoMomentStat::RegrSlope => "Slope of the least-squares line fitting y on x",
+            CoMomentStat::RegrIntercept => "Intercept of the least-squares line fitting y on x",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightedAvgAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for WeightedAvgAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "wavg"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(BivariateAggregatorExecutor::new(
+            "wavg",
+            ApiAttributeType::DOUBLE,
+            WeightedAvgState::default(),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn description(&self) -> &str {
+        "Average of value weighted by weight"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgMaxAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for ArgMaxAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "arg_max"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(BivariateAggregatorExecutor::returning_second_arg(
+            "arg_max",
+            ArgExtremumState::new(true),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn return_type(&self, arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        arg_types
+            .get(1)
+            .copied()
+            .ok_or_else(|| "arg_max() requires two arguments".to_string())
+    }
+    fn description(&self) -> &str {
+        "Value of the event with the largest ordering key"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgMinAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for ArgMinAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "arg_min"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(BivariateAggregatorExecutor::returning_second_arg(
+            "arg_min",
+            ArgExtremumState::new(false),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn return_type(&self, arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        arg_types
+            .get(1)
+            .copied()
+            .ok_or_else(|| "arg_min() requires two arguments".to_string())
+    }
+    fn description(&self) -> &str {
+        "Value of the event with the smallest ordering key"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn co_moment(stat: CoMomentStat, pairs: &[(f64, f64)]) -> Option<f64> {
+        let mut st = CoMomentState::new(stat);
+        for &(a, b) in pairs {
+            st.add_values(a, b);
+        }
+        st.value()
+    }
+
+    #[test]
+    fn test_co_moment_statistics() {
+        // y = 2x + 1
+        let pairs = [(3.0, 1.0), (5.0, 2.0), (7.0, 3.0), (9.0, 4.0)];
+        assert!(approx_eq(
+            co_moment(CoMomentStat::Corr, &pairs).unwrap(),
+            1.0
+        ));
+        assert!(approx_eq(
+            co_moment(CoMomentStat::CovarPop, &pairs).unwrap(),
+            2.5
+        ));
+        assert!(approx_eq(
+            co_moment(CoMomentStat::CovarSamp, &pairs).unwrap(),
+            10.0 / 3.0
+        ));
+        assert!(approx_eq(
+            co_moment(CoMomentStat::RegrSlope, &pairs).unwrap(),
+            2.0
+        ));
+        assert!(approx_eq(
+            co_moment(CoMomentStat::RegrIntercept, &pairs).unwrap(),
+            1.0
+        ));
+        assert_eq!(co_moment(CoMomentStat::CovarSamp, &pairs[..1]), None);
+        assert_eq!(
+            co_moment(CoMomentStat::Corr, &[(1.0, 1.0), (1.0, 2.0)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_co_moment_remove_matches_recompute() {
+        let pairs = [(1.0, 4.0), (2.0, -1.0), (6.0, 3.5), (4.0, 8.0), (-3.0, 2.0)];
+        let mut sliding = CoMomentState::new(CoMomentStat::CovarSamp);
+        for &(a, b) in &pairs {
+            sliding.add_values(a, b);
+        }
+        sliding.remove_values(pairs[0].0, pairs[0].1);
+        sliding.remove_values(pairs[1].0, pairs[1].1);
+        let expected = co_moment(CoMomentStat::CovarSamp, &pairs[2..]).unwrap();
+        assert!(approx_eq(sliding.value().unwrap(), expected));
+
+        for &(a, b) in &pairs[2..] {
+            sliding.remove_values(a, b);
+        }
+        assert_eq!(sliding.count, 0);
+        assert_eq!(sliding.value(), None);
+    }
+
+    #[test]
+    fn test_weighted_avg() {
+        let mut st = WeightedAvgState::default();
+        st.add(&AttributeValue::Double(10.0), &AttributeValue::Int(1));
+        st.add(&AttributeValue::Double(20.0), &AttributeValue::Int(3));
+        assert_eq!(st.result(), Some(AttributeValue::Double(17.5)));
+        st.remove(&AttributeValue::Double(10.0), &AttributeValue::Int(1));
+        assert_eq!(st.result(), Some(AttributeValue::Double(20.0)));
+        st.remove(&AttributeValue::Double(20.0), &AttributeValue::Int(3));
+        assert_eq!(st.result(), None);
+    }
+
+    #[test]
+    fn test_arg_max_and_min() {
+        let s = |v: &str| AttributeValue::String(v.to_string());
+        let mut max = ArgExtremumState::new(true);
+        let mut min = ArgExtremumState::new(false);
+        for (by, value) in [(5, "a"), (9, "b"), (1, "c"), (9, "d")] {
+            max.add(&AttributeValue::Int(by), &s(value));
+            min.add(&AttributeValue::Int(by), &s(value));
+        }
+        // Ties resolve to the earliest event
+        assert_eq!(max.result(), Some(s("b")));
+        assert_eq!(min.result(), Some(s("c")));
+
+        max.remove(&AttributeValue::Int(9), &s("b"));
+        min.remove(&AttributeValue::Int(1), &s("c"));
+        assert_eq!(max.result(), Some(s("d")));
+        assert_eq!(min.result(), Some(s("a")));
+
+        max.clear();
+        assert_eq!(max.result(), None);
+    }
+}
@@ -240,6 +240,13 @@ impl StateHolder for CountAggregatorStateHolder {
         Ok(())
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        let mut change_log = self.change_log.lock().unwrap();
+        let committed = operations.min(change_log.len());
+        change_log.drain(..committed);
+        *self.last_checkpoint_id.lock().unwrap() = Some(checkpoint_id);
+    }
+
     fn estimate_size(&self) -> StateSize {
         // Count aggregator has minimal memory footprint - just stores i64 value
         let base_size = std::mem::size_of::<i64>();
@@ -306,6 +306,13 @@ impl StateHolder for DistinctCountAggregatorStateHolder {
         Ok(())
     }
 
+    fn commit_changelog(&self, checkpoint_id: CheckpointId, operations: usize) {
+        let mut change_log = self.change_log.lock().unwrap();
+        let committed = operations.min(change_log.len());
+        change_log.drain(..committed);
+        *self.last_checkpoint_id.lock().unwrap() = Some(checkpoint_id);
+    }
+
     fn estimate_size(&self) -> StateSize {
         // Distinct count aggregator memory footprint depends on number of distinct values
         let map = self.map.lock().unwrap();
@@ -9,8 +9,12 @@ use crate::core::config::eventflux_query_context::EventFluxQueryContext;
 use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::query::processor::stream::window::snapshot_window_state_holder::{
+    SnapshotWindowStateHolder, WindowState,
+};
 use crate::core::query::processor::ProcessingMode;
 use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use serde::{Deserialize, Serialize};
 
 pub trait AttributeAggregatorExecutor: ExpressionExecutor {
     fn init(
@@ -23,6 +27,21 @@ pub trait AttributeAggregatorExecutor: ExpressionExecutor {
 
     fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue>;
     fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue>;
+
+    /// Add one event's argument values, in argument order, as dispatched by
+    /// [`MultiArgAggregatorExecutor`]. Single-argument aggregators keep the
+    /// default, which forwards the first value to [`process_add`](Self::process_add);
+    /// the parser rejects calls with more arguments than the factory's arity.
+    fn process_add_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.process_add(args.into_iter().next().flatten())
+    }
+
+    /// Remove one event's argument values; counterpart of
+    /// [`process_add_args`](Self::process_add_args)
+    fn process_remove_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.process_remove(args.into_iter().next().flatten())
+    }
+
     fn reset(&self) -> Option<AttributeValue>;
     fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor>;
 }
@@ -73,6 +92,297 @@ impl ExpressionExecutor for AttributeAggregatorExpressionExecutor {
     }
 }
 
+// ============================================================================
+// Multi-argument dispatch
+// ============================================================================
+
+/// Evaluate `args` on the event and feed the values to the aggregator
+/// according to the event type
+pub fn dispatch_args(
+    aggregator: &dyn AttributeAggregatorExecutor,
+    args: &[Box<dyn ExpressionExecutor>],
+    event: Option<&dyn ComplexEvent>,
+) -> Option<AttributeValue> {
+    let event = event?;
+    let values = || args.iter().map(|e| e.execute(Some(event))).collect();
+    match event.get_event_type() {
+        ComplexEventType::Current => aggregator.process_add_args(values()),
+        ComplexEventType::Expired => aggregator.process_remove_args(values()),
+        ComplexEventType::Reset => aggregator.reset(),
+        _ => None,
+    }
+}
+
+/// Drives an aggregator called with several arguments through
+/// [`process_add_args`](AttributeAggregatorExecutor::process_add_args) and
+/// [`process_remove_args`](AttributeAggregatorExecutor::process_remove_args)
+///
+/// The parser wraps every aggregator call with more than one argument, so an
+/// aggregator only has to implement the `*_args` methods to receive them.
+#[derive(Debug)]
+pub struct MultiArgAggregatorExecutor {
+    inner: Box<dyn AttributeAggregatorExecutor>,
+    /// Copies of the executors the aggregator was initialised with
+    args: Vec<Box<dyn ExpressionExecutor>>,
+    app_ctx: Arc<EventFluxAppContext>,
+}
+
+impl MultiArgAggregatorExecutor {
+    pub fn new(
+        inner: Box<dyn AttributeAggregatorExecutor>,
+        args: Vec<Box<dyn ExpressionExecutor>>,
+        ctx: &EventFluxQueryContext,
+    ) -> Self {
+        Self {
+            inner,
+            args,
+            app_ctx: Arc::clone(&ctx.eventflux_app_context),
+        }
+    }
+}
+
+impl AttributeAggregatorExecutor for MultiArgAggregatorExecutor {
+    fn init(
+        &mut self,
+        executors: Vec<Box<dyn ExpressionExecutor>>,
+        processing_mode: ProcessingMode,
+        expired_output: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        self.args = executors
+            .iter()
+            .map(|e| e.clone_executor(&ctx.eventflux_app_context))
+            .collect();
+        self.inner
+            .init(executors, processing_mode, expired_output, ctx)
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.inner.process_add(data)
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.inner.process_remove(data)
+    }
+
+    fn process_add_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.inner.process_add_args(args)
+    }
+
+    fn process_remove_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.inner.process_remove_args(args)
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        self.inner.reset()
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = &self.app_ctx;
+        Box::new(Self {
+            inner: self.inner.clone_box(),
+            args: self.args.iter().map(|e| e.clone_executor(ctx)).collect(),
+            app_ctx: Arc::clone(ctx),
+        })
+    }
+}
+
+impl ExpressionExecutor for MultiArgAggregatorExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        dispatch_args(self.inner.as_ref(), &self.args, event)
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.inner.get_return_type()
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// FILTER (WHERE ...) and DISTINCT modifiers
+// ============================================================================
+
+/// State of [`ModifiedAttributeAggregatorExecutor`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AggregateModifierState {
+    /// Live occurrences of each distinct argument tuple
+    distinct: HashMap<String, u64>,
+    /// Last value produced by the wrapped aggregator, returned for events it
+    /// does not see
+    last: Option<AttributeValue>,
+}
+
+impl WindowState for AggregateModifierState {
+    fn entries(&self) -> usize {
+        self.distinct.len()
+    }
+}
+
+/// Applies `agg(...) FILTER (WHERE cond)` and `agg(DISTINCT ...)` to any aggregator
+///
+/// The filter is evaluated on current and expired events alike, so an event
+/// that was filtered out on arrival is also skipped when it expires. With
+/// DISTINCT, only the first occurrence of an argument tuple reaches the wrapped
+/// aggregator on add, and only the last live occurrence on remove.
+#[derive(Debug)]
+pub struct ModifiedAttributeAggregatorExecutor {
+    inner: Box<dyn AttributeAggregatorExecutor>,
+    filter: Option<Box<dyn ExpressionExecutor>>,
+    /// Copies of the aggregator's argument executors, used to key DISTINCT
+    distinct_args: Option<Vec<Box<dyn ExpressionExecutor>>>,
+    state: Arc<Mutex<AggregateModifierState>>,
+    app_ctx: Arc<EventFluxAppContext>,
+}
+
+impl ModifiedAttributeAggregatorExecutor {
+    pub fn new(
+        inner: Box<dyn AttributeAggregatorExecutor>,
+        filter: Option<Box<dyn ExpressionExecutor>>,
+        distinct_args: Option<Vec<Box<dyn ExpressionExecutor>>>,
+        ctx: &EventFluxQueryContext,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(AggregateModifierState::default()));
+        let component_id = format!("aggregate_modifier_{}", ctx.next_aggregator_id());
+        SnapshotWindowStateHolder::new(Arc::clone(&state), component_id, "aggregate_modifier")
+            .register(ctx);
+        Self {
+            inner,
+            filter,
+            distinct_args,
+            state,
+            app_ctx: Arc::clone(&ctx.eventflux_app_context),
+        }
+    }
+
+    fn passes_filter(&self, event: &dyn ComplexEvent) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|f| matches!(f.execute(Some(event)), Some(AttributeValue::Bool(true))))
+    }
+
+    /// Whether the event changes the DISTINCT set's membership
+    fn updates_distinct_set(&self, event: &dyn ComplexEvent, add: bool) -> bool {
+        let Some(args) = &self.distinct_args else {
+            return true;
+        };
+        let values: Vec<Option<AttributeValue>> =
+            args.iter().map(|e| e.execute(Some(event))).collect();
+        let key = format!("{values:?}");
+        let mut st = self.state.lock().unwrap();
+        if add {
+            let count = st.distinct.entry(key).or_insert(0);
+            *count += 1;
+            *count == 1
+        } else {
+            match st.distinct.get_mut(&key) {
+                Some(count) if *count > 1 => {
+                    *count -= 1;
+                    false
+                }
+                Some(_) => {
+                    st.distinct.remove(&key);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    fn forward(&self, event: &dyn ComplexEvent) -> Option<AttributeValue> {
+        let result = self.inner.execute(Some(event));
+        self.state.lock().unwrap().last = result.clone();
+        result
+    }
+}
+
+impl AttributeAggregatorExecutor for ModifiedAttributeAggregatorExecutor {
+    fn init(
+        &mut self,
+        executors: Vec<Box<dyn ExpressionExecutor>>,
+        processing_mode: ProcessingMode,
+        expired_output: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        self.inner
+            .init(executors, processing_mode, expired_output, ctx)
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.inner.process_add(data)
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        self.inner.process_remove(data)
+    }
+
+    fn process_add_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.inner.process_add_args(args)
+    }
+
+    fn process_remove_args(&self, args: Vec<Option<AttributeValue>>) -> Option<AttributeValue> {
+        self.inner.process_remove_args(args)
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        *self.state.lock().unwrap() = AggregateModifierState::default();
+        self.inner.reset()
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = &self.app_ctx;
+        Box::new(Self {
+            inner: self.inner.clone_box(),
+            filter: self.filter.as_ref().map(|f| f.clone_executor(ctx)),
+            distinct_args: self
+                .distinct_args
+                .as_ref()
+                .map(|args| args.iter().map(|e| e.clone_executor(ctx)).collect()),
+            state: Arc::new(Mutex::new(self.state.lock().unwrap().clone())),
+            app_ctx: Arc::clone(ctx),
+        })
+    }
+}
+
+impl ExpressionExecutor for ModifiedAttributeAggregatorExecutor {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let event = event?;
+        match event.get_event_type() {
+            ComplexEventType::Current | ComplexEventType::Expired => {
+                let add = event.get_event_type() == ComplexEventType::Current;
+                if self.passes_filter(event) && self.updates_distinct_set(event, add) {
+                    self.forward(event)
+                } else {
+                    self.state.lock().unwrap().last.clone()
+                }
+            }
+            ComplexEventType::Reset => {
+                *self.state.lock().unwrap() = AggregateModifierState::default();
+                self.inner.execute(Some(event))
+            }
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.inner.get_return_type()
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod adapter_tests {
     use super::*;
@@ -515,6 +825,16 @@ impl crate::core::persistence::state_holder::StateHolder for SumAttributeAggrega
         }
     }
 
+    fn commit_changelog(
+        &self,
+        checkpoint_id: crate::core::persistence::state_holder::CheckpointId,
+        operations: usize,
+    ) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> crate::core::persistence::state_holder::StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -772,6 +1092,16 @@ impl crate::core::persistence::state_holder::StateHolder for AvgAttributeAggrega
         }
     }
 
+    fn commit_changelog(
+        &self,
+        checkpoint_id: crate::core::persistence::state_holder::CheckpointId,
+        operations: usize,
+    ) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> crate::core::persistence::state_holder::StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -1076,6 +1406,16 @@ impl crate::core::persistence::state_holder::StateHolder for CountAttributeAggre
         }
     }
 
+    fn commit_changelog(
+        &self,
+        checkpoint_id: crate::core::persistence::state_holder::CheckpointId,
+        operations: usize,
+    ) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> crate::core::persistence::state_holder::StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -1324,6 +1664,16 @@ impl crate::core::persistence::state_holder::StateHolder
         }
     }
 
+    fn commit_changelog(
+        &self,
+        checkpoint_id: crate::core::persistence::state_holder::CheckpointId,
+        operations: usize,
+    ) {
+        if let Some(ref state_holder) = self.state_holder {
+            state_holder.commit_changelog(checkpoint_id, operations);
+        }
+    }
+
     fn estimate_size(&self) -> crate::core::persistence::state_holder::StateSize {
         if let Some(ref state_holder) = self.state_holder {
             state_holder.estimate_size()
@@ -1881,6 +2231,18 @@ pub use max_aggregator_state_holder::MaxAggregatorStateHolder;
 pub mod distinctcount_aggregator_state_holder;
 pub use distinctcount_aggregator_state_holder::DistinctCountAggregatorStateHolder;
 
+pub mod bivariate_aggregators;
+pub use bivariate_aggregators::{
+    ArgMaxAttributeAggregatorFactory, ArgMinAttributeAggregatorFactory,
+    CoMomentAttributeAggregatorFactory, CoMomentStat, WeightedAvgAttributeAggregatorFactory,
+};
+
+pub mod sketch_aggregators;
+pub use sketch_aggregators::{
+    ApproxDistinctAttributeAggregatorFactory, MedianAttributeAggregatorFactory,
+    PercentileAttributeAggregatorFactory, TopKAttributeAggregatorFactory,
+};
+
 #[derive(Debug, Clone)]
 pub struct SumAttributeAggregatorFactory;
 
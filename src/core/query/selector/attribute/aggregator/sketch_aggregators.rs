@@ -0,0 +1,850 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+// eventflux_rust/src/core/query/selector/attribute/aggregator/sketch_aggregators.rs
+
+//! Approximate aggregators backed by bounded-memory sketches
+//!
+//! | Aggregator | Sketch | Result |
+//! |------------|--------|--------|
+//! | `percentile(x, q)`, `median(x)` | DDSketch (1% relative error) | DOUBLE |
+//! | `approx_distinct(x)` | HyperLogLog (4096 registers, ~1.6% error) | LONG |
+//! | `top_k(x, k)` | Space-saving | OBJECT (list of `[value, count]` pairs) |
+//!
+//! All three support `process_remove`: DDSketch and HyperLogLog keep per-bucket
+//! counts so removals are exact with respect to the sketch, while space-saving
+//! decrements monitored counters (an approximation once items were evicted).
+//! Sketch state is persisted as a full snapshot.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::{value_as_f64, AttributeAggregatorExecutor, AttributeAggregatorExpressionExecutor};
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::event::value::AttributeValue;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::extension::AttributeAggregatorFactory;
+use crate::core::query::processor::stream::window::snapshot_window_state_holder::{
+    SnapshotWindowStateHolder, WindowState,
+};
+use crate::core::query::processor::ProcessingMode;
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+
+/// Sketch state driven by [`SketchAggregatorExecutor`]
+pub trait Sketch: WindowState + Clone {
+    /// Apply the aggregator's constant parameters (e.g. the quantile)
+    fn configure(&mut self, params: &[AttributeValue]) -> Result<(), String>;
+    fn add(&mut self, value: &AttributeValue);
+    fn remove(&mut self, value: &AttributeValue);
+    fn clear(&mut self);
+    fn result(&self) -> Option<AttributeValue>;
+}
+
+// ============================================================================
+// DDSketch
+// ============================================================================
+
+const DDSKETCH_RELATIVE_ACCURACY: f64 = 0.01;
+const DDSKETCH_MAX_BINS: usize = 2048;
+/// Magnitudes below this are counted as zero
+const DDSKETCH_MIN_VALUE: f64 = 1e-9;
+
+/// Log-bucketed store for values of one sign, keyed by bucket index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BinStore {
+    bins: BTreeMap<i32, u64>,
+}
+
+impl BinStore {
+    fn add(&mut self, index: i32) {
+        *self.bins.entry(index).or_insert(0) += 1;
+        // Collapse the smallest-magnitude bins to bound memory
+        while self.bins.len() > DDSKETCH_MAX_BINS {
+            let (_, count) = self.bins.pop_first().unwrap();
+            *self.bins.first_entry().unwrap().get_mut() += count;
+        }
+    }
+
+    fn remove(&mut self, index: i32) {
+        // Values below the lowest bin were collapsed into it
+        let key = match self.bins.first_key_value() {
+            Some((&first, _)) if index < first => first,
+            Some(_) => index,
+            None => return,
+        };
+        if let Some(count) = self.bins.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                self.bins.remove(&key);
+            }
+        }
+    }
+}
+
+/// Quantile sketch with relative-error guarantees that supports deletion
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DDSketch {
+    positive: BinStore,
+    negative: BinStore,
+    zero_count: u64,
+    count: u64,
+}
+
+impl DDSketch {
+    fn gamma() -> f64 {
+        (1.0 + DDSKETCH_RELATIVE_ACCURACY) / (1.0 - DDSKETCH_RELATIVE_ACCURACY)
+    }
+
+    fn index(magnitude: f64) -> i32 {
+        (magnitude.ln() / Self::gamma().ln()).ceil() as i32
+    }
+
+    fn bin_value(index: i32) -> f64 {
+        let gamma = Self::gamma();
+        2.0 * gamma.powi(index) / (gamma + 1.0)
+    }
+
+    pub fn add(&mut self, v: f64) {
+        if !v.is_finite() {
+            return;
+        }
+        if v > DDSKETCH_MIN_VALUE {
+            self.positive.add(Self::index(v));
+        } else if v < -DDSKETCH_MIN_VALUE {
+            self.negative.add(Self::index(-v));
+        } else {
+            self.zero_count += 1;
+        }
+        self.count += 1;
+    }
+
+    pub fn remove(&mut self, v: f64) {
+        if !v.is_finite() || self.count == 0 {
+            return;
+        }
+        if v > DDSKETCH_MIN_VALUE {
+            self.positive.remove(Self::index(v));
+        } else if v < -DDSKETCH_MIN_VALUE {
+            self.negative.remove(Self::index(-v));
+        } else if self.zero_count > 0 {
+            self.zero_count -= 1;
+        } else {
+            return;
+        }
+        self.count -= 1;
+    }
+
+    /// Estimate the `q`-quantile (`0.0..=1.0`)
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = q * (self.count - 1) as f64;
+        let mut seen = 0u64;
+        for (&index, &count) in self.negative.bins.iter().rev() {
+            seen += count;
+            if seen as f64 > rank {
+                return Some(-Self::bin_value(index));
+            }
+        }
+        seen += self.zero_count;
+        if seen as f64 > rank {
+            return Some(0.0);
+        }
+        for (&index, &count) in &self.positive.bins {
+            seen += count;
+            if seen as f64 > rank {
+                return Some(Self::bin_value(index));
+            }
+        }
+        self.positive
+            .bins
+            .last_key_value()
+            .map(|(&index, _)| Self::bin_value(index))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// `percentile(x, q)` / `median(x)` state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PercentileSketch {
+    quantile: f64,
+    sketch: DDSketch,
+}
+
+impl PercentileSketch {
+    pub fn new(quantile: f64) -> Self {
+        Self {
+            quantile,
+            sketch: DDSketch::default(),
+        }
+    }
+}
+
+impl WindowState for PercentileSketch {
+    fn entries(&self) -> usize {
+        self.sketch.positive.bins.len() + self.sketch.negative.bins.len()
+    }
+}
+
+impl Sketch for PercentileSketch {
+    fn configure(&mut self, params: &[AttributeValue]) -> Result<(), String> {
+        if let Some(p) = params.first() {
+            let q = value_as_f64(p)
+                .filter(|q| (0.0..=1.0).contains(q))
+                .ok_or_else(|| "percentile() requires a constant between 0 and 1".to_string())?;
+            self.quantile = q;
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, value: &AttributeValue) {
+        if let Some(v) = value_as_f64(value) {
+            self.sketch.add(v);
+        }
+    }
+
+    fn remove(&mut self, value: &AttributeValue) {
+        if let Some(v) = value_as_f64(value) {
+            self.sketch.remove(v);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.sketch = DDSketch::default();
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        self.sketch
+            .quantile(self.quantile)
+            .map(AttributeValue::Double)
+    }
+}
+
+// ============================================================================
+// HyperLogLog
+// ============================================================================
+
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// Stable 64-bit hash of a value (FNV-1a followed by a SplitMix64 finalizer),
+/// so sketches restored from snapshots keep hashing identically
+fn hash_value(value: &AttributeValue) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in format!("{value:?}").bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// HyperLogLog that also counts how many values set each register rank, so
+/// values can be removed again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    /// `(register << 8) | rank` -> number of live values with that rank
+    rank_counts: HashMap<u32, u32>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+            rank_counts: HashMap::new(),
+        }
+    }
+}
+
+impl HyperLogLog {
+    fn position(hash: u64) -> (usize, u8) {
+        let register = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rest = hash << HLL_PRECISION;
+        let rank = (rest.leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+        (register, rank)
+    }
+
+    fn key(register: usize, rank: u8) -> u32 {
+        ((register as u32) << 8) | rank as u32
+    }
+
+    pub fn add_hash(&mut self, hash: u64) {
+        let (register, rank) = Self::position(hash);
+        *self
+            .rank_counts
+            .entry(Self::key(register, rank))
+            .or_insert(0) += 1;
+        if rank > self.registers[register] {
+            self.registers[register] = rank;
+        }
+    }
+
+    pub fn remove_hash(&mut self, hash: u64) {
+        let (register, rank) = Self::position(hash);
+        let key = Self::key(register, rank);
+        let Some(count) = self.rank_counts.get_mut(&key) else {
+            return;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        self.rank_counts.remove(&key);
+        if self.registers[register] == rank {
+            self.registers[register] = (1..rank)
+                .rev()
+                .find(|r| self.rank_counts.contains_key(&Self::key(register, *r)))
+                .unwrap_or(0);
+        }
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0usize), |(sum, zeros), &r| {
+                (sum + 2f64.powi(-(r as i32)), zeros + (r == 0) as usize)
+            });
+        let raw = alpha * m * m / sum;
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+impl WindowState for HyperLogLog {
+    fn entries(&self) -> usize {
+        self.rank_counts.len()
+    }
+}
+
+impl Sketch for HyperLogLog {
+    fn configure(&mut self, _params: &[AttributeValue]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn add(&mut self, value: &AttributeValue) {
+        if !matches!(value, AttributeValue::Null) {
+            self.add_hash(hash_value(value));
+        }
+    }
+
+    fn remove(&mut self, value: &AttributeValue) {
+        if !matches!(value, AttributeValue::Null) {
+            self.remove_hash(hash_value(value));
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        Some(AttributeValue::Long(self.estimate() as i64))
+    }
+}
+
+// ============================================================================
+// Space-saving top-k
+// ============================================================================
+
+const TOP_K_DEFAULT: usize = 10;
+/// Counters monitored per requested item
+const TOP_K_CAPACITY_FACTOR: usize = 10;
+const TOP_K_MIN_CAPACITY: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Counter {
+    value: AttributeValue,
+    count: u64,
+    /// Upper bound on how much `count` overestimates the true frequency
+    error: u64,
+}
+
+/// Space-saving heavy hitters sketch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopKSketch {
+    k: usize,
+    counters: HashMap<String, Counter>,
+}
+
+impl Default for TopKSketch {
+    fn default() -> Self {
+        Self {
+            k: TOP_K_DEFAULT,
+            counters: HashMap::new(),
+        }
+    }
+}
+
+impl TopKSketch {
+    fn capacity(&self) -> usize {
+        (self.k * TOP_K_CAPACITY_FACTOR).max(TOP_K_MIN_CAPACITY)
+    }
+
+    /// The `k` most frequent values with their estimated counts
+    pub fn top(&self) -> Vec<(AttributeValue, u64)> {
+        let mut entries: Vec<(&String, &Counter)> = self.counters.iter().collect();
+        entries.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+        entries
+            .into_iter()
+            .take(self.k)
+            .map(|(_, c)| (c.value.clone(), c.count))
+            .collect()
+    }
+}
+
+impl WindowState for TopKSketch {
+    fn entries(&self) -> usize {
+        self.counters.len()
+    }
+}
+
+impl Sketch for TopKSketch {
+    fn configure(&mut self, params: &[AttributeValue]) -> Result<(), String> {
+        if let Some(k) = params.first() {
+            self.k = match k {
+                AttributeValue::Int(k) if *k > 0 => *k as usize,
+                AttributeValue::Long(k) if *k > 0 => *k as usize,
+                _ => return Err("top_k() requires a positive integer constant".to_string()),
+            };
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, value: &AttributeValue) {
+        if matches!(value, AttributeValue::Null) {
+            return;
+        }
+        let key = format!("{value:?}");
+        if let Some(counter) = self.counters.get_mut(&key) {
+            counter.count += 1;
+            return;
+        }
+        if self.counters.len() < self.capacity() {
+            self.counters.insert(
+                key,
+                Counter {
+                    value: value.clone(),
+                    count: 1,
+                    error: 0,
+                },
+            );
+            return;
+        }
+        // Replace the least frequent counter, inheriting its count as error
+        let (min_key, min_count) = self
+            .counters
+            .iter()
+            .min_by_key(|(_, c)| c.count)
+            .map(|(k, c)| (k.clone(), c.count))
+            .unwrap();
+        self.counters.remove(&min_key);
+        self.counters.insert(
+            key,
+            Counter {
+                value: value.clone(),
+                count: min_count + 1,
+                error: min_count,
+            },
+        );
+    }
+
+    fn remove(&mut self, value: &AttributeValue) {
+        let key = format!("{value:?}");
+        if let Some(counter) = self.counters.get_mut(&key) {
+            counter.count -= 1;
+            counter.error = counter.error.min(counter.count);
+            if counter.count == 0 {
+                self.counters.remove(&key);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.counters.clear();
+    }
+
+    fn result(&self) -> Option<AttributeValue> {
+        let items: Vec<AttributeValue> = self
+            .top()
+            .into_iter()
+            .map(|(value, count)| {
+                AttributeValue::Object(Some(Box::new(vec![
+                    value,
+                    AttributeValue::Long(count as i64),
+                ])))
+            })
+            .collect();
+        Some(AttributeValue::Object(Some(Box::new(items))))
+    }
+}
+
+// ============================================================================
+// Executor
+// ============================================================================
+
+/// Aggregator over one argument followed by `param_count` constant parameters,
+/// accumulating into a [`Sketch`]
+#[derive(Debug)]
+pub struct SketchAggregatorExecutor<S: Sketch> {
+    name: &'static str,
+    return_type: ApiAttributeType,
+    param_count: usize,
+    arg_exec: Option<Box<dyn ExpressionExecutor>>,
+    state: Arc<Mutex<S>>,
+    app_ctx: Option<Arc<EventFluxAppContext>>,
+}
+
+impl<S: Sketch> SketchAggregatorExecutor<S> {
+    pub fn new(
+        name: &'static str,
+        return_type: ApiAttributeType,
+        param_count: usize,
+        sketch: S,
+    ) -> Self {
+        Self {
+            name,
+            return_type,
+            param_count,
+            arg_exec: None,
+            state: Arc::new(Mutex::new(sketch)),
+            app_ctx: None,
+        }
+    }
+}
+
+impl<S: Sketch> AttributeAggregatorExecutor for SketchAggregatorExecutor<S> {
+    fn init(
+        &mut self,
+        mut e: Vec<Box<dyn ExpressionExecutor>>,
+        _m: ProcessingMode,
+        _ex: bool,
+        ctx: &EventFluxQueryContext,
+    ) -> Result<(), String> {
+        if e.len() != 1 + self.param_count {
+            return Err(format!(
+                "{}() requires one argument and {} constant parameter(s)",
+                self.name, self.param_count
+            ));
+        }
+        let params = e
+            .drain(1..)
+            .map(|p| {
+                p.execute(None)
+                    .ok_or_else(|| format!("{}() parameters must be constants", self.name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.state.lock().unwrap().configure(&params)?;
+        self.arg_exec = Some(e.remove(0));
+        self.app_ctx = Some(Arc::clone(&ctx.eventflux_app_context));
+
+        let component_id = format!(
+            "{}_aggregator_{}",
+            self.name.to_lowercase(),
+            ctx.next_aggregator_id()
+        );
+        SnapshotWindowStateHolder::new(Arc::clone(&self.state), component_id, self.name)
+            .register(ctx);
+        Ok(())
+    }
+
+    fn process_add(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        let mut st = self.state.lock().unwrap();
+        if let Some(v) = data {
+            st.add(&v);
+        }
+        st.result()
+    }
+
+    fn process_remove(&self, data: Option<AttributeValue>) -> Option<AttributeValue> {
+        let mut st = self.state.lock().unwrap();
+        if let Some(v) = data {
+            st.remove(&v);
+        }
+        st.result()
+    }
+
+    fn reset(&self) -> Option<AttributeValue> {
+        self.state.lock().unwrap().clear();
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        let ctx = self.app_ctx.as_ref().unwrap();
+        Box::new(Self {
+            name: self.name,
+            return_type: self.return_type,
+            param_count: self.param_count,
+            arg_exec: self.arg_exec.as_ref().map(|e| e.clone_executor(ctx)),
+            state: Arc::new(Mutex::new(self.state.lock().unwrap().clone())),
+            app_ctx: Some(Arc::clone(ctx)),
+        })
+    }
+}
+
+impl<S: Sketch> ExpressionExecutor for SketchAggregatorExecutor<S> {
+    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
+        let event = event?;
+        let data = self.arg_exec.as_ref().and_then(|e| e.execute(Some(event)));
+        match event.get_event_type() {
+            ComplexEventType::Current => self.process_add(data),
+            ComplexEventType::Expired => self.process_remove(data),
+            ComplexEventType::Reset => self.reset(),
+            _ => None,
+        }
+    }
+
+    fn get_return_type(&self) -> ApiAttributeType {
+        self.return_type
+    }
+
+    fn clone_executor(&self, _ctx: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
+        Box::new(AttributeAggregatorExpressionExecutor::new(self.clone_box()))
+    }
+
+    fn is_attribute_aggregator(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Factories
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct PercentileAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for PercentileAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "percentile"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(SketchAggregatorExecutor::new(
+            "percentile",
+            ApiAttributeType::DOUBLE,
+            1,
+            PercentileSketch::new(0.5),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn description(&self) -> &str {
+        "Approximate q-quantile (0 <= q <= 1) within 1% relative error"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MedianAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for MedianAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "median"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(SketchAggregatorExecutor::new(
+            "median",
+            ApiAttributeType::DOUBLE,
+            0,
+            PercentileSketch::new(0.5),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn description(&self) -> &str {
+        "Approximate median within 1% relative error"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApproxDistinctAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for ApproxDistinctAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "approx_distinct"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(SketchAggregatorExecutor::new(
+            "approx_distinct",
+            ApiAttributeType::LONG,
+            0,
+            HyperLogLog::default(),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn return_type(&self, _arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        Ok(ApiAttributeType::LONG)
+    }
+    fn description(&self) -> &str {
+        "Approximate number of distinct non-null values (HyperLogLog)"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TopKAttributeAggregatorFactory;
+
+impl AttributeAggregatorFactory for TopKAttributeAggregatorFactory {
+    fn name(&self) -> &'static str {
+        "top_k"
+    }
+    fn create(&self) -> Box<dyn AttributeAggregatorExecutor> {
+        Box::new(SketchAggregatorExecutor::new(
+            "top_k",
+            ApiAttributeType::OBJECT,
+            1,
+            TopKSketch::default(),
+        ))
+    }
+    fn clone_box(&self) -> Box<dyn AttributeAggregatorFactory> {
+        Box::new(Self)
+    }
+    fn arity(&self) -> usize {
+        2
+    }
+    fn return_type(&self, _arg_types: &[ApiAttributeType]) -> Result<ApiAttributeType, String> {
+        Ok(ApiAttributeType::OBJECT)
+    }
+    fn description(&self) -> &str {
+        "Most frequent values as a list of [value, count] pairs (space-saving)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        let tolerance = expected.abs() * DDSKETCH_RELATIVE_ACCURACY * 1.01;
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} not within 1% of {expected}"
+        );
+    }
+
+    #[test]
+    fn test_ddsketch_quantiles() {
+        let mut sketch = DDSketch::default();
+        for v in 1..=1000 {
+            sketch.add(v as f64);
+        }
+        assert_close(sketch.quantile(0.5).unwrap(), 500.0);
+        assert_close(sketch.quantile(0.99).unwrap(), 990.0);
+        assert_close(sketch.quantile(0.0).unwrap(), 1.0);
+        assert_close(sketch.quantile(1.0).unwrap(), 1000.0);
+
+        // Sliding: drop the lower half
+        for v in 1..=500 {
+            sketch.remove(v as f64);
+        }
+        assert_eq!(sketch.count(), 500);
+        assert_close(sketch.quantile(0.5).unwrap(), 750.0);
+    }
+
+    #[test]
+    fn test_ddsketch_signed_values() {
+        let mut sketch = DDSketch::default();
+        for v in [-10.0, -5.0, 0.0, 5.0, 10.0] {
+            sketch.add(v);
+        }
+        assert_close(sketch.quantile(0.0).unwrap(), -10.0);
+        assert_eq!(sketch.quantile(0.5), Some(0.0));
+        assert_close(sketch.quantile(1.0).unwrap(), 10.0);
+        assert_eq!(DDSketch::default().quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_hyperloglog_estimate_and_remove() {
+        let mut hll = HyperLogLog::default();
+        for i in 0..10_000 {
+            hll.add(&AttributeValue::Int(i));
+            // Duplicates do not change the estimate
+            hll.add(&AttributeValue::Int(i));
+        }
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 10_000.0).abs() < 10_000.0 * 0.05, "{estimate}");
+
+        for i in 0..10_000 {
+            hll.remove(&AttributeValue::Int(i));
+        }
+        assert!((hll.estimate() as f64 - 10_000.0).abs() < 10_000.0 * 0.05);
+        for i in 0..10_000 {
+            hll.remove(&AttributeValue::Int(i));
+        }
+        assert_eq!(hll.estimate(), 0);
+        assert!(hll.rank_counts.is_empty());
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopKSketch::default();
+        top.configure(&[AttributeValue::Int(2)]).unwrap();
+        for (value, times) in [("a", 5), ("b", 3), ("c", 1)] {
+            for _ in 0..times {
+                top.add(&AttributeValue::String(value.to_string()));
+            }
+        }
+        let result = match top.result() {
+            Some(AttributeValue::Object(Some(items))) => items,
+            other => panic!("expected an object result, got {other:?}"),
+        };
+        let pairs: Vec<&Vec<AttributeValue>> = result
+            .downcast_ref::<Vec<AttributeValue>>()
+            .unwrap()
+            .iter()
+            .map(|item| match item {
+                AttributeValue::Object(Some(pair)) => pair.downcast_ref().unwrap(),
+                other => panic!("expected a [value, count] pair, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                &vec![
+                    AttributeValue::String("a".to_string()),
+                    AttributeValue::Long(5)
+                ],
+                &vec![
+                    AttributeValue::String("b".to_string()),
+                    AttributeValue::Long(3)
+                ],
+            ]
+        );
+        for _ in 0..4 {
+            top.remove(&AttributeValue::String("a".to_string()));
+        }
+        assert_eq!(top.top()[0], (AttributeValue::String("b".to_string()), 3));
+        assert!(top.configure(&[AttributeValue::Int(0)]).is_err());
+    }
+
+    #[test]
+    fn test_percentile_rejects_out_of_range() {
+        let mut sketch = PercentileSketch::new(0.5);
+        assert!(sketch.configure(&[AttributeValue::Double(1.5)]).is_err());
+        sketch.configure(&[AttributeValue::Double(0.9)]).unwrap();
+        assert_eq!(sketch.quantile, 0.9);
+    }
+}
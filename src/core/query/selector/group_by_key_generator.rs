@@ -1,5 +1,8 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use std::sync::Arc;
+
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::event::complex_event::ComplexEvent;
 use crate::core::executor::expression_executor::ExpressionExecutor;
 use crate::core::util::eventflux_constants::KEY_DELIMITER;
@@ -23,6 +26,17 @@ impl GroupByKeyGenerator {
         Self { executors }
     }
 
+    /// Clone including the key executors, bound to `app_ctx`
+    pub fn clone_with_context(&self, app_ctx: &Arc<EventFluxAppContext>) -> Self {
+        Self {
+            executors: self
+                .executors
+                .iter()
+                .map(|e| e.clone_executor(app_ctx))
+                .collect(),
+        }
+    }
+
     pub fn construct_event_key(&self, event: &dyn ComplexEvent) -> Option<String> {
         if self.executors.is_empty() {
             return None;
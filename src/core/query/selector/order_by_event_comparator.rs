@@ -1,15 +1,22 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::event::complex_event::ComplexEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
 use std::cmp::Ordering;
+use std::sync::Arc;
 
 /// Comparator for events according to ORDER BY clauses.
+///
+/// Without an explicit null ordering, nulls sort as the largest value: last
+/// when ascending and first when descending. `NULLS FIRST`/`NULLS LAST` fix
+/// their position regardless of direction.
 #[derive(Debug)]
 pub struct OrderByEventComparator {
     executors: Vec<Box<dyn ExpressionExecutor>>,
     ascending: Vec<bool>,
+    nulls_first: Vec<Option<bool>>,
 }
 
 impl Clone for OrderByEventComparator {
@@ -17,6 +24,7 @@ impl Clone for OrderByEventComparator {
         Self {
             executors: Vec::new(),
             ascending: self.ascending.clone(),
+            nulls_first: self.nulls_first.clone(),
         }
     }
 }
@@ -26,28 +34,69 @@ impl OrderByEventComparator {
         Self {
             executors,
             ascending,
+            nulls_first: Vec::new(),
+        }
+    }
+
+    /// Set the null ordering per key; `None` keeps the default for that key
+    pub fn with_nulls_first(mut self, nulls_first: Vec<Option<bool>>) -> Self {
+        self.nulls_first = nulls_first;
+        self
+    }
+
+    /// Clone including the key executors, bound to `app_ctx`
+    pub fn clone_with_context(&self, app_ctx: &Arc<EventFluxAppContext>) -> Self {
+        Self {
+            executors: self
+                .executors
+                .iter()
+                .map(|e| e.clone_executor(app_ctx))
+                .collect(),
+            ascending: self.ascending.clone(),
+            nulls_first: self.nulls_first.clone(),
         }
     }
 
     pub fn compare(&self, ev1: &dyn ComplexEvent, ev2: &dyn ComplexEvent) -> Ordering {
-        for (exec, asc) in self.executors.iter().zip(self.ascending.iter()) {
-            let v1 = exec.execute(Some(ev1));
-            let v2 = exec.execute(Some(ev2));
+        for (idx, (exec, asc)) in self.executors.iter().zip(self.ascending.iter()).enumerate() {
+            let v1 = exec
+                .execute(Some(ev1))
+                .filter(|v| !matches!(v, AttributeValue::Null));
+            let v2 = exec
+                .execute(Some(ev2))
+                .filter(|v| !matches!(v, AttributeValue::Null));
             let ord = match (v1, v2) {
-                (Some(a), Some(b)) => compare_attr_values(&a, &b),
-                (Some(_), None) => Ordering::Less,
-                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => {
+                    let ord = compare_attr_values(&a, &b);
+                    if *asc {
+                        ord
+                    } else {
+                        ord.reverse()
+                    }
+                }
                 (None, None) => Ordering::Equal,
+                (v1, _) => {
+                    let nulls_first = self
+                        .nulls_first
+                        .get(idx)
+                        .copied()
+                        .flatten()
+                        .unwrap_or(!*asc);
+                    match (v1.is_none(), nulls_first) {
+                        (true, true) | (false, false) => Ordering::Less,
+                        _ => Ordering::Greater,
+                    }
+                }
             };
             if ord != Ordering::Equal {
-                return if *asc { ord } else { ord.reverse() };
+                return ord;
             }
         }
         Ordering::Equal
     }
 }
 
-fn compare_attr_values(a: &AttributeValue, b: &AttributeValue) -> Ordering {
+pub(crate) fn compare_attr_values(a: &AttributeValue, b: &AttributeValue) -> Ordering {
     match (a, b) {
         (AttributeValue::String(x), AttributeValue::String(y)) => x.cmp(y),
         (AttributeValue::Int(x), AttributeValue::Int(y)) => x.cmp(y),
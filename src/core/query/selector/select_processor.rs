@@ -17,7 +17,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::core::persistence::StateHolder;
 
-use super::{GroupByKeyGenerator, OrderByEventComparator};
+use super::{AnalyticStage, GroupByKeyGenerator, OrderByEventComparator};
 use crate::core::executor::expression_executor::ExpressionExecutor;
 
 #[derive(Debug)]
@@ -338,6 +338,8 @@ pub struct SelectProcessor {
     offset: Option<u64>,
     /// Per-group aggregator state when both group-by and aggregators are used.
     group_states: Mutex<std::collections::HashMap<String, GroupState>>,
+    /// Analytic (`OVER`) functions evaluated over each chunk before projection
+    analytic_stage: Option<AnalyticStage>,
 }
 
 impl SelectProcessor {
@@ -383,9 +385,16 @@ impl SelectProcessor {
                 .as_ref()
                 .and_then(|c| c.value.to_u64_for_limit_offset()),
             group_states: Mutex::new(HashMap::new()),
+            analytic_stage: None,
         }
     }
 
+    /// Evaluate the select list's analytic functions with `stage`
+    pub fn with_analytic_stage(mut self, stage: AnalyticStage) -> Self {
+        self.analytic_stage = Some(stage);
+        self
+    }
+
     /// Clear group states - called during state restoration to ensure clean state
     pub fn clear_group_states(&self) {
         if let Ok(mut group_states) = self.group_states.lock() {
@@ -421,7 +430,11 @@ impl SelectProcessor {
 
 impl Processor for SelectProcessor {
     fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
-        let mut input_event_opt = complex_event_chunk;
+        let mut input_event_opt = match &self.analytic_stage {
+            Some(stage) => stage.prepare_chunk(complex_event_chunk),
+            None => complex_event_chunk,
+        };
+        let mut position = 0usize;
         let mut collected: Vec<Box<dyn ComplexEvent>> = Vec::new();
         let mut group_map: HashMap<String, Box<dyn ComplexEvent>> = HashMap::new();
         let mut state_lock = if self.contains_aggregator && self.is_group_by {
@@ -433,6 +446,10 @@ impl Processor for SelectProcessor {
         while let Some(mut event_box) = input_event_opt {
             let next = event_box.set_next(None);
             let etype = event_box.get_event_type();
+            if let Some(ref stage) = self.analytic_stage {
+                stage.seek(position);
+            }
+            position += 1;
 
             let allowed = match etype {
                 ComplexEventType::Current => self.current_on,
@@ -563,15 +580,29 @@ impl Processor for SelectProcessor {
         &self,
         eventflux_query_context: &Arc<EventFluxQueryContext>,
     ) -> Box<dyn Processor> {
-        let cloned_oaps = self
-            .output_attribute_processors
-            .iter()
-            .map(|oap| oap.clone_oap(&self.meta.eventflux_app_context))
-            .collect();
-        let cloned_having = self
-            .having_condition_executor
-            .as_ref()
-            .map(|exec| exec.clone_executor(&self.meta.eventflux_app_context));
+        let app_ctx = &self.meta.eventflux_app_context;
+        let clone_select = || {
+            let oaps: Vec<OutputAttributeProcessor> = self
+                .output_attribute_processors
+                .iter()
+                .map(|oap| oap.clone_oap(app_ctx))
+                .collect();
+            let having = self
+                .having_condition_executor
+                .as_ref()
+                .map(|exec| exec.clone_executor(app_ctx));
+            (oaps, having)
+        };
+        // The clone gets analytic functions of its own, so partition
+        // instances don't number or total each other's rows
+        let (analytic_stage, (cloned_oaps, cloned_having)) = match &self.analytic_stage {
+            Some(stage) => {
+                let (stage, select) =
+                    stage.clone_stage(app_ctx, eventflux_query_context, clone_select);
+                (Some(stage), select)
+            }
+            None => (None, clone_select()),
+        };
 
         Box::new(SelectProcessor {
             meta: CommonProcessorMeta::new(
@@ -592,6 +623,7 @@ impl Processor for SelectProcessor {
             limit: self.limit,
             offset: self.offset,
             group_states: Mutex::new(HashMap::new()),
+            analytic_stage,
         })
     }
 
@@ -607,6 +639,7 @@ impl Processor for SelectProcessor {
         if self.contains_aggregator
             || self.is_group_by
             || self.is_order_by
+            || self.analytic_stage.is_some()
             || self.limit.is_some()
             || self.offset.is_some()
         {
@@ -617,7 +650,7 @@ impl Processor for SelectProcessor {
     }
 
     fn is_stateful(&self) -> bool {
-        self.contains_aggregator || self.is_group_by // Simplified
+        self.contains_aggregator || self.is_group_by || self.analytic_stage.is_some()
     }
 
     fn clear_group_states(&self) {
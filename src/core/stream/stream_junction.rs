@@ -371,6 +371,13 @@ impl StreamJunction {
         self.started.store(false, Ordering::Release);
     }
 
+    /// Check whether all buffered events have been handed to subscribers
+    ///
+    /// Synchronous junctions never buffer, so they are always drained.
+    pub fn is_drained(&self) -> bool {
+        !self.is_async || self.event_pipeline.is_empty()
+    }
+
     /// Send a single event through the pipeline
     pub fn send_event(&self, event: Event) -> Result<(), EventFluxError> {
         if self.shutdown.load(Ordering::Acquire) {
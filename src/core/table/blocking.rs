@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Runs async database clients behind the synchronous [`Table`](super::Table)
+//! API.
+
+use crate::core::exception::EventFluxError;
+use once_cell::sync::Lazy;
+use std::future::Future;
+
+/// Runtime driving the connections of all external tables. Table operations
+/// may be called from inside another tokio runtime, so futures run here
+/// while the caller waits on a channel.
+static RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("eventflux-table-io")
+        .enable_all()
+        .build()
+        .expect("Failed to start table I/O runtime")
+});
+
+/// Run `future` on the table runtime and wait for its result
+pub(super) fn block_on<T, F>(future: F) -> Result<T, EventFluxError>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, EventFluxError>> + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    RUNTIME.spawn(async move {
+        let _ = tx.send(future.await);
+    });
+    rx.recv().map_err(|_| EventFluxError::DatabaseRuntime {
+        message: "Table operation was cancelled".to_string(),
+        source: None,
+    })?
+}
@@ -5,41 +5,102 @@ use crate::core::event::stream::stream_event::StreamEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
 use crate::core::extension::TableFactory;
+use crate::core::table::retention::{now_millis, RowClock};
 use crate::core::table::Table;
 use crate::core::table::{
     constant_to_av, CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition,
-    InMemoryCompiledUpdateSet,
+    InMemoryCompiledUpdateSet, RetentionPolicy, TtlMode,
 };
 use crate::query_api::execution::query::output::stream::UpdateSet;
 use crate::query_api::expression::Expression;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::sync::{Arc, RwLock};
 
+/// Rows of a [`CacheTable`] in insertion order. Ids are only used to track
+/// row times for retention.
+#[derive(Debug, Clone, Default)]
+struct CacheState {
+    rows: VecDeque<(u64, Vec<AttributeValue>)>,
+    next_id: u64,
+    clock: RowClock<u64>,
+}
+
+impl CacheState {
+    fn push(&mut self, row: Vec<AttributeValue>, touched: Option<i64>) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.rows.push_back((id, row));
+        if let Some(now) = touched {
+            self.clock.touch(id, now);
+        }
+    }
+
+    fn pop_front(&mut self) -> Option<Vec<AttributeValue>> {
+        let (id, row) = self.rows.pop_front()?;
+        self.clock.forget(&id);
+        Some(row)
+    }
+}
+
 #[derive(Debug)]
 pub struct CacheTable {
-    rows: RwLock<VecDeque<Vec<AttributeValue>>>,
+    state: RwLock<CacheState>,
     max_size: usize,
+    retention: Option<RetentionPolicy>,
 }
 
 impl CacheTable {
     pub fn new(max_size: usize) -> Self {
         Self {
-            rows: RwLock::new(VecDeque::new()),
+            state: RwLock::new(CacheState::default()),
             max_size,
+            retention: None,
         }
     }
 
-    fn trim_if_needed(&self, rows: &mut VecDeque<Vec<AttributeValue>>) {
-        while rows.len() > self.max_size {
-            rows.pop_front();
+    /// Also evict rows according to `policy` (see [`Table::evict_expired`])
+    pub fn with_retention(mut self, policy: Option<RetentionPolicy>) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    fn trim_if_needed(&self, state: &mut CacheState) {
+        while state.rows.len() > self.max_size {
+            state.pop_front();
+        }
+    }
+
+    /// Time to record for a row write, if the table tracks row times
+    fn write_time(&self) -> Option<i64> {
+        self.retention.as_ref().map(|_| now_millis())
+    }
+
+    /// Record reads of rows `ids` under `after_access` retention
+    fn touch_accessed(&self, ids: &[u64]) {
+        if ids.is_empty() || !matches!(&self.retention, Some(p) if p.mode == TtlMode::AfterAccess) {
+            return;
+        }
+        let now = now_millis();
+        let mut state = self.state.write().unwrap();
+        let live: HashSet<u64> = state.rows.iter().map(|(id, _)| *id).collect();
+        for id in ids {
+            if live.contains(id) {
+                state.clock.touch(*id, now);
+            }
         }
     }
 
     /// Helper for tests to access all rows
     #[allow(dead_code)]
     pub fn all_rows(&self) -> Vec<Vec<AttributeValue>> {
-        self.rows.read().unwrap().iter().cloned().collect()
+        self.state
+            .read()
+            .unwrap()
+            .rows
+            .iter()
+            .map(|(_, row)| row.clone())
+            .collect()
     }
 
     /// Update rows using already compiled condition and update set.
@@ -48,11 +109,16 @@ impl CacheTable {
         condition: &InMemoryCompiledCondition,
         update_set: &InMemoryCompiledUpdateSet,
     ) -> bool {
-        let mut rows = self.rows.write().unwrap();
+        let now = self.write_time();
+        let mut state = self.state.write().unwrap();
+        let CacheState { rows, clock, .. } = &mut *state;
         let mut updated = false;
-        for row in rows.iter_mut() {
+        for (id, row) in rows.iter_mut() {
             if row.as_slice() == condition.values.as_slice() {
                 *row = update_set.values.clone();
+                if let Some(now) = now {
+                    clock.touch(*id, now);
+                }
                 updated = true;
             }
         }
@@ -61,9 +127,16 @@ impl CacheTable {
 
     /// Delete rows matching the compiled condition.
     pub fn delete_compiled(&self, condition: &InMemoryCompiledCondition) -> bool {
-        let mut rows = self.rows.write().unwrap();
+        let mut state = self.state.write().unwrap();
+        let CacheState { rows, clock, .. } = &mut *state;
         let orig_len = rows.len();
-        rows.retain(|row| row.as_slice() != condition.values.as_slice());
+        rows.retain(|(id, row)| {
+            let matched = row.as_slice() == condition.values.as_slice();
+            if matched {
+                clock.forget(id);
+            }
+            !matched
+        });
         orig_len != rows.len()
     }
 
@@ -72,21 +145,32 @@ impl CacheTable {
         &self,
         condition: &InMemoryCompiledCondition,
     ) -> Option<Vec<AttributeValue>> {
-        self.rows
+        let (id, row) = self
+            .state
             .read()
             .unwrap()
+            .rows
             .iter()
-            .find(|row| row.as_slice() == condition.values.as_slice())
-            .cloned()
+            .find(|(_, row)| row.as_slice() == condition.values.as_slice())
+            .cloned()?;
+        self.touch_accessed(&[id]);
+        Some(row)
     }
 
     /// Check if any row matches the compiled condition.
     pub fn contains_compiled(&self, condition: &InMemoryCompiledCondition) -> bool {
-        self.rows
+        let id = self
+            .state
             .read()
             .unwrap()
+            .rows
             .iter()
-            .any(|row| row.as_slice() == condition.values.as_slice())
+            .find(|(_, row)| row.as_slice() == condition.values.as_slice())
+            .map(|(id, _)| *id);
+        if let Some(id) = id {
+            self.touch_accessed(&[id]);
+        }
+        id.is_some()
     }
 }
 
@@ -95,14 +179,15 @@ impl Table for CacheTable {
         &self,
         values: &[AttributeValue],
     ) -> Result<(), crate::core::exception::EventFluxError> {
-        let mut rows = self.rows.write().unwrap();
-        rows.push_back(values.to_vec());
-        self.trim_if_needed(&mut rows);
+        let now = self.write_time();
+        let mut state = self.state.write().unwrap();
+        state.push(values.to_vec(), now);
+        self.trim_if_needed(&mut state);
         Ok(())
     }
 
     fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
-        Ok(self.rows.read().unwrap().iter().cloned().collect())
+        Ok(CacheTable::all_rows(self))
     }
 
     fn update(
@@ -168,10 +253,11 @@ impl Table for CacheTable {
         _compiled_condition: Option<&dyn CompiledCondition>,
         condition_executor: Option<&dyn ExpressionExecutor>,
     ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
-        let rows = self.rows.read().unwrap();
+        let state = self.state.read().unwrap();
+        let mut matched_ids = Vec::new();
         let mut matched = Vec::new();
         let stream_attr_count = stream_event.before_window_data.len();
-        for row in rows.iter() {
+        for (id, row) in state.rows.iter() {
             if let Some(exec) = condition_executor {
                 let mut joined =
                     StreamEvent::new(stream_event.timestamp, stream_attr_count + row.len(), 0, 0);
@@ -182,12 +268,16 @@ impl Table for CacheTable {
                     joined.before_window_data[stream_attr_count + j] = row[j].clone();
                 }
                 if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+                    matched_ids.push(*id);
                     matched.push(row.clone());
                 }
             } else {
+                matched_ids.push(*id);
                 matched.push(row.clone());
             }
         }
+        drop(state);
+        self.touch_accessed(&matched_ids);
         Ok(matched)
     }
 
@@ -212,12 +302,69 @@ impl Table for CacheTable {
     }
 
     fn clone_table(&self) -> Result<Box<dyn Table>, crate::core::exception::EventFluxError> {
-        let rows = self.rows.read().unwrap().clone();
+        let state = self.state.read().unwrap().clone();
         Ok(Box::new(CacheTable {
-            rows: RwLock::new(rows),
+            state: RwLock::new(state),
             max_size: self.max_size,
+            retention: self.retention.clone(),
         }))
     }
+
+    fn retention(&self) -> Option<&RetentionPolicy> {
+        self.retention.as_ref()
+    }
+
+    fn evict_expired(
+        &self,
+        now: i64,
+    ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
+        let policy = match &self.retention {
+            Some(policy) => policy,
+            None => return Ok(Vec::new()),
+        };
+        let mut state = self.state.write().unwrap();
+        let due: HashSet<u64> = state.clock.due(policy, now).into_iter().collect();
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+        let CacheState { rows, clock, .. } = &mut *state;
+        let mut evicted = Vec::new();
+        rows.retain(|(id, row)| {
+            if due.contains(id) {
+                clock.forget(id);
+                evicted.push(row.clone());
+                false
+            } else {
+                true
+            }
+        });
+        Ok(evicted)
+    }
+
+    fn timed_rows(
+        &self,
+    ) -> Result<Vec<(Vec<AttributeValue>, i64)>, crate::core::exception::EventFluxError> {
+        let state = self.state.read().unwrap();
+        let now = now_millis();
+        Ok(state
+            .rows
+            .iter()
+            .map(|(id, row)| (row.clone(), state.clock.touched_at(id).unwrap_or(now)))
+            .collect())
+    }
+
+    fn restore_timed_rows(
+        &self,
+        rows: Vec<(Vec<AttributeValue>, i64)>,
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let mut state = CacheState::default();
+        for (row, touched) in rows {
+            state.push(row, self.retention.as_ref().map(|_| touched));
+        }
+        self.trim_if_needed(&mut state);
+        *self.state.write().unwrap() = state;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -240,7 +387,8 @@ impl TableFactory for CacheTableFactory {
         let size = size_str
             .parse::<usize>()
             .map_err(|_| "max_size must be a number".to_string())?;
-        Ok(Arc::new(CacheTable::new(size)))
+        let retention = RetentionPolicy::from_properties(&properties)?;
+        Ok(Arc::new(CacheTable::new(size).with_retention(retention)))
     }
 
     fn clone_box(&self) -> Box<dyn TableFactory> {
@@ -0,0 +1,993 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Read cache and write buffer in front of any [`Table`], configured with
+//! `WITH ('cache.policy' = 'lru', 'cache.size' = ..., 'cache.ttl' = ...,
+//! 'cache.negative' = ..., 'write.mode' = 'write_behind', ...)`.
+//!
+//! Lookups (`find`, `contains`, join matches) are cached by the values they
+//! depend on, so joining a stream against an external table only reaches the
+//! backing store once per distinct key. Every write to the backing table
+//! invalidates the cache.
+//!
+//! In write-behind mode inserts are buffered and written in batches, when the
+//! buffer is full, periodically and at shutdown. Reads never force a flush:
+//! they combine the backing table (or its cached lookups) with the buffered
+//! rows, a buffered row hiding the backing row with the same primary key.
+//! Updates and deletes flush the buffer first, as the backing table applies
+//! them.
+
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::table::index::IndexKey;
+use crate::core::table::retention::now_millis;
+use crate::core::table::{
+    CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition, InMemoryTable,
+    RetentionPolicy, Table,
+};
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::definition::stream_definition::StreamDefinition;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::execution::query::output::stream::UpdateSet;
+use crate::query_api::expression::Expression;
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Which cached lookup is dropped when the cache is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// The least recently used lookup
+    #[default]
+    Lru,
+    /// The oldest lookup, regardless of use
+    Fifo,
+}
+
+/// When inserts reach the backing table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Immediately, before `insert` returns
+    #[default]
+    WriteThrough,
+    /// In batches, when the buffer is full, periodically, before updates
+    /// and deletes and at shutdown
+    WriteBehind,
+}
+
+const DEFAULT_CACHE_SIZE: usize = 10_000;
+const DEFAULT_BATCH_SIZE: usize = 100;
+const DEFAULT_FLUSH_INTERVAL_MS: i64 = 1_000;
+
+/// Cache settings of a table; see the module documentation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCacheConfig {
+    pub policy: CachePolicy,
+    /// Maximum number of cached lookups
+    pub max_entries: usize,
+    /// How long a cached lookup stays valid
+    pub ttl_ms: Option<i64>,
+    /// Also cache lookups that found nothing
+    pub negative: bool,
+    pub write_mode: WriteMode,
+    /// Buffered inserts that trigger a write-behind flush
+    pub batch_size: usize,
+    /// Time between periodic write-behind flushes
+    pub flush_interval_ms: i64,
+}
+
+impl Default for TableCacheConfig {
+    fn default() -> Self {
+        Self {
+            policy: CachePolicy::default(),
+            max_entries: DEFAULT_CACHE_SIZE,
+            ttl_ms: None,
+            negative: false,
+            write_mode: WriteMode::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            flush_interval_ms: DEFAULT_FLUSH_INTERVAL_MS,
+        }
+    }
+}
+
+impl TableCacheConfig {
+    /// Read the cache properties of a table; `None` if neither
+    /// `cache.policy` nor `write.mode` is set
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        if !properties.contains_key("cache.policy") && !properties.contains_key("write.mode") {
+            return Ok(None);
+        }
+        let mut config = Self::default();
+        if let Some(policy) = properties.get("cache.policy") {
+            config.policy = match policy.to_ascii_lowercase().as_str() {
+                "lru" => CachePolicy::Lru,
+                "fifo" => CachePolicy::Fifo,
+                other => {
+                    return Err(format!(
+                        "Invalid 'cache.policy' '{other}': expected 'lru' or 'fifo'"
+                    ))
+                }
+            };
+        }
+        if let Some(size) = properties.get("cache.size") {
+            config.max_entries = Self::positive("cache.size", size)?;
+        }
+        if let Some(ttl) = properties.get("cache.ttl") {
+            config.ttl_ms = Some(Self::duration_ms("cache.ttl", ttl)?);
+        }
+        if let Some(negative) = properties.get("cache.negative") {
+            config.negative = negative.parse().map_err(|_| {
+                format!("Invalid 'cache.negative' '{negative}': expected 'true' or 'false'")
+            })?;
+        }
+        if let Some(mode) = properties.get("write.mode") {
+            config.write_mode = match mode.to_ascii_lowercase().as_str() {
+                "write_through" => WriteMode::WriteThrough,
+                "write_behind" => WriteMode::WriteBehind,
+                other => {
+                    return Err(format!(
+                        "Invalid 'write.mode' '{other}': expected 'write_through' or 'write_behind'"
+                    ))
+                }
+            };
+        }
+        if let Some(size) = properties.get("write.batch.size") {
+            config.batch_size = Self::positive("write.batch.size", size)?;
+        }
+        if let Some(interval) = properties.get("write.flush.interval") {
+            config.flush_interval_ms = Self::duration_ms("write.flush.interval", interval)?;
+        }
+        Ok(Some(config))
+    }
+
+    fn positive(key: &str, value: &str) -> Result<usize, String> {
+        match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!(
+                "Invalid '{key}' '{value}': expected a positive integer"
+            )),
+        }
+    }
+
+    fn duration_ms(key: &str, value: &str) -> Result<i64, String> {
+        let ms = crate::core::config::types::parse_duration(value)
+            .map(|d| d.as_millis() as i64)
+            .map_err(|e| format!("Invalid '{key}' '{value}': {e}"))?;
+        if ms > 0 {
+            Ok(ms)
+        } else {
+            Err(format!("'{key}' must be positive"))
+        }
+    }
+}
+
+/// Hit/miss counters of a [`CachingTable`], see [`Table::cache_stats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableCacheStats {
+    /// Lookups answered from the cache, including negative hits
+    pub hits: u64,
+    /// Lookups that reached the backing table
+    pub misses: u64,
+    /// Hits on cached empty results
+    pub negative_hits: u64,
+    /// Lookups dropped to make room or because their TTL lapsed
+    pub evictions: u64,
+    /// Lookups currently cached
+    pub entries: usize,
+    /// Inserts buffered for a write-behind flush
+    pub pending_writes: usize,
+    /// Buffered inserts written to the backing table so far
+    pub flushed_writes: u64,
+}
+
+impl TableCacheStats {
+    /// Fraction of lookups answered from the cache
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Condition compiled by the wrapped table, tagged so its results can be
+/// cached, and by the write buffer. Conditions compiled from expressions hold
+/// constants only, so the id alone identifies their result.
+#[derive(Debug)]
+pub struct CachedCompiledCondition {
+    id: u64,
+    inner: Box<dyn CompiledCondition>,
+    buffered: Box<dyn CompiledCondition>,
+}
+
+impl CompiledCondition for CachedCompiledCondition {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Join condition compiled by the wrapped table, with the stream attributes
+/// its result depends on (`None` if they couldn't be determined, in which
+/// case every stream attribute is part of the cache key)
+#[derive(Debug)]
+pub struct CachedJoinCondition {
+    id: u64,
+    inner: Option<Box<dyn CompiledCondition>>,
+    buffered: Option<Box<dyn CompiledCondition>>,
+    stream_positions: Option<Vec<usize>>,
+}
+
+impl CompiledCondition for CachedJoinCondition {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// What a cached lookup depends on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum LookupKey {
+    /// Value list condition built per event
+    Values(Vec<IndexKey>),
+    /// Condition compiled from an expression
+    Condition(u64),
+    /// Join condition and the stream values it reads
+    Join(u64, Vec<IndexKey>),
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    rows: Arc<Vec<Vec<AttributeValue>>>,
+    stored_at: i64,
+    tick: u64,
+}
+
+/// Cached lookups ordered by insertion (FIFO) or last use (LRU)
+#[derive(Debug, Default)]
+struct LookupCache {
+    entries: HashMap<LookupKey, CacheEntry>,
+    order: BTreeMap<u64, LookupKey>,
+    next_tick: u64,
+    /// Bumped by every invalidation, so results loaded before a write are
+    /// not cached after it
+    generation: u64,
+}
+
+impl LookupCache {
+    fn tick(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+
+    fn remove(&mut self, key: &LookupKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.tick);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.generation += 1;
+    }
+}
+
+/// Caching decorator over another table; see the module documentation.
+#[derive(Debug)]
+pub struct CachingTable {
+    inner: Arc<dyn Table>,
+    definition: TableDefinition,
+    /// Column names, to tell stream attributes from table columns in join
+    /// conditions
+    columns: Vec<String>,
+    /// Positions of the primary key columns
+    primary_key: Vec<usize>,
+    config: TableCacheConfig,
+    cache: Mutex<LookupCache>,
+    /// Inserts not yet written to the backing table, in insertion order.
+    /// Reads hold the lock shared, so a flush never runs halfway through one.
+    pending: RwLock<Vec<Vec<AttributeValue>>>,
+    /// The pending rows, indexed like the table, answering reads
+    buffer: InMemoryTable,
+    next_condition_id: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    negative_hits: AtomicU64,
+    evictions: AtomicU64,
+    flushed_writes: AtomicU64,
+}
+
+impl CachingTable {
+    pub fn new(
+        inner: Arc<dyn Table>,
+        definition: &TableDefinition,
+        config: TableCacheConfig,
+    ) -> Self {
+        let columns: Vec<String> = definition
+            .abstract_definition
+            .attribute_list
+            .iter()
+            .map(|a| a.get_name().clone())
+            .collect();
+        Self {
+            inner,
+            definition: definition.clone(),
+            primary_key: definition
+                .primary_key
+                .iter()
+                .filter_map(|k| columns.iter().position(|c| c == k))
+                .collect(),
+            columns,
+            config,
+            cache: Mutex::new(LookupCache::default()),
+            pending: RwLock::new(Vec::new()),
+            buffer: Self::new_buffer(definition),
+            next_condition_id: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            negative_hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            flushed_writes: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &TableCacheConfig {
+        &self.config
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_condition_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn new_buffer(definition: &TableDefinition) -> InMemoryTable {
+        InMemoryTable::with_definition(definition).unwrap_or_else(|_| InMemoryTable::new())
+    }
+
+    fn invalidate(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Write all buffered inserts to the backing table. On failure they stay
+    /// buffered and are retried by the next flush.
+    fn flush_pending(&self) -> Result<(), EventFluxError> {
+        self.flush_locked(&mut self.pending.write().unwrap())
+    }
+
+    fn flush_locked(&self, pending: &mut Vec<Vec<AttributeValue>>) -> Result<(), EventFluxError> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+        self.inner.insert_batch(pending)?;
+        self.flushed_writes
+            .fetch_add(pending.len() as u64, Ordering::Relaxed);
+        pending.clear();
+        self.buffer.replace_rows(&[])?;
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Primary key of `row`; `None` without a primary key
+    fn row_key(&self, row: &[AttributeValue]) -> Option<Vec<IndexKey>> {
+        if self.primary_key.is_empty() {
+            return None;
+        }
+        value_keys(self.primary_key.iter().filter_map(|&c| row.get(c)))
+    }
+
+    /// Whether a buffered row with the primary key of `row` will overwrite it
+    fn is_overwritten(
+        &self,
+        row: &[AttributeValue],
+        buffered_keys: &HashSet<Vec<IndexKey>>,
+    ) -> bool {
+        !buffered_keys.is_empty()
+            && self
+                .row_key(row)
+                .is_some_and(|k| buffered_keys.contains(&k))
+    }
+
+    /// Backing rows followed by the matching buffered rows
+    fn merge_buffered(
+        &self,
+        backing: &[Vec<AttributeValue>],
+        buffered: Vec<Vec<AttributeValue>>,
+    ) -> Vec<Vec<AttributeValue>> {
+        let keys: HashSet<_> = buffered.iter().filter_map(|r| self.row_key(r)).collect();
+        backing
+            .iter()
+            .filter(|row| !self.is_overwritten(row, &keys))
+            .cloned()
+            .chain(buffered)
+            .collect()
+    }
+
+    /// Answer a lookup of the backing table from the cache or, on a miss,
+    /// from `load`. Lookups without a key bypass the cache.
+    fn lookup(
+        &self,
+        key: Option<LookupKey>,
+        load: impl FnOnce(&dyn Table) -> Result<Vec<Vec<AttributeValue>>, EventFluxError>,
+    ) -> Result<Arc<Vec<Vec<AttributeValue>>>, EventFluxError> {
+        let Some(key) = key else {
+            return load(&*self.inner).map(Arc::new);
+        };
+        let now = now_millis();
+        let generation = {
+            let mut guard = self.cache.lock().unwrap();
+            let cache = &mut *guard;
+            let expired = match cache.entries.get(&key) {
+                Some(entry) => self
+                    .config
+                    .ttl_ms
+                    .is_some_and(|ttl| now - entry.stored_at >= ttl),
+                None => false,
+            };
+            if expired {
+                cache.remove(&key);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            } else if cache.entries.contains_key(&key) {
+                let tick = cache.tick();
+                let entry = cache.entries.get_mut(&key).unwrap();
+                let rows = Arc::clone(&entry.rows);
+                if self.config.policy == CachePolicy::Lru {
+                    let old_tick = std::mem::replace(&mut entry.tick, tick);
+                    cache.order.remove(&old_tick);
+                    cache.order.insert(tick, key);
+                }
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                if rows.is_empty() {
+                    self.negative_hits.fetch_add(1, Ordering::Relaxed);
+                }
+                return Ok(rows);
+            }
+            cache.generation
+        };
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let rows = Arc::new(load(&*self.inner)?);
+        if !rows.is_empty() || self.config.negative {
+            let mut cache = self.cache.lock().unwrap();
+            if cache.generation == generation {
+                cache.remove(&key);
+                while cache.entries.len() >= self.config.max_entries {
+                    let Some((_, oldest)) = cache.order.pop_first() else {
+                        break;
+                    };
+                    cache.entries.remove(&oldest);
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+                let tick = cache.tick();
+                cache.order.insert(tick, key.clone());
+                cache.entries.insert(
+                    key,
+                    CacheEntry {
+                        rows: Arc::clone(&rows),
+                        stored_at: now,
+                        tick,
+                    },
+                );
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Cache key and wrapped condition of a `find` / `contains` condition
+    fn unwrap_condition<'a>(
+        condition: &'a dyn CompiledCondition,
+    ) -> (Option<LookupKey>, &'a dyn CompiledCondition) {
+        let (key, inner, _) = Self::split_condition(condition);
+        (key, inner)
+    }
+
+    /// Like [`Self::unwrap_condition`], also returning the condition for the
+    /// write buffer, if the buffer can evaluate it
+    fn split_condition<'a>(
+        condition: &'a dyn CompiledCondition,
+    ) -> (
+        Option<LookupKey>,
+        &'a dyn CompiledCondition,
+        Option<&'a dyn CompiledCondition>,
+    ) {
+        if let Some(cond) = condition.as_any().downcast_ref::<CachedCompiledCondition>() {
+            (
+                Some(LookupKey::Condition(cond.id)),
+                &*cond.inner,
+                Some(&*cond.buffered),
+            )
+        } else if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+        {
+            (
+                value_keys(cond.values.iter()).map(LookupKey::Values),
+                condition,
+                Some(condition),
+            )
+        } else {
+            (None, condition, None)
+        }
+    }
+
+    /// Stream attributes read by join condition `expr`; `None` if some
+    /// variable can't be attributed to the stream or the table
+    fn stream_positions(
+        &self,
+        expr: &Expression,
+        stream_id: &str,
+        stream_def: &StreamDefinition,
+    ) -> Option<Vec<usize>> {
+        let mut positions = BTreeSet::new();
+        self.collect_stream_positions(expr, stream_id, stream_def, &mut positions)
+            .then(|| positions.into_iter().collect())
+    }
+
+    fn collect_stream_positions(
+        &self,
+        expr: &Expression,
+        stream_id: &str,
+        stream_def: &StreamDefinition,
+        positions: &mut BTreeSet<usize>,
+    ) -> bool {
+        let mut walk =
+            |e: &Expression| self.collect_stream_positions(e, stream_id, stream_def, positions);
+        match expr {
+            Expression::Constant(_) => true,
+            Expression::Variable(v) => {
+                let stream_position = stream_def
+                    .abstract_definition
+                    .attribute_list
+                    .iter()
+                    .position(|a| a.get_name() == &v.attribute_name);
+                let is_column = self.columns.contains(&v.attribute_name);
+                let position = match v.stream_id.as_deref() {
+                    Some(id) if id == stream_id => stream_position,
+                    Some(_) if is_column => return true,
+                    Some(_) => None,
+                    None if is_column && stream_position.is_none() => return true,
+                    None if is_column => None,
+                    None => stream_position,
+                };
+                match position {
+                    Some(p) => {
+                        positions.insert(p);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Expression::IndexedVariable(_) => false,
+            Expression::AttributeFunction(f) => {
+                f.filter.is_none() && f.over.is_none() && f.parameters.iter().all(walk)
+            }
+            Expression::Add(e) => walk(&e.left_value) && walk(&e.right_value),
+            Expression::Subtract(e) => walk(&e.left_value) && walk(&e.right_value),
+            Expression::Multiply(e) => walk(&e.left_value) && walk(&e.right_value),
+            Expression::Divide(e) => walk(&e.left_value) && walk(&e.right_value),
+            Expression::Mod(e) => walk(&e.left_value) && walk(&e.right_value),
+            Expression::And(e) => walk(&e.left_expression) && walk(&e.right_expression),
+            Expression::Or(e) => walk(&e.left_expression) && walk(&e.right_expression),
+            Expression::Compare(e) => walk(&e.left_expression) && walk(&e.right_expression),
+            Expression::Not(e) => walk(&e.expression),
+            Expression::In(e) => walk(&e.expression),
+            Expression::Cast(e) => walk(&e.expression),
+            Expression::IsNull(e) => e.expression.as_deref().is_some_and(walk),
+            Expression::Case(e) => {
+                e.operand.as_deref().is_none_or(&mut walk)
+                    && e.when_clauses
+                        .iter()
+                        .all(|w| walk(&w.condition) && walk(&w.result))
+                    && walk(&e.else_result)
+            }
+        }
+    }
+}
+
+/// Hashable form of lookup values; `None` if one can't be hashed (objects)
+fn value_keys<'a>(values: impl Iterator<Item = &'a AttributeValue>) -> Option<Vec<IndexKey>> {
+    values
+        .map(|v| IndexKey::for_column(v, ApiAttributeType::OBJECT))
+        .collect()
+}
+
+impl Table for CachingTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        match self.config.write_mode {
+            WriteMode::WriteThrough => {
+                self.inner.insert(values)?;
+                self.invalidate();
+            }
+            WriteMode::WriteBehind => {
+                // Cached lookups stay valid: reads add the buffered rows
+                let mut pending = self.pending.write().unwrap();
+                self.buffer.insert(values)?;
+                pending.push(values.to_vec());
+                if pending.len() >= self.config.batch_size {
+                    self.flush_locked(&mut pending)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        self.flush_pending()?;
+        let (_, condition) = Self::unwrap_condition(condition);
+        let updated = self.inner.update(condition, update_set)?;
+        self.invalidate();
+        Ok(updated)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        self.flush_pending()?;
+        let (_, condition) = Self::unwrap_condition(condition);
+        let deleted = self.inner.delete(condition)?;
+        self.invalidate();
+        Ok(deleted)
+    }
+
+    fn update_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<Vec<(Vec<AttributeValue>, Vec<AttributeValue>)>, EventFluxError> {
+        self.flush_pending()?;
+        let (_, condition) = Self::unwrap_condition(condition);
+        let changes = self.inner.update_rows(condition, update_set)?;
+        self.invalidate();
+        Ok(changes)
+    }
+
+    fn delete_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.flush_pending()?;
+        let (_, condition) = Self::unwrap_condition(condition);
+        let deleted = self.inner.delete_rows(condition)?;
+        self.invalidate();
+        Ok(deleted)
+    }
+
+    fn replace_rows(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        // The new rows replace the table, buffered inserts included
+        let mut pending = self.pending.write().unwrap();
+        pending.clear();
+        self.buffer.replace_rows(&[])?;
+        self.inner.replace_rows(rows)?;
+        self.invalidate();
+        Ok(())
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        let (key, condition, buffered) = Self::split_condition(condition);
+        let _pending = self.pending.read().unwrap();
+        // Buffered rows are newer than the backing table's
+        if let Some(buffered) = buffered {
+            if let Some(row) = self.buffer.find(buffered)? {
+                return Ok(Some(row));
+            }
+        }
+        let rows = self.lookup(key, |inner| {
+            Ok(inner.find(condition)?.into_iter().collect())
+        })?;
+        let keys: HashSet<_> = self
+            .buffer
+            .all_rows()
+            .iter()
+            .filter_map(|r| self.row_key(r))
+            .collect();
+        Ok(rows
+            .iter()
+            .find(|row| !self.is_overwritten(row, &keys))
+            .cloned())
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        // Shares cache entries with `find`, which answers both
+        Ok(self.find(condition)?.is_some())
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let _pending = self.pending.read().unwrap();
+        let backing = self.inner.all_rows()?;
+        Ok(self.merge_buffered(&backing, self.buffer.all_rows()))
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let cached =
+            compiled_condition.and_then(|c| c.as_any().downcast_ref::<CachedJoinCondition>());
+        let _pending = self.pending.read().unwrap();
+        let buffered = self.buffer.find_rows_for_join(
+            stream_event,
+            cached.and_then(|c| c.buffered.as_deref()),
+            condition_executor,
+        )?;
+        let Some(cached) = cached else {
+            let backing = self.inner.find_rows_for_join(
+                stream_event,
+                compiled_condition,
+                condition_executor,
+            )?;
+            return Ok(self.merge_buffered(&backing, buffered));
+        };
+        let data = &stream_event.before_window_data;
+        let values = match &cached.stream_positions {
+            Some(positions) => value_keys(positions.iter().filter_map(|&p| data.get(p))),
+            None => value_keys(data.iter()),
+        };
+        let key = values.map(|v| LookupKey::Join(cached.id, v));
+        let rows = self.lookup(key, |inner| {
+            inner.find_rows_for_join(stream_event, cached.inner.as_deref(), condition_executor)
+        })?;
+        Ok(self.merge_buffered(&rows, buffered))
+    }
+
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        let stream_positions = self.stream_positions(&cond, stream_id, stream_def);
+        Some(Box::new(CachedJoinCondition {
+            id: self.next_id(),
+            inner: self
+                .inner
+                .compile_join_condition(cond.clone(), stream_id, stream_def),
+            buffered: self
+                .buffer
+                .compile_join_condition(cond, stream_id, stream_def),
+            stream_positions,
+        }))
+    }
+
+    fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
+        Box::new(CachedCompiledCondition {
+            id: self.next_id(),
+            inner: self.inner.compile_condition(cond.clone()),
+            buffered: self.buffer.compile_condition(cond),
+        })
+    }
+
+    fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        self.inner.compile_update_set(us)
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        // The clone keeps its own copy of the buffered inserts
+        let pending = self.pending.read().unwrap();
+        let buffer = Self::new_buffer(&self.definition);
+        buffer.replace_rows(&pending)?;
+        Ok(Box::new(CachingTable {
+            inner: Arc::from(self.inner.clone_table()?),
+            definition: self.definition.clone(),
+            columns: self.columns.clone(),
+            primary_key: self.primary_key.clone(),
+            config: self.config.clone(),
+            cache: Mutex::new(LookupCache::default()),
+            pending: RwLock::new(pending.clone()),
+            buffer,
+            next_condition_id: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            negative_hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            flushed_writes: AtomicU64::new(0),
+        }))
+    }
+
+    fn retention(&self) -> Option<&RetentionPolicy> {
+        self.inner.retention()
+    }
+
+    fn evict_expired(&self, now: i64) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let evicted = self.inner.evict_expired(now)?;
+        if !evicted.is_empty() {
+            self.invalidate();
+        }
+        Ok(evicted)
+    }
+
+    fn timed_rows(&self) -> Result<Vec<(Vec<AttributeValue>, i64)>, EventFluxError> {
+        let _pending = self.pending.read().unwrap();
+        let buffered = self.buffer.timed_rows()?;
+        let keys: HashSet<_> = buffered
+            .iter()
+            .filter_map(|(r, _)| self.row_key(r))
+            .collect();
+        let mut rows: Vec<_> = self
+            .inner
+            .timed_rows()?
+            .into_iter()
+            .filter(|(row, _)| !self.is_overwritten(row, &keys))
+            .collect();
+        rows.extend(buffered);
+        Ok(rows)
+    }
+
+    fn restore_timed_rows(
+        &self,
+        rows: Vec<(Vec<AttributeValue>, i64)>,
+    ) -> Result<(), EventFluxError> {
+        // The restored rows replace the table, buffered inserts included
+        let mut pending = self.pending.write().unwrap();
+        pending.clear();
+        self.buffer.replace_rows(&[])?;
+        self.inner.restore_timed_rows(rows)?;
+        self.invalidate();
+        Ok(())
+    }
+
+    fn validate_connectivity(&self) -> Result<(), EventFluxError> {
+        self.inner.validate_connectivity()
+    }
+
+    fn flush(&self) -> Result<(), EventFluxError> {
+        self.flush_pending()
+    }
+
+    fn cache_stats(&self) -> Option<TableCacheStats> {
+        Some(TableCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: self.cache.lock().unwrap().entries.len(),
+            pending_writes: self.pending.read().unwrap().len(),
+            flushed_writes: self.flushed_writes.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Periodically writes the buffered inserts of a write-behind table, and
+/// once more at shutdown.
+#[derive(Debug)]
+pub struct TableFlushRuntime {
+    table_id: String,
+    table: Arc<dyn Table>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl TableFlushRuntime {
+    pub fn new(
+        table_id: String,
+        table: Arc<dyn Table>,
+        interval_ms: i64,
+        scheduler: Arc<Scheduler>,
+    ) -> Self {
+        Self {
+            table_id,
+            table,
+            interval_ms,
+            scheduler,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let task = FlushTask {
+            table_id: self.table_id.clone(),
+            table: Arc::clone(&self.table),
+            interval_ms: self.interval_ms,
+            scheduler: Arc::clone(&self.scheduler),
+            running: Arc::clone(&self.running),
+        };
+        self.scheduler
+            .notify_at(now_millis() + self.interval_ms, Arc::new(task));
+    }
+
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Err(e) = self.table.flush() {
+            log::error!(
+                "Failed to flush buffered writes of table '{}' at shutdown: {}",
+                self.table_id,
+                e
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FlushTask {
+    table_id: String,
+    table: Arc<dyn Table>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl Schedulable for FlushTask {
+    fn on_time(&self, timestamp: i64) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = self.table.flush() {
+            log::error!(
+                "Failed to flush buffered writes of table '{}': {}",
+                self.table_id,
+                e
+            );
+        }
+        self.scheduler
+            .notify_at(timestamp + self.interval_ms, Arc::new(self.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_config_from_properties() {
+        assert_eq!(
+            TableCacheConfig::from_properties(&props(&[])).unwrap(),
+            None
+        );
+        let config = TableCacheConfig::from_properties(&props(&[
+            ("cache.policy", "LRU"),
+            ("cache.size", "50"),
+            ("cache.ttl", "30 sec"),
+            ("cache.negative", "true"),
+            ("write.mode", "write_behind"),
+            ("write.batch.size", "10"),
+            ("write.flush.interval", "200 ms"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            config,
+            TableCacheConfig {
+                policy: CachePolicy::Lru,
+                max_entries: 50,
+                ttl_ms: Some(30_000),
+                negative: true,
+                write_mode: WriteMode::WriteBehind,
+                batch_size: 10,
+                flush_interval_ms: 200,
+            }
+        );
+
+        let write_only =
+            TableCacheConfig::from_properties(&props(&[("write.mode", "write_behind")]))
+                .unwrap()
+                .unwrap();
+        assert_eq!(write_only.policy, CachePolicy::Lru);
+
+        assert!(TableCacheConfig::from_properties(&props(&[("cache.policy", "lfu")])).is_err());
+        assert!(TableCacheConfig::from_properties(&props(&[
+            ("cache.policy", "lru"),
+            ("cache.size", "0")
+        ]))
+        .is_err());
+        assert!(TableCacheConfig::from_properties(&props(&[("write.mode", "later")])).is_err());
+    }
+}
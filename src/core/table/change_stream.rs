@@ -0,0 +1,422 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Change streams of tables, defined with
+//! `CREATE STREAM PriceChanges AS CHANGES OF PriceTable`.
+//!
+//! Every row written to the table is sent to the stream as an event holding
+//! the [`ChangeOperation`] in an `operation` column followed by the row. An
+//! update sends the row as it was (`UPDATE_BEFORE`) and as it is now
+//! (`UPDATE_AFTER`), so downstream queries and sinks can materialize or audit
+//! the table. Inserts replacing a row with the same primary key are updates;
+//! rows evicted by the table's retention policy are deletes.
+
+use crate::core::event::event::Event;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::persistence::state_holder::StateOperation;
+use crate::core::stream::stream_junction::StreamJunction;
+use crate::core::table::retention::now_millis;
+use crate::core::table::{
+    CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition, InMemoryTable,
+    RetentionPolicy, Table, TableCacheStats,
+};
+use crate::query_api::definition::stream_definition::StreamDefinition;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::execution::query::output::stream::UpdateSet;
+use crate::query_api::expression::Expression;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Name of the column holding the [`ChangeOperation`] of a change event
+pub const OPERATION_COLUMN: &str = "operation";
+
+/// Kind of change carried by a change stream event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOperation {
+    Insert,
+    /// The row before an update; always followed by its `UpdateAfter`
+    UpdateBefore,
+    UpdateAfter,
+    Delete,
+}
+
+impl ChangeOperation {
+    /// Value of the `operation` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeOperation::Insert => "INSERT",
+            ChangeOperation::UpdateBefore => "UPDATE_BEFORE",
+            ChangeOperation::UpdateAfter => "UPDATE_AFTER",
+            ChangeOperation::Delete => "DELETE",
+        }
+    }
+}
+
+/// Decorator sending the changes of a table to its change streams; see the
+/// module documentation.
+#[derive(Debug)]
+pub struct ChangeStreamTable {
+    inner: Arc<dyn Table>,
+    /// Primary key columns, to tell upserts from inserts
+    primary_key: Vec<usize>,
+    junctions: Vec<Arc<Mutex<StreamJunction>>>,
+    /// Serializes writes so the rows read to describe a change are the ones
+    /// it replaced
+    write_lock: Mutex<()>,
+}
+
+impl ChangeStreamTable {
+    pub fn new(
+        inner: Arc<dyn Table>,
+        definition: &TableDefinition,
+        junctions: Vec<Arc<Mutex<StreamJunction>>>,
+    ) -> Self {
+        Self {
+            inner,
+            primary_key: definition
+                .primary_key
+                .iter()
+                .filter_map(|column| definition.column_position(column))
+                .collect(),
+            junctions,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn emit(&self, changes: Vec<(ChangeOperation, Vec<AttributeValue>)>) {
+        if changes.is_empty() {
+            return;
+        }
+        let timestamp = now_millis();
+        let events: Vec<Event> = changes
+            .into_iter()
+            .map(|(operation, row)| {
+                let mut data = Vec::with_capacity(row.len() + 1);
+                data.push(AttributeValue::String(operation.as_str().to_string()));
+                data.extend(row);
+                Event::new_with_data(timestamp, data)
+            })
+            .collect();
+        for junction in &self.junctions {
+            if let Err(e) = junction.lock().unwrap().send_events(events.clone()) {
+                log::error!("Failed to send table changes: {}", e);
+            }
+        }
+    }
+
+    fn updates(
+        changes: Vec<(Vec<AttributeValue>, Vec<AttributeValue>)>,
+    ) -> Vec<(ChangeOperation, Vec<AttributeValue>)> {
+        changes
+            .into_iter()
+            .flat_map(|(before, after)| {
+                [
+                    (ChangeOperation::UpdateBefore, before),
+                    (ChangeOperation::UpdateAfter, after),
+                ]
+            })
+            .collect()
+    }
+
+    fn deletes(rows: Vec<Vec<AttributeValue>>) -> Vec<(ChangeOperation, Vec<AttributeValue>)> {
+        rows.into_iter()
+            .map(|row| (ChangeOperation::Delete, row))
+            .collect()
+    }
+
+    /// Changes made by replacing the table contents: rows sharing a primary
+    /// key with a removed row are updates, the others inserts and deletes
+    fn replacement_changes(
+        &self,
+        removed: Vec<Vec<AttributeValue>>,
+        added: Vec<Vec<AttributeValue>>,
+    ) -> Vec<(ChangeOperation, Vec<AttributeValue>)> {
+        let key = |row: &[AttributeValue]| {
+            InMemoryTable::row_to_key(
+                &self
+                    .primary_key
+                    .iter()
+                    .map(|&c| row.get(c).cloned().unwrap_or_default())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let mut replaced: HashMap<String, Vec<AttributeValue>> = HashMap::new();
+        let mut changes = Vec::new();
+        for row in removed {
+            if self.primary_key.is_empty() {
+                changes.push((ChangeOperation::Delete, row));
+            } else {
+                replaced.insert(key(&row), row);
+            }
+        }
+        for row in added {
+            match replaced.remove(&key(&row)) {
+                Some(before) if !self.primary_key.is_empty() => {
+                    changes.push((ChangeOperation::UpdateBefore, before));
+                    changes.push((ChangeOperation::UpdateAfter, row));
+                }
+                _ => changes.push((ChangeOperation::Insert, row)),
+            }
+        }
+        changes.extend(Self::deletes(replaced.into_values().collect()));
+        changes
+    }
+}
+
+impl Table for ChangeStreamTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        let changes = {
+            let _guard = self.write_lock.lock().unwrap();
+            let replaced = if self.primary_key.is_empty() {
+                None
+            } else {
+                let key = InMemoryCompiledCondition {
+                    values: self
+                        .primary_key
+                        .iter()
+                        .map(|&c| values.get(c).cloned().unwrap_or_default())
+                        .collect(),
+                };
+                self.inner.find(&key)?
+            };
+            self.inner.insert(values)?;
+            match replaced {
+                Some(before) => Self::updates(vec![(before, values.to_vec())]),
+                None => vec![(ChangeOperation::Insert, values.to_vec())],
+            }
+        };
+        self.emit(changes);
+        Ok(())
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        Ok(!self.update_rows(condition, update_set)?.is_empty())
+    }
+
+    fn update_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<Vec<(Vec<AttributeValue>, Vec<AttributeValue>)>, EventFluxError> {
+        let changes = {
+            let _guard = self.write_lock.lock().unwrap();
+            self.inner.update_rows(condition, update_set)?
+        };
+        self.emit(Self::updates(changes.clone()));
+        Ok(changes)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Ok(!self.delete_rows(condition)?.is_empty())
+    }
+
+    fn delete_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let deleted = {
+            let _guard = self.write_lock.lock().unwrap();
+            self.inner.delete_rows(condition)?
+        };
+        self.emit(Self::deletes(deleted.clone()));
+        Ok(deleted)
+    }
+
+    fn insert_batch(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        rows.iter().try_for_each(|row| self.insert(row))
+    }
+
+    fn replace_rows(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        let changes = {
+            let _guard = self.write_lock.lock().unwrap();
+            let before = self.inner.all_rows()?;
+            self.inner.replace_rows(rows)?;
+            let (removed, added) = super::row_differences(&before, &self.inner.all_rows()?);
+            self.replacement_changes(removed, added)
+        };
+        self.emit(changes);
+        Ok(())
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        self.inner.find(condition)
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        self.inner.contains(condition)
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.inner.all_rows()
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.inner
+            .find_rows_for_join(stream_event, compiled_condition, condition_executor)
+    }
+
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        self.inner
+            .compile_join_condition(cond, stream_id, stream_def)
+    }
+
+    fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
+        self.inner.compile_condition(cond)
+    }
+
+    fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        self.inner.compile_update_set(us)
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        Ok(Box::new(ChangeStreamTable {
+            inner: Arc::from(self.inner.clone_table()?),
+            primary_key: self.primary_key.clone(),
+            junctions: self.junctions.clone(),
+            write_lock: Mutex::new(()),
+        }))
+    }
+
+    fn retention(&self) -> Option<&RetentionPolicy> {
+        self.inner.retention()
+    }
+
+    fn evict_expired(&self, now: i64) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let evicted = {
+            let _guard = self.write_lock.lock().unwrap();
+            self.inner.evict_expired(now)?
+        };
+        self.emit(Self::deletes(evicted.clone()));
+        Ok(evicted)
+    }
+
+    fn timed_rows(&self) -> Result<Vec<(Vec<AttributeValue>, i64)>, EventFluxError> {
+        self.inner.timed_rows()
+    }
+
+    fn restore_timed_rows(
+        &self,
+        rows: Vec<(Vec<AttributeValue>, i64)>,
+    ) -> Result<(), EventFluxError> {
+        // Restoring a snapshot recovers earlier state, it doesn't change it
+        self.inner.restore_timed_rows(rows)
+    }
+
+    fn record_changes(&self) -> bool {
+        self.inner.record_changes()
+    }
+
+    fn pending_changes(&self) -> Result<Vec<StateOperation>, EventFluxError> {
+        self.inner.pending_changes()
+    }
+
+    fn commit_changes(&self, count: usize) {
+        self.inner.commit_changes(count)
+    }
+
+    fn apply_changes(&self, changes: &[StateOperation]) -> Result<(), EventFluxError> {
+        // Like a restore, replaying checkpointed changes emits nothing
+        self.inner.apply_changes(changes)
+    }
+
+    fn validate_connectivity(&self) -> Result<(), EventFluxError> {
+        self.inner.validate_connectivity()
+    }
+
+    fn flush(&self) -> Result<(), EventFluxError> {
+        self.inner.flush()
+    }
+
+    fn cache_stats(&self) -> Option<TableCacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::table::InMemoryCompiledUpdateSet;
+    use crate::query_api::definition::attribute::Type as AttributeType;
+
+    #[test]
+    fn test_default_change_reporting_compares_rows() {
+        // Tables without their own `update_rows` / `delete_rows` report
+        // changes by comparing their rows
+        #[derive(Debug)]
+        struct Plain(InMemoryTable);
+        impl Table for Plain {
+            fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+                self.0.insert(values)
+            }
+            fn update(
+                &self,
+                condition: &dyn CompiledCondition,
+                update_set: &dyn CompiledUpdateSet,
+            ) -> Result<bool, EventFluxError> {
+                self.0.update(condition, update_set)
+            }
+            fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+                self.0.delete(condition)
+            }
+            fn find(
+                &self,
+                condition: &dyn CompiledCondition,
+            ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+                self.0.find(condition)
+            }
+            fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+                self.0.contains(condition)
+            }
+            fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+                Table::all_rows(&self.0)
+            }
+            fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+                self.0.clone_table()
+            }
+        }
+
+        let mut def = TableDefinition::id("T".to_string())
+            .attribute("id".to_string(), AttributeType::INT)
+            .attribute("v".to_string(), AttributeType::STRING);
+        def.primary_key = vec!["id".to_string()];
+        let table = Plain(InMemoryTable::with_definition(&def).unwrap());
+        let row =
+            |id: i32, v: &str| vec![AttributeValue::Int(id), AttributeValue::String(v.into())];
+        table.insert(&row(1, "a")).unwrap();
+        table.insert(&row(2, "b")).unwrap();
+
+        let one = InMemoryCompiledCondition {
+            values: vec![AttributeValue::Int(1)],
+        };
+        let changes = table
+            .update_rows(
+                &one,
+                &InMemoryCompiledUpdateSet {
+                    values: row(1, "z"),
+                },
+            )
+            .unwrap();
+        assert_eq!(changes, vec![(row(1, "a"), row(1, "z"))]);
+        assert_eq!(table.delete_rows(&one).unwrap(), vec![row(1, "z")]);
+        assert!(table.delete_rows(&one).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,540 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::core::event::value::AttributeValue;
+use crate::core::table::{constant_to_av, CompiledCondition, CompiledUpdateSet};
+use crate::query_api::definition::attribute::Type as ApiAttributeType;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::expression::condition::compare::Operator;
+use crate::query_api::expression::{Expression, Variable};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Bound;
+
+/// Column layout, primary key and secondary indexes of a table, with every
+/// column resolved to its position in the row.
+#[derive(Debug, Clone)]
+pub(crate) struct TableSchema {
+    pub name: String,
+    pub columns: Vec<(String, ApiAttributeType)>,
+    pub primary_key: Vec<usize>,
+    pub indexes: Vec<Vec<usize>>,
+}
+
+impl TableSchema {
+    pub fn from_definition(definition: &TableDefinition) -> Result<Self, String> {
+        let name = definition.abstract_definition.id.clone();
+        let position = |column: &String| {
+            definition
+                .column_position(column)
+                .ok_or_else(|| format!("Unknown column '{column}' in key of table '{name}'"))
+        };
+        let primary_key = definition
+            .primary_key
+            .iter()
+            .map(position)
+            .collect::<Result<Vec<_>, _>>()?;
+        let indexes = definition
+            .indexes
+            .iter()
+            .map(|index| index.columns.iter().map(position).collect())
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns = definition
+            .abstract_definition
+            .attribute_list
+            .iter()
+            .map(|attr| (attr.get_name().clone(), *attr.get_type()))
+            .collect();
+        Ok(TableSchema {
+            name,
+            columns,
+            primary_key,
+            indexes,
+        })
+    }
+
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|(column, _)| column == name)
+    }
+
+    fn column_type(&self, column: usize) -> ApiAttributeType {
+        self.columns[column].1
+    }
+
+    /// Index key of `row` over `columns`. Values that cannot be represented in
+    /// their column's type are keyed as NULL, which lookups never match.
+    pub fn row_key(&self, row: &[AttributeValue], columns: &[usize]) -> Vec<IndexKey> {
+        columns
+            .iter()
+            .map(|&c| {
+                row.get(c)
+                    .and_then(|v| IndexKey::for_column(v, self.column_type(c)))
+                    .unwrap_or(IndexKey::Null)
+            })
+            .collect()
+    }
+
+    /// Lookup key for `values` over `columns`; `None` if a value is NULL or
+    /// cannot be represented in its column's type, so no row can match
+    pub fn lookup_key(
+        &self,
+        values: &[AttributeValue],
+        columns: &[usize],
+    ) -> Option<Vec<IndexKey>> {
+        columns
+            .iter()
+            .zip(values)
+            .map(
+                |(&c, v)| match IndexKey::for_column(v, self.column_type(c))? {
+                    IndexKey::Null => None,
+                    key => Some(key),
+                },
+            )
+            .collect()
+    }
+
+    /// Bounds over a single-column index for `lower <= column <= upper`;
+    /// `None` if the range is empty. NULL keys are always excluded.
+    pub fn range_bounds(
+        &self,
+        column: usize,
+        lower: Option<(&AttributeValue, bool)>,
+        upper: Option<(&AttributeValue, bool)>,
+    ) -> Option<(Bound<Vec<IndexKey>>, Bound<Vec<IndexKey>>)> {
+        let column_type = self.column_type(column);
+        let lower = match lower {
+            Some((value, inclusive)) => {
+                IndexKey::bound_for_column(value, column_type, true, inclusive)?
+            }
+            None => Bound::Excluded(IndexKey::Null),
+        };
+        let upper = match upper {
+            Some((value, inclusive)) => {
+                IndexKey::bound_for_column(value, column_type, false, inclusive)?
+            }
+            None => Bound::Unbounded,
+        };
+        // BTreeMap::range panics on inverted or empty-exclusive ranges
+        if let (
+            Bound::Included(lo) | Bound::Excluded(lo),
+            Bound::Included(hi) | Bound::Excluded(hi),
+        ) = (&lower, &upper)
+        {
+            match lo.cmp(hi) {
+                Ordering::Greater => return None,
+                Ordering::Equal
+                    if !matches!((&lower, &upper), (Bound::Included(_), Bound::Included(_))) =>
+                {
+                    return None
+                }
+                _ => {}
+            }
+        }
+        let wrap = |bound: Bound<IndexKey>| match bound {
+            Bound::Included(k) => Bound::Included(vec![k]),
+            Bound::Excluded(k) => Bound::Excluded(vec![k]),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Some((wrap(lower), wrap(upper)))
+    }
+}
+
+/// Totally ordered, hashable form of a column value. Values are normalised
+/// to the column's type so `Int(5)`, `Long(5)` and `Double(5.0)` all find
+/// the same row of an INT column.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum IndexKey {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// IEEE bits remapped so that integer order equals numeric order
+    Float(u64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl IndexKey {
+    /// Key for `value` in a column of `column_type`; `None` if the value cannot
+    /// be represented in that type (e.g. 2.5 in an INT column)
+    pub fn for_column(value: &AttributeValue, column_type: ApiAttributeType) -> Option<IndexKey> {
+        use AttributeValue as V;
+        if let V::Null = value {
+            return Some(IndexKey::Null);
+        }
+        match column_type {
+            ApiAttributeType::INT | ApiAttributeType::LONG => match value {
+                V::Int(i) => Some(IndexKey::Int(*i as i64)),
+                V::Long(l) => Some(IndexKey::Int(*l)),
+                V::Float(f) => Self::integral(*f as f64),
+                V::Double(d) => Self::integral(*d),
+                _ => None,
+            },
+            // FLOAT columns hold f32, so round-trip lookups through f32 as well
+            ApiAttributeType::FLOAT => as_f64(value).map(|f| Self::float(f as f32 as f64)),
+            ApiAttributeType::DOUBLE => as_f64(value).map(Self::float),
+            ApiAttributeType::STRING => match value {
+                V::String(s) => Some(IndexKey::String(s.clone())),
+                _ => None,
+            },
+            ApiAttributeType::BOOL => match value {
+                V::Bool(b) => Some(IndexKey::Bool(*b)),
+                _ => None,
+            },
+            ApiAttributeType::OBJECT => match value {
+                V::Int(i) => Some(IndexKey::Int(*i as i64)),
+                V::Long(l) => Some(IndexKey::Int(*l)),
+                V::Float(f) => Some(Self::float(*f as f64)),
+                V::Double(d) => Some(Self::float(*d)),
+                V::String(s) => Some(IndexKey::String(s.clone())),
+                V::Bool(b) => Some(IndexKey::Bool(*b)),
+                V::Bytes(b) => Some(IndexKey::Bytes(b.clone())),
+                V::Object(_) | V::Null => None,
+            },
+        }
+    }
+
+    /// Range bound for `column > value` style predicates. On integer columns
+    /// a fractional bound is rounded inward, so `x > 2.5` scans from 3.
+    fn bound_for_column(
+        value: &AttributeValue,
+        column_type: ApiAttributeType,
+        lower: bool,
+        inclusive: bool,
+    ) -> Option<Bound<IndexKey>> {
+        let integer_column = matches!(column_type, ApiAttributeType::INT | ApiAttributeType::LONG);
+        if integer_column {
+            if let Some(f) = as_f64(value).filter(|f| f.fract() != 0.0 && f.is_finite()) {
+                let rounded = if lower { f.ceil() } else { f.floor() };
+                return Some(Bound::Included(IndexKey::Int(rounded as i64)));
+            }
+        }
+        // Stored FLOAT keys are rounded to f32, which may move them across an
+        // exclusive bound; include the bound and let the condition decide
+        let inclusive = inclusive || column_type == ApiAttributeType::FLOAT;
+        match Self::for_column(value, column_type)? {
+            IndexKey::Null => None,
+            key if inclusive => Some(Bound::Included(key)),
+            key => Some(Bound::Excluded(key)),
+        }
+    }
+
+    fn integral(f: f64) -> Option<IndexKey> {
+        (f.fract() == 0.0 && f >= i64::MIN as f64 && f <= i64::MAX as f64)
+            .then_some(IndexKey::Int(f as i64))
+    }
+
+    fn float(f: f64) -> IndexKey {
+        // -0.0 and 0.0 compare equal, so they must share a key
+        let bits = if f == 0.0 { 0.0f64 } else { f }.to_bits();
+        IndexKey::Float(if bits >> 63 == 1 {
+            !bits
+        } else {
+            bits | (1 << 63)
+        })
+    }
+}
+
+fn as_f64(value: &AttributeValue) -> Option<f64> {
+    match value {
+        AttributeValue::Int(i) => Some(*i as f64),
+        AttributeValue::Long(l) => Some(*l as f64),
+        AttributeValue::Float(f) => Some(*f as f64),
+        AttributeValue::Double(d) => Some(*d),
+        _ => None,
+    }
+}
+
+/// Compare two values the way a condition would: numbers by value across
+/// widths, strings and booleans within their type. `None` when the values
+/// are not comparable, including when either is NULL.
+pub(crate) fn compare_values(a: &AttributeValue, b: &AttributeValue) -> Option<Ordering> {
+    use AttributeValue as V;
+    match (a, b) {
+        (V::Int(_) | V::Long(_), V::Int(_) | V::Long(_)) => {
+            let as_i64 = |v: &AttributeValue| match v {
+                V::Int(i) => *i as i64,
+                V::Long(l) => *l,
+                _ => unreachable!(),
+            };
+            Some(as_i64(a).cmp(&as_i64(b)))
+        }
+        (V::String(x), V::String(y)) => Some(x.cmp(y)),
+        (V::Bool(x), V::Bool(y)) => Some(x.cmp(y)),
+        _ => as_f64(a)?.partial_cmp(&as_f64(b)?),
+    }
+}
+
+fn operator_holds(op: Operator, ordering: Ordering) -> bool {
+    match op {
+        Operator::Equal => ordering == Ordering::Equal,
+        Operator::NotEqual => ordering != Ordering::Equal,
+        Operator::LessThan => ordering == Ordering::Less,
+        Operator::LessThanEqual => ordering != Ordering::Greater,
+        Operator::GreaterThan => ordering == Ordering::Greater,
+        Operator::GreaterThanEqual => ordering != Ordering::Less,
+    }
+}
+
+/// `a op b` rewritten as `b op' a`
+fn flip(op: Operator) -> Operator {
+    match op {
+        Operator::LessThan => Operator::GreaterThan,
+        Operator::GreaterThan => Operator::LessThan,
+        Operator::LessThanEqual => Operator::GreaterThanEqual,
+        Operator::GreaterThanEqual => Operator::LessThanEqual,
+        op => op,
+    }
+}
+
+/// Where a lookup value comes from when the condition is evaluated
+#[derive(Debug, Clone)]
+pub(crate) enum KeySource {
+    Constant(AttributeValue),
+    /// Attribute of the probing stream event in a join
+    StreamAttr(usize),
+}
+
+impl KeySource {
+    pub fn resolve(&self, stream: Option<&[AttributeValue]>) -> AttributeValue {
+        match self {
+            KeySource::Constant(value) => value.clone(),
+            KeySource::StreamAttr(i) => stream
+                .and_then(|data| data.get(*i))
+                .cloned()
+                .unwrap_or(AttributeValue::Null),
+        }
+    }
+}
+
+/// An operand of a condition once variables are resolved against the table
+/// (and, for joins, the stream)
+#[derive(Debug, Clone)]
+pub(crate) enum Operand {
+    Column(usize),
+    Value(KeySource),
+}
+
+/// How the candidate rows for a condition are located
+#[derive(Debug, Clone)]
+pub(crate) enum KeyLookup {
+    /// Equality on every primary key column, in key order
+    Primary(Vec<KeySource>),
+    /// Equality on every column of a secondary index
+    Index {
+        index: usize,
+        key: Vec<KeySource>,
+    },
+    /// Bounds on a single-column secondary index; the flag marks an
+    /// inclusive bound
+    Range {
+        index: usize,
+        lower: Option<(KeySource, bool)>,
+        upper: Option<(KeySource, bool)>,
+    },
+    Scan,
+}
+
+impl KeyLookup {
+    /// Pick the cheapest lookup for the top-level conjuncts of `condition`:
+    /// the primary key, then the widest fully-bound secondary index, then a
+    /// range over a single-column index. The lookup only narrows the
+    /// candidate rows; the full condition is still evaluated against them.
+    pub fn plan(
+        schema: &TableSchema,
+        condition: &Expression,
+        resolve: &dyn Fn(&Variable) -> Option<Operand>,
+    ) -> KeyLookup {
+        let mut comparisons = Vec::new();
+        collect_comparisons(condition, resolve, &mut comparisons);
+
+        let mut equalities: HashMap<usize, KeySource> = HashMap::new();
+        for (column, op, source) in &comparisons {
+            if *op == Operator::Equal {
+                equalities.entry(*column).or_insert_with(|| source.clone());
+            }
+        }
+        let bind = |columns: &[usize]| -> Option<Vec<KeySource>> {
+            columns.iter().map(|c| equalities.get(c).cloned()).collect()
+        };
+
+        if !schema.primary_key.is_empty() {
+            if let Some(key) = bind(&schema.primary_key) {
+                return KeyLookup::Primary(key);
+            }
+        }
+        if let Some((index, key)) = schema
+            .indexes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, columns)| bind(columns).map(|key| (i, key)))
+            .max_by_key(|(_, key)| key.len())
+        {
+            return KeyLookup::Index { index, key };
+        }
+        for (index, columns) in schema.indexes.iter().enumerate() {
+            if columns.len() != 1 {
+                continue;
+            }
+            let mut lower = None;
+            let mut upper = None;
+            for (column, op, source) in &comparisons {
+                if *column != columns[0] {
+                    continue;
+                }
+                let slot = match op {
+                    Operator::GreaterThan | Operator::GreaterThanEqual => &mut lower,
+                    Operator::LessThan | Operator::LessThanEqual => &mut upper,
+                    _ => continue,
+                };
+                let inclusive = matches!(op, Operator::GreaterThanEqual | Operator::LessThanEqual);
+                slot.get_or_insert_with(|| (source.clone(), inclusive));
+            }
+            if lower.is_some() || upper.is_some() {
+                return KeyLookup::Range {
+                    index,
+                    lower,
+                    upper,
+                };
+            }
+        }
+        KeyLookup::Scan
+    }
+}
+
+/// `column op value` comparisons among the AND-ed terms of `expr`
+fn collect_comparisons(
+    expr: &Expression,
+    resolve: &dyn Fn(&Variable) -> Option<Operand>,
+    out: &mut Vec<(usize, Operator, KeySource)>,
+) {
+    match expr {
+        Expression::And(and) => {
+            collect_comparisons(&and.left_expression, resolve, out);
+            collect_comparisons(&and.right_expression, resolve, out);
+        }
+        Expression::Compare(cmp) => {
+            let operand = |e: &Expression| match e {
+                Expression::Constant(c) => {
+                    Some(Operand::Value(KeySource::Constant(constant_to_av(c))))
+                }
+                Expression::Variable(v) => resolve(v),
+                _ => None,
+            };
+            match (
+                operand(&cmp.left_expression),
+                operand(&cmp.right_expression),
+            ) {
+                (Some(Operand::Column(c)), Some(Operand::Value(v))) => {
+                    out.push((c, cmp.operator, v))
+                }
+                (Some(Operand::Value(v)), Some(Operand::Column(c))) => {
+                    out.push((c, flip(cmp.operator), v))
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A condition over the columns of a single row
+#[derive(Debug, Clone)]
+pub(crate) enum RowPredicate {
+    Compare {
+        left: PredicateOperand,
+        op: Operator,
+        right: PredicateOperand,
+    },
+    And(Box<RowPredicate>, Box<RowPredicate>),
+    Or(Box<RowPredicate>, Box<RowPredicate>),
+    Not(Box<RowPredicate>),
+    Constant(bool),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum PredicateOperand {
+    Column(usize),
+    Value(AttributeValue),
+}
+
+impl RowPredicate {
+    /// Translate `expr`; `None` if it uses anything other than comparisons of
+    /// columns and constants combined with AND/OR/NOT
+    pub fn from_expression(expr: &Expression, schema: &TableSchema) -> Option<RowPredicate> {
+        let operand = |e: &Expression| match e {
+            Expression::Constant(c) => Some(PredicateOperand::Value(constant_to_av(c))),
+            Expression::Variable(v) => schema
+                .column(&v.attribute_name)
+                .map(PredicateOperand::Column),
+            _ => None,
+        };
+        Some(match expr {
+            Expression::Compare(cmp) => RowPredicate::Compare {
+                left: operand(&cmp.left_expression)?,
+                op: cmp.operator,
+                right: operand(&cmp.right_expression)?,
+            },
+            Expression::And(and) => RowPredicate::And(
+                Box::new(Self::from_expression(&and.left_expression, schema)?),
+                Box::new(Self::from_expression(&and.right_expression, schema)?),
+            ),
+            Expression::Or(or) => RowPredicate::Or(
+                Box::new(Self::from_expression(&or.left_expression, schema)?),
+                Box::new(Self::from_expression(&or.right_expression, schema)?),
+            ),
+            Expression::Not(not) => {
+                RowPredicate::Not(Box::new(Self::from_expression(&not.expression, schema)?))
+            }
+            Expression::Constant(c) => match constant_to_av(c) {
+                AttributeValue::Bool(b) => RowPredicate::Constant(b),
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    pub fn matches(&self, row: &[AttributeValue]) -> bool {
+        let value = |operand: &PredicateOperand| match operand {
+            PredicateOperand::Column(c) => row.get(*c).cloned().unwrap_or(AttributeValue::Null),
+            PredicateOperand::Value(v) => v.clone(),
+        };
+        match self {
+            RowPredicate::Compare { left, op, right } => {
+                compare_values(&value(left), &value(right))
+                    .is_some_and(|ordering| operator_holds(*op, ordering))
+            }
+            RowPredicate::And(a, b) => a.matches(row) && b.matches(row),
+            RowPredicate::Or(a, b) => a.matches(row) || b.matches(row),
+            RowPredicate::Not(p) => !p.matches(row),
+            RowPredicate::Constant(b) => *b,
+        }
+    }
+}
+
+/// Condition compiled by a schema-aware [`InMemoryTable`](super::InMemoryTable):
+/// an index lookup to find candidate rows plus, outside joins, the full
+/// predicate they must satisfy.
+#[derive(Debug, Clone)]
+pub struct IndexedCompiledCondition {
+    pub(crate) lookup: KeyLookup,
+    pub(crate) predicate: Option<RowPredicate>,
+}
+
+impl CompiledCondition for IndexedCompiledCondition {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Update set compiled against table columns: `(column position, new value)`
+/// assignments applied to every matching row.
+#[derive(Debug, Clone)]
+pub struct ColumnCompiledUpdateSet {
+    pub(crate) assignments: Vec<(usize, AttributeValue)>,
+}
+
+impl CompiledUpdateSet for ColumnCompiledUpdateSet {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
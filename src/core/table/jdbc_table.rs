@@ -118,7 +118,7 @@ impl CompiledCondition for JdbcJoinCompiledCondition {
     }
 }
 
-fn expression_to_sql(expr: &Expression, params: &mut Vec<AttributeValue>) -> String {
+pub(super) fn expression_to_sql(expr: &Expression, params: &mut Vec<AttributeValue>) -> String {
     use crate::query_api::expression::condition::compare::Operator as CmpOp;
     use Expression::*;
     match expr {
@@ -158,7 +158,7 @@ fn expression_to_sql(expr: &Expression, params: &mut Vec<AttributeValue>) -> Str
     }
 }
 
-fn expression_to_sql_join(
+pub(super) fn expression_to_sql_join(
     expr: &Expression,
     stream_id: &str,
     stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
@@ -219,6 +219,24 @@ fn expression_to_sql_join(
     }
 }
 
+/// Whether `expr` only uses constructs the SQL translation understands
+pub(super) fn is_translatable(expr: &Expression) -> bool {
+    match expr {
+        Expression::Constant(_) | Expression::Variable(_) => true,
+        Expression::Compare(c) => {
+            is_translatable(&c.left_expression) && is_translatable(&c.right_expression)
+        }
+        Expression::And(a) => {
+            is_translatable(&a.left_expression) && is_translatable(&a.right_expression)
+        }
+        Expression::Or(o) => {
+            is_translatable(&o.left_expression) && is_translatable(&o.right_expression)
+        }
+        Expression::Not(n) => is_translatable(&n.expression),
+        _ => false,
+    }
+}
+
 impl Table for JdbcTable {
     fn insert(
         &self,
@@ -0,0 +1,462 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Bulk loading of table contents at startup, so reference data such as
+//! currency rates or geo lookups is present before the first event:
+//!
+//! ```sql
+//! CREATE TABLE Rates (currency STRING PRIMARY KEY, rate DOUBLE) WITH (
+//!     'extension' = 'inMemory',
+//!     'load.path' = 'data/rates.csv',
+//!     'csv.has-header' = 'true',
+//!     'load.refresh' = '1 hour'
+//! );
+//! ```
+//!
+//! Rows come from a CSV or JSON-lines file, parsed by the registered source
+//! mapper of `load.format`, or from `load.query` run against the data source
+//! `load.data.source`. With `load.refresh` the source is read again on that
+//! schedule and the table contents are swapped with [`Table::replace_rows`].
+
+use crate::core::config::eventflux_context::EventFluxContext;
+use crate::core::event::value::AttributeValue;
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use crate::query_api::definition::attribute::Type as AttributeType;
+use crate::query_api::definition::TableDefinition;
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::retention::now_millis;
+use super::Table;
+
+/// Where the rows of a preloaded table come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableLoadSource {
+    /// A file with one row per line, parsed by the source mapper `format`
+    /// configured with the table's `csv.*` / `json.*` properties
+    File {
+        path: String,
+        format: String,
+        mapper_properties: HashMap<String, String>,
+    },
+    /// A query run against a registered data source
+    Query { data_source: String, query: String },
+}
+
+/// Initial load configured with `WITH ('load.path' = ...)` or
+/// `WITH ('load.data.source' = ..., 'load.query' = ...)`, optionally reloaded
+/// every `load.refresh`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableLoadConfig {
+    pub source: TableLoadSource,
+    pub refresh_ms: Option<i64>,
+}
+
+impl TableLoadConfig {
+    /// Read the load properties of a table; `None` if neither `load.path`
+    /// nor `load.data.source` is set
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let source = match (
+            properties.get("load.path"),
+            properties.get("load.data.source"),
+        ) {
+            (Some(_), Some(_)) => {
+                return Err("'load.path' and 'load.data.source' are exclusive".to_string())
+            }
+            (Some(path), None) => {
+                let format = match properties.get("load.format") {
+                    Some(format) => format.to_lowercase(),
+                    None => match path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()) {
+                        Some(ext) if ext == "csv" => "csv".to_string(),
+                        Some(ext) if ext == "json" || ext == "jsonl" || ext == "ndjson" => {
+                            "json".to_string()
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Cannot tell the format of '{path}', set 'load.format'"
+                            ))
+                        }
+                    },
+                };
+                let prefix = format!("{format}.");
+                TableLoadSource::File {
+                    path: path.clone(),
+                    mapper_properties: properties
+                        .iter()
+                        .filter(|(key, _)| key.starts_with(&prefix))
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                    format,
+                }
+            }
+            (None, Some(data_source)) => TableLoadSource::Query {
+                data_source: data_source.clone(),
+                query: properties
+                    .get("load.query")
+                    .cloned()
+                    .ok_or("'load.data.source' requires 'load.query'")?,
+            },
+            (None, None) => {
+                if let Some(key) = ["load.format", "load.query", "load.refresh"]
+                    .into_iter()
+                    .find(|key| properties.contains_key(*key))
+                {
+                    return Err(format!(
+                        "'{key}' requires 'load.path' or 'load.data.source'"
+                    ));
+                }
+                return Ok(None);
+            }
+        };
+        let refresh_ms = properties
+            .get("load.refresh")
+            .map(|refresh| {
+                crate::core::config::types::parse_duration(refresh)
+                    .map(|d| d.as_millis() as i64)
+                    .map_err(|e| format!("Invalid 'load.refresh' '{refresh}': {e}"))
+                    .and_then(|ms| {
+                        if ms > 0 {
+                            Ok(ms)
+                        } else {
+                            Err("'load.refresh' must be positive".to_string())
+                        }
+                    })
+            })
+            .transpose()?;
+        Ok(Some(TableLoadConfig { source, refresh_ms }))
+    }
+
+    /// Read every row of the source, converted to the column types of
+    /// `definition`
+    pub fn load(
+        &self,
+        definition: &TableDefinition,
+        context: &EventFluxContext,
+    ) -> Result<Vec<Vec<AttributeValue>>, String> {
+        let types: Vec<AttributeType> = definition
+            .abstract_definition
+            .attribute_list
+            .iter()
+            .map(|a| *a.get_type())
+            .collect();
+        let rows = match &self.source {
+            TableLoadSource::File {
+                path,
+                format,
+                mapper_properties,
+            } => Self::read_file(path, format, mapper_properties, context)?,
+            TableLoadSource::Query { data_source, query } => {
+                Self::run_query(data_source, query, context)?
+            }
+        };
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                if row.len() != types.len() {
+                    return Err(format!(
+                        "Row {} has {} values, expected {}",
+                        i + 1,
+                        row.len(),
+                        types.len()
+                    ));
+                }
+                row.into_iter()
+                    .zip(&types)
+                    .map(|(value, attr_type)| coerce(value, attr_type))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Row {}: {}", i + 1, e))
+            })
+            .collect()
+    }
+
+    fn read_file(
+        path: &str,
+        format: &str,
+        mapper_properties: &HashMap<String, String>,
+        context: &EventFluxContext,
+    ) -> Result<Vec<Vec<AttributeValue>>, String> {
+        let factory = context
+            .get_source_mapper_factory(format)
+            .ok_or_else(|| format!("Unknown 'load.format' '{format}'"))?;
+        // The file is mapped line by line, so the header is skipped here
+        let mut mapper_properties = mapper_properties.clone();
+        let has_header = mapper_properties
+            .remove("csv.has-header")
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        let mapper = factory
+            .create_initialized(&mapper_properties)
+            .map_err(|e| e.to_string())?;
+
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| format!("Cannot read '{path}': {e}"))?;
+        let mut rows = Vec::new();
+        for (number, line) in contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .skip(usize::from(has_header))
+        {
+            let events = mapper
+                .map(line.as_bytes())
+                .map_err(|e| format!("'{}' line {}: {}", path, number + 1, e))?;
+            rows.extend(events.into_iter().map(|event| event.data));
+        }
+        Ok(rows)
+    }
+
+    fn run_query(
+        data_source: &str,
+        query: &str,
+        context: &EventFluxContext,
+    ) -> Result<Vec<Vec<AttributeValue>>, String> {
+        let ds = context
+            .get_data_source(data_source)
+            .ok_or_else(|| format!("DataSource '{data_source}' not found"))?;
+        let conn = ds
+            .get_connection()?
+            .downcast::<Arc<Mutex<Connection>>>()
+            .map_err(|_| format!("DataSource '{data_source}' does not support queries"))?;
+        let conn = conn.lock().unwrap();
+        let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
+        let column_count = stmt.column_count();
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((0..column_count)
+                    .map(|i| match row.get_ref_unwrap(i) {
+                        ValueRef::Null => AttributeValue::Null,
+                        ValueRef::Integer(v) => AttributeValue::Long(v),
+                        ValueRef::Real(v) => AttributeValue::Double(v),
+                        ValueRef::Text(v) => {
+                            AttributeValue::String(String::from_utf8_lossy(v).to_string())
+                        }
+                        ValueRef::Blob(v) => AttributeValue::Bytes(v.to_vec()),
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Convert a loaded value, typed by the mapper or database, to the declared
+/// column type
+fn coerce(value: AttributeValue, attr_type: &AttributeType) -> Result<AttributeValue, String> {
+    let converted = match (&value, attr_type) {
+        (AttributeValue::Null, _) | (_, AttributeType::OBJECT) => Some(value.clone()),
+        (AttributeValue::String(s), AttributeType::STRING) => {
+            Some(AttributeValue::String(s.clone()))
+        }
+        (AttributeValue::String(s), AttributeType::INT) => {
+            s.trim().parse().ok().map(AttributeValue::Int)
+        }
+        (AttributeValue::String(s), AttributeType::LONG) => {
+            s.trim().parse().ok().map(AttributeValue::Long)
+        }
+        (AttributeValue::String(s), AttributeType::FLOAT) => {
+            s.trim().parse().ok().map(AttributeValue::Float)
+        }
+        (AttributeValue::String(s), AttributeType::DOUBLE) => {
+            s.trim().parse().ok().map(AttributeValue::Double)
+        }
+        (AttributeValue::String(s), AttributeType::BOOL) => s
+            .trim()
+            .to_lowercase()
+            .parse()
+            .ok()
+            .map(AttributeValue::Bool),
+        (_, AttributeType::STRING) => Some(AttributeValue::String(value.to_string())),
+        (AttributeValue::Bool(b), AttributeType::BOOL) => Some(AttributeValue::Bool(*b)),
+        (AttributeValue::Int(v), _) => integer(*v as i64, attr_type),
+        (AttributeValue::Long(v), _) => integer(*v, attr_type),
+        (AttributeValue::Float(v), _) => real(*v as f64, attr_type),
+        (AttributeValue::Double(v), _) => real(*v, attr_type),
+        _ => None,
+    };
+    converted.ok_or_else(|| format!("Cannot convert {value:?} to {attr_type:?}"))
+}
+
+fn integer(v: i64, attr_type: &AttributeType) -> Option<AttributeValue> {
+    match attr_type {
+        AttributeType::INT => i32::try_from(v).ok().map(AttributeValue::Int),
+        AttributeType::LONG => Some(AttributeValue::Long(v)),
+        AttributeType::FLOAT => Some(AttributeValue::Float(v as f32)),
+        AttributeType::DOUBLE => Some(AttributeValue::Double(v as f64)),
+        // SQLite stores booleans as 0 / 1
+        AttributeType::BOOL if v == 0 || v == 1 => Some(AttributeValue::Bool(v == 1)),
+        _ => None,
+    }
+}
+
+fn real(v: f64, attr_type: &AttributeType) -> Option<AttributeValue> {
+    match attr_type {
+        AttributeType::FLOAT => Some(AttributeValue::Float(v as f32)),
+        AttributeType::DOUBLE => Some(AttributeValue::Double(v)),
+        AttributeType::INT | AttributeType::LONG if v.fract() == 0.0 => {
+            integer(v as i64, attr_type)
+        }
+        _ => None,
+    }
+}
+
+/// Periodically reloads a preloaded table (`load.refresh`), swapping its
+/// contents at once.
+#[derive(Debug)]
+pub struct TableLoadRuntime {
+    table_id: String,
+    table: Arc<dyn Table>,
+    definition: Arc<TableDefinition>,
+    config: TableLoadConfig,
+    context: Arc<EventFluxContext>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl TableLoadRuntime {
+    pub fn new(
+        table_id: String,
+        table: Arc<dyn Table>,
+        definition: Arc<TableDefinition>,
+        config: TableLoadConfig,
+        context: Arc<EventFluxContext>,
+        interval_ms: i64,
+        scheduler: Arc<Scheduler>,
+    ) -> Self {
+        Self {
+            table_id,
+            table,
+            definition,
+            config,
+            context,
+            interval_ms,
+            scheduler,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let task = RefreshTask {
+            table_id: self.table_id.clone(),
+            table: Arc::clone(&self.table),
+            definition: Arc::clone(&self.definition),
+            config: self.config.clone(),
+            context: Arc::clone(&self.context),
+            interval_ms: self.interval_ms,
+            scheduler: Arc::clone(&self.scheduler),
+            running: Arc::clone(&self.running),
+        };
+        self.scheduler
+            .notify_at(now_millis() + self.interval_ms, Arc::new(task));
+    }
+
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RefreshTask {
+    table_id: String,
+    table: Arc<dyn Table>,
+    definition: Arc<TableDefinition>,
+    config: TableLoadConfig,
+    context: Arc<EventFluxContext>,
+    interval_ms: i64,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl Schedulable for RefreshTask {
+    fn on_time(&self, timestamp: i64) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        // A failed reload keeps the current rows until the next one
+        match self.config.load(&self.definition, &self.context) {
+            Ok(rows) => match self.table.replace_rows(&rows) {
+                Ok(()) => log::debug!(
+                    "Reloaded {} row(s) into table '{}'",
+                    rows.len(),
+                    self.table_id
+                ),
+                Err(e) => log::error!("Failed to reload table '{}': {}", self.table_id, e),
+            },
+            Err(e) => log::error!("Failed to reload table '{}': {}", self.table_id, e),
+        }
+        self.scheduler
+            .notify_at(timestamp + self.interval_ms, Arc::new(self.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_config_from_properties() {
+        assert_eq!(TableLoadConfig::from_properties(&props(&[])).unwrap(), None);
+        let config = TableLoadConfig::from_properties(&props(&[
+            ("load.path", "data/rates.CSV"),
+            ("csv.delimiter", ";"),
+            ("json.ignore-parse-errors", "true"),
+            ("load.refresh", "1 min"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            config,
+            TableLoadConfig {
+                source: TableLoadSource::File {
+                    path: "data/rates.CSV".to_string(),
+                    format: "csv".to_string(),
+                    mapper_properties: props(&[("csv.delimiter", ";")]),
+                },
+                refresh_ms: Some(60_000),
+            }
+        );
+
+        assert!(TableLoadConfig::from_properties(&props(&[("load.path", "rates.txt")])).is_err());
+        assert!(TableLoadConfig::from_properties(&props(&[("load.data.source", "db")])).is_err());
+        assert!(TableLoadConfig::from_properties(&props(&[("load.refresh", "1 min")])).is_err());
+        assert!(TableLoadConfig::from_properties(&props(&[
+            ("load.path", "rates.csv"),
+            ("load.refresh", "0s"),
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_coerce_to_column_type() {
+        assert_eq!(
+            coerce(AttributeValue::Int(3), &AttributeType::DOUBLE),
+            Ok(AttributeValue::Double(3.0))
+        );
+        assert_eq!(
+            coerce(AttributeValue::Long(1), &AttributeType::BOOL),
+            Ok(AttributeValue::Bool(true))
+        );
+        assert_eq!(
+            coerce(AttributeValue::Int(7), &AttributeType::STRING),
+            Ok(AttributeValue::String("7".to_string()))
+        );
+        assert_eq!(
+            coerce(AttributeValue::String(" 42 ".into()), &AttributeType::LONG),
+            Ok(AttributeValue::Long(42))
+        );
+        assert!(coerce(AttributeValue::Double(1.5), &AttributeType::INT).is_err());
+        assert!(coerce(AttributeValue::String("x".into()), &AttributeType::INT).is_err());
+    }
+}
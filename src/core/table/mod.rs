@@ -3,17 +3,41 @@
 use crate::core::event::stream::stream_event::StreamEvent;
 use crate::core::event::value::AttributeValue;
 use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::persistence::state_holder::StateOperation;
 use crate::query_api::execution::query::output::stream::UpdateSet;
 use crate::query_api::expression::Expression;
 use std::sync::RwLock;
 
+mod blocking;
 mod cache_table;
+mod caching_table;
+mod change_stream;
+mod index;
 mod jdbc_table;
+mod loader;
+mod postgres_table;
+mod redis_table;
+mod retention;
+mod sqlite_table;
 use crate::core::config::eventflux_context::EventFluxContext;
 use crate::core::extension::TableFactory;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::expression::Variable;
 pub use cache_table::{CacheTable, CacheTableFactory};
+pub use caching_table::{
+    CachePolicy, CachingTable, TableCacheConfig, TableCacheStats, TableFlushRuntime, WriteMode,
+};
+pub use change_stream::{ChangeOperation, ChangeStreamTable, OPERATION_COLUMN};
+pub use index::{ColumnCompiledUpdateSet, IndexedCompiledCondition};
+use index::{IndexKey, KeyLookup, KeySource, Operand, RowPredicate, TableSchema};
 pub use jdbc_table::{JdbcTable, JdbcTableFactory};
-use std::collections::HashMap;
+pub use loader::{TableLoadConfig, TableLoadRuntime, TableLoadSource};
+pub use postgres_table::{PostgresTable, PostgresTableFactory};
+pub use redis_table::{RedisTable, RedisTableFactory};
+use retention::{now_millis, RowClock};
+pub use retention::{RetentionPolicy, TableEvictionRuntime, TableRetentionStateHolder, TtlMode};
+pub use sqlite_table::{SqliteTable, SqliteTableFactory};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -88,6 +112,40 @@ impl CompiledUpdateSet for InMemoryCompiledUpdateSet {
     }
 }
 
+/// Rows of `rows` missing from `other`, counting duplicates
+fn rows_missing_from(
+    rows: &[Vec<AttributeValue>],
+    other: &[Vec<AttributeValue>],
+) -> Vec<Vec<AttributeValue>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for row in other {
+        *counts.entry(InMemoryTable::row_to_key(row)).or_default() += 1;
+    }
+    rows.iter()
+        .filter(
+            |row| match counts.get_mut(&InMemoryTable::row_to_key(row)) {
+                Some(n) if *n > 0 => {
+                    *n -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+/// Rows removed from and added to a table between `before` and `after`
+fn row_differences(
+    before: &[Vec<AttributeValue>],
+    after: &[Vec<AttributeValue>],
+) -> (Vec<Vec<AttributeValue>>, Vec<Vec<AttributeValue>>) {
+    (
+        rows_missing_from(before, after),
+        rows_missing_from(after, before),
+    )
+}
+
 /// Trait representing a table that can store rows of `AttributeValue`s.
 pub trait Table: Debug + Send + Sync {
     /// Inserts a row into the table.
@@ -100,6 +158,19 @@ pub trait Table: Debug + Send + Sync {
         values: &[AttributeValue],
     ) -> Result<(), crate::core::exception::EventFluxError>;
 
+    /// Inserts several rows, e.g. a write-behind batch. Tables backed by a
+    /// database override this to write the batch in one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails (e.g., database error).
+    fn insert_batch(
+        &self,
+        rows: &[Vec<AttributeValue>],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        rows.iter().try_for_each(|row| self.insert(row))
+    }
+
     /// Updates rows matching `condition` using the values from `update_set`.
     /// Returns `true` if any row was updated.
     ///
@@ -123,6 +194,65 @@ pub trait Table: Debug + Send + Sync {
         condition: &dyn CompiledCondition,
     ) -> Result<bool, crate::core::exception::EventFluxError>;
 
+    /// Like [`Table::update`], but returns each updated row as it was before
+    /// and after the update. The default implementation compares the table
+    /// contents before and after, pairing changed rows in table order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails (e.g., database error).
+    fn update_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<
+        Vec<(Vec<AttributeValue>, Vec<AttributeValue>)>,
+        crate::core::exception::EventFluxError,
+    > {
+        let before = self.all_rows()?;
+        if !self.update(condition, update_set)? {
+            return Ok(Vec::new());
+        }
+        let (removed, added) = row_differences(&before, &self.all_rows()?);
+        Ok(removed.into_iter().zip(added).collect())
+    }
+
+    /// Like [`Table::delete`], but returns the deleted rows. The default
+    /// implementation compares the table contents before and after.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails (e.g., database error).
+    fn delete_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
+        let before = self.all_rows()?;
+        if !self.delete(condition)? {
+            return Ok(Vec::new());
+        }
+        Ok(row_differences(&before, &self.all_rows()?).0)
+    }
+
+    /// Replaces every row of the table with `rows`, e.g. when a preloaded
+    /// table is refreshed. Tables overriding this swap the contents at once,
+    /// so lookups never see a partly loaded table; the default deletes the
+    /// rows that are gone and inserts the new ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails (e.g., database error).
+    fn replace_rows(
+        &self,
+        rows: &[Vec<AttributeValue>],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let (removed, added) = row_differences(&self.all_rows()?, rows);
+        for row in removed {
+            self.delete(&InMemoryCompiledCondition { values: row })?;
+        }
+        self.insert_batch(&added)
+    }
+
     /// Finds the first row matching `condition` and returns a clone of it.
     ///
     /// # Errors
@@ -221,6 +351,93 @@ pub trait Table: Debug + Send + Sync {
     /// Returns an error if the cloning operation fails (e.g., cannot reconnect to database).
     fn clone_table(&self) -> Result<Box<dyn Table>, crate::core::exception::EventFluxError>;
 
+    /// Row retention (`ttl` / `max.rows`) enforced by this table, if any.
+    /// Tables that don't support retention return `None`.
+    fn retention(&self) -> Option<&RetentionPolicy> {
+        None
+    }
+
+    /// Remove and return the rows whose retention has lapsed at `now`
+    /// (epoch milliseconds). Called periodically by [`TableEvictionRuntime`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    fn evict_expired(
+        &self,
+        _now: i64,
+    ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
+        Ok(Vec::new())
+    }
+
+    /// Every row paired with the time it was last written (or accessed), so
+    /// snapshots of tables with a retention policy restore row ages too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table doesn't track row times.
+    fn timed_rows(
+        &self,
+    ) -> Result<Vec<(Vec<AttributeValue>, i64)>, crate::core::exception::EventFluxError> {
+        Err(crate::core::exception::EventFluxError::Other(
+            "Table does not track row times".to_string(),
+        ))
+    }
+
+    /// Replace the contents of the table with rows captured by
+    /// [`Table::timed_rows`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table doesn't track row times.
+    fn restore_timed_rows(
+        &self,
+        _rows: Vec<(Vec<AttributeValue>, i64)>,
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        Err(crate::core::exception::EventFluxError::Other(
+            "Table does not track row times".to_string(),
+        ))
+    }
+
+    /// Start recording row changes for [`Table::pending_changes`]. Returns
+    /// `false` if the table can't record them.
+    fn record_changes(&self) -> bool {
+        false
+    }
+
+    /// Row changes recorded since they were last committed, oldest first, as
+    /// operations on the timed rows of [`Table::timed_rows`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table isn't recording changes.
+    fn pending_changes(
+        &self,
+    ) -> Result<Vec<StateOperation>, crate::core::exception::EventFluxError> {
+        Err(crate::core::exception::EventFluxError::Other(
+            "Table does not record changes".to_string(),
+        ))
+    }
+
+    /// Drop the first `count` changes returned by [`Table::pending_changes`]
+    /// once they are checkpointed.
+    fn commit_changes(&self, _count: usize) {}
+
+    /// Replay changes captured by [`Table::pending_changes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table doesn't record changes or a change
+    /// doesn't match its rows.
+    fn apply_changes(
+        &self,
+        _changes: &[StateOperation],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        Err(crate::core::exception::EventFluxError::Other(
+            "Table does not record changes".to_string(),
+        ))
+    }
+
     /// Phase 2 validation: Verify connectivity and external resource availability
     ///
     /// This method is called during application initialization (Phase 2) to validate
@@ -263,6 +480,21 @@ pub trait Table: Debug + Send + Sync {
     fn validate_connectivity(&self) -> Result<(), crate::core::exception::EventFluxError> {
         Ok(()) // Default: no validation needed (in-memory tables)
     }
+
+    /// Write out any buffered writes. Called periodically by
+    /// [`TableFlushRuntime`] and at shutdown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    fn flush(&self) -> Result<(), crate::core::exception::EventFluxError> {
+        Ok(())
+    }
+
+    /// Hit/miss counters of tables with a lookup cache (`cache.policy`).
+    fn cache_stats(&self) -> Option<TableCacheStats> {
+        None
+    }
 }
 
 impl Clone for Box<dyn Table> {
@@ -272,19 +504,426 @@ impl Clone for Box<dyn Table> {
     }
 }
 
-/// Simple in-memory table storing rows in a vector with HashMap index for O(1) lookups.
+type RowId = u64;
+
+/// A row and the time it was last written (or accessed)
+type TimedRow = (Vec<AttributeValue>, i64);
+
+/// A change to the rows of an [`InMemoryTable`], kept until it is checkpointed
+#[derive(Debug, Clone)]
+enum RowChange {
+    Insert(TimedRow),
+    Update { old: TimedRow, new: TimedRow },
+    Delete(TimedRow),
+    Clear,
+}
+
+impl RowChange {
+    fn to_operation(&self) -> Result<StateOperation, crate::core::exception::EventFluxError> {
+        let encode = |row: &TimedRow| {
+            crate::core::util::to_bytes(row).map_err(|e| {
+                crate::core::exception::EventFluxError::Other(format!(
+                    "Failed to serialize row change: {e}"
+                ))
+            })
+        };
+        Ok(match self {
+            RowChange::Insert(row) => StateOperation::Insert {
+                key: InMemoryTable::row_to_key(&row.0).into_bytes(),
+                value: encode(row)?,
+            },
+            RowChange::Update { old, new } => StateOperation::Update {
+                key: InMemoryTable::row_to_key(&old.0).into_bytes(),
+                old_value: encode(old)?,
+                new_value: encode(new)?,
+            },
+            RowChange::Delete(row) => StateOperation::Delete {
+                key: InMemoryTable::row_to_key(&row.0).into_bytes(),
+                old_value: encode(row)?,
+            },
+            RowChange::Clear => StateOperation::Clear,
+        })
+    }
+}
+
+/// Rows of an [`InMemoryTable`] together with every index over them. Row ids
+/// grow monotonically, so iterating `rows` yields rows in insertion order.
+#[derive(Debug, Default, Clone)]
+struct TableState {
+    rows: BTreeMap<RowId, Vec<AttributeValue>>,
+    next_id: RowId,
+    /// Serialized whole row → ids, for conditions that match complete rows
+    row_index: HashMap<String, Vec<RowId>>,
+    primary: HashMap<Vec<IndexKey>, RowId>,
+    secondary: Vec<BTreeMap<Vec<IndexKey>, BTreeSet<RowId>>>,
+    /// Row write (or access) times, kept only for tables with retention
+    clock: RowClock<RowId>,
+    /// Changes not yet checkpointed, kept only while recording changes
+    changes: Option<Vec<RowChange>>,
+    /// Position in `changes` of each row's pending access-time update, so
+    /// repeated reads of a row amend one change instead of adding more
+    accessed: HashMap<RowId, usize>,
+}
+
+impl TableState {
+    fn new(schema: Option<&TableSchema>) -> Self {
+        Self {
+            secondary: vec![BTreeMap::new(); schema.map_or(0, |s| s.indexes.len())],
+            ..Default::default()
+        }
+    }
+
+    fn add_row(&mut self, schema: Option<&TableSchema>, id: RowId, row: Vec<AttributeValue>) {
+        self.row_index
+            .entry(InMemoryTable::row_to_key(&row))
+            .or_default()
+            .push(id);
+        if let Some(schema) = schema {
+            if !schema.primary_key.is_empty() {
+                self.primary
+                    .insert(schema.row_key(&row, &schema.primary_key), id);
+            }
+            for (columns, index) in schema.indexes.iter().zip(self.secondary.iter_mut()) {
+                index
+                    .entry(schema.row_key(&row, columns))
+                    .or_default()
+                    .insert(id);
+            }
+        }
+        self.rows.insert(id, row);
+    }
+
+    fn remove_row(
+        &mut self,
+        schema: Option<&TableSchema>,
+        id: RowId,
+    ) -> Option<Vec<AttributeValue>> {
+        let row = self.rows.remove(&id)?;
+        self.clock.forget(&id);
+        // Later changes to the row must follow its access-time update
+        self.accessed.remove(&id);
+        let key = InMemoryTable::row_to_key(&row);
+        if let Some(ids) = self.row_index.get_mut(&key) {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                self.row_index.remove(&key);
+            }
+        }
+        if let Some(schema) = schema {
+            if !schema.primary_key.is_empty() {
+                let pk = schema.row_key(&row, &schema.primary_key);
+                if self.primary.get(&pk) == Some(&id) {
+                    self.primary.remove(&pk);
+                }
+            }
+            for (columns, index) in schema.indexes.iter().zip(self.secondary.iter_mut()) {
+                let key = schema.row_key(&row, columns);
+                if let Some(ids) = index.get_mut(&key) {
+                    ids.remove(&id);
+                    if ids.is_empty() {
+                        index.remove(&key);
+                    }
+                }
+            }
+        }
+        Some(row)
+    }
+
+    /// Remove row `id`, recording the deletion
+    fn delete_row(
+        &mut self,
+        schema: Option<&TableSchema>,
+        id: RowId,
+    ) -> Option<Vec<AttributeValue>> {
+        if self.changes.is_some() && self.rows.contains_key(&id) {
+            let row = self.timed_row(id);
+            self.record(RowChange::Delete(row));
+        }
+        self.remove_row(schema, id)
+    }
+
+    /// Row `id` with the time it was last written (or accessed)
+    fn timed_row(&self, id: RowId) -> TimedRow {
+        (
+            self.rows[&id].clone(),
+            self.clock.touched_at(&id).unwrap_or_else(now_millis),
+        )
+    }
+
+    fn record(&mut self, change: RowChange) {
+        if let Some(changes) = &mut self.changes {
+            changes.push(change);
+        }
+    }
+
+    /// Record that row `id` was read at `now`, amending the row's pending
+    /// access-time update if nothing else changed the row since
+    fn record_access(&mut self, id: RowId, now: i64) {
+        let Some(changes) = &mut self.changes else {
+            return;
+        };
+        if let Some(&position) = self.accessed.get(&id) {
+            if let Some(RowChange::Update { new, .. }) = changes.get_mut(position) {
+                new.1 = now;
+                return;
+            }
+        }
+        let old = (
+            self.rows[&id].clone(),
+            self.clock.touched_at(&id).unwrap_or(now),
+        );
+        let new = (old.0.clone(), now);
+        self.accessed.insert(id, changes.len());
+        changes.push(RowChange::Update { old, new });
+    }
+
+    /// Id of the row a replayed change refers to: one with the contents of
+    /// `row`, preferring the one touched at the same time among duplicates
+    fn replayed_id(&self, (row, touched): &TimedRow) -> Option<RowId> {
+        let ids = self.row_index.get(&InMemoryTable::row_to_key(row))?;
+        ids.iter()
+            .copied()
+            .find(|id| self.clock.touched_at(id) == Some(*touched))
+            .or_else(|| ids.first().copied())
+    }
+
+    /// Ids of rows matching `condition`, in insertion order
+    fn matching_ids(
+        &self,
+        schema: Option<&TableSchema>,
+        condition: &dyn CompiledCondition,
+    ) -> Vec<RowId> {
+        if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+        {
+            // A key-length value list on a keyed table (e.g. `x IN T`) is a
+            // primary key lookup; anything else must match the whole row
+            if let Some(schema) = schema.filter(|s| {
+                !s.primary_key.is_empty()
+                    && cond.values.len() == s.primary_key.len()
+                    && cond.values.len() != s.columns.len()
+            }) {
+                return schema
+                    .lookup_key(&cond.values, &schema.primary_key)
+                    .and_then(|key| self.primary.get(&key).copied())
+                    .into_iter()
+                    .collect();
+            }
+            return self
+                .row_index
+                .get(&InMemoryTable::row_to_key(&cond.values))
+                .cloned()
+                .unwrap_or_default();
+        }
+        match (
+            schema,
+            condition
+                .as_any()
+                .downcast_ref::<IndexedCompiledCondition>(),
+        ) {
+            (Some(schema), Some(cond)) => {
+                let mut ids = self.candidate_ids(schema, &cond.lookup, None);
+                if let Some(predicate) = &cond.predicate {
+                    ids.retain(|id| predicate.matches(&self.rows[id]));
+                }
+                ids
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Ids of rows located by `lookup`, in insertion order. `stream` supplies
+    /// the probing event's attributes for join lookups.
+    fn candidate_ids(
+        &self,
+        schema: &TableSchema,
+        lookup: &KeyLookup,
+        stream: Option<&[AttributeValue]>,
+    ) -> Vec<RowId> {
+        let resolve = |sources: &[KeySource]| {
+            sources
+                .iter()
+                .map(|s| s.resolve(stream))
+                .collect::<Vec<_>>()
+        };
+        let mut ids: Vec<RowId> = match lookup {
+            KeyLookup::Primary(sources) => {
+                return schema
+                    .lookup_key(&resolve(sources), &schema.primary_key)
+                    .and_then(|key| self.primary.get(&key).copied())
+                    .into_iter()
+                    .collect();
+            }
+            KeyLookup::Index { index, key } => schema
+                .lookup_key(&resolve(key), &schema.indexes[*index])
+                .and_then(|key| self.secondary[*index].get(&key))
+                .map(|ids| ids.iter().copied().collect())
+                .unwrap_or_default(),
+            KeyLookup::Range {
+                index,
+                lower,
+                upper,
+            } => {
+                let lower = lower
+                    .as_ref()
+                    .map(|(s, inclusive)| (s.resolve(stream), *inclusive));
+                let upper = upper
+                    .as_ref()
+                    .map(|(s, inclusive)| (s.resolve(stream), *inclusive));
+                match schema.range_bounds(
+                    schema.indexes[*index][0],
+                    lower.as_ref().map(|(v, inclusive)| (v, *inclusive)),
+                    upper.as_ref().map(|(v, inclusive)| (v, *inclusive)),
+                ) {
+                    Some(bounds) => self.secondary[*index]
+                        .range(bounds)
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect(),
+                    None => Vec::new(),
+                }
+            }
+            KeyLookup::Scan => return self.rows.keys().copied().collect(),
+        };
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Fail if replacing the rows in `updates` would give two rows the same
+    /// primary key
+    fn check_primary_key_conflicts(
+        &self,
+        schema: Option<&TableSchema>,
+        updates: &[(RowId, Vec<AttributeValue>)],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let schema = match schema.filter(|s| !s.primary_key.is_empty()) {
+            Some(schema) => schema,
+            None => return Ok(()),
+        };
+        let updated: HashSet<RowId> = updates.iter().map(|(id, _)| *id).collect();
+        let mut new_keys = HashSet::new();
+        for (_, row) in updates {
+            let key = schema.row_key(row, &schema.primary_key);
+            let taken = self
+                .primary
+                .get(&key)
+                .is_some_and(|owner| !updated.contains(owner));
+            if taken || !new_keys.insert(key) {
+                return Err(crate::core::exception::EventFluxError::Other(format!(
+                    "Update would duplicate a primary key in table '{}'",
+                    schema.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// In-memory table. Rows are indexed by their full contents, and tables
+/// created from a definition also maintain a primary key (upsert on insert)
+/// and any secondary indexes, which compiled conditions and joins use to
+/// avoid scanning every row.
 #[derive(Debug, Default)]
 pub struct InMemoryTable {
-    rows: RwLock<Vec<Vec<AttributeValue>>>,
-    // Index: maps serialized row key → Vec of indices in rows Vec (supports duplicates)
-    index: RwLock<HashMap<String, Vec<usize>>>,
+    schema: Option<Arc<TableSchema>>,
+    retention: Option<RetentionPolicy>,
+    state: RwLock<TableState>,
 }
 
 impl InMemoryTable {
     pub fn new() -> Self {
         Self {
-            rows: RwLock::new(Vec::new()),
-            index: RwLock::new(HashMap::new()),
+            schema: None,
+            retention: None,
+            state: RwLock::new(TableState::new(None)),
+        }
+    }
+
+    /// Create a table honouring the primary key and indexes of `definition`
+    pub fn with_definition(definition: &TableDefinition) -> Result<Self, String> {
+        let schema = TableSchema::from_definition(definition)?;
+        Ok(Self {
+            state: RwLock::new(TableState::new(Some(&schema))),
+            schema: Some(Arc::new(schema)),
+            retention: None,
+        })
+    }
+
+    /// Evict rows according to `policy` (see [`Table::evict_expired`])
+    pub fn with_retention(mut self, policy: Option<RetentionPolicy>) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    fn schema(&self) -> Option<&TableSchema> {
+        self.schema.as_deref()
+    }
+
+    /// Add `values` to `state`, replacing the row with the same primary key
+    fn upsert(
+        &self,
+        state: &mut TableState,
+        values: &[AttributeValue],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let schema = self.schema();
+        if let Some(schema) = schema.filter(|s| !s.primary_key.is_empty()) {
+            if let Some(&c) = schema
+                .primary_key
+                .iter()
+                .find(|&&c| matches!(values.get(c), None | Some(AttributeValue::Null)))
+            {
+                return Err(crate::core::exception::EventFluxError::Other(format!(
+                    "Primary key column '{}' of table '{}' cannot be NULL",
+                    schema.columns[c].0, schema.name
+                )));
+            }
+            // Upsert: a row with the same key is replaced
+            let existing = state
+                .primary
+                .get(&schema.row_key(values, &schema.primary_key))
+                .copied();
+            if let Some(existing) = existing {
+                state.delete_row(Some(schema), existing);
+            }
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.add_row(schema, id, values.to_vec());
+        self.touch_written(state, id);
+        if state.changes.is_some() {
+            let row = state.timed_row(id);
+            state.record(RowChange::Insert(row));
+        }
+        Ok(())
+    }
+
+    /// Record a write of row `id` for retention
+    fn touch_written(&self, state: &mut TableState, id: RowId) {
+        if self.retention.is_some() {
+            state.clock.touch(id, now_millis());
+        }
+    }
+
+    /// Record reads of rows `ids`; only `after_access` retention cares. Rows
+    /// removed since they were read are skipped.
+    fn touch_accessed(&self, ids: &[RowId]) {
+        if ids.is_empty() || !matches!(&self.retention, Some(p) if p.mode == TtlMode::AfterAccess) {
+            return;
+        }
+        let now = now_millis();
+        let mut state = self.state.write().unwrap();
+        for &id in ids {
+            if state.rows.contains_key(&id) {
+                state.record_access(id, now);
+                state.clock.touch(id, now);
+            }
+        }
+    }
+
+    /// Set the time of restored row `id` for retention
+    fn touch_restored(&self, state: &mut TableState, id: RowId, touched: i64) {
+        if self.retention.is_some() {
+            state.clock.touch(id, touched);
         }
     }
 
@@ -309,7 +948,36 @@ impl InMemoryTable {
     }
 
     pub fn all_rows(&self) -> Vec<Vec<AttributeValue>> {
-        self.rows.read().unwrap().clone()
+        self.state.read().unwrap().rows.values().cloned().collect()
+    }
+
+    /// Resolve a join condition variable to a table column or an attribute of
+    /// the stream. Variables qualified by the stream belong to it, any other
+    /// qualifier names the table; unqualified names must be unambiguous.
+    fn resolve_join_variable(
+        schema: &TableSchema,
+        variable: &Variable,
+        stream_id: &str,
+        stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
+    ) -> Option<Operand> {
+        let stream_position = || {
+            stream_def
+                .abstract_definition
+                .attribute_list
+                .iter()
+                .position(|a| a.get_name() == &variable.attribute_name)
+        };
+        match variable.stream_id.as_deref() {
+            Some(id) if id == stream_id => {
+                stream_position().map(|i| Operand::Value(KeySource::StreamAttr(i)))
+            }
+            Some(_) => schema.column(&variable.attribute_name).map(Operand::Column),
+            None => match (schema.column(&variable.attribute_name), stream_position()) {
+                (Some(c), None) => Some(Operand::Column(c)),
+                (None, Some(i)) => Some(Operand::Value(KeySource::StreamAttr(i))),
+                _ => None,
+            },
+        }
     }
 }
 
@@ -318,19 +986,36 @@ impl Table for InMemoryTable {
         &self,
         values: &[AttributeValue],
     ) -> Result<(), crate::core::exception::EventFluxError> {
-        let key = Self::row_to_key(values);
-        let mut rows = self.rows.write().unwrap();
-        let new_index = rows.len();
-        rows.push(values.to_vec());
-
-        // Update index: add this row's index to the key's index list
-        let mut index = self.index.write().unwrap();
-        index.entry(key).or_insert_with(Vec::new).push(new_index);
+        let mut state = self.state.write().unwrap();
+        self.upsert(&mut state, values)
+    }
+
+    fn replace_rows(
+        &self,
+        rows: &[Vec<AttributeValue>],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        // Build the new contents aside and swap them in at once
+        let mut state = TableState::new(self.schema());
+        for row in rows {
+            self.upsert(&mut state, row)?;
+        }
+        let mut current = self.state.write().unwrap();
+        if let Some(mut changes) = current.changes.take() {
+            changes.push(RowChange::Clear);
+            changes.extend(
+                state
+                    .rows
+                    .keys()
+                    .map(|&id| RowChange::Insert(state.timed_row(id))),
+            );
+            state.changes = Some(changes);
+        }
+        *current = state;
         Ok(())
     }
 
     fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
-        Ok(self.rows.read().unwrap().clone())
+        Ok(InMemoryTable::all_rows(self))
     }
 
     fn update(
@@ -338,176 +1023,229 @@ impl Table for InMemoryTable {
         condition: &dyn CompiledCondition,
         update_set: &dyn CompiledUpdateSet,
     ) -> Result<bool, crate::core::exception::EventFluxError> {
-        let cond = match condition
+        Ok(!self.update_rows(condition, update_set)?.is_empty())
+    }
+
+    fn update_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<
+        Vec<(Vec<AttributeValue>, Vec<AttributeValue>)>,
+        crate::core::exception::EventFluxError,
+    > {
+        let schema = self.schema();
+        let mut state = self.state.write().unwrap();
+        let ids = state.matching_ids(schema, condition);
+
+        let updates: Vec<(RowId, Vec<AttributeValue>)> = if let Some(us) = update_set
             .as_any()
-            .downcast_ref::<InMemoryCompiledCondition>()
-        {
-            Some(c) => c,
-            None => return Ok(false),
-        };
-        let us = match update_set
+            .downcast_ref::<InMemoryCompiledUpdateSet>(
+        ) {
+            ids.into_iter().map(|id| (id, us.values.clone())).collect()
+        } else if let Some(us) = update_set
             .as_any()
-            .downcast_ref::<InMemoryCompiledUpdateSet>()
+            .downcast_ref::<ColumnCompiledUpdateSet>()
         {
-            Some(u) => u,
-            None => return Ok(false),
-        };
-
-        let old_key = Self::row_to_key(&cond.values);
-        let new_key = Self::row_to_key(&us.values);
-
-        // Use index to find matching rows (O(1) instead of O(n))
-        let mut index = self.index.write().unwrap();
-        let mut rows = self.rows.write().unwrap();
-
-        let indices_to_update = if let Some(indices) = index.get(&old_key) {
-            indices.clone()
+            ids.into_iter()
+                .map(|id| {
+                    let mut row = state.rows[&id].clone();
+                    for (column, value) in &us.assignments {
+                        row[*column] = value.clone();
+                    }
+                    (id, row)
+                })
+                .collect()
         } else {
-            return Ok(false);
+            return Ok(Vec::new());
         };
 
-        if indices_to_update.is_empty() {
-            return Ok(false);
-        }
-
-        // Update rows and maintain index
-        for &idx in &indices_to_update {
-            if let Some(row) = rows.get_mut(idx) {
-                *row = us.values.clone();
+        state.check_primary_key_conflicts(schema, &updates)?;
+        let mut changes = Vec::with_capacity(updates.len());
+        for (id, row) in updates {
+            let old_timed = state.changes.is_some().then(|| state.timed_row(id));
+            if let Some(old) = state.remove_row(schema, id) {
+                changes.push((old, row.clone()));
+            }
+            state.add_row(schema, id, row);
+            self.touch_written(&mut state, id);
+            if let Some(old) = old_timed {
+                let new = state.timed_row(id);
+                state.record(RowChange::Update { old, new });
             }
         }
-
-        // Update index: remove old key entries, add new key entries
-        index.remove(&old_key);
-        index
-            .entry(new_key)
-            .or_insert_with(Vec::new)
-            .extend(indices_to_update);
-
-        Ok(true)
+        Ok(changes)
     }
 
     fn delete(
         &self,
         condition: &dyn CompiledCondition,
     ) -> Result<bool, crate::core::exception::EventFluxError> {
-        let cond = match condition
-            .as_any()
-            .downcast_ref::<InMemoryCompiledCondition>()
-        {
-            Some(c) => c,
-            None => return Ok(false),
-        };
-
-        let key = Self::row_to_key(&cond.values);
-        let mut index = self.index.write().unwrap();
-        let mut rows = self.rows.write().unwrap();
-
-        // Check if any rows match (O(1) index lookup)
-        if !index.contains_key(&key) {
-            return Ok(false);
-        }
-
-        let orig_len = rows.len();
-        rows.retain(|row| row.as_slice() != cond.values.as_slice());
-
-        if orig_len == rows.len() {
-            return Ok(false);
-        }
-
-        // Rebuild index since row indices have shifted after deletion
-        // This is O(n) but delete is less frequent than reads/finds
-        index.clear();
-        for (idx, row) in rows.iter().enumerate() {
-            let row_key = Self::row_to_key(row);
-            index.entry(row_key).or_insert_with(Vec::new).push(idx);
-        }
+        Ok(!self.delete_rows(condition)?.is_empty())
+    }
 
-        Ok(true)
+    fn delete_rows(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
+        let schema = self.schema();
+        let mut state = self.state.write().unwrap();
+        let ids = state.matching_ids(schema, condition);
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| state.delete_row(schema, id))
+            .collect())
     }
 
     fn find(
         &self,
         condition: &dyn CompiledCondition,
     ) -> Result<Option<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
-        let cond = condition
+        if condition
             .as_any()
             .downcast_ref::<InMemoryCompiledCondition>()
-            .ok_or_else(|| {
-                crate::core::exception::EventFluxError::Other("Invalid condition type".to_string())
-            })?;
-
-        // O(1) index lookup instead of O(n) linear scan
-        let key = Self::row_to_key(&cond.values);
-        let index = self.index.read().unwrap();
-
-        if let Some(indices) = index.get(&key) {
-            if let Some(&first_idx) = indices.first() {
-                let rows = self.rows.read().unwrap();
-                return Ok(rows.get(first_idx).cloned());
-            }
+            .is_none()
+            && condition
+                .as_any()
+                .downcast_ref::<IndexedCompiledCondition>()
+                .is_none()
+        {
+            return Err(crate::core::exception::EventFluxError::Other(
+                "Invalid condition type".to_string(),
+            ));
         }
-        Ok(None)
+        let (id, row) = {
+            let state = self.state.read().unwrap();
+            match state.matching_ids(self.schema(), condition).first() {
+                Some(&id) => (id, state.rows[&id].clone()),
+                None => return Ok(None),
+            }
+        };
+        self.touch_accessed(&[id]);
+        Ok(Some(row))
     }
 
     fn contains(
         &self,
         condition: &dyn CompiledCondition,
     ) -> Result<bool, crate::core::exception::EventFluxError> {
-        let cond = match condition
-            .as_any()
-            .downcast_ref::<InMemoryCompiledCondition>()
-        {
-            Some(c) => c,
-            None => return Ok(false),
-        };
-
-        // O(1) index lookup instead of O(n) linear scan
-        let key = Self::row_to_key(&cond.values);
-        let index = self.index.read().unwrap();
-        Ok(index.contains_key(&key))
+        let ids = self
+            .state
+            .read()
+            .unwrap()
+            .matching_ids(self.schema(), condition);
+        self.touch_accessed(&ids);
+        Ok(!ids.is_empty())
     }
 
     fn find_rows_for_join(
         &self,
         stream_event: &StreamEvent,
-        _compiled_condition: Option<&dyn CompiledCondition>,
+        compiled_condition: Option<&dyn CompiledCondition>,
         condition_executor: Option<&dyn ExpressionExecutor>,
     ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
-        let rows = self.rows.read().unwrap();
+        let state = self.state.read().unwrap();
+        let stream_data = &stream_event.before_window_data;
+        let lookup = compiled_condition
+            .and_then(|c| c.as_any().downcast_ref::<IndexedCompiledCondition>())
+            .map(|c| &c.lookup);
+        let candidates: Box<dyn Iterator<Item = (RowId, &Vec<AttributeValue>)> + '_> =
+            match (self.schema(), lookup) {
+                (Some(schema), Some(lookup)) => Box::new(
+                    state
+                        .candidate_ids(schema, lookup, Some(stream_data))
+                        .into_iter()
+                        .map(|id| (id, &state.rows[&id])),
+                ),
+                _ => Box::new(state.rows.iter().map(|(id, row)| (*id, row))),
+            };
+
+        let mut matched_ids = Vec::new();
         let mut matched = Vec::new();
-        let stream_attr_count = stream_event.before_window_data.len();
-        for row in rows.iter() {
-            if let Some(exec) = condition_executor {
-                let mut joined =
-                    StreamEvent::new(stream_event.timestamp, stream_attr_count + row.len(), 0, 0);
-                for i in 0..stream_attr_count {
-                    joined.before_window_data[i] = stream_event.before_window_data[i].clone();
-                }
-                for j in 0..row.len() {
-                    joined.before_window_data[stream_attr_count + j] = row[j].clone();
+        match condition_executor {
+            Some(exec) => {
+                // One joined event is reused for every candidate: stream
+                // attributes first, then the table row
+                let stream_attr_count = stream_data.len();
+                let mut joined = StreamEvent::new(stream_event.timestamp, 0, 0, 0);
+                joined.before_window_data.extend_from_slice(stream_data);
+                for (id, row) in candidates {
+                    joined.before_window_data.truncate(stream_attr_count);
+                    joined.before_window_data.extend_from_slice(row);
+                    if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+                        matched_ids.push(id);
+                        matched.push(row.clone());
+                    }
                 }
-                if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+            }
+            None => {
+                for (id, row) in candidates {
+                    matched_ids.push(id);
                     matched.push(row.clone());
                 }
-            } else {
-                matched.push(row.clone());
             }
         }
+        drop(state);
+        self.touch_accessed(&matched_ids);
         Ok(matched)
     }
 
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        let schema = self.schema()?;
+        let lookup = KeyLookup::plan(schema, &cond, &|v| {
+            Self::resolve_join_variable(schema, v, stream_id, stream_def)
+        });
+        if matches!(lookup, KeyLookup::Scan) {
+            return None;
+        }
+        // The join's condition executor re-checks the full condition
+        Some(Box::new(IndexedCompiledCondition {
+            lookup,
+            predicate: None,
+        }))
+    }
+
     fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
         if let Expression::Constant(c) = cond {
-            Box::new(InMemoryCompiledCondition {
+            return Box::new(InMemoryCompiledCondition {
                 values: vec![constant_to_av(&c)],
-            })
-        } else {
-            Box::new(InMemoryCompiledCondition { values: Vec::new() })
+            });
+        }
+        if let Some(schema) = self.schema() {
+            if let Some(predicate) = RowPredicate::from_expression(&cond, schema) {
+                let lookup = KeyLookup::plan(schema, &cond, &|v| {
+                    schema.column(&v.attribute_name).map(Operand::Column)
+                });
+                return Box::new(IndexedCompiledCondition {
+                    lookup,
+                    predicate: Some(predicate),
+                });
+            }
         }
+        Box::new(InMemoryCompiledCondition { values: Vec::new() })
     }
 
     fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        if let Some(schema) = self.schema() {
+            let assignments: Option<Vec<_>> = us
+                .set_attributes
+                .iter()
+                .map(|sa| match &sa.value_to_set {
+                    Expression::Constant(c) => schema
+                        .column(&sa.table_column.attribute_name)
+                        .map(|column| (column, constant_to_av(c))),
+                    _ => None,
+                })
+                .collect();
+            if let Some(assignments) = assignments {
+                return Box::new(ColumnCompiledUpdateSet { assignments });
+            }
+        }
         let mut vals = Vec::new();
         for sa in us.set_attributes.iter() {
             if let Expression::Constant(c) = &sa.value_to_set {
@@ -518,19 +1256,155 @@ impl Table for InMemoryTable {
     }
 
     fn clone_table(&self) -> Result<Box<dyn Table>, crate::core::exception::EventFluxError> {
-        let rows = self.rows.read().unwrap().clone();
+        let mut state = self.state.read().unwrap().clone();
+        state.changes = None;
+        state.accessed.clear();
+        Ok(Box::new(InMemoryTable {
+            schema: self.schema.clone(),
+            retention: self.retention.clone(),
+            state: RwLock::new(state),
+        }))
+    }
+
+    fn retention(&self) -> Option<&RetentionPolicy> {
+        self.retention.as_ref()
+    }
+
+    fn evict_expired(
+        &self,
+        now: i64,
+    ) -> Result<Vec<Vec<AttributeValue>>, crate::core::exception::EventFluxError> {
+        let policy = match &self.retention {
+            Some(policy) => policy,
+            None => return Ok(Vec::new()),
+        };
+        let schema = self.schema();
+        let mut state = self.state.write().unwrap();
+        let due = state.clock.due(policy, now);
+        Ok(due
+            .into_iter()
+            .filter_map(|id| state.delete_row(schema, id))
+            .collect())
+    }
+
+    fn timed_rows(
+        &self,
+    ) -> Result<Vec<(Vec<AttributeValue>, i64)>, crate::core::exception::EventFluxError> {
+        let state = self.state.read().unwrap();
+        let now = now_millis();
+        Ok(state
+            .rows
+            .iter()
+            .map(|(id, row)| (row.clone(), state.clock.touched_at(id).unwrap_or(now)))
+            .collect())
+    }
+
+    fn restore_timed_rows(
+        &self,
+        rows: Vec<(Vec<AttributeValue>, i64)>,
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let schema = self.schema();
+        let mut state = TableState::new(schema);
+        for (row, touched) in rows {
+            let id = state.next_id;
+            state.next_id += 1;
+            state.add_row(schema, id, row);
+            self.touch_restored(&mut state, id, touched);
+        }
+        let mut current = self.state.write().unwrap();
+        // Changes recorded before the restore no longer apply
+        state.changes = current.changes.as_ref().map(|_| Vec::new());
+        *current = state;
+        Ok(())
+    }
 
-        // Rebuild index for cloned table
-        let mut index = HashMap::new();
-        for (idx, row) in rows.iter().enumerate() {
-            let key = Self::row_to_key(row);
-            index.entry(key).or_insert_with(Vec::new).push(idx);
+    fn record_changes(&self) -> bool {
+        self.state
+            .write()
+            .unwrap()
+            .changes
+            .get_or_insert_with(Vec::new);
+        true
+    }
+
+    fn pending_changes(
+        &self,
+    ) -> Result<Vec<StateOperation>, crate::core::exception::EventFluxError> {
+        match &self.state.read().unwrap().changes {
+            Some(changes) => changes.iter().map(RowChange::to_operation).collect(),
+            None => Err(crate::core::exception::EventFluxError::Other(
+                "Table is not recording changes".to_string(),
+            )),
         }
+    }
 
-        Ok(Box::new(InMemoryTable {
-            rows: RwLock::new(rows),
-            index: RwLock::new(index),
-        }))
+    fn commit_changes(&self, count: usize) {
+        let mut guard = self.state.write().unwrap();
+        let state = &mut *guard;
+        if let Some(changes) = &mut state.changes {
+            let count = count.min(changes.len());
+            changes.drain(..count);
+            // Committed access-time updates can no longer be amended
+            state.accessed.retain(|_, position| *position >= count);
+            for position in state.accessed.values_mut() {
+                *position -= count;
+            }
+        }
+    }
+
+    fn apply_changes(
+        &self,
+        changes: &[StateOperation],
+    ) -> Result<(), crate::core::exception::EventFluxError> {
+        let decode = |data: &[u8]| -> Result<TimedRow, crate::core::exception::EventFluxError> {
+            crate::core::util::from_bytes(data).map_err(|e| {
+                crate::core::exception::EventFluxError::Other(format!(
+                    "Failed to deserialize row change: {e}"
+                ))
+            })
+        };
+        let find = |state: &TableState, old_value: &[u8]| {
+            state.replayed_id(&decode(old_value)?).ok_or_else(|| {
+                crate::core::exception::EventFluxError::Other(
+                    "Replayed change refers to a row that is not in the table".to_string(),
+                )
+            })
+        };
+        let schema = self.schema();
+        let mut state = self.state.write().unwrap();
+        // Replayed changes are already checkpointed, so they aren't recorded
+        for change in changes {
+            match change {
+                StateOperation::Insert { value, .. } => {
+                    let (row, touched) = decode(value)?;
+                    let id = state.next_id;
+                    state.next_id += 1;
+                    state.add_row(schema, id, row);
+                    self.touch_restored(&mut state, id, touched);
+                }
+                StateOperation::Update {
+                    old_value,
+                    new_value,
+                    ..
+                } => {
+                    let id = find(&state, old_value)?;
+                    let (row, touched) = decode(new_value)?;
+                    state.remove_row(schema, id);
+                    state.add_row(schema, id, row);
+                    self.touch_restored(&mut state, id, touched);
+                }
+                StateOperation::Delete { old_value, .. } => {
+                    let id = find(&state, old_value)?;
+                    state.remove_row(schema, id);
+                }
+                StateOperation::Clear => {
+                    let recorded = state.changes.take();
+                    *state = TableState::new(schema);
+                    state.changes = recorded;
+                }
+            }
+        }
+        Ok(())
     }
 }
 
@@ -544,10 +1418,23 @@ impl TableFactory for InMemoryTableFactory {
     fn create(
         &self,
         _name: String,
-        _properties: HashMap<String, String>,
+        properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        let retention = RetentionPolicy::from_properties(&properties)?;
+        Ok(Arc::new(InMemoryTable::new().with_retention(retention)))
+    }
+
+    fn create_for_definition(
+        &self,
+        definition: &TableDefinition,
+        properties: HashMap<String, String>,
         _ctx: Arc<EventFluxContext>,
     ) -> Result<Arc<dyn Table>, String> {
-        Ok(Arc::new(InMemoryTable::new()))
+        let retention = RetentionPolicy::from_properties(&properties)?;
+        Ok(Arc::new(
+            InMemoryTable::with_definition(definition)?.with_retention(retention),
+        ))
     }
 
     fn clone_box(&self) -> Box<dyn TableFactory> {
@@ -0,0 +1,854 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::core::config::eventflux_context::EventFluxContext;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::table::blocking::block_on;
+use crate::core::table::jdbc_table::{
+    expression_to_sql, expression_to_sql_join, is_translatable, JdbcCompiledCondition,
+    JdbcCompiledUpdateSet, JdbcJoinCompiledCondition, JoinParamSource,
+};
+use crate::core::table::{
+    constant_to_av, CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition,
+    InMemoryCompiledUpdateSet, SimpleCompiledCondition, SimpleCompiledUpdateSet, Table,
+};
+use crate::query_api::definition::attribute::Type as AttributeType;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::execution::query::output::stream::UpdateSet;
+use crate::query_api::expression::Expression;
+use bytes::BytesMut;
+use deadpool_postgres::{Manager, ManagerConfig, Pool, PoolError, RecyclingMethod, Runtime};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_postgres::types::{to_sql_checked, IsNull, ToSql, Type};
+use tokio_postgres::{NoTls, Row};
+
+const DEFAULT_POOL_SIZE: usize = 8;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn db_error(e: tokio_postgres::Error) -> EventFluxError {
+    EventFluxError::DatabaseRuntime {
+        message: format!("PostgreSQL error: {}", e),
+        source: Some(Box::new(e)),
+    }
+}
+
+fn pool_error(e: PoolError) -> EventFluxError {
+    EventFluxError::ConnectionUnavailable {
+        message: format!("No PostgreSQL connection available: {}", e),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// Replace `?` placeholders with PostgreSQL's numbered `$1, $2, ...`.
+/// Constants are always bound as parameters, so `?` never occurs in literals.
+fn numbered(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len() + 8);
+    let mut n = 0;
+    for ch in sql.chars() {
+        if ch == '?' {
+            n += 1;
+            out.push_str(&format!("${}", n));
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn sql_type(attr_type: &AttributeType) -> &'static str {
+    match attr_type {
+        AttributeType::STRING => "TEXT",
+        AttributeType::INT => "INTEGER",
+        AttributeType::LONG => "BIGINT",
+        AttributeType::FLOAT => "REAL",
+        AttributeType::DOUBLE => "DOUBLE PRECISION",
+        AttributeType::BOOL => "BOOLEAN",
+        AttributeType::OBJECT => "BYTEA",
+    }
+}
+
+/// Column types (as reported by `information_schema`) rows can be read from
+fn is_supported_type(data_type: &str) -> bool {
+    matches!(
+        data_type,
+        "smallint"
+            | "integer"
+            | "bigint"
+            | "real"
+            | "double precision"
+            | "boolean"
+            | "text"
+            | "character varying"
+            | "character"
+            | "name"
+            | "bytea"
+    )
+}
+
+/// Query parameter converted to whatever type PostgreSQL inferred for its
+/// placeholder, so `?` compared to a `BIGINT` column accepts an `INT` value
+#[derive(Debug)]
+struct PgParam(AttributeValue);
+
+impl PgParam {
+    fn integer(&self) -> Option<i64> {
+        match &self.0 {
+            AttributeValue::Int(v) => Some(*v as i64),
+            AttributeValue::Long(v) => Some(*v),
+            AttributeValue::Float(v) if v.fract() == 0.0 => Some(*v as i64),
+            AttributeValue::Double(v) if v.fract() == 0.0 => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    fn float(&self) -> Option<f64> {
+        match &self.0 {
+            AttributeValue::Int(v) => Some(*v as f64),
+            AttributeValue::Long(v) => Some(*v as f64),
+            AttributeValue::Float(v) => Some(*v as f64),
+            AttributeValue::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn mismatch(&self, ty: &Type) -> Box<dyn std::error::Error + Sync + Send> {
+        format!("cannot bind {:?} to a PostgreSQL {} parameter", self.0, ty).into()
+    }
+}
+
+impl ToSql for PgParam {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        if matches!(self.0, AttributeValue::Null | AttributeValue::Object(_)) {
+            return Ok(IsNull::Yes);
+        }
+        match *ty {
+            Type::BOOL => match &self.0 {
+                AttributeValue::Bool(b) => b.to_sql(ty, out),
+                _ => Err(self.mismatch(ty)),
+            },
+            Type::INT2 => {
+                let v = self.integer().ok_or_else(|| self.mismatch(ty))?;
+                i16::try_from(v)?.to_sql(ty, out)
+            }
+            Type::INT4 => {
+                let v = self.integer().ok_or_else(|| self.mismatch(ty))?;
+                i32::try_from(v)?.to_sql(ty, out)
+            }
+            Type::INT8 => self
+                .integer()
+                .ok_or_else(|| self.mismatch(ty))?
+                .to_sql(ty, out),
+            Type::FLOAT4 => (self.float().ok_or_else(|| self.mismatch(ty))? as f32).to_sql(ty, out),
+            Type::FLOAT8 => self
+                .float()
+                .ok_or_else(|| self.mismatch(ty))?
+                .to_sql(ty, out),
+            Type::BYTEA => match &self.0 {
+                AttributeValue::Bytes(b) => b.to_sql(ty, out),
+                _ => Err(self.mismatch(ty)),
+            },
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => {
+                self.0.to_string().to_sql(ty, out)
+            }
+            _ => Err(self.mismatch(ty)),
+        }
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+/// Convert a numeric value read from PostgreSQL to the declared column type
+fn coerce(value: AttributeValue, attr_type: &AttributeType) -> AttributeValue {
+    match (value, attr_type) {
+        (AttributeValue::Int(v), AttributeType::LONG) => AttributeValue::Long(v as i64),
+        (AttributeValue::Int(v), AttributeType::FLOAT) => AttributeValue::Float(v as f32),
+        (AttributeValue::Int(v), AttributeType::DOUBLE) => AttributeValue::Double(v as f64),
+        (AttributeValue::Long(v), AttributeType::INT) => AttributeValue::Int(v as i32),
+        (AttributeValue::Long(v), AttributeType::FLOAT) => AttributeValue::Float(v as f32),
+        (AttributeValue::Long(v), AttributeType::DOUBLE) => AttributeValue::Double(v as f64),
+        (AttributeValue::Float(v), AttributeType::DOUBLE) => AttributeValue::Double(v as f64),
+        (AttributeValue::Double(v), AttributeType::FLOAT) => AttributeValue::Float(v as f32),
+        (value, _) => value,
+    }
+}
+
+fn decode_row(
+    row: &Row,
+    columns: &[(String, AttributeType)],
+) -> Result<Vec<AttributeValue>, EventFluxError> {
+    let mut values = Vec::with_capacity(columns.len());
+    for (i, (_, attr_type)) in columns.iter().enumerate() {
+        let value = match *row.columns()[i].type_() {
+            Type::BOOL => row
+                .try_get::<_, Option<bool>>(i)
+                .map(|v| v.map(AttributeValue::Bool)),
+            Type::INT2 => row
+                .try_get::<_, Option<i16>>(i)
+                .map(|v| v.map(|v| AttributeValue::Int(v as i32))),
+            Type::INT4 => row
+                .try_get::<_, Option<i32>>(i)
+                .map(|v| v.map(AttributeValue::Int)),
+            Type::INT8 => row
+                .try_get::<_, Option<i64>>(i)
+                .map(|v| v.map(AttributeValue::Long)),
+            Type::FLOAT4 => row
+                .try_get::<_, Option<f32>>(i)
+                .map(|v| v.map(AttributeValue::Float)),
+            Type::FLOAT8 => row
+                .try_get::<_, Option<f64>>(i)
+                .map(|v| v.map(AttributeValue::Double)),
+            Type::BYTEA => row
+                .try_get::<_, Option<Vec<u8>>>(i)
+                .map(|v| v.map(AttributeValue::Bytes)),
+            _ => row
+                .try_get::<_, Option<String>>(i)
+                .map(|v| v.map(AttributeValue::String)),
+        }
+        .map_err(db_error)?;
+        values.push(value.map_or(AttributeValue::Null, |v| coerce(v, attr_type)));
+    }
+    Ok(values)
+}
+
+/// Table backed by a PostgreSQL table, accessed through a connection pool
+/// (`WITH ('extension' = 'postgres', 'url' = 'host=localhost user=app')`).
+///
+/// Conditions, update sets and join conditions are translated to
+/// parameterized SQL; conditions SQL can't express fall back to scanning.
+/// Identifiers are left unquoted, so PostgreSQL folds them to lower case.
+#[derive(Debug)]
+pub struct PostgresTable {
+    table_name: String,
+    columns: Arc<Vec<(String, AttributeType)>>,
+    primary_key: Vec<usize>,
+    select_sql: String,
+    insert_sql: String,
+    pool: Pool,
+}
+
+impl PostgresTable {
+    /// Create a pool of up to `pool_size` connections for `url` (a libpq
+    /// style `key=value` string or a `postgres://` URL). No connection is made
+    /// until the table is used or validated.
+    pub fn connect(
+        definition: &TableDefinition,
+        url: &str,
+        table_name: String,
+        pool_size: usize,
+    ) -> Result<Self, String> {
+        let columns: Vec<(String, AttributeType)> = definition
+            .abstract_definition
+            .attribute_list
+            .iter()
+            .map(|a| (a.get_name().clone(), *a.get_type()))
+            .collect();
+        let primary_key = definition
+            .primary_key
+            .iter()
+            .map(|c| {
+                columns
+                    .iter()
+                    .position(|(name, _)| name == c)
+                    .ok_or_else(|| format!("Unknown column '{}' in table '{}'", c, table_name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut pg_config: tokio_postgres::Config = url
+            .parse()
+            .map_err(|e| format!("Invalid PostgreSQL url for table '{}': {}", table_name, e))?;
+        if pg_config.get_connect_timeout().is_none() {
+            pg_config.connect_timeout(DEFAULT_TIMEOUT);
+        }
+        let manager = Manager::from_config(
+            pg_config,
+            NoTls,
+            ManagerConfig {
+                recycling_method: RecyclingMethod::Fast,
+            },
+        );
+        let pool = Pool::builder(manager)
+            .max_size(pool_size)
+            .wait_timeout(Some(DEFAULT_TIMEOUT))
+            .create_timeout(Some(DEFAULT_TIMEOUT))
+            .runtime(Runtime::Tokio1)
+            .build()
+            .map_err(|e| format!("Cannot create PostgreSQL pool: {}", e))?;
+
+        let column_list = columns
+            .iter()
+            .map(|(c, _)| c.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let select_sql = format!("SELECT {} FROM {}", column_list, table_name);
+        let mut insert_sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table_name,
+            column_list,
+            vec!["?"; columns.len()].join(", ")
+        );
+        // Inserting an existing primary key replaces the row, like InMemoryTable
+        if !primary_key.is_empty() {
+            let key = primary_key
+                .iter()
+                .map(|&i| columns[i].0.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let assignments = columns
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !primary_key.contains(i))
+                .map(|(_, (c, _))| format!("{c} = EXCLUDED.{c}"))
+                .collect::<Vec<_>>();
+            if assignments.is_empty() {
+                insert_sql.push_str(&format!(" ON CONFLICT ({}) DO NOTHING", key));
+            } else {
+                insert_sql.push_str(&format!(
+                    " ON CONFLICT ({}) DO UPDATE SET {}",
+                    key,
+                    assignments.join(", ")
+                ));
+            }
+        }
+
+        Ok(Self {
+            table_name,
+            columns: Arc::new(columns),
+            primary_key,
+            select_sql,
+            insert_sql: numbered(&insert_sql),
+            pool,
+        })
+    }
+
+    /// Create the PostgreSQL table (with its primary key and indexes) if it
+    /// doesn't exist yet
+    pub fn create_if_missing(&self, definition: &TableDefinition) -> Result<(), EventFluxError> {
+        let mut column_defs: Vec<String> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, ty))| {
+                let not_null = if self.primary_key.contains(&i) {
+                    " NOT NULL"
+                } else {
+                    ""
+                };
+                format!("{} {}{}", name, sql_type(ty), not_null)
+            })
+            .collect();
+        if !self.primary_key.is_empty() {
+            column_defs.push(format!(
+                "PRIMARY KEY ({})",
+                self.primary_key
+                    .iter()
+                    .map(|&i| self.columns[i].0.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let mut ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {} ({});",
+            self.table_name,
+            column_defs.join(", ")
+        );
+        for index in &definition.indexes {
+            ddl.push_str(&format!(
+                "CREATE INDEX IF NOT EXISTS {}_{} ON {} ({});",
+                self.table_name.replace('.', "_"),
+                index.name,
+                self.table_name,
+                index.columns.join(", ")
+            ));
+        }
+        let pool = self.pool.clone();
+        block_on(async move {
+            let client = pool.get().await.map_err(pool_error)?;
+            client.batch_execute(&ddl).await.map_err(db_error)
+        })
+    }
+
+    /// Run a query through the pooled connection's statement cache and
+    /// decode every row
+    fn query(
+        &self,
+        sql: &str,
+        params: Vec<AttributeValue>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let pool = self.pool.clone();
+        let columns = Arc::clone(&self.columns);
+        let sql = numbered(sql);
+        block_on(async move {
+            let client = pool.get().await.map_err(pool_error)?;
+            let stmt = client.prepare_cached(&sql).await.map_err(db_error)?;
+            let params: Vec<PgParam> = params.into_iter().map(PgParam).collect();
+            let refs: Vec<&(dyn ToSql + Sync)> =
+                params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+            let rows = client.query(&stmt, &refs).await.map_err(db_error)?;
+            rows.iter().map(|row| decode_row(row, &columns)).collect()
+        })
+    }
+
+    fn execute(&self, sql: &str, params: Vec<AttributeValue>) -> Result<u64, EventFluxError> {
+        let pool = self.pool.clone();
+        let sql = numbered(sql);
+        let table_name = self.table_name.clone();
+        block_on(async move {
+            let client = pool.get().await.map_err(pool_error)?;
+            let stmt = client.prepare_cached(&sql).await.map_err(db_error)?;
+            let params: Vec<PgParam> = params.into_iter().map(PgParam).collect();
+            let refs: Vec<&(dyn ToSql + Sync)> =
+                params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+            client.execute(&stmt, &refs).await.map_err(|e| {
+                log::error!(
+                    "PostgreSQL statement failed on table '{}': {}",
+                    table_name,
+                    e
+                );
+                db_error(e)
+            })
+        })
+    }
+
+    /// Columns matched by a value list condition: the primary key for a
+    /// key-length list on a keyed table (e.g. `x IN T`), otherwise the
+    /// leading columns
+    fn value_columns(&self, len: usize) -> Vec<usize> {
+        if !self.primary_key.is_empty()
+            && len == self.primary_key.len()
+            && len != self.columns.len()
+        {
+            self.primary_key.clone()
+        } else {
+            (0..len.min(self.columns.len())).collect()
+        }
+    }
+
+    /// `WHERE` clause (with `?` placeholders) and parameters matching
+    /// `condition`
+    fn where_clause(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<(String, Vec<AttributeValue>), EventFluxError> {
+        if let Some(cond) = condition.as_any().downcast_ref::<JdbcCompiledCondition>() {
+            return Ok((cond.where_clause.clone(), cond.params.clone()));
+        }
+        if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+        {
+            let clause = self
+                .value_columns(cond.values.len())
+                .iter()
+                .map(|&i| format!("{} IS NOT DISTINCT FROM ?", self.columns[i].0))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            return Ok((
+                if clause.is_empty() {
+                    "TRUE".to_string()
+                } else {
+                    clause
+                },
+                cond.values.clone(),
+            ));
+        }
+        Err(EventFluxError::Other(format!(
+            "Unsupported condition for PostgreSQL table '{}'",
+            self.table_name
+        )))
+    }
+}
+
+impl Table for PostgresTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        self.execute(&self.insert_sql, values.to_vec())?;
+        Ok(())
+    }
+
+    fn insert_batch(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        let pool = self.pool.clone();
+        let sql = numbered(&self.insert_sql);
+        let rows = rows.to_vec();
+        block_on(async move {
+            let mut client = pool.get().await.map_err(pool_error)?;
+            let tx = client.transaction().await.map_err(db_error)?;
+            let stmt = tx.prepare_cached(&sql).await.map_err(db_error)?;
+            for row in rows {
+                let params: Vec<PgParam> = row.into_iter().map(PgParam).collect();
+                let refs: Vec<&(dyn ToSql + Sync)> =
+                    params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+                tx.execute(&stmt, &refs).await.map_err(db_error)?;
+            }
+            tx.commit().await.map_err(db_error)
+        })
+    }
+
+    fn replace_rows(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        let pool = self.pool.clone();
+        let delete_sql = format!("DELETE FROM {}", self.table_name);
+        let sql = numbered(&self.insert_sql);
+        let rows = rows.to_vec();
+        block_on(async move {
+            let mut client = pool.get().await.map_err(pool_error)?;
+            let tx = client.transaction().await.map_err(db_error)?;
+            tx.execute(delete_sql.as_str(), &[])
+                .await
+                .map_err(db_error)?;
+            let stmt = tx.prepare_cached(&sql).await.map_err(db_error)?;
+            for row in rows {
+                let params: Vec<PgParam> = row.into_iter().map(PgParam).collect();
+                let refs: Vec<&(dyn ToSql + Sync)> =
+                    params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+                tx.execute(&stmt, &refs).await.map_err(db_error)?;
+            }
+            tx.commit().await.map_err(db_error)
+        })
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.query(&self.select_sql, Vec::new())
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        let (assignments, mut params) =
+            if let Some(us) = update_set.as_any().downcast_ref::<JdbcCompiledUpdateSet>() {
+                (us.assignments.clone(), us.params.clone())
+            } else if let Some(us) = update_set
+                .as_any()
+                .downcast_ref::<InMemoryCompiledUpdateSet>()
+            {
+                (
+                    self.columns
+                        .iter()
+                        .take(us.values.len())
+                        .map(|(c, _)| format!("{} = ?", c))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    us.values.clone(),
+                )
+            } else {
+                return Err(EventFluxError::Other(format!(
+                    "Unsupported update set for PostgreSQL table '{}'",
+                    self.table_name
+                )));
+            };
+        if assignments.is_empty() {
+            return Ok(false);
+        }
+        let (where_clause, where_params) = self.where_clause(condition)?;
+        params.extend(where_params);
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            self.table_name, assignments, where_clause
+        );
+        Ok(self.execute(&sql, params)? > 0)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        let (where_clause, params) = self.where_clause(condition)?;
+        let sql = format!("DELETE FROM {} WHERE {}", self.table_name, where_clause);
+        Ok(self.execute(&sql, params)? > 0)
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        let (where_clause, params) = self.where_clause(condition)?;
+        let sql = format!("{} WHERE {} LIMIT 1", self.select_sql, where_clause);
+        Ok(self.query(&sql, params)?.into_iter().next())
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Ok(self.find(condition)?.is_some())
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        if let Some(cond) =
+            compiled_condition.and_then(|c| c.as_any().downcast_ref::<JdbcJoinCompiledCondition>())
+        {
+            let params: Vec<AttributeValue> = cond
+                .params
+                .iter()
+                .map(|p| match p {
+                    JoinParamSource::Constant(av) => av.clone(),
+                    JoinParamSource::StreamAttr(idx) => {
+                        stream_event.before_window_data[*idx].clone()
+                    }
+                })
+                .collect();
+            let sql = format!("{} WHERE {}", self.select_sql, cond.where_clause);
+            return self.query(&sql, params);
+        }
+
+        // Conditions SQL can't express are evaluated against every row
+        let rows = self.all_rows()?;
+        let exec = match condition_executor {
+            Some(exec) => exec,
+            None => return Ok(rows),
+        };
+        let stream_attr_count = stream_event.before_window_data.len();
+        let mut joined = StreamEvent::new(stream_event.timestamp, 0, 0, 0);
+        joined
+            .before_window_data
+            .extend_from_slice(&stream_event.before_window_data);
+        let mut matched = Vec::new();
+        for row in rows {
+            joined.before_window_data.truncate(stream_attr_count);
+            joined.before_window_data.extend_from_slice(&row);
+            if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+                matched.push(row);
+            }
+        }
+        Ok(matched)
+    }
+
+    fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
+        if let Expression::Constant(c) = &cond {
+            return Box::new(InMemoryCompiledCondition {
+                values: vec![constant_to_av(c)],
+            });
+        }
+        if !is_translatable(&cond) {
+            return Box::new(SimpleCompiledCondition(cond));
+        }
+        let mut params = Vec::new();
+        let where_clause = expression_to_sql(&cond, &mut params);
+        Box::new(JdbcCompiledCondition {
+            where_clause,
+            params,
+        })
+    }
+
+    fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        if !us
+            .set_attributes
+            .iter()
+            .all(|sa| is_translatable(&sa.value_to_set))
+        {
+            return Box::new(SimpleCompiledUpdateSet(us));
+        }
+        let mut params = Vec::new();
+        let mut assigns = Vec::new();
+        for sa in us.set_attributes.iter() {
+            let expr_sql = expression_to_sql(&sa.value_to_set, &mut params);
+            assigns.push(format!("{} = {}", sa.table_column.attribute_name, expr_sql));
+        }
+        Box::new(JdbcCompiledUpdateSet {
+            assignments: assigns.join(", "),
+            params,
+        })
+    }
+
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        if !is_translatable(&cond) {
+            return None;
+        }
+        let mut params = Vec::new();
+        let where_clause = expression_to_sql_join(&cond, stream_id, stream_def, &mut params);
+        Some(Box::new(JdbcJoinCompiledCondition {
+            where_clause,
+            params,
+        }))
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        // Clones share the pool; the rows live in PostgreSQL
+        Ok(Box::new(PostgresTable {
+            table_name: self.table_name.clone(),
+            columns: Arc::clone(&self.columns),
+            primary_key: self.primary_key.clone(),
+            select_sql: self.select_sql.clone(),
+            insert_sql: self.insert_sql.clone(),
+            pool: self.pool.clone(),
+        }))
+    }
+
+    /// Connect, then check the table exists with every declared column in a
+    /// type that maps to the EventFlux type system
+    fn validate_connectivity(&self) -> Result<(), EventFluxError> {
+        let (schema, name) = match self.table_name.split_once('.') {
+            Some((schema, name)) => (
+                AttributeValue::String(schema.to_lowercase()),
+                name.to_lowercase(),
+            ),
+            None => (AttributeValue::Null, self.table_name.to_lowercase()),
+        };
+        let pool = self.pool.clone();
+        let existing: Vec<(String, String)> = block_on(async move {
+            let client = pool.get().await.map_err(pool_error)?;
+            let rows = client
+                .query(
+                    "SELECT column_name::text, data_type::text FROM information_schema.columns \
+                     WHERE table_schema::text = COALESCE($1::text, current_schema()::text) \
+                     AND table_name::text = $2::text",
+                    &[&PgParam(schema), &PgParam(AttributeValue::String(name))],
+                )
+                .await
+                .map_err(db_error)?;
+            Ok(rows
+                .iter()
+                .map(|row| (row.get::<_, String>(0), row.get::<_, String>(1)))
+                .collect())
+        })
+        .map_err(|e| {
+            EventFluxError::configuration(format!(
+                "PostgreSQL table '{}' is not reachable: {}",
+                self.table_name, e
+            ))
+        })?;
+
+        if existing.is_empty() {
+            return Err(EventFluxError::configuration(format!(
+                "PostgreSQL table '{}' does not exist",
+                self.table_name
+            )));
+        }
+        for (column, _) in self.columns.iter() {
+            match existing
+                .iter()
+                .find(|(name, _)| name == &column.to_lowercase())
+            {
+                None => {
+                    return Err(EventFluxError::configuration(format!(
+                        "PostgreSQL table '{}' has no column '{}'",
+                        self.table_name, column
+                    )))
+                }
+                Some((_, data_type)) if !is_supported_type(data_type) => {
+                    return Err(EventFluxError::configuration(format!(
+                        "Column '{}' of PostgreSQL table '{}' has unsupported type '{}'",
+                        column, self.table_name, data_type
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgresTableFactory;
+
+impl crate::core::extension::TableFactory for PostgresTableFactory {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn create(
+        &self,
+        table_name: String,
+        _properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        Err(format!(
+            "PostgreSQL table '{}' needs its column definitions",
+            table_name
+        ))
+    }
+
+    fn create_for_definition(
+        &self,
+        definition: &TableDefinition,
+        properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        let id = &definition.abstract_definition.id;
+        let url = properties
+            .get("url")
+            .ok_or_else(|| format!("PostgreSQL table '{}' requires a 'url' property", id))?;
+        let table_name = properties
+            .get("table.name")
+            .cloned()
+            .unwrap_or_else(|| id.clone());
+        let pool_size = match properties.get("pool.size") {
+            Some(size) => size
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("'pool.size' must be a positive number, got '{}'", size))?,
+            None => DEFAULT_POOL_SIZE,
+        };
+        let table = PostgresTable::connect(definition, url, table_name, pool_size)?;
+        if properties.get("table.create").map(String::as_str) == Some("true") {
+            table
+                .create_if_missing(definition)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(Arc::new(table))
+    }
+
+    fn clone_box(&self) -> Box<dyn crate::core::extension::TableFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbered_placeholders() {
+        assert_eq!(
+            numbered("a = ? AND (b > ? OR c IS NOT DISTINCT FROM ?)"),
+            "a = $1 AND (b > $2 OR c IS NOT DISTINCT FROM $3)"
+        );
+        assert_eq!(numbered("TRUE"), "TRUE");
+    }
+
+    #[test]
+    fn test_param_follows_placeholder_type() {
+        let mut out = BytesMut::new();
+        PgParam(AttributeValue::Int(7))
+            .to_sql(&Type::INT8, &mut out)
+            .unwrap();
+        assert_eq!(&out[..], &7i64.to_be_bytes());
+
+        let mut out = BytesMut::new();
+        assert!(PgParam(AttributeValue::Double(1.5))
+            .to_sql(&Type::INT4, &mut out)
+            .is_err());
+        assert!(matches!(
+            PgParam(AttributeValue::Null).to_sql(&Type::TEXT, &mut out),
+            Ok(IsNull::Yes)
+        ));
+    }
+
+    #[test]
+    fn test_coerce_to_declared_type() {
+        assert_eq!(
+            coerce(AttributeValue::Int(3), &AttributeType::LONG),
+            AttributeValue::Long(3)
+        );
+        assert_eq!(
+            coerce(AttributeValue::Float(2.5), &AttributeType::DOUBLE),
+            AttributeValue::Double(2.5)
+        );
+    }
+}
@@ -0,0 +1,643 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::core::config::eventflux_context::EventFluxContext;
+use crate::core::distributed::{shared_redis_pool, RedisConfig};
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::table::blocking::block_on;
+use crate::core::table::index::{IndexKey, KeyLookup, Operand, RowPredicate, TableSchema};
+use crate::core::table::{
+    constant_to_av, ColumnCompiledUpdateSet, CompiledCondition, CompiledUpdateSet,
+    InMemoryCompiledCondition, InMemoryCompiledUpdateSet, InMemoryTable, IndexedCompiledCondition,
+    Table,
+};
+use crate::query_api::definition::attribute::Type as AttributeType;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::execution::query::output::stream::UpdateSet;
+use crate::query_api::expression::Expression;
+use deadpool_redis::Pool;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+const DEFAULT_KEY_PREFIX: &str = "eventflux:table:";
+
+/// Keys fetched per `SCAN` round trip
+const SCAN_BATCH: usize = 500;
+
+fn redis_error(e: redis::RedisError) -> EventFluxError {
+    EventFluxError::DatabaseRuntime {
+        message: format!("Redis error: {}", e),
+        source: Some(Box::new(e)),
+    }
+}
+
+fn pool_error(e: deadpool_redis::PoolError) -> EventFluxError {
+    EventFluxError::ConnectionUnavailable {
+        message: format!("No Redis connection available: {}", e),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// Hash field value for `value`; `None` for NULL, which is stored as a
+/// missing field
+fn encode(value: &AttributeValue) -> Option<Vec<u8>> {
+    match value {
+        AttributeValue::Null | AttributeValue::Object(_) => None,
+        AttributeValue::String(s) => Some(s.as_bytes().to_vec()),
+        AttributeValue::Bytes(b) => Some(b.clone()),
+        other => Some(other.to_string().into_bytes()),
+    }
+}
+
+/// Read a hash field back as the column's declared type
+fn decode(bytes: &[u8], attr_type: &AttributeType) -> AttributeValue {
+    let text = || String::from_utf8_lossy(bytes);
+    let parsed = match attr_type {
+        AttributeType::STRING => Some(AttributeValue::String(text().into_owned())),
+        AttributeType::INT => text().parse().ok().map(AttributeValue::Int),
+        AttributeType::LONG => text().parse().ok().map(AttributeValue::Long),
+        AttributeType::FLOAT => text().parse().ok().map(AttributeValue::Float),
+        AttributeType::DOUBLE => text().parse().ok().map(AttributeValue::Double),
+        AttributeType::BOOL => Some(AttributeValue::Bool(matches!(&*text(), "true" | "1"))),
+        AttributeType::OBJECT => Some(AttributeValue::Bytes(bytes.to_vec())),
+    };
+    parsed.unwrap_or(AttributeValue::Null)
+}
+
+/// Key segment for one primary key value. `:` and `\` are escaped so
+/// composite keys can't collide.
+fn key_part(key: &IndexKey) -> String {
+    match key {
+        IndexKey::Null => String::new(),
+        IndexKey::Bool(b) => b.to_string(),
+        IndexKey::Int(i) => i.to_string(),
+        IndexKey::Float(bits) => format!("f{:016x}", bits),
+        IndexKey::String(s) => s.replace('\\', "\\\\").replace(':', "\\:"),
+        IndexKey::Bytes(b) => hex::encode(b),
+    }
+}
+
+/// Escape glob metacharacters for `SCAN MATCH`
+fn glob_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if matches!(ch, '*' | '?' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Table stored in Redis as one hash per row, keyed by primary key
+/// (`WITH ('extension' = 'redis', 'url' = 'redis://cache:6379')`).
+///
+/// Rows live under `<key.prefix><table>:<key>`, so every EventFlux instance
+/// pointing at the same server sees the same rows. Conditions and joins that
+/// bind the whole primary key read a single hash; anything else scans the
+/// table's keys. Connections come from the pool shared with the Redis state
+/// backend and persistence store.
+pub struct RedisTable {
+    schema: TableSchema,
+    /// `<key.prefix><table>:`
+    key_prefix: String,
+    key_ttl_ms: Option<u64>,
+    pool: Pool,
+}
+
+impl std::fmt::Debug for RedisTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisTable")
+            .field("table", &self.schema.name)
+            .field("key_prefix", &self.key_prefix)
+            .field("key_ttl_ms", &self.key_ttl_ms)
+            .finish()
+    }
+}
+
+impl RedisTable {
+    /// Table for `definition`, which must declare a primary key. Keys expire
+    /// `key_ttl_ms` after their last write, if set.
+    pub fn new(
+        definition: &TableDefinition,
+        config: &RedisConfig,
+        key_prefix: &str,
+        key_ttl_ms: Option<u64>,
+    ) -> Result<Self, String> {
+        let schema = TableSchema::from_definition(definition)?;
+        if schema.primary_key.is_empty() {
+            return Err(format!(
+                "Redis table '{}' requires a PRIMARY KEY",
+                schema.name
+            ));
+        }
+        let pool = shared_redis_pool(config).map_err(|e| e.to_string())?;
+        Ok(Self {
+            key_prefix: format!("{}{}:", key_prefix, schema.name),
+            schema,
+            key_ttl_ms,
+            pool,
+        })
+    }
+
+    /// Redis key of the row with primary key `values`; `None` if a value is
+    /// NULL or doesn't fit its column, so no row can match
+    fn key_for(&self, values: &[AttributeValue]) -> Option<String> {
+        let key = self.schema.lookup_key(values, &self.schema.primary_key)?;
+        Some(format!(
+            "{}{}",
+            self.key_prefix,
+            key.iter().map(key_part).collect::<Vec<_>>().join(":")
+        ))
+    }
+
+    fn row_key(&self, row: &[AttributeValue]) -> Result<String, EventFluxError> {
+        let values: Vec<AttributeValue> = self
+            .schema
+            .primary_key
+            .iter()
+            .map(|&c| row.get(c).cloned().unwrap_or(AttributeValue::Null))
+            .collect();
+        self.key_for(&values).ok_or_else(|| {
+            EventFluxError::Other(format!(
+                "Row of Redis table '{}' has a NULL or invalid primary key",
+                self.schema.name
+            ))
+        })
+    }
+
+    /// Rows stored under `keys`, skipping keys that no longer exist
+    fn fetch(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<Vec<(String, Vec<AttributeValue>)>, EventFluxError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pool = self.pool.clone();
+        let (keys, hashes) = block_on(async move {
+            let mut conn = pool.get().await.map_err(pool_error)?;
+            let mut pipe = redis::pipe();
+            for key in &keys {
+                pipe.hgetall(key);
+            }
+            let hashes: Vec<HashMap<String, Vec<u8>>> =
+                pipe.query_async(&mut conn).await.map_err(redis_error)?;
+            Ok((keys, hashes))
+        })?;
+        Ok(keys
+            .into_iter()
+            .zip(hashes)
+            .filter_map(|(key, hash)| self.decode_row(hash).map(|row| (key, row)))
+            .collect())
+    }
+
+    /// Row stored in `hash`; `None` for the empty hash of a key that expired
+    /// or was deleted
+    fn decode_row(&self, hash: HashMap<String, Vec<u8>>) -> Option<Vec<AttributeValue>> {
+        if hash.is_empty() {
+            return None;
+        }
+        Some(
+            self.schema
+                .columns
+                .iter()
+                .map(|(name, ty)| {
+                    hash.get(name)
+                        .map_or(AttributeValue::Null, |bytes| decode(bytes, ty))
+                })
+                .collect(),
+        )
+    }
+
+    /// Keys of every row, in key order
+    fn all_keys(&self) -> Result<Vec<String>, EventFluxError> {
+        let pool = self.pool.clone();
+        let pattern = format!("{}*", glob_escape(&self.key_prefix));
+        block_on(async move {
+            let mut conn = pool.get().await.map_err(pool_error)?;
+            // SCAN may return a key more than once
+            let mut keys = BTreeSet::new();
+            let mut cursor: u64 = 0;
+            loop {
+                let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(SCAN_BATCH)
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(redis_error)?;
+                keys.extend(batch);
+                if next == 0 {
+                    break;
+                }
+                cursor = next;
+            }
+            Ok(keys.into_iter().collect())
+        })
+    }
+
+    /// In one transaction, delete `removed` keys then store `rows`, each
+    /// replacing any row with the same key
+    fn write(
+        &self,
+        removed: Vec<String>,
+        rows: Vec<Vec<AttributeValue>>,
+    ) -> Result<(), EventFluxError> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for key in &removed {
+            pipe.del(key).ignore();
+        }
+        for row in &rows {
+            let key = self.row_key(row)?;
+            let fields: Vec<(&str, Vec<u8>)> = self
+                .schema
+                .columns
+                .iter()
+                .zip(row)
+                .filter_map(|((name, _), value)| encode(value).map(|v| (name.as_str(), v)))
+                .collect();
+            pipe.del(&key).ignore();
+            pipe.hset_multiple(&key, &fields).ignore();
+            if let Some(ttl) = self.key_ttl_ms {
+                pipe.pexpire(&key, ttl as i64).ignore();
+            }
+        }
+        let pool = self.pool.clone();
+        block_on(async move {
+            let mut conn = pool.get().await.map_err(pool_error)?;
+            pipe.query_async::<_, ()>(&mut conn)
+                .await
+                .map_err(redis_error)
+        })
+    }
+
+    /// Rows matching `condition`, with their keys
+    fn matching(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Vec<(String, Vec<AttributeValue>)>, EventFluxError> {
+        let pk_len = self.schema.primary_key.len();
+        if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+        {
+            // A key-length value list (e.g. `x IN T`) is a primary key
+            // lookup; anything else must match the whole row
+            if cond.values.len() == pk_len && pk_len != self.schema.columns.len() {
+                return self.fetch(self.key_for(&cond.values).into_iter().collect());
+            }
+            if cond.values.len() != self.schema.columns.len() {
+                return Ok(Vec::new());
+            }
+            let key = match self.row_key(&cond.values) {
+                Ok(key) => key,
+                Err(_) => return Ok(Vec::new()),
+            };
+            let mut rows = self.fetch(vec![key])?;
+            rows.retain(|(_, row)| row == &cond.values);
+            return Ok(rows);
+        }
+        if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<IndexedCompiledCondition>()
+        {
+            let mut rows = match &cond.lookup {
+                KeyLookup::Primary(sources) => {
+                    let values: Vec<AttributeValue> =
+                        sources.iter().map(|s| s.resolve(None)).collect();
+                    self.fetch(self.key_for(&values).into_iter().collect())?
+                }
+                _ => self.fetch(self.all_keys()?)?,
+            };
+            if let Some(predicate) = &cond.predicate {
+                rows.retain(|(_, row)| predicate.matches(row));
+            }
+            return Ok(rows);
+        }
+        Err(EventFluxError::Other(format!(
+            "Unsupported condition for Redis table '{}'",
+            self.schema.name
+        )))
+    }
+}
+
+impl Table for RedisTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        self.write(Vec::new(), vec![values.to_vec()])
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        Ok(self
+            .fetch(self.all_keys()?)?
+            .into_iter()
+            .map(|(_, row)| row)
+            .collect())
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        let matched = self.matching(condition)?;
+        let updated: Vec<(String, Vec<AttributeValue>)> = if let Some(us) =
+            update_set
+                .as_any()
+                .downcast_ref::<InMemoryCompiledUpdateSet>()
+        {
+            // Replacement rows must be complete
+            if us.values.len() != self.schema.columns.len() {
+                return Ok(false);
+            }
+            matched
+                .into_iter()
+                .map(|(key, _)| (key, us.values.clone()))
+                .collect()
+        } else if let Some(us) = update_set
+            .as_any()
+            .downcast_ref::<ColumnCompiledUpdateSet>()
+        {
+            matched
+                .into_iter()
+                .map(|(key, mut row)| {
+                    for (column, value) in &us.assignments {
+                        row[*column] = value.clone();
+                    }
+                    (key, row)
+                })
+                .collect()
+        } else {
+            return Ok(false);
+        };
+        if updated.is_empty() {
+            return Ok(false);
+        }
+        // Rows whose primary key changed move to a new key
+        let mut removed = Vec::new();
+        let mut rows = Vec::new();
+        for (old_key, row) in updated {
+            if self.row_key(&row)? != old_key {
+                removed.push(old_key);
+            }
+            rows.push(row);
+        }
+        self.write(removed, rows)?;
+        Ok(true)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        let keys: Vec<String> = self
+            .matching(condition)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        if keys.is_empty() {
+            return Ok(false);
+        }
+        self.write(keys, Vec::new())?;
+        Ok(true)
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        Ok(self
+            .matching(condition)?
+            .into_iter()
+            .next()
+            .map(|(_, row)| row))
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Ok(!self.matching(condition)?.is_empty())
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let stream_data = &stream_event.before_window_data;
+        let keys = match compiled_condition
+            .and_then(|c| c.as_any().downcast_ref::<IndexedCompiledCondition>())
+            .map(|c| &c.lookup)
+        {
+            Some(KeyLookup::Primary(sources)) => {
+                let values: Vec<AttributeValue> = sources
+                    .iter()
+                    .map(|s| s.resolve(Some(stream_data)))
+                    .collect();
+                self.key_for(&values).into_iter().collect()
+            }
+            _ => self.all_keys()?,
+        };
+        let rows = self.fetch(keys)?.into_iter().map(|(_, row)| row);
+        let exec = match condition_executor {
+            Some(exec) => exec,
+            None => return Ok(rows.collect()),
+        };
+        let stream_attr_count = stream_data.len();
+        let mut joined = StreamEvent::new(stream_event.timestamp, 0, 0, 0);
+        joined.before_window_data.extend_from_slice(stream_data);
+        let mut matched = Vec::new();
+        for row in rows {
+            joined.before_window_data.truncate(stream_attr_count);
+            joined.before_window_data.extend_from_slice(&row);
+            if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+                matched.push(row);
+            }
+        }
+        Ok(matched)
+    }
+
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        let schema = &self.schema;
+        let lookup = KeyLookup::plan(schema, &cond, &|v| {
+            InMemoryTable::resolve_join_variable(schema, v, stream_id, stream_def)
+        });
+        // Only primary key lookups avoid a scan; Redis has no secondary indexes
+        if !matches!(lookup, KeyLookup::Primary(_)) {
+            return None;
+        }
+        // The join's condition executor re-checks the full condition
+        Some(Box::new(IndexedCompiledCondition {
+            lookup,
+            predicate: None,
+        }))
+    }
+
+    fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
+        if let Expression::Constant(c) = cond {
+            return Box::new(InMemoryCompiledCondition {
+                values: vec![constant_to_av(&c)],
+            });
+        }
+        let schema = &self.schema;
+        if let Some(predicate) = RowPredicate::from_expression(&cond, schema) {
+            let lookup = KeyLookup::plan(schema, &cond, &|v| {
+                schema.column(&v.attribute_name).map(Operand::Column)
+            });
+            return Box::new(IndexedCompiledCondition {
+                lookup,
+                predicate: Some(predicate),
+            });
+        }
+        Box::new(InMemoryCompiledCondition { values: Vec::new() })
+    }
+
+    fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        let assignments: Option<Vec<_>> = us
+            .set_attributes
+            .iter()
+            .map(|sa| match &sa.value_to_set {
+                Expression::Constant(c) => self
+                    .schema
+                    .column(&sa.table_column.attribute_name)
+                    .map(|column| (column, constant_to_av(c))),
+                _ => None,
+            })
+            .collect();
+        match assignments {
+            Some(assignments) => Box::new(ColumnCompiledUpdateSet { assignments }),
+            None => Box::new(InMemoryCompiledUpdateSet { values: Vec::new() }),
+        }
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        // Clones share the pool; the rows live in Redis
+        Ok(Box::new(RedisTable {
+            schema: self.schema.clone(),
+            key_prefix: self.key_prefix.clone(),
+            key_ttl_ms: self.key_ttl_ms,
+            pool: self.pool.clone(),
+        }))
+    }
+
+    fn validate_connectivity(&self) -> Result<(), EventFluxError> {
+        let pool = self.pool.clone();
+        block_on(async move {
+            let mut conn = pool.get().await.map_err(pool_error)?;
+            redis::cmd("PING")
+                .query_async::<_, String>(&mut conn)
+                .await
+                .map_err(redis_error)
+        })
+        .map(|_| ())
+        .map_err(|e| {
+            EventFluxError::configuration(format!(
+                "Redis for table '{}' is not reachable: {}",
+                self.schema.name, e
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisTableFactory;
+
+impl crate::core::extension::TableFactory for RedisTableFactory {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn create(
+        &self,
+        table_name: String,
+        _properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        Err(format!(
+            "Redis table '{}' needs its column definitions",
+            table_name
+        ))
+    }
+
+    fn create_for_definition(
+        &self,
+        definition: &TableDefinition,
+        properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        let mut config = RedisConfig::default();
+        if let Some(url) = properties.get("url") {
+            config.url = url.clone();
+        }
+        if let Some(size) = properties.get("pool.size") {
+            config.max_connections = size
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("'pool.size' must be a positive number, got '{}'", size))?;
+        }
+        let key_ttl_ms = match properties.get("key.ttl") {
+            Some(ttl) => {
+                let ttl = crate::core::config::types::parse_duration(ttl)
+                    .map_err(|e| format!("Invalid 'key.ttl': {}", e))?;
+                if ttl.is_zero() {
+                    return Err("'key.ttl' must be greater than zero".to_string());
+                }
+                Some(ttl.as_millis() as u64)
+            }
+            None => None,
+        };
+        let key_prefix = properties
+            .get("key.prefix")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_KEY_PREFIX);
+        Ok(Arc::new(RedisTable::new(
+            definition, &config, key_prefix, key_ttl_ms,
+        )?))
+    }
+
+    fn clone_box(&self) -> Box<dyn crate::core::extension::TableFactory> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_round_trip_declared_types() {
+        let cases = [
+            (AttributeValue::Int(-4), AttributeType::INT),
+            (AttributeValue::Long(1 << 40), AttributeType::LONG),
+            (AttributeValue::Float(1.25), AttributeType::FLOAT),
+            (AttributeValue::Double(0.1), AttributeType::DOUBLE),
+            (AttributeValue::Bool(true), AttributeType::BOOL),
+            (AttributeValue::String("a:b".into()), AttributeType::STRING),
+        ];
+        for (value, ty) in cases {
+            assert_eq!(decode(&encode(&value).unwrap(), &ty), value);
+        }
+        assert_eq!(encode(&AttributeValue::Null), None);
+    }
+
+    #[test]
+    fn test_key_parts_escape_separators() {
+        assert_eq!(key_part(&IndexKey::String("a:b\\c".into())), "a\\:b\\\\c");
+        assert_ne!(
+            key_part(&IndexKey::String("a:b".into())),
+            [
+                key_part(&IndexKey::String("a".into())),
+                key_part(&IndexKey::String("b".into()))
+            ]
+            .join(":")
+        );
+        assert_eq!(glob_escape("t*[1]"), "t\\*\\[1\\]");
+    }
+}
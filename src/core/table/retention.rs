@@ -0,0 +1,423 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::core::event::event::Event;
+use crate::core::event::value::AttributeValue;
+use crate::core::persistence::{
+    AccessPattern, ChangeLog, CheckpointId, CompressionType, SchemaVersion, SerializationHints,
+    StateError, StateHolder, StateMetadata, StateSize, StateSnapshot,
+};
+use crate::core::stream::stream_junction::StreamJunction;
+use crate::core::util::scheduler::{Schedulable, Scheduler};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::Table;
+
+/// What resets a row's time to live
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TtlMode {
+    /// Rows expire a fixed time after they were inserted or last updated
+    #[default]
+    AfterWrite,
+    /// Reads (lookups and join matches) also keep a row alive
+    AfterAccess,
+}
+
+/// Row retention configured with `WITH ('ttl' = ..., 'ttl.mode' = ...,
+/// 'max.rows' = ...)`. Rows past their TTL, and the least recently written
+/// (or accessed) rows beyond `max.rows`, are evicted by a scheduled task.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RetentionPolicy {
+    pub ttl_ms: Option<i64>,
+    pub mode: TtlMode,
+    pub max_rows: Option<usize>,
+}
+
+/// Default upper bound on the time between eviction passes
+const MAX_EVICTION_INTERVAL_MS: i64 = 1_000;
+const MIN_EVICTION_INTERVAL_MS: i64 = 10;
+
+impl RetentionPolicy {
+    /// Read the retention properties of a table; `None` if neither `ttl` nor
+    /// `max.rows` is set
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let ttl_ms = properties
+            .get("ttl")
+            .map(|ttl| {
+                crate::core::config::types::parse_duration(ttl)
+                    .map(|d| d.as_millis() as i64)
+                    .map_err(|e| format!("Invalid 'ttl' '{ttl}': {e}"))
+                    .and_then(|ms| {
+                        if ms > 0 {
+                            Ok(ms)
+                        } else {
+                            Err("'ttl' must be positive".to_string())
+                        }
+                    })
+            })
+            .transpose()?;
+        let mode = match properties.get("ttl.mode").map(|m| m.to_lowercase()) {
+            None => TtlMode::AfterWrite,
+            Some(m) if m == "after_write" => TtlMode::AfterWrite,
+            Some(m) if m == "after_access" => TtlMode::AfterAccess,
+            Some(other) => {
+                return Err(format!(
+                    "Invalid 'ttl.mode' '{other}', expected 'after_write' or 'after_access'"
+                ))
+            }
+        };
+        if properties.contains_key("ttl.mode") && ttl_ms.is_none() {
+            return Err("'ttl.mode' requires 'ttl'".to_string());
+        }
+        let max_rows = properties
+            .get("max.rows")
+            .map(|n| match n.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(format!("'max.rows' must be a positive integer, got '{n}'")),
+            })
+            .transpose()?;
+
+        if ttl_ms.is_none() && max_rows.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(RetentionPolicy {
+            ttl_ms,
+            mode,
+            max_rows,
+        }))
+    }
+
+    /// Time between eviction passes: `eviction.interval` when given,
+    /// otherwise a quarter of the TTL bounded to [10 ms, 1 s]
+    pub fn eviction_interval_ms(
+        &self,
+        properties: &HashMap<String, String>,
+    ) -> Result<i64, String> {
+        if let Some(interval) = properties.get("eviction.interval") {
+            return crate::core::config::types::parse_duration(interval)
+                .map(|d| (d.as_millis() as i64).max(1))
+                .map_err(|e| format!("Invalid 'eviction.interval' '{interval}': {e}"));
+        }
+        Ok(self
+            .ttl_ms
+            .map_or(MAX_EVICTION_INTERVAL_MS, |ttl| ttl / 4)
+            .clamp(MIN_EVICTION_INTERVAL_MS, MAX_EVICTION_INTERVAL_MS))
+    }
+}
+
+/// Last write (or access) time of every row, ordered so the oldest rows are
+/// found without a scan
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RowClock<K: Ord + Hash + Clone> {
+    touched: HashMap<K, i64>,
+    order: BTreeSet<(i64, K)>,
+}
+
+impl<K: Ord + Hash + Clone> RowClock<K> {
+    pub fn touch(&mut self, key: K, now: i64) {
+        if let Some(previous) = self.touched.insert(key.clone(), now) {
+            self.order.remove(&(previous, key.clone()));
+        }
+        self.order.insert((now, key));
+    }
+
+    pub fn forget(&mut self, key: &K) {
+        if let Some(previous) = self.touched.remove(key) {
+            self.order.remove(&(previous, key.clone()));
+        }
+    }
+
+    /// Rows to evict at `now` under `policy`: every row touched at or before
+    /// `now - ttl`, then the oldest rows until at most `max_rows` remain
+    pub fn due(&self, policy: &RetentionPolicy, now: i64) -> Vec<K> {
+        let expired = policy.ttl_ms.map_or(0, |ttl| {
+            self.order
+                .iter()
+                .take_while(|(touched, _)| *touched + ttl <= now)
+                .count()
+        });
+        let over_limit = policy
+            .max_rows
+            .map_or(0, |max| self.order.len().saturating_sub(max));
+        self.order
+            .iter()
+            .take(expired.max(over_limit))
+            .map(|(_, key)| key.clone())
+            .collect()
+    }
+
+    pub fn touched_at(&self, key: &K) -> Option<i64> {
+        self.touched.get(key).copied()
+    }
+}
+
+/// Wall-clock milliseconds, the time base of row TTLs and the scheduler
+pub(crate) fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Periodically evicts expired rows from a table, optionally forwarding the
+/// evicted rows as events to a stream.
+#[derive(Debug)]
+pub struct TableEvictionRuntime {
+    table_id: String,
+    table: Arc<dyn Table>,
+    interval_ms: i64,
+    eviction_junction: Option<Arc<Mutex<StreamJunction>>>,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl TableEvictionRuntime {
+    pub fn new(
+        table_id: String,
+        table: Arc<dyn Table>,
+        interval_ms: i64,
+        eviction_junction: Option<Arc<Mutex<StreamJunction>>>,
+        scheduler: Arc<Scheduler>,
+    ) -> Self {
+        Self {
+            table_id,
+            table,
+            interval_ms,
+            eviction_junction,
+            scheduler,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn start(&self) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let task = EvictionTask {
+            table_id: self.table_id.clone(),
+            table: Arc::clone(&self.table),
+            interval_ms: self.interval_ms,
+            eviction_junction: self.eviction_junction.as_ref().map(Arc::clone),
+            scheduler: Arc::clone(&self.scheduler),
+            running: Arc::clone(&self.running),
+        };
+        self.scheduler
+            .notify_at(now_millis() + self.interval_ms, Arc::new(task));
+    }
+
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EvictionTask {
+    table_id: String,
+    table: Arc<dyn Table>,
+    interval_ms: i64,
+    eviction_junction: Option<Arc<Mutex<StreamJunction>>>,
+    scheduler: Arc<Scheduler>,
+    running: Arc<AtomicBool>,
+}
+
+impl Schedulable for EvictionTask {
+    fn on_time(&self, timestamp: i64) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        match self.table.evict_expired(now_millis()) {
+            Ok(evicted) if !evicted.is_empty() => {
+                log::debug!(
+                    "Evicted {} row(s) from table '{}'",
+                    evicted.len(),
+                    self.table_id
+                );
+                if let Some(junction) = &self.eviction_junction {
+                    let events = evicted
+                        .into_iter()
+                        .map(|row| Event::new_with_data(timestamp, row))
+                        .collect();
+                    if let Err(e) = junction.lock().unwrap().send_events(events) {
+                        log::error!(
+                            "Failed to emit evicted rows of table '{}': {}",
+                            self.table_id,
+                            e
+                        );
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Eviction failed for table '{}': {}", self.table_id, e),
+        }
+        self.scheduler
+            .notify_at(timestamp + self.interval_ms, Arc::new(self.clone()));
+    }
+}
+
+/// Persists the rows of a table with a retention policy together with their
+/// write (or access) times, so a restored table keeps expiring rows on their
+/// original schedule instead of granting every row a fresh TTL. Tables that
+/// record their changes (see [`Table::record_changes`]) are checkpointed
+/// incrementally.
+#[derive(Debug)]
+pub struct TableRetentionStateHolder {
+    table_id: String,
+    table: Arc<dyn Table>,
+}
+
+impl TableRetentionStateHolder {
+    pub fn new(table_id: String, table: Arc<dyn Table>) -> Self {
+        Self { table_id, table }
+    }
+}
+
+impl StateHolder for TableRetentionStateHolder {
+    fn schema_version(&self) -> SchemaVersion {
+        SchemaVersion::new(1, 0, 0)
+    }
+
+    fn serialize_state(&self, _hints: &SerializationHints) -> Result<StateSnapshot, StateError> {
+        let rows = self
+            .table
+            .timed_rows()
+            .map_err(|e| StateError::SerializationError {
+                message: format!("Failed to read rows of table '{}': {e}", self.table_id),
+            })?;
+        let data =
+            crate::core::util::to_bytes(&rows).map_err(|e| StateError::SerializationError {
+                message: format!("Failed to serialize table '{}': {e}", self.table_id),
+            })?;
+        let checksum = StateSnapshot::calculate_checksum(&data);
+        Ok(StateSnapshot {
+            version: self.schema_version(),
+            checkpoint_id: 0,
+            data,
+            compression: CompressionType::None,
+            checksum,
+            metadata: self.component_metadata(),
+        })
+    }
+
+    fn deserialize_state(&self, snapshot: &StateSnapshot) -> Result<(), StateError> {
+        if !snapshot.verify_integrity() {
+            return Err(StateError::ChecksumMismatch);
+        }
+        let rows: Vec<(Vec<AttributeValue>, i64)> = crate::core::util::from_bytes(&snapshot.data)
+            .map_err(|e| {
+            StateError::DeserializationError {
+                message: format!("Failed to deserialize table '{}': {e}", self.table_id),
+            }
+        })?;
+        self.table
+            .restore_timed_rows(rows)
+            .map_err(|e| StateError::DeserializationError {
+                message: format!("Failed to restore table '{}': {e}", self.table_id),
+            })
+    }
+
+    fn get_changelog(&self, since: CheckpointId) -> Result<ChangeLog, StateError> {
+        let operations =
+            self.table
+                .pending_changes()
+                .map_err(|e| StateError::SerializationError {
+                    message: format!("Failed to read changes of table '{}': {e}", self.table_id),
+                })?;
+        let mut changelog = ChangeLog::new(since, since + 1);
+        for operation in operations {
+            changelog.add_operation(operation);
+        }
+        Ok(changelog)
+    }
+
+    fn apply_changelog(&self, changes: &ChangeLog) -> Result<(), StateError> {
+        self.table.apply_changes(&changes.operations).map_err(|e| {
+            StateError::DeserializationError {
+                message: format!("Failed to apply changes to table '{}': {e}", self.table_id),
+            }
+        })
+    }
+
+    fn commit_changelog(&self, _checkpoint_id: CheckpointId, operations: usize) {
+        self.table.commit_changes(operations);
+    }
+
+    fn estimate_size(&self) -> StateSize {
+        let entries = self.table.all_rows().map_or(0, |rows| rows.len());
+        StateSize {
+            bytes: entries * std::mem::size_of::<(Vec<AttributeValue>, i64)>(),
+            entries,
+            estimated_growth_rate: 0.0,
+        }
+    }
+
+    fn access_pattern(&self) -> AccessPattern {
+        AccessPattern::Hot
+    }
+
+    fn component_metadata(&self) -> StateMetadata {
+        StateMetadata::new(
+            format!("table_{}", self.table_id),
+            "TableRetentionStateHolder".to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_policy_from_properties() {
+        assert_eq!(RetentionPolicy::from_properties(&props(&[])).unwrap(), None);
+        let policy = RetentionPolicy::from_properties(&props(&[
+            ("ttl", "1 hour"),
+            ("ttl.mode", "after_access"),
+            ("max.rows", "100"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            policy,
+            RetentionPolicy {
+                ttl_ms: Some(3_600_000),
+                mode: TtlMode::AfterAccess,
+                max_rows: Some(100),
+            }
+        );
+        assert_eq!(
+            policy.eviction_interval_ms(&props(&[])).unwrap(),
+            MAX_EVICTION_INTERVAL_MS
+        );
+
+        assert!(RetentionPolicy::from_properties(&props(&[("ttl", "0s")])).is_err());
+        assert!(RetentionPolicy::from_properties(&props(&[("ttl.mode", "after_write")])).is_err());
+        assert!(RetentionPolicy::from_properties(&props(&[("max.rows", "-1")])).is_err());
+    }
+
+    #[test]
+    fn test_row_clock_due() {
+        let mut clock = RowClock::default();
+        clock.touch(1u64, 100);
+        clock.touch(2, 200);
+        clock.touch(3, 300);
+        clock.touch(1, 400);
+        let ttl = RetentionPolicy {
+            ttl_ms: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(clock.due(&ttl, 400), vec![2]);
+        let max_rows = RetentionPolicy {
+            max_rows: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(clock.due(&max_rows, 0), vec![2, 3]);
+        clock.forget(&2);
+        assert_eq!(clock.due(&ttl, 500), vec![3]);
+    }
+}
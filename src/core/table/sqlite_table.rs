@@ -0,0 +1,600 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::core::config::eventflux_context::EventFluxContext;
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::table::jdbc_table::{
+    expression_to_sql, expression_to_sql_join, is_translatable, JdbcCompiledCondition,
+    JdbcCompiledUpdateSet, JdbcJoinCompiledCondition, JoinParamSource,
+};
+use crate::core::table::{
+    constant_to_av, CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition,
+    InMemoryCompiledUpdateSet, SimpleCompiledCondition, SimpleCompiledUpdateSet, Table,
+};
+use crate::query_api::definition::attribute::Type as AttributeType;
+use crate::query_api::definition::TableDefinition;
+use crate::query_api::execution::query::output::stream::UpdateSet;
+use crate::query_api::expression::Expression;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params_from_iter, Connection};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Statements kept prepared per connection; compiled conditions are reused
+/// for every event, so their SQL text rarely changes
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Table stored in a local SQLite file it creates and owns
+/// (`WITH ('extension' = 'sqlite', 'path' = 'data/rates.db')`).
+///
+/// The SQL table is created from the definition, including its primary key
+/// and indexes, and rows are read back with the declared column types. Rows
+/// live in the file, so the table survives restarts without snapshots.
+#[derive(Debug)]
+pub struct SqliteTable {
+    table_name: String,
+    path: String,
+    columns: Vec<(String, AttributeType)>,
+    primary_key: Vec<usize>,
+    select_sql: String,
+    insert_sql: String,
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sql_type(attr_type: &AttributeType) -> &'static str {
+    match attr_type {
+        AttributeType::STRING => "TEXT",
+        AttributeType::INT | AttributeType::LONG | AttributeType::BOOL => "INTEGER",
+        AttributeType::FLOAT | AttributeType::DOUBLE => "REAL",
+        AttributeType::OBJECT => "BLOB",
+    }
+}
+
+impl SqliteTable {
+    /// Open (creating if needed) the SQLite file at `path` and the SQL table
+    /// `table_name` described by `definition`. An existing SQL table must
+    /// have the definition's columns.
+    pub fn open(
+        definition: &TableDefinition,
+        path: &str,
+        table_name: String,
+    ) -> Result<Self, String> {
+        let columns: Vec<(String, AttributeType)> = definition
+            .abstract_definition
+            .attribute_list
+            .iter()
+            .map(|a| (a.get_name().clone(), *a.get_type()))
+            .collect();
+        let position = |name: &str| {
+            columns
+                .iter()
+                .position(|(c, _)| c == name)
+                .ok_or_else(|| format!("Unknown column '{}' in table '{}'", name, table_name))
+        };
+        let primary_key = definition
+            .primary_key
+            .iter()
+            .map(|c| position(c.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if path != ":memory:" {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                if !parent.as_os_str().is_empty() {
+                    std::fs::create_dir_all(parent).map_err(|e| {
+                        format!("Cannot create directory for SQLite file '{}': {}", path, e)
+                    })?;
+                }
+            }
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Cannot open SQLite file '{}': {}", path, e))?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        if path != ":memory:" {
+            // Readers don't block the writer under write-ahead logging
+            conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))
+                .map_err(|e| format!("Cannot enable WAL for '{}': {}", path, e))?;
+        }
+
+        let mut column_defs: Vec<String> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, ty))| {
+                let not_null = if primary_key.contains(&i) {
+                    " NOT NULL"
+                } else {
+                    ""
+                };
+                format!("{} {}{}", quote(name), sql_type(ty), not_null)
+            })
+            .collect();
+        if !primary_key.is_empty() {
+            column_defs.push(format!(
+                "PRIMARY KEY ({})",
+                primary_key
+                    .iter()
+                    .map(|&i| quote(&columns[i].0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let mut ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {} ({});",
+            quote(&table_name),
+            column_defs.join(", ")
+        );
+        for index in &definition.indexes {
+            for column in &index.columns {
+                position(column.as_str())?;
+            }
+            ddl.push_str(&format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({});",
+                quote(&format!("{}_{}", table_name, index.name)),
+                quote(&table_name),
+                index
+                    .columns
+                    .iter()
+                    .map(|c| quote(c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        conn.execute_batch(&ddl)
+            .map_err(|e| format!("Cannot create SQLite table '{}': {}", table_name, e))?;
+
+        let existing: Vec<String> = {
+            let mut stmt = conn
+                .prepare(&format!("PRAGMA table_info({})", quote(&table_name)))
+                .map_err(|e| format!("Cannot inspect SQLite table '{}': {}", table_name, e))?;
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| format!("Cannot inspect SQLite table '{}': {}", table_name, e))?
+        };
+        let declared: Vec<String> = columns.iter().map(|(c, _)| c.clone()).collect();
+        if existing != declared {
+            return Err(format!(
+                "SQLite table '{}' in '{}' has columns ({}) but the definition declares ({})",
+                table_name,
+                path,
+                existing.join(", "),
+                declared.join(", ")
+            ));
+        }
+
+        let column_list = declared
+            .iter()
+            .map(|c| quote(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let select_sql = format!("SELECT {} FROM {}", column_list, quote(&table_name));
+        // Inserting an existing primary key replaces the row, like InMemoryTable
+        let insert_sql = format!(
+            "INSERT {}INTO {} ({}) VALUES ({})",
+            if primary_key.is_empty() {
+                ""
+            } else {
+                "OR REPLACE "
+            },
+            quote(&table_name),
+            column_list,
+            vec!["?"; columns.len()].join(", ")
+        );
+
+        Ok(Self {
+            table_name,
+            path: path.to_string(),
+            columns,
+            primary_key,
+            select_sql,
+            insert_sql,
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn av_to_val(av: &AttributeValue) -> Value {
+        match av {
+            AttributeValue::String(s) => Value::Text(s.clone()),
+            AttributeValue::Int(i) => Value::Integer(*i as i64),
+            AttributeValue::Long(l) => Value::Integer(*l),
+            AttributeValue::Float(f) => Value::Real(*f as f64),
+            AttributeValue::Double(d) => Value::Real(*d),
+            AttributeValue::Bool(b) => Value::Integer(if *b { 1 } else { 0 }),
+            AttributeValue::Bytes(b) => Value::Blob(b.clone()),
+            AttributeValue::Null => Value::Null,
+            AttributeValue::Object(_) => Value::Null,
+        }
+    }
+
+    /// Convert a stored value back to the column's declared type
+    fn val_to_av(value: ValueRef, attr_type: &AttributeType) -> AttributeValue {
+        match (value, attr_type) {
+            (ValueRef::Null, _) => AttributeValue::Null,
+            (ValueRef::Integer(v), AttributeType::INT) => AttributeValue::Int(v as i32),
+            (ValueRef::Integer(v), AttributeType::BOOL) => AttributeValue::Bool(v != 0),
+            (ValueRef::Integer(v), AttributeType::FLOAT) => AttributeValue::Float(v as f32),
+            (ValueRef::Integer(v), AttributeType::DOUBLE) => AttributeValue::Double(v as f64),
+            (ValueRef::Integer(v), _) => AttributeValue::Long(v),
+            (ValueRef::Real(v), AttributeType::FLOAT) => AttributeValue::Float(v as f32),
+            (ValueRef::Real(v), AttributeType::INT) => AttributeValue::Int(v as i32),
+            (ValueRef::Real(v), AttributeType::LONG) => AttributeValue::Long(v as i64),
+            (ValueRef::Real(v), _) => AttributeValue::Double(v),
+            (ValueRef::Text(v), _) => {
+                AttributeValue::String(String::from_utf8_lossy(v).to_string())
+            }
+            (ValueRef::Blob(v), _) => AttributeValue::Bytes(v.to_vec()),
+        }
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>, EventFluxError> {
+        self.conn
+            .lock()
+            .map_err(|e| EventFluxError::DatabaseRuntime {
+                message: format!("Failed to acquire connection lock: {}", e),
+                source: None,
+            })
+    }
+
+    /// Run a query through the statement cache and decode every row
+    fn query(
+        &self,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare_cached(sql)?;
+        let mut rows = stmt.query(params_from_iter(params.iter()))?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            out.push(
+                self.columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, ty))| Self::val_to_av(row.get_ref_unwrap(i), ty))
+                    .collect(),
+            );
+        }
+        Ok(out)
+    }
+
+    fn execute(&self, sql: &str, params: &[Value]) -> Result<usize, EventFluxError> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare_cached(sql)?;
+        stmt.execute(params_from_iter(params.iter())).map_err(|e| {
+            log::error!(
+                "SQLite statement failed on table '{}': {}",
+                self.table_name,
+                e
+            );
+            EventFluxError::from(e)
+        })
+    }
+
+    /// Columns matched by a value list condition: the primary key for a
+    /// key-length list on a keyed table (e.g. `x IN T`), otherwise the
+    /// leading columns
+    fn value_columns(&self, len: usize) -> Vec<usize> {
+        if !self.primary_key.is_empty()
+            && len == self.primary_key.len()
+            && len != self.columns.len()
+        {
+            self.primary_key.clone()
+        } else {
+            (0..len.min(self.columns.len())).collect()
+        }
+    }
+
+    /// `WHERE` clause and parameters matching `condition`
+    fn where_clause(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<(String, Vec<Value>), EventFluxError> {
+        if let Some(cond) = condition.as_any().downcast_ref::<JdbcCompiledCondition>() {
+            return Ok((
+                cond.where_clause.clone(),
+                cond.params.iter().map(Self::av_to_val).collect(),
+            ));
+        }
+        if let Some(cond) = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+        {
+            let clause = self
+                .value_columns(cond.values.len())
+                .iter()
+                .map(|&i| format!("{} IS ?", quote(&self.columns[i].0)))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            return Ok((
+                if clause.is_empty() {
+                    "1".to_string()
+                } else {
+                    clause
+                },
+                cond.values.iter().map(Self::av_to_val).collect(),
+            ));
+        }
+        Err(EventFluxError::Other(format!(
+            "Unsupported condition for SQLite table '{}'",
+            self.table_name
+        )))
+    }
+}
+
+impl Table for SqliteTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        let params: Vec<Value> = values.iter().map(Self::av_to_val).collect();
+        self.execute(&self.insert_sql, &params)?;
+        Ok(())
+    }
+
+    fn insert_batch(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(&self.insert_sql)?;
+            for row in rows {
+                stmt.execute(params_from_iter(row.iter().map(Self::av_to_val)))?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn replace_rows(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        tx.execute(&format!("DELETE FROM {}", quote(&self.table_name)), [])?;
+        {
+            let mut stmt = tx.prepare_cached(&self.insert_sql)?;
+            for row in rows {
+                stmt.execute(params_from_iter(row.iter().map(Self::av_to_val)))?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.query(&self.select_sql, &[])
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        let (assignments, mut params) =
+            if let Some(us) = update_set.as_any().downcast_ref::<JdbcCompiledUpdateSet>() {
+                (
+                    us.assignments.clone(),
+                    us.params.iter().map(Self::av_to_val).collect::<Vec<_>>(),
+                )
+            } else if let Some(us) = update_set
+                .as_any()
+                .downcast_ref::<InMemoryCompiledUpdateSet>()
+            {
+                (
+                    self.columns
+                        .iter()
+                        .take(us.values.len())
+                        .map(|(c, _)| format!("{} = ?", quote(c)))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    us.values.iter().map(Self::av_to_val).collect(),
+                )
+            } else {
+                return Err(EventFluxError::Other(format!(
+                    "Unsupported update set for SQLite table '{}'",
+                    self.table_name
+                )));
+            };
+        if assignments.is_empty() {
+            return Ok(false);
+        }
+        let (where_clause, where_params) = self.where_clause(condition)?;
+        params.extend(where_params);
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            quote(&self.table_name),
+            assignments,
+            where_clause
+        );
+        Ok(self.execute(&sql, &params)? > 0)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        let (where_clause, params) = self.where_clause(condition)?;
+        let sql = format!(
+            "DELETE FROM {} WHERE {}",
+            quote(&self.table_name),
+            where_clause
+        );
+        Ok(self.execute(&sql, &params)? > 0)
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        let (where_clause, params) = self.where_clause(condition)?;
+        let sql = format!("{} WHERE {} LIMIT 1", self.select_sql, where_clause);
+        Ok(self.query(&sql, &params)?.into_iter().next())
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Ok(self.find(condition)?.is_some())
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        if let Some(cond) =
+            compiled_condition.and_then(|c| c.as_any().downcast_ref::<JdbcJoinCompiledCondition>())
+        {
+            let params: Vec<Value> = cond
+                .params
+                .iter()
+                .map(|p| match p {
+                    JoinParamSource::Constant(av) => Self::av_to_val(av),
+                    JoinParamSource::StreamAttr(idx) => {
+                        Self::av_to_val(&stream_event.before_window_data[*idx])
+                    }
+                })
+                .collect();
+            let sql = format!("{} WHERE {}", self.select_sql, cond.where_clause);
+            return self.query(&sql, &params);
+        }
+
+        // Conditions SQL can't express are evaluated against every row
+        let rows = self.all_rows()?;
+        let exec = match condition_executor {
+            Some(exec) => exec,
+            None => return Ok(rows),
+        };
+        let stream_attr_count = stream_event.before_window_data.len();
+        let mut joined = StreamEvent::new(stream_event.timestamp, 0, 0, 0);
+        joined
+            .before_window_data
+            .extend_from_slice(&stream_event.before_window_data);
+        let mut matched = Vec::new();
+        for row in rows {
+            joined.before_window_data.truncate(stream_attr_count);
+            joined.before_window_data.extend_from_slice(&row);
+            if let Some(AttributeValue::Bool(true)) = exec.execute(Some(&joined)) {
+                matched.push(row);
+            }
+        }
+        Ok(matched)
+    }
+
+    fn compile_condition(&self, cond: Expression) -> Box<dyn CompiledCondition> {
+        if let Expression::Constant(c) = &cond {
+            return Box::new(InMemoryCompiledCondition {
+                values: vec![constant_to_av(c)],
+            });
+        }
+        if !is_translatable(&cond) {
+            return Box::new(SimpleCompiledCondition(cond));
+        }
+        let mut params = Vec::new();
+        let where_clause = expression_to_sql(&cond, &mut params);
+        Box::new(JdbcCompiledCondition {
+            where_clause,
+            params,
+        })
+    }
+
+    fn compile_update_set(&self, us: UpdateSet) -> Box<dyn CompiledUpdateSet> {
+        if !us
+            .set_attributes
+            .iter()
+            .all(|sa| is_translatable(&sa.value_to_set))
+        {
+            return Box::new(SimpleCompiledUpdateSet(us));
+        }
+        let mut params = Vec::new();
+        let mut assigns = Vec::new();
+        for sa in us.set_attributes.iter() {
+            let expr_sql = expression_to_sql(&sa.value_to_set, &mut params);
+            assigns.push(format!(
+                "{} = {}",
+                quote(&sa.table_column.attribute_name),
+                expr_sql
+            ));
+        }
+        Box::new(JdbcCompiledUpdateSet {
+            assignments: assigns.join(", "),
+            params,
+        })
+    }
+
+    fn compile_join_condition(
+        &self,
+        cond: Expression,
+        stream_id: &str,
+        stream_def: &crate::query_api::definition::stream_definition::StreamDefinition,
+    ) -> Option<Box<dyn CompiledCondition>> {
+        if !is_translatable(&cond) {
+            return None;
+        }
+        let mut params = Vec::new();
+        let where_clause = expression_to_sql_join(&cond, stream_id, stream_def, &mut params);
+        Some(Box::new(JdbcJoinCompiledCondition {
+            where_clause,
+            params,
+        }))
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        // Clones share the connection, and with it the stored rows
+        Ok(Box::new(SqliteTable {
+            table_name: self.table_name.clone(),
+            path: self.path.clone(),
+            columns: self.columns.clone(),
+            primary_key: self.primary_key.clone(),
+            select_sql: self.select_sql.clone(),
+            insert_sql: self.insert_sql.clone(),
+            conn: Arc::clone(&self.conn),
+        }))
+    }
+
+    fn validate_connectivity(&self) -> Result<(), EventFluxError> {
+        let sql = format!("{} LIMIT 0", self.select_sql);
+        self.query(&sql, &[]).map(|_| ()).map_err(|e| {
+            EventFluxError::configuration(format!(
+                "SQLite table '{}' in '{}' is not readable: {}",
+                self.table_name, self.path, e
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SqliteTableFactory;
+
+impl crate::core::extension::TableFactory for SqliteTableFactory {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn create(
+        &self,
+        table_name: String,
+        _properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        Err(format!(
+            "SQLite table '{}' needs its column definitions",
+            table_name
+        ))
+    }
+
+    fn create_for_definition(
+        &self,
+        definition: &TableDefinition,
+        properties: HashMap<String, String>,
+        _ctx: Arc<EventFluxContext>,
+    ) -> Result<Arc<dyn Table>, String> {
+        let id = &definition.abstract_definition.id;
+        let path = properties
+            .get("path")
+            .ok_or_else(|| format!("SQLite table '{}' requires a 'path' property", id))?;
+        let table_name = properties
+            .get("table.name")
+            .cloned()
+            .unwrap_or_else(|| id.clone());
+        Ok(Arc::new(SqliteTable::open(definition, path, table_name)?))
+    }
+
+    fn clone_box(&self) -> Box<dyn crate::core::extension::TableFactory> {
+        Box::new(self.clone())
+    }
+}
@@ -8,9 +8,10 @@ use std::sync::{Arc, Mutex}; // Added Mutex // If QueryParser needs table_map et
 use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::config::eventflux_query_context::EventFluxQueryContext; // QueryParser will need this
 use crate::core::config::ApplicationConfig;
+use crate::core::event::stream::meta_stream_event::MetaStreamEvent;
 use crate::core::eventflux_app_runtime_builder::EventFluxAppRuntimeBuilder;
 use crate::core::stream::junction_factory::{JunctionConfig, StreamJunctionFactory}; // For creating junctions
-use crate::core::window::WindowRuntime;
+use crate::core::window::{NamedWindowOutputProcessor, NamedWindowShared, WindowRuntime};
 use crate::query_api::{
     definition::{Attribute as ApiAttribute, StreamDefinition as ApiStreamDefinition}, // For fault stream creation
     // Other API definitions will be needed by specific parsers (Table, Window etc.)
@@ -205,6 +206,13 @@ impl EventFluxAppParser {
                 table_type = None;
             }
 
+            let retention = crate::core::table::RetentionPolicy::from_properties(&props)
+                .map_err(|e| format!("Table '{}': {}", table_id, e))?;
+            let cache_config = crate::core::table::TableCacheConfig::from_properties(&props)
+                .map_err(|e| format!("Table '{}': {}", table_id, e))?;
+            let load_config = crate::core::table::TableLoadConfig::from_properties(&props)
+                .map_err(|e| format!("Table '{}': {}", table_id, e))?;
+
             // Create table based on type
             let table: Arc<dyn crate::core::table::Table> = if let Some(t_type) = table_type {
                 // Try registered factory first
@@ -212,8 +220,8 @@ impl EventFluxAppParser {
                     .get_eventflux_context()
                     .get_table_factory(&t_type)
                 {
-                    factory.create(
-                        table_id.clone(),
+                    factory.create_for_definition(
+                        table_def,
                         props.clone(),
                         eventflux_app_context.get_eventflux_context(),
                     )?
@@ -229,7 +237,10 @@ impl EventFluxAppParser {
                 }
                 // Built-in cache table
                 else if t_type == "cache" || t_type == "inMemory" {
-                    Arc::new(crate::core::table::InMemoryTable::new())
+                    Arc::new(
+                        crate::core::table::InMemoryTable::with_definition(table_def)?
+                            .with_retention(retention.clone()),
+                    )
                 } else {
                     // Unknown extension type - error instead of silent fallback
                     return Err(format!(
@@ -248,6 +259,92 @@ impl EventFluxAppParser {
                 ));
             };
 
+            // The initial load fills the backing table directly; it isn't a
+            // change to report or cache
+            let backing_table = Arc::clone(&table);
+            let table: Arc<dyn crate::core::table::Table> = match &cache_config {
+                Some(config) => Arc::new(crate::core::table::CachingTable::new(
+                    table,
+                    table_def,
+                    config.clone(),
+                )),
+                None => table,
+            };
+
+            // Change streams wrap the table last so they see every write,
+            // including retention evictions
+            let mut change_junctions = Vec::new();
+            for (stream_id, stream_def) in &api_eventflux_app.stream_definition_map {
+                if stream_def.changes_of.as_deref() == Some(table_id.as_str()) {
+                    let junction = builder.stream_junction_map.get(stream_id).ok_or_else(|| {
+                        format!(
+                            "Change stream '{}' of table '{}' not found",
+                            stream_id, table_id
+                        )
+                    })?;
+                    change_junctions.push(Arc::clone(junction));
+                }
+            }
+            let table: Arc<dyn crate::core::table::Table> = if change_junctions.is_empty() {
+                table
+            } else {
+                Arc::new(crate::core::table::ChangeStreamTable::new(
+                    table,
+                    table_def,
+                    change_junctions,
+                ))
+            };
+
+            // Fail fast on unreachable or mismatched backing stores
+            table.validate_connectivity().map_err(|e| {
+                format!("Table '{}' connectivity validation failed: {}", table_id, e)
+            })?;
+
+            if let Some(config) = load_config {
+                Self::load_table(
+                    table_id,
+                    table_def,
+                    &backing_table,
+                    &table,
+                    config,
+                    eventflux_app_context,
+                    builder,
+                )?;
+            }
+
+            match &retention {
+                Some(policy) => {
+                    Self::start_table_retention(table_id, &table, policy, &props, builder)?
+                }
+                None if props.contains_key("eviction.stream") => {
+                    return Err(format!(
+                        "Table '{}': 'eviction.stream' requires 'ttl' or 'max.rows'",
+                        table_id
+                    ));
+                }
+                None => {}
+            }
+
+            if let Some(config) = cache_config
+                .as_ref()
+                .filter(|c| c.write_mode == crate::core::table::WriteMode::WriteBehind)
+            {
+                let scheduler = eventflux_app_context.get_scheduler().ok_or_else(|| {
+                    format!(
+                        "No scheduler available to flush writes of table '{}'",
+                        table_id
+                    )
+                })?;
+                builder.add_table_flush_runtime(Arc::new(
+                    crate::core::table::TableFlushRuntime::new(
+                        table_id.clone(),
+                        Arc::clone(&table),
+                        config.flush_interval_ms,
+                        scheduler,
+                    ),
+                ));
+            }
+
             eventflux_app_context
                 .get_eventflux_context()
                 .add_table(table_id.clone(), table);
@@ -263,7 +360,137 @@ impl EventFluxAppParser {
         Ok(())
     }
 
+    /// Fill a table from its `load.*` source and schedule its reloads, if
+    /// `load.refresh` is set. Reloads go through `table` so caches and
+    /// change streams see them.
+    fn load_table(
+        table_id: &str,
+        table_def: &Arc<crate::query_api::definition::TableDefinition>,
+        backing_table: &Arc<dyn crate::core::table::Table>,
+        table: &Arc<dyn crate::core::table::Table>,
+        config: crate::core::table::TableLoadConfig,
+        eventflux_app_context: &Arc<EventFluxAppContext>,
+        builder: &mut EventFluxAppRuntimeBuilder,
+    ) -> Result<(), String> {
+        let context = eventflux_app_context.get_eventflux_context();
+        let rows = config
+            .load(table_def, &context)
+            .map_err(|e| format!("Table '{}' initial load failed: {}", table_id, e))?;
+        backing_table
+            .replace_rows(&rows)
+            .map_err(|e| format!("Table '{}' initial load failed: {}", table_id, e))?;
+        log::info!("Loaded {} row(s) into table '{}'", rows.len(), table_id);
+
+        if let Some(interval_ms) = config.refresh_ms {
+            let scheduler = eventflux_app_context
+                .get_scheduler()
+                .ok_or_else(|| format!("No scheduler available to reload table '{}'", table_id))?;
+            builder.add_table_load_runtime(Arc::new(crate::core::table::TableLoadRuntime::new(
+                table_id.to_string(),
+                Arc::clone(table),
+                Arc::clone(table_def),
+                config,
+                context,
+                interval_ms,
+                scheduler,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Schedule eviction for a table with a `ttl` / `max.rows` retention
+    /// policy and register its rows (with their ages) for snapshots, recording
+    /// row changes when the app takes incremental checkpoints. Evicted rows
+    /// are sent to the stream named by `eviction.stream`, if any.
+    fn start_table_retention(
+        table_id: &str,
+        table: &Arc<dyn crate::core::table::Table>,
+        policy: &crate::core::table::RetentionPolicy,
+        props: &HashMap<String, String>,
+        builder: &mut EventFluxAppRuntimeBuilder,
+    ) -> Result<(), String> {
+        if table.retention() != Some(policy) {
+            return Err(format!(
+                "Table '{}' does not support 'ttl' or 'max.rows' retention",
+                table_id
+            ));
+        }
+        let interval_ms = policy
+            .eviction_interval_ms(props)
+            .map_err(|e| format!("Table '{}': {}", table_id, e))?;
+
+        let eviction_junction = match props.get("eviction.stream") {
+            Some(stream_id) => {
+                let junction = builder.stream_junction_map.get(stream_id).ok_or_else(|| {
+                    format!(
+                        "Eviction stream '{}' of table '{}' is not defined",
+                        stream_id, table_id
+                    )
+                })?;
+                let stream_attrs = junction
+                    .lock()
+                    .unwrap()
+                    .get_stream_definition()
+                    .abstract_definition
+                    .attribute_list
+                    .len();
+                let table_attrs = builder.table_definition_map[table_id]
+                    .abstract_definition
+                    .attribute_list
+                    .len();
+                if stream_attrs != table_attrs {
+                    return Err(format!(
+                        "Eviction stream '{}' has {} attribute(s) but table '{}' has {}",
+                        stream_id, stream_attrs, table_id, table_attrs
+                    ));
+                }
+                Some(Arc::clone(junction))
+            }
+            None => None,
+        };
+
+        let app_context = Arc::clone(&builder.eventflux_app_context);
+        let scheduler = app_context.get_scheduler().ok_or_else(|| {
+            format!(
+                "No scheduler available to evict rows of table '{}'",
+                table_id
+            )
+        })?;
+        builder.add_table_eviction_runtime(Arc::new(
+            crate::core::table::TableEvictionRuntime::new(
+                table_id.to_string(),
+                Arc::clone(table),
+                interval_ms,
+                eviction_junction,
+                scheduler,
+            ),
+        ));
+
+        if let Some(service) = app_context.get_snapshot_service() {
+            if app_context.is_incremental_checkpointing_enabled() && !table.record_changes() {
+                log::debug!(
+                    "Table '{}' does not record its changes, checkpoints will snapshot it",
+                    table_id
+                );
+            }
+            service.register_state_holder(
+                format!("table_{}", table_id),
+                Arc::new(Mutex::new(
+                    crate::core::table::TableRetentionStateHolder::new(
+                        table_id.to_string(),
+                        Arc::clone(table),
+                    ),
+                )),
+            );
+        }
+        Ok(())
+    }
+
     /// Process window definitions and create window runtimes
+    ///
+    /// Each named window gets an input junction (so queries can `INSERT INTO`
+    /// it) feeding its window processor, whose output is shared by every query
+    /// reading from the window.
     fn process_window_definitions(
         api_eventflux_app: &ApiEventFluxApp,
         builder: &mut EventFluxAppRuntimeBuilder,
@@ -273,35 +500,68 @@ impl EventFluxAppParser {
             builder.add_window_definition(Arc::clone(window_def));
             let mut runtime = WindowRuntime::new(Arc::clone(window_def));
             if let Some(handler) = &window_def.window_handler {
+                let query_name = format!("__window_{window_id}");
                 let qctx = Arc::new(EventFluxQueryContext::new(
                     Arc::clone(eventflux_app_context),
-                    format!("__window_{window_id}"),
+                    query_name.clone(),
                     None,
                 ));
-                // Create minimal parse context for legacy WindowDefinition path
-                let empty_parse_ctx =
+                let stream_def = Arc::new(window_def.stream_definition.clone());
+                let meta = Arc::new(MetaStreamEvent::new_for_single_input(Arc::clone(
+                    &stream_def,
+                )));
+                let parse_ctx =
                     crate::core::util::parser::expression_parser::ExpressionParserContext {
                         eventflux_app_context: Arc::clone(eventflux_app_context),
                         eventflux_query_context: Arc::clone(&qctx),
-                        stream_meta_map: std::collections::HashMap::new(),
-                        table_meta_map: std::collections::HashMap::new(),
-                        window_meta_map: std::collections::HashMap::new(),
-                        aggregation_meta_map: std::collections::HashMap::new(),
-                        state_meta_map: std::collections::HashMap::new(),
-                        stream_positions: std::collections::HashMap::new(),
-                        default_source: String::new(),
-                        query_name: &format!("__window_{window_id}"),
+                        stream_meta_map: HashMap::from([(window_id.clone(), meta)]),
+                        table_meta_map: HashMap::new(),
+                        window_meta_map: HashMap::new(),
+                        aggregation_meta_map: HashMap::new(),
+                        state_meta_map: HashMap::new(),
+                        stream_positions: HashMap::from([(window_id.clone(), 0)]),
+                        default_source: window_id.clone(),
+                        query_name: &query_name,
                     };
-                if let Ok(proc) =
-                    crate::core::query::processor::stream::window::create_window_processor(
-                        handler,
-                        Arc::clone(eventflux_app_context),
-                        Arc::clone(&qctx),
-                        &empty_parse_ctx,
-                    )
-                {
-                    runtime.set_processor(proc);
-                }
+                let proc = crate::core::query::processor::stream::window::create_window_processor(
+                    handler,
+                    Arc::clone(eventflux_app_context),
+                    Arc::clone(&qctx),
+                    &parse_ctx,
+                )
+                .map_err(|e| format!("Failed to create window '{window_id}': {e}"))?;
+
+                // Joins and readers see the window processor's own buffer
+                let shared = Arc::new(NamedWindowShared::new(
+                    proc.lock().unwrap().window_contents(),
+                ));
+                let output = NamedWindowOutputProcessor::new(
+                    Arc::clone(eventflux_app_context),
+                    Arc::clone(&qctx),
+                    window_def.output_event_type,
+                    Arc::clone(&shared),
+                );
+                proc.lock()
+                    .unwrap()
+                    .set_next_processor(Some(Arc::new(Mutex::new(output))));
+
+                let junction = StreamJunctionFactory::create(
+                    JunctionConfig::new(window_id.clone())
+                        .with_buffer_size(eventflux_app_context.buffer_size as usize),
+                    Arc::clone(&stream_def),
+                    eventflux_app_context.clone(),
+                    None,
+                )
+                .map_err(|e| {
+                    format!("Failed to create stream junction for window '{window_id}': {e}")
+                })?;
+                junction.lock().unwrap().subscribe(Arc::clone(&proc));
+                builder.add_stream_definition(stream_def);
+                builder.add_stream_junction(window_id.clone(), junction);
+
+                eventflux_app_context.add_named_window(window_id.clone(), Arc::clone(&shared));
+                runtime.set_processor(proc);
+                runtime.set_shared(shared);
             }
             builder.add_window(window_id.clone(), Arc::new(Mutex::new(runtime)));
         }
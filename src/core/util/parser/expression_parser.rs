@@ -11,6 +11,7 @@ use crate::core::event::value::AttributeValue as CoreAttributeValue;
 use crate::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
 use crate::core::executor::{
     cast_executor::CastExecutor,
+    collection_aggregation_executor::CollectionAggregationExecutor,
     condition::*,
     constant_expression_executor::ConstantExpressionExecutor,
     expression_executor::ExpressionExecutor,
@@ -22,11 +23,19 @@ use crate::core::executor::{
     EventVariableFunctionExecutor, MultiValueVariableFunctionExecutor,
 };
 use crate::core::query::processor::ProcessingMode;
-use crate::core::query::selector::attribute::aggregator::AttributeAggregatorExpressionExecutor;
+use crate::core::query::selector::attribute::aggregator::{
+    AttributeAggregatorExpressionExecutor, ModifiedAttributeAggregatorExecutor,
+    MultiArgAggregatorExecutor,
+};
+use crate::core::query::selector::{
+    AnalyticFunction, AnalyticFunctionExecutor, OrderByEventComparator,
+};
 use crate::query_api::{
     definition::attribute::Type as ApiAttributeType, // Import Type enum
+    execution::query::selection::Order as ApiOrder,
     expression::{
-        constant::ConstantValueWithFloat as ApiConstantValue, Expression as ApiExpression,
+        constant::ConstantValueWithFloat as ApiConstantValue, AnalyticWindow,
+        AttributeFunction as ApiAttributeFunction, Expression as ApiExpression,
     },
 };
 
@@ -421,6 +430,10 @@ pub fn parse_expression<'a>(
             )))
         }
         ApiExpression::AttributeFunction(api_func) => {
+            if let Some(exec) = parse_collection_aggregation(api_func, context)? {
+                return Ok(exec);
+            }
+
             let mut arg_execs: Vec<Box<dyn ExpressionExecutor>> = Vec::new();
             for arg_expr in &api_func.parameters {
                 arg_execs.push(parse_expression(arg_expr, context)?);
@@ -436,6 +449,16 @@ pub fn parse_expression<'a>(
                 api_func.function_name.clone()
             };
 
+            if let Some(over) = &api_func.over {
+                return parse_analytic_function(
+                    api_func,
+                    over,
+                    &function_lookup_name,
+                    arg_execs,
+                    context,
+                );
+            }
+
             // Handle special variable functions not implemented via factories
             match (
                 api_func.extension_namespace.as_deref(),
@@ -543,11 +566,64 @@ pub fn parse_expression<'a>(
                 }
                 // All other functions (aggregators, scalar functions, scripts) are looked up from registry
                 _ => {
-                    if let Some(factory) = context
+                    let aggregator_factory = context
                         .eventflux_app_context
                         .get_eventflux_context()
-                        .get_attribute_aggregator_factory(&function_lookup_name)
+                        .get_attribute_aggregator_factory(&function_lookup_name);
+                    if aggregator_factory.is_none()
+                        && (api_func.distinct || api_func.filter.is_some())
                     {
+                        return Err(ExpressionParseError::new(
+                            format!(
+                                "DISTINCT and FILTER are only allowed on aggregate functions, not {function_lookup_name}"
+                            ),
+                            &api_func.eventflux_element,
+                            context.query_name,
+                        ));
+                    }
+                    if let Some(factory) = aggregator_factory {
+                        if arg_execs.len() > factory.arity() {
+                            return Err(ExpressionParseError::new(
+                                format!(
+                                    "{function_lookup_name}() takes at most {} argument(s), got {}",
+                                    factory.arity(),
+                                    arg_execs.len()
+                                ),
+                                &api_func.eventflux_element,
+                                context.query_name,
+                            ));
+                        }
+                        let filter_exec = match &api_func.filter {
+                            Some(filter) => {
+                                let exec = parse_expression(filter, context)?;
+                                if exec.get_return_type() != ApiAttributeType::BOOL {
+                                    return Err(ExpressionParseError::new(
+                                        format!(
+                                            "FILTER condition of {function_lookup_name} must be BOOL, found {:?}",
+                                            exec.get_return_type()
+                                        ),
+                                        &api_func.eventflux_element,
+                                        context.query_name,
+                                    ));
+                                }
+                                Some(exec)
+                            }
+                            None => None,
+                        };
+                        let distinct_execs: Option<Vec<Box<dyn ExpressionExecutor>>> =
+                            api_func.distinct.then(|| {
+                                arg_execs
+                                    .iter()
+                                    .map(|e| e.clone_executor(&context.eventflux_app_context))
+                                    .collect()
+                            });
+                        let dispatch_args: Option<Vec<Box<dyn ExpressionExecutor>>> =
+                            (arg_execs.len() > 1).then(|| {
+                                arg_execs
+                                    .iter()
+                                    .map(|e| e.clone_executor(&context.eventflux_app_context))
+                                    .collect()
+                            });
                         let mut exec = factory.create();
                         exec.init(
                             arg_execs,
@@ -562,6 +638,21 @@ pub fn parse_expression<'a>(
                                 context.query_name,
                             )
                         })?;
+                        if let Some(args) = dispatch_args {
+                            exec = Box::new(MultiArgAggregatorExecutor::new(
+                                exec,
+                                args,
+                                &context.eventflux_query_context,
+                            ));
+                        }
+                        if filter_exec.is_some() || distinct_execs.is_some() {
+                            exec = Box::new(ModifiedAttributeAggregatorExecutor::new(
+                                exec,
+                                filter_exec,
+                                distinct_execs,
+                                &context.eventflux_query_context,
+                            ));
+                        }
                         Ok(Box::new(AttributeAggregatorExpressionExecutor::new(exec)))
                     } else if let Some(scalar_fn_factory) = context
                         .eventflux_app_context
@@ -591,6 +682,7 @@ pub fn parse_expression<'a>(
                                 Box::new(ScriptFunctionExecutor::new(
                                     function_lookup_name.clone(),
                                     script_fn.return_type,
+                                    script_fn.script.clone(),
                                 )),
                                 arg_execs,
                                 Arc::clone(&context.eventflux_app_context),
@@ -722,6 +814,144 @@ pub fn parse_expression<'a>(
     }
 }
 
+/// Parse `fn(...) OVER (...)`. The select processor evaluates the function
+/// per chunk; the returned executor reads the value of the projected event.
+fn parse_analytic_function(
+    api_func: &ApiAttributeFunction,
+    over: &AnalyticWindow,
+    function_name: &str,
+    arg_execs: Vec<Box<dyn ExpressionExecutor>>,
+    context: &ExpressionParserContext<'_>,
+) -> ExpressionParseResult<Box<dyn ExpressionExecutor>> {
+    let error = |message: String| {
+        ExpressionParseError::new(message, &api_func.eventflux_element, context.query_name)
+    };
+    if api_func.distinct || api_func.filter.is_some() {
+        return Err(error(format!(
+            "DISTINCT and FILTER are not supported with OVER ({function_name})"
+        )));
+    }
+
+    let mut partition_execs = Vec::with_capacity(over.partition_by.len());
+    for expr in &over.partition_by {
+        partition_execs.push(parse_expression(expr, context)?);
+    }
+    let order_comparator = if over.order_by.is_empty() {
+        None
+    } else {
+        let mut order_execs = Vec::with_capacity(over.order_by.len());
+        for ob in &over.order_by {
+            order_execs.push(parse_expression(ob.get_expression(), context)?);
+        }
+        let ascending = over
+            .order_by
+            .iter()
+            .map(|ob| *ob.get_order() == ApiOrder::Asc)
+            .collect();
+        Some(OrderByEventComparator::new(order_execs, ascending))
+    };
+
+    let function = Arc::new(
+        AnalyticFunction::new(
+            function_name,
+            arg_execs,
+            partition_execs,
+            over.order_by.clone(),
+            order_comparator,
+            &context.eventflux_query_context,
+        )
+        .map_err(error)?,
+    );
+    context
+        .eventflux_query_context
+        .register_analytic_function(Arc::clone(&function));
+    Ok(Box::new(AnalyticFunctionExecutor::new(function)))
+}
+
+/// Parse an aggregation over a count-quantified pattern capture, such as
+/// `first(e1.user)` or `count(e1)` in `FROM PATTERN (e1=Login{3,5} -> ...)`
+///
+/// The registered [`CollectionAggregationFunction`](crate::core::extension::CollectionAggregationFunction)
+/// is evaluated by the pattern terminal on every match; the returned executor
+/// reads its result from the flattened match. Returns `None` for any other call.
+fn parse_collection_aggregation(
+    api_func: &ApiAttributeFunction,
+    context: &ExpressionParserContext<'_>,
+) -> ExpressionParseResult<Option<Box<dyn ExpressionExecutor>>> {
+    let [ApiExpression::Variable(var)] = api_func.parameters.as_slice() else {
+        return Ok(None);
+    };
+    if api_func.over.is_some()
+        || api_func
+            .extension_namespace
+            .as_deref()
+            .is_some_and(|ns| !ns.is_empty())
+    {
+        return Ok(None);
+    }
+    // `e1.user` aggregates an attribute, `count(e1)` the events themselves
+    let (alias, attribute) = match var.stream_id.as_deref() {
+        Some(alias) => (alias, Some(var.attribute_name.as_str())),
+        None => (var.attribute_name.as_str(), None),
+    };
+    let query_ctx = &context.eventflux_query_context;
+    let Some((chain_index, definition)) = query_ctx.pattern_capture(alias) else {
+        return Ok(None);
+    };
+    let eventflux_context = context.eventflux_app_context.get_eventflux_context();
+    let Some(function) = eventflux_context
+        .list_collection_aggregation_function_names()
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(&api_func.function_name))
+        .and_then(|name| eventflux_context.get_collection_aggregation_function(&name))
+    else {
+        return Ok(None);
+    };
+
+    let error = |message: String| {
+        ExpressionParseError::new(message, &api_func.eventflux_element, context.query_name)
+    };
+    if api_func.distinct || api_func.filter.is_some() {
+        return Err(error(format!(
+            "DISTINCT and FILTER are not supported on {}() over pattern collection '{alias}'",
+            api_func.function_name
+        )));
+    }
+    let (position, input_type) = match attribute {
+        Some(name) => {
+            let attributes = &definition.abstract_definition.attribute_list;
+            let index = attributes
+                .iter()
+                .position(|a| a.get_name() == name)
+                .ok_or_else(|| error(format!("Variable '{alias}.{name}' not found")))?;
+            (
+                Some([
+                    crate::core::util::eventflux_constants::BEFORE_WINDOW_DATA_INDEX as i32,
+                    index as i32,
+                ]),
+                *attributes[index].get_type(),
+            )
+        }
+        None => (None, ApiAttributeType::LONG),
+    };
+    let executor = CollectionAggregationExecutor::new(chain_index, position, function, input_type)
+        .map_err(error)?;
+    let return_type = executor.get_return_type();
+    let Some(index) = query_ctx.register_collection_aggregation(executor) else {
+        return Ok(None);
+    };
+    Ok(Some(Box::new(VariableExpressionExecutor::new(
+        [
+            0,
+            0,
+            crate::core::util::eventflux_constants::BEFORE_WINDOW_DATA_INDEX as i32,
+            index as i32,
+        ],
+        return_type,
+        format!("{}({alias})", api_func.function_name),
+    ))))
+}
+
 fn convert_api_constant_to_core_attribute_value(
     api_val: &ApiConstantValue,
 ) -> (CoreAttributeValue, ApiAttributeType) {
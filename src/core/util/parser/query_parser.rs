@@ -5,21 +5,31 @@ use super::expression_parser::{parse_expression, ExpressionParserContext};
 use crate::core::config::eventflux_app_context::EventFluxAppContext;
 use crate::core::config::eventflux_query_context::EventFluxQueryContext;
 use crate::core::event::stream::meta_stream_event::MetaStreamEvent;
+use crate::core::executor::collection_aggregation_executor::{
+    CollectionAggregationExecutor, PatternCollections,
+};
+use crate::core::executor::expression_executor::ExpressionExecutor;
+use crate::core::executor::variable_expression_executor::VariableExpressionExecutor;
 use crate::core::query::input::stream::join::{JoinProcessor, JoinSide, TableJoinProcessor};
 use crate::core::query::output::insert_into_stream_processor::InsertIntoStreamProcessor;
+use crate::core::query::output::ratelimit::{SnapshotOutputRateLimiter, TimeOutputRateLimiter};
 use crate::core::query::processor::stream::filter::FilterProcessor;
 use crate::core::query::processor::stream::window::create_window_processor;
 use crate::core::query::processor::Processor; // Trait
 use crate::core::query::query_runtime::QueryRuntime;
 use crate::core::query::selector::attribute::OutputAttributeProcessor; // OAP
 use crate::core::query::selector::select_processor::{OutputRateLimiter, SelectProcessor};
-use crate::core::query::selector::{GroupByKeyGenerator, OrderByEventComparator};
+use crate::core::query::selector::{AnalyticStage, GroupByKeyGenerator, OrderByEventComparator};
 use crate::core::stream::stream_junction::StreamJunction;
+use crate::core::util::eventflux_constants::STATE_OUTPUT_DATA_INDEX;
+use crate::core::window::NamedWindowTable;
 use crate::query_api::{
     definition::Attribute as ApiAttribute, // For constructing output attributes
     definition::StreamDefinition as ApiStreamDefinition,
+    execution::query::input::handler::StreamHandler as ApiStreamHandler,
     execution::query::input::InputStream as ApiInputStream,
     execution::query::Query as ApiQuery,
+    execution::query::Selector as ApiSelector,
     expression::Expression as ApiExpression, // Added this import
 };
 use std::collections::HashMap;
@@ -36,6 +46,10 @@ use crate::core::query::input::stream::state::PreStateProcessor;
 /// 1. Receives completed StateEvent from the last pattern PostStateProcessor
 /// 2. Flattens all StreamEvents into a single StreamEvent
 /// 3. Forwards the flattened event to the Processor chain (SelectProcessor, etc.)
+///
+/// Collection aggregations over count-quantified captures need every event of
+/// a capture, so they are evaluated on the StateEvent and their results are
+/// appended after the capture attributes.
 #[derive(Debug)]
 struct TerminalPostStateProcessor {
     state_id: usize,
@@ -43,6 +57,7 @@ struct TerminalPostStateProcessor {
     output_processor: Option<Arc<Mutex<dyn Processor>>>,
     is_event_returned: bool,
     total_attr_count: usize,
+    collection_aggregations: Vec<CollectionAggregationExecutor>,
 }
 
 impl TerminalPostStateProcessor {
@@ -53,6 +68,7 @@ impl TerminalPostStateProcessor {
             output_processor: None,
             is_event_returned: false,
             total_attr_count,
+            collection_aggregations: Vec::new(),
         }
     }
 
@@ -60,6 +76,10 @@ impl TerminalPostStateProcessor {
         self.output_processor = Some(processor);
     }
 
+    fn set_collection_aggregations(&mut self, aggregations: Vec<CollectionAggregationExecutor>) {
+        self.collection_aggregations = aggregations;
+    }
+
     /// Flatten StateEvent into a single StreamEvent
     /// Copies all attributes from each position's StreamEvent
     fn flatten_state_event(
@@ -86,6 +106,17 @@ impl TerminalPostStateProcessor {
             }
         }
 
+        if !self.collection_aggregations.is_empty() {
+            flattened_data.resize(self.total_attr_count, AttributeValue::Null);
+            for aggregation in &self.collection_aggregations {
+                flattened_data.push(
+                    aggregation
+                        .execute(Some(state_event))
+                        .unwrap_or(AttributeValue::Null),
+                );
+            }
+        }
+
         // Create the flattened StreamEvent
         let mut result = StreamEvent::new_with_data(timestamp, flattened_data);
         result.event_type = state_event.event_type;
@@ -327,8 +358,12 @@ impl QueryParser {
                     .right_input_stream
                     .get_stream_id_str()
                     .to_string();
-                let left_is_table = table_def_map.contains_key(&left_id);
-                let right_is_table = table_def_map.contains_key(&right_id);
+                // Named windows join like tables, looked up against their current contents
+                let named_window = |id: &str| eventflux_app_context.get_named_window(id);
+                let left_is_table =
+                    table_def_map.contains_key(&left_id) || named_window(&left_id).is_some();
+                let right_is_table =
+                    table_def_map.contains_key(&right_id) || named_window(&right_id).is_some();
 
                 if left_is_table ^ right_is_table {
                     // stream-table join
@@ -342,23 +377,38 @@ impl QueryParser {
                         .ok_or_else(|| format!("Input stream '{stream_id}' not found"))?
                         .clone();
                     let stream_def = stream_junction.lock().unwrap().get_stream_definition();
-                    let table_def = table_def_map
-                        .get(&table_id)
-                        .ok_or_else(|| format!("Table definition '{table_id}' not found"))?
-                        .clone();
-                    let table = eventflux_app_context
-                        .get_eventflux_context()
-                        .get_table(&table_id)
-                        .ok_or_else(|| format!("Table '{table_id}' not found"))?;
+                    let (table_abstract_def, table): (_, Arc<dyn crate::core::table::Table>) =
+                        if let Some(window) = named_window(&table_id) {
+                            let window_stream_def = stream_junction_map
+                                .get(&table_id)
+                                .ok_or_else(|| format!("Window '{table_id}' not found"))?
+                                .lock()
+                                .unwrap()
+                                .get_stream_definition();
+                            (
+                                window_stream_def.abstract_definition.clone(),
+                                Arc::new(NamedWindowTable::new(table_id.clone(), window)),
+                            )
+                        } else {
+                            let table_def = table_def_map.get(&table_id).ok_or_else(|| {
+                                format!("Table definition '{table_id}' not found")
+                            })?;
+                            let table = eventflux_app_context
+                                .get_eventflux_context()
+                                .get_table(&table_id)
+                                .ok_or_else(|| format!("Table '{table_id}' not found"))?;
+                            (table_def.abstract_definition.clone(), table)
+                        };
 
                     let stream_len = stream_def.abstract_definition.attribute_list.len();
-                    let table_len = table_def.abstract_definition.attribute_list.len();
+                    let table_len = table_abstract_def.attribute_list.len();
 
                     let mut stream_meta = MetaStreamEvent::new_for_single_input(stream_def.clone());
                     let table_stream_def = Arc::new(
                         crate::query_api::definition::stream_definition::StreamDefinition {
-                            abstract_definition: table_def.abstract_definition.clone(),
+                            abstract_definition: table_abstract_def,
                             with_config: None, // Tables don't use SQL WITH config
+                            changes_of: None,
                         },
                     );
                     let mut table_meta = MetaStreamEvent::new_for_single_input(table_stream_def);
@@ -407,7 +457,16 @@ impl QueryParser {
                     };
 
                     let comp_cond = if let Some(expr) = &join_stream.on_compare {
-                        table.compile_join_condition(expr.clone(), &stream_id, &stream_def)
+                        // Conditions qualify stream columns by the alias when one is given
+                        let stream_input = if stream_on_left {
+                            &join_stream.left_input_stream
+                        } else {
+                            &join_stream.right_input_stream
+                        };
+                        let stream_ref = stream_input
+                            .get_stream_reference_id_str()
+                            .unwrap_or(&stream_id);
+                        table.compile_join_condition(expr.clone(), stream_ref, &stream_def)
                     } else {
                         None
                     };
@@ -920,6 +979,26 @@ impl QueryParser {
                 let table_meta_map: HashMap<String, Arc<MetaStreamEvent>> = HashMap::new();
                 let default_source = all_elements[0].stream_id.clone();
 
+                if let PatternType::Sequence(elements) = &pattern_type {
+                    let mut collections = PatternCollections::new(total_attr_count);
+                    for (idx, elem) in elements.iter().enumerate() {
+                        if elem.min_count == 1 && elem.max_count == 1 {
+                            continue;
+                        }
+                        let junction =
+                            stream_junction_map.get(&elem.stream_id).ok_or_else(|| {
+                                format!("Input stream '{}' not found", elem.stream_id)
+                            })?;
+                        let stream_def = junction.lock().unwrap().get_stream_definition();
+                        collections.add_capture(
+                            elem.alias.clone().unwrap_or_else(|| elem.stream_id.clone()),
+                            idx,
+                            stream_def,
+                        );
+                    }
+                    eventflux_query_context.set_pattern_collections(collections);
+                }
+
                 // Handle patterns based on type
                 match &pattern_type {
                     PatternType::Sequence(elements) => {
@@ -1342,6 +1421,12 @@ impl QueryParser {
         };
 
         // 5. Selector (Projections)
+        // Analytic functions are evaluated by the select processor only
+        if !eventflux_query_context.take_analytic_functions().is_empty() {
+            return Err(format!(
+                "Query '{query_name}': analytic functions (OVER) are only allowed in SELECT and HAVING"
+            ));
+        }
         let api_selector = &api_query.selector; // Selector is not Option in query_api::Query
         let mut oaps = Vec::new();
         let mut output_attributes_for_def = Vec::new();
@@ -1386,12 +1471,23 @@ impl QueryParser {
         } else {
             None
         };
+        let analytic_functions = eventflux_query_context.take_analytic_functions();
+        let analytic_stage = if analytic_functions.is_empty() {
+            None
+        } else {
+            Some(
+                AnalyticStage::new(analytic_functions)
+                    .map_err(|e| format!("Query '{query_name}': {e}"))?,
+            )
+        };
 
         let mut group_execs = Vec::new();
-        for var in &api_selector.group_by_list {
-            let expr = ApiExpression::Variable(var.clone());
-            group_execs
-                .push(parse_expression(&expr, &expr_parser_context).map_err(|e| e.to_string())?);
+        for expr in &api_selector.group_by_list {
+            group_execs.push(Self::parse_group_by_expression(
+                expr,
+                api_selector,
+                &expr_parser_context,
+            )?);
         }
         let group_by_key_generator = if group_execs.is_empty() {
             None
@@ -1402,9 +1498,32 @@ impl QueryParser {
         let mut order_execs = Vec::new();
         let mut order_flags = Vec::new();
         for ob in &api_selector.order_by_list {
-            let expr = ApiExpression::Variable(ob.get_variable().clone());
-            order_execs
-                .push(parse_expression(&expr, &expr_parser_context).map_err(|e| e.to_string())?);
+            // Select aliases and expressions repeated from SELECT (including
+            // aggregates) sort on the already computed output column
+            let order_exec: Box<dyn ExpressionExecutor> = match Self::output_column_of(
+                ob.get_expression(),
+                api_selector,
+            ) {
+                Some(idx) => {
+                    let attr = &select_output_stream_def.abstract_definition.attribute_list[idx];
+                    Box::new(VariableExpressionExecutor::new(
+                        [0, 0, STATE_OUTPUT_DATA_INDEX as i32, idx as i32],
+                        attr.attribute_type,
+                        attr.name.clone(),
+                    ))
+                }
+                None => {
+                    let exec = parse_expression(ob.get_expression(), &expr_parser_context)
+                        .map_err(|e| e.to_string())?;
+                    if exec.is_attribute_aggregator() {
+                        return Err(format!(
+                                "Query '{query_name}': aggregate in ORDER BY must also appear in the SELECT list"
+                            ));
+                    }
+                    exec
+                }
+            };
+            order_execs.push(order_exec);
             order_flags.push(*ob.get_order() == crate::query_api::execution::query::selection::order_by_attribute::Order::Asc);
         }
         let order_by_comparator = if order_execs.is_empty() {
@@ -1412,8 +1531,15 @@ impl QueryParser {
         } else {
             Some(OrderByEventComparator::new(order_execs, order_flags))
         };
+        if !eventflux_query_context.take_analytic_functions().is_empty() {
+            return Err(format!(
+                "Query '{query_name}': analytic functions (OVER) in ORDER BY must also appear in the SELECT list"
+            ));
+        }
+
+        let contains_aggregator = oaps.iter().any(|oap| oap.is_aggregator());
 
-        let select_processor = Arc::new(Mutex::new(SelectProcessor::new(
+        let mut select_processor = SelectProcessor::new(
             api_selector,
             true,
             true,
@@ -1425,19 +1551,76 @@ impl QueryParser {
             group_by_key_generator,
             order_by_comparator,
             None,
-        )));
+        );
+        if let Some(stage) = analytic_stage {
+            select_processor = select_processor.with_analytic_stage(stage);
+        }
+        let select_processor = Arc::new(Mutex::new(select_processor));
         link_processor(select_processor.clone());
 
         if let Some(rate) = api_query.get_output_rate() {
-            if let crate::query_api::execution::query::output::ratelimit::OutputRateVariant::Events(ev, beh) = &rate.variant {
-                let limiter = Arc::new(Mutex::new(OutputRateLimiter::new(
-                    None,
-                    Arc::clone(eventflux_app_context),
-                    Arc::clone(&eventflux_query_context),
-                    ev.event_count as usize,
-                    *beh,
-                )));
-                link_processor(limiter);
+            use crate::query_api::execution::query::output::ratelimit::OutputRateVariant;
+            match &rate.variant {
+                OutputRateVariant::Events(ev, beh) => {
+                    let limiter = Arc::new(Mutex::new(OutputRateLimiter::new(
+                        None,
+                        Arc::clone(eventflux_app_context),
+                        Arc::clone(&eventflux_query_context),
+                        ev.event_count as usize,
+                        *beh,
+                    )));
+                    link_processor(limiter);
+                }
+                OutputRateVariant::Time(time, beh) => {
+                    let limiter = Arc::new(Mutex::new(TimeOutputRateLimiter::new(
+                        Arc::clone(eventflux_app_context),
+                        Arc::clone(&eventflux_query_context),
+                        time.time_value_millis,
+                        *beh,
+                    )));
+                    link_processor(limiter);
+                }
+                OutputRateVariant::Snapshot(snapshot) => {
+                    // The snapshot limiter keys retained rows by the same GROUP BY
+                    // expressions as the selector
+                    let mut snapshot_group_execs = Vec::new();
+                    for expr in &api_selector.group_by_list {
+                        snapshot_group_execs.push(Self::parse_group_by_expression(
+                            expr,
+                            api_selector,
+                            &expr_parser_context,
+                        )?);
+                    }
+                    let snapshot_key_generator = if snapshot_group_execs.is_empty() {
+                        None
+                    } else {
+                        Some(Arc::new(GroupByKeyGenerator::new(snapshot_group_execs)))
+                    };
+                    // Joins and windowed inputs (including named windows) retract
+                    // rows through EXPIRED events
+                    let windowed = match input_stream_api {
+                        ApiInputStream::Single(single_in_stream) => {
+                            single_in_stream
+                                .get_stream_handlers()
+                                .iter()
+                                .any(|handler| matches!(handler, ApiStreamHandler::Window(_)))
+                                || eventflux_app_context
+                                    .get_named_window(&expr_parser_context.default_source)
+                                    .is_some()
+                        }
+                        ApiInputStream::Join(_) => true,
+                        _ => false,
+                    };
+                    let limiter = Arc::new(Mutex::new(SnapshotOutputRateLimiter::new(
+                        Arc::clone(eventflux_app_context),
+                        Arc::clone(&eventflux_query_context),
+                        snapshot.time_value_millis,
+                        contains_aggregator,
+                        windowed,
+                        snapshot_key_generator,
+                    )));
+                    link_processor(limiter);
+                }
             }
         }
 
@@ -1519,12 +1702,13 @@ impl QueryParser {
         // For N-element patterns, connect the terminal's output to the processor chain head
         // This bridges the PostStateProcessor chain to the Processor chain
         if let Some(ref terminal) = n_element_terminal {
+            let mut terminal = terminal.lock().unwrap();
             if let Some(ref head) = processor_chain_head {
-                terminal
-                    .lock()
-                    .unwrap()
-                    .set_output_processor(Arc::clone(head));
+                terminal.set_output_processor(Arc::clone(head));
             }
+            terminal.set_collection_aggregations(
+                eventflux_query_context.take_collection_aggregations(),
+            );
         }
 
         // 7. Create QueryRuntime
@@ -1540,7 +1724,13 @@ impl QueryParser {
         // NOTE: For logical patterns, skip this - they subscribe their processors directly to junctions
         if n_element_terminal.is_none() && !is_logical_pattern {
             if let Some(head_proc_arc) = &query_runtime.processor_chain_head {
-                if let Some(junction) = stream_junction_map.get(&expr_parser_context.default_source)
+                // Queries reading a named window consume the window's output, not its input
+                if let Some(window) =
+                    eventflux_app_context.get_named_window(&expr_parser_context.default_source)
+                {
+                    window.subscribe(Arc::clone(head_proc_arc));
+                } else if let Some(junction) =
+                    stream_junction_map.get(&expr_parser_context.default_source)
                 {
                     junction
                         .lock()
@@ -1552,4 +1742,56 @@ impl QueryParser {
 
         Ok(query_runtime)
     }
+
+    /// Parse a GROUP BY key. A bare name that is not an input attribute may
+    /// refer to a select alias, in which case the aliased expression is used.
+    fn parse_group_by_expression(
+        expr: &ApiExpression,
+        api_selector: &ApiSelector,
+        context: &ExpressionParserContext<'_>,
+    ) -> Result<Box<dyn ExpressionExecutor>, String> {
+        let exec = match parse_expression(expr, context) {
+            Ok(exec) => exec,
+            Err(e) => {
+                let aliased = match expr {
+                    ApiExpression::Variable(v) if v.stream_id.is_none() => api_selector
+                        .selection_list
+                        .iter()
+                        .find(|oa| oa.rename.as_deref() == Some(v.attribute_name.as_str())),
+                    _ => None,
+                };
+                match aliased {
+                    Some(oa) => {
+                        parse_expression(&oa.expression, context).map_err(|e| e.to_string())?
+                    }
+                    None => return Err(e.to_string()),
+                }
+            }
+        };
+        if exec.is_attribute_aggregator() {
+            return Err(format!(
+                "Query '{}': aggregate functions are not allowed in GROUP BY",
+                context.query_name
+            ));
+        }
+        Ok(exec)
+    }
+
+    /// Index of the select output column an ORDER BY key refers to, either by
+    /// alias or by repeating the selected expression
+    fn output_column_of(expr: &ApiExpression, api_selector: &ApiSelector) -> Option<usize> {
+        let selection = &api_selector.selection_list;
+        if let ApiExpression::Variable(v) = expr {
+            if v.stream_id.is_none() {
+                if let Some(idx) = selection.iter().position(|oa| {
+                    oa.rename.as_deref() == Some(v.attribute_name.as_str())
+                        || matches!(&oa.expression, ApiExpression::Variable(sv)
+                            if oa.rename.is_none() && sv.attribute_name == v.attribute_name)
+                }) {
+                    return Some(idx);
+                }
+            }
+        }
+        selection.iter().position(|oa| &oa.expression == expr)
+    }
 }
@@ -51,6 +51,18 @@ impl ThreadBarrier {
         *locked = false;
         self.cvar.notify_all();
     }
+
+    /// Run `f` while no event is being processed: new threads wait at the
+    /// barrier and the active ones are drained first.
+    pub fn paused<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.lock();
+        while self.get_active_threads() > 0 {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        let result = f();
+        self.unlock();
+        result
+    }
 }
 
 impl Default for ThreadBarrier {
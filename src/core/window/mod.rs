@@ -4,6 +4,8 @@
 
 //! Window related runtime structures.
 
+pub mod named_window;
 pub mod window_runtime;
 
+pub use named_window::{NamedWindowOutputProcessor, NamedWindowShared, NamedWindowTable};
 pub use window_runtime::WindowRuntime;
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Shared output side of a named window (`CREATE WINDOW`).
+//!
+//! The window processor of a named window feeds a single
+//! [`NamedWindowOutputProcessor`], which fans window output out to every query
+//! reading `FROM` the window. Joins look the window up through
+//! [`NamedWindowTable`], which reads the window processor's own buffer; the
+//! processor's state holder is the only snapshot of the window's contents.
+
+use crate::core::config::eventflux_app_context::EventFluxAppContext;
+use crate::core::config::eventflux_query_context::EventFluxQueryContext;
+use crate::core::event::complex_event::{ComplexEvent, ComplexEventType};
+use crate::core::event::stream::stream_event::StreamEvent;
+use crate::core::event::value::AttributeValue;
+use crate::core::exception::EventFluxError;
+use crate::core::query::processor::stream::window::WindowContents;
+use crate::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
+use crate::core::table::{CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition, Table};
+use crate::query_api::execution::query::output::OutputEventType;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Contents view and readers of a named window, shared by its output
+/// processor and table view
+#[derive(Debug, Default)]
+pub struct NamedWindowShared {
+    /// Buffer of the window processor; `None` when the window holds no events
+    contents: Option<Arc<dyn WindowContents>>,
+    /// Entry processors of the queries reading from the window
+    subscribers: RwLock<Vec<Arc<Mutex<dyn Processor>>>>,
+}
+
+impl NamedWindowShared {
+    pub fn new(contents: Option<Arc<dyn WindowContents>>) -> Self {
+        Self {
+            contents,
+            subscribers: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn subscribe(&self, processor: Arc<Mutex<dyn Processor>>) {
+        self.subscribers.write().unwrap().push(processor);
+    }
+
+    /// Attribute rows of the events currently in the window
+    pub fn rows(&self) -> Vec<Vec<AttributeValue>> {
+        self.contents
+            .as_ref()
+            .map(|contents| {
+                contents
+                    .events()
+                    .into_iter()
+                    .map(|e| e.before_window_data)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Terminal processor of a named window's processing chain
+#[derive(Debug)]
+pub struct NamedWindowOutputProcessor {
+    meta: CommonProcessorMeta,
+    output_event_type: OutputEventType,
+    shared: Arc<NamedWindowShared>,
+}
+
+impl NamedWindowOutputProcessor {
+    pub fn new(
+        eventflux_app_context: Arc<EventFluxAppContext>,
+        eventflux_query_context: Arc<EventFluxQueryContext>,
+        output_event_type: OutputEventType,
+        shared: Arc<NamedWindowShared>,
+    ) -> Self {
+        Self {
+            meta: CommonProcessorMeta::new(eventflux_app_context, eventflux_query_context),
+            output_event_type,
+            shared,
+        }
+    }
+
+    pub fn shared(&self) -> Arc<NamedWindowShared> {
+        Arc::clone(&self.shared)
+    }
+
+    fn is_published(&self, event_type: ComplexEventType) -> bool {
+        match event_type {
+            // RESET keeps downstream aggregators in step with batch windows
+            ComplexEventType::Reset => true,
+            ComplexEventType::Current => !matches!(
+                self.output_event_type,
+                OutputEventType::ExpiredEvents | OutputEventType::ExpiredRawEvents
+            ),
+            ComplexEventType::Expired => {
+                !matches!(self.output_event_type, OutputEventType::CurrentEvents)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn link_events(events: &[StreamEvent]) -> Option<Box<dyn ComplexEvent>> {
+    let mut head: Option<Box<dyn ComplexEvent>> = None;
+    let mut tail = &mut head;
+    for event in events {
+        *tail = Some(Box::new(event.clone_without_next()));
+        if let Some(ref mut t) = *tail {
+            tail = t.mut_next_ref_option();
+        }
+    }
+    head
+}
+
+impl Processor for NamedWindowOutputProcessor {
+    fn process(&self, complex_event_chunk: Option<Box<dyn ComplexEvent>>) {
+        let mut published = Vec::new();
+        let mut current = complex_event_chunk;
+        while let Some(mut ev) = current {
+            current = ev.set_next(None);
+            if let Some(se) = ev.as_any().downcast_ref::<StreamEvent>() {
+                if self.is_published(se.event_type) {
+                    published.push(se.clone_without_next());
+                }
+            }
+        }
+
+        if published.is_empty() {
+            return;
+        }
+        let subscribers = self.shared.subscribers.read().unwrap().clone();
+        for subscriber in subscribers {
+            subscriber.lock().unwrap().process(link_events(&published));
+        }
+    }
+
+    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
+        None
+    }
+
+    fn set_next_processor(&mut self, _next_processor: Option<Arc<Mutex<dyn Processor>>>) {
+        // Readers subscribe through NamedWindowShared::subscribe
+    }
+
+    fn clone_processor(
+        &self,
+        eventflux_query_context: &Arc<EventFluxQueryContext>,
+    ) -> Box<dyn Processor> {
+        Box::new(NamedWindowOutputProcessor::new(
+            Arc::clone(&self.meta.eventflux_app_context),
+            Arc::clone(eventflux_query_context),
+            self.output_event_type,
+            Arc::clone(&self.shared),
+        ))
+    }
+
+    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
+        Arc::clone(&self.meta.eventflux_app_context)
+    }
+
+    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
+        self.meta.get_eventflux_query_context()
+    }
+
+    fn get_processing_mode(&self) -> ProcessingMode {
+        ProcessingMode::DEFAULT
+    }
+
+    fn is_stateful(&self) -> bool {
+        true
+    }
+}
+
+/// Read-only table view over a named window, used when a query joins with it
+#[derive(Debug, Clone)]
+pub struct NamedWindowTable {
+    window_id: String,
+    shared: Arc<NamedWindowShared>,
+}
+
+impl NamedWindowTable {
+    pub fn new(window_id: String, shared: Arc<NamedWindowShared>) -> Self {
+        Self { window_id, shared }
+    }
+
+    fn read_only(&self) -> EventFluxError {
+        EventFluxError::Other(format!(
+            "Named window '{}' is read-only in joins; use INSERT INTO to add events",
+            self.window_id
+        ))
+    }
+}
+
+impl Table for NamedWindowTable {
+    fn insert(&self, _values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        Err(self.read_only())
+    }
+
+    fn update(
+        &self,
+        _condition: &dyn CompiledCondition,
+        _update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        Err(self.read_only())
+    }
+
+    fn delete(&self, _condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Err(self.read_only())
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        let cond = condition
+            .as_any()
+            .downcast_ref::<InMemoryCompiledCondition>()
+            .ok_or_else(|| EventFluxError::Other("Invalid condition type".to_string()))?;
+        Ok(self.shared.rows().into_iter().find(|r| *r == cond.values))
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        Ok(self.find(condition)?.is_some())
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        Ok(self.shared.rows())
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(value: i32, event_type: ComplexEventType) -> StreamEvent {
+        let mut se = StreamEvent::new(0, 1, 0, 0);
+        se.before_window_data = vec![AttributeValue::Int(value)];
+        se.event_type = event_type;
+        se
+    }
+
+    #[derive(Debug)]
+    struct FixedContents(Vec<StreamEvent>);
+
+    impl WindowContents for FixedContents {
+        fn events(&self) -> Vec<StreamEvent> {
+            self.0.iter().map(|e| e.clone_without_next()).collect()
+        }
+    }
+
+    #[test]
+    fn test_rows_read_window_buffer() {
+        let contents = FixedContents(vec![event(2, ComplexEventType::Current)]);
+        let shared = NamedWindowShared::new(Some(Arc::new(contents)));
+        assert_eq!(shared.rows(), vec![vec![AttributeValue::Int(2)]]);
+        assert!(NamedWindowShared::new(None).rows().is_empty());
+
+        let table = NamedWindowTable::new("W".to_string(), Arc::new(shared));
+        assert_eq!(table.all_rows().unwrap().len(), 1);
+        assert!(table.insert(&[AttributeValue::Int(3)]).is_err());
+    }
+}
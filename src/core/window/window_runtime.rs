@@ -3,6 +3,7 @@
 // eventflux_rust/src/core/window/window_runtime.rs
 
 use crate::core::query::processor::Processor;
+use crate::core::window::named_window::NamedWindowShared;
 use crate::query_api::definition::WindowDefinition;
 use std::sync::{Arc, Mutex};
 
@@ -11,6 +12,8 @@ use std::sync::{Arc, Mutex};
 pub struct WindowRuntime {
     pub definition: Arc<WindowDefinition>,
     pub processor: Option<Arc<Mutex<dyn Processor>>>,
+    /// Contents and readers of the window, shared with the queries using it
+    pub shared: Option<Arc<NamedWindowShared>>,
     initialized: bool,
 }
 
@@ -19,6 +22,7 @@ impl WindowRuntime {
         Self {
             definition,
             processor: None,
+            shared: None,
             initialized: false,
         }
     }
@@ -27,6 +31,10 @@ impl WindowRuntime {
         self.processor = Some(processor);
     }
 
+    pub fn set_shared(&mut self, shared: Arc<NamedWindowShared>) {
+        self.shared = Some(shared);
+    }
+
     /// Perform one-time initialization for the window processor if present.
     pub fn initialize(&mut self) {
         if self.initialized {
@@ -2,7 +2,7 @@
 
 // Corresponds to io.eventflux.query.api.definition.FunctionDefinition
 use crate::query_api::annotation::Annotation;
-use crate::query_api::definition::attribute::Type as AttributeType;
+use crate::query_api::definition::attribute::{Attribute, Type as AttributeType};
 use crate::query_api::eventflux_element::EventFluxElement;
 
 #[derive(Clone, Debug, PartialEq, Default)] // Added Default
@@ -14,6 +14,8 @@ pub struct FunctionDefinition {
     pub language: String,
     pub body: String,
     pub return_type: AttributeType, // Default for AttributeType::OBJECT
+    /// Declared parameters, bound by name when the body runs
+    pub parameters: Vec<Attribute>,
     pub annotations: Vec<Annotation>,
 }
 
@@ -26,6 +28,7 @@ impl FunctionDefinition {
             language,
             body,
             return_type,
+            parameters: Vec::new(),
             annotations: Vec::new(),
         }
     }
@@ -62,6 +65,11 @@ impl FunctionDefinition {
         self
     }
 
+    pub fn parameter(mut self, name: String, attribute_type: AttributeType) -> Self {
+        self.parameters.push(Attribute::new(name, attribute_type));
+        self
+    }
+
     pub fn annotation(mut self, annotation: Annotation) -> Self {
         self.annotations.push(annotation);
         self
@@ -14,7 +14,7 @@ pub use self::aggregation_definition::AggregationDefinition; // Keep this
 pub use self::attribute::{Attribute, Type as AttributeType};
 pub use self::function_definition::FunctionDefinition;
 pub use self::stream_definition::StreamDefinition;
-pub use self::table_definition::TableDefinition;
+pub use self::table_definition::{TableDefinition, TableIndex};
 pub use self::trigger_definition::TriggerDefinition;
 pub use self::window_definition::WindowDefinition;
 pub use crate::query_api::aggregation::TimePeriod as AggregationTimePeriod; // Import directly
@@ -43,6 +43,10 @@ pub struct StreamDefinition {
     ///
     /// None if no WITH clause was specified in SQL.
     pub with_config: Option<crate::core::config::stream_config::FlatConfig>,
+
+    /// Table whose changes this stream carries
+    /// (`CREATE STREAM S AS CHANGES OF T`), if any
+    pub changes_of: Option<String>,
 }
 
 impl StreamDefinition {
@@ -51,6 +55,7 @@ impl StreamDefinition {
         StreamDefinition {
             abstract_definition: AbstractDefinition::new(id),
             with_config: None,
+            changes_of: None,
         }
     }
 
@@ -110,6 +115,12 @@ impl StreamDefinition {
         self
     }
 
+    /// Mark this stream as the change stream of table `table_id`
+    pub fn changes_of(mut self, table_id: String) -> Self {
+        self.changes_of = Some(table_id);
+        self
+    }
+
     /// Get reference to WITH configuration if present
     pub fn get_with_config(&self) -> Option<&crate::core::config::stream_config::FlatConfig> {
         self.with_config.as_ref()
@@ -23,6 +23,25 @@ pub struct TableDefinition {
     ///
     /// None if no WITH clause was specified in SQL.
     pub with_config: Option<crate::core::config::stream_config::FlatConfig>,
+
+    /// Columns of the `PRIMARY KEY`; empty if the table has none
+    pub primary_key: Vec<String>,
+
+    /// Secondary indexes declared with `CREATE INDEX`
+    pub indexes: Vec<TableIndex>,
+}
+
+/// A secondary index over one or more table columns
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TableIndex {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+impl TableIndex {
+    pub fn new(name: String, columns: Vec<String>) -> Self {
+        TableIndex { name, columns }
+    }
 }
 
 impl TableDefinition {
@@ -31,6 +50,8 @@ impl TableDefinition {
         TableDefinition {
             abstract_definition: AbstractDefinition::new(id),
             with_config: None,
+            primary_key: Vec::new(),
+            indexes: Vec::new(),
         }
     }
 
@@ -70,6 +91,26 @@ impl TableDefinition {
         self.with_config = Some(config);
         self
     }
+
+    /// Declare the primary key columns
+    pub fn primary_key(mut self, columns: Vec<String>) -> Self {
+        self.primary_key = columns;
+        self
+    }
+
+    /// Add a secondary index
+    pub fn index(mut self, index: TableIndex) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// Position of `column` in the attribute list
+    pub fn column_position(&self, column: &str) -> Option<usize> {
+        self.abstract_definition
+            .attribute_list
+            .iter()
+            .position(|attr| attr.get_name() == column)
+    }
 }
 
 // Provide access to AbstractDefinition fields and EventFluxElement fields
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::query_api::eventflux_element::EventFluxElement;
-use crate::query_api::expression::Variable;
+use crate::query_api::expression::{Expression, Variable};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)] // Added Eq, Hash, Copy
 #[derive(Default)]
@@ -11,24 +11,20 @@ pub enum Order {
     Desc,
 }
 
-#[derive(Clone, Debug, PartialEq)] // Default requires Variable to be Default
-#[derive(Default)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct OrderByAttribute {
     pub eventflux_element: EventFluxElement, // Composed EventFluxElement
 
     // OrderByAttribute fields
-    pub variable: Variable, // Variable struct has Default derive
+    /// Sort key: a plain attribute, a select alias, or any scalar/aggregate expression
+    pub expression: Expression,
     pub order: Order,
 }
 
 impl OrderByAttribute {
     // Constructor for `OrderByAttribute(Variable variable, Order order)`
     pub fn new(variable: Variable, order: Order) -> Self {
-        OrderByAttribute {
-            eventflux_element: EventFluxElement::default(), // Or copy from variable.eventflux_element?
-            variable,
-            order,
-        }
+        Self::new_expression(Expression::Variable(variable), order)
     }
 
     // Constructor for `OrderByAttribute(Variable variable)` which defaults to ASC
@@ -36,14 +32,28 @@ impl OrderByAttribute {
         Self::new(variable, Order::default())
     }
 
-    pub fn get_variable(&self) -> &Variable {
-        &self.variable
+    /// Order by an arbitrary expression (e.g. `ORDER BY price * volume DESC`)
+    pub fn new_expression(expression: Expression, order: Order) -> Self {
+        OrderByAttribute {
+            eventflux_element: EventFluxElement::default(),
+            expression,
+            order,
+        }
+    }
+
+    /// Returns the sort key when it is a plain variable
+    pub fn get_variable(&self) -> Option<&Variable> {
+        match &self.expression {
+            Expression::Variable(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn get_expression(&self) -> &Expression {
+        &self.expression
     }
 
     pub fn get_order(&self) -> &Order {
         &self.order
     }
 }
-
-// If Variable is Default, OrderByAttribute can derive Default.
-// Variable's Default requires attribute_name to be String::default().
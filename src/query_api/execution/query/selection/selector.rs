@@ -14,7 +14,7 @@ pub struct Selector {
 
     // Selector fields
     pub selection_list: Vec<OutputAttribute>,
-    pub group_by_list: Vec<Variable>,
+    pub group_by_list: Vec<Expression>,
     pub having_expression: Option<Expression>,
     pub order_by_list: Vec<OrderByAttribute>,
     pub limit: Option<Constant>,
@@ -120,12 +120,19 @@ impl Selector {
     }
 
     pub fn group_by(mut self, variable: Variable) -> Self {
-        self.group_by_list.push(variable);
+        self.group_by_list.push(Expression::Variable(variable));
         self
     }
 
-    pub fn add_group_by_list(mut self, mut list: Vec<Variable>) -> Self {
-        self.group_by_list.append(&mut list);
+    /// Group by an arbitrary scalar expression (e.g. `GROUP BY upper(symbol)`)
+    pub fn group_by_expression(mut self, expression: Expression) -> Self {
+        self.group_by_list.push(expression);
+        self
+    }
+
+    pub fn add_group_by_list(mut self, list: Vec<Variable>) -> Self {
+        self.group_by_list
+            .extend(list.into_iter().map(Expression::Variable));
         self
     }
 
@@ -146,6 +153,12 @@ impl Selector {
         self
     }
 
+    pub fn order_by_expression(mut self, expression: Expression, order: OrderByOrder) -> Self {
+        self.order_by_list
+            .push(OrderByAttribute::new_expression(expression, order));
+        self
+    }
+
     pub fn add_order_by_list(mut self, mut list: Vec<OrderByAttribute>) -> Self {
         self.order_by_list.append(&mut list);
         self
@@ -176,7 +189,7 @@ impl Selector {
         &self.selection_list
     }
 
-    pub fn get_group_by_list(&self) -> &Vec<Variable> {
+    pub fn get_group_by_list(&self) -> &Vec<Expression> {
         &self.group_by_list
     }
 
@@ -279,7 +292,7 @@ mod tests {
         let var = Variable::new("attr_group".to_string());
         let s = Selector::selector().group_by(var.clone());
         assert_eq!(s.get_group_by_list().len(), 1);
-        assert_eq!(s.get_group_by_list()[0], var);
+        assert_eq!(s.get_group_by_list()[0], Expression::Variable(var));
     }
 
     #[test]
@@ -302,7 +315,7 @@ mod tests {
         let var = Variable::new("attr_order".to_string());
         let s = Selector::selector().order_by(var.clone());
         assert_eq!(s.get_order_by_list().len(), 1);
-        assert_eq!(s.get_order_by_list()[0].get_variable(), &var);
+        assert_eq!(s.get_order_by_list()[0].get_variable(), Some(&var));
         assert_eq!(s.get_order_by_list()[0].get_order(), &OrderByOrder::Asc); // Default order
     }
 
@@ -311,10 +324,24 @@ mod tests {
         let var = Variable::new("attr_order_desc".to_string());
         let s = Selector::selector().order_by_with_order(var.clone(), OrderByOrder::Desc);
         assert_eq!(s.get_order_by_list().len(), 1);
-        assert_eq!(s.get_order_by_list()[0].get_variable(), &var);
+        assert_eq!(s.get_order_by_list()[0].get_variable(), Some(&var));
         assert_eq!(s.get_order_by_list()[0].get_order(), &OrderByOrder::Desc);
     }
 
+    #[test]
+    fn test_selector_expression_group_by_and_order_by() {
+        let expr = Expression::function_no_ns(
+            "upper".to_string(),
+            vec![Expression::variable("symbol".to_string())],
+        );
+        let s = Selector::selector()
+            .group_by_expression(expr.clone())
+            .order_by_expression(expr.clone(), OrderByOrder::Desc);
+        assert_eq!(s.get_group_by_list()[0], expr);
+        assert_eq!(s.get_order_by_list()[0].get_expression(), &expr);
+        assert!(s.get_order_by_list()[0].get_variable().is_none());
+    }
+
     #[test]
     fn test_selector_add_order_by_list() {
         let ob1 = OrderByAttribute::new(Variable::new("o1".to_string()), OrderByOrder::Asc);
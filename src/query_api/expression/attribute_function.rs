@@ -4,6 +4,7 @@
 // This also implements Extension in Java, which means it has namespace and name.
 use super::Expression;
 use crate::query_api::eventflux_element::EventFluxElement; // Assuming expression.rs will define the Expression enum.
+use crate::query_api::execution::query::selection::OrderByAttribute;
 
 #[derive(Clone, Debug, PartialEq, Default)] // Added Default
 pub struct AttributeFunction {
@@ -15,6 +16,29 @@ pub struct AttributeFunction {
 
     // AttributeFunction specific fields
     pub parameters: Vec<Expression>,
+
+    // Aggregate modifiers: `agg(DISTINCT ...)` and `agg(...) FILTER (WHERE ...)`
+    pub distinct: bool,
+    pub filter: Option<Box<Expression>>,
+
+    // Analytic call: `fn(...) OVER (PARTITION BY ... ORDER BY ...)`
+    pub over: Option<AnalyticWindow>,
+}
+
+/// The `OVER (...)` clause of an analytic function call
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct AnalyticWindow {
+    pub partition_by: Vec<Expression>,
+    pub order_by: Vec<OrderByAttribute>,
+}
+
+impl AnalyticWindow {
+    pub fn new(partition_by: Vec<Expression>, order_by: Vec<OrderByAttribute>) -> Self {
+        AnalyticWindow {
+            partition_by,
+            order_by,
+        }
+    }
 }
 
 impl AttributeFunction {
@@ -28,8 +52,29 @@ impl AttributeFunction {
             extension_namespace,
             function_name,
             parameters,
+            distinct: false,
+            filter: None,
+            over: None,
         }
     }
+
+    /// Aggregate only distinct argument values
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Aggregate only events matching `condition`
+    pub fn filter(mut self, condition: Expression) -> Self {
+        self.filter = Some(Box::new(condition));
+        self
+    }
+
+    /// Evaluate as an analytic function over `window`
+    pub fn over(mut self, window: AnalyticWindow) -> Self {
+        self.over = Some(window);
+        self
+    }
 }
 
 // The Java `Extension` interface has `getNamespace()` and `getName()`.
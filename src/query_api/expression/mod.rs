@@ -17,7 +17,7 @@ pub mod variable; // This is the main Expression enum
 
 // Re-export the main Expression enum and key structs/enums for easier access
 // from parent modules (e.g., query_api)
-pub use self::attribute_function::AttributeFunction;
+pub use self::attribute_function::{AnalyticWindow, AttributeFunction};
 pub use self::case::{Case, WhenClause};
 pub use self::cast::Cast;
 pub use self::constant::{Constant, ConstantValueWithFloat, TimeUtil as ConstantTimeUtil}; // Updated ConstantValue to ConstantValueWithFloat
@@ -4,16 +4,17 @@
 //!
 //! Parses multi-statement SQL applications with DDL and queries.
 
-use sqlparser::dialect::GenericDialect;
-use sqlparser::parser::Parser;
-
-use crate::query_api::definition::TriggerDefinition;
-use sqlparser::ast::{CreateStreamTrigger, StreamTriggerTiming};
+use crate::query_api::definition::{
+    FunctionDefinition, StreamDefinition, TableIndex, TriggerDefinition, WindowDefinition,
+};
+use sqlparser::ast::{CreateFunction, CreateStreamTrigger, StreamTriggerTiming};
 
 use super::catalog::{SqlApplication, SqlCatalog};
 use super::converter::SqlConverter;
-use super::error::ApplicationError;
+use super::error::{ApplicationError, CatalogError, ConverterError};
+use super::named_window::NamedWindowSpec;
 use super::normalization::normalize_stream_syntax;
+use super::statement::{parse_statements, parse_tokens, tokenize, ParsedStatement};
 use super::type_inference::TypeInferenceEngine;
 use super::type_mapping::sql_type_to_attribute_type;
 use super::with_clause::{extract_with_options, validate_with_clause};
@@ -34,6 +35,120 @@ fn convert_stream_trigger(
     }
 }
 
+/// Convert `CREATE FUNCTION name(p TYPE, ...) RETURNS TYPE LANGUAGE lang AS 'body'`
+///
+/// Function names are lowercased like every function call. The body is kept
+/// as text; it is compiled by the language's runtime when the app starts.
+fn convert_function_definition(
+    create: &CreateFunction,
+) -> Result<FunctionDefinition, ApplicationError> {
+    use sqlparser::ast::{CreateFunctionBody, Expr, Value};
+
+    let name = create.name.to_string().to_lowercase();
+    let unsupported =
+        |what: &str| ConverterError::UnsupportedFeature(format!("Function '{name}': {what}"));
+
+    let language = create
+        .language
+        .as_ref()
+        .map(|l| l.value.to_lowercase())
+        .ok_or_else(|| unsupported("LANGUAGE is required"))?;
+    let return_type = match &create.return_type {
+        Some(data_type) => sql_type_to_attribute_type(data_type)?,
+        None => return Err(unsupported("RETURNS type is required").into()),
+    };
+    let body = match &create.function_body {
+        Some(CreateFunctionBody::AsBeforeOptions(Expr::Value(v)))
+        | Some(CreateFunctionBody::AsAfterOptions(Expr::Value(v))) => match &v.value {
+            Value::SingleQuotedString(body) => body.clone(),
+            Value::DollarQuotedString(body) => body.value.clone(),
+            _ => return Err(unsupported("body must be a string literal").into()),
+        },
+        _ => return Err(unsupported("body must be given as AS '<body>'").into()),
+    };
+
+    let mut function = FunctionDefinition::new(name.clone(), language, body, return_type);
+    for arg in create.args.iter().flatten() {
+        let param = arg
+            .name
+            .as_ref()
+            .ok_or_else(|| unsupported("parameters must be named"))?;
+        if arg.default_expr.is_some() {
+            return Err(unsupported("parameter defaults are not supported").into());
+        }
+        function = function.parameter(
+            param.value.clone(),
+            sql_type_to_attribute_type(&arg.data_type)?,
+        );
+    }
+    Ok(function)
+}
+
+/// Build a named window definition from its schema and `CREATE WINDOW` specification
+///
+/// The window clause is converted through the regular query path so named
+/// windows accept exactly the window types and parameters queries do.
+fn convert_window_definition(
+    stream_def: StreamDefinition,
+    spec: NamedWindowSpec,
+) -> Result<WindowDefinition, ApplicationError> {
+    use crate::query_api::execution::query::input::handler::StreamHandler;
+    use crate::query_api::execution::query::input::stream::InputStream;
+
+    let name = stream_def.abstract_definition.id.clone();
+    let mut scratch = SqlCatalog::new();
+    scratch.register_stream(name.clone(), stream_def.clone())?;
+
+    let mut window_query = tokenize(&format!("SELECT * FROM {name}"))?;
+    window_query.extend(spec.window);
+    let invalid = || ConverterError::ConversionFailed(format!("Invalid window for '{name}'"));
+    let ParsedStatement {
+        statement, queries, ..
+    } = parse_tokens(window_query)
+        .map_err(|e| ConverterError::ConversionFailed(format!("Invalid window for '{name}': {e}")))?
+        .into_iter()
+        .next()
+        .ok_or_else(invalid)?;
+    if let Some(extension) = queries
+        .into_iter()
+        .next()
+        .and_then(|clauses| clauses.window)
+    {
+        let mut window_def = WindowDefinition::new(name)
+            .window(extension.to_window_handler(&scratch)?)
+            .output_event_type(spec.output_event_type);
+        window_def.stream_definition = stream_def;
+        return Ok(window_def);
+    }
+
+    let query = match &statement {
+        sqlparser::ast::Statement::Query(query) => {
+            SqlConverter::convert_query_ast(query, &scratch, None)?
+        }
+        _ => return Err(invalid().into()),
+    };
+
+    let handler = match query.get_input_stream() {
+        Some(InputStream::Single(single)) => {
+            single
+                .get_stream_handlers()
+                .iter()
+                .find_map(|handler| match handler {
+                    StreamHandler::Window(window) => Some(window.as_ref().clone()),
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+
+    let mut window_def = WindowDefinition::new(name)
+        .window(handler)
+        .output_event_type(spec.output_event_type);
+    window_def.stream_definition = stream_def;
+    Ok(window_def)
+}
+
 /// Validate expression types in a query using the type inference engine
 fn validate_query_types(
     query: &crate::query_api::execution::Query,
@@ -45,6 +160,61 @@ fn validate_query_types(
         .map_err(ApplicationError::Type)
 }
 
+/// Column names of a key or index column list
+///
+/// Only plain column references are supported; a trailing sort order or
+/// operator class is ignored.
+fn key_column_names<T: std::fmt::Display>(columns: &[T]) -> Vec<String> {
+    columns
+        .iter()
+        .filter_map(|c| {
+            c.to_string()
+                .split_whitespace()
+                .next()
+                .map(|name| name.trim_matches(|ch| ch == '"' || ch == '`').to_string())
+        })
+        .collect()
+}
+
+/// Primary key columns declared inline (`id INT PRIMARY KEY`) or as a table
+/// constraint (`PRIMARY KEY (a, b)`)
+fn extract_primary_key(
+    create: &sqlparser::ast::CreateTable,
+) -> Result<Vec<String>, ApplicationError> {
+    let inline: Vec<String> = create
+        .columns
+        .iter()
+        .filter(|col| {
+            col.options
+                .iter()
+                .any(|opt| opt.option.to_string().starts_with("PRIMARY KEY"))
+        })
+        .map(|col| col.name.value.clone())
+        .collect();
+    let constraints: Vec<Vec<String>> = create
+        .constraints
+        .iter()
+        .filter_map(|constraint| match constraint {
+            sqlparser::ast::TableConstraint::PrimaryKey { columns, .. } => {
+                Some(key_column_names(columns))
+            }
+            _ => None,
+        })
+        .collect();
+
+    match (inline.len(), constraints.len()) {
+        (0, 0) => Ok(Vec::new()),
+        (1, 0) => Ok(inline),
+        (0, 1) => Ok(constraints.into_iter().next().unwrap_or_default()),
+        _ => Err(ApplicationError::Converter(
+            ConverterError::UnsupportedFeature(format!(
+                "Table '{}' declares more than one PRIMARY KEY; use PRIMARY KEY (a, b) for a composite key",
+                create.name
+            )),
+        )),
+    }
+}
+
 /// Parse a complete SQL application with multiple statements
 pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationError> {
     let mut catalog = SqlCatalog::new();
@@ -53,20 +223,25 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
     // Normalize EventFlux-specific syntax for standard SQL parsing
     let normalized_sql = normalize_stream_syntax(sql);
 
-    // Parse all statements at once using sqlparser
-    let parsed_statements = Parser::parse_sql(&GenericDialect, &normalized_sql).map_err(|e| {
-        ApplicationError::Converter(super::error::ConverterError::ConversionFailed(format!(
-            "SQL parse error: {}",
-            e
-        )))
-    })?;
+    // Parse statement by statement; extension windows and OUTPUT ... EVERY
+    // clauses are taken out of each query's tokens and attached after
+    // conversion, CREATE WINDOW is parsed as CREATE TABLE plus its window and
+    // CREATE STREAM ... AS CHANGES OF as CREATE TABLE with a placeholder column
+    // list, the table columns being filled in below
+    let parsed_statements = parse_statements(&normalized_sql)?;
 
     if parsed_statements.is_empty() {
         return Err(ApplicationError::EmptyApplication);
     }
 
     // Process each parsed statement
-    for stmt in parsed_statements {
+    for ParsedStatement {
+        statement: stmt,
+        queries,
+        window,
+        change_source,
+    } in parsed_statements
+    {
         match stmt {
             sqlparser::ast::Statement::CreateTable(create) => {
                 let name = create.name.to_string();
@@ -81,7 +256,25 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
                 let is_table =
                     with_config.get("type").is_none() && with_config.get("extension").is_some();
 
-                if is_table {
+                if let Some(table) = change_source {
+                    // This is a change stream (e.g., CREATE STREAM C AS CHANGES OF T)
+                    let with_config = if with_config.is_empty() {
+                        None
+                    } else {
+                        validate_with_clause(&with_config)?;
+                        Some(with_config)
+                    };
+                    catalog.register_change_stream(name, &table, with_config)?;
+                } else if let Some(spec) = window {
+                    // This is a named WINDOW (e.g., CREATE WINDOW W (...) TIME(10 MIN))
+                    let mut stream_def = StreamDefinition::new(name.clone());
+                    for col in &create.columns {
+                        let attr_type = sql_type_to_attribute_type(&col.data_type)?;
+                        stream_def = stream_def.attribute(col.name.value.clone(), attr_type);
+                    }
+
+                    catalog.register_window(convert_window_definition(stream_def, spec)?)?;
+                } else if is_table {
                     // This is a TABLE (e.g., CREATE TABLE T (...) WITH ('extension' = 'cache'))
                     let mut table_def =
                         crate::query_api::definition::TableDefinition::new(name.clone());
@@ -92,6 +285,14 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
                         table_def = table_def.attribute(col.name.value.clone(), attr_type);
                     }
 
+                    let primary_key = extract_primary_key(&create)?;
+                    for column in &primary_key {
+                        if table_def.column_position(column).is_none() {
+                            return Err(CatalogError::UnknownColumn(name, column.clone()).into());
+                        }
+                    }
+                    table_def = table_def.primary_key(primary_key);
+
                     if !with_config.is_empty() {
                         validate_with_clause(&with_config)?;
                         table_def = table_def.with_config(with_config);
@@ -120,6 +321,11 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
             sqlparser::ast::Statement::Query(query) => {
                 // Convert query AST directly (no re-parsing!)
                 let q = SqlConverter::convert_query_ast(&query, &catalog, None)?;
+                let q = queries
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default()
+                    .attach(q, &catalog)?;
 
                 // Type validation: validate expression types in the query
                 validate_query_types(&q, &catalog)?;
@@ -146,6 +352,11 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
                 })?;
 
                 let q = SqlConverter::convert_query_ast(source, &catalog, Some(target_stream))?;
+                let q = queries
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default()
+                    .attach(q, &catalog)?;
 
                 // Type validation: validate expression types in the query
                 validate_query_types(&q, &catalog)?;
@@ -157,16 +368,38 @@ pub fn parse_sql_application(sql: &str) -> Result<SqlApplication, ApplicationErr
                 body,
             } => {
                 // Handle partition directly without re-parsing
-                let partition = SqlConverter::convert_partition(&partition_keys, &body, &catalog)?;
+                let mut partition =
+                    SqlConverter::convert_partition(&partition_keys, &body, &catalog)?;
+                let mut query_list = Vec::with_capacity(partition.query_list.len());
+                let mut clauses = queries.into_iter();
+                for q in partition.query_list {
+                    query_list.push(clauses.next().unwrap_or_default().attach(q, &catalog)?);
+                }
+                partition.query_list = query_list;
                 execution_elements.push(crate::query_api::execution::ExecutionElement::Partition(
                     partition,
                 ));
             }
+            sqlparser::ast::Statement::CreateIndex(index) => {
+                // CREATE INDEX idx ON T (a, b) - secondary index on a table
+                let table = index.table_name.to_string();
+                let columns = key_column_names(&index.columns);
+                let index_name = index
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| format!("{}_{}_idx", table, columns.join("_")));
+                catalog.add_table_index(&table, TableIndex::new(index_name, columns))?;
+            }
             sqlparser::ast::Statement::CreateStreamTrigger(stream_trigger) => {
                 // Convert EventFlux streaming trigger to TriggerDefinition
                 let trigger_def = convert_stream_trigger(&stream_trigger)?;
                 catalog.register_trigger(trigger_def);
             }
+            sqlparser::ast::Statement::CreateFunction(create) => {
+                let function_def = convert_function_definition(&create)?;
+                catalog.register_function(function_def, create.or_replace)?;
+            }
             _ => {
                 return Err(ApplicationError::Converter(
                     super::error::ConverterError::UnsupportedFeature(format!(
@@ -671,4 +904,75 @@ mod tests {
             "Empty WITH clause should not store configuration"
         );
     }
+
+    #[test]
+    fn test_create_function_registered_in_catalog() {
+        use crate::query_api::definition::attribute::Type as AttributeType;
+
+        let sql = r#"
+            CREATE FUNCTION Risk(amount DOUBLE, country STRING) RETURNS DOUBLE
+                LANGUAGE rhai AS 'amount * 2.0';
+            CREATE STREAM In (amount DOUBLE, country STRING);
+            SELECT risk(amount, country) AS score FROM In;
+        "#;
+
+        let app = parse_sql_application(sql).unwrap();
+        let function = app.catalog.get_function("risk").unwrap();
+        assert_eq!(function.id, "risk");
+        assert_eq!(function.language, "rhai");
+        assert_eq!(function.body, "amount * 2.0");
+        assert_eq!(function.return_type, AttributeType::DOUBLE);
+        assert_eq!(function.parameters.len(), 2);
+        assert_eq!(function.parameters[1].attribute_type, AttributeType::STRING);
+    }
+
+    #[test]
+    fn test_create_function_duplicate_rejected_unless_replace() {
+        let sql = "
+            CREATE FUNCTION f(x INT) RETURNS INT LANGUAGE rhai AS 'x';
+            CREATE FUNCTION f(x INT) RETURNS INT LANGUAGE rhai AS 'x + 1';
+            CREATE STREAM In (x INT);
+            SELECT f(x) AS y FROM In;
+        ";
+        assert!(parse_sql_application(sql).is_err());
+
+        let sql = sql.replacen(
+            "CREATE FUNCTION f(x INT) RETURNS INT LANGUAGE rhai AS 'x + 1'",
+            "CREATE OR REPLACE FUNCTION f(x INT) RETURNS INT LANGUAGE rhai AS 'x + 1'",
+            1,
+        );
+        let app = parse_sql_application(&sql).unwrap();
+        assert_eq!(app.catalog.get_function("f").unwrap().body, "x + 1");
+    }
+
+    #[test]
+    fn test_table_primary_key_and_index() {
+        let sql = "
+            CREATE TABLE Orders (id BIGINT, region VARCHAR, amount DOUBLE, PRIMARY KEY (id))
+                WITH ('extension' = 'inMemory');
+            CREATE TABLE Users (name VARCHAR PRIMARY KEY, age INT) WITH ('extension' = 'inMemory');
+            CREATE INDEX orders_region ON Orders (region);
+            CREATE INDEX ON Orders (region, amount);
+        ";
+        let app = parse_sql_application(sql).unwrap();
+
+        let orders = app.catalog.get_table("Orders").unwrap();
+        assert_eq!(orders.primary_key, vec!["id".to_string()]);
+        assert_eq!(orders.indexes.len(), 2);
+        assert_eq!(orders.indexes[0].name, "orders_region");
+        assert_eq!(
+            orders.indexes[1].columns,
+            vec!["region".to_string(), "amount".to_string()]
+        );
+        assert_eq!(
+            app.catalog.get_table("Users").unwrap().primary_key,
+            vec!["name".to_string()]
+        );
+
+        let bad_index = "
+            CREATE TABLE T (id INT PRIMARY KEY) WITH ('extension' = 'inMemory');
+            CREATE INDEX t_missing ON T (missing);
+        ";
+        assert!(parse_sql_application(bad_index).is_err());
+    }
 }
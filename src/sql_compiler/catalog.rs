@@ -6,7 +6,10 @@
 
 use crate::query_api::definition::abstract_definition::AbstractDefinition;
 use crate::query_api::definition::attribute::{Attribute, Type as AttributeType};
-use crate::query_api::definition::{StreamDefinition, TableDefinition, TriggerDefinition};
+use crate::query_api::definition::{
+    FunctionDefinition, StreamDefinition, TableDefinition, TableIndex, TriggerDefinition,
+    WindowDefinition,
+};
 use crate::query_api::eventflux_app::EventFluxApp;
 use crate::query_api::execution::ExecutionElement;
 use crate::query_api::expression::Expression;
@@ -92,12 +95,14 @@ pub struct CreateStreamInfo {
     pub columns: Vec<ColumnDef>,
 }
 
-/// SQL Catalog manages stream, table, and trigger schemas
+/// SQL Catalog manages stream, table, window, trigger, and function schemas
 #[derive(Debug, Clone)]
 pub struct SqlCatalog {
     streams: HashMap<String, Arc<StreamDefinition>>,
     tables: HashMap<String, Arc<TableDefinition>>,
+    windows: HashMap<String, Arc<WindowDefinition>>,
     triggers: HashMap<String, Arc<TriggerDefinition>>,
+    functions: HashMap<String, Arc<FunctionDefinition>>,
     aliases: HashMap<String, String>,
 }
 
@@ -107,7 +112,9 @@ impl SqlCatalog {
         SqlCatalog {
             streams: HashMap::new(),
             tables: HashMap::new(),
+            windows: HashMap::new(),
             triggers: HashMap::new(),
+            functions: HashMap::new(),
             aliases: HashMap::new(),
         }
     }
@@ -158,6 +165,102 @@ impl SqlCatalog {
         self.tables.insert(name, Arc::new(definition));
     }
 
+    /// Register the change stream `name` of table `table` (`CREATE STREAM
+    /// name AS CHANGES OF table`): an `operation` column followed by the
+    /// table's columns
+    pub fn register_change_stream(
+        &mut self,
+        name: String,
+        table: &str,
+        with_config: Option<crate::core::config::stream_config::FlatConfig>,
+    ) -> Result<(), CatalogError> {
+        use crate::core::table::OPERATION_COLUMN;
+
+        let table_def = self
+            .tables
+            .get(table)
+            .ok_or_else(|| CatalogError::UnknownRelation(table.to_string()))?;
+        if table_def.column_position(OPERATION_COLUMN).is_some() {
+            return Err(CatalogError::InvalidChangeStream(
+                name,
+                format!("table '{table}' already has a column named '{OPERATION_COLUMN}'"),
+            ));
+        }
+        let mut stream_def = StreamDefinition::new(name.clone())
+            .attribute(OPERATION_COLUMN.to_string(), AttributeType::STRING)
+            .changes_of(table.to_string());
+        for attribute in &table_def.abstract_definition.attribute_list {
+            stream_def = stream_def.attribute(attribute.get_name().clone(), *attribute.get_type());
+        }
+        if let Some(config) = with_config {
+            stream_def = stream_def.with_config(config);
+        }
+        self.register_stream(name, stream_def)
+    }
+
+    /// Add a secondary index to a registered table
+    pub fn add_table_index(&mut self, table: &str, index: TableIndex) -> Result<(), CatalogError> {
+        let definition = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| CatalogError::UnknownRelation(table.to_string()))?;
+        if let Some(column) = index
+            .columns
+            .iter()
+            .find(|column| definition.column_position(column).is_none())
+        {
+            return Err(CatalogError::UnknownColumn(
+                table.to_string(),
+                column.clone(),
+            ));
+        }
+        Arc::make_mut(definition).indexes.push(index);
+        Ok(())
+    }
+
+    /// Register a named window definition
+    ///
+    /// Like triggers, windows are also registered as streams so queries can
+    /// `INSERT INTO` them and read them in FROM/JOIN clauses.
+    pub fn register_window(&mut self, window: WindowDefinition) -> Result<(), CatalogError> {
+        let window_name = window.stream_definition.abstract_definition.id.clone();
+        self.register_stream(window_name.clone(), window.stream_definition.clone())?;
+        self.windows.insert(window_name, Arc::new(window));
+        Ok(())
+    }
+
+    /// Get a named window definition by name
+    pub fn get_window(&self, name: &str) -> Option<Arc<WindowDefinition>> {
+        self.windows.get(name).map(Arc::clone)
+    }
+
+    /// Check if a name refers to a named window
+    pub fn is_window(&self, name: &str) -> bool {
+        self.windows.contains_key(name)
+    }
+
+    /// Register a script function definition (`CREATE FUNCTION`)
+    ///
+    /// Function names are case-insensitive and stored lowercase, matching how
+    /// function calls are resolved. `replace` allows redefinition.
+    pub fn register_function(
+        &mut self,
+        function: FunctionDefinition,
+        replace: bool,
+    ) -> Result<(), CatalogError> {
+        let name = function.id.to_lowercase();
+        if !replace && self.functions.contains_key(&name) {
+            return Err(CatalogError::DuplicateFunction(name));
+        }
+        self.functions.insert(name, Arc::new(function));
+        Ok(())
+    }
+
+    /// Get a script function definition by (case-insensitive) name
+    pub fn get_function(&self, name: &str) -> Option<Arc<FunctionDefinition>> {
+        self.functions.get(&name.to_lowercase()).map(Arc::clone)
+    }
+
     /// Register an alias for a stream
     pub fn register_alias(&mut self, alias: String, stream_name: String) {
         self.aliases.insert(alias, stream_name);
@@ -294,7 +397,10 @@ impl SqlCatalog {
 
     /// Check if catalog is empty
     pub fn is_empty(&self) -> bool {
-        self.streams.is_empty() && self.tables.is_empty() && self.triggers.is_empty()
+        self.streams.is_empty()
+            && self.tables.is_empty()
+            && self.windows.is_empty()
+            && self.triggers.is_empty()
     }
 }
 
@@ -432,8 +538,10 @@ impl SqlApplication {
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| "OutputStream".to_string());
 
-                    // Don't create StreamDefinition for tables
-                    if !app.table_definition_map.contains_key(&target_stream_name) {
+                    // Don't create StreamDefinition for tables or named windows
+                    if !app.table_definition_map.contains_key(&target_stream_name)
+                        && !self.catalog.is_window(&target_stream_name)
+                    {
                         app.stream_definition_map
                             .entry(target_stream_name.clone())
                             .or_insert_with(|| {
@@ -474,7 +582,9 @@ impl SqlApplication {
                             .map(|s| s.to_string())
                             .unwrap_or_else(|| "OutputStream".to_string());
 
-                        if !app.table_definition_map.contains_key(&target_stream_name) {
+                        if !app.table_definition_map.contains_key(&target_stream_name)
+                            && !self.catalog.is_window(&target_stream_name)
+                        {
                             app.stream_definition_map
                                 .entry(target_stream_name.clone())
                                 .or_insert_with(|| {
@@ -524,9 +634,15 @@ impl SqlApplication {
         // This allows us to use type_engine with catalog reference
         self.process_output_streams(&mut app)?;
 
-        // Move catalog data into app
+        // Move catalog data into app (named windows own their input stream)
         for (stream_name, stream_def) in self.catalog.streams {
-            app.stream_definition_map.insert(stream_name, stream_def);
+            if !self.catalog.windows.contains_key(&stream_name) {
+                app.stream_definition_map.insert(stream_name, stream_def);
+            }
+        }
+
+        for (window_name, window_def) in self.catalog.windows {
+            app.window_definition_map.insert(window_name, window_def);
         }
 
         for (table_name, table_def) in self.catalog.tables {
@@ -538,6 +654,11 @@ impl SqlApplication {
             app.trigger_definition_map.insert(trigger_name, trigger_def);
         }
 
+        for (function_name, function_def) in self.catalog.functions {
+            app.function_definition_map
+                .insert(function_name, function_def);
+        }
+
         // Add all execution elements
         for elem in self.execution_elements {
             app.add_execution_element(elem);
@@ -646,4 +767,33 @@ mod tests {
         assert!(catalog.get_stream("ts").is_ok());
         assert!(catalog.has_column("ts", "col1"));
     }
+
+    #[test]
+    fn test_add_table_index() {
+        let mut catalog = SqlCatalog::new();
+        let table = TableDefinition::new("T".to_string())
+            .attribute("id".to_string(), AttributeType::INT)
+            .attribute("region".to_string(), AttributeType::STRING);
+        catalog.register_table("T".to_string(), table);
+
+        catalog
+            .add_table_index(
+                "T",
+                TableIndex::new("t_region".to_string(), vec!["region".to_string()]),
+            )
+            .unwrap();
+        assert_eq!(catalog.get_table("T").unwrap().indexes.len(), 1);
+
+        assert!(matches!(
+            catalog.add_table_index(
+                "T",
+                TableIndex::new("bad".to_string(), vec!["missing".to_string()])
+            ),
+            Err(CatalogError::UnknownColumn(_, _))
+        ));
+        assert!(matches!(
+            catalog.add_table_index("Nope", TableIndex::default()),
+            Err(CatalogError::UnknownRelation(_))
+        ));
+    }
 }
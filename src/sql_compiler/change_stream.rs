@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Change Stream Definitions
+//!
+//! `CREATE STREAM ... AS CHANGES OF` is not part of the SQL grammar. Its
+//! statement tokens are rewritten into a `CREATE TABLE` with a placeholder
+//! column list (so its WITH clause flows through the normal DDL path), and the
+//! table whose changes it carries is kept aside for the application parser:
+//!
+//! ```sql
+//! CREATE STREAM PriceChanges AS CHANGES OF PriceTable;
+//! CREATE STREAM PriceAudit AS CHANGES OF PriceTable WITH ('type' = 'sink', ...);
+//! ```
+//!
+//! Runs after [`normalize_stream_syntax`](super::normalize_stream_syntax), so
+//! the statements already read `CREATE TABLE ... AS CHANGES OF`.
+
+use sqlparser::tokenizer::{Token, TokenWithSpan};
+
+use super::error::ConverterError;
+use super::statement::is_keyword;
+
+/// Rewrite the tokens of a `CREATE TABLE name AS CHANGES OF table` statement
+/// to `CREATE TABLE name (operation STRING)`
+///
+/// Returns the source table, or `None` for any other statement. The
+/// placeholder column list is replaced by the table's columns.
+pub(super) fn take_change_stream(
+    tokens: &mut Vec<TokenWithSpan>,
+) -> Result<Option<String>, ConverterError> {
+    let is_change_stream = tokens.len() >= 6
+        && is_keyword(&tokens[0], "CREATE")
+        && is_keyword(&tokens[1], "TABLE")
+        && is_keyword(&tokens[3], "AS")
+        && is_keyword(&tokens[4], "CHANGES")
+        && is_keyword(&tokens[5], "OF");
+    if !is_change_stream {
+        return Ok(None);
+    }
+    let location = tokens[0].span.start;
+
+    let name = match &tokens[2].token {
+        Token::Word(w) => w.value.clone(),
+        other => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "Invalid change stream name '{other}'{location}"
+            )))
+        }
+    };
+    let table = match tokens.get(6).map(|t| &t.token) {
+        Some(Token::Word(w)) => w.value.clone(),
+        _ => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "CREATE STREAM {name} AS CHANGES OF requires a table name{location}"
+            )))
+        }
+    };
+
+    tokens.splice(
+        3..7,
+        [
+            Token::LParen,
+            Token::make_word("operation", None),
+            Token::make_keyword("STRING"),
+            Token::RParen,
+        ]
+        .map(TokenWithSpan::wrap),
+    );
+    Ok(Some(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql_compiler::statement::parse_statements;
+
+    #[test]
+    fn test_change_stream_rewrite() {
+        let parsed = parse_statements(
+            "CREATE TABLE Prices (symbol STRING) WITH ('extension' = 'inMemory');\n\
+             CREATE TABLE PriceChanges AS CHANGES OF Prices;\n\
+             create table Audit as changes of Prices WITH ('type' = 'sink');",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert!(parsed[0].change_source.is_none());
+        assert_eq!(
+            parsed[1].statement.to_string(),
+            "CREATE TABLE PriceChanges (operation STRING)"
+        );
+        assert_eq!(parsed[1].change_source.as_deref(), Some("Prices"));
+        assert!(parsed[2]
+            .statement
+            .to_string()
+            .starts_with("CREATE TABLE Audit (operation STRING) WITH"));
+        assert_eq!(parsed[2].change_source.as_deref(), Some("Prices"));
+    }
+
+    #[test]
+    fn test_change_stream_requires_table() {
+        assert!(parse_statements("CREATE TABLE C AS CHANGES OF;").is_err());
+    }
+
+    #[test]
+    fn test_keywords_in_strings_untouched() {
+        let parsed =
+            parse_statements("SELECT 'CREATE TABLE C AS CHANGES OF T' AS s FROM S;").unwrap();
+        assert!(parsed[0].change_source.is_none());
+    }
+}
@@ -9,8 +9,6 @@ use sqlparser::ast::{
     PatternExpression, PatternLogicalOp, PatternMode, Select as SqlSelect, SetExpr, Statement,
     Subscript, TableFactor, UnaryOperator, WithinConstraint,
 };
-use sqlparser::dialect::GenericDialect;
-use sqlparser::parser::Parser;
 
 use crate::core::query::processor::stream::window::types::{
     WINDOW_TYPE_EXTERNAL_TIME, WINDOW_TYPE_EXTERNAL_TIME_BATCH, WINDOW_TYPE_LENGTH,
@@ -28,16 +26,18 @@ use crate::query_api::execution::query::input::stream::state_input_stream::State
 use crate::query_api::execution::query::output::output_stream::{
     InsertIntoStreamAction, OutputStream, OutputStreamAction,
 };
+use crate::query_api::execution::query::selection::OrderByAttribute;
 use crate::query_api::execution::query::Query;
 use crate::query_api::expression::indexed_variable::{EventIndex, IndexedVariable};
 use crate::query_api::expression::variable::Variable;
 use crate::query_api::expression::CompareOperator;
-use crate::query_api::expression::{Expression, WhenClause};
+use crate::query_api::expression::{AnalyticWindow, AttributeFunction, Expression, WhenClause};
 
 use super::catalog::SqlCatalog;
 use super::error::ConverterError;
 use super::expansion::SelectExpander;
 use super::pattern_validation::PatternValidator;
+use super::statement::{parse_statements, ParsedStatement};
 use super::type_inference::TypeInferenceEngine;
 
 /// SQL to Query Converter
@@ -46,18 +46,20 @@ pub struct SqlConverter;
 impl SqlConverter {
     /// Convert SQL string to Query (legacy API - parses then converts)
     pub fn convert(sql: &str, catalog: &SqlCatalog) -> Result<Query, ConverterError> {
-        // Parse SQL directly (WINDOW clause now handled natively by parser)
-        let statements = Parser::parse_sql(&GenericDialect, sql)
-            .map_err(|e| ConverterError::ConversionFailed(format!("SQL parse error: {}", e)))?;
+        // OUTPUT ... EVERY is taken out while parsing and re-attached below
+        let mut statements = parse_statements(sql)?;
 
         if statements.is_empty() {
             return Err(ConverterError::ConversionFailed(
                 "No SQL statements found".to_string(),
             ));
         }
+        let ParsedStatement {
+            statement, queries, ..
+        } = statements.swap_remove(0);
 
         // Convert SELECT or INSERT INTO statement to Query
-        match &statements[0] {
+        let query = match &statement {
             Statement::Query(query) => Self::convert_query_ast(query, catalog, None),
             Statement::Insert(insert) => {
                 let target_stream = match &insert.table {
@@ -80,7 +82,13 @@ impl SqlConverter {
             _ => Err(ConverterError::UnsupportedFeature(
                 "Only SELECT and INSERT INTO queries are supported".to_string(),
             )),
-        }
+        }?;
+
+        queries
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+            .attach(query, catalog)
     }
 
     /// Convert parsed Query AST directly to Query (no re-parsing!)
@@ -246,6 +254,13 @@ impl SqlConverter {
                         return Err(ConverterError::DirectTableQuery(stream_name));
                     }
 
+                    // Named windows already hold their own buffer
+                    if window.is_some() && catalog.is_window(&stream_name) {
+                        return Err(ConverterError::UnsupportedFeature(format!(
+                            "Named window '{stream_name}' already defines its window; remove the WINDOW clause"
+                        )));
+                    }
+
                     // Create InputStream (works for both streams and tables - runtime will differentiate)
                     let mut single_stream = SingleInputStream::new_basic(
                         stream_name.clone(),
@@ -318,14 +333,11 @@ impl SqlConverter {
                 ));
             }
 
+            // Any scalar expression may be grouped on; select aliases and aggregate
+            // misuse are resolved against the selection list by the query parser
             for expr in group_exprs {
-                if let SqlExpr::Identifier(ident) = expr {
-                    selector = selector.group_by(Variable::new(ident.value.clone()));
-                } else {
-                    return Err(ConverterError::UnsupportedFeature(
-                        "Complex GROUP BY expressions not supported".to_string(),
-                    ));
-                }
+                let group_expr = Self::convert_expression(expr, catalog)?;
+                selector = selector.group_by_expression(group_expr);
             }
         }
 
@@ -348,24 +360,9 @@ impl SqlConverter {
             };
 
             for order_expr in order_exprs {
-                // Extract variable from order_expr.expr
-                let variable = match &order_expr.expr {
-                    SqlExpr::Identifier(ident) => Variable::new(ident.value.clone()),
-                    SqlExpr::CompoundIdentifier(idents) => {
-                        if idents.len() == 1 {
-                            Variable::new(idents[0].value.clone())
-                        } else {
-                            return Err(ConverterError::UnsupportedFeature(
-                                "Qualified column names in ORDER BY not supported".to_string(),
-                            ));
-                        }
-                    }
-                    _ => {
-                        return Err(ConverterError::UnsupportedFeature(
-                            "Complex expressions in ORDER BY not supported".to_string(),
-                        ))
-                    }
-                };
+                // Plain names may refer to select aliases; the query parser sorts
+                // those (and expressions repeated from SELECT) on the output row
+                let sort_expr = Self::convert_expression(&order_expr.expr, catalog)?;
 
                 // Determine order (ASC/DESC)
                 let order = if let Some(asc) = order_expr.options.asc {
@@ -379,7 +376,7 @@ impl SqlConverter {
                     crate::query_api::execution::query::selection::order_by_attribute::Order::Asc
                 };
 
-                selector = selector.order_by_with_order(variable, order);
+                selector = selector.order_by_expression(sort_expr, order);
             }
         }
 
@@ -1181,7 +1178,7 @@ impl SqlConverter {
     ) -> Result<Expression, ConverterError> {
         // Get function name and strip backticks if present (for namespace-prefixed functions like `math:sin`)
         let raw_name = func.name.to_string();
-        let func_name = raw_name
+        let mut func_name = raw_name
             .trim_start_matches('`')
             .trim_end_matches('`')
             .to_lowercase();
@@ -1221,6 +1218,27 @@ impl SqlConverter {
             }
         }
 
+        // Aggregate modifiers: agg(DISTINCT ...) and agg(...) FILTER (WHERE ...)
+        let mut distinct = matches!(
+            arg_list.duplicate_treatment,
+            Some(sqlparser::ast::DuplicateTreatment::Distinct)
+        );
+        let filter = func
+            .filter
+            .as_deref()
+            .map(|f| Self::convert_expression(f, catalog))
+            .transpose()?;
+        if distinct && func_name == "count" {
+            if args.is_empty() {
+                return Err(ConverterError::UnsupportedFeature(
+                    "COUNT(DISTINCT *) is not supported".to_string(),
+                ));
+            }
+            // COUNT(DISTINCT x) is the distinctCount aggregator
+            distinct = false;
+            func_name = "distinctcount".to_string();
+        }
+
         // Map SQL function names to EventFlux function names
         let eventflux_func_name = match func_name.as_str() {
             // Aggregations
@@ -1233,8 +1251,25 @@ impl SqlConverter {
             "maxforever" => "maxforever",
             "distinctcount" => "distinctCount",
             "stddev" => "stddev",
+            "percentile" => "percentile",
+            "median" => "median",
+            "approx_distinct" => "approx_distinct",
+            "top_k" => "top_k",
+            "corr" => "corr",
+            "covar_pop" => "covar_pop",
+            "covar_samp" => "covar_samp",
+            "regr_slope" => "regr_slope",
+            "regr_intercept" => "regr_intercept",
+            "wavg" => "wavg",
+            "arg_max" => "arg_max",
+            "arg_min" => "arg_min",
             "first" => "first",
             "last" => "last",
+            "collect" => "collect",
+            // Analytic functions (only valid with OVER)
+            "row_number" => "row_number",
+            "lag" => "lag",
+            "lead" => "lead",
             // Math functions
             "round" => "round",
             "abs" => "abs",
@@ -1298,14 +1333,36 @@ impl SqlConverter {
             }
             "lpad" => "lpad",
             "rpad" => "rpad",
+            // Regex and parsing functions
+            "regexp_matches" => "regexp_matches",
+            "regexp_extract" => "regexp_extract",
+            "regexp_replace" => "regexp_replace",
+            "split_part" => "split_part",
+            "split" => "split",
+            "parse_url" => "parse_url",
+            "url_decode" => "url_decode",
             // Utility functions
             "coalesce" => "coalesce",
             "default" => "default",
             "ifnull" => "default", // IFNULL maps to default (2 args only)
             "nullif" => "nullif",
             "uuid" => "uuid",
+            "uuid_v5" => "uuid_v5",
+            // Hashing and encoding functions
+            "md5" => "md5",
+            "sha1" => "sha1",
+            "sha256" => "sha256",
+            "hmac_sha256" => "hmac_sha256",
+            "crc32" => "crc32",
+            "xxhash64" => "xxhash64",
+            "base64_encode" => "base64_encode",
+            "base64_decode" => "base64_decode",
+            "hex" => "hex",
+            "unhex" => "unhex",
             "eventtimestamp" => "eventTimestamp",
             "now" => "now",
+            // Functions declared with CREATE FUNCTION
+            _ if catalog.get_function(&func_name).is_some() => func_name.as_str(),
             _ => {
                 return Err(ConverterError::UnsupportedFeature(format!(
                     "Function '{}' not supported",
@@ -1314,10 +1371,62 @@ impl SqlConverter {
             }
         };
 
-        Ok(Expression::function_no_ns(
-            eventflux_func_name.to_string(),
-            args,
-        ))
+        let mut function = AttributeFunction::new(None, eventflux_func_name.to_string(), args);
+        if distinct {
+            function = function.distinct();
+        }
+        if let Some(filter) = filter {
+            function = function.filter(filter);
+        }
+        if let Some(over) = &func.over {
+            function = function.over(Self::convert_analytic_window(over, catalog)?);
+        }
+        Ok(Expression::AttributeFunction(Box::new(function)))
+    }
+
+    /// Convert the `OVER (PARTITION BY ... ORDER BY ...)` clause of an analytic call
+    fn convert_analytic_window(
+        over: &sqlparser::ast::WindowType,
+        catalog: &SqlCatalog,
+    ) -> Result<AnalyticWindow, ConverterError> {
+        let spec = match over {
+            sqlparser::ast::WindowType::WindowSpec(spec) => spec,
+            sqlparser::ast::WindowType::NamedWindow(name) => {
+                return Err(ConverterError::UnsupportedFeature(format!(
+                    "Named window '{}' in OVER not supported",
+                    name
+                )))
+            }
+        };
+        if spec.window_name.is_some() {
+            return Err(ConverterError::UnsupportedFeature(
+                "Named window reference in OVER not supported".to_string(),
+            ));
+        }
+        if spec.window_frame.is_some() {
+            // Running aggregates always span the partition up to the current row
+            return Err(ConverterError::UnsupportedFeature(
+                "Window frames (ROWS/RANGE) in OVER not supported".to_string(),
+            ));
+        }
+
+        let mut partition_by = Vec::new();
+        for expr in &spec.partition_by {
+            partition_by.push(Self::convert_expression(expr, catalog)?);
+        }
+        let mut order_by = Vec::new();
+        for order_expr in &spec.order_by {
+            let order = if order_expr.options.asc == Some(false) {
+                crate::query_api::execution::query::selection::order_by_attribute::Order::Desc
+            } else {
+                crate::query_api::execution::query::selection::order_by_attribute::Order::Asc
+            };
+            order_by.push(OrderByAttribute::new_expression(
+                Self::convert_expression(&order_expr.expr, catalog)?,
+                order,
+            ));
+        }
+        Ok(AnalyticWindow::new(partition_by, order_by))
     }
 
     /// Convert SQL expression to Constant (for LIMIT/OFFSET)
@@ -1886,6 +1995,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aggregate_filter_and_distinct() {
+        let catalog = setup_catalog();
+        let sql = "SELECT count(*) FILTER (WHERE price > 100) AS expensive, \
+                   sum(DISTINCT volume) AS volumes, count(DISTINCT symbol) AS symbols \
+                   FROM StockStream";
+        let query = SqlConverter::convert(sql, &catalog).unwrap();
+
+        let functions: Vec<&AttributeFunction> = query
+            .get_selector()
+            .selection_list
+            .iter()
+            .map(|oa| match &oa.expression {
+                Expression::AttributeFunction(f) => f.as_ref(),
+                other => panic!("expected function, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(functions[0].function_name, "count");
+        assert!(matches!(
+            functions[0].filter.as_deref(),
+            Some(Expression::Compare(_))
+        ));
+        assert!(functions[1].distinct && functions[1].filter.is_none());
+        // COUNT(DISTINCT x) maps onto the distinctCount aggregator
+        assert_eq!(functions[2].function_name, "distinctCount");
+        assert!(!functions[2].distinct);
+    }
+
+    #[test]
+    fn test_analytic_over() {
+        let catalog = setup_catalog();
+        let sql = "SELECT price - LAG(price) OVER (PARTITION BY symbol) AS delta, \
+                   ROW_NUMBER() OVER (PARTITION BY symbol ORDER BY volume DESC) AS rn \
+                   FROM StockStream";
+        let query = SqlConverter::convert(sql, &catalog).unwrap();
+
+        let selection = &query.get_selector().selection_list;
+        let lag = match &selection[0].expression {
+            Expression::Subtract(sub) => match sub.right_value.as_ref() {
+                Expression::AttributeFunction(f) => f,
+                other => panic!("expected LAG, got {other:?}"),
+            },
+            other => panic!("expected subtraction, got {other:?}"),
+        };
+        assert_eq!(lag.function_name, "lag");
+        let over = lag.over.as_ref().unwrap();
+        assert_eq!(over.partition_by.len(), 1);
+        assert!(over.order_by.is_empty());
+
+        let rn = match &selection[1].expression {
+            Expression::AttributeFunction(f) => f,
+            other => panic!("expected ROW_NUMBER, got {other:?}"),
+        };
+        assert_eq!(rn.function_name, "row_number");
+        let over = rn.over.as_ref().unwrap();
+        assert_eq!(
+            *over.order_by[0].get_order(),
+            crate::query_api::execution::query::selection::order_by_attribute::Order::Desc
+        );
+
+        let framed =
+            "SELECT SUM(price) OVER (ORDER BY volume ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) \
+                      AS s FROM StockStream";
+        assert!(SqlConverter::convert(framed, &catalog).is_err());
+    }
+
+    #[test]
+    fn test_expression_group_by_and_order_by() {
+        let catalog = setup_catalog();
+        let sql = "SELECT upper(symbol) AS sym, SUM(volume) AS total FROM StockStream \
+                   GROUP BY upper(symbol) ORDER BY total DESC, StockStream.price * 2";
+        let query = SqlConverter::convert(sql, &catalog).unwrap();
+
+        let selector = query.get_selector();
+        assert!(matches!(
+            selector.get_group_by_list()[0],
+            Expression::AttributeFunction(_)
+        ));
+        let order_by = selector.get_order_by_list();
+        assert_eq!(order_by.len(), 2);
+        assert_eq!(
+            order_by[0]
+                .get_variable()
+                .map(|v| v.get_attribute_name().as_str()),
+            Some("total")
+        );
+        assert!(matches!(
+            order_by[1].get_expression(),
+            Expression::Multiply(_)
+        ));
+    }
+
     #[test]
     fn test_where_with_function_non_boolean() {
         let catalog = setup_catalog();
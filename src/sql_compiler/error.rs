@@ -35,6 +35,12 @@ pub enum CatalogError {
 
     #[error("Unknown column: {0}.{1}")]
     UnknownColumn(String, String),
+
+    #[error("Duplicate function definition: {0}")]
+    DuplicateFunction(String),
+
+    #[error("Invalid change stream {0}: {1}")]
+    InvalidChangeStream(String, String),
 }
 
 #[derive(Debug, Error)]
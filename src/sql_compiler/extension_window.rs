@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Extension Window Clauses
+//!
+//! The parser's window grammar covers the core window types only. Windows
+//! implemented as processor extensions are taken out of each query's tokens
+//! by [`parse_statements`](super::statement::parse_statements) and attached to
+//! the query after conversion:
+//!
+//! ```sql
+//! SELECT symbol, price FROM Trades WINDOW('unique', symbol);
+//! SELECT symbol, price FROM Trades WINDOW timeLength(10 SEC, 100);
+//! SELECT symbol, price FROM Trades WINDOW expression(count() <= 10);
+//! ```
+//!
+//! Both the `WINDOW('type', args)` and the shorthand `WINDOW type(args)` forms
+//! are accepted. Duration arguments such as `10 SEC` are rewritten to
+//! milliseconds. Only clauses at the top level of a query are taken, so a
+//! window always belongs to the query it is written in.
+//!
+//! `sort` windows are taken here as well so their keys can be written the
+//! way ORDER BY keys are, e.g. `WINDOW SORT(100, price DESC NULLS LAST, ts)`.
+//! Each key is expanded to the `attr, 'desc', 'nulls last'` parameter list the
+//! sort window takes.
+
+use sqlparser::ast::{Expr, Value};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, TokenWithSpan};
+
+use crate::core::query::processor::stream::window::types::{
+    WINDOW_TYPE_CRON, WINDOW_TYPE_DELAY, WINDOW_TYPE_EXPRESSION, WINDOW_TYPE_FIRST_UNIQUE,
+    WINDOW_TYPE_FREQUENT, WINDOW_TYPE_LOSSY_COUNTING, WINDOW_TYPE_SORT, WINDOW_TYPE_TIME_LENGTH,
+    WINDOW_TYPE_UNIQUE,
+};
+use crate::query_api::execution::query::input::handler::{StreamHandler, WindowHandler};
+use crate::query_api::execution::query::input::stream::input_stream::InputStream;
+use crate::query_api::execution::query::Query;
+
+use super::catalog::SqlCatalog;
+use super::converter::SqlConverter;
+use super::error::ConverterError;
+use super::output_rate::time_unit_millis;
+use super::statement::{is_keyword, matching_paren, tokenize};
+
+/// Window types attached through extraction rather than the parser's grammar
+const EXTENSION_WINDOW_TYPES: &[&str] = &[
+    WINDOW_TYPE_UNIQUE,
+    WINDOW_TYPE_FIRST_UNIQUE,
+    WINDOW_TYPE_DELAY,
+    WINDOW_TYPE_FREQUENT,
+    WINDOW_TYPE_EXPRESSION,
+    WINDOW_TYPE_TIME_LENGTH,
+    WINDOW_TYPE_CRON,
+    WINDOW_TYPE_LOSSY_COUNTING,
+    WINDOW_TYPE_SORT,
+];
+
+/// An extension window clause taken out of a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionWindowSpec {
+    /// Canonical window type, e.g. `firstUnique`
+    pub window_type: String,
+    /// Window arguments, with durations already in milliseconds
+    pub args: Vec<Expr>,
+}
+
+fn extension_window_type(name: &str) -> Option<&'static str> {
+    EXTENSION_WINDOW_TYPES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+fn string_arg(value: String) -> Expr {
+    Expr::Value(Value::SingleQuotedString(value).into())
+}
+
+/// Split the tokens between `tokens[open]` and `tokens[close]` at top-level commas
+fn split_args(tokens: &[TokenWithSpan], open: usize, close: usize) -> Vec<&[TokenWithSpan]> {
+    let inner = &tokens[open + 1..close];
+    if inner.is_empty() {
+        return Vec::new();
+    }
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut arg_start = 0;
+    for (idx, token) in inner.iter().enumerate() {
+        match token.token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            Token::Comma if depth == 0 => {
+                args.push(&inner[arg_start..idx]);
+                arg_start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(&inner[arg_start..]);
+    args
+}
+
+/// Parse one argument, rewriting a `<number> <unit>` duration to milliseconds
+fn parse_arg(tokens: &[TokenWithSpan], window_type: &str) -> Result<Expr, ConverterError> {
+    let invalid = |detail: String| {
+        ConverterError::InvalidExpression(format!(
+            "Invalid argument for window '{window_type}': {detail}"
+        ))
+    };
+
+    match tokens {
+        [] => return Err(invalid("empty argument".to_string())),
+        [value, unit] => {
+            if let (Token::Number(value, _), Token::Word(unit)) = (&value.token, &unit.token) {
+                if let (Ok(value), Some(millis)) =
+                    (value.parse::<i64>(), time_unit_millis(&unit.value))
+                {
+                    let duration = value.checked_mul(millis).ok_or_else(|| {
+                        invalid(format!("duration {} {} overflows", value, unit.value))
+                    })?;
+                    return Ok(Expr::Value(
+                        Value::Number(duration.to_string(), false).into(),
+                    ));
+                }
+            }
+        }
+        // The condition of an expression window may be given as a string literal
+        [literal] if window_type == WINDOW_TYPE_EXPRESSION => {
+            if let Token::SingleQuotedString(condition) = &literal.token {
+                return parse_arg(&tokenize(condition)?, window_type);
+            }
+        }
+        _ => {}
+    }
+
+    let mut parser = Parser::new(&GenericDialect).with_tokens_with_locations(tokens.to_vec());
+    let expr = parser.parse_expr().map_err(|e| invalid(e.to_string()))?;
+    let rest = parser.peek_token();
+    if rest.token != Token::EOF {
+        return Err(invalid(format!(
+            "unexpected {}{}",
+            rest.token, rest.span.start
+        )));
+    }
+    Ok(expr)
+}
+
+/// Parse an ORDER BY style sort key into the sort window's parameters
+///
+/// `price DESC NULLS LAST` becomes `price`, `'desc'`, `'nulls last'`.
+fn parse_sort_key(tokens: &[TokenWithSpan]) -> Result<Vec<Expr>, ConverterError> {
+    let mut end = tokens.len();
+    let mut nulls = None;
+    if end >= 3
+        && is_keyword(&tokens[end - 2], "NULLS")
+        && (is_keyword(&tokens[end - 1], "FIRST") || is_keyword(&tokens[end - 1], "LAST"))
+    {
+        nulls = Some(format!("nulls {}", tokens[end - 1].token).to_ascii_lowercase());
+        end -= 2;
+    }
+    let mut order = None;
+    if end >= 2 && (is_keyword(&tokens[end - 1], "ASC") || is_keyword(&tokens[end - 1], "DESC")) {
+        order = Some(tokens[end - 1].token.to_string().to_ascii_lowercase());
+        end -= 1;
+    }
+
+    let mut params = vec![parse_arg(&tokens[..end], WINDOW_TYPE_SORT)?];
+    params.extend(order.into_iter().chain(nulls).map(string_arg));
+    Ok(params)
+}
+
+/// Match an extension window clause starting at the `WINDOW` token `idx`
+///
+/// Returns the spec and the index one past the clause's closing parenthesis,
+/// or `None` for windows the parser's grammar handles itself.
+pub(super) fn parse_window_clause(
+    tokens: &[TokenWithSpan],
+    idx: usize,
+) -> Result<Option<(ExtensionWindowSpec, usize)>, ConverterError> {
+    let (window_type, open, skip) = match (tokens.get(idx + 1), tokens.get(idx + 2)) {
+        // WINDOW('type', args)
+        (Some(open), Some(name)) if open.token == Token::LParen => match &name.token {
+            Token::SingleQuotedString(name) => match extension_window_type(name) {
+                Some(window_type) => (window_type, idx + 1, 1),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        },
+        // WINDOW type(args)
+        (Some(name), Some(open)) if open.token == Token::LParen => match &name.token {
+            Token::Word(w) if w.quote_style.is_none() => match extension_window_type(&w.value) {
+                Some(window_type) => (window_type, idx + 2, 0),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    let close = matching_paren(tokens, open).ok_or_else(|| {
+        ConverterError::InvalidExpression(format!(
+            "Unbalanced parentheses in WINDOW clause{}",
+            tokens[idx].span.start
+        ))
+    })?;
+    let mut args = Vec::new();
+    for (position, arg) in split_args(tokens, open, close)
+        .into_iter()
+        .enumerate()
+        .skip(skip)
+    {
+        if window_type == WINDOW_TYPE_SORT && position > skip {
+            args.extend(parse_sort_key(arg)?);
+        } else {
+            args.push(parse_arg(arg, window_type)?);
+        }
+    }
+    Ok(Some((
+        ExtensionWindowSpec {
+            window_type: window_type.to_string(),
+            args,
+        },
+        close + 1,
+    )))
+}
+
+impl ExtensionWindowSpec {
+    /// Convert the window arguments into a window handler
+    pub fn to_window_handler(&self, catalog: &SqlCatalog) -> Result<WindowHandler, ConverterError> {
+        let params = self
+            .args
+            .iter()
+            .map(|arg| SqlConverter::convert_expression(arg, catalog))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(WindowHandler::new(self.window_type.clone(), None, params))
+    }
+
+    /// Attach the window to a converted single-stream query
+    ///
+    /// The window goes ahead of the query's WHERE filter, matching the order
+    /// used for the built-in window types.
+    pub fn attach(&self, mut query: Query, catalog: &SqlCatalog) -> Result<Query, ConverterError> {
+        let handler = self.to_window_handler(catalog)?;
+        let single = match query.input_stream.as_mut() {
+            Some(InputStream::Single(single)) => single,
+            _ => {
+                return Err(ConverterError::UnsupportedFeature(format!(
+                    "WINDOW {}(...) is only supported on single-stream queries",
+                    self.window_type
+                )))
+            }
+        };
+
+        let stream_name = single.get_stream_id_str().to_string();
+        if catalog.is_window(&stream_name) {
+            return Err(ConverterError::UnsupportedFeature(format!(
+                "Named window '{stream_name}' already defines its window; remove the WINDOW clause"
+            )));
+        }
+        let handlers = single.get_stream_handlers_mut();
+        if handlers
+            .iter()
+            .any(|h| matches!(h, StreamHandler::Window(_)))
+        {
+            return Err(ConverterError::UnsupportedFeature(
+                "A query may declare only one WINDOW clause".to_string(),
+            ));
+        }
+        handlers.insert(0, StreamHandler::Window(Box::new(handler)));
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_compiler::statement::parse_statements;
+
+    /// Window of the query in `sql` and the query as parsed without it
+    fn window(sql: &str) -> (ExtensionWindowSpec, String) {
+        let parsed = parse_statements(sql).unwrap();
+        let spec = parsed[0].queries[0].window.clone().unwrap();
+        (spec, parsed[0].statement.to_string())
+    }
+
+    fn args(spec: &ExtensionWindowSpec) -> Vec<String> {
+        spec.args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_quoted_form_with_duration() {
+        let (spec, query) = window("SELECT * FROM S WINDOW('delay', 1 SEC) WHERE v > 1;");
+        assert_eq!(query, "SELECT * FROM S WHERE v > 1");
+        assert_eq!(spec.window_type, "delay");
+        assert_eq!(args(&spec), vec!["1000"]);
+    }
+
+    #[test]
+    fn test_shorthand_form_is_case_insensitive() {
+        let parsed =
+            parse_statements("SELECT 1 FROM A; SELECT * FROM S WINDOW FIRSTUNIQUE(a, b);").unwrap();
+        assert!(parsed[0].queries[0].window.is_none());
+        let spec = parsed[1].queries[0].window.as_ref().unwrap();
+        assert_eq!(spec.window_type, "firstUnique");
+        assert_eq!(args(spec), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_string_arguments_kept_whole() {
+        let (spec, _) = window("SELECT * FROM S WINDOW cron('*/1 * * * * *');");
+        assert_eq!(args(&spec), vec!["'*/1 * * * * *'"]);
+    }
+
+    #[test]
+    fn test_nested_parentheses_in_arguments() {
+        let (spec, query) = window("SELECT * FROM S WINDOW expression(count() <= 3);");
+        assert_eq!(query, "SELECT * FROM S");
+        assert_eq!(args(&spec), vec!["count() <= 3"]);
+    }
+
+    #[test]
+    fn test_expression_condition_as_string() {
+        let (spec, _) = window("SELECT * FROM S WINDOW('expression', 'count() <= 3');");
+        assert_eq!(args(&spec), vec!["count() <= 3"]);
+    }
+
+    #[test]
+    fn test_sort_keys_expanded() {
+        let (spec, _) =
+            window("SELECT * FROM S WINDOW SORT(100, price DESC NULLS LAST, ts ASC, volume);");
+        assert_eq!(spec.window_type, "sort");
+        assert_eq!(
+            args(&spec),
+            vec![
+                "100",
+                "price",
+                "'desc'",
+                "'nulls last'",
+                "ts",
+                "'asc'",
+                "volume"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_quoted_modifiers_kept() {
+        let (spec, _) = window("SELECT * FROM S WINDOW('sort', 5, price, 'desc');");
+        assert_eq!(args(&spec), vec!["5", "price", "'desc'"]);
+    }
+
+    #[test]
+    fn test_core_windows_untouched() {
+        let sql = "SELECT * FROM S WINDOW('length', 5); SELECT * FROM T WINDOW('time', 1 SEC);";
+        let parsed = parse_statements(sql).unwrap();
+        assert!(parsed.iter().all(|p| p.queries[0].window.is_none()));
+    }
+
+    #[test]
+    fn test_invalid_argument_rejected() {
+        assert!(parse_statements("SELECT * FROM S WINDOW unique(a b c);").is_err());
+    }
+
+    #[test]
+    fn test_overflowing_duration_rejected() {
+        let err = parse_statements("SELECT * FROM S WINDOW('delay', 9999999999999999 DAYS);")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("overflows"), "{err}");
+    }
+}
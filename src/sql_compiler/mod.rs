@@ -9,10 +9,22 @@
 //!
 //! The SQL compiler follows a multi-stage pipeline:
 //! 1. **SQL Parsing** - Forked sqlparser-rs with native WINDOW() support
-//! 2. **DDL Parsing** - Parse CREATE STREAM statements
-//! 3. **Type Mapping** - Convert SQL types to AttributeType
-//! 4. **SELECT Expansion** - Expand SELECT * using schema
-//! 5. **Conversion** - Convert to query_api::Query structures
+//! 2. **DDL Parsing** - Parse CREATE STREAM / CREATE WINDOW / CHANGES OF statements
+//! 3. **Query Clauses** - Take extension WINDOW and OUTPUT ... EVERY clauses from each statement's tokens
+//! 4. **Type Mapping** - Convert SQL types to AttributeType
+//! 5. **SELECT Expansion** - Expand SELECT * using schema
+//! 6. **Conversion** - Convert to query_api::Query structures
+//!
+//! # Clauses outside the parser's grammar
+//!
+//! The forked parser only carries the core `WINDOW('type', ...)` extension
+//! (see `FORK_MAINTENANCE.md`). `OUTPUT ... EVERY` rate clauses, extension
+//! windows, `CREATE WINDOW` and `CHANGES OF` are not part of its grammar:
+//! [`statement`] recognises them on the parser's own tokens and removes them
+//! before the statement is parsed, so they never appear in the sqlparser AST.
+//! This keeps the fork's diff against upstream small. Errors raised inside
+//! these clauses come from their own parsers rather than sqlparser's; they
+//! carry the line and column of the clause in the original SQL.
 //!
 //! # Example
 //!
@@ -32,11 +44,16 @@
 
 pub mod application;
 pub mod catalog;
+pub mod change_stream;
 pub mod converter;
 pub mod error;
 pub mod expansion;
+pub mod extension_window;
+pub mod named_window;
 pub mod normalization;
+pub mod output_rate;
 pub mod pattern_validation;
+pub mod statement;
 pub mod type_inference;
 pub mod type_mapping;
 pub mod with_clause;
@@ -49,8 +66,11 @@ pub use error::{
     ApplicationError, CatalogError, ConverterError, ExpansionError, SqlCompilerError, TypeError,
 };
 pub use expansion::SelectExpander;
+pub use extension_window::ExtensionWindowSpec;
+pub use named_window::NamedWindowSpec;
 pub use normalization::{is_create_stream, normalize_stream_syntax};
 pub use pattern_validation::{PatternValidationError, PatternValidator};
+pub use statement::{parse_statements, ParsedStatement, QueryClauses};
 pub use type_inference::{TypeContext, TypeInferenceEngine};
 pub use type_mapping::{attribute_type_to_sql_type, sql_type_to_attribute_type};
 pub use with_clause::{extract_with_options, validate_with_clause};
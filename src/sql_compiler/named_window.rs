@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Named Window Definitions
+//!
+//! `CREATE WINDOW` is not part of the SQL grammar. Its statement tokens are
+//! rewritten into a plain `CREATE TABLE` (so the schema flows through the
+//! normal DDL path) and the window specification is kept aside for the
+//! application parser:
+//!
+//! ```sql
+//! CREATE WINDOW RecentTrades (symbol STRING, price DOUBLE) TIME(10 MIN);
+//! CREATE WINDOW LastTen (symbol STRING, price DOUBLE) WINDOW('length', 10) OUTPUT EXPIRED EVENTS;
+//! ```
+//!
+//! The shorthand `NAME(args)` form is rewritten to `WINDOW('name', args)`, which
+//! is the form understood by the parser's window support.
+
+use sqlparser::tokenizer::{Token, TokenWithSpan};
+
+use crate::core::query::processor::stream::window::types::{
+    WINDOW_TYPE_CRON, WINDOW_TYPE_DELAY, WINDOW_TYPE_EXPRESSION, WINDOW_TYPE_EXTERNAL_TIME,
+    WINDOW_TYPE_EXTERNAL_TIME_BATCH, WINDOW_TYPE_FIRST_UNIQUE, WINDOW_TYPE_FREQUENT,
+    WINDOW_TYPE_LENGTH, WINDOW_TYPE_LENGTH_BATCH, WINDOW_TYPE_LOSSY_COUNTING, WINDOW_TYPE_SESSION,
+    WINDOW_TYPE_SORT, WINDOW_TYPE_TIME, WINDOW_TYPE_TIME_BATCH, WINDOW_TYPE_TIME_LENGTH,
+    WINDOW_TYPE_UNIQUE,
+};
+use crate::query_api::execution::query::output::OutputEventType;
+
+use super::error::ConverterError;
+use super::statement::{is_keyword, matching_paren};
+
+/// Window type names recognised in the `NAME(args)` shorthand
+const KNOWN_WINDOW_TYPES: &[&str] = &[
+    WINDOW_TYPE_LENGTH,
+    WINDOW_TYPE_TIME,
+    WINDOW_TYPE_LENGTH_BATCH,
+    WINDOW_TYPE_TIME_BATCH,
+    WINDOW_TYPE_EXTERNAL_TIME,
+    WINDOW_TYPE_EXTERNAL_TIME_BATCH,
+    WINDOW_TYPE_SESSION,
+    WINDOW_TYPE_SORT,
+    WINDOW_TYPE_CRON,
+    WINDOW_TYPE_LOSSY_COUNTING,
+    WINDOW_TYPE_UNIQUE,
+    WINDOW_TYPE_FIRST_UNIQUE,
+    WINDOW_TYPE_DELAY,
+    WINDOW_TYPE_FREQUENT,
+    WINDOW_TYPE_EXPRESSION,
+    WINDOW_TYPE_TIME_LENGTH,
+];
+
+/// Window specification of a `CREATE WINDOW` statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedWindowSpec {
+    /// Tokens of the window clause in `WINDOW('type', args)` form
+    pub window: Vec<TokenWithSpan>,
+    /// Which events the window emits to its readers
+    pub output_event_type: OutputEventType,
+}
+
+fn canonical_window_type(name: &str) -> String {
+    KNOWN_WINDOW_TYPES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(name))
+        .map(|known| known.to_string())
+        .unwrap_or_else(|| name.to_ascii_lowercase())
+}
+
+/// Parse a trailing `OUTPUT {ALL | CURRENT | EXPIRED} EVENTS` clause
+fn parse_output_event_type(
+    tokens: &[TokenWithSpan],
+    window_name: &str,
+) -> Result<OutputEventType, ConverterError> {
+    let location = tokens[0].span.start;
+    let kind = match tokens {
+        [output, kind, events]
+            if is_keyword(output, "OUTPUT")
+                && (is_keyword(events, "EVENTS") || is_keyword(events, "EVENT")) =>
+        {
+            kind
+        }
+        _ => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "Invalid clause after window '{window_name}'; expected OUTPUT {{ALL | CURRENT | EXPIRED}} EVENTS{location}"
+            )))
+        }
+    };
+    if is_keyword(kind, "ALL") {
+        Ok(OutputEventType::AllEvents)
+    } else if is_keyword(kind, "CURRENT") {
+        Ok(OutputEventType::CurrentEvents)
+    } else if is_keyword(kind, "EXPIRED") {
+        Ok(OutputEventType::ExpiredEvents)
+    } else {
+        Err(ConverterError::InvalidExpression(format!(
+            "Unknown output event type '{}' for window '{window_name}'{location}",
+            kind.token
+        )))
+    }
+}
+
+/// Rewrite the tokens of a `CREATE WINDOW` statement to `CREATE TABLE`
+///
+/// Returns the window specification, or `None` for any other statement.
+pub(super) fn take_window_definition(
+    tokens: &mut Vec<TokenWithSpan>,
+) -> Result<Option<NamedWindowSpec>, ConverterError> {
+    if !(tokens.len() >= 2 && is_keyword(&tokens[0], "CREATE") && is_keyword(&tokens[1], "WINDOW"))
+    {
+        return Ok(None);
+    }
+    let location = tokens[0].span.start;
+
+    let name = match tokens.get(2).map(|t| &t.token) {
+        Some(Token::Word(w)) => w.value.clone(),
+        _ => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "CREATE WINDOW requires a window name{location}"
+            )))
+        }
+    };
+    let columns_close = tokens
+        .get(3)
+        .filter(|t| t.token == Token::LParen)
+        .and_then(|_| matching_paren(tokens, 3))
+        .ok_or_else(|| {
+            ConverterError::InvalidExpression(format!(
+                "CREATE WINDOW {name} requires a column list{location}"
+            ))
+        })?;
+
+    let statement_end = match tokens.last() {
+        Some(last) if last.token == Token::SemiColon => tokens.len() - 1,
+        _ => tokens.len(),
+    };
+    let spec = &tokens[columns_close + 1..statement_end];
+    if spec.len() < 3 || spec[1].token != Token::LParen {
+        return Err(ConverterError::InvalidExpression(format!(
+            "CREATE WINDOW {name} requires a window specification, e.g. TIME(10 MIN){location}"
+        )));
+    }
+    let spec_close = matching_paren(spec, 1).ok_or_else(|| {
+        ConverterError::InvalidExpression(format!(
+            "Unbalanced parentheses in window specification of {name}{location}"
+        ))
+    })?;
+
+    let window = if is_keyword(&spec[0], "WINDOW") {
+        spec[..=spec_close].to_vec()
+    } else {
+        let window_type = match &spec[0].token {
+            Token::Word(w) => canonical_window_type(&w.value),
+            other => {
+                return Err(ConverterError::InvalidExpression(format!(
+                    "Unknown window type '{other}' for window {name}{location}"
+                )))
+            }
+        };
+        let mut window = vec![
+            TokenWithSpan::wrap(Token::make_keyword("WINDOW")),
+            spec[1].clone(),
+            TokenWithSpan::wrap(Token::SingleQuotedString(window_type)),
+        ];
+        if spec_close > 2 {
+            window.push(TokenWithSpan::wrap(Token::Comma));
+            window.extend_from_slice(&spec[2..spec_close]);
+        }
+        window.push(spec[spec_close].clone());
+        window
+    };
+    let output_event_type = if spec_close + 1 < spec.len() {
+        parse_output_event_type(&spec[spec_close + 1..], &name)?
+    } else {
+        OutputEventType::AllEvents
+    };
+
+    tokens[1].token = Token::make_keyword("TABLE");
+    tokens.drain(columns_close + 1..statement_end);
+    Ok(Some(NamedWindowSpec {
+        window,
+        output_event_type,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_compiler::statement::parse_statements;
+
+    fn window_sql(spec: &NamedWindowSpec) -> String {
+        spec.window
+            .iter()
+            .map(|t| t.token.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_shorthand_window_spec() {
+        let parsed = parse_statements(
+            "CREATE WINDOW RecentTrades (symbol STRING, price DOUBLE) TIME(10 MIN);\nSELECT 1;",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed[0].statement.to_string(),
+            "CREATE TABLE RecentTrades (symbol STRING, price DOUBLE)"
+        );
+        let spec = parsed[0].window.as_ref().unwrap();
+        assert_eq!(window_sql(spec), "WINDOW ( 'time' , 10 MIN )");
+        assert_eq!(spec.output_event_type, OutputEventType::AllEvents);
+        assert!(parsed[1].window.is_none());
+    }
+
+    #[test]
+    fn test_window_clause_and_output_type() {
+        let parsed = parse_statements(
+            "create window LastTen (v INT) WINDOW('length', 10) OUTPUT EXPIRED EVENTS;",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed[0].statement.to_string(),
+            "CREATE TABLE LastTen (v INT)"
+        );
+        let spec = parsed[0].window.as_ref().unwrap();
+        assert_eq!(window_sql(spec), "WINDOW ( 'length' , 10 )");
+        assert_eq!(spec.output_event_type, OutputEventType::ExpiredEvents);
+    }
+
+    #[test]
+    fn test_camel_case_window_type() {
+        let parsed = parse_statements("CREATE WINDOW W (v INT) LENGTHBATCH(5)").unwrap();
+        assert_eq!(
+            window_sql(parsed[0].window.as_ref().unwrap()),
+            "WINDOW ( 'lengthBatch' , 5 )"
+        );
+    }
+
+    #[test]
+    fn test_missing_window_spec_is_error() {
+        assert!(parse_statements("CREATE WINDOW W (v INT);").is_err());
+    }
+
+    #[test]
+    fn test_unknown_output_clause_is_error() {
+        let err = parse_statements("CREATE WINDOW W (v INT) LENGTH(5) OUTPUT SOME EVENTS;")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown output event type"), "{err}");
+    }
+
+    #[test]
+    fn test_statements_without_windows_untouched() {
+        let sql = "CREATE TABLE T (v INT); SELECT 'CREATE WINDOW' AS s FROM S;";
+        let parsed = parse_statements(sql).unwrap();
+        assert!(parsed.iter().all(|p| p.window.is_none()));
+    }
+}
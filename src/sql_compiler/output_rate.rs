@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Output Rate Limiting Clauses
+//!
+//! Parses EventFlux `OUTPUT ... EVERY ...` clauses from a query's tokens and
+//! maps them onto query_api [`OutputRate`] values. The clauses are found and
+//! removed per statement by [`parse_statements`](super::statement::parse_statements).
+//!
+//! Supported forms (case-insensitive):
+//!
+//! ```sql
+//! OUTPUT [ALL | FIRST | LAST] EVERY <n> EVENTS
+//! OUTPUT [ALL | FIRST | LAST] EVERY <n> <time unit>
+//! OUTPUT SNAPSHOT EVERY <n> <time unit>
+//! ```
+
+use sqlparser::tokenizer::{Token, TokenWithSpan};
+
+use crate::query_api::execution::query::output::ratelimit::{OutputRate, OutputRateBehavior};
+use crate::query_api::expression::constant::Constant;
+
+use super::error::ConverterError;
+use super::statement::is_keyword;
+
+/// Milliseconds per unit for time-based output rates
+pub(super) fn time_unit_millis(unit: &str) -> Option<i64> {
+    match unit.to_ascii_uppercase().as_str() {
+        "MILLISECOND" | "MILLISECONDS" | "MILLISEC" | "MILLISECS" | "MS" => Some(1),
+        "SECOND" | "SECONDS" | "SEC" | "SECS" => Some(1_000),
+        "MINUTE" | "MINUTES" | "MIN" | "MINS" => Some(60_000),
+        "HOUR" | "HOURS" => Some(3_600_000),
+        "DAY" | "DAYS" => Some(86_400_000),
+        _ => None,
+    }
+}
+
+fn is_event_unit(unit: &str) -> bool {
+    unit.eq_ignore_ascii_case("EVENT") || unit.eq_ignore_ascii_case("EVENTS")
+}
+
+/// Parse the tokens following `OUTPUT` into an [`OutputRate`].
+///
+/// Returns `Ok(None)` when the tokens do not form a rate clause (e.g. a column
+/// named `output`), and the index one past the clause's last token otherwise.
+pub(super) fn parse_rate_clause(
+    tokens: &[TokenWithSpan],
+    output_idx: usize,
+) -> Result<Option<(OutputRate, usize)>, ConverterError> {
+    let location = tokens[output_idx].span.start;
+    let mut idx = output_idx + 1;
+
+    enum Kind {
+        Rate(OutputRateBehavior),
+        Snapshot,
+    }
+
+    let kind = match tokens.get(idx) {
+        Some(t) if is_keyword(t, "ALL") => {
+            idx += 1;
+            Kind::Rate(OutputRateBehavior::All)
+        }
+        Some(t) if is_keyword(t, "FIRST") => {
+            idx += 1;
+            Kind::Rate(OutputRateBehavior::First)
+        }
+        Some(t) if is_keyword(t, "LAST") => {
+            idx += 1;
+            Kind::Rate(OutputRateBehavior::Last)
+        }
+        Some(t) if is_keyword(t, "SNAPSHOT") => {
+            idx += 1;
+            Kind::Snapshot
+        }
+        Some(t) if is_keyword(t, "EVERY") => Kind::Rate(OutputRateBehavior::All),
+        _ => return Ok(None),
+    };
+
+    match tokens.get(idx) {
+        Some(t) if is_keyword(t, "EVERY") => idx += 1,
+        _ => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "OUTPUT rate clause requires EVERY, e.g. OUTPUT LAST EVERY 5 SECONDS{location}"
+            )))
+        }
+    }
+
+    let value = match tokens.get(idx).map(|t| &t.token) {
+        Some(Token::Number(n, _)) => n.parse::<i64>().ok().filter(|v| *v > 0),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        ConverterError::InvalidExpression(format!(
+            "OUTPUT ... EVERY requires a positive integer value{location}"
+        ))
+    })?;
+    idx += 1;
+
+    let unit = match tokens.get(idx).map(|t| &t.token) {
+        Some(Token::Word(w)) if w.quote_style.is_none() => w.value.as_str(),
+        _ => {
+            return Err(ConverterError::InvalidExpression(format!(
+                "OUTPUT ... EVERY requires a unit (EVENTS or a time unit){location}"
+            )))
+        }
+    };
+    idx += 1;
+
+    let rate = if is_event_unit(unit) {
+        match kind {
+            Kind::Rate(behavior) => {
+                let count = i32::try_from(value).map_err(|_| {
+                    ConverterError::InvalidExpression(format!(
+                        "OUTPUT event count {} exceeds supported range{location}",
+                        value
+                    ))
+                })?;
+                OutputRate::per_events(Constant::int(count), behavior)
+            }
+            Kind::Snapshot => {
+                return Err(ConverterError::UnsupportedFeature(format!(
+                    "OUTPUT SNAPSHOT requires a time interval, not an event count{location}"
+                )))
+            }
+        }
+    } else {
+        let millis = time_unit_millis(unit).ok_or_else(|| {
+            ConverterError::InvalidExpression(format!(
+                "Unknown OUTPUT rate unit '{}'{location}",
+                unit
+            ))
+        })?;
+        let period = value.checked_mul(millis).ok_or_else(|| {
+            ConverterError::InvalidExpression(format!(
+                "OUTPUT rate interval {} {} overflows{location}",
+                value, unit
+            ))
+        })?;
+        match kind {
+            Kind::Rate(behavior) => OutputRate::per_time_period(Constant::long(period), behavior),
+            Kind::Snapshot => OutputRate::per_snapshot(Constant::long(period)),
+        }
+    }
+    .map_err(ConverterError::ConversionFailed)?;
+
+    Ok(Some((rate, idx)))
+}
@@ -0,0 +1,398 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Statement Parsing
+//!
+//! Splits a SQL application into its statements on the parser's own token
+//! stream and parses them one at a time. EventFlux clauses that the parser's
+//! grammar does not cover are recognised on the tokens of the statement they
+//! belong to and removed before the statement is parsed:
+//!
+//! ```sql
+//! INSERT INTO Out SELECT symbol FROM S WINDOW unique(symbol) OUTPUT LAST EVERY 5 SECONDS;
+//! ```
+//!
+//! Working on tokens rather than text means string literals, quoted
+//! identifiers, comments and `$$` bodies are never searched, clauses inside
+//! subqueries are left alone, and parse errors keep the line and column of the
+//! original SQL. Each clause is attached to the query of its own statement;
+//! for a PARTITION, to the body statement it appears in. `CREATE WINDOW` and
+//! `CREATE STREAM ... AS CHANGES OF` statements are rewritten to
+//! `CREATE TABLE` the same way.
+
+use sqlparser::ast::Statement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
+
+use crate::query_api::execution::query::output::ratelimit::OutputRate;
+use crate::query_api::execution::query::Query;
+
+use super::catalog::SqlCatalog;
+use super::change_stream::take_change_stream;
+use super::error::ConverterError;
+use super::extension_window::{parse_window_clause, ExtensionWindowSpec};
+use super::named_window::{take_window_definition, NamedWindowSpec};
+use super::output_rate::parse_rate_clause;
+
+/// EventFlux clauses taken out of one query
+#[derive(Debug, Clone, Default)]
+pub struct QueryClauses {
+    /// Window outside the parser's grammar, e.g. `WINDOW unique(symbol)`
+    pub window: Option<ExtensionWindowSpec>,
+    /// `OUTPUT ... EVERY ...` rate limit
+    pub output_rate: Option<OutputRate>,
+}
+
+impl QueryClauses {
+    /// Apply the clauses to the converted query
+    pub fn attach(self, query: Query, catalog: &SqlCatalog) -> Result<Query, ConverterError> {
+        let query = match self.window {
+            Some(window) => window.attach(query, catalog)?,
+            None => query,
+        };
+        Ok(match self.output_rate {
+            Some(rate) => query.output(rate),
+            None => query,
+        })
+    }
+}
+
+/// A parsed statement together with the clauses of the queries it contains
+#[derive(Debug, Clone)]
+pub struct ParsedStatement {
+    pub statement: Statement,
+    /// One entry per query, in order: a single entry for SELECT and INSERT,
+    /// one per body statement for PARTITION and none for DDL
+    pub queries: Vec<QueryClauses>,
+    /// Window of a `CREATE WINDOW` statement, parsed as `CREATE TABLE`
+    pub window: Option<NamedWindowSpec>,
+    /// Source table of a `CREATE STREAM ... AS CHANGES OF` statement, parsed
+    /// as `CREATE TABLE`
+    pub change_source: Option<String>,
+}
+
+/// Whether `token` is the (unquoted) keyword `keyword`
+pub(super) fn is_keyword(token: &TokenWithSpan, keyword: &str) -> bool {
+    match &token.token {
+        Token::Word(w) => w.quote_style.is_none() && w.value.eq_ignore_ascii_case(keyword),
+        _ => false,
+    }
+}
+
+/// Index of the token closing the parenthesis opened at `open_idx`
+pub(super) fn matching_paren(tokens: &[TokenWithSpan], open_idx: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, token) in tokens.iter().enumerate().skip(open_idx) {
+        match token.token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Tokenize SQL, dropping whitespace and comments
+pub(super) fn tokenize(sql: &str) -> Result<Vec<TokenWithSpan>, ConverterError> {
+    let tokens = Tokenizer::new(&GenericDialect, sql)
+        .tokenize_with_location()
+        .map_err(|e| ConverterError::ConversionFailed(format!("SQL parse error: {}", e)))?;
+    Ok(tokens
+        .into_iter()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .collect())
+}
+
+/// Split tokens after every `;` that is outside parentheses and BEGIN/CASE ... END
+///
+/// Each unit keeps its terminating `;`, so units can be joined back together.
+fn split_units(tokens: Vec<TokenWithSpan>) -> Vec<Vec<TokenWithSpan>> {
+    let mut units = Vec::new();
+    let mut current = Vec::new();
+    let mut parens = 0usize;
+    let mut blocks = 0usize;
+    for token in tokens {
+        let end_of_unit = match &token.token {
+            Token::LParen => {
+                parens += 1;
+                false
+            }
+            Token::RParen => {
+                parens = parens.saturating_sub(1);
+                false
+            }
+            Token::SemiColon => parens == 0 && blocks == 0,
+            _ if is_keyword(&token, "BEGIN") || is_keyword(&token, "CASE") => {
+                blocks += 1;
+                false
+            }
+            _ if is_keyword(&token, "END") => {
+                blocks = blocks.saturating_sub(1);
+                false
+            }
+            _ => false,
+        };
+        current.push(token);
+        if end_of_unit {
+            units.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        units.push(current);
+    }
+    units
+}
+
+fn is_query(tokens: &[TokenWithSpan]) -> bool {
+    match tokens.first() {
+        Some(first) => {
+            matches!(first.token, Token::LParen)
+                || ["SELECT", "INSERT", "WITH"]
+                    .iter()
+                    .any(|keyword| is_keyword(first, keyword))
+        }
+        None => false,
+    }
+}
+
+/// Remove the EventFlux clauses from the top level of one query's tokens
+fn take_query_clauses(tokens: &mut Vec<TokenWithSpan>) -> Result<QueryClauses, ConverterError> {
+    let mut clauses = QueryClauses::default();
+    let mut depth = 0usize;
+    let mut idx = 0;
+    while idx < tokens.len() {
+        match &tokens[idx].token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            _ if depth == 0 && is_keyword(&tokens[idx], "OUTPUT") => {
+                if let Some((rate, next)) = parse_rate_clause(tokens, idx)? {
+                    if clauses.output_rate.replace(rate).is_some() {
+                        return Err(ConverterError::InvalidExpression(format!(
+                            "A query may declare only one OUTPUT rate clause{}",
+                            tokens[idx].span.start
+                        )));
+                    }
+                    tokens.drain(idx..next);
+                    continue;
+                }
+            }
+            _ if depth == 0 && is_keyword(&tokens[idx], "WINDOW") => {
+                if let Some((window, next)) = parse_window_clause(tokens, idx)? {
+                    if clauses.window.replace(window).is_some() {
+                        return Err(ConverterError::UnsupportedFeature(format!(
+                            "A query may declare only one extension window{}",
+                            tokens[idx].span.start
+                        )));
+                    }
+                    tokens.drain(idx..next);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    Ok(clauses)
+}
+
+/// Take the clauses of every statement in a PARTITION body
+///
+/// The body runs from the first top-level BEGIN to the last END.
+fn take_partition_clauses(
+    tokens: Vec<TokenWithSpan>,
+) -> Result<(Vec<TokenWithSpan>, Vec<QueryClauses>), ConverterError> {
+    let begin = tokens.iter().position(|t| is_keyword(t, "BEGIN"));
+    let end = tokens.iter().rposition(|t| is_keyword(t, "END"));
+    let (begin, end) = match (begin, end) {
+        (Some(begin), Some(end)) if begin < end => (begin, end),
+        // Malformed; let the parser report it
+        _ => return Ok((tokens, Vec::new())),
+    };
+
+    let mut tokens = tokens;
+    let tail = tokens.split_off(end);
+    let body = tokens.split_off(begin + 1);
+    let mut queries = Vec::new();
+    for mut unit in split_units(body) {
+        if is_query(&unit) {
+            queries.push(take_query_clauses(&mut unit)?);
+        }
+        tokens.extend(unit);
+    }
+    tokens.extend(tail);
+    Ok((tokens, queries))
+}
+
+fn parse_unit(tokens: Vec<TokenWithSpan>) -> Result<Vec<Statement>, ConverterError> {
+    Parser::new(&GenericDialect)
+        .with_tokens_with_locations(tokens)
+        .parse_statements()
+        .map_err(|e| ConverterError::ConversionFailed(format!("SQL parse error: {}", e)))
+}
+
+/// Parse every statement of a SQL application, collecting each query's EventFlux clauses
+///
+/// # Example
+///
+/// ```
+/// use eventflux_rust::sql_compiler::statement::parse_statements;
+///
+/// let parsed = parse_statements(
+///     "CREATE TABLE S (symbol VARCHAR);\n\
+///      SELECT symbol FROM S OUTPUT LAST EVERY 5 SECONDS;",
+/// )
+/// .unwrap();
+/// assert_eq!(parsed.len(), 2);
+/// assert!(parsed[0].queries.is_empty());
+/// assert!(parsed[1].queries[0].output_rate.is_some());
+/// ```
+pub fn parse_statements(sql: &str) -> Result<Vec<ParsedStatement>, ConverterError> {
+    parse_tokens(tokenize(sql)?)
+}
+
+/// Parse statements from tokens without whitespace, as produced by [`tokenize`]
+pub(super) fn parse_tokens(
+    tokens: Vec<TokenWithSpan>,
+) -> Result<Vec<ParsedStatement>, ConverterError> {
+    let mut parsed = Vec::new();
+    for mut unit in split_units(tokens) {
+        let mut window = None;
+        let mut change_source = None;
+        let mut queries = if unit.first().is_some_and(|t| is_keyword(t, "PARTITION")) {
+            let (tokens, queries) = take_partition_clauses(unit)?;
+            unit = tokens;
+            queries
+        } else if is_query(&unit) {
+            vec![take_query_clauses(&mut unit)?]
+        } else {
+            window = take_window_definition(&mut unit)?;
+            change_source = take_change_stream(&mut unit)?;
+            Vec::new()
+        };
+
+        for statement in parse_unit(unit)? {
+            parsed.push(ParsedStatement {
+                statement,
+                queries: std::mem::take(&mut queries),
+                window: window.take(),
+                change_source: change_source.take(),
+            });
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_api::execution::query::output::ratelimit::{
+        OutputRateBehavior, OutputRateVariant,
+    };
+
+    fn rate(parsed: &[ParsedStatement], stmt: usize, query: usize) -> Option<OutputRateVariant> {
+        parsed[stmt].queries[query]
+            .output_rate
+            .as_ref()
+            .map(|r| r.variant.clone())
+    }
+
+    #[test]
+    fn test_events_last() {
+        let parsed = parse_statements("SELECT a FROM S OUTPUT LAST EVERY 3 EVENTS;").unwrap();
+        match rate(&parsed, 0, 0).unwrap() {
+            OutputRateVariant::Events(ev, behavior) => {
+                assert_eq!(ev.event_count, 3);
+                assert_eq!(behavior, OutputRateBehavior::Last);
+            }
+            other => panic!("unexpected variant {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_time_default_all() {
+        let parsed = parse_statements("select a from S output every 2 seconds").unwrap();
+        match rate(&parsed, 0, 0).unwrap() {
+            OutputRateVariant::Time(t, behavior) => {
+                assert_eq!(t.time_value_millis, 2_000);
+                assert_eq!(behavior, OutputRateBehavior::All);
+            }
+            other => panic!("unexpected variant {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let parsed =
+            parse_statements("SELECT sum(x) FROM S OUTPUT SNAPSHOT EVERY 1 MINUTE;").unwrap();
+        match rate(&parsed, 0, 0).unwrap() {
+            OutputRateVariant::Snapshot(s) => assert_eq!(s.time_value_millis, 60_000),
+            other => panic!("unexpected variant {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_clause_belongs_to_its_statement() {
+        let sql = "CREATE TABLE S (a INT);\n\
+                   INSERT INTO O1 SELECT a FROM S WHERE a IN (SELECT a FROM S);\n\
+                   INSERT INTO O2 SELECT a FROM S OUTPUT FIRST EVERY 100 EVENTS;";
+        let parsed = parse_statements(sql).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert!(rate(&parsed, 1, 0).is_none());
+        assert!(rate(&parsed, 2, 0).is_some());
+    }
+
+    #[test]
+    fn test_partition_body_statements() {
+        let sql = "PARTITION WITH (a OF S) BEGIN\n\
+                     INSERT INTO O1 SELECT a FROM S;\n\
+                     INSERT INTO O2 SELECT CASE WHEN a > 1 THEN a END AS b FROM S\n\
+                     OUTPUT LAST EVERY 2 EVENTS;\n\
+                   END;\n\
+                   SELECT a FROM S OUTPUT EVERY 5 EVENTS;";
+        let parsed = parse_statements(sql).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].queries.len(), 2);
+        assert!(rate(&parsed, 0, 0).is_none());
+        assert!(rate(&parsed, 0, 1).is_some());
+        assert!(rate(&parsed, 1, 0).is_some());
+    }
+
+    #[test]
+    fn test_strings_columns_and_dollar_quotes_ignored() {
+        let sql = "SELECT output, 'OUTPUT LAST EVERY 3 EVENTS' FROM S;\n\
+                   CREATE FUNCTION f(x INT) RETURNS INT LANGUAGE js\n\
+                   AS $$ x; // OUTPUT EVERY 1 EVENTS $$;";
+        let parsed = parse_statements(sql).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(rate(&parsed, 0, 0).is_none());
+    }
+
+    #[test]
+    fn test_subquery_clause_not_taken() {
+        let sql = "SELECT a FROM (SELECT a FROM S OUTPUT LAST EVERY 3 EVENTS) AS t;";
+        assert!(parse_statements(sql).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_with_events_rejected() {
+        assert!(parse_statements("SELECT a FROM S OUTPUT SNAPSHOT EVERY 3 EVENTS").is_err());
+    }
+
+    #[test]
+    fn test_missing_every_rejected() {
+        assert!(parse_statements("SELECT a FROM S OUTPUT LAST 3 EVENTS").is_err());
+    }
+
+    #[test]
+    fn test_parse_error_keeps_location() {
+        let err = parse_statements("SELECT a FROM S;\nSELECT FROM WHERE;")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Line: 2"), "{err}");
+    }
+}
@@ -101,6 +101,17 @@ fn is_numeric(t: AttributeType) -> bool {
     )
 }
 
+/// Return type of two-argument numeric aggregators such as `corr` and `wavg`
+fn numeric_pair_to_double(args: &[AttributeType]) -> Result<AttributeType, TypeError> {
+    if is_numeric(args[0]) && is_numeric(args[1]) {
+        Ok(AttributeType::DOUBLE)
+    } else {
+        Err(TypeError::ConversionFailed(
+            "Two-argument statistical aggregates require numeric arguments".into(),
+        ))
+    }
+}
+
 // Function registry - data-driven instead of huge match statement
 fn get_function_signature(name: &str) -> Option<&'static FunctionSignature> {
     static FUNCTIONS: &[FunctionSignature] = &[
@@ -136,6 +147,45 @@ fn get_function_signature(name: &str) -> Option<&'static FunctionSignature> {
                 ))
             }
         }),
+        // Sketch aggregators - percentile/median return DOUBLE, top_k an OBJECT list
+        FunctionSignature::new("percentile", 2, |args| {
+            if is_numeric(args[0]) && is_numeric(args[1]) {
+                Ok(AttributeType::DOUBLE)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "PERCENTILE requires numeric arguments".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("median", 1, |args| {
+            if is_numeric(args[0]) {
+                Ok(AttributeType::DOUBLE)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "MEDIAN requires numeric argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("approx_distinct", 1, |_| Ok(AttributeType::LONG)),
+        FunctionSignature::new("top_k", 2, |_| Ok(AttributeType::OBJECT)),
+        // Two-argument aggregators
+        FunctionSignature::new("corr", 2, numeric_pair_to_double),
+        FunctionSignature::new("covar_pop", 2, numeric_pair_to_double),
+        FunctionSignature::new("covar_samp", 2, numeric_pair_to_double),
+        FunctionSignature::new("regr_slope", 2, numeric_pair_to_double),
+        FunctionSignature::new("regr_intercept", 2, numeric_pair_to_double),
+        FunctionSignature::new("wavg", 2, numeric_pair_to_double),
+        // arg_max(by, value) / arg_min(by, value) return the value's type
+        FunctionSignature::new("arg_max", 2, |args| Ok(args[1])),
+        FunctionSignature::new("arg_min", 2, |args| Ok(args[1])),
+        // Pattern collection aggregates - first/last return the value's type
+        FunctionSignature::new("first", 1, |args| Ok(args[0])),
+        FunctionSignature::new("last", 1, |args| Ok(args[0])),
+        FunctionSignature::new("collect", 1, |_| Ok(AttributeType::OBJECT)),
+        // Analytic functions (with OVER) - lag/lead return the value's type
+        FunctionSignature::new("row_number", 0, |_| Ok(AttributeType::LONG)),
+        FunctionSignature::new("lag", 1, |args| Ok(args[0])),
+        FunctionSignature::new("lead", 1, |args| Ok(args[0])),
         // Math functions
         FunctionSignature::new("round", 1, |args| match args[0] {
             AttributeType::FLOAT | AttributeType::DOUBLE => Ok(AttributeType::DOUBLE),
@@ -191,6 +241,108 @@ fn get_function_signature(name: &str) -> Option<&'static FunctionSignature> {
         FunctionSignature::new("nullif", 2, |args| Ok(args[0])),
         // uuid - generates a random UUID string
         FunctionSignature::new("uuid", 0, |_| Ok(AttributeType::STRING)),
+        // uuid_v5 - name-based UUID from (namespace, name)
+        FunctionSignature::new("uuid_v5", 2, |args| {
+            if args[0] == AttributeType::STRING && args[1] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "UUID_V5 requires (STRING, STRING) arguments".into(),
+                ))
+            }
+        }),
+        // Hashing functions - digests are lowercase hex, checksums are LONG
+        FunctionSignature::new("md5", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "MD5 requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("sha1", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "SHA1 requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("sha256", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "SHA256 requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("hmac_sha256", 2, |args| {
+            if args[0] == AttributeType::STRING && args[1] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "HMAC_SHA256 requires (STRING, STRING) arguments".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("crc32", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::LONG)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "CRC32 requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("xxhash64", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::LONG)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "XXHASH64 requires STRING argument".into(),
+                ))
+            }
+        }),
+        // Encoding functions
+        FunctionSignature::new("base64_encode", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "BASE64_ENCODE requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("base64_decode", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "BASE64_DECODE requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("hex", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "HEX requires STRING argument".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("unhex", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "UNHEX requires STRING argument".into(),
+                ))
+            }
+        }),
         // eventTimestamp - returns event timestamp as LONG
         FunctionSignature::new("eventtimestamp", 0, |_| Ok(AttributeType::LONG)),
         // now - returns current timestamp in milliseconds
@@ -563,6 +715,83 @@ fn get_function_signature(name: &str) -> Option<&'static FunctionSignature> {
                 ))
             }
         }),
+        // regexp_matches - whether a regex matches anywhere in the string
+        FunctionSignature::new("regexp_matches", 2, |args| {
+            if args[0] == AttributeType::STRING && args[1] == AttributeType::STRING {
+                Ok(AttributeType::BOOL)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "REGEXP_MATCHES requires (STRING, STRING) arguments".into(),
+                ))
+            }
+        }),
+        // regexp_extract - capture group of the first match
+        FunctionSignature::new("regexp_extract", 2, |args| {
+            if args[0] == AttributeType::STRING
+                && args[1] == AttributeType::STRING
+                && (args.len() < 3 || is_numeric(args[2]))
+            {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "REGEXP_EXTRACT requires (STRING, STRING[, INT]) arguments".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("regexp_replace", 3, |args| {
+            if args[0] == AttributeType::STRING
+                && args[1] == AttributeType::STRING
+                && args[2] == AttributeType::STRING
+            {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "REGEXP_REPLACE requires STRING arguments".into(),
+                ))
+            }
+        }),
+        // split_part - nth field of a delimited string
+        FunctionSignature::new("split_part", 3, |args| {
+            if args[0] == AttributeType::STRING
+                && args[1] == AttributeType::STRING
+                && is_numeric(args[2])
+            {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "SPLIT_PART requires (STRING, STRING, INT) arguments".into(),
+                ))
+            }
+        }),
+        // split - array of the fields of a delimited string
+        FunctionSignature::new("split", 2, |args| {
+            if args[0] == AttributeType::STRING && args[1] == AttributeType::STRING {
+                Ok(AttributeType::OBJECT)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "SPLIT requires (STRING, STRING) arguments".into(),
+                ))
+            }
+        }),
+        // parse_url - one component of a URL
+        FunctionSignature::new("parse_url", 2, |args| {
+            if args.iter().all(|a| *a == AttributeType::STRING) {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "PARSE_URL requires STRING arguments".into(),
+                ))
+            }
+        }),
+        FunctionSignature::new("url_decode", 1, |args| {
+            if args[0] == AttributeType::STRING {
+                Ok(AttributeType::STRING)
+            } else {
+                Err(TypeError::ConversionFailed(
+                    "URL_DECODE requires STRING argument".into(),
+                ))
+            }
+        }),
     ];
 
     FUNCTIONS.iter().find(|f| f.name == name)
@@ -859,6 +1088,20 @@ impl<'a> TypeInferenceEngine<'a> {
     ) -> Result<AttributeType, TypeError> {
         let func_name = func.function_name.to_lowercase();
 
+        // count(e1) over a pattern capture counts its events
+        if func_name == "count" {
+            if let [Expression::Variable(var)] = func.parameters.as_slice() {
+                if var.get_stream_id().is_none()
+                    && context
+                        .available_streams
+                        .iter()
+                        .any(|s| s == var.get_attribute_name())
+                {
+                    return Ok(AttributeType::LONG);
+                }
+            }
+        }
+
         // Infer parameter types
         let param_types: Result<Vec<AttributeType>, TypeError> = func
             .parameters
@@ -867,6 +1110,18 @@ impl<'a> TypeInferenceEngine<'a> {
             .collect();
         let param_types = param_types?;
 
+        if let Some(filter) = &func.filter {
+            self.validate_boolean_expression(filter, context, "FILTER")?;
+        }
+        if let Some(over) = &func.over {
+            for expr in &over.partition_by {
+                self.infer_type(expr, context)?;
+            }
+            for order in &over.order_by {
+                self.infer_type(order.get_expression(), context)?;
+            }
+        }
+
         // Lookup function signature
         if let Some(sig) = get_function_signature(&func_name) {
             // Validate argument count
@@ -881,6 +1136,17 @@ impl<'a> TypeInferenceEngine<'a> {
 
             // Apply signature-based type inference
             (sig.return_type)(&param_types)
+        } else if let Some(function) = self.catalog.get_function(&func_name) {
+            // Script function declared with CREATE FUNCTION
+            if param_types.len() != function.parameters.len() {
+                return Err(TypeError::ConversionFailed(format!(
+                    "{} requires {} argument(s), found {}",
+                    func_name.to_uppercase(),
+                    function.parameters.len(),
+                    param_types.len()
+                )));
+            }
+            Ok(function.return_type)
         } else {
             // Unknown function - default to OBJECT (allows UDFs)
             Ok(AttributeType::OBJECT)
@@ -1255,6 +1521,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_function_signatures() {
+        let catalog = create_test_catalog();
+        let engine = TypeInferenceEngine::new(&catalog);
+        let context = TypeContext::from_stream("TestStream".to_string());
+        let call = |name: &str, arg: &str| {
+            Expression::function_no_ns(
+                name.to_string(),
+                vec![Expression::variable(arg.to_string())],
+            )
+        };
+
+        assert_eq!(
+            engine
+                .infer_type(&call("sha256", "symbol"), &context)
+                .unwrap(),
+            AttributeType::STRING
+        );
+        assert_eq!(
+            engine
+                .infer_type(&call("xxhash64", "symbol"), &context)
+                .unwrap(),
+            AttributeType::LONG
+        );
+        assert!(engine.infer_type(&call("md5", "price"), &context).is_err());
+    }
+
     #[test]
     fn test_cast_supported_conversions() {
         // String to numeric
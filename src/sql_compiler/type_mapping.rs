@@ -60,11 +60,12 @@ pub fn sql_type_to_attribute_type(sql_type: &DataType) -> Result<AttributeType,
             TypeError::UnsupportedType("Binary types not supported".to_string()),
         ),
 
-        // Handle custom types (like LONG which is not standard SQL)
+        // Handle custom types (like LONG and OBJECT which are not standard SQL)
         DataType::Custom(name, _) => {
             let type_name = name.to_string().to_uppercase();
             match type_name.as_str() {
                 "LONG" => Ok(AttributeType::LONG),
+                "OBJECT" => Ok(AttributeType::OBJECT),
                 _ => Err(TypeError::UnsupportedType(format!("{:?}", sql_type))),
             }
         }
@@ -90,6 +91,7 @@ pub fn attribute_type_to_sql_type(attr_type: &AttributeType) -> DataType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlparser::ast::{Ident, ObjectName};
 
     #[test]
     fn test_string_types() {
@@ -119,6 +121,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_object_type() {
+        let object = DataType::Custom(ObjectName::from(vec![Ident::new("OBJECT")]), vec![]);
+        assert_eq!(
+            sql_type_to_attribute_type(&object).unwrap(),
+            AttributeType::OBJECT
+        );
+    }
+
     #[test]
     fn test_float_types() {
         assert_eq!(
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+
+fn event(uid: &str, status: &str, amount: i32) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::String(uid.to_string()),
+        AttributeValue::String(status.to_string()),
+        AttributeValue::Int(amount),
+    ]
+}
+
+#[tokio::test]
+async fn filter_and_distinct_in_same_select() {
+    let app = "\
+        CREATE STREAM In (uid STRING, status STRING, amount INT);\n\
+        CREATE STREAM Out (failed BIGINT, failedAmount BIGINT, users BIGINT);\n\
+        INSERT INTO Out\n\
+        SELECT count(*) FILTER (WHERE status = 'FAILED') AS failed,\n\
+               sum(amount) FILTER (WHERE status = 'FAILED') AS failedAmount,\n\
+               count(DISTINCT uid) AS users\n\
+        FROM In WINDOW('length', 3);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", event("a", "FAILED", 10));
+    runner.send("In", event("b", "OK", 20));
+    runner.send("In", event("a", "FAILED", 5));
+    // Expires the first FAILED event; "a" is still in the window
+    runner.send("In", event("c", "OK", 7));
+    let out = runner.shutdown();
+    assert!(out.contains(&vec![
+        AttributeValue::Long(2),
+        AttributeValue::Long(15),
+        AttributeValue::Long(2)
+    ]));
+    assert_eq!(
+        *out.last().unwrap(),
+        vec![
+            AttributeValue::Long(1),
+            AttributeValue::Long(5),
+            AttributeValue::Long(3)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sum_distinct_over_sliding_window() {
+    let app = "\
+        CREATE STREAM In (uid STRING, status STRING, amount INT);\n\
+        CREATE STREAM Out (total BIGINT);\n\
+        INSERT INTO Out\n\
+        SELECT sum(DISTINCT amount) AS total FROM In WINDOW('length', 2);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", event("a", "OK", 10));
+    runner.send("In", event("b", "OK", 10));
+    // Expires one of the two 10s; the other still counts once
+    runner.send("In", event("c", "OK", 5));
+    let out = runner.shutdown();
+    assert_eq!(out[0], vec![AttributeValue::Long(10)]);
+    assert_eq!(out[1], vec![AttributeValue::Long(10)]);
+    assert_eq!(*out.last().unwrap(), vec![AttributeValue::Long(15)]);
+}
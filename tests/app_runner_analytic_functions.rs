@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+
+fn s(v: &str) -> AttributeValue {
+    AttributeValue::String(v.to_string())
+}
+
+#[tokio::test]
+async fn lag_row_number_and_running_sum_per_key() {
+    let app = "\
+        CREATE STREAM In (symbol STRING, price DOUBLE, volume INT);\n\
+        CREATE STREAM Out (symbol STRING, delta DOUBLE, n BIGINT, total BIGINT);\n\
+        INSERT INTO Out\n\
+        SELECT symbol,\n\
+               price - LAG(price, 1, price) OVER (PARTITION BY symbol) AS delta,\n\
+               ROW_NUMBER() OVER (PARTITION BY symbol) AS n,\n\
+               SUM(volume) OVER (PARTITION BY symbol) AS total\n\
+        FROM In;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for (symbol, price, volume) in [
+        ("A", 10.0, 100),
+        ("B", 20.0, 5),
+        ("A", 12.0, 50),
+        ("A", 11.0, 1),
+        ("B", 25.0, 5),
+    ] {
+        runner.send(
+            "In",
+            vec![
+                s(symbol),
+                AttributeValue::Double(price),
+                AttributeValue::Int(volume),
+            ],
+        );
+    }
+    let out = runner.shutdown();
+    let row = |symbol: &str, delta: f64, n: i64, total: i64| {
+        vec![
+            s(symbol),
+            AttributeValue::Double(delta),
+            AttributeValue::Long(n),
+            AttributeValue::Long(total),
+        ]
+    };
+    assert_eq!(
+        out,
+        vec![
+            row("A", 0.0, 1, 100),
+            row("B", 0.0, 1, 5),
+            row("A", 2.0, 2, 150),
+            row("A", -1.0, 3, 151),
+            row("B", 5.0, 2, 10),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rank_and_lead_within_batch() {
+    let app = "\
+        CREATE STREAM In (symbol STRING, price DOUBLE);\n\
+        CREATE STREAM Out (symbol STRING, price DOUBLE, rn BIGINT, next_price DOUBLE);\n\
+        INSERT INTO Out\n\
+        SELECT symbol, price,\n\
+               ROW_NUMBER() OVER (PARTITION BY symbol ORDER BY price DESC) AS rn,\n\
+               LEAD(price) OVER (PARTITION BY symbol ORDER BY price DESC) AS next_price\n\
+        FROM In WINDOW('lengthBatch', 3);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for (symbol, price) in [
+        ("A", 10.0),
+        ("A", 30.0),
+        ("B", 5.0),
+        ("A", 1.0),
+        ("B", 7.0),
+        ("B", 9.0),
+    ] {
+        runner.send("In", vec![s(symbol), AttributeValue::Double(price)]);
+    }
+    // Expired rows of the previous batch carry NULL analytic values
+    let out: Vec<_> = runner
+        .shutdown()
+        .into_iter()
+        .filter(|r| r[2] != AttributeValue::Null)
+        .collect();
+    let row = |symbol: &str, price: f64, rn: i64, next: Option<f64>| {
+        vec![
+            s(symbol),
+            AttributeValue::Double(price),
+            AttributeValue::Long(rn),
+            next.map_or(AttributeValue::Null, AttributeValue::Double),
+        ]
+    };
+    // Each batch is ordered by price and numbered from 1 again
+    assert_eq!(
+        out,
+        vec![
+            row("A", 30.0, 1, Some(10.0)),
+            row("A", 10.0, 2, None),
+            row("B", 5.0, 1, None),
+            row("B", 9.0, 1, Some(7.0)),
+            row("B", 7.0, 2, None),
+            row("A", 1.0, 1, None),
+        ]
+    );
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+fn as_f64(v: &AttributeValue) -> f64 {
+    match v {
+        AttributeValue::Double(d) => *d,
+        other => panic!("expected DOUBLE, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn regression_over_sliding_window() {
+    let app = "\
+        CREATE STREAM In (x DOUBLE, y DOUBLE);\n\
+        CREATE STREAM Out (c DOUBLE, slope DOUBLE, intercept DOUBLE, cov DOUBLE);\n\
+        INSERT INTO Out\n\
+        SELECT corr(x, y) AS c, regr_slope(y, x) AS slope, regr_intercept(y, x) AS intercept,\n\
+               covar_pop(x, y) AS cov\n\
+        FROM In WINDOW('length', 3);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    // The first point is off the line and expires once the window slides
+    for (x, y) in [(0.0, 50.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)] {
+        runner.send(
+            "In",
+            vec![AttributeValue::Double(x), AttributeValue::Double(y)],
+        );
+    }
+    let out = runner.shutdown();
+    let last = out.last().unwrap();
+    assert!((as_f64(&last[0]) - 1.0).abs() < 1e-6);
+    assert!((as_f64(&last[1]) - 2.0).abs() < 1e-6);
+    assert!((as_f64(&last[2]) - 1.0).abs() < 1e-6);
+    assert!((as_f64(&last[3]) - 4.0 / 3.0).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn wavg_and_arg_max_grouped() {
+    let app = "\
+        CREATE STREAM In (symbol STRING, price DOUBLE, volume INT, trader STRING);\n\
+        CREATE STREAM Out (symbol STRING, vwap DOUBLE, top_trader STRING);\n\
+        INSERT INTO Out\n\
+        SELECT symbol, wavg(price, volume) AS vwap, arg_max(volume, trader) AS top_trader\n\
+        FROM In WINDOW('length', 4) GROUP BY symbol;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    let trade = |symbol: &str, price: f64, volume: i32, trader: &str| {
+        vec![
+            AttributeValue::String(symbol.to_string()),
+            AttributeValue::Double(price),
+            AttributeValue::Int(volume),
+            AttributeValue::String(trader.to_string()),
+        ]
+    };
+    runner.send("In", trade("IBM", 10.0, 100, "alice"));
+    runner.send("In", trade("ORCL", 50.0, 10, "bob"));
+    runner.send("In", trade("IBM", 20.0, 300, "carol"));
+    let out = runner.shutdown();
+    let last = out.last().unwrap();
+    assert_eq!(last[0], AttributeValue::String("IBM".to_string()));
+    assert_eq!(last[1], AttributeValue::Double(17.5));
+    assert_eq!(last[2], AttributeValue::String("carol".to_string()));
+}
+
+#[tokio::test]
+async fn single_argument_aggregator_rejects_extra_arguments() {
+    let manager = EventFluxManager::new();
+    let result = manager
+        .create_eventflux_app_runtime_from_string(
+            "CREATE STREAM In (v INT, w INT);\n\
+             CREATE STREAM Out (c BIGINT);\n\
+             INSERT INTO Out SELECT count(v, w) AS c FROM In;\n",
+        )
+        .await;
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.contains("count() takes at most 1 argument(s), got 2"),
+        "{err}"
+    );
+}
@@ -73,3 +73,95 @@ async fn app_runner_builtin_functions() {
         ]]
     );
 }
+
+/// Scripted UDF declared with CREATE FUNCTION; INT argument widens to DOUBLE
+#[tokio::test]
+async fn app_runner_create_function_script() {
+    let app = "\
+        CREATE FUNCTION risk(amount DOUBLE, country STRING) RETURNS DOUBLE \
+            LANGUAGE rhai AS 'if country == \"US\" { amount * 0.1 } else { amount * 0.3 }';\n\
+        CREATE STREAM In (amount INT, country STRING);\n\
+        CREATE STREAM Out (score DOUBLE);\n\
+        INSERT INTO Out SELECT risk(amount, country) as score FROM In;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send(
+        "In",
+        vec![
+            AttributeValue::Int(100),
+            AttributeValue::String("US".to_string()),
+        ],
+    );
+    runner.send(
+        "In",
+        vec![
+            AttributeValue::Int(100),
+            AttributeValue::String("DE".to_string()),
+        ],
+    );
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::Double(10.0)],
+            vec![AttributeValue::Double(30.0)],
+        ]
+    );
+}
+
+/// A script call that exceeds its limits yields null instead of stalling the query
+#[tokio::test]
+async fn app_runner_create_function_limit_yields_null() {
+    let app = "\
+        CREATE FUNCTION spin(x INT) RETURNS INT LANGUAGE rhai AS 'loop { x += 1; }';\n\
+        CREATE STREAM In (x INT);\n\
+        CREATE STREAM Out (y INT);\n\
+        INSERT INTO Out SELECT spin(x) as y FROM In;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    let out = runner.shutdown();
+    assert_eq!(out, vec![vec![AttributeValue::Null]]);
+}
+
+/// Regex and URL functions parse access-log lines in SQL
+#[tokio::test]
+async fn app_runner_parse_log_lines() {
+    let app = "\
+        CREATE STREAM In (line STRING);\n\
+        CREATE STREAM Out (method STRING, host STRING, q STRING, status STRING, masked STRING);\n\
+        INSERT INTO Out\n\
+        SELECT split_part(line, ' ', 1) as method,\n\
+               parse_url(split_part(line, ' ', 2), 'HOST') as host,\n\
+               url_decode(parse_url(split_part(line, ' ', 2), 'QUERY', 'q')) as q,\n\
+               regexp_extract(line, 'status=([0-9]+)') as status,\n\
+               regexp_replace(line, '[0-9]+', '#') as masked\n\
+        FROM In WHERE regexp_matches(line, '^(GET|POST) ');\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for line in [
+        "GET http://shop.example/search?q=red+shoes status=200",
+        "HEALTHCHECK status=200",
+        "POST https://api.example/v1?q=a%26b status=503",
+    ] {
+        runner.send("In", vec![AttributeValue::String(line.to_string())]);
+    }
+    let out = runner.shutdown();
+    let s = |v: &str| AttributeValue::String(v.to_string());
+    assert_eq!(
+        out,
+        vec![
+            vec![
+                s("GET"),
+                s("shop.example"),
+                s("red shoes"),
+                s("200"),
+                s("GET http://shop.example/search?q=red+shoes status=#"),
+            ],
+            vec![
+                s("POST"),
+                s("api.example"),
+                s("a&b"),
+                s("503"),
+                s("POST https://api.example/v#?q=a%#b status=#"),
+            ],
+        ]
+    );
+}
@@ -3,8 +3,13 @@
 #[path = "common/mod.rs"]
 mod common;
 use common::AppRunner;
+use eventflux_rust::core::event::event::Event;
 use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::exception::EventFluxError;
 use eventflux_rust::core::persistence::{InMemoryPersistenceStore, PersistenceStore};
+use eventflux_rust::core::stream::handler::SinkStreamHandler;
+use eventflux_rust::core::stream::output::sink::Sink;
+use eventflux_rust::query_api::annotation::Annotation;
 use eventflux_rust::query_api::definition::{attribute::Type as AttrType, StreamDefinition};
 use eventflux_rust::query_api::eventflux_app::EventFluxApp;
 use eventflux_rust::query_api::execution::query::input::InputStream;
@@ -18,7 +23,9 @@ use eventflux_rust::query_api::execution::query::selection::Selector;
 use eventflux_rust::query_api::execution::query::Query;
 use eventflux_rust::query_api::execution::ExecutionElement;
 use eventflux_rust::query_api::expression::{constant::Constant, variable::Variable};
-use std::sync::Arc;
+use eventflux_rust::sql_compiler::parse_sql_application;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 fn make_app() -> EventFluxApp {
     let mut app = EventFluxApp::new("RateApp".to_string());
@@ -62,6 +69,69 @@ async fn rate_limit_emits_in_batches() {
     assert_eq!(out.len(), 4);
 }
 
+/// Sink keeping the values it publishes; like a disconnected transport it
+/// fails to publish once stopped
+#[derive(Debug, Clone, Default)]
+struct RecordingSink {
+    published: Arc<Mutex<Vec<AttributeValue>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Sink for RecordingSink {
+    fn publish(&self, payload: &[u8]) -> Result<(), EventFluxError> {
+        if self.stopped.load(Ordering::SeqCst) {
+            return Err(EventFluxError::app_runtime("sink stopped"));
+        }
+        let events: Vec<Event> = bincode::deserialize(payload).unwrap();
+        self.published
+            .lock()
+            .unwrap()
+            .extend(events.into_iter().flat_map(|e| e.data));
+        Ok(())
+    }
+
+    fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    fn clone_box(&self) -> Box<dyn Sink> {
+        Box::new(self.clone())
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_output_reaches_sink_on_graceful_shutdown() {
+    let runner = AppRunner::new_from_api(make_app(), "Out").await;
+    let runtime = runner.runtime();
+    let sink = RecordingSink::default();
+    let handler = Arc::new(SinkStreamHandler::new(
+        Box::new(sink.clone()),
+        None,
+        "Out".to_string(),
+    ));
+    runtime
+        .attach_sink_to_junction("Out", Arc::clone(&handler))
+        .unwrap();
+    runtime.register_sink_handler("Out".to_string(), Arc::clone(&handler));
+    handler.start();
+
+    for v in 1..=3 {
+        runner.send("In", vec![AttributeValue::Int(v)]);
+    }
+    // The third event waits for a second one to complete its batch
+    assert_eq!(sink.published.lock().unwrap().len(), 2);
+
+    runtime.graceful_shutdown(std::time::Duration::from_secs(5));
+    assert_eq!(
+        *sink.published.lock().unwrap(),
+        vec![
+            AttributeValue::Int(1),
+            AttributeValue::Int(2),
+            AttributeValue::Int(3)
+        ]
+    );
+}
+
 #[tokio::test]
 async fn rate_limit_persist_restore() {
     let store: Arc<dyn PersistenceStore> = Arc::new(InMemoryPersistenceStore::new());
@@ -77,3 +147,192 @@ async fn rate_limit_persist_restore() {
     let out = runner.shutdown();
     assert_eq!(out.len(), 5);
 }
+
+#[tokio::test]
+async fn sql_output_last_every_events() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In OUTPUT LAST EVERY 2 EVENTS;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for v in 1..=4 {
+        runner.send("In", vec![AttributeValue::Int(v)]);
+    }
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![AttributeValue::Int(2)], vec![AttributeValue::Int(4)]]
+    );
+}
+
+#[tokio::test]
+async fn sql_output_first_every_time() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In OUTPUT FIRST EVERY 1 SECOND;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for v in 1..=3 {
+        runner.send("In", vec![AttributeValue::Int(v)]);
+    }
+    // FIRST emits immediately and suppresses the rest of the period
+    assert_eq!(
+        runner.collected.lock().unwrap().clone(),
+        vec![vec![AttributeValue::Int(1)]]
+    );
+    let out = runner.shutdown();
+    assert_eq!(out.len(), 1);
+}
+
+/// Parse `sql` in playback mode, so event timestamps drive the snapshot period
+async fn playback_runner(sql: &str) -> AppRunner {
+    let mut app = parse_sql_application(sql)
+        .unwrap()
+        .to_eventflux_app("SnapshotApp".to_string())
+        .unwrap();
+    app.add_annotation(
+        Annotation::new("app".to_string())
+            .element(Some("playback".to_string()), "true".to_string()),
+    );
+    AppRunner::new_from_api(app, "Out").await
+}
+
+#[tokio::test]
+async fn sql_output_snapshot_group_by() {
+    let sql = "\
+        CREATE STREAM In (sym STRING, v INT);\n\
+        CREATE STREAM Out (sym STRING, total BIGINT);\n\
+        INSERT INTO Out SELECT sym, sum(v) AS total FROM In GROUP BY sym\n\
+        OUTPUT SNAPSHOT EVERY 100 MILLISECONDS;\n";
+    let runner = playback_runner(sql).await;
+    runner.send_with_ts(
+        "In",
+        1000,
+        vec![AttributeValue::String("a".into()), AttributeValue::Int(1)],
+    );
+    runner.send_with_ts(
+        "In",
+        1010,
+        vec![AttributeValue::String("b".into()), AttributeValue::Int(2)],
+    );
+    runner.send_with_ts(
+        "In",
+        1050,
+        vec![AttributeValue::String("a".into()), AttributeValue::Int(3)],
+    );
+    assert!(runner.collected.lock().unwrap().is_empty());
+    // The first event past the period triggers the snapshot before it is applied
+    runner.send_with_ts(
+        "In",
+        1100,
+        vec![AttributeValue::String("c".into()), AttributeValue::Int(5)],
+    );
+    let out = runner.shutdown();
+    // The snapshot carries the latest row of every group, in first-seen order
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::String("a".into()), AttributeValue::Long(4)],
+            vec![AttributeValue::String("b".into()), AttributeValue::Long(2)],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sql_output_snapshot_without_window_keeps_last_row() {
+    let runner = playback_runner(
+        "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In OUTPUT SNAPSHOT EVERY 100 MILLISECONDS;\n",
+    )
+    .await;
+    for (ts, v) in [(1000, 1), (1010, 2), (1020, 3), (1100, 4)] {
+        runner.send_with_ts("In", ts, vec![AttributeValue::Int(v)]);
+    }
+    let out = runner.shutdown();
+    assert_eq!(out, vec![vec![AttributeValue::Int(3)]]);
+}
+
+#[tokio::test]
+async fn sql_output_snapshot_drops_expired_groups() {
+    let runner = playback_runner(
+        "\
+        CREATE STREAM In (sym STRING, v INT);\n\
+        CREATE STREAM Out (sym STRING, total BIGINT);\n\
+        INSERT INTO Out SELECT sym, sum(v) AS total FROM In WINDOW('length', 1) GROUP BY sym\n\
+        OUTPUT SNAPSHOT EVERY 100 MILLISECONDS;\n",
+    )
+    .await;
+    runner.send_with_ts(
+        "In",
+        1000,
+        vec![AttributeValue::String("a".into()), AttributeValue::Int(1)],
+    );
+    // Pushes a's only event out of the window
+    runner.send_with_ts(
+        "In",
+        1010,
+        vec![AttributeValue::String("b".into()), AttributeValue::Int(2)],
+    );
+    runner.send_with_ts(
+        "In",
+        1100,
+        vec![AttributeValue::String("c".into()), AttributeValue::Int(5)],
+    );
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::String("b".into()),
+            AttributeValue::Long(2)
+        ]]
+    );
+}
+
+#[tokio::test]
+async fn sql_output_every_time_after_gap_in_playback() {
+    let runner = playback_runner(
+        "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In OUTPUT ALL EVERY 100 MILLISECONDS;\n",
+    )
+    .await;
+    runner.send_with_ts("In", 1000, vec![AttributeValue::Int(1)]);
+    runner.send_with_ts("In", 1010, vec![AttributeValue::Int(2)]);
+    // Three periods later: flushes the rows due at 1100
+    runner.send_with_ts("In", 1350, vec![AttributeValue::Int(3)]);
+    assert_eq!(runner.collected.lock().unwrap().len(), 2);
+    // Still within the period ending at 1400
+    runner.send_with_ts("In", 1380, vec![AttributeValue::Int(4)]);
+    assert_eq!(runner.collected.lock().unwrap().len(), 2);
+    runner.send_with_ts("In", 1400, vec![AttributeValue::Int(5)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        (1..=4)
+            .map(|v| vec![AttributeValue::Int(v)])
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn sql_output_snapshot_after_gap_in_playback() {
+    let runner = playback_runner(
+        "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In OUTPUT SNAPSHOT EVERY 100 MILLISECONDS;\n",
+    )
+    .await;
+    // 1250 takes the snapshot due at 1100; the next one is due at 1300, not 1200
+    for (ts, v) in [(1000, 1), (1250, 2), (1280, 3), (1300, 4)] {
+        runner.send_with_ts("In", ts, vec![AttributeValue::Int(v)]);
+    }
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![AttributeValue::Int(1)], vec![AttributeValue::Int(3)]]
+    );
+}
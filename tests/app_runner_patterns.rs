@@ -1182,3 +1182,43 @@ async fn pattern_logical_and_same_stream_sql() {
         ]]
     );
 }
+
+/// Test collection aggregates over a count-quantified capture
+/// Pattern: e1=Logins{3} -> e2=Alerts, aggregating the three captured logins
+#[tokio::test]
+async fn pattern_collection_aggregates_sql() {
+    let app = "\
+        CREATE STREAM Logins (name STRING, ip STRING);\n\
+        CREATE STREAM Alerts (name STRING);\n\
+        CREATE STREAM Out (first_name STRING, last_name STRING, min_name STRING, ips BIGINT, logins BIGINT);\n\
+        INSERT INTO Out\n\
+        SELECT first(e1.name) AS first_name, last(e1.name) AS last_name, min(e1.name) AS min_name,\n\
+               distinctCount(e1.ip) AS ips, count(e1) AS logins\n\
+        FROM PATTERN (e1=Logins{3} -> e2=Alerts);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for (name, ip) in [
+        ("carol", "10.0.0.1"),
+        ("alice", "10.0.0.2"),
+        ("bob", "10.0.0.1"),
+    ] {
+        runner.send(
+            "Logins",
+            vec![
+                AttributeValue::String(name.to_string()),
+                AttributeValue::String(ip.to_string()),
+            ],
+        );
+    }
+    runner.send("Alerts", vec![AttributeValue::String("bob".to_string())]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::String("carol".to_string()),
+            AttributeValue::String("bob".to_string()),
+            AttributeValue::String("alice".to_string()),
+            AttributeValue::Long(2),
+            AttributeValue::Long(3),
+        ]]
+    );
+}
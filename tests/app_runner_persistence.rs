@@ -89,3 +89,278 @@ async fn persist_shutdown_restore_state() {
     let out = runner2.shutdown();
     assert_eq!(out.last().unwrap(), &vec![AttributeValue::Int(4)]);
 }
+
+fn config_with_persistence(
+    app_name: &str,
+    interval: Option<std::time::Duration>,
+) -> eventflux_rust::core::config::EventFluxConfig {
+    use eventflux_rust::core::config::types::global_config::ApplicationGlobalConfig;
+    use eventflux_rust::core::config::{EventFluxConfig, GlobalPersistenceConfig};
+
+    let mut config = EventFluxConfig::default();
+    config.eventflux.application = Some(ApplicationGlobalConfig {
+        name: Some(app_name.to_string()),
+        ..Default::default()
+    });
+    config.eventflux.persistence = Some(GlobalPersistenceConfig {
+        interval,
+        ..GlobalPersistenceConfig::memory()
+    });
+    config
+}
+
+#[tokio::test]
+async fn periodic_snapshots_persist_state() {
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let store: Arc<dyn PersistenceStore> = Arc::new(InMemoryPersistenceStore::new());
+    let config = config_with_persistence("PeriodicApp", Some(std::time::Duration::from_millis(50)));
+    let manager = EventFluxManager::new_with_config(config);
+    manager.set_persistence_store(Arc::clone(&store)).unwrap();
+
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In WINDOW('length', 2);\n";
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let first = store
+        .get_last_revision("PeriodicApp")
+        .expect("periodic snapshot");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let later = store.get_last_revision("PeriodicApp").unwrap();
+    let _ = runner.shutdown();
+    assert!(later > first, "{later} should follow {first}");
+}
+
+#[tokio::test]
+async fn app_persistence_interval_overrides_global() {
+    use eventflux_rust::core::config::{ApplicationConfig, PersistenceConfig};
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let store: Arc<dyn PersistenceStore> = Arc::new(InMemoryPersistenceStore::new());
+    let mut config = config_with_persistence("OverrideApp", None);
+    config.applications.insert(
+        "OverrideApp".to_string(),
+        ApplicationConfig {
+            persistence: Some(PersistenceConfig {
+                enabled: true,
+                store_type: None,
+                checkpoint_interval: std::time::Duration::from_secs(60),
+                interval: Some(std::time::Duration::from_millis(50)),
+                incremental: false,
+                path: None,
+                max_incremental_count: None,
+                config: None,
+            }),
+            ..Default::default()
+        },
+    );
+    let manager = EventFluxManager::new_with_config(config);
+    manager.set_persistence_store(Arc::clone(&store)).unwrap();
+
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In;\n";
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    assert!(store.get_last_revision("OverrideApp").is_some());
+    let _ = runner.shutdown();
+}
+
+#[tokio::test]
+async fn graceful_shutdown_snapshot_restored_on_start() {
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let store: Arc<dyn PersistenceStore> = Arc::new(InMemoryPersistenceStore::new());
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT, count BIGINT);\n\
+        INSERT INTO Out SELECT v, COUNT() as count FROM In WINDOW('length', 3);\n";
+
+    let manager = EventFluxManager::new_with_config(config_with_persistence("RestartApp", None));
+    manager.set_persistence_store(Arc::clone(&store)).unwrap();
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner
+        .runtime()
+        .graceful_shutdown(std::time::Duration::from_secs(5));
+    assert!(store.get_last_revision("RestartApp").is_some());
+
+    // A new instance picks up the window where the first one stopped
+    let manager = EventFluxManager::new_with_config(config_with_persistence("RestartApp", None));
+    manager.set_persistence_store(Arc::clone(&store)).unwrap();
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    assert_eq!(
+        runner.shutdown(),
+        vec![vec![AttributeValue::Int(3), AttributeValue::Long(3)]]
+    );
+}
+
+fn config_with_checkpoints(
+    app_name: &str,
+    path: &std::path::Path,
+    max_incremental_count: usize,
+) -> eventflux_rust::core::config::EventFluxConfig {
+    use eventflux_rust::core::config::types::global_config::ApplicationGlobalConfig;
+    use eventflux_rust::core::config::{ApplicationConfig, EventFluxConfig, PersistenceConfig};
+
+    let mut config = EventFluxConfig::default();
+    config.eventflux.application = Some(ApplicationGlobalConfig {
+        name: Some(app_name.to_string()),
+        ..Default::default()
+    });
+    config.applications.insert(
+        app_name.to_string(),
+        ApplicationConfig {
+            persistence: Some(PersistenceConfig {
+                enabled: true,
+                store_type: None,
+                checkpoint_interval: std::time::Duration::from_secs(60),
+                interval: None,
+                incremental: true,
+                path: Some(path.to_string_lossy().into_owned()),
+                max_incremental_count: Some(max_incremental_count),
+                config: None,
+            }),
+            ..Default::default()
+        },
+    );
+    config
+}
+
+#[tokio::test]
+async fn incremental_checkpoints_recovered_on_start() {
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT, count BIGINT);\n\
+        INSERT INTO Out SELECT v, COUNT() as count FROM In WINDOW('length', 3);\n";
+
+    let manager =
+        EventFluxManager::new_with_config(config_with_checkpoints("CheckpointApp", dir.path(), 10));
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    let first = runner.runtime().checkpoint().unwrap().expect("checkpoint");
+    assert!(first.full);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    let second = runner.runtime().checkpoint().unwrap().expect("checkpoint");
+    assert!(!second.full);
+    assert!(second.checkpoint_id > first.checkpoint_id);
+    assert!(runner.runtime().checkpoint().unwrap().is_none());
+    let _ = runner.shutdown();
+
+    // A new instance replays the incremental checkpoint over the full one
+    let manager =
+        EventFluxManager::new_with_config(config_with_checkpoints("CheckpointApp", dir.path(), 10));
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    assert_eq!(
+        runner.shutdown(),
+        vec![vec![AttributeValue::Int(3), AttributeValue::Long(3)]]
+    );
+}
+
+#[tokio::test]
+async fn incremental_checkpoints_compacted_into_full() {
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In WINDOW('length', 2);\n";
+
+    let manager =
+        EventFluxManager::new_with_config(config_with_checkpoints("CompactApp", dir.path(), 1));
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    let mut full = Vec::new();
+    for v in 1..=4 {
+        runner.send("In", vec![AttributeValue::Int(v)]);
+        full.push(runner.runtime().checkpoint().unwrap().unwrap().full);
+    }
+    let _ = runner.shutdown();
+    assert_eq!(full, vec![true, false, true, false]);
+    // The last full checkpoint truncated the log, only the last change is left
+    assert_eq!(
+        std::fs::read_dir(dir.path().join("wal")).unwrap().count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn incremental_checkpoints_default_under_persistence_path() {
+    use eventflux_rust::core::config::GlobalPersistenceConfig;
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = config_with_checkpoints("DefaultPathApp", dir.path(), 10);
+    config
+        .applications
+        .get_mut("DefaultPathApp")
+        .unwrap()
+        .persistence
+        .as_mut()
+        .unwrap()
+        .path = None;
+    config.eventflux.persistence = Some(GlobalPersistenceConfig::file(
+        dir.path().to_string_lossy().into_owned(),
+    ));
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out SELECT v FROM In WINDOW('length', 2);\n";
+
+    let runner =
+        AppRunner::new_with_manager(EventFluxManager::new_with_config(config), app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    assert!(runner.runtime().checkpoint().unwrap().unwrap().full);
+    let _ = runner.shutdown();
+    assert!(dir.path().join("DefaultPathApp").join("wal").is_dir());
+}
+
+#[tokio::test]
+async fn incremental_checkpoints_cover_tables_and_snapshot_only_state() {
+    use eventflux_rust::core::eventflux_manager::EventFluxManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        CREATE TABLE T (v INT) WITH ('extension' = 'inMemory', 'ttl' = '1 hour');\n\
+        INSERT INTO T SELECT v FROM In;\n\
+        INSERT INTO Out SELECT v FROM In WINDOW('sort', 2, v, 'asc');\n";
+
+    let manager =
+        EventFluxManager::new_with_config(config_with_checkpoints("TableApp", dir.path(), 10));
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    assert!(runner.runtime().checkpoint().unwrap().unwrap().full);
+    // The table reports its changes, the sort window is snapshotted
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    let second = runner.runtime().checkpoint().unwrap().unwrap();
+    assert!(!second.full);
+    assert!(runner.runtime().checkpoint().unwrap().is_none());
+    let _ = runner.shutdown();
+
+    let manager =
+        EventFluxManager::new_with_config(config_with_checkpoints("TableApp", dir.path(), 10));
+    let runner = AppRunner::new_with_manager(manager, app, "Out").await;
+    let table = runner
+        .runtime()
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table("T")
+        .unwrap();
+    assert_eq!(
+        table.all_rows().unwrap(),
+        vec![vec![AttributeValue::Int(1)], vec![AttributeValue::Int(2)]]
+    );
+    let _ = runner.shutdown();
+}
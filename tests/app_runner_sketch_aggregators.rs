@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+
+fn as_f64(v: &AttributeValue) -> f64 {
+    match v {
+        AttributeValue::Double(d) => *d,
+        other => panic!("expected DOUBLE, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn percentile_and_median_over_length_window() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (p99 DOUBLE, med DOUBLE);\n\
+        INSERT INTO Out\n\
+        SELECT percentile(v, 0.99) AS p99, median(v) AS med FROM In WINDOW('length', 100);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for v in 1..=200 {
+        runner.send("In", vec![AttributeValue::Int(v)]);
+    }
+    let out = runner.shutdown();
+    // One row per event, plus one per expired event once the window is full
+    assert_eq!(out.len(), 300);
+    // Window holds 101..=200 once the first 100 events expired
+    let last = out.last().unwrap();
+    assert!((as_f64(&last[0]) - 199.0).abs() <= 199.0 * 0.02);
+    assert!((as_f64(&last[1]) - 150.0).abs() <= 150.0 * 0.02);
+}
+
+#[tokio::test]
+async fn approx_distinct_and_top_k() {
+    let app = "\
+        CREATE STREAM In (symbol STRING);\n\
+        CREATE STREAM Out (uniques BIGINT, top OBJECT);\n\
+        INSERT INTO Out\n\
+        SELECT approx_distinct(symbol) AS uniques, top_k(symbol, 1) AS top FROM In WINDOW('length', 3);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    for s in ["IBM", "IBM", "WSO2", "ORCL", "ORCL"] {
+        runner.send("In", vec![AttributeValue::String(s.to_string())]);
+    }
+    let out = runner.shutdown();
+    let last = out.last().unwrap();
+    // Window holds WSO2, ORCL, ORCL
+    assert_eq!(last[0], AttributeValue::Long(2));
+    // OBJECT values are opaque once they reach the callback
+    assert!(matches!(last[1], AttributeValue::Object(_)));
+}
@@ -633,3 +633,126 @@ async fn test_error_unknown_column_in_table() {
 
     let _runner = AppRunner::new(query, "Out").await;
 }
+
+#[tokio::test]
+async fn table_ttl_evicts_rows_to_stream() {
+    let query = "\
+        CREATE STREAM In (v STRING);\n\
+        CREATE STREAM Expired (v STRING);\n\
+        CREATE TABLE T (v STRING) WITH ('extension' = 'inMemory', 'ttl' = '100ms', \
+            'eviction.interval' = '20ms', 'eviction.stream' = 'Expired');\n\
+        INSERT INTO T SELECT v FROM In;\n";
+    let runner = AppRunner::new(query, "Expired").await;
+    runner.send("In", vec![AttributeValue::String("a".into())]);
+    std::thread::sleep(std::time::Duration::from_millis(20));
+
+    let table = runner
+        .runtime()
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table("T")
+        .unwrap();
+    assert_eq!(table.all_rows().unwrap().len(), 1);
+
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    assert!(table.all_rows().unwrap().is_empty());
+    let out = runner.shutdown();
+    assert_eq!(out, vec![vec![AttributeValue::String("a".into())]]);
+}
+
+#[tokio::test]
+async fn table_max_rows_keeps_newest_rows() {
+    let query = "\
+        CREATE STREAM In (v STRING);\n\
+        CREATE STREAM Out (v STRING);\n\
+        CREATE TABLE T (v STRING) WITH ('extension' = 'inMemory', 'max.rows' = '2', \
+            'eviction.interval' = '20ms');\n\
+        INSERT INTO T SELECT v FROM In;\n";
+    let runner = AppRunner::new(query, "Out").await;
+    for v in ["a", "b", "c"] {
+        runner.send("In", vec![AttributeValue::String(v.into())]);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let table = runner
+        .runtime()
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table("T")
+        .unwrap();
+    assert_eq!(
+        table.all_rows().unwrap(),
+        vec![
+            vec![AttributeValue::String("b".into())],
+            vec![AttributeValue::String("c".into())],
+        ]
+    );
+    runner.shutdown();
+}
+
+#[tokio::test]
+async fn test_error_retention_on_unsupported_table() {
+    let manager = EventFluxManager::new();
+    let result = manager
+        .create_eventflux_app_runtime_from_string(
+            "CREATE STREAM In (v STRING);\n\
+             CREATE TABLE T (v STRING) WITH ('extension' = 'inMemory', 'ttl' = 'soon');\n\
+             INSERT INTO T SELECT v FROM In;\n",
+        )
+        .await;
+    assert!(result.unwrap_err().to_string().contains("'ttl'"));
+}
+
+#[tokio::test]
+async fn table_change_stream_reports_writes() {
+    let query = "\
+        CREATE STREAM In (id INT, price DOUBLE);\n\
+        CREATE TABLE Prices (id INT PRIMARY KEY, price DOUBLE) \
+            WITH ('extension' = 'inMemory');\n\
+        CREATE STREAM PriceChanges AS CHANGES OF Prices;\n\
+        INSERT INTO Prices SELECT id, price FROM In;\n";
+    let runner = AppRunner::new(query, "PriceChanges").await;
+    let row = |id: i32, price: f64| vec![AttributeValue::Int(id), AttributeValue::Double(price)];
+    runner.send("In", row(1, 10.0));
+    runner.send("In", row(1, 11.0));
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    let table = runner
+        .runtime()
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table("Prices")
+        .unwrap();
+    let key = eventflux_rust::core::table::InMemoryCompiledCondition {
+        values: vec![AttributeValue::Int(1)],
+    };
+    assert!(table.delete(&key).unwrap());
+
+    let change = |op: &str, id: i32, price: f64| {
+        let mut data = vec![AttributeValue::String(op.into())];
+        data.extend(row(id, price));
+        data
+    };
+    assert_eq!(
+        runner.shutdown(),
+        vec![
+            change("INSERT", 1, 10.0),
+            change("UPDATE_BEFORE", 1, 10.0),
+            change("UPDATE_AFTER", 1, 11.0),
+            change("DELETE", 1, 11.0),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_error_change_stream_of_unknown_table() {
+    let manager = EventFluxManager::new();
+    let result = manager
+        .create_eventflux_app_runtime_from_string(
+            "CREATE STREAM In (v STRING);\n\
+             CREATE STREAM Changes AS CHANGES OF Missing;\n",
+        )
+        .await;
+    assert!(result.is_err());
+}
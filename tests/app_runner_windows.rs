@@ -200,10 +200,7 @@ async fn external_time_batch_window() {
     );
 }
 
-// TODO: NOT PART OF M1 - lossyCounting window SQL syntax not yet supported
-// See comment above for details.
 #[tokio::test]
-#[ignore = "lossyCounting window SQL syntax not supported in M1"]
 async fn lossy_counting_window() {
     let app = "\
         CREATE STREAM In (v TEXT);\n\
@@ -223,10 +220,7 @@ async fn lossy_counting_window() {
     );
 }
 
-// TODO: NOT PART OF M1 - cron window SQL syntax not yet supported
-// See comment above for details.
 #[tokio::test]
-#[ignore = "cron window SQL syntax not supported in M1"]
 async fn cron_window_basic() {
     let app = "\
         CREATE STREAM In (v INT);\n\
@@ -240,3 +234,167 @@ async fn cron_window_basic() {
     assert!(!out.is_empty());
     assert_eq!(out[0], vec![AttributeValue::Int(1)]);
 }
+
+#[tokio::test]
+async fn named_window_shared_by_queries() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        CREATE WINDOW Recent (v INT) LENGTH(2);\n\
+        INSERT INTO Recent\n\
+        SELECT v FROM In;\n\
+        INSERT INTO Out\n\
+        SELECT v FROM Recent;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(2)],
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(3)],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn named_window_join_uses_current_contents() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Probe (v INT);\n\
+        CREATE STREAM Out (probe INT, held INT);\n\
+        CREATE WINDOW Recent (v INT) LENGTH(2) OUTPUT CURRENT EVENTS;\n\
+        INSERT INTO Recent\n\
+        SELECT v FROM In;\n\
+        INSERT INTO Out\n\
+        SELECT Probe.v AS probe, Recent.v AS held\n\
+        FROM Probe JOIN Recent ON Probe.v = Recent.v;\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    runner.send("Probe", vec![AttributeValue::Int(1)]);
+    runner.send("Probe", vec![AttributeValue::Int(3)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![AttributeValue::Int(3), AttributeValue::Int(3)]]
+    );
+}
+
+#[tokio::test]
+async fn time_length_window_expires_oldest_on_length() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out\n\
+        SELECT v FROM In WINDOW timeLength(10 SEC, 2);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(2)],
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(3)],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn expression_window_bounds_by_count() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (total BIGINT);\n\
+        INSERT INTO Out\n\
+        SELECT sum(v) AS total FROM In WINDOW expression(count() <= 2);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    let out = runner.shutdown();
+    assert_eq!(out.last(), Some(&vec![AttributeValue::Long(5)]));
+}
+
+#[tokio::test]
+async fn sort_window_multiple_keys() {
+    let app = "\
+        CREATE STREAM In (sym STRING, price DOUBLE, volume INT);\n\
+        CREATE STREAM Out (sym STRING);\n\
+        INSERT INTO Out\n\
+        SELECT sym FROM In WINDOW SORT(2, price DESC, volume ASC);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    let send = |sym: &str, price: f64, volume: i32| {
+        runner.send(
+            "In",
+            vec![
+                AttributeValue::String(sym.to_string()),
+                AttributeValue::Double(price),
+                AttributeValue::Int(volume),
+            ],
+        )
+    };
+    send("A", 100.0, 50);
+    send("B", 100.0, 30);
+    send("C", 50.0, 10);
+    send("D", 200.0, 1);
+    let out = runner.shutdown();
+    let syms: Vec<_> = out.iter().map(|row| row[0].to_string()).collect();
+    // C sorts last and expires on arrival; D pushes out A, the larger volume of the 100.0 tie
+    assert_eq!(syms, vec!["A", "B", "C", "C", "D", "A"]);
+}
+
+#[tokio::test]
+async fn sort_window_nulls_first() {
+    let app = "\
+        CREATE STREAM In (price DOUBLE);\n\
+        CREATE STREAM Out (price DOUBLE);\n\
+        INSERT INTO Out\n\
+        SELECT price FROM In WINDOW SORT(2, price ASC NULLS FIRST);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Double(1.0)]);
+    runner.send("In", vec![AttributeValue::Null]);
+    runner.send("In", vec![AttributeValue::Double(2.0)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::Double(1.0)],
+            vec![AttributeValue::Null],
+            vec![AttributeValue::Double(2.0)],
+            vec![AttributeValue::Double(2.0)],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn frequent_window_expires_exhausted_keys_in_arrival_order() {
+    let app = "\
+        CREATE STREAM In (v INT);\n\
+        CREATE STREAM Out (v INT);\n\
+        INSERT INTO Out\n\
+        SELECT v FROM In WINDOW frequent(2, v);\n";
+    let runner = AppRunner::new(app, "Out").await;
+    runner.send("In", vec![AttributeValue::Int(1)]);
+    runner.send("In", vec![AttributeValue::Int(2)]);
+    runner.send("In", vec![AttributeValue::Int(3)]);
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(2)],
+            vec![AttributeValue::Int(1)],
+            vec![AttributeValue::Int(2)],
+            vec![AttributeValue::Int(3)],
+        ]
+    );
+}
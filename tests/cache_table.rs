@@ -2,7 +2,7 @@
 
 use eventflux_rust::core::event::value::AttributeValue;
 use eventflux_rust::core::table::{
-    CacheTable, InMemoryCompiledCondition, InMemoryCompiledUpdateSet, Table,
+    CacheTable, InMemoryCompiledCondition, InMemoryCompiledUpdateSet, RetentionPolicy, Table,
 };
 
 #[test]
@@ -55,3 +55,20 @@ fn test_cache_update_delete_find() {
         .unwrap()
         .is_none());
 }
+
+#[test]
+fn test_cache_ttl_eviction() {
+    let table = CacheTable::new(10).with_retention(Some(RetentionPolicy {
+        ttl_ms: Some(1_000),
+        ..Default::default()
+    }));
+    table.insert(&[AttributeValue::Int(1)]).unwrap();
+    table.insert(&[AttributeValue::Int(2)]).unwrap();
+    let now = chrono::Utc::now().timestamp_millis();
+    assert!(table.evict_expired(now).unwrap().is_empty());
+    assert_eq!(
+        table.evict_expired(now + 1_001).unwrap(),
+        vec![vec![AttributeValue::Int(1)], vec![AttributeValue::Int(2)]]
+    );
+    assert!(table.all_rows().is_empty());
+}
@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::stream::stream_event::StreamEvent;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::exception::EventFluxError;
+use eventflux_rust::core::executor::expression_executor::ExpressionExecutor;
+use eventflux_rust::core::table::{
+    CachePolicy, CachingTable, CompiledCondition, CompiledUpdateSet, InMemoryCompiledCondition,
+    InMemoryTable, Table, TableCacheConfig, WriteMode,
+};
+use eventflux_rust::query_api::definition::attribute::Type as AttributeType;
+use eventflux_rust::query_api::definition::TableDefinition;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// In-memory table counting the operations that reach it
+#[derive(Debug)]
+struct CountingTable {
+    inner: InMemoryTable,
+    reads: Arc<AtomicUsize>,
+    batches: Arc<AtomicUsize>,
+}
+
+impl Table for CountingTable {
+    fn insert(&self, values: &[AttributeValue]) -> Result<(), EventFluxError> {
+        self.inner.insert(values)
+    }
+
+    fn insert_batch(&self, rows: &[Vec<AttributeValue>]) -> Result<(), EventFluxError> {
+        self.batches.fetch_add(1, Ordering::SeqCst);
+        rows.iter().try_for_each(|row| self.inner.insert(row))
+    }
+
+    fn update(
+        &self,
+        condition: &dyn CompiledCondition,
+        update_set: &dyn CompiledUpdateSet,
+    ) -> Result<bool, EventFluxError> {
+        self.inner.update(condition, update_set)
+    }
+
+    fn delete(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        self.inner.delete(condition)
+    }
+
+    fn find(
+        &self,
+        condition: &dyn CompiledCondition,
+    ) -> Result<Option<Vec<AttributeValue>>, EventFluxError> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        Table::find(&self.inner, condition)
+    }
+
+    fn contains(&self, condition: &dyn CompiledCondition) -> Result<bool, EventFluxError> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        Table::contains(&self.inner, condition)
+    }
+
+    fn all_rows(&self) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        Table::all_rows(&self.inner)
+    }
+
+    fn find_rows_for_join(
+        &self,
+        stream_event: &StreamEvent,
+        compiled_condition: Option<&dyn CompiledCondition>,
+        condition_executor: Option<&dyn ExpressionExecutor>,
+    ) -> Result<Vec<Vec<AttributeValue>>, EventFluxError> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner
+            .find_rows_for_join(stream_event, compiled_condition, condition_executor)
+    }
+
+    fn clone_table(&self) -> Result<Box<dyn Table>, EventFluxError> {
+        self.inner.clone_table()
+    }
+}
+
+fn users_definition() -> TableDefinition {
+    let mut def = TableDefinition::id("Users".to_string())
+        .attribute("id".to_string(), AttributeType::LONG)
+        .attribute("name".to_string(), AttributeType::STRING);
+    def.primary_key = vec!["id".to_string()];
+    def
+}
+
+fn user(id: i64, name: &str) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::Long(id),
+        AttributeValue::String(name.to_string()),
+    ]
+}
+
+fn key(id: i64) -> InMemoryCompiledCondition {
+    InMemoryCompiledCondition {
+        values: vec![AttributeValue::Long(id)],
+    }
+}
+
+/// A caching table over a counting in-memory table, and the counters of the
+/// reads and batch writes reaching it
+fn caching_table(config: TableCacheConfig) -> (CachingTable, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let batches = Arc::new(AtomicUsize::new(0));
+    let inner = CountingTable {
+        inner: InMemoryTable::with_definition(&users_definition()).unwrap(),
+        reads: Arc::clone(&reads),
+        batches: Arc::clone(&batches),
+    };
+    let table = CachingTable::new(Arc::new(inner), &users_definition(), config);
+    (table, reads, batches)
+}
+
+#[test]
+fn test_lookups_are_cached_until_a_write() {
+    let (table, reads, _) = caching_table(TableCacheConfig::default());
+    table.insert(&user(1, "ann")).unwrap();
+
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "ann")));
+    assert!(table.contains(&key(1)).unwrap());
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "ann")));
+    assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+    // Writes invalidate cached lookups
+    table.insert(&user(1, "amy")).unwrap();
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "amy")));
+    assert_eq!(reads.load(Ordering::SeqCst), 2);
+
+    let stats = table.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (2, 2, 1));
+    assert_eq!(stats.hit_ratio(), 0.5);
+}
+
+#[test]
+fn test_negative_caching() {
+    let (table, reads, _) = caching_table(TableCacheConfig::default());
+    assert_eq!(table.find(&key(9)).unwrap(), None);
+    assert_eq!(table.find(&key(9)).unwrap(), None);
+    assert_eq!(reads.load(Ordering::SeqCst), 2);
+
+    let (table, reads, _) = caching_table(TableCacheConfig {
+        negative: true,
+        ..Default::default()
+    });
+    assert_eq!(table.find(&key(9)).unwrap(), None);
+    assert!(!table.contains(&key(9)).unwrap());
+    assert_eq!(reads.load(Ordering::SeqCst), 1);
+    assert_eq!(table.cache_stats().unwrap().negative_hits, 1);
+}
+
+#[test]
+fn test_lru_and_fifo_eviction() {
+    for (policy, survivor, evicted) in [(CachePolicy::Lru, 1, 2), (CachePolicy::Fifo, 2, 1)] {
+        let (table, reads, _) = caching_table(TableCacheConfig {
+            policy,
+            max_entries: 2,
+            ..Default::default()
+        });
+        for id in 1..=3 {
+            table.insert(&user(id, "u")).unwrap();
+        }
+        table.find(&key(1)).unwrap();
+        table.find(&key(2)).unwrap();
+        // A hit makes key 1 the most recently used one
+        table.find(&key(1)).unwrap();
+        table.find(&key(3)).unwrap();
+        assert_eq!(reads.load(Ordering::SeqCst), 3);
+
+        table.find(&key(survivor)).unwrap();
+        assert_eq!(reads.load(Ordering::SeqCst), 3, "{policy:?}");
+        table.find(&key(evicted)).unwrap();
+        assert_eq!(reads.load(Ordering::SeqCst), 4, "{policy:?}");
+        assert!(table.cache_stats().unwrap().evictions >= 1);
+    }
+}
+
+#[test]
+fn test_write_behind_batches_inserts() {
+    let (table, reads, batches) = caching_table(TableCacheConfig {
+        write_mode: WriteMode::WriteBehind,
+        batch_size: 3,
+        ..Default::default()
+    });
+    table.insert(&user(1, "ann")).unwrap();
+    table.insert(&user(2, "bob")).unwrap();
+    assert_eq!(table.cache_stats().unwrap().pending_writes, 2);
+    assert_eq!(batches.load(Ordering::SeqCst), 0);
+
+    // A full batch is written at once
+    table.insert(&user(3, "cat")).unwrap();
+    let stats = table.cache_stats().unwrap();
+    assert_eq!((stats.pending_writes, stats.flushed_writes), (0, 3));
+    assert_eq!(batches.load(Ordering::SeqCst), 1);
+
+    // Reads see buffered rows without flushing them
+    table.insert(&user(4, "dan")).unwrap();
+    assert_eq!(table.find(&key(4)).unwrap(), Some(user(4, "dan")));
+    assert_eq!(table.all_rows().unwrap().len(), 4);
+    assert_eq!(batches.load(Ordering::SeqCst), 1);
+    assert_eq!(table.cache_stats().unwrap().pending_writes, 1);
+
+    // A buffered row overrides the cached backing row with its key
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "ann")));
+    let backing_reads = reads.load(Ordering::SeqCst);
+    table.insert(&user(1, "amy")).unwrap();
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "amy")));
+    assert_eq!(
+        table
+            .all_rows()
+            .unwrap()
+            .iter()
+            .filter(|r| r[0] == AttributeValue::Long(1))
+            .count(),
+        1
+    );
+    assert_eq!(reads.load(Ordering::SeqCst), backing_reads);
+
+    table.flush().unwrap();
+    assert_eq!(batches.load(Ordering::SeqCst), 2);
+    assert_eq!(table.all_rows().unwrap().len(), 4);
+    assert_eq!(table.find(&key(1)).unwrap(), Some(user(1, "amy")));
+    assert_eq!(table.cache_stats().unwrap().flushed_writes, 5);
+}
+
+#[tokio::test]
+async fn cached_join_via_app_runner() {
+    let query = "\
+        CREATE STREAM UserStream (id LONG, name STRING);\n\
+        CREATE STREAM Clicks (userId LONG, page STRING);\n\
+        CREATE STREAM Out (name STRING, page STRING);\n\
+        CREATE TABLE CachedUsers (id LONG PRIMARY KEY, name STRING) \
+            WITH ('extension' = 'inMemory', 'cache.policy' = 'lru', 'cache.size' = '100', \
+            'cache.negative' = 'true', 'write.mode' = 'write_behind');\n\
+        INSERT INTO CachedUsers SELECT id, name FROM UserStream;\n\
+        INSERT INTO Out SELECT CachedUsers.name, Clicks.page \
+            FROM Clicks JOIN CachedUsers ON Clicks.userId = CachedUsers.id;\n";
+    let runner = AppRunner::new(query, "Out").await;
+    runner.send("UserStream", user(1, "ann"));
+    for (id, page) in [(1, "/a"), (1, "/b"), (2, "/c"), (2, "/d")] {
+        runner.send(
+            "Clicks",
+            vec![
+                AttributeValue::Long(id),
+                AttributeValue::String(page.into()),
+            ],
+        );
+    }
+    let runtime = runner.runtime();
+    let out = runner.shutdown();
+    let stats = runtime
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table("CachedUsers")
+        .unwrap()
+        .cache_stats()
+        .unwrap();
+    // Only the first click of each user reaches the table; user 1 is still
+    // buffered, so both cached backing lookups are empty
+    assert_eq!((stats.hits, stats.misses, stats.negative_hits), (2, 2, 2));
+    assert_eq!(
+        out,
+        vec![
+            vec![
+                AttributeValue::String("ann".into()),
+                AttributeValue::String("/a".into()),
+            ],
+            vec![
+                AttributeValue::String("ann".into()),
+                AttributeValue::String("/b".into()),
+            ],
+        ]
+    );
+}
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use eventflux_rust::core::config::eventflux_app_context::EventFluxAppContext;
 use eventflux_rust::core::config::eventflux_context::EventFluxContext;
 use eventflux_rust::core::config::types::EventFluxConfig;
+use eventflux_rust::core::event::value::AttributeValue;
 use eventflux_rust::query_api::definition::attribute::Type as ApiAttributeType;
 use eventflux_rust::query_api::eventflux_app::EventFluxApp;
 
@@ -35,20 +36,49 @@ fn collection_aggregation_functions_registered_and_work() {
 
     // Validate basic behavior and return types from registry instances
     let sum_fn = ctx.get_collection_aggregation_function("sum").unwrap();
-    assert_eq!(sum_fn.aggregate(&[1.0, 2.0, 3.0]), Some(6.0));
+    assert_eq!(
+        sum_fn.aggregate(&[
+            AttributeValue::Int(1),
+            AttributeValue::Int(2),
+            AttributeValue::Int(3)
+        ]),
+        Some(AttributeValue::Long(6))
+    );
     assert_eq!(
         sum_fn.return_type(ApiAttributeType::INT),
         ApiAttributeType::LONG
     );
 
     let avg_fn = ctx.get_collection_aggregation_function("avg").unwrap();
-    assert_eq!(avg_fn.aggregate(&[10.0, 20.0, 30.0]), Some(20.0));
+    assert_eq!(
+        avg_fn.aggregate(&[
+            AttributeValue::Double(10.0),
+            AttributeValue::Double(20.0),
+            AttributeValue::Double(30.0)
+        ]),
+        Some(AttributeValue::Double(20.0))
+    );
     assert_eq!(
         avg_fn.return_type(ApiAttributeType::DOUBLE),
         ApiAttributeType::DOUBLE
     );
 
     let count_fn = ctx.get_collection_aggregation_function("count").unwrap();
-    assert_eq!(count_fn.aggregate(&[]), Some(0.0));
+    assert_eq!(count_fn.aggregate(&[]), Some(AttributeValue::Long(0)));
     assert!(count_fn.supports_count_only());
+
+    // Non-numeric captures
+    let collect_fn = ctx.get_collection_aggregation_function("collect").unwrap();
+    let symbols = vec![
+        AttributeValue::String("IBM".to_string()),
+        AttributeValue::String("ORCL".to_string()),
+    ];
+    match collect_fn.aggregate(&symbols) {
+        Some(AttributeValue::Object(Some(b))) => {
+            assert_eq!(b.downcast_ref::<Vec<AttributeValue>>(), Some(&symbols))
+        }
+        other => panic!("expected array, got {other:?}"),
+    }
+    let max_fn = ctx.get_collection_aggregation_function("max").unwrap();
+    assert_eq!(max_fn.aggregate(&symbols), Some(symbols[1].clone()));
 }
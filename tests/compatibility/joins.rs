@@ -610,7 +610,6 @@ async fn join_test18_left_outer_null_id() {
 
 /// Join with GROUP BY aggregation
 #[tokio::test]
-#[ignore = "Complex GROUP BY expressions with qualified column names not yet supported"]
 async fn join_test19_with_group_by() {
     let app = "\
         CREATE STREAM Orders (productId INT, quantity INT);\n\
@@ -1781,7 +1780,6 @@ async fn join_test_cross_multiplication() {
 
 /// Join with count aggregation
 #[tokio::test]
-#[ignore = "Join with GROUP BY aggregation not yet fully supported"]
 async fn join_test_count_agg() {
     let app = "\
         CREATE STREAM stream1 (id INT, category STRING);\n\
@@ -1822,7 +1820,6 @@ async fn join_test_count_agg() {
 
 /// Join with sum aggregation
 #[tokio::test]
-#[ignore = "Join with GROUP BY aggregation not yet fully supported"]
 async fn join_test_sum_agg() {
     let app = "\
         CREATE STREAM stream1 (id INT, category STRING);\n\
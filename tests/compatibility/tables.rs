@@ -223,16 +223,22 @@ async fn table_test3_delete() {
     assert!(out.is_empty());
 }
 
-/// Table with primary key
+/// Table with primary key: inserting an existing key replaces the row
 /// Reference: DefineTableTestCase.java
 #[tokio::test]
-#[ignore = "Primary key syntax not yet supported"]
 async fn table_test4_primary_key() {
     let app = "\
-        CREATE TABLE stockTable (symbol STRING PRIMARY KEY, price FLOAT, volume INT);\n\
+        CREATE TABLE stockTable (symbol STRING PRIMARY KEY, price FLOAT, volume INT) \
+            WITH ('extension' = 'inMemory');\n\
         CREATE STREAM stockStream (symbol STRING, price FLOAT, volume INT);\n\
+        CREATE STREAM queryStream (symbol STRING);\n\
+        CREATE STREAM outputStream (symbol STRING, price FLOAT, volume INT);\n\
         \n\
-        INSERT INTO stockTable SELECT * FROM stockStream;\n";
+        INSERT INTO stockTable SELECT * FROM stockStream;\n\
+        \n\
+        INSERT INTO outputStream\n\
+        SELECT stockTable.symbol, stockTable.price, stockTable.volume\n\
+        FROM queryStream JOIN stockTable ON queryStream.symbol = stockTable.symbol;\n";
     let runner = AppRunner::new(app, "outputStream").await;
     runner.send(
         "stockStream",
@@ -242,7 +248,7 @@ async fn table_test4_primary_key() {
             AttributeValue::Int(100),
         ],
     );
-    // Insert duplicate key - should update or error
+    // Insert duplicate key - replaces the existing row
     runner.send(
         "stockStream",
         vec![
@@ -251,9 +257,21 @@ async fn table_test4_primary_key() {
             AttributeValue::Int(200),
         ],
     );
+    sleep(Duration::from_millis(50));
+    runner.send(
+        "queryStream",
+        vec![AttributeValue::String("IBM".to_string())],
+    );
     let out = runner.shutdown();
-    // Table should have only one IBM entry
-    assert!(!out.is_empty());
+    // Table should have only one IBM entry, holding the latest values
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::String("IBM".to_string()),
+            AttributeValue::Float(150.0),
+            AttributeValue::Int(200),
+        ]]
+    );
 }
 
 // ============================================================================
@@ -315,7 +333,6 @@ async fn table_test5_multiple_queries() {
 /// Test table with aggregation query
 /// Reference: InsertIntoTableTestCase.java:insertIntoTableTest5
 #[tokio::test]
-#[ignore = "Complex GROUP BY with table join not yet supported"]
 async fn table_test6_with_aggregation() {
     let app = "\
         CREATE TABLE salesTable (productId INT, region STRING, amount FLOAT);\n\
@@ -1570,7 +1587,6 @@ async fn table_test_full_outer_join() {
 
 /// Test table with ORDER BY in query
 #[tokio::test]
-#[ignore = "ORDER BY with table join not yet supported"]
 async fn table_test_order_by() {
     let app = "\
         CREATE TABLE scoreTable (playerId INT, score INT);\n\
@@ -851,7 +851,6 @@ async fn session_window_test2_with_partition() {
 
 /// Unique window - keeps only one event per unique key
 #[tokio::test]
-#[ignore = "Unique window syntax not yet supported"]
 async fn unique_window_test1_basic() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT, volume INT);\n\
@@ -895,7 +894,6 @@ async fn unique_window_test1_basic() {
 
 /// First unique window - keeps first occurrence of each unique key
 #[tokio::test]
-#[ignore = "FirstUnique window syntax not yet supported"]
 async fn first_unique_window_test1_basic() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT);\n\
@@ -929,7 +927,6 @@ async fn first_unique_window_test1_basic() {
 
 /// Delay window - delays events by specified time
 #[tokio::test]
-#[ignore = "Delay window syntax not yet supported"]
 async fn delay_window_test1_basic() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT);\n\
@@ -1147,27 +1144,29 @@ async fn length_batch_window_test7_with_filter() {
 // Reference: query/window/ExpressionWindowTestCase.java
 // ============================================================================
 
-/// Expression window - dynamic window size based on expression
+/// Expression window - keeps events while the condition holds
+/// Reference: ExpressionWindowTestCase.java:expressionWindowTest1
 #[tokio::test]
-#[ignore = "Expression window syntax not yet supported"]
 async fn expression_window_test1_basic() {
     let app = "\
-        CREATE STREAM stockStream (symbol STRING, price FLOAT, windowSize INT);\n\
-        CREATE STREAM outputStream (symbol STRING, total DOUBLE);\n\
+        CREATE STREAM stockStream (symbol STRING, price FLOAT);\n\
+        CREATE STREAM outputStream (total DOUBLE);\n\
         INSERT INTO outputStream\n\
-        SELECT symbol, sum(price) AS total \
-        FROM stockStream WINDOW('expression', 'length', windowSize);\n";
+        SELECT sum(price) AS total \
+        FROM stockStream WINDOW('expression', 'count() <= 2');\n";
     let runner = AppRunner::new(app, "outputStream").await;
-    runner.send(
-        "stockStream",
-        vec![
-            AttributeValue::String("IBM".to_string()),
-            AttributeValue::Float(100.0),
-            AttributeValue::Int(2),
-        ],
-    );
+    for (symbol, price) in [("IBM", 100.0), ("WSO2", 200.0), ("ORCL", 300.0)] {
+        runner.send(
+            "stockStream",
+            vec![
+                AttributeValue::String(symbol.to_string()),
+                AttributeValue::Float(price),
+            ],
+        );
+    }
     let out = runner.shutdown();
-    assert!(!out.is_empty());
+    // The first event is expired once a third one arrives
+    assert_eq!(out.last(), Some(&vec![AttributeValue::Double(500.0)]));
 }
 
 // ============================================================================
@@ -1177,7 +1176,6 @@ async fn expression_window_test1_basic() {
 
 /// Cron window - trigger output on schedule
 #[tokio::test]
-#[ignore = "Cron window syntax not yet fully supported"]
 async fn cron_window_test1_basic() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT);\n\
@@ -1205,7 +1203,6 @@ async fn cron_window_test1_basic() {
 
 /// Frequent window - tracks most frequent values
 #[tokio::test]
-#[ignore = "Frequent window syntax not yet supported"]
 async fn frequent_window_test1_basic() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT);\n\
@@ -2611,7 +2608,6 @@ async fn length_window_test_string_functions() {
 
 /// Sort window with multiple sort criteria
 #[tokio::test]
-#[ignore = "Multiple sort criteria in sort window not yet supported"]
 async fn sort_window_test_multiple_criteria() {
     let app = "\
         CREATE STREAM stockStream (symbol STRING, price FLOAT, volume INT);\n\
@@ -2637,7 +2633,7 @@ async fn sort_window_test_multiple_criteria() {
         ],
     );
     let out = runner.shutdown();
-    assert!(!out.is_empty());
+    assert_eq!(out.len(), 2);
 }
 
 /// Length window with boolean filter result
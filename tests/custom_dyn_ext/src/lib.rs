@@ -1,111 +1,87 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::sync::{Arc, Mutex};
-use eventflux_rust::core::config::{eventflux_app_context::EventFluxAppContext, eventflux_query_context::EventFluxQueryContext};
-use eventflux_rust::core::event::complex_event::ComplexEvent;
-use eventflux_rust::core::event::value::AttributeValue;
-use eventflux_rust::core::extension::WindowProcessorFactory;
-use eventflux_rust::core::executor::expression_executor::ExpressionExecutor;
-use eventflux_rust::core::executor::function::scalar_function_executor::ScalarFunctionExecutor;
-use eventflux_rust::core::query::processor::{CommonProcessorMeta, ProcessingMode, Processor};
-use eventflux_rust::core::query::processor::stream::window::WindowProcessor;
-use eventflux_rust::core::eventflux_manager::EventFluxManager;
-use eventflux_rust::query_api::definition::attribute::Type as AttrType;
-use eventflux_rust::query_api::execution::query::input::handler::WindowHandler;
+//! Test extension built against the stable C ABI. Only the `repr(C)` types
+//! from `eventflux_rust::core::extension::abi` cross the library boundary.
 
-#[derive(Debug)]
-struct DynWindowProcessor {
-    meta: CommonProcessorMeta,
-}
-
-impl Processor for DynWindowProcessor {
-    fn process(&self, chunk: Option<Box<dyn ComplexEvent>>) {
-        if let Some(ref next) = self.meta.next_processor {
-            next.lock().unwrap().process(chunk);
-        }
-    }
-
-    fn next_processor(&self) -> Option<Arc<Mutex<dyn Processor>>> {
-        self.meta.next_processor.as_ref().map(Arc::clone)
-    }
-
-    fn set_next_processor(&mut self, next: Option<Arc<Mutex<dyn Processor>>>) {
-        self.meta.next_processor = next;
-    }
-
-    fn clone_processor(&self, q: &Arc<EventFluxQueryContext>) -> Box<dyn Processor> {
-        Box::new(DynWindowProcessor { meta: CommonProcessorMeta::new(Arc::clone(&self.meta.eventflux_app_context), Arc::clone(q)) })
-    }
-
-    fn get_eventflux_app_context(&self) -> Arc<EventFluxAppContext> {
-        Arc::clone(&self.meta.eventflux_app_context)
-    }
+use std::ffi::c_void;
 
-    fn get_eventflux_query_context(&self) -> Arc<EventFluxQueryContext> {
-        Arc::clone(&self.meta.eventflux_query_context)
-    }
+use eventflux_rust::core::extension::abi::{
+    EfExtension, EfFunction, EfOut, EfStr, EfValue, EfWindow, EF_ERROR, EF_OK, EF_TYPE_INT,
+    EVENTFLUX_ABI_VERSION,
+};
 
-    fn get_processing_mode(&self) -> ProcessingMode {
-        ProcessingMode::BATCH
+unsafe extern "C" fn dyn_plus_one(args: *const EfValue, len: usize, out: *const EfOut) -> i32 {
+    if len != 1 {
+        (*out).emit_error("dynPlusOne expects one argument");
+        return EF_ERROR;
     }
-
-    fn is_stateful(&self) -> bool {
-        false
+    let arg = &*args;
+    if arg.tag == EF_TYPE_INT {
+        (*out).emit_value(&EfValue::int(arg.long as i32 + 1));
+    } else {
+        (*out).emit_value(&EfValue::null());
     }
+    EF_OK
 }
-impl WindowProcessor for DynWindowProcessor {}
 
-#[derive(Debug, Clone)]
-struct DynWindowFactory;
-impl WindowProcessorFactory for DynWindowFactory {
-    fn name(&self) -> &'static str { "dynWindow" }
-    fn create(&self, _h: &WindowHandler, app: Arc<EventFluxAppContext>, q: Arc<EventFluxQueryContext>, _parse_ctx: &eventflux_rust::core::util::parser::expression_parser::ExpressionParserContext) -> Result<Arc<Mutex<dyn Processor>>, String> {
-        Ok(Arc::new(Mutex::new(DynWindowProcessor { meta: CommonProcessorMeta::new(app, q) })))
+/// `dynWindow(n)` keeps the last `n` events (one when no length is given)
+unsafe extern "C" fn dyn_window_create(
+    params: *const EfValue,
+    len: usize,
+    out: *const EfOut,
+) -> *mut c_void {
+    let length = if len == 0 { 1 } else { (*params).long };
+    if length <= 0 {
+        (*out).emit_error("dynWindow length must be positive");
+        return std::ptr::null_mut();
     }
-    fn clone_box(&self) -> Box<dyn WindowProcessorFactory> { Box::new(self.clone()) }
+    Box::into_raw(Box::new(length as usize)) as *mut c_void
 }
 
-#[derive(Debug, Default)]
-struct DynPlusOne {
-    arg: Option<Box<dyn ExpressionExecutor>>,
-}
-impl Clone for DynPlusOne {
-    fn clone(&self) -> Self { Self { arg: None } }
-}
-impl ExpressionExecutor for DynPlusOne {
-    fn execute(&self, event: Option<&dyn ComplexEvent>) -> Option<AttributeValue> {
-        let v = self.arg.as_ref()?.execute(event)?;
-        match v {
-            AttributeValue::Int(i) => Some(AttributeValue::Int(i + 1)),
-            _ => None,
-        }
-    }
-    fn get_return_type(&self) -> AttrType { AttrType::INT }
-    fn clone_executor(&self, _c: &Arc<EventFluxAppContext>) -> Box<dyn ExpressionExecutor> {
-        Box::new(self.clone())
-    }
+unsafe extern "C" fn dyn_window_on_event(
+    state: *mut c_void,
+    _timestamp: i64,
+    _values: *const EfValue,
+    _len: usize,
+    held: usize,
+) -> i64 {
+    let length = *(state as *const usize);
+    (held + 1).saturating_sub(length) as i64
 }
-impl ScalarFunctionExecutor for DynPlusOne {
-    fn init(&mut self, args: &Vec<Box<dyn ExpressionExecutor>>, ctx: &Arc<EventFluxAppContext>) -> Result<(), String> {
-        if args.len() != 1 { return Err("dynPlusOne expects one argument".to_string()); }
-        self.arg = Some(args[0].clone_executor(ctx));
-        Ok(())
-    }
-    fn destroy(&mut self) {}
-    fn get_name(&self) -> String { "dynPlusOne".to_string() }
-    fn clone_scalar_function(&self) -> Box<dyn ScalarFunctionExecutor> { Box::new(self.clone()) }
+
+unsafe extern "C" fn dyn_window_destroy(state: *mut c_void) {
+    drop(Box::from_raw(state as *mut usize));
 }
 
+static FUNCTIONS: [EfFunction; 1] = [EfFunction {
+    name: EfStr::from_static("dynPlusOne"),
+    return_type: EF_TYPE_INT,
+    call: dyn_plus_one,
+}];
+
+static WINDOWS: [EfWindow; 1] = [EfWindow {
+    name: EfStr::from_static("dynWindow"),
+    create: dyn_window_create,
+    on_event: dyn_window_on_event,
+    destroy: dyn_window_destroy,
+}];
+
+static EXTENSION: EfExtension = EfExtension {
+    functions: FUNCTIONS.as_ptr(),
+    function_count: FUNCTIONS.len(),
+    windows: WINDOWS.as_ptr(),
+    window_count: WINDOWS.len(),
+    ..EfExtension::empty(EfStr::from_static("custom_dyn_ext"))
+};
+
 #[no_mangle]
-pub extern "C" fn register_windows(manager: &EventFluxManager) {
-    println!("[dyn_ext] register_windows called");
-    manager.add_window_factory("dynWindow".to_string(), Box::new(DynWindowFactory));
+pub extern "C" fn eventflux_abi_version() -> u32 {
+    EVENTFLUX_ABI_VERSION
 }
 
 #[no_mangle]
-pub extern "C" fn register_functions(manager: &EventFluxManager) {
-    println!("[dyn_ext] register_functions called");
-    manager.add_scalar_function_factory("dynPlusOne".to_string(), Box::new(DynPlusOne::default()));
+pub extern "C" fn eventflux_extension() -> *const EfExtension {
+    &EXTENSION
 }
 
 /// Returns the path to the compiled dynamic library for this crate.
@@ -114,6 +90,10 @@ pub fn library_path() -> std::path::PathBuf {
     let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     p.push("../../target/debug/deps");
     p = p.canonicalize().unwrap_or(p);
-    p.push(format!("{}custom_dyn_ext.{}", std::env::consts::DLL_PREFIX, std::env::consts::DLL_EXTENSION));
+    p.push(format!(
+        "{}custom_dyn_ext.{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_EXTENSION
+    ));
     p
 }
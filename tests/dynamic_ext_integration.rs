@@ -35,3 +35,43 @@ async fn test_dynamic_extension_loading() {
     let out = runner.shutdown();
     assert_eq!(out, vec![vec![AttributeValue::Int(2)]]);
 }
+
+#[test]
+fn test_abi_extension_registration() {
+    let manager = EventFluxManager::new();
+    let lib_path = custom_dyn_ext::library_path();
+    manager
+        .set_extension("dynlib", lib_path.to_str().unwrap().to_string())
+        .unwrap();
+
+    let ctx = manager.eventflux_context();
+    assert!(ctx.get_window_factory("dynWindow").is_some());
+    let plus_one = ctx.get_scalar_function_factory("dynPlusOne").unwrap();
+    assert_eq!(plus_one.get_name(), "dynPlusOne");
+}
+
+#[test]
+fn test_non_extension_library_is_rejected() {
+    let manager = EventFluxManager::new();
+    let err = manager
+        .set_extension("missing", "/nonexistent/libnothing.so".to_string())
+        .unwrap_err();
+    assert!(!err.is_empty());
+}
+
+#[test]
+fn test_wrong_abi_version_is_rejected() {
+    let manager = EventFluxManager::new();
+    let lib_path = wrong_abi_ext::library_path();
+    let err = manager
+        .set_extension("wrongabi", lib_path.to_str().unwrap().to_string())
+        .unwrap_err();
+    assert!(
+        err.contains(&format!(
+            "Extension 'wrongabi' was built for EventFlux extension ABI version {}",
+            wrong_abi_ext::WRONG_ABI_VERSION
+        )),
+        "{err}"
+    );
+    assert!(err.contains("rebuild the extension"), "{err}");
+}
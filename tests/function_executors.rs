@@ -4,7 +4,12 @@ use eventflux_rust::core::event::value::AttributeValue;
 use eventflux_rust::core::executor::constant_expression_executor::ConstantExpressionExecutor;
 use eventflux_rust::core::executor::expression_executor::ExpressionExecutor;
 use eventflux_rust::core::executor::function::{
-    CoalesceFunctionExecutor, InstanceOfStringExpressionExecutor, UuidFunctionExecutor,
+    BinaryEncoding, CoalesceFunctionExecutor, DecodeFunctionExecutor, EncodeFunctionExecutor,
+    HashAlgorithm, HashFunctionExecutor, HmacSha256FunctionExecutor,
+    InstanceOfStringExpressionExecutor, ParseUrlFunctionExecutor, RegexpExtractFunctionExecutor,
+    RegexpMatchesFunctionExecutor, RegexpReplaceFunctionExecutor, SplitFunctionExecutor,
+    SplitPartFunctionExecutor, UrlDecodeFunctionExecutor, UuidFunctionExecutor,
+    UuidV5FunctionExecutor,
 };
 use eventflux_rust::query_api::definition::attribute::Type as AttrType;
 
@@ -45,3 +50,201 @@ fn test_instance_of_string() {
     let exec = InstanceOfStringExpressionExecutor::new(inner).unwrap();
     assert_eq!(exec.execute(None), Some(AttributeValue::Bool(true)));
 }
+
+fn string_const(v: &str) -> Box<dyn ExpressionExecutor> {
+    Box::new(ConstantExpressionExecutor::new(
+        AttributeValue::String(v.to_string()),
+        AttrType::STRING,
+    ))
+}
+
+fn int_const(v: i32) -> Box<dyn ExpressionExecutor> {
+    Box::new(ConstantExpressionExecutor::new(
+        AttributeValue::Int(v),
+        AttrType::INT,
+    ))
+}
+
+fn string_val(v: &str) -> Option<AttributeValue> {
+    Some(AttributeValue::String(v.to_string()))
+}
+
+#[test]
+fn test_regexp_functions() {
+    let matches =
+        RegexpMatchesFunctionExecutor::new(string_const("GET /index.html"), string_const(r"^GET "))
+            .unwrap();
+    assert_eq!(matches.execute(None), Some(AttributeValue::Bool(true)));
+
+    let extract = RegexpExtractFunctionExecutor::new(
+        string_const("status=404 bytes=512"),
+        string_const(r"status=(\d+) bytes=(\d+)"),
+        Some(int_const(2)),
+    )
+    .unwrap();
+    assert_eq!(extract.execute(None), string_val("512"));
+
+    let no_match =
+        RegexpExtractFunctionExecutor::new(string_const("ok"), string_const(r"status=(\d+)"), None)
+            .unwrap();
+    assert_eq!(no_match.execute(None), Some(AttributeValue::Null));
+
+    let replace = RegexpReplaceFunctionExecutor::new(
+        string_const("user=alice id=42"),
+        string_const(r"(\w+)=(\w+)"),
+        string_const("$2"),
+    )
+    .unwrap();
+    assert_eq!(replace.execute(None), string_val("alice 42"));
+}
+
+#[test]
+fn test_regexp_constant_pattern_validated_at_build() {
+    assert!(
+        RegexpMatchesFunctionExecutor::new(string_const("x"), string_const("(unclosed")).is_err()
+    );
+    assert!(RegexpExtractFunctionExecutor::new(
+        string_const("x"),
+        string_const("(a)"),
+        Some(int_const(2)),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_split_functions() {
+    let part = |n: i32| {
+        SplitPartFunctionExecutor::new(string_const("a,b,c"), string_const(","), int_const(n))
+            .unwrap()
+            .execute(None)
+    };
+    assert_eq!(part(2), string_val("b"));
+    assert_eq!(part(-1), string_val("c"));
+    assert_eq!(part(4), string_val(""));
+
+    let split = SplitFunctionExecutor::new(string_const("a|b"), string_const("|")).unwrap();
+    match split.execute(None) {
+        Some(AttributeValue::Object(Some(obj))) => {
+            let fields = obj.downcast_ref::<Vec<AttributeValue>>().unwrap();
+            assert_eq!(
+                fields,
+                &vec![
+                    AttributeValue::String("a".into()),
+                    AttributeValue::String("b".into())
+                ]
+            );
+        }
+        other => panic!("expected array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_url_and_url_decode() {
+    let url = "https://bob@example.com:8443/a/b.html?q=rust+lang&page=2#top";
+    let parse = |part: &str, key: Option<&str>| {
+        ParseUrlFunctionExecutor::new(string_const(url), string_const(part), key.map(string_const))
+            .unwrap()
+            .execute(None)
+    };
+    assert_eq!(parse("PROTOCOL", None), string_val("https"));
+    assert_eq!(parse("HOST", None), string_val("example.com"));
+    assert_eq!(parse("AUTHORITY", None), string_val("bob@example.com:8443"));
+    assert_eq!(parse("USERINFO", None), string_val("bob"));
+    assert_eq!(parse("PATH", None), string_val("/a/b.html"));
+    assert_eq!(parse("QUERY", None), string_val("q=rust+lang&page=2"));
+    assert_eq!(parse("QUERY", Some("page")), string_val("2"));
+    assert_eq!(parse("QUERY", Some("missing")), Some(AttributeValue::Null));
+    assert_eq!(
+        parse("FILE", None),
+        string_val("/a/b.html?q=rust+lang&page=2")
+    );
+    assert_eq!(parse("REF", None), string_val("top"));
+    assert!(ParseUrlFunctionExecutor::new(string_const(url), string_const("PORT"), None).is_err());
+
+    let decode = UrlDecodeFunctionExecutor::new(string_const("rust+lang%2Fcore%20x")).unwrap();
+    assert_eq!(decode.execute(None), string_val("rust lang/core x"));
+}
+
+#[test]
+fn test_hash_functions_match_reference_vectors() {
+    let hash = |algorithm: HashAlgorithm, v: &str| {
+        HashFunctionExecutor::new(algorithm, string_const(v))
+            .unwrap()
+            .execute(None)
+    };
+    assert_eq!(
+        hash(HashAlgorithm::Md5, "abc"),
+        string_val("900150983cd24fb0d6963f7d28e17f72")
+    );
+    assert_eq!(
+        hash(HashAlgorithm::Sha1, "abc"),
+        string_val("a9993e364706816aba3e25717850c26c9cd0d89d")
+    );
+    assert_eq!(
+        hash(HashAlgorithm::Sha256, "abc"),
+        string_val("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(
+        hash(HashAlgorithm::Crc32, "123456789"),
+        Some(AttributeValue::Long(3421780262))
+    );
+    assert_eq!(
+        hash(HashAlgorithm::XxHash64, ""),
+        Some(AttributeValue::Long(-1205034819632174695))
+    );
+
+    let hmac = HmacSha256FunctionExecutor::new(
+        string_const("key"),
+        string_const("The quick brown fox jumps over the lazy dog"),
+    )
+    .unwrap();
+    assert_eq!(
+        hmac.execute(None),
+        string_val("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8")
+    );
+
+    let uuid =
+        UuidV5FunctionExecutor::new(string_const("dns"), string_const("python.org")).unwrap();
+    assert_eq!(
+        uuid.execute(None),
+        string_val("886313e1-3b8a-5372-9b90-0c9aee199e5d")
+    );
+    assert!(UuidV5FunctionExecutor::new(string_const("bogus"), string_const("x")).is_err());
+}
+
+#[test]
+fn test_encode_decode_round_trip() {
+    let encode = |encoding: BinaryEncoding, v: &str| {
+        EncodeFunctionExecutor::new(encoding, string_const(v))
+            .unwrap()
+            .execute(None)
+    };
+    let decode = |encoding: BinaryEncoding, v: &str| {
+        DecodeFunctionExecutor::new(encoding, string_const(v))
+            .unwrap()
+            .execute(None)
+    };
+    assert_eq!(
+        encode(BinaryEncoding::Base64, "hello"),
+        string_val("aGVsbG8=")
+    );
+    assert_eq!(
+        decode(BinaryEncoding::Base64, "aGVsbG8="),
+        string_val("hello")
+    );
+    assert_eq!(encode(BinaryEncoding::Hex, "hi"), string_val("6869"));
+    assert_eq!(decode(BinaryEncoding::Hex, "6869"), string_val("hi"));
+    // Malformed input and non-UTF-8 bytes decode to NULL
+    assert_eq!(
+        decode(BinaryEncoding::Hex, "zz"),
+        Some(AttributeValue::Null)
+    );
+    assert_eq!(
+        decode(BinaryEncoding::Hex, "ff"),
+        Some(AttributeValue::Null)
+    );
+    assert_eq!(
+        decode(BinaryEncoding::Base64, "@@"),
+        Some(AttributeValue::Null)
+    );
+}
@@ -1,9 +1,17 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use eventflux_rust::core::event::stream::stream_event::StreamEvent;
 use eventflux_rust::core::event::value::AttributeValue;
 use eventflux_rust::core::table::{
-    InMemoryCompiledCondition, InMemoryCompiledUpdateSet, InMemoryTable, Table,
+    InMemoryCompiledCondition, InMemoryCompiledUpdateSet, InMemoryTable, RetentionPolicy, Table,
+    TtlMode,
 };
+use eventflux_rust::query_api::definition::{
+    AttributeType, StreamDefinition, TableDefinition, TableIndex,
+};
+use eventflux_rust::query_api::execution::query::output::stream::UpdateSet;
+use eventflux_rust::query_api::expression::condition::compare::Operator as CompareOp;
+use eventflux_rust::query_api::expression::{Expression, Variable};
 
 #[test]
 fn test_insert_and_contains() {
@@ -92,3 +100,318 @@ fn test_find() {
         .unwrap()
         .is_none());
 }
+
+fn orders_table() -> InMemoryTable {
+    let definition = TableDefinition::new("Orders".to_string())
+        .attribute("id".to_string(), AttributeType::LONG)
+        .attribute("region".to_string(), AttributeType::STRING)
+        .attribute("amount".to_string(), AttributeType::DOUBLE)
+        .primary_key(vec!["id".to_string()])
+        .index(TableIndex::new(
+            "orders_region".to_string(),
+            vec!["region".to_string()],
+        ))
+        .index(TableIndex::new(
+            "orders_amount".to_string(),
+            vec!["amount".to_string()],
+        ));
+    let table = InMemoryTable::with_definition(&definition).unwrap();
+    for (id, region, amount) in [(1, "eu", 10.0), (2, "us", 25.0), (3, "eu", 40.0)] {
+        table
+            .insert(&[
+                AttributeValue::Long(id),
+                AttributeValue::String(region.to_string()),
+                AttributeValue::Double(amount),
+            ])
+            .unwrap();
+    }
+    table
+}
+
+fn column(name: &str) -> Expression {
+    Expression::variable(name.to_string())
+}
+
+fn ids(rows: Vec<Vec<AttributeValue>>) -> Vec<AttributeValue> {
+    rows.into_iter().map(|row| row[0].clone()).collect()
+}
+
+#[test]
+fn test_primary_key_upsert() {
+    let table = orders_table();
+    table
+        .insert(&[
+            AttributeValue::Long(2),
+            AttributeValue::String("apac".to_string()),
+            AttributeValue::Double(99.0),
+        ])
+        .unwrap();
+    let rows = Table::all_rows(&table).unwrap();
+    assert_eq!(rows.len(), 3);
+
+    // Key-length value lists (as used by `IN`) look up the primary key,
+    // normalising the value to the column type
+    let found = table
+        .find(&InMemoryCompiledCondition {
+            values: vec![AttributeValue::Int(2)],
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(found[1], AttributeValue::String("apac".to_string()));
+
+    // The stale index entry for the replaced row is gone
+    let cond = table.compile_condition(Expression::compare(
+        column("region"),
+        CompareOp::Equal,
+        Expression::value_string("us".to_string()),
+    ));
+    assert!(!table.contains(&*cond).unwrap());
+
+    assert!(table
+        .insert(&[
+            AttributeValue::Null,
+            AttributeValue::String("eu".to_string()),
+            AttributeValue::Double(1.0),
+        ])
+        .is_err());
+}
+
+#[test]
+fn test_index_equality_and_range_conditions() {
+    let table = orders_table();
+
+    let eu = table.compile_condition(Expression::compare(
+        column("region"),
+        CompareOp::Equal,
+        Expression::value_string("eu".to_string()),
+    ));
+    assert!(table.contains(&*eu).unwrap());
+
+    // amount > 10 AND amount <= 40, with the constant on either side
+    let range = table.compile_condition(Expression::and(
+        Expression::compare(
+            Expression::value_long(10),
+            CompareOp::LessThan,
+            column("amount"),
+        ),
+        Expression::compare(
+            column("amount"),
+            CompareOp::LessThanEqual,
+            Expression::value_double(40.0),
+        ),
+    ));
+    assert!(table.delete(&*range).unwrap());
+    assert_eq!(
+        ids(Table::all_rows(&table).unwrap()),
+        vec![AttributeValue::Long(1)]
+    );
+
+    // Residual terms outside the index still apply
+    let none = table.compile_condition(Expression::and(
+        Expression::compare(
+            column("region"),
+            CompareOp::Equal,
+            Expression::value_string("eu".to_string()),
+        ),
+        Expression::compare(
+            column("amount"),
+            CompareOp::GreaterThan,
+            Expression::value_double(10.0),
+        ),
+    ));
+    assert!(!table.contains(&*none).unwrap());
+}
+
+#[test]
+fn test_update_by_key_and_key_conflicts() {
+    let table = orders_table();
+    let by_id = table.compile_condition(Expression::compare(
+        column("id"),
+        CompareOp::Equal,
+        Expression::value_long(1),
+    ));
+    let set_amount = table.compile_update_set(UpdateSet::new().add_set_attribute(
+        Variable::new("amount".to_string()),
+        Expression::value_double(15.0),
+    ));
+    assert!(table.update(&*by_id, &*set_amount).unwrap());
+    let found = table.find(&*by_id).unwrap().unwrap();
+    assert_eq!(found[2], AttributeValue::Double(15.0));
+
+    // Moving row 1 onto key 3 would duplicate the primary key
+    let set_id = table.compile_update_set(
+        UpdateSet::new()
+            .add_set_attribute(Variable::new("id".to_string()), Expression::value_long(3)),
+    );
+    assert!(table.update(&*by_id, &*set_id).is_err());
+    assert_eq!(Table::all_rows(&table).unwrap().len(), 3);
+}
+
+#[test]
+fn test_join_condition_uses_index() {
+    let table = orders_table();
+    let stream_def = StreamDefinition::new("Lookups".to_string())
+        .attribute("region".to_string(), AttributeType::STRING);
+    let cond = Expression::compare(
+        Expression::Variable(Variable::new("region".to_string()).of_stream("Lookups".to_string())),
+        CompareOp::Equal,
+        Expression::Variable(Variable::new("region".to_string()).of_stream("Orders".to_string())),
+    );
+    let compiled = table
+        .compile_join_condition(cond, "Lookups", &stream_def)
+        .expect("equality on an indexed column compiles to a lookup");
+
+    let mut probe = StreamEvent::new(0, 1, 0, 0);
+    probe.before_window_data[0] = AttributeValue::String("eu".to_string());
+    let rows = table
+        .find_rows_for_join(&probe, Some(&*compiled), None)
+        .unwrap();
+    assert_eq!(
+        ids(rows),
+        vec![AttributeValue::Long(1), AttributeValue::Long(3)]
+    );
+}
+
+#[test]
+fn test_ttl_and_max_rows_eviction() {
+    let table = InMemoryTable::new().with_retention(Some(RetentionPolicy {
+        ttl_ms: Some(60_000),
+        mode: TtlMode::AfterWrite,
+        max_rows: Some(2),
+    }));
+    for i in 1..=3 {
+        table.insert(&[AttributeValue::Int(i)]).unwrap();
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    // Over the row limit: the oldest row goes first
+    assert_eq!(
+        table.evict_expired(now).unwrap(),
+        vec![vec![AttributeValue::Int(1)]]
+    );
+    assert!(table.evict_expired(now).unwrap().is_empty());
+    // Past the TTL every remaining row expires
+    assert_eq!(
+        table.evict_expired(now + 60_001).unwrap(),
+        vec![vec![AttributeValue::Int(2)], vec![AttributeValue::Int(3)]]
+    );
+    assert!(table.all_rows().is_empty());
+}
+
+#[test]
+fn test_ttl_after_access_and_restore() {
+    let table = InMemoryTable::new().with_retention(Some(RetentionPolicy {
+        ttl_ms: Some(1_000),
+        mode: TtlMode::AfterAccess,
+        max_rows: None,
+    }));
+    let written = chrono::Utc::now().timestamp_millis();
+    table
+        .restore_timed_rows(vec![
+            (vec![AttributeValue::Int(1)], written - 5_000),
+            (vec![AttributeValue::Int(2)], written - 5_000),
+        ])
+        .unwrap();
+    // Reading row 2 keeps it alive; row 1 keeps its restored age
+    assert!(table
+        .contains(&InMemoryCompiledCondition {
+            values: vec![AttributeValue::Int(2)]
+        })
+        .unwrap());
+    assert_eq!(
+        table.evict_expired(written).unwrap(),
+        vec![vec![AttributeValue::Int(1)]]
+    );
+    let timed = table.timed_rows().unwrap();
+    assert_eq!(timed.len(), 1);
+    assert!(timed[0].1 >= written);
+}
+
+#[test]
+fn test_recorded_changes_replay() {
+    let table = orders_table();
+    let replica = orders_table();
+    assert!(table.pending_changes().is_err());
+    assert!(table.record_changes());
+    assert!(table.pending_changes().unwrap().is_empty());
+
+    // Upsert over key 2, update row 1, delete row 3
+    table
+        .insert(&[
+            AttributeValue::Long(2),
+            AttributeValue::String("apac".to_string()),
+            AttributeValue::Double(99.0),
+        ])
+        .unwrap();
+    let by_id = |id| {
+        table.compile_condition(Expression::compare(
+            column("id"),
+            CompareOp::Equal,
+            Expression::value_long(id),
+        ))
+    };
+    let set_amount = table.compile_update_set(UpdateSet::new().add_set_attribute(
+        Variable::new("amount".to_string()),
+        Expression::value_double(15.0),
+    ));
+    assert!(table.update(&*by_id(1), &*set_amount).unwrap());
+    assert!(table.delete(&*by_id(3)).unwrap());
+
+    let changes = table.pending_changes().unwrap();
+    assert_eq!(changes.len(), 4);
+    replica.apply_changes(&changes).unwrap();
+    assert_eq!(
+        Table::all_rows(&replica).unwrap(),
+        Table::all_rows(&table).unwrap()
+    );
+
+    // Committed changes are dropped, later ones are kept
+    table
+        .insert(&[
+            AttributeValue::Long(4),
+            AttributeValue::String("us".to_string()),
+            AttributeValue::Double(1.0),
+        ])
+        .unwrap();
+    table.commit_changes(changes.len());
+    assert_eq!(table.pending_changes().unwrap().len(), 1);
+}
+
+#[test]
+fn test_repeated_reads_record_one_access_change() {
+    let retention = || {
+        Some(RetentionPolicy {
+            ttl_ms: Some(60_000),
+            mode: TtlMode::AfterAccess,
+            max_rows: None,
+        })
+    };
+    let table = InMemoryTable::new().with_retention(retention());
+    let replica = InMemoryTable::new().with_retention(retention());
+    assert!(table.record_changes());
+    table.insert(&[AttributeValue::Int(1)]).unwrap();
+    table.insert(&[AttributeValue::Int(2)]).unwrap();
+    let one = InMemoryCompiledCondition {
+        values: vec![AttributeValue::Int(1)],
+    };
+    for _ in 0..3 {
+        assert!(table.contains(&one).unwrap());
+    }
+    let changes = table.pending_changes().unwrap();
+    assert_eq!(changes.len(), 3);
+    replica.apply_changes(&changes).unwrap();
+    assert_eq!(replica.timed_rows().unwrap(), table.timed_rows().unwrap());
+
+    // Reads after a commit start a new change, amended until the row is written
+    table.commit_changes(changes.len());
+    assert!(table.contains(&one).unwrap());
+    table.insert(&[AttributeValue::Int(3)]).unwrap();
+    assert!(table.find(&one).unwrap().is_some());
+    assert_eq!(table.pending_changes().unwrap().len(), 2);
+    assert!(table.delete(&one).unwrap());
+    assert!(table
+        .contains(&InMemoryCompiledCondition {
+            values: vec![AttributeValue::Int(2)]
+        })
+        .unwrap());
+    assert_eq!(table.pending_changes().unwrap().len(), 4);
+}
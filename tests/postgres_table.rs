@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! PostgreSQL table tests. Tests needing a server use
+//! `EVENTFLUX_TEST_POSTGRES_URL` (default
+//! `host=localhost user=postgres password=postgres`) and are skipped when it
+//! isn't reachable.
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::eventflux_manager::EventFluxManager;
+use eventflux_rust::core::table::{InMemoryCompiledCondition, PostgresTable, Table};
+use eventflux_rust::query_api::definition::attribute::Type as AttributeType;
+use eventflux_rust::query_api::definition::TableDefinition;
+use eventflux_rust::query_api::execution::query::output::stream::UpdateSet;
+use eventflux_rust::query_api::expression::condition::compare::Operator as CompareOp;
+use eventflux_rust::query_api::expression::{Expression, Variable};
+
+fn postgres_url() -> String {
+    std::env::var("EVENTFLUX_TEST_POSTGRES_URL")
+        .unwrap_or_else(|_| "host=localhost user=postgres password=postgres".to_string())
+}
+
+fn unique_name(prefix: &str) -> String {
+    format!("{}_{}", prefix, uuid::Uuid::new_v4().simple())
+}
+
+fn accounts_definition() -> TableDefinition {
+    let mut def = TableDefinition::id("Accounts".to_string())
+        .attribute("id".to_string(), AttributeType::STRING)
+        .attribute("balance".to_string(), AttributeType::LONG)
+        .attribute("rate".to_string(), AttributeType::DOUBLE)
+        .attribute("active".to_string(), AttributeType::BOOL);
+    def.primary_key = vec!["id".to_string()];
+    def
+}
+
+fn account(id: &str, balance: i64, rate: f64) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::String(id.to_string()),
+        AttributeValue::Long(balance),
+        AttributeValue::Double(rate),
+        AttributeValue::Bool(true),
+    ]
+}
+
+/// Whether the test server answers; a missing table still proves it does
+fn server_available() -> bool {
+    let probe = PostgresTable::connect(
+        &accounts_definition(),
+        &postgres_url(),
+        unique_name("probe"),
+        1,
+    )
+    .unwrap();
+    match probe.validate_connectivity() {
+        Err(e) if e.to_string().contains("not reachable") => {
+            println!("PostgreSQL not available, skipping: {e}");
+            false
+        }
+        _ => true,
+    }
+}
+
+/// A fresh table in the test server, or `None` if there is no server
+fn live_table(table_name: &str) -> Option<PostgresTable> {
+    if !server_available() {
+        return None;
+    }
+    let table = PostgresTable::connect(
+        &accounts_definition(),
+        &postgres_url(),
+        table_name.into(),
+        2,
+    )
+    .unwrap();
+    table.create_if_missing(&accounts_definition()).unwrap();
+    Some(table)
+}
+
+#[test]
+fn test_unreachable_server_fails_validation() {
+    let table = PostgresTable::connect(
+        &accounts_definition(),
+        "host=127.0.0.1 port=1 user=postgres connect_timeout=2",
+        "accounts".to_string(),
+        1,
+    )
+    .unwrap();
+    let err = table.validate_connectivity().unwrap_err().to_string();
+    assert!(err.contains("not reachable"), "{err}");
+}
+
+#[tokio::test]
+async fn test_unreachable_server_fails_app_creation() {
+    let manager = EventFluxManager::new();
+    let result = manager
+        .create_eventflux_app_runtime_from_string(
+            "CREATE STREAM In (id STRING);\n\
+             CREATE TABLE T (id STRING) WITH ('extension' = 'postgres', \
+                'url' = 'host=127.0.0.1 port=1 user=postgres connect_timeout=2');\n\
+             INSERT INTO T SELECT id FROM In;\n",
+        )
+        .await;
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("connectivity validation failed"), "{err}");
+}
+
+#[test]
+fn test_crud_with_parameterized_sql() {
+    let name = unique_name("accounts");
+    let Some(table) = live_table(&name) else {
+        return;
+    };
+    table.validate_connectivity().unwrap();
+    table.insert(&account("a", 10, 0.5)).unwrap();
+    table.insert(&account("a", 20, 0.5)).unwrap();
+    table.insert(&account("b", 5, 1.5)).unwrap();
+
+    // Key-length value lists look rows up by primary key
+    let a = InMemoryCompiledCondition {
+        values: vec![AttributeValue::String("a".to_string())],
+    };
+    assert_eq!(table.find(&a).unwrap(), Some(account("a", 20, 0.5)));
+
+    // An INT constant binds to the BIGINT column
+    let low = table.compile_condition(Expression::compare(
+        Expression::Variable(Variable::new("balance".to_string())),
+        CompareOp::LessThan,
+        Expression::value_int(10),
+    ));
+    assert_eq!(table.find(&*low).unwrap(), Some(account("b", 5, 1.5)));
+
+    let update = table.compile_update_set(UpdateSet::new().add_set_attribute(
+        Variable::new("rate".to_string()),
+        Expression::value_double(2.5),
+    ));
+    assert!(table.update(&*low, &*update).unwrap());
+    assert_eq!(table.find(&*low).unwrap(), Some(account("b", 5, 2.5)));
+
+    assert!(table.delete(&*low).unwrap());
+    assert_eq!(table.all_rows().unwrap(), vec![account("a", 20, 0.5)]);
+}
+
+#[test]
+fn test_mismatched_definition_fails_validation() {
+    let name = unique_name("accounts");
+    if live_table(&name).is_none() {
+        return;
+    }
+    let other = TableDefinition::id("Accounts".to_string())
+        .attribute("owner".to_string(), AttributeType::STRING);
+    let table = PostgresTable::connect(&other, &postgres_url(), name, 1).unwrap();
+    let err = table.validate_connectivity().unwrap_err().to_string();
+    assert!(err.contains("no column 'owner'"), "{err}");
+}
+
+#[tokio::test]
+async fn postgres_table_join_via_app_runner() {
+    if !server_available() {
+        return;
+    }
+    let query = format!(
+        "\
+        CREATE STREAM RoomStream (roomNo INT, type STRING);\n\
+        CREATE STREAM TempStream (roomNo INT, temp DOUBLE);\n\
+        CREATE STREAM Out (roomNo INT, type STRING, temp DOUBLE);\n\
+        CREATE TABLE Rooms (roomNo INT PRIMARY KEY, type STRING) \
+            WITH ('extension' = 'postgres', 'url' = '{}', 'table.name' = '{}', \
+            'table.create' = 'true');\n\
+        INSERT INTO Rooms SELECT roomNo, type FROM RoomStream;\n\
+        INSERT INTO Out SELECT TempStream.roomNo, Rooms.type, TempStream.temp \
+            FROM TempStream JOIN Rooms ON TempStream.roomNo = Rooms.roomNo;\n",
+        postgres_url(),
+        unique_name("rooms")
+    );
+    let runner = AppRunner::new(&query, "Out").await;
+    runner.send(
+        "RoomStream",
+        vec![
+            AttributeValue::Int(1),
+            AttributeValue::String("suite".into()),
+        ],
+    );
+    runner.send(
+        "TempStream",
+        vec![AttributeValue::Int(1), AttributeValue::Double(21.5)],
+    );
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::Int(1),
+            AttributeValue::String("suite".into()),
+            AttributeValue::Double(21.5),
+        ]]
+    );
+}
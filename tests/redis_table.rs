@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Redis table tests. Tests needing a server use `EVENTFLUX_TEST_REDIS_URL`
+//! (default `redis://localhost:6379`) and are skipped when Redis isn't running.
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::distributed::RedisConfig;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::table::{InMemoryCompiledCondition, RedisTable, Table};
+use eventflux_rust::query_api::definition::attribute::Type as AttributeType;
+use eventflux_rust::query_api::definition::TableDefinition;
+use eventflux_rust::query_api::execution::query::output::stream::UpdateSet;
+use eventflux_rust::query_api::expression::condition::compare::Operator as CompareOp;
+use eventflux_rust::query_api::expression::{Expression, Variable};
+use std::time::Duration;
+
+fn users_definition() -> TableDefinition {
+    let mut def = TableDefinition::id("Users".to_string())
+        .attribute("id".to_string(), AttributeType::LONG)
+        .attribute("name".to_string(), AttributeType::STRING)
+        .attribute("score".to_string(), AttributeType::DOUBLE);
+    def.primary_key = vec!["id".to_string()];
+    def
+}
+
+fn user(id: i64, name: &str, score: f64) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::Long(id),
+        AttributeValue::String(name.to_string()),
+        AttributeValue::Double(score),
+    ]
+}
+
+fn redis_url() -> String {
+    std::env::var("EVENTFLUX_TEST_REDIS_URL")
+        .unwrap_or_else(|_| "redis://localhost:6379".to_string())
+}
+
+fn test_prefix() -> String {
+    format!("test:eventflux:{}:", uuid::Uuid::new_v4())
+}
+
+/// A table in the test server under `prefix`, or `None` if Redis isn't running
+fn redis_table(prefix: &str, key_ttl_ms: Option<u64>) -> Option<RedisTable> {
+    let config = RedisConfig {
+        url: redis_url(),
+        ..RedisConfig::default()
+    };
+    let table = RedisTable::new(&users_definition(), &config, prefix, key_ttl_ms).unwrap();
+    if let Err(e) = table.validate_connectivity() {
+        println!("Redis not available, skipping: {e}");
+        return None;
+    }
+    Some(table)
+}
+
+#[test]
+fn test_requires_primary_key() {
+    let def =
+        TableDefinition::id("NoKey".to_string()).attribute("v".to_string(), AttributeType::STRING);
+    let err = RedisTable::new(&def, &RedisConfig::default(), "p:", None).unwrap_err();
+    assert!(err.contains("PRIMARY KEY"), "{err}");
+}
+
+#[test]
+fn test_key_lookups_update_and_delete() {
+    let prefix = test_prefix();
+    let Some(table) = redis_table(&prefix, None) else {
+        return;
+    };
+    table.insert(&user(1, "ann", 1.5)).unwrap();
+    table.insert(&user(2, "bob", 2.5)).unwrap();
+    table.insert(&user(1, "ann", 3.0)).unwrap();
+
+    // An INT key finds the LONG key column
+    let one = InMemoryCompiledCondition {
+        values: vec![AttributeValue::Int(1)],
+    };
+    assert_eq!(table.find(&one).unwrap(), Some(user(1, "ann", 3.0)));
+
+    let by_name = table.compile_condition(Expression::compare(
+        Expression::Variable(Variable::new("name".to_string())),
+        CompareOp::Equal,
+        Expression::value_string("bob".to_string()),
+    ));
+    assert!(table.contains(&*by_name).unwrap());
+
+    let update = table.compile_update_set(UpdateSet::new().add_set_attribute(
+        Variable::new("score".to_string()),
+        Expression::value_double(9.0),
+    ));
+    assert!(table.update(&*by_name, &*update).unwrap());
+    assert!(table.delete(&one).unwrap());
+    assert_eq!(table.all_rows().unwrap(), vec![user(2, "bob", 9.0)]);
+
+    // Another instance on the same prefix sees the same rows
+    let other = redis_table(&prefix, None).unwrap();
+    assert_eq!(other.all_rows().unwrap(), vec![user(2, "bob", 9.0)]);
+    assert!(other.delete(&*by_name).unwrap());
+    assert!(table.all_rows().unwrap().is_empty());
+}
+
+#[test]
+fn test_rows_expire_after_key_ttl() {
+    let Some(table) = redis_table(&test_prefix(), Some(200)) else {
+        return;
+    };
+    table.insert(&user(7, "eve", 0.5)).unwrap();
+    assert_eq!(table.all_rows().unwrap().len(), 1);
+    std::thread::sleep(Duration::from_millis(400));
+    assert!(table.all_rows().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn redis_table_join_via_app_runner() {
+    let prefix = test_prefix();
+    if redis_table(&prefix, None).is_none() {
+        return;
+    }
+    let query = format!(
+        "\
+        CREATE STREAM UserStream (id LONG, name STRING, score DOUBLE);\n\
+        CREATE STREAM Clicks (userId LONG, page STRING);\n\
+        CREATE STREAM Out (name STRING, page STRING);\n\
+        CREATE TABLE Users (id LONG PRIMARY KEY, name STRING, score DOUBLE) \
+            WITH ('extension' = 'redis', 'url' = '{}', 'key.prefix' = '{}');\n\
+        INSERT INTO Users SELECT id, name, score FROM UserStream;\n\
+        INSERT INTO Out SELECT Users.name, Clicks.page \
+            FROM Clicks JOIN Users ON Clicks.userId = Users.id;\n",
+        redis_url(),
+        prefix
+    );
+    let runner = AppRunner::new(&query, "Out").await;
+    runner.send("UserStream", user(3, "cat", 1.0));
+    runner.send(
+        "Clicks",
+        vec![
+            AttributeValue::Long(3),
+            AttributeValue::String("/home".into()),
+        ],
+    );
+    runner.send(
+        "Clicks",
+        vec![
+            AttributeValue::Long(4),
+            AttributeValue::String("/faq".into()),
+        ],
+    );
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::String("cat".into()),
+            AttributeValue::String("/home".into()),
+        ]]
+    );
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::table::{InMemoryCompiledCondition, SqliteTable, Table};
+use eventflux_rust::query_api::definition::attribute::Type as AttributeType;
+use eventflux_rust::query_api::definition::TableDefinition;
+use eventflux_rust::query_api::expression::condition::compare::Operator as CompareOp;
+use eventflux_rust::query_api::expression::{Expression, Variable};
+
+fn rates_definition() -> TableDefinition {
+    let mut def = TableDefinition::id("Rates".to_string())
+        .attribute("currency".to_string(), AttributeType::STRING)
+        .attribute("rate".to_string(), AttributeType::FLOAT)
+        .attribute("updates".to_string(), AttributeType::INT)
+        .attribute("active".to_string(), AttributeType::BOOL);
+    def.primary_key = vec!["currency".to_string()];
+    def
+}
+
+fn rate_row(currency: &str, rate: f32, updates: i32) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::String(currency.to_string()),
+        AttributeValue::Float(rate),
+        AttributeValue::Int(updates),
+        AttributeValue::Bool(true),
+    ]
+}
+
+#[test]
+fn test_declared_types_and_key_upsert() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rates.db");
+    let table = SqliteTable::open(
+        &rates_definition(),
+        path.to_str().unwrap(),
+        "Rates".to_string(),
+    )
+    .unwrap();
+    table.insert(&rate_row("EUR", 1.5, 1)).unwrap();
+    table.insert(&rate_row("EUR", 1.25, 2)).unwrap();
+    table.insert(&rate_row("GBP", 0.75, 1)).unwrap();
+
+    // Key-length value lists look rows up by primary key
+    let eur = InMemoryCompiledCondition {
+        values: vec![AttributeValue::String("EUR".to_string())],
+    };
+    assert_eq!(table.find(&eur).unwrap(), Some(rate_row("EUR", 1.25, 2)));
+
+    let cond = table.compile_condition(Expression::compare(
+        Expression::Variable(Variable::new("rate".to_string())),
+        CompareOp::LessThan,
+        Expression::value_float(1.0),
+    ));
+    assert_eq!(table.find(&*cond).unwrap(), Some(rate_row("GBP", 0.75, 1)));
+    assert!(table.delete(&*cond).unwrap());
+    assert_eq!(table.all_rows().unwrap(), vec![rate_row("EUR", 1.25, 2)]);
+}
+
+#[test]
+fn test_rows_survive_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("rates.db");
+    let path = path.to_str().unwrap();
+    {
+        let table = SqliteTable::open(&rates_definition(), path, "Rates".to_string()).unwrap();
+        table.insert(&rate_row("JPY", 0.01, 3)).unwrap();
+    }
+    let reopened = SqliteTable::open(&rates_definition(), path, "Rates".to_string()).unwrap();
+    assert_eq!(reopened.all_rows().unwrap(), vec![rate_row("JPY", 0.01, 3)]);
+
+    // A definition that doesn't match the stored table is rejected
+    let other = TableDefinition::id("Rates".to_string())
+        .attribute("currency".to_string(), AttributeType::STRING);
+    let err = SqliteTable::open(&other, path, "Rates".to_string()).unwrap_err();
+    assert!(err.contains("has columns"), "{err}");
+}
+
+#[tokio::test]
+async fn sqlite_table_join_via_app_runner() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rooms.db");
+    let query = format!(
+        "\
+        CREATE STREAM RoomStream (roomNo INT, type STRING);\n\
+        CREATE STREAM TempStream (roomNo INT, temp DOUBLE);\n\
+        CREATE STREAM Out (roomNo INT, type STRING, temp DOUBLE);\n\
+        CREATE TABLE Rooms (roomNo INT PRIMARY KEY, type STRING) \
+            WITH ('extension' = 'sqlite', 'path' = '{}');\n\
+        INSERT INTO Rooms SELECT roomNo, type FROM RoomStream;\n\
+        INSERT INTO Out SELECT TempStream.roomNo, Rooms.type, TempStream.temp \
+            FROM TempStream JOIN Rooms ON TempStream.roomNo = Rooms.roomNo;\n",
+        path.to_str().unwrap()
+    );
+    let runner = AppRunner::new(&query, "Out").await;
+    runner.send(
+        "RoomStream",
+        vec![
+            AttributeValue::Int(1),
+            AttributeValue::String("suite".into()),
+        ],
+    );
+    runner.send(
+        "TempStream",
+        vec![AttributeValue::Int(1), AttributeValue::Double(21.5)],
+    );
+    let out = runner.shutdown();
+    assert_eq!(
+        out,
+        vec![vec![
+            AttributeValue::Int(1),
+            AttributeValue::String("suite".into()),
+            AttributeValue::Double(21.5),
+        ]]
+    );
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[path = "common/mod.rs"]
+mod common;
+use common::AppRunner;
+use eventflux_rust::core::event::value::AttributeValue;
+use eventflux_rust::core::eventflux_manager::EventFluxManager;
+use eventflux_rust::core::persistence::data_source::SqliteDataSource;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn rate(currency: &str, rate: f64) -> Vec<AttributeValue> {
+    vec![
+        AttributeValue::String(currency.to_string()),
+        AttributeValue::Double(rate),
+    ]
+}
+
+fn table_rows(runner: &AppRunner, table: &str) -> Vec<Vec<AttributeValue>> {
+    let mut rows = runner
+        .runtime()
+        .eventflux_app_context
+        .get_eventflux_context()
+        .get_table(table)
+        .unwrap()
+        .all_rows()
+        .unwrap();
+    rows.sort_by_key(|row| row[0].to_string());
+    rows
+}
+
+#[tokio::test]
+async fn csv_file_preloads_table_for_joins() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("rates.csv");
+    std::fs::write(&path, "currency,rate\nEUR,1.1\n\nGBP,1\n").unwrap();
+
+    let query = format!(
+        "\
+        CREATE STREAM Orders (currency STRING, amount DOUBLE);\n\
+        CREATE STREAM Out (currency STRING, rate DOUBLE);\n\
+        CREATE TABLE Rates (currency STRING PRIMARY KEY, rate DOUBLE) \
+            WITH ('extension' = 'inMemory', 'load.path' = '{}', 'csv.has-header' = 'true');\n\
+        INSERT INTO Out SELECT Orders.currency, Rates.rate \
+            FROM Orders JOIN Rates ON Orders.currency = Rates.currency;\n",
+        path.display()
+    );
+    let runner = AppRunner::new(&query, "Out").await;
+    assert_eq!(
+        table_rows(&runner, "Rates"),
+        vec![rate("EUR", 1.1), rate("GBP", 1.0)]
+    );
+    runner.send(
+        "Orders",
+        vec![
+            AttributeValue::String("GBP".into()),
+            AttributeValue::Double(5.0),
+        ],
+    );
+    assert_eq!(runner.shutdown(), vec![rate("GBP", 1.0)]);
+}
+
+#[tokio::test]
+async fn json_lines_file_preloads_table() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("rates.jsonl");
+    std::fs::write(
+        &path,
+        "{\"currency\": \"EUR\", \"rate\": 1.1}\n{\"currency\": \"JPY\", \"rate\": 0.006}\n",
+    )
+    .unwrap();
+
+    let query = format!(
+        "\
+        CREATE STREAM In (currency STRING);\n\
+        CREATE TABLE Rates (currency STRING, rate DOUBLE) \
+            WITH ('extension' = 'inMemory', 'load.path' = '{}');\n",
+        path.display()
+    );
+    let runner = AppRunner::new(&query, "In").await;
+    assert_eq!(
+        table_rows(&runner, "Rates"),
+        vec![rate("EUR", 1.1), rate("JPY", 0.006)]
+    );
+    runner.shutdown();
+}
+
+#[tokio::test]
+async fn query_preloads_table_from_data_source() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("ref.db");
+    let conn = Connection::open(&db).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE fx (code TEXT, value REAL, active INTEGER);\n\
+         INSERT INTO fx VALUES ('EUR', 1.1, 1), ('CHF', 1.2, 0), ('USD', 1, 1);",
+    )
+    .unwrap();
+
+    let manager = EventFluxManager::new();
+    manager
+        .add_data_source(
+            "RefDB".to_string(),
+            Arc::new(SqliteDataSource::new(db.to_str().unwrap()).unwrap()),
+        )
+        .unwrap();
+    let query = "\
+        CREATE STREAM In (currency STRING);\n\
+        CREATE TABLE Rates (currency STRING, rate DOUBLE) \
+            WITH ('extension' = 'inMemory', 'load.data.source' = 'RefDB', \
+            'load.query' = 'SELECT code, value FROM fx WHERE active = 1');\n";
+    let runner = AppRunner::new_with_manager(manager, query, "In").await;
+    assert_eq!(
+        table_rows(&runner, "Rates"),
+        vec![rate("EUR", 1.1), rate("USD", 1.0)]
+    );
+    runner.shutdown();
+}
+
+#[tokio::test]
+async fn refresh_swaps_table_contents() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("rates.csv");
+    std::fs::write(&path, "EUR,1.1\nGBP,1.3\n").unwrap();
+
+    let query = format!(
+        "\
+        CREATE STREAM In (currency STRING);\n\
+        CREATE TABLE Rates (currency STRING PRIMARY KEY, rate DOUBLE) \
+            WITH ('extension' = 'inMemory', 'load.path' = '{}', 'load.refresh' = '50ms');\n\
+        CREATE STREAM RateChanges AS CHANGES OF Rates;\n",
+        path.display()
+    );
+    let runner = AppRunner::new(&query, "RateChanges").await;
+    assert_eq!(
+        table_rows(&runner, "Rates"),
+        vec![rate("EUR", 1.1), rate("GBP", 1.3)]
+    );
+
+    // Replaced at once, so a reload never reads a half-written file
+    let next = dir.path().join("rates.next");
+    std::fs::write(&next, "EUR,1.2\nUSD,1.0\n").unwrap();
+    std::fs::rename(&next, &path).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    assert_eq!(
+        table_rows(&runner, "Rates"),
+        vec![rate("EUR", 1.2), rate("USD", 1.0)]
+    );
+
+    // The initial load isn't a change; the reload is
+    let mut changes = runner.shutdown();
+    changes.sort_by_key(|row| format!("{}{}", row[1], row[0]));
+    let change = |op: &str, currency: &str, value: f64| {
+        let mut data = vec![AttributeValue::String(op.into())];
+        data.extend(rate(currency, value));
+        data
+    };
+    assert_eq!(
+        changes,
+        vec![
+            change("UPDATE_AFTER", "EUR", 1.2),
+            change("UPDATE_BEFORE", "EUR", 1.1),
+            change("DELETE", "GBP", 1.3),
+            change("INSERT", "USD", 1.0),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_error_missing_load_file() {
+    let manager = EventFluxManager::new();
+    let result = manager
+        .create_eventflux_app_runtime_from_string(
+            "CREATE STREAM In (v STRING);\n\
+             CREATE TABLE T (v STRING) \
+                WITH ('extension' = 'inMemory', 'load.path' = '/nonexistent/rows.csv');\n",
+        )
+        .await;
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("initial load failed"), "{err}");
+}
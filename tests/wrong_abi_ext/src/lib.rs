@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Test extension claiming an ABI version the host does not support. The host
+//! must reject it before looking up `eventflux_extension`.
+
+use eventflux_rust::core::extension::abi::{EfExtension, EfStr, EVENTFLUX_ABI_VERSION};
+
+/// ABI version reported by this library
+pub const WRONG_ABI_VERSION: u32 = EVENTFLUX_ABI_VERSION + 1;
+
+static EXTENSION: EfExtension = EfExtension {
+    abi_version: WRONG_ABI_VERSION,
+    ..EfExtension::empty(EfStr::from_static("wrong_abi_ext"))
+};
+
+#[no_mangle]
+pub extern "C" fn eventflux_abi_version() -> u32 {
+    WRONG_ABI_VERSION
+}
+
+#[no_mangle]
+pub extern "C" fn eventflux_extension() -> *const EfExtension {
+    &EXTENSION
+}
+
+/// Returns the path to the compiled dynamic library for this crate.
+pub fn library_path() -> std::path::PathBuf {
+    use std::path::PathBuf;
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../target/debug/deps");
+    p = p.canonicalize().unwrap_or(p);
+    p.push(format!(
+        "{}wrong_abi_ext.{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_EXTENSION
+    ));
+    p
+}